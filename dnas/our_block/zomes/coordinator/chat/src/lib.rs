@@ -1,5 +1,6 @@
 use hdk::prelude::*;
 use chat_integrity::*;
+use signal_envelope::SignalEnvelope;
 
 /// ───────────────────────────────────────────────────────────────────────────
 /// SIGNAL INPUT/OUTPUT TYPES
@@ -9,6 +10,25 @@ use chat_integrity::*;
 pub struct SendMessageInput {
     pub recipient: AgentPubKey,
     pub message: String,
+    /// Keeps a reply attached to the subject its conversation was started about
+    #[serde(default)]
+    pub context: Option<ChatContext>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StartChatAboutInput {
+    pub subject: ChatSubject,
+    /// Who to talk to; defaults to the subject's author, owner, or host
+    #[serde(default)]
+    pub recipient: Option<AgentPubKey>,
+    pub message: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StartChatAboutOutput {
+    pub peer: AgentPubKey,
+    pub context: ChatContext,
+    pub message: SendMessageOutput,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -21,6 +41,24 @@ pub struct SendMessageOutput {
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SendTypingInput {
     pub recipient: AgentPubKey,
+    /// When the client last sent a TypingStarted to this recipient (ms),
+    /// used to debounce repeated keystrokes
+    pub last_sent_at: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SendTypingOutput {
+    /// False when the signal was suppressed by the debounce window
+    pub sent: bool,
+    /// Timestamp the client should pass back as `last_sent_at`
+    pub last_sent_at: i64,
+    /// When the recipient's indicator will expire unless renewed
+    pub expires_at: i64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct StopTypingInput {
+    pub recipient: AgentPubKey,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -29,6 +67,154 @@ pub struct SendReadReceiptInput {
     pub message_id: String,
 }
 
+/// How far each side of a conversation has read
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ReadState {
+    pub peer: AgentPubKey,
+    /// The last of the peer's messages we marked read
+    pub my_last_read: Option<String>,
+    /// The last message the peer sent us a read receipt for
+    pub peer_last_read: Option<String>,
+    /// Messages from the peer after `my_last_read`
+    pub unread_count: usize,
+}
+
+/// One entry in the conversation list
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConversationSummary {
+    pub peer: AgentPubKey,
+    pub last_message: ChatMessage,
+    pub read_state: ReadState,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SendReactionInput {
+    pub recipient: AgentPubKey,
+    pub message_id: String,
+    pub emoji: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EditMessageInput {
+    pub message_id: String,
+    pub new_content: String,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeleteMessageInput {
+    pub message_id: String,
+}
+
+/// Share a file in a DM, either by uploading new bytes or by referencing
+/// a file already stored in file_storage. Exactly one must be provided.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SendAttachmentInput {
+    pub recipient: AgentPubKey,
+    pub file_name: String,
+    pub file_type: String,
+    pub file_bytes: Option<Vec<u8>>,
+    pub metadata_hash: Option<ActionHash>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SendVoiceMemoInput {
+    pub recipient: AgentPubKey,
+    pub audio_bytes: Vec<u8>,
+    pub duration_ms: u32,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SendAttachmentOutput {
+    pub message_id: String,
+    pub timestamp: i64,
+    pub metadata_hash: ActionHash,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RegisterDeviceInput {
+    /// Agent key of the other device (e.g. the hub kiosk)
+    pub device: AgentPubKey,
+    pub label: String,
+    /// From `sign_device_registration`, called on the device with our key
+    pub signature: Signature,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DeviceInfo {
+    pub device: AgentPubKey,
+    pub label: String,
+    pub registered_at: Timestamp,
+}
+
+/// The fields of a help request, tool shed item, or event that a chat about
+/// it needs; each zome names the neighbor responsible differently
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct RequestSubject {
+    title: String,
+    author: AgentPubKey,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct ItemSubject {
+    title: String,
+    owner: AgentPubKey,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct EventSubject {
+    title: String,
+    host: AgentPubKey,
+}
+
+/// Mirror of file_storage's `UploadFileInput`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct UploadFileInput {
+    name: String,
+    file_type: String,
+    data: Vec<u8>,
+}
+
+/// Subset of file_storage's `FileMetadata` needed for attachments
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct StoredFileMetadata {
+    name: String,
+    size: u32,
+    file_type: String,
+}
+
+/// Mirror of file_storage's `FileMetadataOutput`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct StoredFile {
+    metadata_hash: ActionHash,
+    metadata: StoredFileMetadata,
+}
+
+/// Mirror of the vouch zome's `AccessCheckInput`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+struct AccessCheckInput {
+    agent: AgentPubKey,
+    scope: Option<GuestScope>,
+    counterparty: Option<AgentPubKey>,
+}
+
+/// The vouch zome's `GuestScope` variant for messaging the pass issuer
+#[derive(Clone, Debug, Serialize, Deserialize)]
+enum GuestScope {
+    ChatIssuer,
+}
+
+/// A conversation transcript for the user to keep
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ConversationExport {
+    pub me: AgentPubKey,
+    pub peer: AgentPubKey,
+    pub exported_at: Timestamp,
+    /// Oldest first
+    pub messages: Vec<ChatMessage>,
+    /// The same messages as a plain-text transcript, one per line
+    pub transcript: String,
+}
+
 /// ───────────────────────────────────────────────────────────────────────────
 /// ANCHOR HELPERS
 /// ───────────────────────────────────────────────────────────────────────────
@@ -47,7 +233,8 @@ fn online_agents_anchor() -> ExternResult<EntryHash> {
 /// ───────────────────────────────────────────────────────────────────────────
 
 /// Send an ephemeral message to a specific agent
-/// This uses send_remote_signal - message is NOT stored in the DHT
+/// This uses send_remote_signal - message is NOT stored in the DHT, only as a
+/// private entry on each side's own chain for `export_conversation`
 #[hdk_extern]
 pub fn send_message(input: SendMessageInput) -> ExternResult<SendMessageOutput> {
     // Validate message length
@@ -62,31 +249,33 @@ pub fn send_message(input: SendMessageInput) -> ExternResult<SendMessageOutput>
             MAX_MESSAGE_LENGTH
         ))));
     }
+    if input.context.as_ref().is_some_and(|context| context.title.len() > MAX_CONTEXT_TITLE_LENGTH) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Context title cannot exceed {} characters",
+            MAX_CONTEXT_TITLE_LENGTH
+        ))));
+    }
+    require_chat_access(&input.recipient)?;
+    require_send_allowance(&input.recipient)?;
 
     let sender = agent_info()?.agent_initial_pubkey;
     let timestamp = sys_time()?.as_millis() as i64;
-    
-    // Generate a unique message ID
-    let rand_bytes = random_bytes(4)?;
-    let rand_hex: String = rand_bytes.iter().map(|b| format!("{:02x}", b)).collect();
-    let message_id = format!(
-        "{}-{}-{}",
-        hex_encode(sender.get_raw_36()),
-        timestamp,
-        rand_hex
-    );
+    let message_id = generate_message_id(&sender, timestamp)?;
 
     let chat_message = ChatMessage {
         sender: sender.clone(),
         content: input.message,
         timestamp,
         message_id: message_id.clone(),
+        context: input.context,
+        recipient: Some(input.recipient.clone()),
     };
+    store_message(input.recipient.clone(), &chat_message)?;
 
     let signal = ChatSignal::Message(chat_message);
 
-    // Send remote signal to recipient (ephemeral, not stored)
-    send_remote_signal(signal, vec![input.recipient])?;
+    // Send remote signal to every device of the recipient (ephemeral, not stored)
+    send_remote_signal(signal, recipient_devices(&input.recipient)?)?;
 
     Ok(SendMessageOutput {
         message_id,
@@ -95,21 +284,209 @@ pub fn send_message(input: SendMessageInput) -> ExternResult<SendMessageOutput>
     })
 }
 
+/// Start a conversation about a help request, tool shed item, or event
+///
+/// The first message goes to the subject's author, owner, or host unless a
+/// recipient is given (as it must be when the caller is that neighbor), and
+/// carries a reference to the subject. Send replies with the returned context
+/// so the whole thread stays attached to it.
+#[hdk_extern]
+pub fn start_chat_about(input: StartChatAboutInput) -> ExternResult<StartChatAboutOutput> {
+    let (title, responsible) = resolve_subject(&input.subject)?;
+    let peer = input.recipient.unwrap_or(responsible);
+    if peer == agent_info()?.agent_initial_pubkey {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Choose a neighbor to chat with about your own post".to_string()
+        )));
+    }
+
+    let context = ChatContext {
+        subject: input.subject,
+        title: title.chars().take(MAX_CONTEXT_TITLE_LENGTH).collect(),
+    };
+    let message = send_message(SendMessageInput {
+        recipient: peer.clone(),
+        message: input.message,
+        context: Some(context.clone()),
+    })?;
+
+    Ok(StartChatAboutOutput { peer, context, message })
+}
+
+/// Every stored message about a request, item, or event, oldest first
+#[hdk_extern]
+pub fn get_messages_about(target: ActionHash) -> ExternResult<Vec<ChatMessage>> {
+    let mut stored: Vec<StoredMessage> = stored_messages()?
+        .into_iter()
+        .filter(|m| !m.deleted && m.context.as_ref().is_some_and(|c| *c.subject.target() == target))
+        .collect();
+    stored.sort_by_key(|m| m.timestamp);
+
+    let me = agent_info()?.agent_initial_pubkey;
+    Ok(stored.into_iter().map(|m| chat_message(m, &me)).collect())
+}
+
+/// Send a file or image to a specific agent
+///
+/// New bytes are uploaded through the file_storage zome; only the resulting
+/// reference travels in the signal, so large files never hit signal limits.
+#[hdk_extern]
+pub fn send_attachment(input: SendAttachmentInput) -> ExternResult<SendAttachmentOutput> {
+    require_chat_access(&input.recipient)?;
+    require_send_allowance(&input.recipient)?;
+    let stored = match (input.file_bytes, input.metadata_hash) {
+        (Some(data), None) => {
+            validate_attachment(&input.file_type, data.len())?;
+            call_file_storage::<_, StoredFile>(
+                "upload_file",
+                UploadFileInput {
+                    name: input.file_name,
+                    file_type: input.file_type,
+                    data,
+                },
+            )?
+        }
+        (None, Some(metadata_hash)) => {
+            let existing: Option<StoredFile> = call_file_storage("get_file_metadata", metadata_hash)?;
+            let existing = existing.ok_or(wasm_error!(WasmErrorInner::Guest(
+                "Attachment file not found".to_string()
+            )))?;
+            validate_attachment(&existing.metadata.file_type, existing.metadata.size as usize)?;
+            existing
+        }
+        _ => {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Provide either file bytes or an existing file reference".to_string()
+            )));
+        }
+    };
+
+    let sender = agent_info()?.agent_initial_pubkey;
+    let timestamp = sys_time()?.as_millis() as i64;
+    let message_id = generate_message_id(&sender, timestamp)?;
+
+    let signal = ChatSignal::Attachment(ChatAttachment {
+        sender,
+        metadata_hash: stored.metadata_hash.clone(),
+        file_name: stored.metadata.name,
+        file_type: stored.metadata.file_type,
+        size: stored.metadata.size,
+        timestamp,
+        message_id: message_id.clone(),
+    });
+    send_remote_signal(signal, recipient_devices(&input.recipient)?)?;
+    log_attachment(input.recipient, agent_info()?.agent_initial_pubkey)?;
+
+    Ok(SendAttachmentOutput {
+        message_id,
+        timestamp,
+        metadata_hash: stored.metadata_hash,
+    })
+}
+
+/// Send a recorded voice message to a specific agent
+///
+/// The audio format is read from the bytes rather than trusted from the
+/// client. Like attachments, the audio is uploaded through file_storage and
+/// only a reference travels in the signal.
+#[hdk_extern]
+pub fn send_voice_memo(input: SendVoiceMemoInput) -> ExternResult<SendAttachmentOutput> {
+    require_chat_access(&input.recipient)?;
+    require_send_allowance(&input.recipient)?;
+    let file_type = voice_memo_type(&input.audio_bytes).ok_or(wasm_error!(WasmErrorInner::Guest(
+        "Voice memos must be WebM, Ogg, or MP3 audio".to_string()
+    )))?;
+    validate_voice_memo(file_type, input.audio_bytes.len(), input.duration_ms)?;
+
+    let sender = agent_info()?.agent_initial_pubkey;
+    let timestamp = sys_time()?.as_millis() as i64;
+    let message_id = generate_message_id(&sender, timestamp)?;
+
+    let extension = file_type.trim_start_matches("audio/").replace("mpeg", "mp3");
+    let stored = call_file_storage::<_, StoredFile>(
+        "upload_file",
+        UploadFileInput {
+            name: format!("voice-memo-{}.{}", timestamp, extension),
+            file_type: file_type.to_string(),
+            data: input.audio_bytes,
+        },
+    )?;
+
+    let signal = ChatSignal::VoiceMemo(ChatVoiceMemo {
+        sender,
+        metadata_hash: stored.metadata_hash.clone(),
+        file_type: stored.metadata.file_type,
+        size: stored.metadata.size,
+        duration_ms: input.duration_ms,
+        timestamp,
+        message_id: message_id.clone(),
+    });
+    send_remote_signal(signal, recipient_devices(&input.recipient)?)?;
+    log_attachment(input.recipient, agent_info()?.agent_initial_pubkey)?;
+
+    Ok(SendAttachmentOutput {
+        message_id,
+        timestamp,
+        metadata_hash: stored.metadata_hash,
+    })
+}
+
 /// Send typing indicator to a specific agent
+///
+/// Clients may call this on every keystroke; the signal is only re-sent
+/// once per `TYPING_DEBOUNCE_MS`. Each TypingStarted lives for
+/// `TYPING_INDICATOR_TTL_MS`, so the recipient's indicator clears itself
+/// if the matching TypingStopped is lost.
 #[hdk_extern]
-pub fn send_typing(input: SendTypingInput) -> ExternResult<()> {
+pub fn send_typing(input: SendTypingInput) -> ExternResult<SendTypingOutput> {
+    let now = sys_time()?.as_millis() as i64;
+    
+    if let Some(last_sent_at) = input.last_sent_at {
+        if !typing_debounce_elapsed(last_sent_at, now) {
+            return Ok(SendTypingOutput {
+                sent: false,
+                last_sent_at,
+                expires_at: last_sent_at + TYPING_INDICATOR_TTL_MS,
+            });
+        }
+    }
+    
+    let sender = agent_info()?.agent_initial_pubkey;
+    let expires_at = now + TYPING_INDICATOR_TTL_MS;
+    
+    let signal = ChatSignal::TypingStarted {
+        sender,
+        timestamp: now,
+        expires_at,
+    };
+    send_remote_signal(signal, vec![input.recipient])?;
+    
+    Ok(SendTypingOutput {
+        sent: true,
+        last_sent_at: now,
+        expires_at,
+    })
+}
+
+/// Tell a specific agent we stopped typing (message sent or input cleared)
+#[hdk_extern]
+pub fn stop_typing(input: StopTypingInput) -> ExternResult<()> {
     let sender = agent_info()?.agent_initial_pubkey;
+    let timestamp = sys_time()?.as_millis() as i64;
     
-    let signal = ChatSignal::Typing { sender };
+    let signal = ChatSignal::TypingStopped { sender, timestamp };
     send_remote_signal(signal, vec![input.recipient])?;
     
     Ok(())
 }
 
 /// Send read receipt to a specific agent
+///
+/// Also recorded locally, so `get_read_state` remembers it after a restart.
 #[hdk_extern]
 pub fn send_read_receipt(input: SendReadReceiptInput) -> ExternResult<()> {
     let sender = agent_info()?.agent_initial_pubkey;
+    record_read(input.recipient.clone(), sender.clone(), &input.message_id)?;
     
     let signal = ChatSignal::Read {
         sender,
@@ -120,6 +497,216 @@ pub fn send_read_receipt(input: SendReadReceiptInput) -> ExternResult<()> {
     Ok(())
 }
 
+/// How far we and `peer` have read our conversation, from stored markers
+#[hdk_extern]
+pub fn get_read_state(peer: AgentPubKey) -> ExternResult<ReadState> {
+    let markers = read_markers()?;
+    let messages = stored_messages()?;
+    read_state(peer, &markers, &messages)
+}
+
+/// Every conversation with a stored message, most recently active first
+#[hdk_extern]
+pub fn get_conversations(_: ()) -> ExternResult<Vec<ConversationSummary>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let markers = read_markers()?;
+    let messages = stored_messages()?;
+
+    let mut latest: Vec<&StoredMessage> = Vec::new();
+    for message in messages.iter().filter(|m| !m.deleted) {
+        match latest.iter_mut().find(|m| m.peer == message.peer) {
+            Some(existing) if existing.timestamp < message.timestamp => *existing = message,
+            Some(_) => {}
+            None => latest.push(message),
+        }
+    }
+    latest.sort_by_key(|m| std::cmp::Reverse(m.timestamp));
+
+    latest
+        .into_iter()
+        .map(|last| {
+            Ok(ConversationSummary {
+                peer: last.peer.clone(),
+                read_state: read_state(last.peer.clone(), &markers, &messages)?,
+                last_message: chat_message(last.clone(), &me),
+            })
+        })
+        .collect()
+}
+
+/// Send an emoji reaction to a message
+#[hdk_extern]
+pub fn send_reaction(input: SendReactionInput) -> ExternResult<()> {
+    validate_reaction_emoji(&input.emoji)?;
+    if input.message_id.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Message ID cannot be empty".to_string()
+        )));
+    }
+
+    let sender = agent_info()?.agent_initial_pubkey;
+    
+    let signal = ChatSignal::Reaction {
+        message_id: input.message_id,
+        emoji: input.emoji,
+        sender,
+    };
+    send_remote_signal(signal, vec![input.recipient])?;
+    
+    Ok(())
+}
+
+/// Change the text of a message we sent
+///
+/// The recipient is taken from our stored copy, so only messages sent since
+/// persistence was added can be edited.
+#[hdk_extern]
+pub fn edit_message(input: EditMessageInput) -> ExternResult<()> {
+    if input.new_content.trim().is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Message cannot be empty".to_string()
+        )));
+    }
+    if input.new_content.len() > MAX_MESSAGE_LENGTH {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Message cannot exceed {} characters",
+            MAX_MESSAGE_LENGTH
+        ))));
+    }
+
+    let me = agent_info()?.agent_initial_pubkey;
+    let original = my_sent_message(&me, &input.message_id)?;
+    require_send_allowance(&original.peer)?;
+    let edited_at = sys_time()?.as_millis() as i64;
+
+    create_entry(EntryTypes::StoredMessage(StoredMessage {
+        content: input.new_content.clone(),
+        edited_at: Some(edited_at),
+        ..original.clone()
+    }))?;
+
+    let signal = ChatSignal::Edited {
+        sender: me,
+        message_id: input.message_id,
+        content: input.new_content,
+        timestamp: original.timestamp,
+        edited_at,
+    };
+    send_remote_signal(signal, recipient_devices(&original.peer)?)?;
+
+    Ok(())
+}
+
+/// Delete a message we sent, for us and the recipient
+#[hdk_extern]
+pub fn delete_message(input: DeleteMessageInput) -> ExternResult<()> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let original = my_sent_message(&me, &input.message_id)?;
+
+    create_entry(EntryTypes::StoredMessage(tombstone(original.clone())))?;
+
+    let signal = ChatSignal::Deleted {
+        sender: me,
+        message_id: input.message_id,
+    };
+    send_remote_signal(signal, recipient_devices(&original.peer)?)?;
+
+    Ok(())
+}
+
+/// ───────────────────────────────────────────────────────────────────────────
+/// DEVICES
+/// ───────────────────────────────────────────────────────────────────────────
+
+/// Agree to receive `owner`'s messages on this device
+///
+/// Called on the device; the owner passes the signature to `register_device`.
+#[hdk_extern]
+pub fn sign_device_registration(owner: AgentPubKey) -> ExternResult<Signature> {
+    let me = agent_info()?.agent_initial_pubkey;
+    if owner == me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "A device cannot register itself".to_string()
+        )));
+    }
+    sign(me, device_registration_payload(&owner))
+}
+
+/// Register another device's agent key so messages sent to us reach it too
+#[hdk_extern]
+pub fn register_device(input: RegisterDeviceInput) -> ExternResult<DeviceInfo> {
+    let me = agent_info()?.agent_initial_pubkey;
+    if input.device == me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "This device is already registered".to_string()
+        )));
+    }
+    let label = input.label.trim().to_string();
+    if label.len() > MAX_DEVICE_LABEL_LENGTH {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Device label cannot exceed {} characters",
+            MAX_DEVICE_LABEL_LENGTH
+        ))));
+    }
+
+    if !verify_signature(input.device.clone(), input.signature.clone(), device_registration_payload(&me))? {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "The device's signature does not match; sign again on the device".to_string()
+        )));
+    }
+
+    let devices = get_devices(me.clone())?;
+    if let Some(existing) = devices.iter().find(|d| d.device == input.device) {
+        return Ok(existing.clone());
+    }
+    if devices.len() >= MAX_DEVICES {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Cannot register more than {} devices",
+            MAX_DEVICES
+        ))));
+    }
+
+    let mut tag = input.signature.0.to_vec();
+    tag.extend_from_slice(label.as_bytes());
+    create_link(me, input.device.clone(), LinkTypes::AgentToDevices, LinkTag::new(tag))?;
+
+    Ok(DeviceInfo {
+        device: input.device,
+        label,
+        registered_at: sys_time()?,
+    })
+}
+
+/// Stop sending our messages to a device
+#[hdk_extern]
+pub fn revoke_device(device: AgentPubKey) -> ExternResult<()> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let links = get_links(
+        LinkQuery::try_new(me, LinkTypes::AgentToDevices)?,
+        GetStrategy::Local,
+    )?;
+
+    let mut revoked = false;
+    for link in links {
+        if link.target.into_agent_pub_key().as_ref() == Some(&device) {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+            revoked = true;
+        }
+    }
+    if !revoked {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Device is not registered".to_string()
+        )));
+    }
+    Ok(())
+}
+
+/// Devices the caller has registered
+#[hdk_extern]
+pub fn get_my_devices(_: ()) -> ExternResult<Vec<DeviceInfo>> {
+    get_devices(agent_info()?.agent_initial_pubkey)
+}
+
 /// Announce that this agent is online (broadcasts to known agents)
 #[hdk_extern]
 pub fn announce_online(agents: Vec<AgentPubKey>) -> ExternResult<()> {
@@ -131,7 +718,7 @@ pub fn announce_online(agents: Vec<AgentPubKey>) -> ExternResult<()> {
     
     // Notify specified agents
     if !agents.is_empty() {
-        let signal = ChatSignal::Online { agent: me };
+        let signal = ChatSignal::Online { agent: me, status: my_status() };
         send_remote_signal(signal, agents)?;
     }
     
@@ -151,6 +738,36 @@ pub fn announce_offline(agents: Vec<AgentPubKey>) -> ExternResult<()> {
     Ok(())
 }
 
+/// Drop out of online presence entirely: remove my entries from the online
+/// agents anchor and tell everyone still listed that I've gone offline
+///
+/// Used when deactivating an account, where `announce_offline` alone would
+/// leave me listed as online.
+#[hdk_extern]
+pub fn leave_presence(_: ()) -> ExternResult<()> {
+    let me = agent_info()?.agent_initial_pubkey;
+    
+    let anchor = online_agents_anchor()?;
+    let links = get_links(
+        LinkQuery::try_new(anchor, LinkTypes::OnlineAgents)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut others = Vec::new();
+    for link in links {
+        let Ok(agent) = AgentPubKey::try_from(link.target) else {
+            continue;
+        };
+        if agent == me {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        } else if !others.contains(&agent) {
+            others.push(agent);
+        }
+    }
+    
+    announce_offline(others)
+}
+
 /// Get list of online agents
 #[hdk_extern]
 pub fn get_online_agents(_: ()) -> ExternResult<Vec<AgentPubKey>> {
@@ -174,6 +791,41 @@ pub fn get_my_agent_key(_: ()) -> ExternResult<AgentPubKey> {
     Ok(agent_info()?.agent_initial_pubkey)
 }
 
+/// Export every locally stored message exchanged with `peer`
+///
+/// Only messages sent or received on this device since persistence was
+/// added are included; nothing is fetched from the peer.
+#[hdk_extern]
+pub fn export_conversation(peer: AgentPubKey) -> ExternResult<ConversationExport> {
+    let me = agent_info()?.agent_initial_pubkey;
+
+    let mut stored: Vec<StoredMessage> = stored_messages()?
+        .into_iter()
+        .filter(|m| m.peer == peer && !m.deleted)
+        .collect();
+    stored.sort_by_key(|m| m.timestamp);
+
+    let transcript = stored
+        .iter()
+        .map(|m| {
+            let who = if m.sender == me { "me".to_string() } else { m.sender.to_string() };
+            let edited = if m.edited_at.is_some() { " (edited)" } else { "" };
+            format!("[{}] {}: {}{}", Timestamp::from_micros(m.timestamp * 1000), who, m.content, edited)
+        })
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let messages = stored.into_iter().map(|m| chat_message(m, &me)).collect();
+
+    Ok(ConversationExport {
+        me,
+        peer,
+        exported_at: sys_time()?,
+        messages,
+        transcript,
+    })
+}
+
 /// ───────────────────────────────────────────────────────────────────────────
 /// SIGNAL CALLBACK
 /// ───────────────────────────────────────────────────────────────────────────
@@ -189,8 +841,116 @@ pub fn recv_remote_signal(signal: ExternIO) -> ExternResult<()> {
         )))
     })?;
 
+    // Signals that carry a claimed sender are dropped if spoofed or malformed
+    let provenance = call_info()?.provenance;
+    let counted = matches!(
+        chat_signal,
+        ChatSignal::Message(_) | ChatSignal::Edited { .. } | ChatSignal::Attachment(_) | ChatSignal::VoiceMemo(_)
+    );
+    if counted && recent_message_times(&provenance, &provenance)?.len() >= MAX_RECEIVED_PER_WINDOW {
+        return flag_flooding(provenance);
+    }
+    if let ChatSignal::Message(ref message) = chat_signal {
+        if !addressed_to_me(message.recipient.as_ref())? {
+            return Ok(());
+        }
+        // A guest's messages are only kept while their pass lets them
+        // message whoever they're addressed to
+        let me = agent_info()?.agent_initial_pubkey;
+        if !chat_access_allowed(&provenance, message.recipient.as_ref().unwrap_or(&me))? {
+            return Ok(());
+        }
+    }
+    let chat_signal = match chat_signal {
+        ChatSignal::TypingStarted { sender, timestamp, .. } => {
+            if sender != provenance {
+                return Ok(());
+            }
+            // Re-stamp expiry against the local clock so sender clock skew
+            // can never keep an indicator alive longer than the TTL
+            let now = sys_time()?.as_millis() as i64;
+            ChatSignal::TypingStarted {
+                sender,
+                timestamp,
+                expires_at: now + TYPING_INDICATOR_TTL_MS,
+            }
+        }
+        ChatSignal::Message(ref message)
+            if message.sender != provenance
+                || message.content.len() > MAX_MESSAGE_LENGTH
+                || message.context.as_ref().is_some_and(|c| c.title.len() > MAX_CONTEXT_TITLE_LENGTH) =>
+        {
+            return Ok(());
+        }
+        ChatSignal::Edited { sender, message_id, content, timestamp, edited_at } => {
+            if sender != provenance
+                || !sent_by(&message_id, &sender)
+                || content.trim().is_empty()
+                || content.len() > MAX_MESSAGE_LENGTH
+            {
+                return Ok(());
+            }
+            // Keep our own record of when it was sent over the sender's claim
+            let timestamp = match stored_message(&message_id)? {
+                Some(original) if original.deleted => return Ok(()),
+                Some(original) if original.sender == sender => {
+                    create_entry(EntryTypes::StoredMessage(StoredMessage {
+                        content: content.clone(),
+                        edited_at: Some(edited_at),
+                        ..original.clone()
+                    }))?;
+                    original.timestamp
+                }
+                _ => timestamp,
+            };
+            ChatSignal::Edited { sender, message_id, content, timestamp, edited_at }
+        }
+        ChatSignal::Deleted { sender, message_id } => {
+            if sender != provenance || !sent_by(&message_id, &sender) {
+                return Ok(());
+            }
+            if let Some(original) = stored_message(&message_id)?.filter(|m| m.sender == sender) {
+                create_entry(EntryTypes::StoredMessage(tombstone(original)))?;
+            }
+            ChatSignal::Deleted { sender, message_id }
+        }
+        ChatSignal::TypingStopped { ref sender, .. } if *sender != provenance => return Ok(()),
+        ChatSignal::Flooding { .. } => return Ok(()),
+        ChatSignal::Read { sender, message_id } => {
+            if sender != provenance {
+                return Ok(());
+            }
+            record_read(sender.clone(), sender.clone(), &message_id)?;
+            ChatSignal::Read { sender, message_id }
+        }
+        ChatSignal::Reaction { ref emoji, ref sender, .. }
+            if *sender != provenance || validate_reaction_emoji(emoji).is_err() =>
+        {
+            return Ok(());
+        }
+        ChatSignal::Attachment(ref attachment)
+            if attachment.sender != provenance
+                || validate_attachment(&attachment.file_type, attachment.size as usize).is_err() =>
+        {
+            return Ok(());
+        }
+        ChatSignal::VoiceMemo(ref memo)
+            if memo.sender != provenance
+                || validate_voice_memo(&memo.file_type, memo.size as usize, memo.duration_ms).is_err() =>
+        {
+            return Ok(());
+        }
+        other => other,
+    };
+
+    match chat_signal {
+        ChatSignal::Message(ref message) => store_message(provenance, message)?,
+        ChatSignal::Attachment(_) | ChatSignal::VoiceMemo(_) => log_attachment(provenance.clone(), provenance)?,
+        _ => {}
+    }
+
     // Emit as a local signal for the UI to receive
-    emit_signal(chat_signal)?;
+    emit_signal(SignalEnvelope::new("chat", chat_signal))?;
 
     Ok(())
 }
@@ -199,6 +959,540 @@ pub fn recv_remote_signal(signal: ExternIO) -> ExternResult<()> {
 /// HELPERS
 /// ───────────────────────────────────────────────────────────────────────────
 
+fn validate_reaction_emoji(emoji: &str) -> ExternResult<()> {
+    if emoji.trim().is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Reaction cannot be empty".to_string()
+        )));
+    }
+    if emoji.len() > MAX_REACTION_EMOJI_LENGTH {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Reaction cannot exceed {} bytes",
+            MAX_REACTION_EMOJI_LENGTH
+        ))));
+    }
+    Ok(())
+}
+
+/// Whether enough time has passed since the last TypingStarted to send another
+fn typing_debounce_elapsed(last_sent_at: i64, now: i64) -> bool {
+    now - last_sent_at >= TYPING_DEBOUNCE_MS || now < last_sent_at
+}
+
+fn validate_attachment(file_type: &str, size: usize) -> ExternResult<()> {
+    if !ALLOWED_ATTACHMENT_TYPES.contains(&file_type) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Attachments of type '{}' are not allowed",
+            file_type
+        ))));
+    }
+    if size == 0 {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Attachment cannot be empty".to_string()
+        )));
+    }
+    if size > MAX_ATTACHMENT_SIZE {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Attachment cannot exceed {} bytes",
+            MAX_ATTACHMENT_SIZE
+        ))));
+    }
+    Ok(())
+}
+
+fn validate_voice_memo(file_type: &str, size: usize, duration_ms: u32) -> ExternResult<()> {
+    if !VOICE_MEMO_TYPES.contains(&file_type) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Voice memos of type '{}' are not allowed",
+            file_type
+        ))));
+    }
+    if size == 0 || size > MAX_VOICE_MEMO_SIZE {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Voice memo must be between 1 and {} bytes",
+            MAX_VOICE_MEMO_SIZE
+        ))));
+    }
+    if !(MIN_VOICE_MEMO_DURATION_MS..=MAX_VOICE_MEMO_DURATION_MS).contains(&duration_ms) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Voice memo must be between {} and {} seconds long",
+            MIN_VOICE_MEMO_DURATION_MS as f32 / 1000.0,
+            MAX_VOICE_MEMO_DURATION_MS / 1000
+        ))));
+    }
+    Ok(())
+}
+
+/// The audio format of a recording, from its leading bytes
+fn voice_memo_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        Some("audio/webm")
+    } else if bytes.starts_with(b"OggS") {
+        Some("audio/ogg")
+    } else if bytes.starts_with(b"ID3") || bytes.starts_with(&[0xFF, 0xFB]) {
+        Some("audio/mpeg")
+    } else {
+        None
+    }
+}
+
+fn get_devices(agent: AgentPubKey) -> ExternResult<Vec<DeviceInfo>> {
+    let links = get_links(
+        LinkQuery::try_new(agent.clone(), LinkTypes::AgentToDevices)?,
+        GetStrategy::Local,
+    )?;
+
+    let mut devices: Vec<DeviceInfo> = Vec::new();
+    for link in links {
+        // Only the agent's own registrations, countersigned by the device, count
+        if link.author != agent {
+            continue;
+        }
+        let Some(device) = link.target.into_agent_pub_key() else {
+            continue;
+        };
+        let Some((signature, label)) = device_tag_parts(&link.tag) else {
+            continue;
+        };
+        if devices.iter().any(|d| d.device == device)
+            || !verify_signature(device.clone(), signature, device_registration_payload(&agent))?
+        {
+            continue;
+        }
+        devices.push(DeviceInfo {
+            device,
+            label,
+            registered_at: link.timestamp,
+        });
+    }
+    devices.sort_by_key(|d| d.registered_at);
+
+    Ok(devices)
+}
+
+/// The recipient followed by every device they've registered
+fn recipient_devices(recipient: &AgentPubKey) -> ExternResult<Vec<AgentPubKey>> {
+    let mut targets = vec![recipient.clone()];
+    for device in get_devices(recipient.clone())? {
+        if !targets.contains(&device.device) {
+            targets.push(device.device);
+        }
+    }
+    Ok(targets)
+}
+
+/// Whether a message for `recipient` is meant for this agent: sent to us, or
+/// to an agent that registered us as one of their devices
+fn addressed_to_me(recipient: Option<&AgentPubKey>) -> ExternResult<bool> {
+    let Some(recipient) = recipient else {
+        return Ok(false);
+    };
+    let me = agent_info()?.agent_initial_pubkey;
+    if *recipient == me {
+        return Ok(true);
+    }
+    Ok(get_devices(recipient.clone())?.iter().any(|d| d.device == me))
+}
+
+/// Keep a copy of a DM on our own chain for later export
+fn store_message(peer: AgentPubKey, message: &ChatMessage) -> ExternResult<()> {
+    create_entry(EntryTypes::StoredMessage(StoredMessage {
+        peer,
+        sender: message.sender.clone(),
+        content: message.content.clone(),
+        timestamp: message.timestamp,
+        message_id: message.message_id.clone(),
+        edited_at: None,
+        deleted: false,
+        context: message.context.clone(),
+    }))?;
+    Ok(())
+}
+
+/// The revision recording a deleted message: it keeps only what identifies
+/// the message, so the newest revision no longer carries its text or the
+/// subject it was about
+fn tombstone(original: StoredMessage) -> StoredMessage {
+    StoredMessage {
+        content: String::new(),
+        context: None,
+        edited_at: None,
+        deleted: true,
+        ..original
+    }
+}
+
+/// Count an attachment or voice memo toward the rate limits
+fn log_attachment(peer: AgentPubKey, sender: AgentPubKey) -> ExternResult<()> {
+    create_entry(EntryTypes::AttachmentLog(AttachmentLog { peer, sender }))?;
+    Ok(())
+}
+
+fn chat_message(stored: StoredMessage, me: &AgentPubKey) -> ChatMessage {
+    let recipient = if stored.sender == *me { stored.peer } else { me.clone() };
+    ChatMessage {
+        sender: stored.sender,
+        content: stored.content,
+        timestamp: stored.timestamp,
+        message_id: stored.message_id,
+        context: stored.context,
+        recipient: Some(recipient),
+    }
+}
+
+/// The title of a chat subject and the neighbor responsible for it
+fn resolve_subject(subject: &ChatSubject) -> ExternResult<(String, AgentPubKey)> {
+    let record = get(subject.target().clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Chat subject not found".to_string())))?;
+    let decode_error = |kind: &str| wasm_error!(WasmErrorInner::Guest(format!("Chat subject is not {}", kind)));
+
+    match subject {
+        ChatSubject::Request { .. } => {
+            let request = record.entry().to_app_option::<RequestSubject>().ok().flatten();
+            let request = request.ok_or(decode_error("a help request"))?;
+            Ok((request.title, request.author))
+        }
+        ChatSubject::Item { .. } => {
+            let item = record.entry().to_app_option::<ItemSubject>().ok().flatten();
+            let item = item.ok_or(decode_error("a tool shed item"))?;
+            Ok((item.title, item.owner))
+        }
+        ChatSubject::Event { .. } => {
+            let event = record.entry().to_app_option::<EventSubject>().ok().flatten();
+            let event = event.ok_or(decode_error("an event"))?;
+            Ok((event.title, event.host))
+        }
+    }
+}
+
+/// The latest revision of every stored message, in the order first stored
+fn stored_messages() -> ExternResult<Vec<StoredMessage>> {
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::StoredMessage.try_into()?)
+            .include_entries(true),
+    )?;
+
+    let mut messages: Vec<StoredMessage> = Vec::new();
+    for record in records {
+        let Some(stored) = record
+            .entry()
+            .to_app_option::<StoredMessage>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        else {
+            continue;
+        };
+        match messages.iter_mut().find(|m| m.message_id == stored.message_id) {
+            Some(existing) => *existing = stored,
+            None => messages.push(stored),
+        }
+    }
+
+    Ok(messages)
+}
+
+fn stored_message(message_id: &str) -> ExternResult<Option<StoredMessage>> {
+    Ok(stored_messages()?
+        .into_iter()
+        .find(|m| m.message_id == message_id))
+}
+
+/// The newest read marker for each (peer, reader) pair
+fn read_markers() -> ExternResult<Vec<ReadMarker>> {
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::ReadMarker.try_into()?)
+            .include_entries(true),
+    )?;
+
+    let mut markers: Vec<ReadMarker> = Vec::new();
+    for record in records {
+        let Some(marker) = record
+            .entry()
+            .to_app_option::<ReadMarker>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        else {
+            continue;
+        };
+        match markers.iter_mut().find(|m| m.peer == marker.peer && m.reader == marker.reader) {
+            Some(existing) => *existing = marker,
+            None => markers.push(marker),
+        }
+    }
+
+    Ok(markers)
+}
+
+/// Store that `reader` has read up to `message_id` in the conversation with
+/// `peer`, unless a later message is already marked read
+fn record_read(peer: AgentPubKey, reader: AgentPubKey, message_id: &str) -> ExternResult<()> {
+    // Our own copy is authoritative; otherwise fall back to the send time in the ID
+    let message_timestamp = match stored_message(message_id)? {
+        Some(message) => message.timestamp,
+        None => match message_id.split('-').nth(1).and_then(|part| part.parse::<i64>().ok()) {
+            Some(timestamp) => timestamp,
+            None => return Ok(()),
+        },
+    };
+
+    let newer_exists = read_markers()?
+        .iter()
+        .any(|m| m.peer == peer && m.reader == reader && m.message_timestamp >= message_timestamp);
+    if newer_exists {
+        return Ok(());
+    }
+
+    create_entry(EntryTypes::ReadMarker(ReadMarker {
+        peer,
+        reader,
+        message_id: message_id.to_string(),
+        message_timestamp,
+    }))?;
+    Ok(())
+}
+
+fn read_state(peer: AgentPubKey, markers: &[ReadMarker], messages: &[StoredMessage]) -> ExternResult<ReadState> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let mine = markers.iter().find(|m| m.peer == peer && m.reader == me);
+    let theirs = markers.iter().find(|m| m.peer == peer && m.reader == peer);
+
+    let read_up_to = mine.map_or(i64::MIN, |m| m.message_timestamp);
+    let unread_count = messages
+        .iter()
+        .filter(|m| m.peer == peer && m.sender == peer && !m.deleted && m.timestamp > read_up_to)
+        .count();
+
+    Ok(ReadState {
+        my_last_read: mine.map(|m| m.message_id.clone()),
+        peer_last_read: theirs.map(|m| m.message_id.clone()),
+        unread_count,
+        peer,
+    })
+}
+
+/// A message we sent that hasn't been deleted
+fn my_sent_message(me: &AgentPubKey, message_id: &str) -> ExternResult<StoredMessage> {
+    match stored_message(message_id)? {
+        Some(message) if message.sender != *me => Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the sender can change a message".to_string()
+        ))),
+        Some(message) if !message.deleted => Ok(message),
+        _ => Err(wasm_error!(WasmErrorInner::Guest(
+            "Message not found".to_string()
+        ))),
+    }
+}
+
+/// Message IDs start with the sender's key (see `generate_message_id`), so
+/// a peer can't edit or delete messages it didn't send even without a
+/// stored copy to compare against
+fn sent_by(message_id: &str, sender: &AgentPubKey) -> bool {
+    message_id.starts_with(&format!("{}-", hex_encode(sender.get_raw_36())))
+}
+
+/// The caller's current profile status; presence still goes out without it
+/// if the profile zome can't be reached
+fn my_status() -> Option<PresenceStatus> {
+    let me = agent_info().ok()?.agent_initial_pubkey;
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("profile"),
+        FunctionName::from("get_agent_status"),
+        None,
+        me,
+    )
+    .ok()?;
+    match response {
+        ZomeCallResponse::Ok(result) => result.decode::<Option<PresenceStatus>>().ok().flatten(),
+        _ => None,
+    }
+}
+
+fn call_file_storage<I, O>(fn_name: &str, payload: I) -> ExternResult<O>
+where
+    I: serde::Serialize + std::fmt::Debug,
+    O: serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("file_storage"),
+        FunctionName::from(fn_name),
+        None,
+        payload,
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(result) => result.decode().map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "Failed to decode file_storage response: {:?}",
+                e
+            )))
+        }),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "file_storage call failed: {:?}",
+            other
+        )))),
+    }
+}
+
+/// Refuse to message `recipient` if we're a guest whose pass doesn't cover them
+fn require_chat_access(recipient: &AgentPubKey) -> ExternResult<()> {
+    let allowed = chat_access_allowed(&agent_info()?.agent_initial_pubkey, recipient)?;
+    if !allowed {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Guests can only message the neighbor who invited them, while their pass lasts".to_string()
+        )));
+    }
+    Ok(())
+}
+
+/// Whether `agent` may message `counterparty`
+///
+/// Guests may only message the member who issued their pass, and only while
+/// it's active; everyone else passes the vouch zome's check.
+fn chat_access_allowed(agent: &AgentPubKey, counterparty: &AgentPubKey) -> ExternResult<bool> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("vouch"),
+        FunctionName::from("check_access"),
+        None,
+        AccessCheckInput {
+            agent: agent.clone(),
+            scope: Some(GuestScope::ChatIssuer),
+            counterparty: Some(counterparty.clone()),
+        },
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(result) => result.decode().map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "Failed to decode access check: {:?}",
+                e
+            )))
+        }),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to check access: {:?}",
+            other
+        )))),
+    }
+}
+
+/// When messages, edits, attachments, and voice memos between us and `peer`
+/// sent by `sender` were written to our chain within the last rate limit window
+///
+/// Uses our own action timestamps, so a peer can't dodge the limit by
+/// backdating messages.
+fn recent_message_times(peer: &AgentPubKey, sender: &AgentPubKey) -> ExternResult<Vec<Timestamp>> {
+    let since = Timestamp::from_micros(sys_time()?.as_micros() - RATE_LIMIT_WINDOW_MS * 1000);
+
+    let mut times = Vec::new();
+    for record in records_since(UnitEntryTypes::StoredMessage.try_into()?, since)? {
+        if let Ok(Some(stored)) = record.entry().to_app_option::<StoredMessage>() {
+            if stored.peer == *peer && stored.sender == *sender {
+                times.push(record.action().timestamp());
+            }
+        }
+    }
+    for record in records_since(UnitEntryTypes::AttachmentLog.try_into()?, since)? {
+        if let Ok(Some(log)) = record.entry().to_app_option::<AttachmentLog>() {
+            if log.peer == *peer && log.sender == *sender {
+                times.push(record.action().timestamp());
+            }
+        }
+    }
+    times.sort();
+
+    Ok(times)
+}
+
+/// How many actions `records_since` reads per query
+const RECENT_QUERY_BATCH: u32 = 64;
+
+/// Our records of `entry_type` written at or after `since`
+///
+/// Walks back from the chain head one sequence range at a time and stops at
+/// the first range that starts before `since`, so only the tail of the chain
+/// is read no matter how long it grows.
+fn records_since(entry_type: EntryType, since: Timestamp) -> ExternResult<Vec<Record>> {
+    let (_, head_seq, head_timestamp) = agent_info()?.chain_head;
+    let mut records = Vec::new();
+    if head_timestamp < since {
+        return Ok(records);
+    }
+
+    let mut end = head_seq;
+    loop {
+        let start = end.saturating_sub(RECENT_QUERY_BATCH - 1);
+        let batch = query(
+            ChainQueryFilter::new()
+                .sequence_range(ChainQueryFilterRange::ActionSeqRange(start, end))
+                .entry_type(entry_type.clone())
+                .include_entries(true),
+        )?;
+        records.extend(batch.into_iter().filter(|record| record.action().timestamp() >= since));
+
+        // Chain timestamps only move forward, so once the first action of
+        // this range is outside the window, every earlier one is too
+        let first = query(
+            ChainQueryFilter::new().sequence_range(ChainQueryFilterRange::ActionSeqRange(start, start)),
+        )?;
+        let reached_window_start = !first.first().is_some_and(|record| record.action().timestamp() >= since);
+        if start == 0 || reached_window_start {
+            return Ok(records);
+        }
+        end = start - 1;
+    }
+}
+
+/// Refuse to send to `recipient` once we've hit the per-recipient rate limit
+fn require_send_allowance(recipient: &AgentPubKey) -> ExternResult<()> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let times = recent_message_times(recipient, &me)?;
+    if times.len() < MAX_MESSAGES_PER_WINDOW {
+        return Ok(());
+    }
+
+    let now = sys_time()?.as_millis() as i64;
+    let frees_up_at = times[times.len() - MAX_MESSAGES_PER_WINDOW].as_millis() as i64 + RATE_LIMIT_WINDOW_MS;
+    let wait_secs = ((frees_up_at - now).max(0) + 999) / 1000;
+    Err(wasm_error!(WasmErrorInner::Guest(format!(
+        "You can send up to {} messages a minute to one neighbor; try again in {}s",
+        MAX_MESSAGES_PER_WINDOW, wait_secs
+    ))))
+}
+
+/// Drop a signal from a sender over the receive limit, telling the UI once
+/// per window that their messages are being dropped
+fn flag_flooding(sender: AgentPubKey) -> ExternResult<()> {
+    let since = Timestamp::from_micros(sys_time()?.as_micros() - RATE_LIMIT_WINDOW_MS * 1000);
+    let records = records_since(UnitEntryTypes::FloodNotice.try_into()?, since)?;
+    let already_flagged = records.iter().any(|record| {
+        matches!(record.entry().to_app_option::<FloodNotice>(), Ok(Some(notice)) if notice.sender == sender)
+    });
+    if already_flagged {
+        return Ok(());
+    }
+
+    create_entry(EntryTypes::FloodNotice(FloodNotice { sender: sender.clone() }))?;
+    emit_signal(SignalEnvelope::new(
+        "chat",
+        ChatSignal::Flooding { sender, window_ms: RATE_LIMIT_WINDOW_MS },
+    ))?;
+    Ok(())
+}
+
+/// Generate a unique message ID
+fn generate_message_id(sender: &AgentPubKey, timestamp: i64) -> ExternResult<String> {
+    let rand_bytes = random_bytes(4)?;
+    let rand_hex: String = rand_bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    Ok(format!(
+        "{}-{}-{}",
+        hex_encode(sender.get_raw_36()),
+        timestamp,
+        rand_hex
+    ))
+}
+
 fn hex_encode(bytes: &[u8]) -> String {
     bytes.iter().take(8).map(|b| format!("{:02x}", b)).collect()
 }
@@ -5,13 +5,34 @@
 
 use hdk::prelude::*;
 use events_integrity::*;
+use idempotency::{find_previous_create, idempotency_tag};
+use list_options::{get_list_links, ListOptions};
+use signal_envelope::SignalEnvelope;
+use std::collections::BTreeSet;
 
-/// Signal types for real-time updates
+/// Signal types for real-time updates, emitted inside a SignalEnvelope
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(tag = "type")]
+#[serde(tag = "event", content = "payload")]
 pub enum Signal {
     NewEvent { event_hash: ActionHash, event: Event },
     EventRSVP { event_hash: ActionHash, attendee: AgentPubKey },
+    CheckedIn { event_hash: ActionHash, attendee: AgentPubKey },
+    /// A note to an event's attendees; sent remotely, passed on per each
+    /// recipient's notification level
+    EventMessage {
+        event_hash: ActionHash,
+        title: String,
+        from: AgentPubKey,
+        message: String,
+        mentions: Vec<AgentPubKey>,
+    },
+}
+
+/// Zome name clients see in the signal envelope
+const SIGNAL_ZOME: &str = "events";
+
+fn emit_zome_signal(signal: Signal) -> ExternResult<()> {
+    emit_signal(SignalEnvelope::new(SIGNAL_ZOME, signal))
 }
 
 /// Input for creating an event
@@ -22,17 +43,232 @@ pub struct CreateEventInput {
     pub location: String,
     pub event_date: Timestamp,
     pub max_attendees: Option<u32>,
+    #[serde(default)]
+    pub place: Option<Location>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub guest_list_visibility: GuestListVisibility,
+    #[serde(default)]
+    pub duration_minutes: Option<u32>,
+    #[serde(default)]
+    pub visibility: EventVisibility,
+    /// Neighbors invited to an invite-only event
+    #[serde(default)]
+    pub invitees: Vec<AgentPubKey>,
+    /// Client-chosen key; resubmitting with the same key returns the original
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 /// Output for event operations
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct EventOutput {
+    /// `attendees` only lists the caller and host when the guest list is hidden from them
     pub event: Event,
     pub action_hash: ActionHash,
     pub entry_hash: EntryHash,
+    pub attendee_count: usize,
+}
+
+/// Who an event's guest list is visible to
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EventAttendees {
+    pub event_hash: ActionHash,
+    pub host: AgentPubKey,
+    /// False when the guest list is hidden from the caller; `attendees` is then empty
+    pub visible: bool,
+    pub attendees: Vec<AgentPubKey>,
+    pub attendee_count: usize,
+    pub max_attendees: Option<u32>,
+}
+
+/// Host-signed check-in code, rendered by the UI as a QR code
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CheckinCode {
+    pub event_hash: ActionHash,
+    pub issued_at: Timestamp,
+    pub expires_at: Timestamp,
+    pub signature: Signature,
+}
+
+/// Input for checking in to an event
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CheckInInput {
+    pub event_hash: ActionHash,
+    pub code: CheckinCode,
+}
+
+/// Output for attendance records
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AttendanceOutput {
+    pub attendance: Attendance,
+    pub action_hash: ActionHash,
+}
+
+/// Input for messaging an event's attendees
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EventMessageInput {
+    pub event_hash: ActionHash,
+    pub message: String,
+    /// Attendees who should hear about it even with mentions-only notifications
+    #[serde(default)]
+    pub mentions: Vec<AgentPubKey>,
+}
+
+/// Input for choosing which messages about an event reach me
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetEventNotificationsInput {
+    pub event_hash: ActionHash,
+    pub level: NotificationLevel,
+}
+
+/// Mirror of the search zome's IndexContentInput
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct SearchIndexInput {
+    kind: SearchKind,
+    target: ActionHash,
+    title: String,
+    text: String,
+}
+
+/// Mirror of the search zome's LocationIndexInput
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LocationIndexInput {
+    kind: SearchKind,
+    target: ActionHash,
+    title: String,
+    geohash: String,
+}
+
+/// The search zome's ContentKind variant for events
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum SearchKind {
+    Event,
+}
+
+/// Input for listing upcoming events; everything is optional
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct UpcomingEventsInput {
+    /// Defaults to now
+    pub from: Option<Timestamp>,
+    /// Defaults to DEFAULT_UPCOMING_WINDOW_WEEKS after `from`
+    pub to: Option<Timestamp>,
+    /// Zero-based
+    pub page: Option<u32>,
+    pub page_size: Option<u32>,
+    /// Matched case-insensitively
+    pub category: Option<String>,
+    pub tag: Option<String>,
+    /// Read week buckets from the network; otherwise only when nothing is held locally
+    #[serde(default)]
+    pub prefer_network: bool,
+}
+
+/// Where a page of past events stopped. Events are ordered by date and then
+/// action hash, so events on the same date are neither skipped nor repeated;
+/// without a hash, the next page starts with anything before `event_date`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PastEventsCursor {
+    pub event_date: Timestamp,
+    #[serde(default)]
+    pub action_hash: Option<ActionHash>,
+}
+
+impl PastEventsCursor {
+    fn is_after(&self, output: &EventOutput) -> bool {
+        match &self.action_hash {
+            Some(action_hash) => (output.event.event_date, &output.action_hash) < (self.event_date, action_hash),
+            None => output.event.event_date < self.event_date,
+        }
+    }
+}
+
+/// Input for listing past events, newest first
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PastEventsInput {
+    /// Cursor from the previous page; defaults to now
+    pub before: Option<PastEventsCursor>,
+    pub limit: Option<u32>,
+    /// Matched case-insensitively
+    pub category: Option<String>,
+    pub tag: Option<String>,
+    /// Read week buckets from the network; otherwise only when nothing is held locally
+    #[serde(default)]
+    pub prefer_network: bool,
+}
+
+/// One page of upcoming events, soonest first
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UpcomingEventsPage {
+    pub events: Vec<EventOutput>,
+    pub page: u32,
+    pub has_more: bool,
+}
+
+/// One page of past events; pass `next_before` back to get the next page
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PastEventsPage {
+    pub events: Vec<EventOutput>,
+    pub next_before: Option<PastEventsCursor>,
+}
+
+/// Defaults stored in an event template
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EventTemplateInput {
+    pub title: String,
+    pub description: String,
+    pub location: String,
+    pub duration_minutes: Option<u32>,
+    pub max_attendees: Option<u32>,
+    #[serde(default)]
+    pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Input for replacing a template's defaults
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UpdateEventTemplateInput {
+    pub template_hash: ActionHash,
+    pub template: EventTemplateInput,
+}
+
+/// Input for scheduling an event from a template
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreateEventFromTemplateInput {
+    pub template_hash: ActionHash,
+    pub event_date: Timestamp,
+    /// Client-chosen key; resubmitting with the same key returns the original
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+}
+
+/// Output for template operations
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EventTemplateOutput {
+    /// Latest revision
+    pub template: EventTemplate,
+    /// Stays the same across edits
+    pub template_hash: ActionHash,
+    pub revision_hash: ActionHash,
 }
 
 const ALL_EVENTS_PATH: &str = "all_events";
+const WEEK_BUCKET_PATH: &str = "events.week";
+const WEEK_MICROS: i64 = 7 * 24 * 60 * 60 * 1_000_000;
+const DEFAULT_UPCOMING_WINDOW_WEEKS: i64 = 13;
+/// Longest range `get_upcoming_events` will scan in one call
+const MAX_UPCOMING_WINDOW_WEEKS: i64 = 53;
+/// `get_past_events` gives up after this many empty weeks in a row
+const MAX_EMPTY_PAST_WEEKS: i64 = 26;
+const DEFAULT_EVENTS_PAGE_SIZE: u32 = 20;
+const MAX_EVENTS_PAGE_SIZE: u32 = 100;
+
+/// How long a freshly generated check-in code stays valid (15 minutes)
+const CHECKIN_CODE_VALIDITY_MICROS: i64 = 15 * 60 * 1_000_000;
 
 // ============================================================================
 // EVENT MANAGEMENT
@@ -42,6 +278,19 @@ const ALL_EVENTS_PATH: &str = "all_events";
 #[hdk_extern]
 pub fn create_event(input: CreateEventInput) -> ExternResult<EventOutput> {
     let host = agent_info()?.agent_initial_pubkey;
+    let viewer = host.clone();
+    
+    if let Some(original) = find_previous_create(
+        input.idempotency_key.as_deref(),
+        host.clone(),
+        LinkTypes::AgentToEvents,
+    )? {
+        let event = get_event_entry(original.clone())?;
+        return event_output(event, original, &host);
+    }
+    if input.visibility == EventVisibility::Neighborhood && !input.invitees.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only invite-only events have invitees".into())));
+    }
     
     let event = Event {
         title: input.title,
@@ -52,28 +301,44 @@ pub fn create_event(input: CreateEventInput) -> ExternResult<EventOutput> {
         attendees: vec![host.clone()], // Host is automatically attending
         max_attendees: input.max_attendees,
         created_at: sys_time()?,
+        place: input.place,
+        category: input.category.map(|category| category.trim().to_string()),
+        tags: normalized_tags(input.tags),
+        guest_list_visibility: input.guest_list_visibility,
+        duration_minutes: input.duration_minutes,
+        visibility: input.visibility,
+        invitees: invitees(input.invitees, &host),
     };
     
+    ensure_membership_proof()?;
     let action_hash = create_entry(EntryTypes::Event(event.clone()))?;
-    let entry_hash = hash_entry(&event)?;
     
-    // Link from host to event
+    // Link from host to event, tagged with the idempotency key
     create_link(
         host.clone(),
         action_hash.clone(),
         LinkTypes::AgentToEvents,
-        (),
+        idempotency_tag(input.idempotency_key.as_deref())?,
     )?;
     
     // Link from host to attending (they're auto-attending)
     create_link(
-        host,
+        host.clone(),
         action_hash.clone(),
         LinkTypes::AgentToAttendingEvents,
         (),
     )?;
     
-    // Link to global all_events anchor
+    for invitee in &event.invitees {
+        create_link(
+            invitee.clone(),
+            action_hash.clone(),
+            LinkTypes::AgentToInvitations,
+            (),
+        )?;
+    }
+    
+    // Link to global all_events anchor; invite-only events are hidden when listing
     let all_events_anchor = all_events_anchor_hash()?;
     create_link(
         all_events_anchor,
@@ -82,28 +347,51 @@ pub fn create_event(input: CreateEventInput) -> ExternResult<EventOutput> {
         (),
     )?;
     
+    // Link to the week the event happens in, for date-range queries
+    create_link(
+        week_bucket_hash(week_of(event.event_date))?,
+        action_hash.clone(),
+        LinkTypes::WeekToEvents,
+        (),
+    )?;
+    
+    // Search results aren't filtered by invitation, so private events stay out
+    if event.visibility == EventVisibility::Neighborhood {
+        let search_text = format!("{} {}", event.description, event.location);
+        call_search("index_content", action_hash.clone(), &event.title, &search_text)?;
+        index_place(action_hash.clone(), &event.title, &event.place)?;
+    }
+    
+    // Hosting enough events earns a badge
+    let mut hosted = vec![action_hash.clone()];
+    for link in get_links(
+        LinkQuery::try_new(host, LinkTypes::AgentToEvents)?,
+        GetStrategy::Local,
+    )? {
+        if let Some(event_hash) = link.target.into_action_hash() {
+            hosted.push(event_hash);
+        }
+    }
+    grant_badge(event.host.clone(), hosted)?;
+    
     // Emit signal for real-time updates
-    emit_signal(Signal::NewEvent {
+    emit_zome_signal(Signal::NewEvent {
         event_hash: action_hash.clone(),
         event: event.clone(),
     })?;
     
-    Ok(EventOutput {
-        event,
-        action_hash,
-        entry_hash,
-    })
+    event_output(event, action_hash, &viewer)
 }
 
-/// Get all events
+/// Get all events; invite-only events are left out unless the caller is
+/// the host or invited
 #[hdk_extern]
-pub fn get_all_events(_: ()) -> ExternResult<Vec<EventOutput>> {
+pub fn get_all_events(options: Option<ListOptions>) -> ExternResult<Vec<EventOutput>> {
+    let options = options.unwrap_or_default();
+    let viewer = agent_info()?.agent_initial_pubkey;
     let all_events_anchor = all_events_anchor_hash()?;
     
-    let links = get_links(
-        LinkQuery::try_new(all_events_anchor, LinkTypes::AllEvents)?,
-        GetStrategy::Local,
-    )?;
+    let links = get_list_links(all_events_anchor, LinkTypes::AllEvents, options.prefer_network)?;
     
     let mut events = Vec::new();
     
@@ -113,12 +401,9 @@ pub fn get_all_events(_: ()) -> ExternResult<Vec<EventOutput>> {
                 if let Some(event) = record.entry().to_app_option::<Event>()
                     .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
                 {
-                    let entry_hash = hash_entry(&event)?;
-                    events.push(EventOutput {
-                        event,
-                        action_hash,
-                        entry_hash,
-                    });
+                    if event.is_invited(&viewer) {
+                        events.push(event_output(event, action_hash, &viewer)?);
+                    }
                 }
             }
         }
@@ -130,6 +415,187 @@ pub fn get_all_events(_: ()) -> ExternResult<Vec<EventOutput>> {
     Ok(events)
 }
 
+/// An event's guest list, if the host lets the caller see it
+///
+/// Follows the event's update chain, so RSVPs made since `event_hash` count.
+/// Hiding the list is a display setting, not access control; see
+/// `GuestListVisibility`.
+#[hdk_extern]
+pub fn get_event_attendees(event_hash: ActionHash) -> ExternResult<EventAttendees> {
+    let viewer = agent_info()?.agent_initial_pubkey;
+    let Some(record) = latest_record(event_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Event not found".into())));
+    };
+    let event = record
+        .entry()
+        .to_app_option::<Event>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid event entry".into())))?;
+    if !event.is_invited(&viewer) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Event not found".into())));
+    }
+    
+    let visible = can_see_guest_list(&event, &viewer);
+    Ok(EventAttendees {
+        event_hash,
+        host: event.host,
+        visible,
+        attendee_count: event.attendees.len(),
+        attendees: if visible { event.attendees } else { Vec::new() },
+        max_attendees: event.max_attendees,
+    })
+}
+
+/// Upcoming events in a date range, a page at a time
+///
+/// Only the week buckets overlapping the range are read, so this stays cheap
+/// however much history the neighborhood has. If nothing in the range is held
+/// locally, the buckets are read again from the network.
+#[hdk_extern]
+pub fn get_upcoming_events(input: UpcomingEventsInput) -> ExternResult<UpcomingEventsPage> {
+    let viewer = agent_info()?.agent_initial_pubkey;
+    let from = match input.from {
+        Some(from) => from,
+        None => sys_time()?,
+    };
+    let to = input.to.unwrap_or(Timestamp::from_micros(
+        from.as_micros() + DEFAULT_UPCOMING_WINDOW_WEEKS * WEEK_MICROS,
+    ));
+    if to < from {
+        return Err(wasm_error!(WasmErrorInner::Guest("Range must end after it starts".into())));
+    }
+    let (first_week, last_week) = (week_of(from), week_of(to));
+    if last_week - first_week >= MAX_UPCOMING_WINDOW_WEEKS {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Range cannot span more than {} weeks",
+            MAX_UPCOMING_WINDOW_WEEKS
+        ))));
+    }
+    
+    let mut events = Vec::new();
+    for week in first_week..=last_week {
+        events.extend(
+            week_events(week, &viewer, input.prefer_network)?
+                .into_iter()
+                .filter(|output| output.event.event_date >= from && output.event.event_date <= to)
+                .filter(|output| matches_filters(&output.event, &input.category, &input.tag)),
+        );
+    }
+    if events.is_empty() && !input.prefer_network {
+        return get_upcoming_events(UpcomingEventsInput {
+            prefer_network: true,
+            ..input
+        });
+    }
+    events.sort_by(|a, b| a.event.event_date.cmp(&b.event.event_date));
+    
+    let page = input.page.unwrap_or(0);
+    let page_size = page_size(input.page_size);
+    let start = (page as usize).saturating_mul(page_size);
+    let has_more = events.len() > start.saturating_add(page_size);
+    let events = events.into_iter().skip(start).take(page_size).collect();
+    
+    Ok(UpcomingEventsPage { events, page, has_more })
+}
+
+/// Past events, newest first
+///
+/// Walks back one week bucket at a time until the page is full, stopping
+/// once MAX_EMPTY_PAST_WEEKS weeks in a row turn up nothing. Like upcoming
+/// events, an empty local result is retried from the network.
+#[hdk_extern]
+pub fn get_past_events(input: PastEventsInput) -> ExternResult<PastEventsPage> {
+    let viewer = agent_info()?.agent_initial_pubkey;
+    let before = match input.before.clone() {
+        Some(before) => before,
+        None => PastEventsCursor {
+            event_date: sys_time()?,
+            action_hash: None,
+        },
+    };
+    let limit = page_size(input.limit);
+    
+    let mut events: Vec<EventOutput> = Vec::new();
+    let mut week = week_of(before.event_date);
+    let mut empty_weeks = 0;
+    
+    while events.len() < limit && empty_weeks < MAX_EMPTY_PAST_WEEKS {
+        let mut found: Vec<EventOutput> = week_events(week, &viewer, input.prefer_network)?
+            .into_iter()
+            .filter(|output| before.is_after(output))
+            .filter(|output| matches_filters(&output.event, &input.category, &input.tag))
+            .collect();
+        if found.is_empty() {
+            empty_weeks += 1;
+        } else {
+            empty_weeks = 0;
+        }
+        found.sort_by(|a, b| {
+            b.event
+                .event_date
+                .cmp(&a.event.event_date)
+                .then_with(|| b.action_hash.cmp(&a.action_hash))
+        });
+        events.extend(found);
+        week -= 1;
+    }
+    if events.is_empty() && !input.prefer_network {
+        return get_past_events(PastEventsInput {
+            prefer_network: true,
+            ..input
+        });
+    }
+    
+    let next_before = if events.len() > limit {
+        events.truncate(limit);
+        events.last().map(|output| PastEventsCursor {
+            event_date: output.event.event_date,
+            action_hash: Some(output.action_hash.clone()),
+        })
+    } else if empty_weeks < MAX_EMPTY_PAST_WEEKS {
+        // The page filled up exactly at a week boundary
+        Some(PastEventsCursor {
+            event_date: Timestamp::from_micros((week + 1) * WEEK_MICROS),
+            action_hash: None,
+        })
+    } else {
+        None
+    };
+    
+    Ok(PastEventsPage { events, next_before })
+}
+
+/// Add week bucket links for events I hosted before they existed
+#[hdk_extern]
+pub fn backfill_event_dates(_: ()) -> ExternResult<usize> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let links = get_links(
+        LinkQuery::try_new(me, LinkTypes::AgentToEvents)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut linked = 0;
+    for link in links {
+        let Some(event_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let event = get_event_entry(event_hash.clone())?;
+        let bucket = week_bucket_hash(week_of(event.event_date))?;
+        let existing = get_links(
+            LinkQuery::try_new(bucket.clone(), LinkTypes::WeekToEvents)?,
+            GetStrategy::Local,
+        )?;
+        let target = AnyLinkableHash::from(event_hash.clone());
+        if existing.iter().any(|link| link.target == target) {
+            continue;
+        }
+        create_link(bucket, event_hash, LinkTypes::WeekToEvents, ())?;
+        linked += 1;
+    }
+    
+    Ok(linked)
+}
+
 /// RSVP to an event
 #[hdk_extern]
 pub fn rsvp_event(event_hash: ActionHash) -> ExternResult<EventOutput> {
@@ -146,6 +612,10 @@ pub fn rsvp_event(event_hash: ActionHash) -> ExternResult<EventOutput> {
         return Err(wasm_error!(WasmErrorInner::Guest("Invalid event entry".into())));
     };
     
+    if !event.is_invited(&agent) {
+        return Err(wasm_error!(WasmErrorInner::Guest("This event is invite-only".into())));
+    }
+    
     // Check if already attending
     if event.attendees.contains(&agent) {
         return Err(wasm_error!(WasmErrorInner::Guest("Already attending this event".into())));
@@ -163,7 +633,6 @@ pub fn rsvp_event(event_hash: ActionHash) -> ExternResult<EventOutput> {
     
     // Update the event
     let new_action_hash = update_entry(event_hash.clone(), &event)?;
-    let entry_hash = hash_entry(&event)?;
     
     // Create link from agent to attending events
     create_link(
@@ -173,17 +642,15 @@ pub fn rsvp_event(event_hash: ActionHash) -> ExternResult<EventOutput> {
         (),
     )?;
     
+    record_onboarding_step()?;
+    
     // Emit signal for real-time updates
-    emit_signal(Signal::EventRSVP {
+    emit_zome_signal(Signal::EventRSVP {
         event_hash: new_action_hash.clone(),
-        attendee: agent,
+        attendee: agent.clone(),
     })?;
     
-    Ok(EventOutput {
-        event,
-        action_hash: new_action_hash,
-        entry_hash,
-    })
+    event_output(event, new_action_hash, &agent)
 }
 
 /// Cancel RSVP to an event
@@ -212,13 +679,49 @@ pub fn cancel_rsvp(event_hash: ActionHash) -> ExternResult<EventOutput> {
     
     // Update the event
     let new_action_hash = update_entry(event_hash, &event)?;
-    let entry_hash = hash_entry(&event)?;
     
-    Ok(EventOutput {
-        event,
-        action_hash: new_action_hash,
-        entry_hash,
-    })
+    event_output(event, new_action_hash, &agent)
+}
+
+/// Withdraw my RSVP from every upcoming event I'm attending but not hosting
+///
+/// Part of account deactivation; returns the events left.
+#[hdk_extern]
+pub fn cancel_my_future_rsvps(_: ()) -> ExternResult<Vec<ActionHash>> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+    
+    let links = get_links(
+        LinkQuery::try_new(agent.clone(), LinkTypes::AgentToAttendingEvents)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut cancelled = Vec::new();
+    for link in links {
+        let Some(action_hash) = link.target.clone().into_action_hash() else {
+            continue;
+        };
+        // Others may have RSVP'd since, so withdraw from the latest version
+        let Some(record) = latest_record(action_hash)? else {
+            continue;
+        };
+        let Some(event) = record.entry().to_app_option::<Event>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        else {
+            continue;
+        };
+        if event.host == agent || event.event_date <= now {
+            continue;
+        }
+        
+        if event.attendees.contains(&agent) {
+            let output = cancel_rsvp(record.action_address().clone())?;
+            cancelled.push(output.action_hash);
+        }
+        delete_link(link.create_link_hash, GetOptions::default())?;
+    }
+    
+    Ok(cancelled)
 }
 
 /// Get events the calling agent is attending
@@ -227,7 +730,7 @@ pub fn get_my_events(_: ()) -> ExternResult<Vec<EventOutput>> {
     let agent = agent_info()?.agent_initial_pubkey;
     
     let links = get_links(
-        LinkQuery::try_new(agent, LinkTypes::AgentToAttendingEvents)?,
+        LinkQuery::try_new(agent.clone(), LinkTypes::AgentToAttendingEvents)?,
         GetStrategy::Local,
     )?;
     
@@ -239,12 +742,7 @@ pub fn get_my_events(_: ()) -> ExternResult<Vec<EventOutput>> {
                 if let Some(event) = record.entry().to_app_option::<Event>()
                     .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
                 {
-                    let entry_hash = hash_entry(&event)?;
-                    events.push(EventOutput {
-                        event,
-                        action_hash,
-                        entry_hash,
-                    });
+                    events.push(event_output(event, action_hash, &agent)?);
                 }
             }
         }
@@ -255,11 +753,732 @@ pub fn get_my_events(_: ()) -> ExternResult<Vec<EventOutput>> {
     Ok(events)
 }
 
+/// Invite-only events I've been invited to, soonest first
+#[hdk_extern]
+pub fn get_my_invitations(_: ()) -> ExternResult<Vec<EventOutput>> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    
+    let links = get_links(
+        LinkQuery::try_new(agent.clone(), LinkTypes::AgentToInvitations)?,
+        GetStrategy::Network,
+    )?;
+    
+    let mut events = Vec::new();
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        // Follow RSVPs made since the invitation
+        let Some(record) = latest_record(action_hash)? else {
+            continue;
+        };
+        if let Some(event) = record.entry().to_app_option::<Event>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        {
+            events.push(event_output(event, record.action_address().clone(), &agent)?);
+        }
+    }
+    
+    events.sort_by(|a, b| a.event.event_date.cmp(&b.event.event_date));
+    
+    Ok(events)
+}
+
+// ============================================================================
+// EVENT DISCUSSION
+// ============================================================================
+
+/// Send a message to everyone attending an event (host and attendees only)
+///
+/// Messages aren't stored. Notification levels are private, so every
+/// attendee is sent the message and their own zome decides whether to show
+/// it. Returns how many attendees it was sent to.
+#[hdk_extern]
+pub fn post_event_message(input: EventMessageInput) -> ExternResult<usize> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let message = input.message.trim().to_string();
+    if message.is_empty() || message.len() > MAX_EVENT_MESSAGE_LENGTH {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Message must be 1-{} characters",
+            MAX_EVENT_MESSAGE_LENGTH
+        ))));
+    }
+    
+    let Some(record) = latest_record(input.event_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Event not found".into())));
+    };
+    let event = record
+        .entry()
+        .to_app_option::<Event>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid event entry".into())))?;
+    if event.host != me && !event.attendees.contains(&me) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only attendees can message an event".into())));
+    }
+    
+    let recipients: Vec<AgentPubKey> = event.attendees.iter().filter(|agent| **agent != me).cloned().collect();
+    let mentions = input.mentions.into_iter().filter(|agent| recipients.contains(agent)).collect();
+    send_remote_signal(
+        Signal::EventMessage {
+            event_hash: input.event_hash,
+            title: event.title,
+            from: me,
+            message,
+            mentions,
+        },
+        recipients.clone(),
+    )?;
+    
+    Ok(recipients.len())
+}
+
+/// Choose which messages about an event reach me; kept private on my own chain
+#[hdk_extern]
+pub fn set_event_notifications(input: SetEventNotificationsInput) -> ExternResult<NotificationLevel> {
+    create_entry(EntryTypes::EventNotificationPreference(EventNotificationPreference {
+        event_hash: input.event_hash,
+        level: input.level,
+    }))?;
+    Ok(input.level)
+}
+
+/// My notification level for an event; everything comes through until I choose
+#[hdk_extern]
+pub fn get_event_notifications(event_hash: ActionHash) -> ExternResult<NotificationLevel> {
+    let latest = my_chain_entries::<EventNotificationPreference>(UnitEntryTypes::EventNotificationPreference)?
+        .into_iter()
+        .rev()
+        .find(|(_, preference)| preference.event_hash == event_hash)
+        .map(|(_, preference)| preference.level);
+    
+    Ok(latest.unwrap_or_default())
+}
+
+/// Let attendees reach each other with event messages
+#[hdk_extern]
+pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    let mut functions = BTreeSet::new();
+    functions.insert((zome_info()?.name, FunctionName::from("recv_remote_signal")));
+    create_cap_grant(CapGrantEntry {
+        tag: "remote_signals".to_string(),
+        access: CapAccess::Unrestricted,
+        functions: GrantedFunctions::Listed(functions),
+    })?;
+    Ok(InitCallbackResult::Pass)
+}
+
+/// Pass event messages on to the UI unless I've muted them
+///
+/// Only messages from their actual sender get through, and with
+/// mentions-only notifications only those mentioning me do.
+#[hdk_extern]
+pub fn recv_remote_signal(signal: ExternIO) -> ExternResult<()> {
+    let signal: Signal = signal.decode().map_err(|e| {
+        wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to decode events signal: {:?}",
+            e
+        )))
+    })?;
+    
+    let Signal::EventMessage { event_hash, from, message, mentions, .. } = &signal else {
+        return Ok(());
+    };
+    if *from != call_info()?.provenance || message.len() > MAX_EVENT_MESSAGE_LENGTH {
+        return Ok(());
+    }
+    let me = agent_info()?.agent_initial_pubkey;
+    let show = match get_event_notifications(event_hash.clone())? {
+        NotificationLevel::All => true,
+        NotificationLevel::MentionsOnly => mentions.contains(&me),
+        NotificationLevel::None => false,
+    };
+    if !show {
+        return Ok(());
+    }
+    
+    emit_zome_signal(signal)
+}
+
+// ============================================================================
+// ATTENDANCE CHECK-IN
+// ============================================================================
+
+/// Generate a signed, time-limited check-in code (host only)
+///
+/// The host displays the code as a QR; attendees scan it and pass it to
+/// `check_in`. Codes expire quickly so a screenshot can't be reused later.
+#[hdk_extern]
+pub fn generate_checkin_code(event_hash: ActionHash) -> ExternResult<CheckinCode> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    let event = get_event_entry(event_hash.clone())?;
+    
+    if event.host != agent {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only the event host can generate check-in codes".into())));
+    }
+    
+    let issued_at = sys_time()?;
+    let expires_at = Timestamp::from_micros(issued_at.as_micros() + CHECKIN_CODE_VALIDITY_MICROS);
+    let signature = sign(agent, checkin_code_payload(&event_hash, issued_at, expires_at))?;
+    
+    Ok(CheckinCode {
+        event_hash,
+        issued_at,
+        expires_at,
+        signature,
+    })
+}
+
+/// Check in to an event using a code scanned from the host
+#[hdk_extern]
+pub fn check_in(input: CheckInInput) -> ExternResult<AttendanceOutput> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    
+    if input.code.event_hash != input.event_hash {
+        return Err(wasm_error!(WasmErrorInner::Guest("Check-in code is for a different event".into())));
+    }
+    
+    let event = get_event_entry(input.event_hash.clone())?;
+    if event.host == agent {
+        return Err(wasm_error!(WasmErrorInner::Guest("Host cannot check in to their own event".into())));
+    }
+    
+    let now = sys_time()?;
+    if now < input.code.issued_at || now > input.code.expires_at {
+        return Err(wasm_error!(WasmErrorInner::Guest("Check-in code has expired".into())));
+    }
+    
+    let payload = checkin_code_payload(&input.event_hash, input.code.issued_at, input.code.expires_at);
+    if !verify_signature(event.host.clone(), input.code.signature.clone(), payload)? {
+        return Err(wasm_error!(WasmErrorInner::Guest("Invalid check-in code".into())));
+    }
+    
+    // One check-in per agent per event
+    let existing = get_attendance(input.event_hash.clone())?;
+    if existing.iter().any(|a| a.attendance.attendee == agent) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Already checked in to this event".into())));
+    }
+    
+    let attendance = Attendance {
+        event_hash: input.event_hash.clone(),
+        attendee: agent.clone(),
+        code_issued_at: input.code.issued_at,
+        code_expires_at: input.code.expires_at,
+        host_signature: input.code.signature,
+        checked_in_at: now,
+    };
+    
+    ensure_membership_proof()?;
+    let action_hash = create_entry(EntryTypes::Attendance(attendance.clone()))?;
+    
+    create_link(
+        input.event_hash.clone(),
+        action_hash.clone(),
+        LinkTypes::EventToAttendance,
+        (),
+    )?;
+    
+    create_link(
+        agent.clone(),
+        action_hash.clone(),
+        LinkTypes::AgentToAttendance,
+        (),
+    )?;
+    
+    emit_zome_signal(Signal::CheckedIn {
+        event_hash: input.event_hash,
+        attendee: agent,
+    })?;
+    
+    Ok(AttendanceOutput {
+        attendance,
+        action_hash,
+    })
+}
+
+/// Get everyone who checked in to an event
+#[hdk_extern]
+pub fn get_attendance(event_hash: ActionHash) -> ExternResult<Vec<AttendanceOutput>> {
+    let links = get_links(
+        LinkQuery::try_new(event_hash, LinkTypes::EventToAttendance)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut attendance = Vec::new();
+    
+    for link in links {
+        if let Some(action_hash) = link.target.into_action_hash() {
+            if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
+                if let Some(entry) = record.entry().to_app_option::<Attendance>()
+                    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+                {
+                    attendance.push(AttendanceOutput {
+                        attendance: entry,
+                        action_hash,
+                    });
+                }
+            }
+        }
+    }
+    
+    attendance.sort_by(|a, b| a.attendance.checked_in_at.cmp(&b.attendance.checked_in_at));
+    
+    Ok(attendance)
+}
+
+// ============================================================================
+// EVENT TEMPLATES
+// ============================================================================
+
+/// Save reusable defaults for a recurring event
+#[hdk_extern]
+pub fn create_event_template(input: EventTemplateInput) -> ExternResult<EventTemplateOutput> {
+    let creator = agent_info()?.agent_initial_pubkey;
+    let template = event_template(input, creator.clone())?;
+    
+    ensure_membership_proof()?;
+    let template_hash = create_entry(EntryTypes::EventTemplate(template.clone()))?;
+    create_link(
+        creator,
+        template_hash.clone(),
+        LinkTypes::AgentToEventTemplates,
+        (),
+    )?;
+    
+    Ok(EventTemplateOutput {
+        template,
+        revision_hash: template_hash.clone(),
+        template_hash,
+    })
+}
+
+/// Replace the defaults in one of my templates
+#[hdk_extern]
+pub fn update_event_template(input: UpdateEventTemplateInput) -> ExternResult<EventTemplateOutput> {
+    let creator = agent_info()?.agent_initial_pubkey;
+    let latest = get_event_template(input.template_hash.clone())?;
+    if latest.template.creator != creator {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the creator can edit their template".into()
+        )));
+    }
+    
+    let template = event_template(input.template, creator)?;
+    let revision_hash = update_entry(latest.revision_hash, &template)?;
+    
+    Ok(EventTemplateOutput {
+        template,
+        template_hash: input.template_hash,
+        revision_hash,
+    })
+}
+
+/// The latest revision of a template
+#[hdk_extern]
+pub fn get_event_template(template_hash: ActionHash) -> ExternResult<EventTemplateOutput> {
+    let Some(record) = latest_record(template_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Template not found".into())));
+    };
+    let template = record
+        .entry()
+        .to_app_option::<EventTemplate>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid template entry".into())))?;
+    
+    Ok(EventTemplateOutput {
+        template,
+        template_hash,
+        revision_hash: record.action_address().clone(),
+    })
+}
+
+/// Templates the calling agent created, by title
+#[hdk_extern]
+pub fn get_my_event_templates(_: ()) -> ExternResult<Vec<EventTemplateOutput>> {
+    let creator = agent_info()?.agent_initial_pubkey;
+    let links = get_links(
+        LinkQuery::try_new(creator, LinkTypes::AgentToEventTemplates)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut templates = Vec::new();
+    for link in links {
+        if let Some(template_hash) = link.target.into_action_hash() {
+            templates.push(get_event_template(template_hash)?);
+        }
+    }
+    
+    templates.sort_by(|a, b| a.template.title.cmp(&b.template.title));
+    Ok(templates)
+}
+
+/// Schedule a new event on `event_date` using a template's latest defaults
+#[hdk_extern]
+pub fn create_event_from_template(input: CreateEventFromTemplateInput) -> ExternResult<EventOutput> {
+    let template = get_event_template(input.template_hash)?.template;
+    
+    create_event(CreateEventInput {
+        title: template.title,
+        description: template.description,
+        location: template.location,
+        event_date: input.event_date,
+        max_attendees: template.max_attendees,
+        place: None,
+        category: template.category,
+        tags: template.tags,
+        guest_list_visibility: GuestListVisibility::default(),
+        duration_minutes: template.duration_minutes,
+        visibility: EventVisibility::default(),
+        invitees: Vec::new(),
+        idempotency_key: input.idempotency_key,
+    })
+}
+
 // ============================================================================
 // HELPER FUNCTIONS
 // ============================================================================
 
+fn event_template(input: EventTemplateInput, creator: AgentPubKey) -> ExternResult<EventTemplate> {
+    Ok(EventTemplate {
+        title: input.title.trim().to_string(),
+        description: input.description,
+        location: input.location,
+        duration_minutes: input.duration_minutes,
+        max_attendees: input.max_attendees,
+        category: input.category.map(|category| category.trim().to_string()),
+        tags: normalized_tags(input.tags),
+        creator,
+        created_at: sys_time()?,
+    })
+}
+
+/// Invitees without the host or repeats
+fn invitees(requested: Vec<AgentPubKey>, host: &AgentPubKey) -> Vec<AgentPubKey> {
+    let mut invitees: Vec<AgentPubKey> = Vec::new();
+    for agent in requested {
+        if agent != *host && !invitees.contains(&agent) {
+            invitees.push(agent);
+        }
+    }
+    invitees
+}
+
+fn get_event_entry(event_hash: ActionHash) -> ExternResult<Event> {
+    let Some(record) = get(event_hash, GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Event not found".into())));
+    };
+    
+    record.entry().to_app_option::<Event>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid event entry".into())))
+}
+
+/// Build an EventOutput, trimming the guest list to what the viewer may see
+///
+/// The count is always reported, and the viewer can always see themselves.
+fn event_output(mut event: Event, action_hash: ActionHash, viewer: &AgentPubKey) -> ExternResult<EventOutput> {
+    let entry_hash = hash_entry(&event)?;
+    let attendee_count = event.attendees.len();
+    if !can_see_guest_list(&event, viewer) {
+        let host = event.host.clone();
+        event.attendees.retain(|attendee| attendee == viewer || *attendee == host);
+    }
+    
+    Ok(EventOutput {
+        event,
+        action_hash,
+        entry_hash,
+        attendee_count,
+    })
+}
+
+fn can_see_guest_list(event: &Event, viewer: &AgentPubKey) -> bool {
+    match event.guest_list_visibility {
+        GuestListVisibility::Public => true,
+        GuestListVisibility::AttendeesOnly => event.host == *viewer || event.attendees.contains(viewer),
+        GuestListVisibility::HostOnly => event.host == *viewer,
+    }
+}
+
+fn latest_record(action_hash: ActionHash) -> ExternResult<Option<Record>> {
+    let mut current = action_hash;
+    
+    loop {
+        let Some(Details::Record(details)) = get_details(current, GetOptions::default())? else {
+            return Ok(None);
+        };
+        let next = details
+            .updates
+            .iter()
+            .max_by_key(|update| update.action().timestamp())
+            .map(|update| update.action_address().clone());
+        match next {
+            Some(next) => current = next,
+            None => return Ok(Some(details.record)),
+        }
+    }
+}
+
+/// Weeks since the Unix epoch
+fn week_of(timestamp: Timestamp) -> i64 {
+    timestamp.as_micros().div_euclid(WEEK_MICROS)
+}
+
+fn week_bucket_hash(week: i64) -> ExternResult<EntryHash> {
+    let path = Path::from(format!("{}.{}", WEEK_BUCKET_PATH, week));
+    path.path_entry_hash()
+}
+
+fn week_events(week: i64, viewer: &AgentPubKey, network: bool) -> ExternResult<Vec<EventOutput>> {
+    let strategy = if network { GetStrategy::Network } else { GetStrategy::Local };
+    let links = get_links(
+        LinkQuery::try_new(week_bucket_hash(week)?, LinkTypes::WeekToEvents)?,
+        strategy,
+    )?;
+    
+    let mut events = Vec::new();
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        if events.iter().any(|output: &EventOutput| output.action_hash == action_hash) {
+            continue;
+        }
+        let event = get_event_entry(action_hash.clone())?;
+        if event.is_invited(viewer) {
+            events.push(event_output(event, action_hash, viewer)?);
+        }
+    }
+    
+    Ok(events)
+}
+
+fn matches_filters(event: &Event, category: &Option<String>, tag: &Option<String>) -> bool {
+    let category_matches = category.as_ref().map_or(true, |category| {
+        event
+            .category
+            .as_ref()
+            .is_some_and(|own| own.eq_ignore_ascii_case(category.trim()))
+    });
+    let tag_matches = tag
+        .as_ref()
+        .map_or(true, |tag| event.tags.contains(&tag.trim().to_lowercase()));
+    category_matches && tag_matches
+}
+
+fn normalized_tags(tags: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().to_lowercase();
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+    normalized
+}
+
+fn page_size(requested: Option<u32>) -> usize {
+    requested
+        .unwrap_or(DEFAULT_EVENTS_PAGE_SIZE)
+        .clamp(1, MAX_EVENTS_PAGE_SIZE) as usize
+}
+
 fn all_events_anchor_hash() -> ExternResult<EntryHash> {
     let path = Path::from(ALL_EVENTS_PATH);
     path.path_entry_hash()
 }
+
+/// Add or remove an event in the search zome's keyword index
+fn call_search(fn_name: &str, target: ActionHash, title: &str, text: &str) -> ExternResult<()> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("search"),
+        FunctionName::from(fn_name),
+        None,
+        SearchIndexInput {
+            kind: SearchKind::Event,
+            target,
+            title: title.to_string(),
+            text: text.to_string(),
+        },
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to update search index: {:?}",
+            other
+        )))),
+    }
+}
+
+/// Add a located event to the search zome's geohash index, if it has a geohash
+fn index_place(target: ActionHash, title: &str, place: &Option<Location>) -> ExternResult<()> {
+    let Some(geohash) = place.as_ref().and_then(|place| place.geohash.clone()) else {
+        return Ok(());
+    };
+
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("search"),
+        FunctionName::from("index_location"),
+        None,
+        LocationIndexInput {
+            kind: SearchKind::Event,
+            target,
+            title: title.to_string(),
+            geohash,
+        },
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to update location index: {:?}",
+            other
+        )))),
+    }
+}
+
+/// Mirror of the profile zome's GrantBadgeInput
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct GrantBadgeInput {
+    agent: AgentPubKey,
+    kind: BadgeKind,
+    evidence: Vec<ActionHash>,
+}
+
+/// The profile zome's BadgeKind variant this zome grants
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum BadgeKind {
+    EventHost,
+}
+
+/// Offer the profile zome the events an agent hosted as badge evidence;
+/// it grants the badge once there are enough and ignores the call otherwise
+fn grant_badge(agent: AgentPubKey, evidence: Vec<ActionHash>) -> ExternResult<()> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("profile"),
+        FunctionName::from("grant_badge"),
+        None,
+        GrantBadgeInput {
+            agent,
+            kind: BadgeKind::EventHost,
+            evidence,
+        },
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to grant badge: {:?}",
+            other
+        )))),
+    }
+}
+
+/// The profile zome's OnboardingStep variant this zome completes
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum OnboardingStep {
+    JoinedFirstEvent,
+}
+
+/// Tick off an onboarding step in the profile zome; it ignores steps already done
+fn record_onboarding_step() -> ExternResult<()> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("profile"),
+        FunctionName::from("record_onboarding_step"),
+        None,
+        OnboardingStep::JoinedFirstEvent,
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to record onboarding step: {:?}",
+            other
+        )))),
+    }
+}
+
+/// Have the vouch zome put a MembershipProof on our chain if we don't have
+/// one yet; integrity rejects events, RSVPs, and templates from authors without one
+fn ensure_membership_proof() -> ExternResult<()> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("vouch"),
+        FunctionName::from("ensure_membership_proof"),
+        None,
+        (),
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to check membership proof: {:?}",
+            other
+        )))),
+    }
+}
+
+// ============================================================================
+// DATA EXPORT
+// ============================================================================
+
+/// Events the calling agent hosts or RSVP'd to, and their check-ins
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EventsExport {
+    pub hosted: Vec<EventOutput>,
+    pub attending: Vec<EventOutput>,
+    pub check_ins: Vec<AttendanceOutput>,
+}
+
+/// Export the caller's events footprint
+#[hdk_extern]
+pub fn export_my_data(_: ()) -> ExternResult<EventsExport> {
+    let viewer = agent_info()?.agent_initial_pubkey;
+    let mut hosted = Vec::new();
+    for (action_hash, event) in my_chain_entries::<Event>(UnitEntryTypes::Event)? {
+        hosted.push(event_output(event, action_hash, &viewer)?);
+    }
+
+    let check_ins = my_chain_entries::<Attendance>(UnitEntryTypes::Attendance)?
+        .into_iter()
+        .map(|(action_hash, attendance)| AttendanceOutput {
+            attendance,
+            action_hash,
+        })
+        .collect();
+
+    Ok(EventsExport {
+        hosted,
+        attending: get_my_events(())?,
+        check_ins,
+    })
+}
+
+/// Every entry of one type on my own source chain, oldest first
+fn my_chain_entries<T>(entry_type: UnitEntryTypes) -> ExternResult<Vec<(ActionHash, T)>>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(entry_type.try_into()?)
+            .include_entries(true),
+    )?;
+
+    let mut entries = Vec::new();
+    for record in records {
+        if let Some(entry) = record
+            .entry()
+            .to_app_option::<T>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        {
+            entries.push((record.action_address().clone(), entry));
+        }
+    }
+
+    Ok(entries)
+}
@@ -11,14 +11,36 @@
 
 use hdk::prelude::*;
 use feed_integrity::*;
+use idempotency::{find_previous_create, idempotency_tag};
+use list_options::{get_list_links, ListOptions};
+use publish_status::{publish_status, PublishStatusOutput};
+use signal_envelope::SignalEnvelope;
+use std::collections::{BTreeSet, HashMap, HashSet};
 
-/// Signal types for real-time updates
+/// Signal types for real-time updates, emitted inside a SignalEnvelope
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(tag = "type")]
+#[serde(tag = "event", content = "payload")]
 pub enum Signal {
     NewPost { post_hash: ActionHash, post: Post },
     NewReaction { post_hash: ActionHash, reaction_hash: ActionHash },
+    NewCommentReaction { comment_hash: ActionHash, reaction_hash: ActionHash },
     NewComment { post_hash: ActionHash, comment_hash: ActionHash },
+    /// Sent by the resolving anchor to the neighbor who filed the report
+    ReportResolved {
+        report_hash: ActionHash,
+        resolution_hash: ActionHash,
+        post_hash: ActionHash,
+        action: ResolutionAction,
+    },
+    /// Sent by the resolving anchor to the author of a post they were warned about
+    ModerationWarning { post_hash: ActionHash, resolution_hash: ActionHash, note: Option<String> },
+}
+
+/// Zome name clients see in the signal envelope
+const SIGNAL_ZOME: &str = "feed";
+
+fn emit_zome_signal(signal: Signal) -> ExternResult<()> {
+    emit_signal(SignalEnvelope::new(SIGNAL_ZOME, signal))
 }
 
 /// Input for creating a post
@@ -26,20 +48,103 @@ pub enum Signal {
 pub struct CreatePostInput {
     pub title: String,
     pub content: String,
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Previews the client already fetched for URLs in the content
+    #[serde(default)]
+    pub links: Vec<LinkPreview>,
+    /// Tags to add besides the `#tags` written in the content
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Client-chosen key; resubmitting with the same key returns the original
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+}
+
+/// Input for editing a post; the edit always applies to the latest revision
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UpdatePostInput {
+    pub original_post_hash: ActionHash,
+    pub title: String,
+    pub content: String,
+    /// Replaces the tags given outside the content; None keeps them
+    #[serde(default)]
+    pub tags: Option<Vec<String>>,
+}
+
+/// Input for adding a link preview card to a post
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AttachLinkPreviewInput {
+    pub original_post_hash: ActionHash,
+    pub preview: LinkPreview,
+}
+
+/// Where a page of posts stopped: the last link read, by its timestamp and
+/// then its action hash, so links made in the same microsecond are neither
+/// skipped nor repeated across pages
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PageCursor {
+    pub timestamp: Timestamp,
+    pub action_hash: ActionHash,
+}
+
+/// Input for one page of the feed
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PostsPageInput {
+    /// Cursor from the previous page's `next_before`; None for the newest posts
+    pub before: Option<PageCursor>,
+    pub limit: Option<usize>,
+    /// Read the feed from the network even when posts are held locally
+    #[serde(default)]
+    pub prefer_network: bool,
+}
+
+/// Input for one page of posts carrying a hashtag
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PostsByTagInput {
+    /// With or without the leading `#`
+    pub tag: String,
+    pub page: PostsPageInput,
+}
+
+/// One page of the feed, with the caller's preferences applied
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PostsPage {
+    pub posts: Vec<PostOutput>,
+    /// Pass as `before` to get the next page; None when there are no more
+    pub next_before: Option<PageCursor>,
+}
+
+/// Input for replacing the caller's feed preferences
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetFeedPreferencesInput {
+    pub muted_agents: Vec<AgentPubKey>,
+    pub muted_channels: Vec<String>,
+    pub keyword_filters: Vec<String>,
 }
 
 /// Output after creating or fetching a post
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct PostOutput {
     pub post: Post,
+    /// The original post's hash; reactions and comments attach here
     pub action_hash: ActionHash,
     pub entry_hash: EntryHash,
+    /// The revision `post` was read from; equals `action_hash` until edited
+    pub revision_hash: ActionHash,
+    pub edited_at: Option<Timestamp>,
+    /// Cached comment count; filled in on feed pages, None elsewhere
+    pub comment_count: Option<u32>,
 }
 
 /// Input for creating a reaction
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CreateReactionInput {
-    pub post_hash: ActionHash,
+    /// The post or comment to react to
+    #[serde(alias = "post_hash")]
+    pub target: ActionHash,
+    #[serde(default)]
+    pub target_kind: ReactionTarget,
     pub reaction_type: String,
 }
 
@@ -66,8 +171,118 @@ pub struct CommentOutput {
     pub entry_hash: EntryHash,
 }
 
+/// Reactions of one type on a post or comment
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReactionSummary {
+    pub reaction_type: String,
+    pub count: u32,
+    /// The caller's own reaction of this type, for removing it
+    pub my_reaction: Option<ActionHash>,
+}
+
+/// A comment with its reactions summarized
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CommentWithReactions {
+    pub comment: CommentOutput,
+    pub reactions: Vec<ReactionSummary>,
+}
+
+/// Everything needed to render a post in the feed
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PostWithDetails {
+    pub post: PostOutput,
+    /// Oldest first
+    pub comments: Vec<CommentWithReactions>,
+    pub reactions: Vec<ReactionSummary>,
+}
+
+/// Input for posting a lost or found item
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreateLostFoundInput {
+    pub kind: LostFoundKind,
+    pub item_description: String,
+    pub last_seen: Option<Location>,
+    pub last_seen_at: Option<Timestamp>,
+    pub details: Option<String>,
+}
+
+/// A lost-and-found post at its latest revision
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LostFoundOutput {
+    pub post: LostFoundPost,
+    /// The original post's hash; status changes are made against this
+    pub action_hash: ActionHash,
+    pub revision_hash: ActionHash,
+}
+
+/// Input for reporting a post to the anchors
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReportPostInput {
+    pub post_hash: ActionHash,
+    pub reason: String,
+}
+
+/// A report with its hash
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ReportOutput {
+    pub report: Report,
+    pub action_hash: ActionHash,
+}
+
+/// Input for an anchor's decision on a report
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ResolveReportInput {
+    pub report_hash: ActionHash,
+    pub action: ResolutionAction,
+    pub note: Option<String>,
+}
+
+/// A resolution with its hash
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ResolutionOutput {
+    pub resolution: ReportResolution,
+    pub action_hash: ActionHash,
+}
+
+/// Mirror of the search zome's IndexContentInput
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct SearchIndexInput {
+    kind: SearchKind,
+    target: ActionHash,
+    title: String,
+    text: String,
+}
+
+/// The search zome's ContentKind variant for posts
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum SearchKind {
+    Post,
+}
+
+/// Mirror of the vouch zome's AccessCheckInput
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct AccessCheckInput {
+    agent: AgentPubKey,
+    scope: Option<GuestScope>,
+}
+
+/// The vouch zome's GuestScope variant for reading the feed
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum GuestScope {
+    ViewFeed,
+}
+
 /// Anchor path for listing all posts
 const ALL_POSTS_PATH: &str = "all_posts";
+/// Anchor path for reports awaiting an anchor's review
+const REVIEW_QUEUE_PATH: &str = "review_queue";
+/// Anchor path for listing lost and found posts
+const LOST_FOUND_PATH: &str = "lost_and_found";
+/// Prefix of the per-hashtag anchor paths, e.g. `tags.garden`
+const TAGS_PATH: &str = "tags";
+
+const DEFAULT_PAGE_SIZE: usize = 20;
+const MAX_PAGE_SIZE: usize = 100;
 
 // ============================================================================
 // POST CREATION
@@ -85,28 +300,42 @@ const ALL_POSTS_PATH: &str = "all_posts";
 #[hdk_extern]
 pub fn create_post(input: CreatePostInput) -> ExternResult<PostOutput> {
     let author = agent_info()?.agent_initial_pubkey;
+    require_access(None)?;
+    
+    if let Some(original) = find_previous_create(
+        input.idempotency_key.as_deref(),
+        author.clone(),
+        LinkTypes::AgentToPosts,
+    )? {
+        return get_post(original)?
+            .ok_or(wasm_error!(WasmErrorInner::Guest("Original post not found".into())));
+    }
     
     // Create the post entry
     let post = Post {
+        tags: post_tags(&input.tags, &input.content)?,
         title: input.title,
         content: input.content,
         author: author.clone(),
         created_at: sys_time()?,
+        channel: input.channel,
+        links: input.links,
     };
     
+    ensure_membership_proof()?;
     let action_hash = create_entry(EntryTypes::Post(post.clone()))?;
     let entry_hash = hash_entry(&post)?;
     
-    // Create link from author to post
+    // Create link from author to post, tagged with the idempotency key
     create_link(
         author.clone(),
         action_hash.clone(),
         LinkTypes::AgentToPosts,
-        (),
+        idempotency_tag(input.idempotency_key.as_deref())?,
     )?;
     
     // Emit signal for real-time updates
-    emit_signal(Signal::NewPost {
+    emit_zome_signal(Signal::NewPost {
         post_hash: action_hash.clone(),
         post: post.clone(),
     })?;
@@ -119,11 +348,20 @@ pub fn create_post(input: CreatePostInput) -> ExternResult<PostOutput> {
         LinkTypes::AllPosts,
         (),
     )?;
+    record_comment_count(&action_hash)?;
+    
+    call_search("index_content", action_hash.clone(), &post.title, &post.content)?;
+    for tag in &post.tags {
+        create_link(tag_anchor_hash(tag)?, action_hash.clone(), LinkTypes::TagToPosts, ())?;
+    }
     
     Ok(PostOutput {
         post,
+        revision_hash: action_hash.clone(),
         action_hash,
         entry_hash,
+        edited_at: None,
+        comment_count: Some(0),
     })
 }
 
@@ -160,15 +398,14 @@ pub fn create_verified_post(input: CreatePostInput) -> ExternResult<PostOutput>
 
 /// Get all posts in the DHT
 ///
-/// Fetches all posts from the global anchor. Returns them in the order
-/// they were linked (roughly chronological).
+/// Fetches all posts from the global anchor, each at its latest revision,
+/// newest first.
 #[hdk_extern]
-pub fn get_all_posts(_: ()) -> ExternResult<Vec<PostOutput>> {
+pub fn get_all_posts(options: Option<ListOptions>) -> ExternResult<Vec<PostOutput>> {
+    require_access(Some(GuestScope::ViewFeed))?;
+    let options = options.unwrap_or_default();
     let all_posts_anchor = all_posts_anchor_hash()?;
-    let links = get_links(
-        LinkQuery::try_new(all_posts_anchor, LinkTypes::AllPosts)?,
-        GetStrategy::Local,
-    )?;
+    let links = get_list_links(all_posts_anchor, LinkTypes::AllPosts, options.prefer_network)?;
     
     let mut posts = Vec::new();
     
@@ -177,19 +414,11 @@ pub fn get_all_posts(_: ()) -> ExternResult<Vec<PostOutput>> {
             wasm_error!(WasmErrorInner::Guest("Invalid action hash in link".to_string()))
         })?;
         
-        if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
-            if let Some(post) = record
-                .entry()
-                .to_app_option::<Post>()
-                .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
-            {
-                let entry_hash = hash_entry(&post)?;
-                posts.push(PostOutput {
-                    post,
-                    action_hash,
-                    entry_hash,
-                });
-            }
+        if is_hidden(&action_hash)? {
+            continue;
+        }
+        if let Some(post_output) = get_latest_post(action_hash)? {
+            posts.push(post_output);
         }
     }
     
@@ -199,11 +428,80 @@ pub fn get_all_posts(_: ()) -> ExternResult<Vec<PostOutput>> {
     Ok(posts)
 }
 
+/// Get one page of the feed, newest first, without posts the caller has muted
+///
+/// Muted authors, muted channels, and keyword filters from the caller's
+/// feed preferences are applied here, so filtered posts never reach the UI.
+/// Posts an anchor has hidden are left out as well.
+/// Muted authors are skipped before their posts are fetched.
+#[hdk_extern]
+pub fn get_posts_page(input: PostsPageInput) -> ExternResult<PostsPage> {
+    require_access(Some(GuestScope::ViewFeed))?;
+    let links = get_list_links(all_posts_anchor_hash()?, LinkTypes::AllPosts, input.prefer_network)?;
+    posts_page(links, &input)
+}
+
+/// One page of posts from a set of links to original posts, newest link first,
+/// with the caller's feed preferences and anchor hides applied
+fn posts_page(mut links: Vec<Link>, input: &PostsPageInput) -> ExternResult<PostsPage> {
+    let limit = input.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let preferences = get_feed_preferences(())?;
+    let comment_counts = cached_comment_counts(input.prefer_network)?;
+    
+    links.retain(|link| {
+        input.before.as_ref().map_or(true, |before| {
+            (link.timestamp, &link.create_link_hash) < (before.timestamp, &before.action_hash)
+        })
+    });
+    links.sort_by(|a, b| {
+        b.timestamp
+            .cmp(&a.timestamp)
+            .then_with(|| b.create_link_hash.cmp(&a.create_link_hash))
+    });
+    
+    let mut posts = Vec::new();
+    let mut next_before = None;
+    let mut last_seen = None;
+    
+    for link in links {
+        if posts.len() == limit {
+            next_before = last_seen;
+            break;
+        }
+        last_seen = Some(PageCursor {
+            timestamp: link.timestamp,
+            action_hash: link.create_link_hash.clone(),
+        });
+        if preferences.muted_agents.contains(&link.author) {
+            continue;
+        }
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        if is_hidden(&action_hash)? {
+            continue;
+        }
+        if let Some(mut post_output) = get_latest_post(action_hash)? {
+            if !is_filtered(&post_output.post, &preferences) {
+                post_output.comment_count = Some(match comment_counts.get(&post_output.action_hash) {
+                    Some(count) => *count,
+                    // Posts from before counts were cached
+                    None => count_comments(&post_output.action_hash)?,
+                });
+                posts.push(post_output);
+            }
+        }
+    }
+    
+    Ok(PostsPage { posts, next_before })
+}
+
 /// Get all posts by a specific agent
 ///
 /// Follows the AgentToPosts links from the given agent's public key.
 #[hdk_extern]
 pub fn get_posts_for_agent(agent: AgentPubKey) -> ExternResult<Vec<PostOutput>> {
+    require_access(Some(GuestScope::ViewFeed))?;
     let links = get_links(
         LinkQuery::try_new(agent, LinkTypes::AgentToPosts)?,
         GetStrategy::Local,
@@ -216,19 +514,11 @@ pub fn get_posts_for_agent(agent: AgentPubKey) -> ExternResult<Vec<PostOutput>>
             wasm_error!(WasmErrorInner::Guest("Invalid action hash in link".to_string()))
         })?;
         
-        if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
-            if let Some(post) = record
-                .entry()
-                .to_app_option::<Post>()
-                .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
-            {
-                let entry_hash = hash_entry(&post)?;
-                posts.push(PostOutput {
-                    post,
-                    action_hash,
-                    entry_hash,
-                });
-            }
+        if is_hidden(&action_hash)? {
+            continue;
+        }
+        if let Some(post_output) = get_latest_post(action_hash)? {
+            posts.push(post_output);
         }
     }
     
@@ -245,179 +535,1124 @@ pub fn get_my_posts(_: ()) -> ExternResult<Vec<PostOutput>> {
     get_posts_for_agent(agent)
 }
 
-/// Get a single post by its action hash
+/// Get a single post record by its action hash, without following edits
+///
+/// Use `get_latest_post` to show a post; this returns exactly the revision asked for.
 #[hdk_extern]
 pub fn get_post(action_hash: ActionHash) -> ExternResult<Option<PostOutput>> {
     let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
         return Ok(None);
     };
     
-    let Some(post) = record
-        .entry()
-        .to_app_option::<Post>()
-        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
-    else {
+    post_output(action_hash, &record)
+}
+
+/// Get a post at its latest revision
+#[hdk_extern]
+pub fn get_latest_post(original_hash: ActionHash) -> ExternResult<Option<PostOutput>> {
+    let revisions = post_revision_records(original_hash.clone(), false)?;
+    let Some(record) = revisions.last() else {
         return Ok(None);
     };
     
-    let entry_hash = hash_entry(&post)?;
-    
-    Ok(Some(PostOutput {
-        post,
-        action_hash,
-        entry_hash,
-    }))
+    post_output(original_hash, record)
 }
 
-// ============================================================================
-// HELPER FUNCTIONS
-// ============================================================================
+/// Get every revision of a post, oldest (the original) first
+#[hdk_extern]
+pub fn get_post_revisions(original_hash: ActionHash) -> ExternResult<Vec<PostOutput>> {
+    let mut revisions = Vec::new();
+    for record in post_revision_records(original_hash.clone(), true)? {
+        if let Some(revision) = post_output(original_hash.clone(), &record)? {
+            revisions.push(revision);
+        }
+    }
+    
+    Ok(revisions)
+}
 
-/// Get the path hash for the all-posts anchor
-fn all_posts_anchor_hash() -> ExternResult<EntryHash> {
-    let path = Path::from(ALL_POSTS_PATH);
-    path.path_entry_hash()
+/// How far one of my posts or its edits has spread, from its validation receipts
+///
+/// Pass the revision hash to check an edit rather than the original post.
+#[hdk_extern]
+pub fn get_publish_status(action_hash: ActionHash) -> ExternResult<PublishStatusOutput> {
+    publish_status::<Post>(action_hash, "Post")
 }
 
 // ============================================================================
-// REACTIONS
+// POST EDITING
 // ============================================================================
 
-/// Add a reaction to a post
+/// Edit a post (author only)
+///
+/// The edit is recorded as an update of the latest revision, so the full
+/// history stays available through `get_post_revisions`. The post keeps
+/// its original hash, reactions, and comments.
 #[hdk_extern]
-pub fn add_reaction(input: CreateReactionInput) -> ExternResult<ReactionOutput> {
-    let author = agent_info()?.agent_initial_pubkey;
-    
-    let reaction = Reaction {
-        post_hash: input.post_hash.clone(),
-        author: author.clone(),
-        reaction_type: input.reaction_type,
-        created_at: sys_time()?,
+pub fn update_post(input: UpdatePostInput) -> ExternResult<PostOutput> {
+    let Some(latest) = get_latest_post(input.original_post_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Post not found".to_string())));
     };
+    // Tags that didn't come from the old content were given explicitly
+    let explicit_tags = input.tags.unwrap_or_else(|| {
+        let old_content_tags = parse_hashtags(&latest.post.content);
+        latest.post.tags.into_iter().filter(|tag| !old_content_tags.contains(tag)).collect()
+    });
+    let tags = post_tags(&explicit_tags, &input.content)?;
     
-    let action_hash = create_entry(EntryTypes::Reaction(reaction.clone()))?;
-    let entry_hash = hash_entry(&reaction)?;
-    
-    // Link from post to reaction
-    create_link(
-        input.post_hash.clone(),
-        action_hash.clone(),
-        LinkTypes::PostToReactions,
-        (),
-    )?;
-    
-    // Link from agent to reaction (for finding user's reactions)
-    create_link(
-        author,
-        action_hash.clone(),
-        LinkTypes::AgentToReactions,
-        (),
-    )?;
-        // Emit signal for real-time updates
-    emit_signal(Signal::NewReaction {
-        post_hash: input.post_hash.clone(),
-        reaction_hash: action_hash.clone(),
-    })?;
-        Ok(ReactionOutput {
-        reaction,
-        action_hash,
-        entry_hash,
+    revise_post(input.original_post_hash, |post| {
+        post.title = input.title;
+        post.content = input.content;
+        post.tags = tags;
     })
 }
 
-/// Remove a reaction (by deleting the entry and links)
+/// Attach a link preview card to a post (author only)
+///
+/// The client fetches the page's title, description, and image (uploaded
+/// through file_storage) since zomes can't make HTTP requests; integrity
+/// only checks the sizes. Attaching the same URL again replaces its card.
 #[hdk_extern]
-pub fn remove_reaction(reaction_hash: ActionHash) -> ExternResult<()> {
-    delete_entry(reaction_hash)?;
-    Ok(())
+pub fn attach_link_preview(input: AttachLinkPreviewInput) -> ExternResult<PostOutput> {
+    revise_post(input.original_post_hash, |post| {
+        post.links.retain(|link| link.url != input.preview.url);
+        post.links.push(input.preview);
+    })
 }
 
-/// Get all reactions for a post
-#[hdk_extern]
-pub fn get_post_reactions(post_hash: ActionHash) -> ExternResult<Vec<ReactionOutput>> {
-    let links = get_links(
-        LinkQuery::try_new(post_hash, LinkTypes::PostToReactions)?,
-        GetStrategy::Local,
-    )?;
+/// Apply an edit to the latest revision of one of the caller's posts
+fn revise_post(original_post_hash: ActionHash, edit: impl FnOnce(&mut Post)) -> ExternResult<PostOutput> {
+    let author = agent_info()?.agent_initial_pubkey;
     
-    let mut reactions = Vec::new();
+    let Some(latest) = get_latest_post(original_post_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Post not found".to_string())));
+    };
     
-    for link in links {
-        if let Some(action_hash) = link.target.into_action_hash() {
-            if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
-                if let Some(reaction) = record.entry().to_app_option::<Reaction>()
-                    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))? 
-                {
-                    let entry_hash = hash_entry(&reaction)?;
-                    reactions.push(ReactionOutput {
-                        reaction,
-                        action_hash,
-                        entry_hash,
-                    });
-                }
-            }
-        }
+    if latest.post.author != author {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the author can edit their post".to_string()
+        )));
     }
     
-    Ok(reactions)
+    let mut post = latest.post.clone();
+    edit(&mut post);
+    
+    let revision_hash = update_entry(latest.revision_hash, &post)?;
+    
+    // Re-index under the original hash so search results keep pointing at the post
+    if post.title != latest.post.title || post.content != latest.post.content {
+        call_search("unindex_content", original_post_hash.clone(), &latest.post.title, &latest.post.content)?;
+        call_search("index_content", original_post_hash.clone(), &post.title, &post.content)?;
+    }
+    retag_post(&original_post_hash, &latest.post.tags, &post.tags)?;
+    
+    let Some(record) = get(revision_hash, GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Updated post not found".to_string())));
+    };
+    post_output(original_post_hash, &record)?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Updated post not found".into())))
 }
 
 // ============================================================================
-// COMMENTS
+// FEED PREFERENCES
 // ============================================================================
 
-/// Add a comment to a post
+/// Replace the caller's feed preferences
+///
+/// Stored as a private entry on the caller's own chain; nobody else can see
+/// who or what they have muted.
 #[hdk_extern]
-pub fn add_comment(input: CreateCommentInput) -> ExternResult<CommentOutput> {
-    let author = agent_info()?.agent_initial_pubkey;
+pub fn set_feed_preferences(input: SetFeedPreferencesInput) -> ExternResult<FeedPreferences> {
+    let mut muted_agents = input.muted_agents;
+    muted_agents.sort();
+    muted_agents.dedup();
     
-    let comment = Comment {
-        post_hash: input.post_hash.clone(),
-        author,
-        content: input.content,
-        created_at: sys_time()?,
+    let preferences = FeedPreferences {
+        muted_agents,
+        muted_channels: normalized_filters(input.muted_channels),
+        keyword_filters: normalized_filters(input.keyword_filters),
+        // Changed through follow_tag and unfollow_tag
+        followed_tags: get_feed_preferences(())?.followed_tags,
     };
     
-    let action_hash = create_entry(EntryTypes::Comment(comment.clone()))?;
-    let entry_hash = hash_entry(&comment)?;
+    create_entry(EntryTypes::FeedPreferences(preferences.clone()))?;
     
-    // Link from post to comment
-    create_link(
-        input.post_hash.clone(),
-        action_hash.clone(),
-        LinkTypes::PostToComments,
-        (),
-    )?;
-        // Emit signal for real-time updates
-    emit_signal(Signal::NewComment {
-        post_hash: input.post_hash,
-        comment_hash: action_hash.clone(),
-    })?;
-        Ok(CommentOutput {
-        comment,
-        action_hash,
-        entry_hash,
-    })
+    Ok(preferences)
 }
 
-/// Get all comments for a post
+/// Get the caller's feed preferences; empty if they never set any
 #[hdk_extern]
-pub fn get_post_comments(post_hash: ActionHash) -> ExternResult<Vec<CommentOutput>> {
-    let links = get_links(
-        LinkQuery::try_new(post_hash, LinkTypes::PostToComments)?,
-        GetStrategy::Local,
-    )?;
+pub fn get_feed_preferences(_: ()) -> ExternResult<FeedPreferences> {
+    let latest = my_chain_entries::<FeedPreferences>(UnitEntryTypes::FeedPreferences)?
+        .pop()
+        .map(|(_, preferences)| preferences);
     
-    let mut comments = Vec::new();
+    Ok(latest.unwrap_or_default())
+}
+
+/// Whether a post matches a muted channel or keyword filter
+fn is_filtered(post: &Post, preferences: &FeedPreferences) -> bool {
+    if post.channel.as_ref().is_some_and(|channel| {
+        preferences.muted_channels.contains(&channel.to_lowercase())
+    }) {
+        return true;
+    }
     
-    for link in links {
-        if let Some(action_hash) = link.target.into_action_hash() {
-            if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
-                if let Some(comment) = record.entry().to_app_option::<Comment>()
-                    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))? 
-                {
-                    let entry_hash = hash_entry(&comment)?;
-                    comments.push(CommentOutput {
+    let title = post.title.to_lowercase();
+    let content = post.content.to_lowercase();
+    preferences
+        .keyword_filters
+        .iter()
+        .any(|keyword| title.contains(keyword.as_str()) || content.contains(keyword.as_str()))
+}
+
+/// Trimmed, lowercased, non-empty, and deduplicated
+fn normalized_filters(filters: Vec<String>) -> Vec<String> {
+    let mut normalized: Vec<String> = filters
+        .iter()
+        .map(|filter| filter.trim().to_lowercase())
+        .filter(|filter| !filter.is_empty())
+        .collect();
+    normalized.sort();
+    normalized.dedup();
+    normalized
+}
+
+// ============================================================================
+// HASHTAGS
+// ============================================================================
+
+/// One page of posts carrying a hashtag, newest first
+///
+/// The caller's mutes and keyword filters apply as in `get_posts_page`.
+#[hdk_extern]
+pub fn get_posts_by_tag(input: PostsByTagInput) -> ExternResult<PostsPage> {
+    require_access(Some(GuestScope::ViewFeed))?;
+    let Some(tag) = normalize_tag(&input.tag) else {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("'{}' is not a valid tag", input.tag))));
+    };
+    let links = get_list_links(tag_anchor_hash(&tag)?, LinkTypes::TagToPosts, input.page.prefer_network)?;
+    posts_page(links, &input.page)
+}
+
+/// One page of posts carrying any tag the caller follows, newest first
+#[hdk_extern]
+pub fn get_followed_tags_page(input: PostsPageInput) -> ExternResult<PostsPage> {
+    require_access(Some(GuestScope::ViewFeed))?;
+    let mut seen = HashSet::new();
+    let mut links = Vec::new();
+    for tag in get_feed_preferences(())?.followed_tags {
+        for link in get_list_links(tag_anchor_hash(&tag)?, LinkTypes::TagToPosts, input.prefer_network)? {
+            // A post with several followed tags shows up once
+            if seen.insert(link.target.clone()) {
+                links.push(link);
+            }
+        }
+    }
+    posts_page(links, &input)
+}
+
+/// Follow a hashtag, e.g. "garden" or "#lostandfound"
+#[hdk_extern]
+pub fn follow_tag(tag: String) -> ExternResult<FeedPreferences> {
+    let Some(tag) = normalize_tag(&tag) else {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("'{}' is not a valid tag", tag))));
+    };
+    let mut preferences = get_feed_preferences(())?;
+    if preferences.followed_tags.contains(&tag) {
+        return Ok(preferences);
+    }
+    preferences.followed_tags.push(tag);
+    preferences.followed_tags.sort();
+    create_entry(EntryTypes::FeedPreferences(preferences.clone()))?;
+    Ok(preferences)
+}
+
+/// Stop following a hashtag
+#[hdk_extern]
+pub fn unfollow_tag(tag: String) -> ExternResult<FeedPreferences> {
+    let mut preferences = get_feed_preferences(())?;
+    let Some(tag) = normalize_tag(&tag) else {
+        return Ok(preferences);
+    };
+    if !preferences.followed_tags.contains(&tag) {
+        return Ok(preferences);
+    }
+    preferences.followed_tags.retain(|followed| *followed != tag);
+    create_entry(EntryTypes::FeedPreferences(preferences.clone()))?;
+    Ok(preferences)
+}
+
+/// The explicit tags plus the `#tags` in the content, normalized and deduplicated
+fn post_tags(explicit: &[String], content: &str) -> ExternResult<Vec<String>> {
+    let mut tags = Vec::new();
+    for tag in explicit {
+        let Some(normalized) = normalize_tag(tag) else {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!("'{}' is not a valid tag", tag))));
+        };
+        if !tags.contains(&normalized) {
+            tags.push(normalized);
+        }
+    }
+    for tag in parse_hashtags(content) {
+        if !tags.contains(&tag) {
+            tags.push(tag);
+        }
+    }
+    if tags.len() > MAX_TAGS {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Posts cannot have more than {} tags",
+            MAX_TAGS
+        ))));
+    }
+    Ok(tags)
+}
+
+/// Move a post's tag links from its old tags to its new ones
+fn retag_post(original_post_hash: &ActionHash, old_tags: &[String], new_tags: &[String]) -> ExternResult<()> {
+    for tag in old_tags.iter().filter(|tag| !new_tags.contains(tag)) {
+        let links = get_links(
+            LinkQuery::try_new(tag_anchor_hash(tag)?, LinkTypes::TagToPosts)?,
+            GetStrategy::Local,
+        )?;
+        for link in links {
+            if link.target.clone().into_action_hash().as_ref() == Some(original_post_hash) {
+                delete_link(link.create_link_hash, GetOptions::default())?;
+            }
+        }
+    }
+    for tag in new_tags.iter().filter(|tag| !old_tags.contains(tag)) {
+        create_link(tag_anchor_hash(tag)?, original_post_hash.clone(), LinkTypes::TagToPosts, ())?;
+    }
+    Ok(())
+}
+
+fn tag_anchor_hash(tag: &str) -> ExternResult<EntryHash> {
+    let path = Path::from(format!("{}.{}", TAGS_PATH, tag));
+    path.path_entry_hash()
+}
+
+// ============================================================================
+// LOST AND FOUND
+// ============================================================================
+
+/// Post something lost or found on the block
+#[hdk_extern]
+pub fn create_lost_found_post(input: CreateLostFoundInput) -> ExternResult<LostFoundOutput> {
+    let author = agent_info()?.agent_initial_pubkey;
+    require_access(None)?;
+    
+    let post = LostFoundPost {
+        kind: input.kind,
+        item_description: input.item_description,
+        last_seen: input.last_seen,
+        last_seen_at: input.last_seen_at,
+        details: input.details,
+        author: author.clone(),
+        created_at: sys_time()?,
+        status: LostFoundStatus::Open,
+        resolved_at: None,
+    };
+    
+    ensure_membership_proof()?;
+    let action_hash = create_entry(EntryTypes::LostFoundPost(post.clone()))?;
+    create_link(author, action_hash.clone(), LinkTypes::AgentToLostFound, ())?;
+    create_link(lost_found_anchor_hash()?, action_hash.clone(), LinkTypes::AllLostFound, ())?;
+    
+    Ok(LostFoundOutput {
+        post,
+        revision_hash: action_hash.clone(),
+        action_hash,
+    })
+}
+
+/// Mark my lost item as found (author only)
+#[hdk_extern]
+pub fn mark_found(original_hash: ActionHash) -> ExternResult<LostFoundOutput> {
+    set_lost_found_status(original_hash, LostFoundStatus::Found)
+}
+
+/// Mark the item as back with its owner (author only)
+#[hdk_extern]
+pub fn mark_claimed(original_hash: ActionHash) -> ExternResult<LostFoundOutput> {
+    set_lost_found_status(original_hash, LostFoundStatus::Claimed)
+}
+
+/// Lost and found posts at their latest revision, open ones first, then newest first
+#[hdk_extern]
+pub fn get_lost_and_found(include_resolved: bool) -> ExternResult<Vec<LostFoundOutput>> {
+    require_access(Some(GuestScope::ViewFeed))?;
+    let links = get_links(
+        LinkQuery::try_new(lost_found_anchor_hash()?, LinkTypes::AllLostFound)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut posts = Vec::new();
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(output) = get_latest_lost_found(action_hash)? else {
+            continue;
+        };
+        if include_resolved || output.post.status == LostFoundStatus::Open {
+            posts.push(output);
+        }
+    }
+    
+    posts.sort_by(|a, b| {
+        (b.post.status == LostFoundStatus::Open)
+            .cmp(&(a.post.status == LostFoundStatus::Open))
+            .then_with(|| b.post.created_at.cmp(&a.post.created_at))
+    });
+    Ok(posts)
+}
+
+fn set_lost_found_status(original_hash: ActionHash, status: LostFoundStatus) -> ExternResult<LostFoundOutput> {
+    let author = agent_info()?.agent_initial_pubkey;
+    
+    let Some(latest) = get_latest_lost_found(original_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Lost and found post not found".to_string())));
+    };
+    if latest.post.author != author {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the author can update their post".to_string()
+        )));
+    }
+    if !latest.post.status.can_become(status, latest.post.kind) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "A {:?} post cannot go from {:?} to {:?}",
+            latest.post.kind, latest.post.status, status
+        ))));
+    }
+    
+    let post = LostFoundPost {
+        status,
+        resolved_at: Some(sys_time()?),
+        ..latest.post
+    };
+    let revision_hash = update_entry(latest.revision_hash, &post)?;
+    
+    Ok(LostFoundOutput {
+        post,
+        action_hash: original_hash,
+        revision_hash,
+    })
+}
+
+fn get_latest_lost_found(original_hash: ActionHash) -> ExternResult<Option<LostFoundOutput>> {
+    let Some(record) = post_revision_records(original_hash.clone(), false)?.pop() else {
+        return Ok(None);
+    };
+    let post = record
+        .entry()
+        .to_app_option::<LostFoundPost>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+    
+    Ok(post.map(|post| LostFoundOutput {
+        post,
+        action_hash: original_hash,
+        revision_hash: record.action_address().clone(),
+    }))
+}
+
+fn lost_found_anchor_hash() -> ExternResult<EntryHash> {
+    let path = Path::from(LOST_FOUND_PATH);
+    path.path_entry_hash()
+}
+
+// ============================================================================
+// READ MARKERS
+// ============================================================================
+
+/// Mark the feed as read up to the given post timestamp
+///
+/// The marker only moves forward; marking an older timestamp keeps the
+/// current one. Future timestamps are clamped to now.
+#[hdk_extern]
+pub fn mark_feed_read(up_to: Timestamp) -> ExternResult<ReadMarker> {
+    let up_to = up_to.min(sys_time()?);
+    
+    if let Some(current) = latest_read_marker()? {
+        if current.up_to >= up_to {
+            return Ok(current);
+        }
+    }
+    
+    let marker = ReadMarker { up_to };
+    create_entry(EntryTypes::ReadMarker(marker.clone()))?;
+    
+    Ok(marker)
+}
+
+/// Count posts newer than the caller's read marker
+///
+/// Works from the all-posts links alone, so no posts are downloaded. The
+/// caller's own posts and posts by muted neighbors don't count.
+#[hdk_extern]
+pub fn get_unread_count(_: ()) -> ExternResult<u32> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let read_up_to = latest_read_marker()?.map(|marker| marker.up_to);
+    let preferences = get_feed_preferences(())?;
+    
+    let links = get_links(
+        LinkQuery::try_new(all_posts_anchor_hash()?, LinkTypes::AllPosts)?,
+        GetStrategy::Local,
+    )?;
+    
+    let unread = links
+        .iter()
+        .filter(|link| read_up_to.map_or(true, |up_to| link.timestamp > up_to))
+        .filter(|link| link.author != me && !preferences.muted_agents.contains(&link.author))
+        .count();
+    
+    Ok(unread as u32)
+}
+
+fn latest_read_marker() -> ExternResult<Option<ReadMarker>> {
+    Ok(my_chain_entries::<ReadMarker>(UnitEntryTypes::ReadMarker)?
+        .pop()
+        .map(|(_, marker)| marker))
+}
+
+// ============================================================================
+// ACTIVITY DIGEST
+// ============================================================================
+
+/// Something new on the block, in a "what's new" summary
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DigestEntry {
+    pub action_hash: ActionHash,
+    pub title: String,
+    pub author: AgentPubKey,
+    /// When it was posted, or for requests when it was last escalated
+    pub at: Timestamp,
+    /// Event start time; None for everything else
+    pub starts_at: Option<Timestamp>,
+    /// Request urgency; None for everything else
+    pub urgency: Option<RequestUrgency>,
+}
+
+/// The helpinghands zome's Urgency
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(tag = "type")]
+pub enum RequestUrgency {
+    Low,
+    High,
+    Emergency,
+}
+
+/// Everything new across the feed, tool shed, events, and helping hands
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActivityDigest {
+    pub since: Timestamp,
+    pub generated_at: Timestamp,
+    pub posts: Vec<DigestEntry>,
+    pub items: Vec<DigestEntry>,
+    pub events: Vec<DigestEntry>,
+    /// Unfulfilled requests posted or escalated since
+    pub open_requests: Vec<DigestEntry>,
+}
+
+/// The toolshed zome's ItemOutput, only the fields the digest needs
+#[derive(Serialize, Deserialize, Debug)]
+struct ItemSummary {
+    item: ItemSummaryFields,
+    action_hash: ActionHash,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct ItemSummaryFields {
+    title: String,
+    owner: AgentPubKey,
+    created_at: Timestamp,
+}
+
+/// The events zome's EventOutput, only the fields the digest needs
+#[derive(Serialize, Deserialize, Debug)]
+struct EventSummary {
+    event: EventSummaryFields,
+    action_hash: ActionHash,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct EventSummaryFields {
+    title: String,
+    host: AgentPubKey,
+    event_date: Timestamp,
+    created_at: Timestamp,
+}
+
+/// The helpinghands zome's RequestOutput, only the fields the digest needs
+#[derive(Serialize, Deserialize, Debug)]
+struct RequestSummary {
+    request: RequestSummaryFields,
+    action_hash: ActionHash,
+    #[serde(default)]
+    bumped_at: Option<Timestamp>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+struct RequestSummaryFields {
+    title: String,
+    author: AgentPubKey,
+    created_at: Timestamp,
+    is_fulfilled: bool,
+    urgency: RequestUrgency,
+}
+
+/// Collect what's new on the block since a timestamp in one call
+///
+/// Powers the daily "what's new" summary. Posts and other content from
+/// neighbors the caller has muted are left out, as are posts caught by
+/// their channel and keyword filters. Each list is newest first.
+#[hdk_extern]
+pub fn get_activity_digest(since: Timestamp) -> ExternResult<ActivityDigest> {
+    let preferences = get_feed_preferences(())?;
+    let muted = |agent: &AgentPubKey| preferences.muted_agents.contains(agent);
+    
+    let posts = get_all_posts(None)?
+        .into_iter()
+        .filter(|output| output.post.created_at > since)
+        .filter(|output| !muted(&output.post.author) && !is_filtered(&output.post, &preferences))
+        .map(|output| DigestEntry {
+            action_hash: output.action_hash,
+            title: output.post.title,
+            author: output.post.author,
+            at: output.post.created_at,
+            starts_at: None,
+            urgency: None,
+        })
+        .collect();
+    
+    let items = call_zome::<Vec<ItemSummary>>("toolshed", "get_all_items")?
+        .into_iter()
+        .filter(|summary| summary.item.created_at > since && !muted(&summary.item.owner))
+        .map(|summary| DigestEntry {
+            action_hash: summary.action_hash,
+            title: summary.item.title,
+            author: summary.item.owner,
+            at: summary.item.created_at,
+            starts_at: None,
+            urgency: None,
+        })
+        .collect();
+    
+    let events = call_zome::<Vec<EventSummary>>("events", "get_all_events")?
+        .into_iter()
+        .filter(|summary| summary.event.created_at > since && !muted(&summary.event.host))
+        .map(|summary| DigestEntry {
+            action_hash: summary.action_hash,
+            title: summary.event.title,
+            author: summary.event.host,
+            at: summary.event.created_at,
+            starts_at: Some(summary.event.event_date),
+            urgency: None,
+        })
+        .collect();
+    
+    let open_requests = call_zome::<Vec<RequestSummary>>("helpinghands", "get_all_requests")?
+        .into_iter()
+        .filter(|summary| !summary.request.is_fulfilled && !muted(&summary.request.author))
+        .map(|summary| DigestEntry {
+            at: summary.bumped_at.unwrap_or(summary.request.created_at),
+            action_hash: summary.action_hash,
+            title: summary.request.title,
+            author: summary.request.author,
+            starts_at: None,
+            urgency: Some(summary.request.urgency),
+        })
+        .filter(|entry| entry.at > since)
+        .collect();
+    
+    Ok(ActivityDigest {
+        since,
+        generated_at: sys_time()?,
+        posts: newest_first(posts),
+        items: newest_first(items),
+        events: newest_first(events),
+        open_requests: newest_first(open_requests),
+    })
+}
+
+fn newest_first(mut entries: Vec<DigestEntry>) -> Vec<DigestEntry> {
+    entries.sort_by(|a, b| b.at.cmp(&a.at));
+    entries
+}
+
+/// A new post in the digest, with how much neighbors have engaged with it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DigestPost {
+    pub entry: DigestEntry,
+    pub reaction_count: usize,
+    pub comment_count: usize,
+}
+
+/// A compact digest for the push relay to deliver
+///
+/// Plain data only, so the relay can render it as an email, a push
+/// notification, or anything else.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Digest {
+    pub since: Timestamp,
+    pub generated_at: Timestamp,
+    pub frequency: DigestFrequency,
+    /// Whether a digest is due, taking `since` as the last delivery
+    pub due: bool,
+    /// New posts with the most reactions, at most DIGEST_TOP_POSTS
+    pub top_posts: Vec<DigestPost>,
+    /// Events announced since, soonest first
+    pub events: Vec<DigestEntry>,
+    /// Open High and Emergency requests posted or escalated since, most urgent first
+    pub urgent_requests: Vec<DigestEntry>,
+}
+
+/// How many posts the digest highlights
+const DIGEST_TOP_POSTS: usize = 5;
+/// A digest counts as due this early, so a daily relay run that fires a
+/// little ahead of schedule doesn't skip a day
+const DIGEST_DUE_LEEWAY_SECS: i64 = 60 * 60;
+
+/// Build the caller's digest of what happened since their last one
+///
+/// Called by the push relay on a daily schedule with the time of the last
+/// delivery; it sends nothing unless `due` is set. Muted neighbors and
+/// filtered posts are left out, as in `get_activity_digest`.
+#[hdk_extern]
+pub fn generate_digest(since: Timestamp) -> ExternResult<Digest> {
+    let frequency = get_digest_preferences(())?.frequency;
+    let activity = get_activity_digest(since)?;
+    
+    let mut top_posts = Vec::new();
+    for entry in activity.posts {
+        top_posts.push(DigestPost {
+            reaction_count: link_targets(&entry.action_hash, LinkTypes::PostToReactions)?.len(),
+            comment_count: link_targets(&entry.action_hash, LinkTypes::PostToComments)?.len(),
+            entry,
+        });
+    }
+    // Posts are newest first, so ties go to the newer post
+    top_posts.sort_by(|a, b| {
+        b.reaction_count
+            .cmp(&a.reaction_count)
+            .then_with(|| b.comment_count.cmp(&a.comment_count))
+    });
+    top_posts.truncate(DIGEST_TOP_POSTS);
+    
+    let mut events = activity.events;
+    events.sort_by(|a, b| a.starts_at.cmp(&b.starts_at));
+    
+    let mut urgent_requests: Vec<DigestEntry> = activity
+        .open_requests
+        .into_iter()
+        .filter(|entry| matches!(entry.urgency, Some(RequestUrgency::High | RequestUrgency::Emergency)))
+        .collect();
+    urgent_requests.sort_by_key(|entry| entry.urgency != Some(RequestUrgency::Emergency));
+    
+    let due = frequency
+        .interval_secs()
+        .is_some_and(|interval| {
+            activity.generated_at.as_micros() - since.as_micros() >= (interval - DIGEST_DUE_LEEWAY_SECS) * 1_000_000
+        });
+    
+    Ok(Digest {
+        since,
+        generated_at: activity.generated_at,
+        frequency,
+        due,
+        top_posts,
+        events,
+        urgent_requests,
+    })
+}
+
+/// Set how often the caller wants their digest; kept private on their own chain
+#[hdk_extern]
+pub fn set_digest_frequency(frequency: DigestFrequency) -> ExternResult<DigestPreferences> {
+    let preferences = DigestPreferences { frequency };
+    create_entry(EntryTypes::DigestPreferences(preferences.clone()))?;
+    Ok(preferences)
+}
+
+/// Get the caller's digest preferences; digests are off until they choose
+#[hdk_extern]
+pub fn get_digest_preferences(_: ()) -> ExternResult<DigestPreferences> {
+    let latest = my_chain_entries::<DigestPreferences>(UnitEntryTypes::DigestPreferences)?
+        .pop()
+        .map(|(_, preferences)| preferences);
+    
+    Ok(latest.unwrap_or_default())
+}
+
+/// Call a no-argument extern in another zome of this DNA
+fn call_zome<O>(zome: &str, fn_name: &str) -> ExternResult<O>
+where
+    O: serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from(zome),
+        FunctionName::from(fn_name),
+        None,
+        (),
+    )?;
+    
+    match response {
+        ZomeCallResponse::Ok(result) => result.decode().map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "Failed to decode {} response: {:?}",
+                zome, e
+            )))
+        }),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to call {}.{}: {:?}",
+            zome, fn_name, other
+        )))),
+    }
+}
+
+// ============================================================================
+// HELPER FUNCTIONS
+// ============================================================================
+
+/// Follow a post's update chain from the original
+///
+/// Returns just the newest revision unless `all` is set. Where a revision
+/// was updated more than once, the most recent update is followed.
+fn post_revision_records(original_hash: ActionHash, all: bool) -> ExternResult<Vec<Record>> {
+    let mut records = Vec::new();
+    let mut current = original_hash;
+    
+    loop {
+        let Some(Details::Record(details)) = get_details(current, GetOptions::default())? else {
+            break;
+        };
+        let next = details
+            .updates
+            .iter()
+            .max_by_key(|update| update.action().timestamp())
+            .map(|update| update.action_address().clone());
+        
+        if all || next.is_none() {
+            records.push(details.record);
+        }
+        match next {
+            Some(next) => current = next,
+            None => break,
+        }
+    }
+    
+    Ok(records)
+}
+
+/// Build the output for one revision of a post
+fn post_output(original_hash: ActionHash, record: &Record) -> ExternResult<Option<PostOutput>> {
+    let Some(post) = record
+        .entry()
+        .to_app_option::<Post>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    else {
+        return Ok(None);
+    };
+    
+    let edited_at = match record.action() {
+        Action::Update(update) => Some(update.timestamp),
+        _ => None,
+    };
+    
+    Ok(Some(PostOutput {
+        entry_hash: hash_entry(&post)?,
+        post,
+        action_hash: original_hash,
+        revision_hash: record.action_address().clone(),
+        edited_at,
+        comment_count: None,
+    }))
+}
+
+/// Get the path hash for the all-posts anchor
+fn all_posts_anchor_hash() -> ExternResult<EntryHash> {
+    let path = Path::from(ALL_POSTS_PATH);
+    path.path_entry_hash()
+}
+
+/// Add or remove a post in the search zome's keyword index
+fn call_search(fn_name: &str, target: ActionHash, title: &str, text: &str) -> ExternResult<()> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("search"),
+        FunctionName::from(fn_name),
+        None,
+        SearchIndexInput {
+            kind: SearchKind::Post,
+            target,
+            title: title.to_string(),
+            text: text.to_string(),
+        },
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to update search index: {:?}",
+            other
+        )))),
+    }
+}
+
+/// Ask the vouch zome whether the caller may do this; only guests are ever
+/// refused. `None` is for writes, which guest passes never allow.
+fn require_access(scope: Option<GuestScope>) -> ExternResult<()> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("vouch"),
+        FunctionName::from("check_access"),
+        None,
+        AccessCheckInput {
+            agent: agent_info()?.agent_initial_pubkey,
+            scope,
+        },
+    )?;
+
+    let allowed: bool = match response {
+        ZomeCallResponse::Ok(result) => result.decode().map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!("Failed to decode access check: {:?}", e)))
+        })?,
+        other => {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Failed to check access: {:?}",
+                other
+            ))));
+        }
+    };
+    if !allowed {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Your guest pass doesn't allow this, or has expired.".to_string()
+        )));
+    }
+    Ok(())
+}
+
+// ============================================================================
+// REACTIONS
+// ============================================================================
+
+/// Add a reaction to a post or comment
+#[hdk_extern]
+pub fn add_reaction(input: CreateReactionInput) -> ExternResult<ReactionOutput> {
+    let author = agent_info()?.agent_initial_pubkey;
+    require_access(None)?;
+    
+    let reaction = Reaction {
+        target: input.target.clone(),
+        target_kind: input.target_kind,
+        author: author.clone(),
+        reaction_type: input.reaction_type,
+        created_at: sys_time()?,
+    };
+    
+    ensure_membership_proof()?;
+    let action_hash = create_entry(EntryTypes::Reaction(reaction.clone()))?;
+    let entry_hash = hash_entry(&reaction)?;
+    
+    // Link from the post or comment to the reaction
+    let link_type = match input.target_kind {
+        ReactionTarget::Post => LinkTypes::PostToReactions,
+        ReactionTarget::Comment => LinkTypes::CommentToReactions,
+    };
+    create_link(
+        input.target.clone(),
+        action_hash.clone(),
+        link_type,
+        (),
+    )?;
+    
+    // Link from agent to reaction (for finding user's reactions)
+    create_link(
+        author,
+        action_hash.clone(),
+        LinkTypes::AgentToReactions,
+        (),
+    )?;
+    
+    // Emit signal for real-time updates
+    emit_zome_signal(match input.target_kind {
+        ReactionTarget::Post => Signal::NewReaction {
+            post_hash: input.target,
+            reaction_hash: action_hash.clone(),
+        },
+        ReactionTarget::Comment => Signal::NewCommentReaction {
+            comment_hash: input.target,
+            reaction_hash: action_hash.clone(),
+        },
+    })?;
+    
+    Ok(ReactionOutput {
+        reaction,
+        action_hash,
+        entry_hash,
+    })
+}
+
+/// Remove a reaction (by deleting the entry and links)
+#[hdk_extern]
+pub fn remove_reaction(reaction_hash: ActionHash) -> ExternResult<()> {
+    delete_entry(reaction_hash)?;
+    Ok(())
+}
+
+/// Get all reactions for a post
+#[hdk_extern]
+pub fn get_post_reactions(post_hash: ActionHash) -> ExternResult<Vec<ReactionOutput>> {
+    get_reactions(post_hash, LinkTypes::PostToReactions)
+}
+
+/// Get all reactions for a comment
+#[hdk_extern]
+pub fn get_comment_reactions(comment_hash: ActionHash) -> ExternResult<Vec<ReactionOutput>> {
+    get_reactions(comment_hash, LinkTypes::CommentToReactions)
+}
+
+fn get_reactions(target: ActionHash, link_type: LinkTypes) -> ExternResult<Vec<ReactionOutput>> {
+    let links = get_links(
+        LinkQuery::try_new(target, link_type)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut reactions = Vec::new();
+    
+    for link in links {
+        if let Some(action_hash) = link.target.into_action_hash() {
+            if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
+                if let Some(reaction) = record.entry().to_app_option::<Reaction>()
+                    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))? 
+                {
+                    let entry_hash = hash_entry(&reaction)?;
+                    reactions.push(ReactionOutput {
+                        reaction,
+                        action_hash,
+                        entry_hash,
+                    });
+                }
+            }
+        }
+    }
+    
+    Ok(reactions)
+}
+
+// ============================================================================
+// COMMENTS
+// ============================================================================
+
+/// Add a comment to a post
+#[hdk_extern]
+pub fn add_comment(input: CreateCommentInput) -> ExternResult<CommentOutput> {
+    let author = agent_info()?.agent_initial_pubkey;
+    require_access(None)?;
+    
+    let comment = Comment {
+        post_hash: input.post_hash.clone(),
+        author,
+        content: input.content,
+        created_at: sys_time()?,
+    };
+    
+    ensure_membership_proof()?;
+    let action_hash = create_entry(EntryTypes::Comment(comment.clone()))?;
+    let entry_hash = hash_entry(&comment)?;
+    
+    // Link from post to comment
+    create_link(
+        input.post_hash.clone(),
+        action_hash.clone(),
+        LinkTypes::PostToComments,
+        (),
+    )?;
+    record_comment_count(&input.post_hash)?;
+        // Emit signal for real-time updates
+    emit_zome_signal(Signal::NewComment {
+        post_hash: input.post_hash,
+        comment_hash: action_hash.clone(),
+    })?;
+        Ok(CommentOutput {
+        comment,
+        action_hash,
+        entry_hash,
+    })
+}
+
+/// Record a post's comment count as the caller now sees it
+///
+/// Replaces the caller's previous count for the post, so each neighbor who
+/// has commented holds at most one. Comments are never deleted, so readers
+/// take the highest count; a count that missed a concurrent comment is
+/// corrected by the next one.
+fn record_comment_count(post_hash: &ActionHash) -> ExternResult<()> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let anchor = all_posts_anchor_hash()?;
+    let counts = get_links(
+        LinkQuery::try_new(anchor.clone(), LinkTypes::CommentCounts)?,
+        GetStrategy::Local,
+    )?;
+    for link in counts {
+        if link.author == me && link.target.clone().into_action_hash().as_ref() == Some(post_hash) {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
+    }
+    
+    let count = count_comments(post_hash)?;
+    create_link(
+        anchor,
+        post_hash.clone(),
+        LinkTypes::CommentCounts,
+        LinkTag::new(count.to_le_bytes().to_vec()),
+    )?;
+    Ok(())
+}
+
+/// Cached comment counts of every post, from one read of the all-posts anchor
+fn cached_comment_counts(prefer_network: bool) -> ExternResult<HashMap<ActionHash, u32>> {
+    let links = get_list_links(all_posts_anchor_hash()?, LinkTypes::CommentCounts, prefer_network)?;
+    
+    let mut counts: HashMap<ActionHash, u32> = HashMap::new();
+    for link in links {
+        let Some(post_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Ok(bytes) = <[u8; 4]>::try_from(link.tag.0.as_slice()) else {
+            continue;
+        };
+        let count = counts.entry(post_hash).or_default();
+        *count = (*count).max(u32::from_le_bytes(bytes));
+    }
+    Ok(counts)
+}
+
+/// Count a post's comment links
+fn count_comments(post_hash: &ActionHash) -> ExternResult<u32> {
+    let count = link_targets(post_hash, LinkTypes::PostToComments)?.len();
+    Ok(u32::try_from(count).unwrap_or(u32::MAX))
+}
+
+/// Get all comments for a post
+#[hdk_extern]
+pub fn get_post_comments(post_hash: ActionHash) -> ExternResult<Vec<CommentOutput>> {
+    let links = get_links(
+        LinkQuery::try_new(post_hash, LinkTypes::PostToComments)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut comments = Vec::new();
+    
+    for link in links {
+        if let Some(action_hash) = link.target.into_action_hash() {
+            if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
+                if let Some(comment) = record.entry().to_app_option::<Comment>()
+                    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))? 
+                {
+                    let entry_hash = hash_entry(&comment)?;
+                    comments.push(CommentOutput {
                         comment,
                         action_hash,
                         entry_hash,
@@ -432,3 +1667,487 @@ pub fn get_post_comments(post_hash: ActionHash) -> ExternResult<Vec<CommentOutpu
     
     Ok(comments)
 }
+
+// ============================================================================
+// REPORTS AND REVIEW
+// ============================================================================
+
+/// Report a post for the anchors to review
+///
+/// Reporting the same post twice returns the first report.
+#[hdk_extern]
+pub fn report_post(input: ReportPostInput) -> ExternResult<ReportOutput> {
+    let reporter = agent_info()?.agent_initial_pubkey;
+    require_access(None)?;
+    
+    if get_latest_post(input.post_hash.clone())?.is_none() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Post not found".to_string())));
+    }
+    if let Some(existing) = queued_reports()?
+        .into_iter()
+        .find(|output| output.report.post_hash == input.post_hash && output.report.reporter == reporter)
+    {
+        return Ok(existing);
+    }
+    
+    let report = Report {
+        post_hash: input.post_hash,
+        reporter,
+        reason: input.reason,
+        created_at: sys_time()?,
+    };
+    
+    ensure_membership_proof()?;
+    let action_hash = create_entry(EntryTypes::Report(report.clone()))?;
+    create_link(
+        review_queue_anchor_hash()?,
+        action_hash.clone(),
+        LinkTypes::ReviewQueue,
+        (),
+    )?;
+    
+    Ok(ReportOutput {
+        report,
+        action_hash,
+    })
+}
+
+/// Reports no anchor has resolved yet, oldest first (anchors only)
+#[hdk_extern]
+pub fn get_review_queue(_: ()) -> ExternResult<Vec<ReportOutput>> {
+    require_anchor()?;
+    
+    let mut open = Vec::new();
+    for output in queued_reports()? {
+        if link_targets(&output.action_hash, LinkTypes::ReportToResolutions)?.is_empty() {
+            open.push(output);
+        }
+    }
+    open.sort_by(|a, b| a.report.created_at.cmp(&b.report.created_at));
+    Ok(open)
+}
+
+/// Decide a report (anchors only)
+///
+/// Hiding takes the post out of everyone's feed; warning sends its author a
+/// `ModerationWarning` signal. The reporter gets a `ReportResolved` signal
+/// either way.
+#[hdk_extern]
+pub fn resolve_report(input: ResolveReportInput) -> ExternResult<ResolutionOutput> {
+    let resolver_anchor = require_anchor()?;
+    
+    let Some(record) = get(input.report_hash.clone(), GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Report not found".to_string())));
+    };
+    let Some(report) = record
+        .entry()
+        .to_app_option::<Report>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Invalid report".to_string())));
+    };
+    if !link_targets(&input.report_hash, LinkTypes::ReportToResolutions)?.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "This report has already been resolved".to_string()
+        )));
+    }
+    
+    let resolution = ReportResolution {
+        report: input.report_hash.clone(),
+        action: input.action,
+        note: input.note,
+        resolver_anchor,
+        resolved_at: sys_time()?,
+    };
+    let action_hash = create_entry(EntryTypes::ReportResolution(resolution.clone()))?;
+    create_link(
+        input.report_hash.clone(),
+        action_hash.clone(),
+        LinkTypes::ReportToResolutions,
+        (),
+    )?;
+    
+    match resolution.action {
+        ResolutionAction::Dismiss => {}
+        ResolutionAction::Hide => {
+            create_link(
+                report.post_hash.clone(),
+                action_hash.clone(),
+                LinkTypes::PostToHides,
+                (),
+            )?;
+        }
+        ResolutionAction::WarnAuthor => {
+            if let Some(post_output) = get_latest_post(report.post_hash.clone())? {
+                send_remote_signal(
+                    Signal::ModerationWarning {
+                        post_hash: report.post_hash.clone(),
+                        resolution_hash: action_hash.clone(),
+                        note: resolution.note.clone(),
+                    },
+                    vec![post_output.post.author],
+                )?;
+            }
+        }
+    }
+    
+    send_remote_signal(
+        Signal::ReportResolved {
+            report_hash: input.report_hash,
+            resolution_hash: action_hash.clone(),
+            post_hash: report.post_hash,
+            action: resolution.action,
+        },
+        vec![report.reporter],
+    )?;
+    
+    Ok(ResolutionOutput {
+        resolution,
+        action_hash,
+    })
+}
+
+/// Every report ever filed, resolved or not
+fn queued_reports() -> ExternResult<Vec<ReportOutput>> {
+    let links = get_links(
+        LinkQuery::try_new(review_queue_anchor_hash()?, LinkTypes::ReviewQueue)?,
+        GetStrategy::Local,
+    )?;
+    let report_hashes: Vec<ActionHash> = links.into_iter().filter_map(|link| link.target.into_action_hash()).collect();
+    
+    let mut reports = Vec::new();
+    for (action_hash, record) in get_records(&report_hashes)? {
+        if let Some(report) = record
+            .entry()
+            .to_app_option::<Report>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        {
+            reports.push(ReportOutput {
+                report,
+                action_hash,
+            });
+        }
+    }
+    Ok(reports)
+}
+
+/// Whether an anchor has hidden a post
+fn is_hidden(post_hash: &ActionHash) -> ExternResult<bool> {
+    Ok(!link_targets(post_hash, LinkTypes::PostToHides)?.is_empty())
+}
+
+/// The caller's TrustedAnchor record; errors for anyone who isn't an anchor
+fn require_anchor() -> ExternResult<ActionHash> {
+    call_zome::<Option<ActionHash>>("vouch", "get_my_anchor_record")?.ok_or(wasm_error!(WasmErrorInner::Guest(
+        "Only trusted anchors can review reports".to_string()
+    )))
+}
+
+fn review_queue_anchor_hash() -> ExternResult<EntryHash> {
+    let path = Path::from(REVIEW_QUEUE_PATH);
+    path.path_entry_hash()
+}
+
+/// Let anchors reach reporters and warned authors with remote signals
+#[hdk_extern]
+pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    let mut functions = BTreeSet::new();
+    functions.insert((zome_info()?.name, FunctionName::from("recv_remote_signal")));
+    create_cap_grant(CapGrantEntry {
+        tag: "remote_signals".to_string(),
+        access: CapAccess::Unrestricted,
+        functions: GrantedFunctions::Listed(functions),
+    })?;
+    Ok(InitCallbackResult::Pass)
+}
+
+/// Forward moderation signals to the UI
+///
+/// Only moderation outcomes arrive remotely, and each is only passed on if
+/// it came from the anchor who committed the resolution it names.
+#[hdk_extern]
+pub fn recv_remote_signal(signal: ExternIO) -> ExternResult<()> {
+    let signal: Signal = signal.decode().map_err(|e| {
+        wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to decode feed signal: {:?}",
+            e
+        )))
+    })?;
+    
+    let resolution_hash = match &signal {
+        Signal::ReportResolved { resolution_hash, .. } | Signal::ModerationWarning { resolution_hash, .. } => {
+            resolution_hash.clone()
+        }
+        _ => return Ok(()),
+    };
+    let Some(record) = get(resolution_hash, GetOptions::default())? else {
+        return Ok(());
+    };
+    if record.action().author() != &call_info()?.provenance {
+        return Ok(());
+    }
+    
+    emit_zome_signal(signal)
+}
+
+// ============================================================================
+// BATCHED RETRIEVAL
+// ============================================================================
+
+/// Get posts at their latest revision together with their comments and reaction summaries
+///
+/// Replaces a `get_post_comments` plus `get_post_reactions` round trip per post
+/// (and `get_comment_reactions` per comment). Links are read locally, then every
+/// comment and reaction record is fetched once in a single batched `get`.
+/// Results follow the order of `post_hashes`; duplicates and posts that can't
+/// be found are left out.
+#[hdk_extern]
+pub fn get_posts_with_details(post_hashes: Vec<ActionHash>) -> ExternResult<Vec<PostWithDetails>> {
+    require_access(Some(GuestScope::ViewFeed))?;
+    let mut seen = HashSet::new();
+    let post_hashes: Vec<ActionHash> = post_hashes.into_iter().filter(|hash| seen.insert(hash.clone())).collect();
+    if post_hashes.len() > MAX_PAGE_SIZE {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "At most {} posts can be fetched at once",
+            MAX_PAGE_SIZE
+        ))));
+    }
+
+    let mut posts = Vec::new();
+    for hash in post_hashes {
+        if let Some(post) = get_latest_post(hash)? {
+            posts.push(post);
+        }
+    }
+
+    let mut comment_hashes: HashMap<ActionHash, Vec<ActionHash>> = HashMap::new();
+    let mut reaction_hashes: HashMap<ActionHash, Vec<ActionHash>> = HashMap::new();
+    for post in &posts {
+        comment_hashes.insert(post.action_hash.clone(), link_targets(&post.action_hash, LinkTypes::PostToComments)?);
+        reaction_hashes.insert(post.action_hash.clone(), link_targets(&post.action_hash, LinkTypes::PostToReactions)?);
+    }
+
+    let comment_records = get_records(comment_hashes.values().flatten())?;
+    for comment_hash in comment_records.keys() {
+        reaction_hashes.insert(comment_hash.clone(), link_targets(comment_hash, LinkTypes::CommentToReactions)?);
+    }
+    let reaction_records = get_records(reaction_hashes.values().flatten())?;
+
+    let me = agent_info()?.agent_initial_pubkey;
+    let summarize = |target: &ActionHash| {
+        summarize_reactions(reaction_hashes.get(target).map_or(&[][..], Vec::as_slice), &reaction_records, &me)
+    };
+
+    let mut details = Vec::new();
+    for post in posts {
+        let mut comments = Vec::new();
+        for hash in comment_hashes.get(&post.action_hash).into_iter().flatten() {
+            let Some(record) = comment_records.get(hash) else {
+                continue;
+            };
+            let Some(comment) = record
+                .entry()
+                .to_app_option::<Comment>()
+                .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+            else {
+                continue;
+            };
+            comments.push(CommentWithReactions {
+                reactions: summarize(hash)?,
+                comment: CommentOutput {
+                    entry_hash: hash_entry(&comment)?,
+                    comment,
+                    action_hash: hash.clone(),
+                },
+            });
+        }
+        comments.sort_by(|a, b| a.comment.comment.created_at.cmp(&b.comment.comment.created_at));
+
+        details.push(PostWithDetails {
+            reactions: summarize(&post.action_hash)?,
+            post,
+            comments,
+        });
+    }
+
+    Ok(details)
+}
+
+/// Action hashes a base links to, in link order
+fn link_targets(base: &ActionHash, link_type: LinkTypes) -> ExternResult<Vec<ActionHash>> {
+    let links = get_links(
+        LinkQuery::try_new(base.clone(), link_type)?,
+        GetStrategy::Local,
+    )?;
+
+    Ok(links.into_iter().filter_map(|link| link.target.into_action_hash()).collect())
+}
+
+/// Fetch each distinct record once, in one host call, keyed by action hash
+///
+/// Deleted or unreachable records are simply absent from the result.
+fn get_records<'a>(hashes: impl IntoIterator<Item = &'a ActionHash>) -> ExternResult<HashMap<ActionHash, Record>> {
+    let unique: HashSet<&ActionHash> = hashes.into_iter().collect();
+    if unique.is_empty() {
+        return Ok(HashMap::new());
+    }
+
+    let inputs: Vec<GetInput> = unique
+        .into_iter()
+        .map(|hash| GetInput::new(hash.clone().into(), GetOptions::default()))
+        .collect();
+    let records = HDK.with(|hdk| hdk.borrow().get(inputs))?;
+
+    Ok(records
+        .into_iter()
+        .flatten()
+        .map(|record| (record.action_address().clone(), record))
+        .collect())
+}
+
+/// Count a target's reactions by type, most used first
+fn summarize_reactions(
+    hashes: &[ActionHash],
+    records: &HashMap<ActionHash, Record>,
+    me: &AgentPubKey,
+) -> ExternResult<Vec<ReactionSummary>> {
+    let mut summaries: Vec<ReactionSummary> = Vec::new();
+
+    for hash in hashes {
+        let Some(record) = records.get(hash) else {
+            continue;
+        };
+        let Some(reaction) = record
+            .entry()
+            .to_app_option::<Reaction>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        else {
+            continue;
+        };
+
+        let index = match summaries.iter().position(|summary| summary.reaction_type == reaction.reaction_type) {
+            Some(index) => index,
+            None => {
+                summaries.push(ReactionSummary {
+                    reaction_type: reaction.reaction_type.clone(),
+                    count: 0,
+                    my_reaction: None,
+                });
+                summaries.len() - 1
+            }
+        };
+        let summary = &mut summaries[index];
+        summary.count += 1;
+        if reaction.author == *me {
+            summary.my_reaction = Some(hash.clone());
+        }
+    }
+
+    summaries.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.reaction_type.cmp(&b.reaction_type)));
+    Ok(summaries)
+}
+
+/// Have the vouch zome put a MembershipProof on our chain if we don't have
+/// one yet; integrity rejects posts, comments, and reactions from authors without one
+fn ensure_membership_proof() -> ExternResult<()> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("vouch"),
+        FunctionName::from("ensure_membership_proof"),
+        None,
+        (),
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to check membership proof: {:?}",
+            other
+        )))),
+    }
+}
+
+// ============================================================================
+// DATA EXPORT
+// ============================================================================
+
+/// The calling agent's posts, comments, and reactions
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FeedExport {
+    pub posts: Vec<PostOutput>,
+    pub comments: Vec<CommentOutput>,
+    pub reactions: Vec<ReactionOutput>,
+    /// Every revision, oldest first
+    pub lost_and_found: Vec<LostFoundPost>,
+}
+
+/// Export everything the caller has written to the feed, from their own chain
+#[hdk_extern]
+pub fn export_my_data(_: ()) -> ExternResult<FeedExport> {
+    let mut posts = Vec::new();
+    for (action_hash, post) in my_chain_entries::<Post>(UnitEntryTypes::Post)? {
+        posts.push(PostOutput {
+            entry_hash: hash_entry(&post)?,
+            post,
+            revision_hash: action_hash.clone(),
+            action_hash,
+            edited_at: None,
+            comment_count: None,
+        });
+    }
+
+    let mut comments = Vec::new();
+    for (action_hash, comment) in my_chain_entries::<Comment>(UnitEntryTypes::Comment)? {
+        comments.push(CommentOutput {
+            entry_hash: hash_entry(&comment)?,
+            comment,
+            action_hash,
+        });
+    }
+
+    let mut reactions = Vec::new();
+    for (action_hash, reaction) in my_chain_entries::<Reaction>(UnitEntryTypes::Reaction)? {
+        reactions.push(ReactionOutput {
+            entry_hash: hash_entry(&reaction)?,
+            reaction,
+            action_hash,
+        });
+    }
+
+    let lost_and_found = my_chain_entries::<LostFoundPost>(UnitEntryTypes::LostFoundPost)?
+        .into_iter()
+        .map(|(_, post)| post)
+        .collect();
+
+    Ok(FeedExport {
+        posts,
+        comments,
+        reactions,
+        lost_and_found,
+    })
+}
+
+/// Every entry of one type on my own source chain, oldest first
+fn my_chain_entries<T>(entry_type: UnitEntryTypes) -> ExternResult<Vec<(ActionHash, T)>>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(entry_type.try_into()?)
+            .include_entries(true),
+    )?;
+
+    let mut entries = Vec::new();
+    for record in records {
+        if let Some(entry) = record
+            .entry()
+            .to_app_option::<T>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        {
+            entries.push((record.action_address().clone(), entry));
+        }
+    }
+
+    Ok(entries)
+}
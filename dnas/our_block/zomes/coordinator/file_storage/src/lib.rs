@@ -1,11 +1,33 @@
 use hdk::prelude::*;
 use file_storage_integrity::*;
+use signal_envelope::SignalEnvelope;
+
+/// Signal types for real-time updates, emitted inside a SignalEnvelope
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "event", content = "payload")]
+pub enum Signal {
+    /// One of my files expires soon; `renew_file` keeps it
+    FileExpiringSoon { metadata_hash: ActionHash, name: String, expires_at: Timestamp },
+}
+
+/// Zome name clients see in the signal envelope
+const SIGNAL_ZOME: &str = "file_storage";
+
+fn emit_zome_signal(signal: Signal) -> ExternResult<()> {
+    emit_signal(SignalEnvelope::new(SIGNAL_ZOME, signal))
+}
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct UploadFileInput {
     pub name: String,
     pub file_type: String,
     pub data: Vec<u8>,
+    #[serde(default)]
+    pub log_access: bool, // Record who fetches it, for sensitive documents
+    #[serde(default)]
+    pub expires_at: Option<Timestamp>,
+    #[serde(default)]
+    pub compress: Option<bool>, // None decides by type and size
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -14,28 +36,211 @@ pub struct FileMetadataOutput {
     pub metadata: FileMetadata,
 }
 
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetFileChunkInput {
+    pub metadata_hash: ActionHash,
+    pub index: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FileChunkOutput {
+    pub index: u32,
+    pub total_chunks: u32,
+    #[serde(with = "serde_bytes")]
+    pub data: Vec<u8>,
+}
+
+/// Result of checking every chunk of a file against its recorded hashes
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FileVerification {
+    pub metadata_hash: ActionHash,
+    pub total_chunks: u32,
+    /// Chunks whose content doesn't match their recorded hash
+    pub corrupted_chunks: Vec<u32>,
+    /// Chunks that couldn't be fetched
+    pub missing_chunks: Vec<u32>,
+    /// Whether the reassembled file matches the whole-file hash; None if
+    /// chunks are missing
+    pub file_hash_matches: Option<bool>,
+    pub ok: bool,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct FileOutput {
     pub metadata: FileMetadata,
     pub data: Vec<u8>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default)]
+pub enum FileSort {
+    #[default]
+    Newest,
+    Oldest,
+    Name,
+    Largest,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetAllFilesInput {
+    pub page: u32, // Zero-based
+    pub page_size: Option<u32>,
+    pub mime_prefix: Option<String>, // e.g. "image/" or "application/pdf"
+    pub sort: Option<FileSort>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FilePage {
+    pub files: Vec<FileMetadataOutput>,
+    pub page: u32,
+    pub page_size: u32,
+    pub total: u32, // Matching files across all pages
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateCollectionInput {
+    pub name: String,
+    pub description: Option<String>,
+    pub parent: Option<ActionHash>,
+    pub managers: Vec<AgentPubKey>, // The caller is always added
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CollectionOutput {
+    pub collection_hash: ActionHash,
+    pub collection: FileCollection,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CollectionFileInput {
+    pub collection_hash: ActionHash,
+    pub file_hash: ActionHash,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CollectionContents {
+    pub collection: CollectionOutput,
+    pub files: Vec<FileMetadataOutput>,
+    pub children: Vec<CollectionOutput>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BeginUploadInput {
+    pub name: String,
+    pub file_type: String,
+    pub size: u32,
+    pub sha256: String,
+    pub chunk_size: Option<u32>, // Defaults to DEFAULT_SESSION_CHUNK_SIZE
+    #[serde(default)]
+    pub log_access: bool,
+    #[serde(default)]
+    pub expires_at: Option<Timestamp>,
+    #[serde(default)]
+    pub compress: Option<bool>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UploadChunkInput {
+    pub session: ActionHash,
+    pub index: u32,
+    #[serde(with = "serde_bytes")]
+    pub bytes: Vec<u8>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RenewFileInput {
+    pub metadata_hash: ActionHash,
+    pub expires_at: Option<Timestamp>, // None keeps the file until deleted
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExpiryCleanup {
+    pub deleted: u32,
+    /// Files within FILE_EXPIRY_WARNING_MICROS of expiring, each also signalled
+    pub expiring_soon: Vec<FileMetadataOutput>,
+}
+
+/// Files and bytes under one top-level MIME type, e.g. `image`
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CategoryUsage {
+    pub category: String,
+    pub files: u32,
+    pub bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UploaderUsage {
+    pub agent: AgentPubKey,
+    pub files: u32,
+    pub bytes: u64,
+}
+
+/// What the DHT is carrying for the file index, for hub operators
+#[derive(Serialize, Deserialize, Debug)]
+pub struct StorageStats {
+    /// Every file still stored, including expired ones awaiting cleanup
+    pub total_files: u32,
+    pub total_bytes: u64,
+    /// Largest first
+    pub by_category: Vec<CategoryUsage>,
+    /// Up to STORAGE_STATS_TOP_UPLOADERS, most bytes first
+    pub top_uploaders: Vec<UploaderUsage>,
+    pub expired_files: u32,
+    /// Chunks still stored although their file was deleted. A lower bound:
+    /// chunks from uploads that never finished aren't in the file index.
+    pub orphaned_chunks: u32,
+    /// Chunks held by my own expired upload sessions; `cleanup_abandoned_uploads` removes them
+    pub my_abandoned_upload_chunks: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UploadProgress {
+    pub session: ActionHash,
+    pub upload: UploadSession,
+    pub received_chunks: Vec<u32>,
+}
+
+/// Which file types and sizes this neighborhood accepts, for checking before upload
+#[hdk_extern]
+pub fn get_upload_policy(_: ()) -> ExternResult<UploadPolicy> {
+    upload_policy()
+}
+
 /// Upload a file by chunking it and storing metadata
+///
+/// The declared type is checked against the neighborhood's upload policy
+/// and against the content's leading bytes before anything is stored.
 #[hdk_extern]
 pub fn upload_file(input: UploadFileInput) -> ExternResult<FileMetadataOutput> {
+    let file_type = normalize_file_type(&input.file_type);
+    if input.data.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest("File cannot be empty".into())));
+    }
+    require_upload_allowed(&file_type, input.data.len() as u64)?;
+    require_content_matches(&file_type, &input.data)?;
+    require_future_expiry(input.expires_at)?;
+    
     // Calculate SHA-256 hash of the file
-    let hash = format!("{:x}", sha2::Sha256::digest(&input.data));
+    let hash = sha256_hex(&input.data);
     
     // Split file into chunks
     let chunk_size = MAX_CHUNK_SIZE;
+    let compress = should_compress(&file_type, input.data.len() as u64, input.compress);
     let mut chunks = Vec::new();
+    let mut chunk_hashes = Vec::new();
+    let mut compression = Compression::None;
     let mut chunk_index = 0u32;
     
     for chunk_data in input.data.chunks(chunk_size) {
+        let (data, chunk_compression) = pack_chunk(chunk_data, compress);
+        chunk_hashes.push(sha256_hex(&data));
+        if chunk_compression != Compression::None {
+            compression = chunk_compression;
+        }
         let chunk = FileChunk {
             file_hash: ActionHash::from_raw_39(vec![0; 39]), // Placeholder
             chunk_index,
-            data: chunk_data.to_vec(),
+            data,
+            compression: chunk_compression,
         };
         
         let chunk_hash = create_entry(EntryTypes::FileChunk(chunk))?;
@@ -47,104 +252,561 @@ pub fn upload_file(input: UploadFileInput) -> ExternResult<FileMetadataOutput> {
     let metadata = FileMetadata {
         name: input.name,
         size: input.data.len() as u32,
-        file_type: input.file_type,
+        file_type,
         hash,
         chunks,
         author: agent_info()?.agent_initial_pubkey,
         created_at: sys_time()?,
+        chunk_hashes,
+        log_access: input.log_access,
+        expires_at: input.expires_at,
+        compression,
     };
     
-    let metadata_hash = create_entry(EntryTypes::FileMetadata(metadata.clone()))?;
+    let metadata_hash = publish_file_metadata(&metadata)?;
     
-    // Create links
-    let all_files_anchor = Path::from("all_files");
-    let _anchor_hash = all_files_anchor.clone().typed(LinkTypes::AllFiles)?.ensure()?;
-    create_link(
-        all_files_anchor.path_entry_hash()?,
-        metadata_hash.clone(),
-        LinkTypes::AllFiles,
-        (),
+    Ok(FileMetadataOutput {
+        metadata_hash,
+        metadata,
+    })
+}
+
+// ============================================================================
+// COLLECTIONS
+// ============================================================================
+
+/// Create a collection, either top-level or nested inside one I manage
+#[hdk_extern]
+pub fn create_collection(input: CreateCollectionInput) -> ExternResult<CollectionOutput> {
+    let my_pub_key = agent_info()?.agent_initial_pubkey;
+    
+    let mut managers = vec![my_pub_key.clone()];
+    for manager in input.managers {
+        if !managers.contains(&manager) {
+            managers.push(manager);
+        }
+    }
+    
+    if let Some(parent_hash) = &input.parent {
+        let parent = get_collection(parent_hash.clone())?
+            .ok_or(wasm_error!(WasmErrorInner::Guest("Parent collection not found".into())))?;
+        if !parent.collection.managers.contains(&my_pub_key) {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Only managers of the parent collection can nest collections in it".into()
+            )));
+        }
+    }
+    
+    let collection = FileCollection {
+        name: input.name,
+        description: input.description,
+        parent: input.parent,
+        managers,
+        created_at: sys_time()?,
+    };
+    
+    let collection_hash = create_entry(EntryTypes::FileCollection(collection.clone()))?;
+    
+    match &collection.parent {
+        Some(parent_hash) => {
+            create_link(
+                parent_hash.clone(),
+                collection_hash.clone(),
+                LinkTypes::CollectionToChildren,
+                (),
+            )?;
+        }
+        None => {
+            let all_collections_anchor = Path::from("all_collections");
+            create_link(
+                all_collections_anchor.path_entry_hash()?,
+                collection_hash.clone(),
+                LinkTypes::AllCollections,
+                (),
+            )?;
+        }
+    }
+    
+    Ok(CollectionOutput {
+        collection_hash,
+        collection,
+    })
+}
+
+/// Get a single collection
+#[hdk_extern]
+pub fn get_collection(collection_hash: ActionHash) -> ExternResult<Option<CollectionOutput>> {
+    let Some(record) = get(collection_hash.clone(), GetOptions::default())? else {
+        return Ok(None);
+    };
+    
+    let Some(collection) = record
+        .entry()
+        .to_app_option::<FileCollection>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to deserialize: {:?}", e))))?
+    else {
+        return Ok(None);
+    };
+    
+    Ok(Some(CollectionOutput {
+        collection_hash,
+        collection,
+    }))
+}
+
+/// Get all top-level collections
+#[hdk_extern]
+pub fn get_collections(_: ()) -> ExternResult<Vec<CollectionOutput>> {
+    let links = get_links(
+        LinkQuery::try_new(Path::from("all_collections").path_entry_hash()?, LinkTypes::AllCollections)?,
+        GetStrategy::Local,
     )?;
     
-    let agent_pub_key = agent_info()?.agent_initial_pubkey;
+    collections_from_links(links)
+}
+
+/// File an existing upload into a collection (managers only)
+#[hdk_extern]
+pub fn add_file_to_collection(input: CollectionFileInput) -> ExternResult<()> {
+    require_collection_manager(&input.collection_hash)?;
+    
+    if get_file_metadata(input.file_hash.clone())?.is_none() {
+        return Err(wasm_error!(WasmErrorInner::Guest("File not found".into())));
+    }
+    
+    let already_added = get_collection_file_links(&input.collection_hash)?
+        .iter()
+        .any(|link| link.target.clone().into_action_hash().as_ref() == Some(&input.file_hash));
+    if already_added {
+        return Ok(());
+    }
+    
     create_link(
-        agent_pub_key,
-        metadata_hash.clone(),
-        LinkTypes::AgentToFiles,
+        input.collection_hash,
+        input.file_hash,
+        LinkTypes::CollectionToFiles,
         (),
     )?;
     
-    // Link chunks to metadata
-    for chunk_hash in &metadata.chunks {
+    Ok(())
+}
+
+/// Take a file out of a collection without deleting the file itself (managers only)
+#[hdk_extern]
+pub fn remove_file_from_collection(input: CollectionFileInput) -> ExternResult<()> {
+    require_collection_manager(&input.collection_hash)?;
+    
+    for link in get_collection_file_links(&input.collection_hash)? {
+        if link.target.clone().into_action_hash().as_ref() == Some(&input.file_hash) {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
+    }
+    
+    Ok(())
+}
+
+/// Get the files and sub-collections directly inside a collection
+#[hdk_extern]
+pub fn get_collection_contents(collection_hash: ActionHash) -> ExternResult<CollectionContents> {
+    let collection = get_collection(collection_hash.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Collection not found".into())))?;
+    
+    let now = sys_time()?;
+    let mut files = Vec::new();
+    for link in get_collection_file_links(&collection_hash)? {
+        if let Some(file_hash) = link.target.into_action_hash() {
+            if let Some(file) = get_file_metadata(file_hash)? {
+                if !is_expired(&file.metadata, now) {
+                    files.push(file);
+                }
+            }
+        }
+    }
+    files.sort_by_key(|file| file.metadata.name.to_lowercase());
+    
+    let child_links = get_links(
+        LinkQuery::try_new(collection_hash, LinkTypes::CollectionToChildren)?,
+        GetStrategy::Local,
+    )?;
+    let children = collections_from_links(child_links)?;
+    
+    Ok(CollectionContents {
+        collection,
+        files,
+        children,
+    })
+}
+
+// ============================================================================
+// RESUMABLE UPLOAD SESSIONS
+// ============================================================================
+
+/// Start a multi-call upload; chunks are sent one per call with `upload_chunk`
+#[hdk_extern]
+pub fn begin_upload(input: BeginUploadInput) -> ExternResult<UploadProgress> {
+    let chunk_size = input.chunk_size.unwrap_or(DEFAULT_SESSION_CHUNK_SIZE as u32);
+    if chunk_size == 0 {
+        return Err(wasm_error!(WasmErrorInner::Guest("Chunk size must be positive".into())));
+    }
+    let file_type = normalize_file_type(&input.file_type);
+    require_upload_allowed(&file_type, input.size as u64)?;
+    require_future_expiry(input.expires_at)?;
+    let compress = should_compress(&file_type, input.size as u64, input.compress);
+    
+    let started_at = sys_time()?;
+    let upload = UploadSession {
+        name: input.name,
+        file_type,
+        size: input.size,
+        sha256: input.sha256.to_lowercase(),
+        chunk_size,
+        total_chunks: input.size.div_ceil(chunk_size),
+        started_at,
+        expires_at: Timestamp::from_micros(started_at.as_micros() + UPLOAD_SESSION_TTL_MICROS),
+        log_access: input.log_access,
+        file_expires_at: input.expires_at,
+        compress,
+    };
+    
+    let session = create_entry(EntryTypes::UploadSession(upload.clone()))?;
+    
+    Ok(UploadProgress {
+        session,
+        upload,
+        received_chunks: Vec::new(),
+    })
+}
+
+/// Store one chunk of an open session. Re-sending an index already stored is a no-op,
+/// so clients can safely retry after a dropped connection.
+#[hdk_extern]
+pub fn upload_chunk(input: UploadChunkInput) -> ExternResult<UploadProgress> {
+    let upload = get_open_session(&input.session)?;
+    
+    if input.index >= upload.total_chunks {
+        return Err(wasm_error!(WasmErrorInner::Guest("Chunk index out of range".into())));
+    }
+    if input.bytes.len() != expected_chunk_len(&upload, input.index) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Chunk {} has the wrong length",
+            input.index
+        ))));
+    }
+    // Catch a mismatched type now rather than after the whole file is sent
+    if input.index == 0 {
+        require_content_matches(&upload.file_type, &input.bytes)?;
+    }
+    
+    let mut chunk_links = get_session_chunk_links(&input.session)?;
+    if !chunk_links.iter().any(|(index, _)| *index == input.index) {
+        let (data, compression) = pack_chunk(&input.bytes, upload.compress);
+        let chunk_hash = create_entry(EntryTypes::FileChunk(FileChunk {
+            file_hash: input.session.clone(),
+            chunk_index: input.index,
+            data,
+            compression,
+        }))?;
         create_link(
-            metadata_hash.clone(),
+            input.session.clone(),
             chunk_hash.clone(),
-            LinkTypes::FileToChunks,
-            (),
+            LinkTypes::UploadSessionToChunks,
+            LinkTag::new(input.index.to_le_bytes().to_vec()),
         )?;
+        chunk_links.push((input.index, chunk_hash));
+    }
+    
+    Ok(UploadProgress {
+        session: input.session,
+        upload,
+        received_chunks: received_indices(&chunk_links),
+    })
+}
+
+/// Report which chunks of a session are stored, for resuming an interrupted upload
+#[hdk_extern]
+pub fn get_upload_progress(session: ActionHash) -> ExternResult<UploadProgress> {
+    let upload = get_open_session(&session)?;
+    let chunk_links = get_session_chunk_links(&session)?;
+    
+    Ok(UploadProgress {
+        session,
+        upload,
+        received_chunks: received_indices(&chunk_links),
+    })
+}
+
+/// Verify every chunk is present and the content matches the declared SHA-256,
+/// then publish the file and close the session
+#[hdk_extern]
+pub fn finish_upload(session: ActionHash) -> ExternResult<FileMetadataOutput> {
+    let upload = get_open_session(&session)?;
+    
+    let mut chunk_links = get_session_chunk_links(&session)?;
+    chunk_links.sort_by_key(|(index, _)| *index);
+    chunk_links.dedup_by_key(|(index, _)| *index);
+    if chunk_links.len() != upload.total_chunks as usize {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Upload incomplete: {} of {} chunks received",
+            chunk_links.len(),
+            upload.total_chunks
+        ))));
+    }
+    
+    // Hash one chunk at a time so the whole file is never held in memory
+    let mut hasher = sha2::Sha256::new();
+    let mut size = 0u64;
+    let mut chunk_hashes = Vec::new();
+    let mut compression = Compression::None;
+    for (_, chunk_hash) in &chunk_links {
+        let chunk = get_chunk(chunk_hash)?;
+        let content = chunk_bytes(&chunk)?;
+        if size == 0 {
+            require_content_matches(&upload.file_type, &content)?;
+        }
+        size += content.len() as u64;
+        hasher.update(&content);
+        chunk_hashes.push(sha256_hex(&chunk.data));
+        if chunk.compression != Compression::None {
+            compression = chunk.compression;
+        }
+    }
+    let hash = format!("{:x}", hasher.finalize());
+    
+    if size != upload.size as u64 {
+        return Err(wasm_error!(WasmErrorInner::Guest("Uploaded size does not match declared size".into())));
+    }
+    if hash != upload.sha256 {
+        return Err(wasm_error!(WasmErrorInner::Guest("Uploaded content does not match declared SHA-256".into())));
     }
     
+    let metadata = FileMetadata {
+        name: upload.name,
+        size: upload.size,
+        file_type: upload.file_type,
+        hash,
+        chunks: chunk_links.into_iter().map(|(_, chunk_hash)| chunk_hash).collect(),
+        author: agent_info()?.agent_initial_pubkey,
+        created_at: sys_time()?,
+        chunk_hashes,
+        log_access: upload.log_access,
+        expires_at: upload.file_expires_at,
+        compression,
+    };
+    
+    let metadata_hash = publish_file_metadata(&metadata)?;
+    delete_entry(session)?;
+    
     Ok(FileMetadataOutput {
         metadata_hash,
         metadata,
     })
 }
 
+/// Discard an open session and every chunk uploaded to it
+#[hdk_extern]
+pub fn abandon_upload(session: ActionHash) -> ExternResult<()> {
+    get_open_session(&session)?;
+    discard_session(session)
+}
+
+/// Discard all of my sessions that expired before being finished.
+/// Returns the number of sessions cleaned up.
+#[hdk_extern]
+pub fn cleanup_abandoned_uploads(_: ()) -> ExternResult<u32> {
+    let now = sys_time()?;
+    let mut cleaned = 0;
+    
+    for (session, upload) in get_open_sessions()? {
+        if upload.expires_at <= now {
+            discard_session(session)?;
+            cleaned += 1;
+        }
+    }
+    
+    Ok(cleaned)
+}
+
 /// Get file by its metadata hash
+///
+/// Fetching an access-logged file records a FileAccess first.
 #[hdk_extern]
 pub fn get_file(metadata_hash: ActionHash) -> ExternResult<FileOutput> {
-    let record = get(metadata_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("File not found".into())))?;
+    let metadata = get_file_metadata(metadata_hash.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("File not found".into())))?
+        .metadata;
+    record_access(&metadata_hash, &metadata)?;
     
-    let metadata: FileMetadata = record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to deserialize: {:?}", e))))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid file metadata".into())))?;
-    
-    // Reconstruct file data from chunks
+    // Reconstruct file data from chunks, failing at the first corrupted one
     let mut data = Vec::new();
-    for chunk_hash in &metadata.chunks {
-        let chunk_record = get(chunk_hash.clone(), GetOptions::default())?
-            .ok_or(wasm_error!(WasmErrorInner::Guest("Chunk not found".into())))?;
-        
-        let chunk: FileChunk = chunk_record
-            .entry()
-            .to_app_option()
-            .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to deserialize chunk: {:?}", e))))?
-            .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid chunk data".into())))?;
-        
-        data.extend_from_slice(&chunk.data);
+    for index in 0..metadata.chunks.len() {
+        let chunk = get_verified_chunk(&metadata, index)?;
+        data.extend_from_slice(&chunk_bytes(&chunk)?);
     }
     
     Ok(FileOutput { metadata, data })
 }
 
-/// Get all files uploaded by an agent
+/// Get one chunk of a file, checked against its recorded hash
+///
+/// Lets clients stream large files and retry a single bad chunk instead of
+/// refetching the whole file. Streaming an access-logged file records one
+/// FileAccess, when its first chunk is fetched.
+#[hdk_extern]
+pub fn get_file_chunk(input: GetFileChunkInput) -> ExternResult<FileChunkOutput> {
+    let metadata = get_file_metadata(input.metadata_hash.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("File not found".into())))?
+        .metadata;
+    if input.index as usize >= metadata.chunks.len() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Chunk index out of range".into())));
+    }
+    if input.index == 0 {
+        record_access(&input.metadata_hash, &metadata)?;
+    }
+    
+    let chunk = get_verified_chunk(&metadata, input.index as usize)?;
+    
+    Ok(FileChunkOutput {
+        index: input.index,
+        total_chunks: metadata.chunks.len() as u32,
+        data: chunk_bytes(&chunk)?,
+    })
+}
+
+/// Check every chunk of a file and report which are corrupted or missing
+#[hdk_extern]
+pub fn verify_file(metadata_hash: ActionHash) -> ExternResult<FileVerification> {
+    let metadata = get_file_metadata(metadata_hash.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("File not found".into())))?
+        .metadata;
+    
+    let mut corrupted_chunks = Vec::new();
+    let mut missing_chunks = Vec::new();
+    let mut hasher = sha2::Sha256::new();
+    for (index, chunk_hash) in metadata.chunks.iter().enumerate() {
+        let chunk = match get(chunk_hash.clone(), GetOptions::default())? {
+            Some(record) => record.entry().to_app_option::<FileChunk>().ok().flatten(),
+            None => None,
+        };
+        let Some(chunk) = chunk else {
+            missing_chunks.push(index as u32);
+            continue;
+        };
+        let mut corrupted = metadata
+            .chunk_hashes
+            .get(index)
+            .is_some_and(|expected| sha256_hex(&chunk.data) != *expected);
+        match chunk_content(&chunk) {
+            Ok(content) => hasher.update(&content),
+            Err(_) => corrupted = true,
+        }
+        if corrupted {
+            corrupted_chunks.push(index as u32);
+        }
+    }
+    
+    let file_hash_matches = missing_chunks
+        .is_empty()
+        .then(|| format!("{:x}", hasher.finalize()) == metadata.hash);
+    let ok = corrupted_chunks.is_empty() && missing_chunks.is_empty() && file_hash_matches == Some(true);
+    
+    Ok(FileVerification {
+        metadata_hash,
+        total_chunks: metadata.chunks.len() as u32,
+        corrupted_chunks,
+        missing_chunks,
+        file_hash_matches,
+        ok,
+    })
+}
+
+/// Get file metadata without fetching any chunks
+///
+/// Follows renewals, so the expiry is the latest one; `metadata_hash` stays
+/// the original upload's.
+#[hdk_extern]
+pub fn get_file_metadata(metadata_hash: ActionHash) -> ExternResult<Option<FileMetadataOutput>> {
+    let Some(record) = latest_record(metadata_hash.clone())? else {
+        return Ok(None);
+    };
+    
+    let Some(metadata) = record
+        .entry()
+        .to_app_option::<FileMetadata>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to deserialize: {:?}", e))))?
+    else {
+        return Ok(None);
+    };
+    
+    Ok(Some(FileMetadataOutput {
+        metadata_hash,
+        metadata,
+    }))
+}
+
+/// Get all files uploaded by an agent, leaving out expired ones
 #[hdk_extern]
 pub fn get_my_files(_: ()) -> ExternResult<Vec<FileMetadataOutput>> {
-    let agent_pub_key = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+    Ok(my_files()?
+        .into_iter()
+        .filter(|file| !is_expired(&file.metadata, now))
+        .collect())
+}
+
+/// Browse every file shared in the neighborhood. Only metadata is fetched, never chunks.
+#[hdk_extern]
+pub fn get_all_files(input: GetAllFilesInput) -> ExternResult<FilePage> {
+    let page_size = input
+        .page_size
+        .unwrap_or(DEFAULT_FILE_PAGE_SIZE)
+        .clamp(1, MAX_FILE_PAGE_SIZE);
+    let mime_prefix = input.mime_prefix.map(|prefix| prefix.to_lowercase());
+    let now = sys_time()?;
     
     let links = get_links(
-        LinkQuery::try_new(agent_pub_key, LinkTypes::AgentToFiles)?,
+        LinkQuery::try_new(Path::from("all_files").path_entry_hash()?, LinkTypes::AllFiles)?,
         GetStrategy::Local,
     )?;
     
     let mut files = Vec::new();
     for link in links {
-        if let Some(record) = get(link.target.clone().into_action_hash().ok_or(wasm_error!(
-            WasmErrorInner::Guest("Invalid link target".into())
-        ))?, GetOptions::default())? {
-            if let Some(metadata) = record.entry().to_app_option::<FileMetadata>().map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to deserialize: {:?}", e))))? {
-                files.push(FileMetadataOutput {
-                    metadata_hash: link.target.into_action_hash().unwrap(),
-                    metadata,
-                });
+        let Some(metadata_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(file) = get_file_metadata(metadata_hash)? else {
+            continue;
+        };
+        if is_expired(&file.metadata, now) {
+            continue;
+        }
+        if let Some(prefix) = &mime_prefix {
+            if !file.metadata.file_type.to_lowercase().starts_with(prefix.as_str()) {
+                continue;
             }
         }
+        files.push(file);
     }
     
-    Ok(files)
+    match input.sort.unwrap_or_default() {
+        FileSort::Newest => files.sort_by(|a, b| b.metadata.created_at.cmp(&a.metadata.created_at)),
+        FileSort::Oldest => files.sort_by(|a, b| a.metadata.created_at.cmp(&b.metadata.created_at)),
+        FileSort::Name => files.sort_by_key(|file| file.metadata.name.to_lowercase()),
+        FileSort::Largest => files.sort_by(|a, b| b.metadata.size.cmp(&a.metadata.size)),
+    }
+    
+    let total = files.len() as u32;
+    let files = files
+        .into_iter()
+        .skip(input.page as usize * page_size as usize)
+        .take(page_size as usize)
+        .collect();
+    
+    Ok(FilePage {
+        files,
+        page: input.page,
+        page_size,
+        total,
+    })
 }
 
 /// Delete a file and its chunks
@@ -170,4 +832,537 @@ pub fn delete_file(metadata_hash: ActionHash) -> ExternResult<()> {
     Ok(())
 }
 
+// ============================================================================
+// STORAGE STATS
+// ============================================================================
+
+/// Totals across every file in the neighborhood, by MIME category and by
+/// uploader, plus how many chunks are stored without a file
+#[hdk_extern]
+pub fn get_storage_stats(_: ()) -> ExternResult<StorageStats> {
+    let links = get_links(
+        LinkQuery::try_new(Path::from("all_files").path_entry_hash()?, LinkTypes::AllFiles)?,
+        GetStrategy::Local,
+    )?;
+    let now = sys_time()?;
+    
+    let mut seen = HashSet::new();
+    let mut total_files = 0;
+    let mut total_bytes = 0;
+    let mut categories: HashMap<String, CategoryUsage> = HashMap::new();
+    let mut uploaders: HashMap<AgentPubKey, UploaderUsage> = HashMap::new();
+    let mut expired_files = 0;
+    let mut orphaned_chunks = 0;
+    
+    for link in links {
+        let Some(metadata_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        if !seen.insert(metadata_hash.clone()) {
+            continue;
+        }
+        let Some(Details::Record(details)) = get_details(metadata_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let Ok(Some(original)) = details.record.entry().to_app_option::<FileMetadata>() else {
+            continue;
+        };
+        
+        // A deleted file's index link stays behind; any chunk still live is orphaned
+        if !details.deletes.is_empty() {
+            for chunk_hash in original.chunks {
+                if is_live(chunk_hash)? {
+                    orphaned_chunks += 1;
+                }
+            }
+            continue;
+        }
+        
+        let Some(file) = get_file_metadata(metadata_hash)? else {
+            continue;
+        };
+        let metadata = file.metadata;
+        let bytes = metadata.size as u64;
+        total_files += 1;
+        total_bytes += bytes;
+        if is_expired(&metadata, now) {
+            expired_files += 1;
+        }
+        
+        let category = metadata.file_type.split('/').next().unwrap_or_default().to_string();
+        let usage = categories.entry(category.clone()).or_insert(CategoryUsage {
+            category,
+            files: 0,
+            bytes: 0,
+        });
+        usage.files += 1;
+        usage.bytes += bytes;
+        
+        let usage = uploaders.entry(metadata.author.clone()).or_insert(UploaderUsage {
+            agent: metadata.author,
+            files: 0,
+            bytes: 0,
+        });
+        usage.files += 1;
+        usage.bytes += bytes;
+    }
+    
+    let mut by_category: Vec<CategoryUsage> = categories.into_values().collect();
+    by_category.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.category.cmp(&b.category)));
+    let mut top_uploaders: Vec<UploaderUsage> = uploaders.into_values().collect();
+    top_uploaders.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| b.files.cmp(&a.files)));
+    top_uploaders.truncate(STORAGE_STATS_TOP_UPLOADERS);
+    
+    let mut my_abandoned_upload_chunks = 0;
+    for (session, upload) in get_open_sessions()? {
+        if upload.expires_at <= now {
+            my_abandoned_upload_chunks += received_indices(&get_session_chunk_links(&session)?).len() as u32;
+        }
+    }
+    
+    Ok(StorageStats {
+        total_files,
+        total_bytes,
+        by_category,
+        top_uploaders,
+        expired_files,
+        orphaned_chunks,
+        my_abandoned_upload_chunks,
+    })
+}
+
+// ============================================================================
+// RETENTION
+// ============================================================================
+
+/// Set a new expiry on one of my files, or clear it to keep the file
+#[hdk_extern]
+pub fn renew_file(input: RenewFileInput) -> ExternResult<FileMetadataOutput> {
+    require_future_expiry(input.expires_at)?;
+    let latest = latest_record(input.metadata_hash.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("File not found".into())))?;
+    let mut metadata: FileMetadata = latest
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to deserialize: {:?}", e))))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid file metadata".into())))?;
+    if metadata.author != agent_info()?.agent_initial_pubkey {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only the author can renew their file".into())));
+    }
+    
+    metadata.expires_at = input.expires_at;
+    update_entry(latest.action_address().clone(), &metadata)?;
+    
+    Ok(FileMetadataOutput {
+        metadata_hash: input.metadata_hash,
+        metadata,
+    })
+}
+
+/// Delete my files that have expired, and signal each one that expires within
+/// FILE_EXPIRY_WARNING_MICROS so the UI can offer renewal. Meant to be called
+/// periodically, like `cleanup_abandoned_uploads`.
+#[hdk_extern]
+pub fn cleanup_expired_files(_: ()) -> ExternResult<ExpiryCleanup> {
+    let now = sys_time()?;
+    let warn_before = Timestamp::from_micros(now.as_micros() + FILE_EXPIRY_WARNING_MICROS);
+    let mut deleted = 0;
+    let mut expiring_soon = Vec::new();
+    
+    for file in my_files()? {
+        let Some(expires_at) = file.metadata.expires_at else {
+            continue;
+        };
+        if expires_at <= now {
+            delete_file(file.metadata_hash)?;
+            deleted += 1;
+        } else if expires_at <= warn_before {
+            emit_zome_signal(Signal::FileExpiringSoon {
+                metadata_hash: file.metadata_hash.clone(),
+                name: file.metadata.name.clone(),
+                expires_at,
+            })?;
+            expiring_soon.push(file);
+        }
+    }
+    
+    Ok(ExpiryCleanup { deleted, expiring_soon })
+}
+
+// ============================================================================
+// ACCESS LOGS
+// ============================================================================
+
+/// Everyone who has fetched one of my access-logged files, newest first
+#[hdk_extern]
+pub fn get_access_log(metadata_hash: ActionHash) -> ExternResult<Vec<FileAccess>> {
+    let metadata = get_file_metadata(metadata_hash.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("File not found".into())))?
+        .metadata;
+    if metadata.author != agent_info()?.agent_initial_pubkey {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only the file's author can see its access log".into())));
+    }
+    
+    let links = get_links(
+        LinkQuery::try_new(metadata_hash, LinkTypes::FileToAccesses)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut accesses = Vec::new();
+    for link in links {
+        let Some(access_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(access_hash, GetOptions::default())? else {
+            continue;
+        };
+        if let Ok(Some(access)) = record.entry().to_app_option::<FileAccess>() {
+            accesses.push(access);
+        }
+    }
+    accesses.sort_by(|a, b| b.accessed_at.cmp(&a.accessed_at));
+    
+    Ok(accesses)
+}
+
+/// Record that I fetched an access-logged file; the author's own reads aren't logged
+fn record_access(metadata_hash: &ActionHash, metadata: &FileMetadata) -> ExternResult<()> {
+    let me = agent_info()?.agent_initial_pubkey;
+    if !metadata.log_access || metadata.author == me {
+        return Ok(());
+    }
+    
+    let access_hash = create_entry(EntryTypes::FileAccess(FileAccess {
+        file: metadata_hash.clone(),
+        accessor: me,
+        accessed_at: sys_time()?,
+    }))?;
+    create_link(
+        metadata_hash.clone(),
+        access_hash,
+        LinkTypes::FileToAccesses,
+        (),
+    )?;
+    
+    Ok(())
+}
+
+// ============================================================================
+// HELPER FUNCTIONS
+// ============================================================================
+
+/// Create the metadata entry and link it into the file indexes
+fn publish_file_metadata(metadata: &FileMetadata) -> ExternResult<ActionHash> {
+    let metadata_hash = create_entry(EntryTypes::FileMetadata(metadata.clone()))?;
+    
+    // Create links
+    let all_files_anchor = Path::from("all_files");
+    let _anchor_hash = all_files_anchor.clone().typed(LinkTypes::AllFiles)?.ensure()?;
+    create_link(
+        all_files_anchor.path_entry_hash()?,
+        metadata_hash.clone(),
+        LinkTypes::AllFiles,
+        (),
+    )?;
+    
+    let agent_pub_key = agent_info()?.agent_initial_pubkey;
+    create_link(
+        agent_pub_key,
+        metadata_hash.clone(),
+        LinkTypes::AgentToFiles,
+        (),
+    )?;
+    
+    // Link chunks to metadata
+    for chunk_hash in &metadata.chunks {
+        create_link(
+            metadata_hash.clone(),
+            chunk_hash.clone(),
+            LinkTypes::FileToChunks,
+            (),
+        )?;
+    }
+    
+    Ok(metadata_hash)
+}
+
+/// Every file I've uploaded, expired or not
+fn my_files() -> ExternResult<Vec<FileMetadataOutput>> {
+    let links = get_links(
+        LinkQuery::try_new(agent_info()?.agent_initial_pubkey, LinkTypes::AgentToFiles)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut files = Vec::new();
+    for link in links {
+        let Some(metadata_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        if let Some(file) = get_file_metadata(metadata_hash)? {
+            files.push(file);
+        }
+    }
+    
+    Ok(files)
+}
+
+/// Whether a record exists and hasn't been deleted
+fn is_live(action_hash: ActionHash) -> ExternResult<bool> {
+    Ok(matches!(
+        get_details(action_hash, GetOptions::default())?,
+        Some(Details::Record(details)) if details.deletes.is_empty()
+    ))
+}
+
+fn is_expired(metadata: &FileMetadata, now: Timestamp) -> bool {
+    metadata.expires_at.is_some_and(|expires_at| expires_at <= now)
+}
+
+fn require_future_expiry(expires_at: Option<Timestamp>) -> ExternResult<()> {
+    if let Some(expires_at) = expires_at {
+        if expires_at <= sys_time()? {
+            return Err(wasm_error!(WasmErrorInner::Guest("File expiry must be in the future".into())));
+        }
+    }
+    Ok(())
+}
+
+/// Follow a record's updates to the newest one; None once it has been deleted
+fn latest_record(action_hash: ActionHash) -> ExternResult<Option<Record>> {
+    let mut current = action_hash;
+    
+    loop {
+        let Some(Details::Record(details)) = get_details(current, GetOptions::default())? else {
+            return Ok(None);
+        };
+        if !details.deletes.is_empty() {
+            return Ok(None);
+        }
+        let next = details
+            .updates
+            .iter()
+            .max_by_key(|update| update.action().timestamp())
+            .map(|update| update.action_address().clone());
+        match next {
+            Some(next) => current = next,
+            None => return Ok(Some(details.record)),
+        }
+    }
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    format!("{:x}", sha2::Sha256::digest(data))
+}
+
+/// Whether to try compressing a new file's chunks: only formats that aren't
+/// already compressed, and only past COMPRESSION_MIN_SIZE, unless the uploader
+/// asked either way
+fn should_compress(file_type: &str, size: u64, requested: Option<bool>) -> bool {
+    requested.unwrap_or_else(|| {
+        let compressible = COMPRESSIBLE_FILE_TYPES.iter().any(|compressible| match compressible.strip_suffix("/*") {
+            Some(family) => file_type.split('/').next() == Some(family),
+            None => *compressible == file_type,
+        });
+        compressible && size >= COMPRESSION_MIN_SIZE as u64
+    })
+}
+
+/// The bytes to store for a chunk, deflated if that makes them smaller
+fn pack_chunk(content: &[u8], compress: bool) -> (Vec<u8>, Compression) {
+    if compress {
+        let deflated = miniz_oxide::deflate::compress_to_vec(content, 6); // zlib's default level
+        if deflated.len() < content.len() {
+            return (deflated, Compression::Deflate);
+        }
+    }
+    (content.to_vec(), Compression::None)
+}
+
+fn chunk_bytes(chunk: &FileChunk) -> ExternResult<Vec<u8>> {
+    chunk_content(chunk).map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))
+}
+
+/// Fetch chunk `index` of a file and check it against the recorded hash;
+/// files stored before per-chunk hashes are only checked as a whole
+fn get_verified_chunk(metadata: &FileMetadata, index: usize) -> ExternResult<FileChunk> {
+    let chunk = get_chunk(&metadata.chunks[index])?;
+    if let Some(expected) = metadata.chunk_hashes.get(index) {
+        if sha256_hex(&chunk.data) != *expected {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Chunk {} is corrupted",
+                index
+            ))));
+        }
+    }
+    Ok(chunk)
+}
+
+fn require_upload_allowed(file_type: &str, size: u64) -> ExternResult<()> {
+    let policy = upload_policy()?;
+    if !policy.allows_type(file_type) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Files of type {} are not allowed in this neighborhood",
+            file_type
+        ))));
+    }
+    if size > policy.max_file_size as u64 {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "File cannot exceed {} bytes",
+            policy.max_file_size
+        ))));
+    }
+    Ok(())
+}
+
+fn require_content_matches(file_type: &str, first_bytes: &[u8]) -> ExternResult<()> {
+    if !content_matches_type(file_type, first_bytes) {
+        let detected = sniff_file_type(first_bytes).unwrap_or("unrecognized content");
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "File declared as {} but looks like {}",
+            file_type, detected
+        ))));
+    }
+    Ok(())
+}
+
+/// Open sessions live only on my source chain; closed ones have been deleted
+fn get_open_sessions() -> ExternResult<Vec<(ActionHash, UploadSession)>> {
+    let closed: HashSet<ActionHash> = query(ChainQueryFilter::new().action_type(ActionType::Delete))?
+        .into_iter()
+        .filter_map(|record| match record.action() {
+            Action::Delete(delete) => Some(delete.deletes_address.clone()),
+            _ => None,
+        })
+        .collect();
+    
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::UploadSession.try_into()?)
+            .include_entries(true),
+    )?;
+    
+    let mut sessions = Vec::new();
+    for record in records {
+        let session = record.action_address().clone();
+        if closed.contains(&session) {
+            continue;
+        }
+        if let Some(upload) = record
+            .entry()
+            .to_app_option::<UploadSession>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to deserialize: {:?}", e))))?
+        {
+            sessions.push((session, upload));
+        }
+    }
+    
+    Ok(sessions)
+}
+
+fn get_open_session(session: &ActionHash) -> ExternResult<UploadSession> {
+    let upload = get_open_sessions()?
+        .into_iter()
+        .find(|(hash, _)| hash == session)
+        .map(|(_, upload)| upload)
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Upload session not found or already closed".into())))?;
+    
+    if upload.expires_at <= sys_time()? {
+        return Err(wasm_error!(WasmErrorInner::Guest("Upload session has expired".into())));
+    }
+    
+    Ok(upload)
+}
+
+/// Chunk links of a session as (index, chunk hash); the index is stored in the link tag
+fn get_session_chunk_links(session: &ActionHash) -> ExternResult<Vec<(u32, ActionHash)>> {
+    let links = get_links(
+        LinkQuery::try_new(session.clone(), LinkTypes::UploadSessionToChunks)?,
+        GetStrategy::Local,
+    )?;
+    
+    Ok(links
+        .into_iter()
+        .filter_map(|link| {
+            let index = u32::from_le_bytes(link.tag.0.as_slice().try_into().ok()?);
+            Some((index, link.target.into_action_hash()?))
+        })
+        .collect())
+}
+
+fn received_indices(chunk_links: &[(u32, ActionHash)]) -> Vec<u32> {
+    let mut indices: Vec<u32> = chunk_links.iter().map(|(index, _)| *index).collect();
+    indices.sort_unstable();
+    indices.dedup();
+    indices
+}
+
+/// Every chunk is `chunk_size` bytes except the last, which holds the remainder
+fn expected_chunk_len(upload: &UploadSession, index: u32) -> usize {
+    if index + 1 < upload.total_chunks {
+        upload.chunk_size as usize
+    } else {
+        (upload.size - upload.chunk_size * (upload.total_chunks - 1)) as usize
+    }
+}
+
+fn get_chunk(chunk_hash: &ActionHash) -> ExternResult<FileChunk> {
+    let record = get(chunk_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Chunk not found".into())))?;
+    
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to deserialize chunk: {:?}", e))))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid chunk data".into())))
+}
+
+fn discard_session(session: ActionHash) -> ExternResult<()> {
+    let links = get_links(
+        LinkQuery::try_new(session.clone(), LinkTypes::UploadSessionToChunks)?,
+        GetStrategy::Local,
+    )?;
+    
+    for link in links {
+        if let Some(chunk_hash) = link.target.clone().into_action_hash() {
+            delete_entry(chunk_hash)?;
+        }
+        delete_link(link.create_link_hash, GetOptions::default())?;
+    }
+    
+    delete_entry(session)?;
+    Ok(())
+}
+
+fn require_collection_manager(collection_hash: &ActionHash) -> ExternResult<CollectionOutput> {
+    let collection = get_collection(collection_hash.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Collection not found".into())))?;
+    
+    if !collection.collection.managers.contains(&agent_info()?.agent_initial_pubkey) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only collection managers can change its contents".into())));
+    }
+    
+    Ok(collection)
+}
+
+fn get_collection_file_links(collection_hash: &ActionHash) -> ExternResult<Vec<Link>> {
+    get_links(
+        LinkQuery::try_new(collection_hash.clone(), LinkTypes::CollectionToFiles)?,
+        GetStrategy::Local,
+    )
+}
+
+fn collections_from_links(links: Vec<Link>) -> ExternResult<Vec<CollectionOutput>> {
+    let mut collections = Vec::new();
+    for link in links {
+        if let Some(collection_hash) = link.target.into_action_hash() {
+            if let Some(collection) = get_collection(collection_hash)? {
+                collections.push(collection);
+            }
+        }
+    }
+    collections.sort_by_key(|output| output.collection.name.to_lowercase());
+    Ok(collections)
+}
+
 use sha2::Digest;
+use std::collections::{HashMap, HashSet};
@@ -0,0 +1,538 @@
+//! Governance Coordinator Zome
+//!
+//! Binding neighborhood polls, as opposed to casual feed chatter. Verified
+//! members propose polls and vote once each; after a poll closes, one trusted
+//! anchor counts the votes and asks a second anchor to count them too. When
+//! both tallies agree, both signatures go into a PollResult, and the result
+//! is listed by `get_decisions`.
+
+use governance_integrity::*;
+use hdk::prelude::*;
+use signal_envelope::SignalEnvelope;
+use std::collections::{BTreeSet, HashSet};
+
+/// Signal types for real-time updates, emitted inside a SignalEnvelope
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "event", content = "payload")]
+pub enum Signal {
+    NewPoll { poll_hash: ActionHash, poll: Poll },
+    DecisionCertified { poll_hash: ActionHash, result_hash: ActionHash },
+}
+
+/// Zome name clients see in the signal envelope
+const SIGNAL_ZOME: &str = "governance";
+
+fn emit_zome_signal(signal: Signal) -> ExternResult<()> {
+    emit_signal(SignalEnvelope::new(SIGNAL_ZOME, signal))
+}
+
+/// Input for proposing a poll
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreatePollInput {
+    pub title: String,
+    pub description: String,
+    pub options: Vec<String>,
+    pub closes_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PollOutput {
+    pub poll: Poll,
+    pub action_hash: ActionHash,
+    pub proposer: AgentPubKey,
+}
+
+/// A poll with its running tally
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PollSummary {
+    pub poll: PollOutput,
+    /// Votes per option seen so far; unofficial until certified
+    pub tallies: Vec<u32>,
+    /// The option the caller voted for, if any
+    pub my_vote: Option<u32>,
+    pub closed: bool,
+    pub result: Option<ActionHash>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CastVoteInput {
+    pub poll_hash: ActionHash,
+    pub option: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VoteOutput {
+    pub vote: Vote,
+    pub action_hash: ActionHash,
+    pub voter: AgentPubKey,
+}
+
+/// Input for certifying a closed poll's result
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CertifyPollInput {
+    pub poll_hash: ActionHash,
+    /// The second anchor asked to count and sign
+    pub cosigner: AgentPubKey,
+}
+
+/// A tally sent to the cosigning anchor to check and sign
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CosignInput {
+    pub poll_hash: ActionHash,
+    pub tallies: Vec<u32>,
+    pub votes: Vec<ActionHash>,
+}
+
+/// A binding neighborhood decision
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Decision {
+    pub poll: PollOutput,
+    pub result: PollResult,
+    pub result_hash: ActionHash,
+    /// The options with the most votes; more than one on a tie
+    pub winning_options: Vec<String>,
+}
+
+const ALL_POLLS_PATH: &str = "all_polls";
+const DECISIONS_PATH: &str = "neighborhood_decisions";
+
+/// Let the certifying anchor ask another anchor to cosign a tally
+#[hdk_extern]
+pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    let mut functions = BTreeSet::new();
+    functions.insert((zome_info()?.name, FunctionName::from("cosign_poll_result")));
+    create_cap_grant(CapGrantEntry {
+        tag: "governance".to_string(),
+        access: CapAccess::Unrestricted,
+        functions: GrantedFunctions::Listed(functions),
+    })?;
+    Ok(InitCallbackResult::Pass)
+}
+
+// ============================================================================
+// POLLS
+// ============================================================================
+
+/// Propose a binding poll (verified members only)
+#[hdk_extern]
+pub fn create_poll(input: CreatePollInput) -> ExternResult<PollOutput> {
+    let proposer = agent_info()?.agent_initial_pubkey;
+
+    let poll = Poll {
+        title: input.title,
+        description: input.description,
+        options: input.options,
+        created_at: sys_time()?,
+        closes_at: input.closes_at,
+    };
+
+    ensure_membership_proof()?;
+    let action_hash = create_entry(EntryTypes::Poll(poll.clone()))?;
+    create_link(path_hash(ALL_POLLS_PATH)?, action_hash.clone(), LinkTypes::AllPolls, ())?;
+
+    emit_zome_signal(Signal::NewPoll {
+        poll_hash: action_hash.clone(),
+        poll: poll.clone(),
+    })?;
+
+    Ok(PollOutput {
+        poll,
+        action_hash,
+        proposer,
+    })
+}
+
+/// Get a single poll
+#[hdk_extern]
+pub fn get_poll(poll_hash: ActionHash) -> ExternResult<Option<PollOutput>> {
+    let Some(record) = get(poll_hash.clone(), GetOptions::default())? else {
+        return Ok(None);
+    };
+    let Some(poll) = record
+        .entry()
+        .to_app_option::<Poll>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    else {
+        return Ok(None);
+    };
+
+    Ok(Some(PollOutput {
+        poll,
+        action_hash: poll_hash,
+        proposer: record.action().author().clone(),
+    }))
+}
+
+/// Get every poll with its running tally, newest first
+#[hdk_extern]
+pub fn get_polls(_: ()) -> ExternResult<Vec<PollSummary>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+    let links = get_links(
+        LinkQuery::try_new(path_hash(ALL_POLLS_PATH)?, LinkTypes::AllPolls)?,
+        GetStrategy::Local,
+    )?;
+
+    let mut polls = Vec::new();
+
+    for link in links {
+        let Some(poll_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(poll) = get_poll(poll_hash.clone())? else {
+            continue;
+        };
+
+        let votes = counted_votes(&poll_hash, &poll.poll, GetStrategy::Local)?;
+        let my_vote = votes.iter().find(|vote| vote.voter == me).map(|vote| vote.vote.option);
+        let result = poll_results(&poll_hash)?.into_iter().next().map(|(result_hash, _)| result_hash);
+
+        polls.push(PollSummary {
+            tallies: tally(&poll.poll, &votes),
+            my_vote,
+            closed: now >= poll.poll.closes_at,
+            result,
+            poll,
+        });
+    }
+
+    polls.sort_by(|a, b| b.poll.poll.created_at.cmp(&a.poll.poll.created_at));
+
+    Ok(polls)
+}
+
+// ============================================================================
+// VOTING
+// ============================================================================
+
+/// Vote in an open poll (verified members only, once per poll)
+#[hdk_extern]
+pub fn cast_vote(input: CastVoteInput) -> ExternResult<VoteOutput> {
+    let voter = agent_info()?.agent_initial_pubkey;
+
+    let Some(poll) = get_poll(input.poll_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Poll not found".to_string())));
+    };
+    if sys_time()? >= poll.poll.closes_at {
+        return Err(wasm_error!(WasmErrorInner::Guest("This poll has closed".to_string())));
+    }
+    if input.option as usize >= poll.poll.options.len() {
+        return Err(wasm_error!(WasmErrorInner::Guest("No such option".to_string())));
+    }
+
+    let already_voted = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::Vote.try_into()?)
+            .include_entries(true),
+    )?
+    .iter()
+    .filter_map(|record| record.entry().to_app_option::<Vote>().ok().flatten())
+    .any(|vote| vote.poll == input.poll_hash);
+    if already_voted {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "You have already voted in this poll".to_string()
+        )));
+    }
+
+    let vote = Vote {
+        poll: input.poll_hash.clone(),
+        option: input.option,
+    };
+
+    ensure_membership_proof()?;
+    let action_hash = create_entry(EntryTypes::Vote(vote.clone()))?;
+    create_link(input.poll_hash, action_hash.clone(), LinkTypes::PollToVotes, ())?;
+
+    Ok(VoteOutput {
+        vote,
+        action_hash,
+        voter,
+    })
+}
+
+// ============================================================================
+// CERTIFIED RESULTS
+// ============================================================================
+
+/// Count a closed poll and certify the result with a second anchor
+///
+/// The caller and `cosigner` must both be trusted anchors. The cosigner
+/// counts the votes independently and only signs if its tally matches.
+#[hdk_extern]
+pub fn certify_poll_result(input: CertifyPollInput) -> ExternResult<Decision> {
+    let me = agent_info()?.agent_initial_pubkey;
+    if input.cosigner == me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "A second anchor must cosign the result".to_string()
+        )));
+    }
+
+    let cosign = certify_tally(&input.poll_hash)?;
+    let my_certification = sign_tally(&cosign)?;
+
+    let response = call_remote(
+        input.cosigner.clone(),
+        ZomeName::from("governance"),
+        FunctionName::from("cosign_poll_result"),
+        None,
+        cosign.clone(),
+    )?;
+    let cosigner_certification: AnchorCertification = match response {
+        ZomeCallResponse::Ok(result) => result.decode().map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!("Failed to decode cosignature: {:?}", e)))
+        })?,
+        other => {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "The cosigning anchor did not sign: {:?}",
+                other
+            ))));
+        }
+    };
+
+    let result = PollResult {
+        poll: cosign.poll_hash.clone(),
+        tallies: cosign.tallies,
+        votes: cosign.votes,
+        certifications: vec![my_certification, cosigner_certification],
+        certified_at: sys_time()?,
+    };
+
+    let result_hash = create_entry(EntryTypes::PollResult(result.clone()))?;
+    create_link(cosign.poll_hash.clone(), result_hash.clone(), LinkTypes::PollToResults, ())?;
+    create_link(path_hash(DECISIONS_PATH)?, result_hash.clone(), LinkTypes::AllDecisions, ())?;
+
+    emit_zome_signal(Signal::DecisionCertified {
+        poll_hash: cosign.poll_hash.clone(),
+        result_hash: result_hash.clone(),
+    })?;
+
+    decision(result_hash, result)?.ok_or(wasm_error!(WasmErrorInner::Guest("Poll not found".into())))
+}
+
+/// Sign another anchor's tally after counting the votes ourselves
+///
+/// Called remotely by `certify_poll_result`. Fails unless we are a trusted
+/// anchor and our own count matches the one proposed.
+#[hdk_extern]
+pub fn cosign_poll_result(input: CosignInput) -> ExternResult<AnchorCertification> {
+    let ours = certify_tally(&input.poll_hash)?;
+    if ours.tallies != input.tallies || ours.votes != input.votes {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Our count of the votes doesn't match the proposed tally".to_string()
+        )));
+    }
+    sign_tally(&ours)
+}
+
+/// Every certified neighborhood decision, newest first
+#[hdk_extern]
+pub fn get_decisions(_: ()) -> ExternResult<Vec<Decision>> {
+    let links = get_links(
+        LinkQuery::try_new(path_hash(DECISIONS_PATH)?, LinkTypes::AllDecisions)?,
+        GetStrategy::Local,
+    )?;
+
+    let mut decisions = Vec::new();
+    let mut polls_seen = HashSet::new();
+
+    for link in links {
+        let Some(result_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(result_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let Some(result) = record.entry().to_app_option::<PollResult>().ok().flatten() else {
+            continue;
+        };
+        if let Some(decision) = decision(result_hash, result)? {
+            decisions.push(decision);
+        }
+    }
+
+    // A poll certified twice by different anchor pairs counts once, by its first result
+    decisions.sort_by(|a, b| a.result.certified_at.cmp(&b.result.certified_at));
+    decisions.retain(|decision| polls_seen.insert(decision.poll.action_hash.clone()));
+    decisions.reverse();
+
+    Ok(decisions)
+}
+
+// ============================================================================
+// HELPER FUNCTIONS
+// ============================================================================
+
+/// A vote that counts: the voter's first, cast before the poll closed
+struct CountedVote {
+    action_hash: ActionHash,
+    vote: Vote,
+    voter: AgentPubKey,
+}
+
+fn counted_votes(poll_hash: &ActionHash, poll: &Poll, strategy: GetStrategy) -> ExternResult<Vec<CountedVote>> {
+    let links = get_links(LinkQuery::try_new(poll_hash.clone(), LinkTypes::PollToVotes)?, strategy)?;
+
+    let mut votes = Vec::new();
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let Some(vote) = record.entry().to_app_option::<Vote>().ok().flatten() else {
+            continue;
+        };
+        if vote.poll != *poll_hash
+            || vote.option as usize >= poll.options.len()
+            || record.action().timestamp() >= poll.closes_at
+        {
+            continue;
+        }
+        votes.push((record.action().timestamp(), CountedVote {
+            action_hash,
+            vote,
+            voter: record.action().author().clone(),
+        }));
+    }
+
+    votes.sort_by(|a, b| a.0.cmp(&b.0));
+    let mut voters = HashSet::new();
+    let mut counted: Vec<CountedVote> = votes
+        .into_iter()
+        .map(|(_, vote)| vote)
+        .filter(|vote| voters.insert(vote.voter.clone()))
+        .collect();
+    // A canonical order so both certifying anchors sign the same bytes
+    counted.sort_by(|a, b| a.action_hash.cmp(&b.action_hash));
+    Ok(counted)
+}
+
+fn tally(poll: &Poll, votes: &[CountedVote]) -> Vec<u32> {
+    let mut tallies = vec![0; poll.options.len()];
+    for vote in votes {
+        tallies[vote.vote.option as usize] += 1;
+    }
+    tallies
+}
+
+/// Count a closed poll from the network's view, as an anchor about to sign
+fn certify_tally(poll_hash: &ActionHash) -> ExternResult<CosignInput> {
+    let Some(poll) = get_poll(poll_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Poll not found".to_string())));
+    };
+    if sys_time()? < poll.poll.closes_at {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Results can only be certified after the poll closes".to_string()
+        )));
+    }
+
+    let votes = counted_votes(poll_hash, &poll.poll, GetStrategy::Network)?;
+    Ok(CosignInput {
+        poll_hash: poll_hash.clone(),
+        tallies: tally(&poll.poll, &votes),
+        votes: votes.into_iter().map(|vote| vote.action_hash).collect(),
+    })
+}
+
+fn sign_tally(tally: &CosignInput) -> ExternResult<AnchorCertification> {
+    let anchor = agent_info()?.agent_initial_pubkey;
+    let Some(anchor_record) = get_my_anchor_record()? else {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only trusted anchors can certify poll results".to_string()
+        )));
+    };
+
+    let signature = sign(anchor.clone(), result_payload(&tally.poll_hash, &tally.tallies, &tally.votes))?;
+    Ok(AnchorCertification {
+        anchor,
+        anchor_record,
+        signature,
+    })
+}
+
+fn poll_results(poll_hash: &ActionHash) -> ExternResult<Vec<(ActionHash, PollResult)>> {
+    let links = get_links(
+        LinkQuery::try_new(poll_hash.clone(), LinkTypes::PollToResults)?,
+        GetStrategy::Local,
+    )?;
+
+    let mut results = Vec::new();
+    for link in links {
+        let Some(result_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(result_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        if let Some(result) = record.entry().to_app_option::<PollResult>().ok().flatten() {
+            results.push((result_hash, result));
+        }
+    }
+    results.sort_by(|a, b| a.1.certified_at.cmp(&b.1.certified_at));
+    Ok(results)
+}
+
+fn decision(result_hash: ActionHash, result: PollResult) -> ExternResult<Option<Decision>> {
+    let Some(poll) = get_poll(result.poll.clone())? else {
+        return Ok(None);
+    };
+
+    let most = result.tallies.iter().copied().max().unwrap_or(0);
+    let winning_options = if most == 0 {
+        Vec::new()
+    } else {
+        poll.poll
+            .options
+            .iter()
+            .zip(&result.tallies)
+            .filter(|(_, count)| **count == most)
+            .map(|(option, _)| option.clone())
+            .collect()
+    };
+
+    Ok(Some(Decision {
+        poll,
+        result,
+        result_hash,
+        winning_options,
+    }))
+}
+
+fn path_hash(path: &str) -> ExternResult<EntryHash> {
+    Path::from(path).path_entry_hash()
+}
+
+/// Ask the vouch zome for the caller's TrustedAnchor record
+fn get_my_anchor_record() -> ExternResult<Option<ActionHash>> {
+    call_vouch("get_my_anchor_record")
+}
+
+/// Have the vouch zome put a MembershipProof on our chain if we don't have
+/// one yet; integrity rejects polls and votes from authors without one
+fn ensure_membership_proof() -> ExternResult<()> {
+    call_vouch::<Option<ActionHash>>("ensure_membership_proof").map(|_| ())
+}
+
+fn call_vouch<O>(fn_name: &str) -> ExternResult<O>
+where
+    O: serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("vouch"),
+        FunctionName::from(fn_name),
+        None,
+        (),
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(result) => result.decode().map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!("Failed to decode vouch response: {:?}", e)))
+        }),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "vouch call failed: {:?}",
+            other
+        )))),
+    }
+}
@@ -1,5 +1,38 @@
 use hdk::prelude::*;
 use helpinghands_integrity::*;
+use idempotency::{find_previous_create, idempotency_tag};
+use signal_envelope::SignalEnvelope;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Signal types for real-time updates, emitted inside a SignalEnvelope
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "event", content = "payload")]
+pub enum Signal {
+    /// Sent by a request's author to recent commenters when they raise its urgency
+    RequestEscalated { request_hash: ActionHash, title: String, urgency: Urgency },
+    /// Sent by a request's author to the helper whose offer they accepted
+    OfferAccepted { request_hash: ActionHash, offer_hash: ActionHash, scheduled_for: Option<Timestamp> },
+    /// Sent by a request's author to the other neighbors who offered
+    OfferDeclined { request_hash: ActionHash },
+}
+
+/// Zome name clients see in the signal envelope
+const SIGNAL_ZOME: &str = "helpinghands";
+
+/// How many of the latest comments' authors hear about an escalation
+const RECENT_COMMENTS_NOTIFIED: usize = 10;
+
+/// How many available helpers also hear when a request becomes an emergency
+const EMERGENCY_HELPERS_NOTIFIED: usize = 20;
+
+/// How many helpers `get_suggested_helpers` returns
+const MAX_SUGGESTED_HELPERS: usize = 10;
+
+/// How many categories `get_aid_stats` ranks
+const TOP_CATEGORIES: usize = 5;
+
+/// Anchor path for the directory of helpers who have set their availability
+const AVAILABLE_HELPERS_PATH: &str = "available_helpers";
 
 /// ───────────────────────────────────────────────────────────────────────────
 /// ANCHOR HELPERS
@@ -15,6 +48,7 @@ fn all_requests_anchor() -> ExternResult<EntryHash> {
         author: AgentPubKey::from_raw_36(vec![0u8; 36]),
         created_at: Timestamp::from_micros(0),
         is_fulfilled: false,
+        place: None,
     };
     hash_entry(&anchor)
 }
@@ -29,14 +63,45 @@ pub struct CreateRequestInput {
     pub category: RequestCategory,
     pub urgency: Urgency,
     pub description: String,
+    #[serde(default)]
+    pub place: Option<Location>,
+    /// Client-chosen key; resubmitting with the same key returns the original
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct RequestOutput {
+    /// `request.urgency` is the current urgency, including escalations
     pub request: Request,
     pub action_hash: ActionHash,
     pub entry_hash: EntryHash,
     pub comment_count: usize,
+    /// When the request was last escalated, which also bumps it up the list
+    pub bumped_at: Option<Timestamp>,
+    pub status: RequestStatus,
+    /// The offer the author accepted, with the agreed time
+    pub acceptance: Option<OfferAcceptance>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EscalateRequestInput {
+    pub action_hash: ActionHash,
+    pub new_urgency: Urgency,
+    pub note: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AcceptOfferInput {
+    /// The comment the offer was made in
+    pub offer_hash: ActionHash,
+    pub scheduled_for: Option<Timestamp>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct EscalationOutput {
+    pub escalation: Escalation,
+    pub action_hash: ActionHash,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -53,6 +118,122 @@ pub struct CommentOutput {
     pub entry_hash: EntryHash,
 }
 
+/// A ready-made request for a common need
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RequestTemplate {
+    pub id: String,
+    /// What the template is shown as, e.g. "Airport ride"
+    pub name: String,
+    pub title: String,
+    pub category: RequestCategory,
+    pub urgency: Urgency,
+    /// Opening line of the description
+    pub description: String,
+    /// Questions to ask; answers become lines of the description
+    pub prompts: Vec<TemplatePrompt>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct TemplatePrompt {
+    pub key: String,
+    pub label: String,
+}
+
+/// Changes to a template; anything left out keeps the template's value
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct TemplateOverrides {
+    #[serde(default)]
+    pub title: Option<String>,
+    #[serde(default)]
+    pub urgency: Option<Urgency>,
+    /// Answers to the template's prompts, by prompt key
+    #[serde(default)]
+    pub answers: BTreeMap<String, String>,
+    /// Anything else the helper should know, added after the answers
+    #[serde(default)]
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub place: Option<Location>,
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreateFromTemplateInput {
+    pub template_id: String,
+    #[serde(default)]
+    pub overrides: TemplateOverrides,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SuggestedHelpersInput {
+    pub request_hash: ActionHash,
+    /// When help is needed; defaults to the accepted offer's time, then now
+    #[serde(default)]
+    pub at: Option<Timestamp>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SuggestedHelper {
+    pub helper: AgentPubKey,
+    /// Their availability covers the requested time
+    pub available: bool,
+    /// Whether they have offered to help with this request
+    pub offered: bool,
+}
+
+/// A reporting period; `to` is exclusive
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AidStatsWindow {
+    pub from: Timestamp,
+    pub to: Timestamp,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CategoryCount {
+    /// The category's variant name, e.g. "PetCare"; every Other counts together
+    pub category: String,
+    pub count: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AidStats {
+    pub window: AidStatsWindow,
+    /// Requests posted during the window
+    pub requests_created: usize,
+    /// Requests marked fulfilled during the window, whenever they were posted
+    pub requests_fulfilled: usize,
+    /// Median time from posting to fulfillment of the requests fulfilled
+    /// during the window; None when there were none
+    pub median_secs_to_fulfillment: Option<i64>,
+    /// Categories of the requests posted during the window, most common first
+    pub top_categories: Vec<CategoryCount>,
+}
+
+/// Mirror of the search zome's IndexContentInput
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct SearchIndexInput {
+    kind: SearchKind,
+    target: ActionHash,
+    title: String,
+    text: String,
+}
+
+/// Mirror of the search zome's LocationIndexInput
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LocationIndexInput {
+    kind: SearchKind,
+    target: ActionHash,
+    title: String,
+    geohash: String,
+}
+
+/// The search zome's ContentKind variant for help requests
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum SearchKind {
+    Request,
+}
+
 /// ───────────────────────────────────────────────────────────────────────────
 /// REQUEST FUNCTIONS
 /// ───────────────────────────────────────────────────────────────────────────
@@ -61,6 +242,16 @@ pub struct CommentOutput {
 #[hdk_extern]
 pub fn create_request(input: CreateRequestInput) -> ExternResult<RequestOutput> {
     let agent = agent_info()?.agent_initial_pubkey;
+
+    if let Some(original) = find_previous_create(
+        input.idempotency_key.as_deref(),
+        agent.clone(),
+        LinkTypes::AgentToRequests,
+    )? {
+        return get_request(original)?
+            .ok_or(wasm_error!(WasmErrorInner::Guest("Original request not found".into())));
+    }
+
     let now = sys_time()?;
 
     let request = Request {
@@ -71,6 +262,7 @@ pub fn create_request(input: CreateRequestInput) -> ExternResult<RequestOutput>
         author: agent.clone(),
         created_at: now,
         is_fulfilled: false,
+        place: input.place,
     };
 
     let action_hash = create_entry(EntryTypes::Request(request.clone()))?;
@@ -85,19 +277,25 @@ pub fn create_request(input: CreateRequestInput) -> ExternResult<RequestOutput>
         (),
     )?;
 
-    // Link from agent to their request
+    // Link from agent to their request, tagged with the idempotency key
     create_link(
         agent,
         action_hash.clone(),
         LinkTypes::AgentToRequests,
-        (),
+        idempotency_tag(input.idempotency_key.as_deref())?,
     )?;
 
+    call_search("index_content", action_hash.clone(), &request.title, &request.description)?;
+    index_place(action_hash.clone(), &request.title, &request.place)?;
+
     Ok(RequestOutput {
         request,
         action_hash,
         entry_hash,
         comment_count: 0,
+        bumped_at: None,
+        status: RequestStatus::Open,
+        acceptance: None,
     })
 }
 
@@ -117,26 +315,13 @@ pub fn get_all_requests(_: ()) -> ExternResult<Vec<RequestOutput>> {
             wasm_error!(WasmErrorInner::Guest("Invalid action hash".to_string()))
         })?;
         
-        if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
-            if let Some(request) = record
-                .entry()
-                .to_app_option::<Request>()
-                .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
-            {
-                let entry_hash = hash_entry(&request)?;
-                let comment_count = get_comment_count(action_hash.clone())?;
-                
-                requests.push(RequestOutput {
-                    request,
-                    action_hash,
-                    entry_hash,
-                    comment_count,
-                });
-            }
+        if let Some(request_output) = get_request(action_hash)? {
+            requests.push(request_output);
         }
     }
     
-    // Sort by urgency (Emergency first) then by created_at (newest first)
+    // Sort by urgency (Emergency first) then by created_at or the latest
+    // escalation (newest first)
     requests.sort_by(|a, b| {
         let urgency_order = |u: &Urgency| match u {
             Urgency::Emergency => 0,
@@ -145,7 +330,11 @@ pub fn get_all_requests(_: ()) -> ExternResult<Vec<RequestOutput>> {
         };
         
         match urgency_order(&a.request.urgency).cmp(&urgency_order(&b.request.urgency)) {
-            std::cmp::Ordering::Equal => b.request.created_at.cmp(&a.request.created_at),
+            std::cmp::Ordering::Equal => {
+                let a_active = a.bumped_at.unwrap_or(a.request.created_at);
+                let b_active = b.bumped_at.unwrap_or(b.request.created_at);
+                b_active.cmp(&a_active)
+            }
             other => other,
         }
     });
@@ -170,32 +359,18 @@ pub fn get_my_requests(_: ()) -> ExternResult<Vec<RequestOutput>> {
             wasm_error!(WasmErrorInner::Guest("Invalid action hash".to_string()))
         })?;
         
-        if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
-            if let Some(request) = record
-                .entry()
-                .to_app_option::<Request>()
-                .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
-            {
-                let entry_hash = hash_entry(&request)?;
-                let comment_count = get_comment_count(action_hash.clone())?;
-                
-                requests.push(RequestOutput {
-                    request,
-                    action_hash,
-                    entry_hash,
-                    comment_count,
-                });
-            }
+        if let Some(request_output) = get_request(action_hash)? {
+            requests.push(request_output);
         }
     }
     
     Ok(requests)
 }
 
-/// Get a single request by hash
+/// Get a single request by hash, as of its latest update
 #[hdk_extern]
 pub fn get_request(action_hash: ActionHash) -> ExternResult<Option<RequestOutput>> {
-    let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+    let Some(record) = latest_request_record(action_hash.clone())? else {
         return Ok(None);
     };
     
@@ -207,15 +382,7 @@ pub fn get_request(action_hash: ActionHash) -> ExternResult<Option<RequestOutput
         return Ok(None);
     };
     
-    let entry_hash = hash_entry(&request)?;
-    let comment_count = get_comment_count(action_hash.clone())?;
-    
-    Ok(Some(RequestOutput {
-        request,
-        action_hash,
-        entry_hash,
-        comment_count,
-    }))
+    Ok(Some(request_output(action_hash, request)?))
 }
 
 /// Mark a request as fulfilled
@@ -223,7 +390,7 @@ pub fn get_request(action_hash: ActionHash) -> ExternResult<Option<RequestOutput
 pub fn fulfill_request(action_hash: ActionHash) -> ExternResult<RequestOutput> {
     let agent = agent_info()?.agent_initial_pubkey;
     
-    let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+    let Some(record) = latest_request_record(action_hash.clone())? else {
         return Err(wasm_error!(WasmErrorInner::Guest("Request not found".to_string())));
     };
     
@@ -244,18 +411,370 @@ pub fn fulfill_request(action_hash: ActionHash) -> ExternResult<RequestOutput> {
     
     request.is_fulfilled = true;
     
-    let new_action_hash = update_entry(action_hash.clone(), &request)?;
+    let new_action_hash = update_entry(record.action_address().clone(), &request)?;
+    
+    // Seeing a request through counts toward the helper's badge
+    if let Some(acceptance) = get_acceptance(action_hash.clone())? {
+        let evidence = fulfilled_acceptances(&acceptance.helper, &action_hash)?;
+        grant_badge(acceptance.helper, evidence)?;
+    }
+    
+    let mut output = request_output(action_hash, request)?;
+    output.action_hash = new_action_hash;
+    
+    Ok(output)
+}
+
+/// Raise a request's urgency one level (author only)
+///
+/// The escalation is recorded alongside the request rather than editing it,
+/// so its history stays visible, and the request moves back up the list.
+/// Neighbors who commented recently get a signal; when it becomes an
+/// emergency, so do helpers whose availability covers the present moment.
+#[hdk_extern]
+pub fn escalate_request(input: EscalateRequestInput) -> ExternResult<RequestOutput> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    
+    let Some(current) = get_request(input.action_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Request not found".to_string())));
+    };
+    
+    if current.request.author != agent {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the request author can escalate it".to_string()
+        )));
+    }
+    if current.request.is_fulfilled {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Fulfilled requests cannot be escalated".to_string()
+        )));
+    }
+    if current.request.urgency.next() != Some(input.new_urgency.clone()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Urgency can only be raised one level at a time".to_string()
+        )));
+    }
+    
+    let previous = get_escalation_history(input.action_hash.clone())?
+        .pop()
+        .map(|escalation| escalation.action_hash);
+    
+    let escalation = Escalation {
+        request_hash: input.action_hash.clone(),
+        previous,
+        from: current.request.urgency.clone(),
+        to: input.new_urgency.clone(),
+        note: input.note,
+        created_at: sys_time()?,
+    };
+    
+    let escalation_hash = create_entry(EntryTypes::Escalation(escalation))?;
+    create_link(
+        input.action_hash.clone(),
+        escalation_hash,
+        LinkTypes::RequestToEscalations,
+        (),
+    )?;
+    
+    let mut recent_commenters: Vec<AgentPubKey> = get_comments_for_request(input.action_hash.clone())?
+        .into_iter()
+        .rev()
+        .take(RECENT_COMMENTS_NOTIFIED)
+        .map(|comment_output| comment_output.comment.author)
+        .filter(|author| *author != agent)
+        .collect();
+    if input.new_urgency == Urgency::Emergency {
+        let now = sys_time()?;
+        let available = get_availabilities()?
+            .into_iter()
+            .filter(|availability| availability.helper != agent && availability.covers(now))
+            .take(EMERGENCY_HELPERS_NOTIFIED)
+            .map(|availability| availability.helper);
+        recent_commenters.extend(available);
+    }
+    recent_commenters.sort();
+    recent_commenters.dedup();
+    
+    if !recent_commenters.is_empty() {
+        send_remote_signal(
+            Signal::RequestEscalated {
+                request_hash: input.action_hash.clone(),
+                title: current.request.title.clone(),
+                urgency: input.new_urgency,
+            },
+            recent_commenters,
+        )?;
+    }
+    
+    get_request(input.action_hash)?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Request not found".into())))
+}
+
+/// Every escalation of a request, oldest first
+#[hdk_extern]
+pub fn get_escalation_history(request_hash: ActionHash) -> ExternResult<Vec<EscalationOutput>> {
+    let links = get_links(
+        LinkQuery::try_new(request_hash, LinkTypes::RequestToEscalations)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut escalations = Vec::new();
+    
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
+            if let Some(escalation) = record
+                .entry()
+                .to_app_option::<Escalation>()
+                .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+            {
+                escalations.push(EscalationOutput {
+                    escalation,
+                    action_hash,
+                });
+            }
+        }
+    }
+    
+    escalations.sort_by(|a, b| a.escalation.created_at.cmp(&b.escalation.created_at));
+    
+    Ok(escalations)
+}
+
+/// Build a request's output with its current urgency and comment count
+fn request_output(action_hash: ActionHash, mut request: Request) -> ExternResult<RequestOutput> {
     let entry_hash = hash_entry(&request)?;
-    let comment_count = get_comment_count(action_hash)?;
+    let comment_count = get_comment_count(action_hash.clone())?;
+    
+    let latest_escalation = get_escalation_history(action_hash.clone())?.pop();
+    let bumped_at = latest_escalation.as_ref().map(|latest| latest.escalation.created_at);
+    if let Some(latest) = latest_escalation {
+        request.urgency = latest.escalation.to;
+    }
+    
+    let acceptance = get_acceptance(action_hash.clone())?;
+    let status = if request.is_fulfilled {
+        RequestStatus::Fulfilled
+    } else if acceptance.is_some() {
+        RequestStatus::InProgress
+    } else {
+        RequestStatus::Open
+    };
     
     Ok(RequestOutput {
         request,
-        action_hash: new_action_hash,
+        action_hash,
         entry_hash,
         comment_count,
+        bumped_at,
+        status,
+        acceptance,
     })
 }
 
+/// Follow a request's updates to its newest revision
+fn latest_request_record(action_hash: ActionHash) -> ExternResult<Option<Record>> {
+    let mut current = action_hash;
+    
+    loop {
+        let Some(Details::Record(details)) = get_details(current, GetOptions::default())? else {
+            return Ok(None);
+        };
+        let next = details
+            .updates
+            .iter()
+            .max_by_key(|update| update.action().timestamp())
+            .map(|update| update.action_address().clone());
+        match next {
+            Some(next) => current = next,
+            None => return Ok(Some(details.record)),
+        }
+    }
+}
+
+/// The accepted offer for a request; the earliest wins if there are several
+fn get_acceptance(request_hash: ActionHash) -> ExternResult<Option<OfferAcceptance>> {
+    let links = get_links(
+        LinkQuery::try_new(request_hash, LinkTypes::RequestToAcceptance)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut earliest: Option<OfferAcceptance> = None;
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash, GetOptions::default())? else {
+            continue;
+        };
+        if let Some(acceptance) = record
+            .entry()
+            .to_app_option::<OfferAcceptance>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        {
+            if earliest.as_ref().map_or(true, |current| acceptance.created_at < current.created_at) {
+                earliest = Some(acceptance);
+            }
+        }
+    }
+    
+    Ok(earliest)
+}
+
+/// A helper's accepted offers on requests that have been fulfilled;
+/// `fulfilling` is the request being marked fulfilled right now
+fn fulfilled_acceptances(helper: &AgentPubKey, fulfilling: &ActionHash) -> ExternResult<Vec<ActionHash>> {
+    let links = get_links(
+        LinkQuery::try_new(helper.clone(), LinkTypes::HelperToAcceptances)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut acceptances = Vec::new();
+    for link in links {
+        let Some(acceptance_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(acceptance_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let Some(acceptance) = record
+            .entry()
+            .to_app_option::<OfferAcceptance>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        else {
+            continue;
+        };
+        if acceptance.helper != *helper {
+            continue;
+        }
+        let fulfilled = acceptance.request_hash == *fulfilling
+            || get_request(acceptance.request_hash)?.is_some_and(|output| output.request.is_fulfilled);
+        if fulfilled {
+            acceptances.push(acceptance_hash);
+        }
+    }
+    
+    Ok(acceptances)
+}
+
+/// ───────────────────────────────────────────────────────────────────────────
+/// REQUEST TEMPLATES
+/// ───────────────────────────────────────────────────────────────────────────
+
+/// The predefined templates, in the order to offer them
+#[hdk_extern]
+pub fn get_request_templates(_: ()) -> ExternResult<Vec<RequestTemplate>> {
+    Ok(request_templates())
+}
+
+/// Post a request from a template, filling in the prompts' answers
+///
+/// The description is the template's opening line followed by one
+/// "Label: answer" line per answered prompt and then any notes.
+#[hdk_extern]
+pub fn create_request_from_template(input: CreateFromTemplateInput) -> ExternResult<RequestOutput> {
+    let template = request_templates()
+        .into_iter()
+        .find(|template| template.id == input.template_id)
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+            "Unknown request template '{}'",
+            input.template_id
+        ))))?;
+    let overrides = input.overrides;
+
+    if let Some(key) = overrides
+        .answers
+        .keys()
+        .find(|key| !template.prompts.iter().any(|prompt| &prompt.key == *key))
+    {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Template '{}' has no prompt '{}'",
+            template.id, key
+        ))));
+    }
+
+    let mut description = template.description;
+    for prompt in &template.prompts {
+        let Some(answer) = overrides.answers.get(&prompt.key).map(|answer| answer.trim()) else {
+            continue;
+        };
+        if !answer.is_empty() {
+            description.push_str(&format!("\n{}: {}", prompt.label, answer));
+        }
+    }
+    if let Some(notes) = overrides.notes.as_deref().map(str::trim).filter(|notes| !notes.is_empty()) {
+        description.push_str(&format!("\n\n{}", notes));
+    }
+
+    create_request(CreateRequestInput {
+        title: overrides.title.unwrap_or(template.title),
+        category: template.category,
+        urgency: overrides.urgency.unwrap_or(template.urgency),
+        description,
+        place: overrides.place,
+        idempotency_key: overrides.idempotency_key,
+    })
+}
+
+fn request_templates() -> Vec<RequestTemplate> {
+    let prompts = |prompts: &[(&str, &str)]| {
+        prompts
+            .iter()
+            .map(|(key, label)| TemplatePrompt {
+                key: key.to_string(),
+                label: label.to_string(),
+            })
+            .collect()
+    };
+
+    vec![
+        RequestTemplate {
+            id: "airport_ride".to_string(),
+            name: "Airport ride".to_string(),
+            title: "Ride to the airport".to_string(),
+            category: RequestCategory::Transportation,
+            urgency: Urgency::Low,
+            description: "I need a ride to or from the airport.".to_string(),
+            prompts: prompts(&[
+                ("airport", "Airport"),
+                ("pickup_time", "Pickup time"),
+                ("flight", "Flight"),
+                ("passengers", "Passengers and bags"),
+            ]),
+        },
+        RequestTemplate {
+            id: "package_pickup".to_string(),
+            name: "Package pickup".to_string(),
+            title: "Pick up a package for me".to_string(),
+            category: RequestCategory::Other { description: "Package pickup".to_string() },
+            urgency: Urgency::Low,
+            description: "I need someone to pick up a package for me.".to_string(),
+            prompts: prompts(&[
+                ("pickup_from", "Pick up from"),
+                ("deliver_to", "Deliver to"),
+                ("when", "When"),
+                ("size", "Size and weight"),
+            ]),
+        },
+        RequestTemplate {
+            id: "dog_walk".to_string(),
+            name: "Dog walk".to_string(),
+            title: "Walk my dog".to_string(),
+            category: RequestCategory::PetCare,
+            urgency: Urgency::Low,
+            description: "I need someone to walk my dog.".to_string(),
+            prompts: prompts(&[
+                ("dog", "Dog's name and size"),
+                ("when", "When"),
+                ("duration", "How long"),
+                ("temperament", "Leash and temperament"),
+            ]),
+        },
+    ]
+}
+
 /// ───────────────────────────────────────────────────────────────────────────
 /// COMMENT FUNCTIONS
 /// ───────────────────────────────────────────────────────────────────────────
@@ -292,6 +811,97 @@ pub fn create_comment(input: CreateCommentInput) -> ExternResult<CommentOutput>
     })
 }
 
+/// Accept one of the offers on my request
+///
+/// Moves the request to InProgress with the agreed time. The chosen helper
+/// gets an `OfferAccepted` signal and everyone else who offered gets
+/// `OfferDeclined`, so nobody is left waiting.
+#[hdk_extern]
+pub fn accept_offer(input: AcceptOfferInput) -> ExternResult<RequestOutput> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    
+    let Some(record) = get(input.offer_hash.clone(), GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Offer not found".to_string())));
+    };
+    let Some(offer) = record
+        .entry()
+        .to_app_option::<Comment>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Invalid offer".to_string())));
+    };
+    if !offer.is_offer {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "This comment is not an offer".to_string()
+        )));
+    }
+    
+    let Some(current) = get_request(offer.request_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Request not found".to_string())));
+    };
+    if current.request.author != agent {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the request author can accept an offer".to_string()
+        )));
+    }
+    if current.status != RequestStatus::Open {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "This request has already been taken on".to_string()
+        )));
+    }
+    
+    let acceptance = OfferAcceptance {
+        request_hash: offer.request_hash.clone(),
+        offer_hash: input.offer_hash.clone(),
+        helper: offer.author.clone(),
+        scheduled_for: input.scheduled_for,
+        created_at: sys_time()?,
+    };
+    let acceptance_hash = create_entry(EntryTypes::OfferAcceptance(acceptance))?;
+    create_link(
+        offer.request_hash.clone(),
+        acceptance_hash.clone(),
+        LinkTypes::RequestToAcceptance,
+        (),
+    )?;
+    create_link(
+        offer.author.clone(),
+        acceptance_hash,
+        LinkTypes::HelperToAcceptances,
+        (),
+    )?;
+    
+    send_remote_signal(
+        Signal::OfferAccepted {
+            request_hash: offer.request_hash.clone(),
+            offer_hash: input.offer_hash.clone(),
+            scheduled_for: input.scheduled_for,
+        },
+        vec![offer.author.clone()],
+    )?;
+    
+    let mut declined: Vec<AgentPubKey> = get_comments_for_request(offer.request_hash.clone())?
+        .into_iter()
+        .filter(|comment_output| comment_output.comment.is_offer)
+        .map(|comment_output| comment_output.comment.author)
+        .filter(|author| *author != offer.author && *author != agent)
+        .collect();
+    declined.sort();
+    declined.dedup();
+    
+    if !declined.is_empty() {
+        send_remote_signal(
+            Signal::OfferDeclined {
+                request_hash: offer.request_hash.clone(),
+            },
+            declined,
+        )?;
+    }
+    
+    get_request(offer.request_hash)?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Request not found".into())))
+}
+
 /// Get comments for a request
 #[hdk_extern]
 pub fn get_comments_for_request(request_hash: ActionHash) -> ExternResult<Vec<CommentOutput>> {
@@ -338,6 +948,271 @@ fn get_comment_count(request_hash: ActionHash) -> ExternResult<usize> {
     Ok(links.len())
 }
 
+/// ───────────────────────────────────────────────────────────────────────────
+/// HELPER AVAILABILITY
+/// ───────────────────────────────────────────────────────────────────────────
+
+/// Replace the caller's weekly availability; an empty list means not currently available
+#[hdk_extern]
+pub fn set_my_availability(mut windows: Vec<WeeklyWindow>) -> ExternResult<HelperAvailability> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    windows.sort();
+    windows.dedup();
+    
+    let listed = get_availability(&agent)?.is_some();
+    let availability = HelperAvailability {
+        helper: agent.clone(),
+        windows,
+        updated_at: sys_time()?,
+    };
+    let action_hash = create_entry(EntryTypes::HelperAvailability(availability.clone()))?;
+    create_link(agent.clone(), action_hash, LinkTypes::HelperToAvailability, ())?;
+    if !listed {
+        create_link(available_helpers_anchor()?, agent, LinkTypes::AvailableHelpers, ())?;
+    }
+    
+    Ok(availability)
+}
+
+/// The caller's current availability, if they have set any
+#[hdk_extern]
+pub fn get_my_availability(_: ()) -> ExternResult<Option<HelperAvailability>> {
+    get_availability(&agent_info()?.agent_initial_pubkey)
+}
+
+/// Neighbors to ask for help with a request, those free at the time first
+///
+/// Candidates are the neighbors who offered on the request and everyone who
+/// has set their availability. Helpers whose availability covers the time
+/// come first; among them, those who already offered lead.
+#[hdk_extern]
+pub fn get_suggested_helpers(input: SuggestedHelpersInput) -> ExternResult<Vec<SuggestedHelper>> {
+    let Some(current) = get_request(input.request_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Request not found".to_string())));
+    };
+    let at = match input.at.or(current.acceptance.and_then(|acceptance| acceptance.scheduled_for)) {
+        Some(at) => at,
+        None => sys_time()?,
+    };
+    
+    let offerers: BTreeSet<AgentPubKey> = get_comments_for_request(input.request_hash)?
+        .into_iter()
+        .filter(|comment_output| comment_output.comment.is_offer)
+        .map(|comment_output| comment_output.comment.author)
+        .collect();
+    let mut available = BTreeSet::new();
+    let mut candidates = offerers.clone();
+    for availability in get_availabilities()? {
+        if availability.covers(at) {
+            available.insert(availability.helper.clone());
+        }
+        candidates.insert(availability.helper);
+    }
+    candidates.remove(&current.request.author);
+    
+    let mut helpers: Vec<SuggestedHelper> = candidates
+        .into_iter()
+        .map(|helper| SuggestedHelper {
+            available: available.contains(&helper),
+            offered: offerers.contains(&helper),
+            helper,
+        })
+        .collect();
+    helpers.sort_by(|a, b| b.available.cmp(&a.available).then_with(|| b.offered.cmp(&a.offered)));
+    helpers.truncate(MAX_SUGGESTED_HELPERS);
+    
+    Ok(helpers)
+}
+
+/// A helper's latest availability
+fn get_availability(helper: &AgentPubKey) -> ExternResult<Option<HelperAvailability>> {
+    let links = get_links(
+        LinkQuery::try_new(helper.clone(), LinkTypes::HelperToAvailability)?,
+        GetStrategy::Local,
+    )?;
+    let Some(latest) = links.into_iter().max_by_key(|link| link.timestamp) else {
+        return Ok(None);
+    };
+    let Some(action_hash) = latest.target.into_action_hash() else {
+        return Ok(None);
+    };
+    let Some(record) = get(action_hash, GetOptions::default())? else {
+        return Ok(None);
+    };
+    
+    Ok(record
+        .entry()
+        .to_app_option::<HelperAvailability>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .filter(|availability| availability.helper == *helper))
+}
+
+/// The latest availability of every listed helper
+fn get_availabilities() -> ExternResult<Vec<HelperAvailability>> {
+    let links = get_links(
+        LinkQuery::try_new(available_helpers_anchor()?, LinkTypes::AvailableHelpers)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut availabilities = Vec::new();
+    for link in links {
+        let Some(helper) = link.target.into_agent_pub_key() else {
+            continue;
+        };
+        if let Some(availability) = get_availability(&helper)? {
+            availabilities.push(availability);
+        }
+    }
+    
+    Ok(availabilities)
+}
+
+fn available_helpers_anchor() -> ExternResult<EntryHash> {
+    Path::from(AVAILABLE_HELPERS_PATH).path_entry_hash()
+}
+
+/// ───────────────────────────────────────────────────────────────────────────
+/// AID STATISTICS
+/// ───────────────────────────────────────────────────────────────────────────
+
+/// Summarize mutual aid over a period for organizers' reports
+///
+/// Counted from every request on the all-requests anchor. A request's
+/// fulfillment time is when its author marked it fulfilled.
+#[hdk_extern]
+pub fn get_aid_stats(window: AidStatsWindow) -> ExternResult<AidStats> {
+    if window.from >= window.to {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "The stats window must start before it ends".to_string()
+        )));
+    }
+    let in_window = |at: Timestamp| window.from <= at && at < window.to;
+    
+    let links = get_links(
+        LinkQuery::try_new(all_requests_anchor()?, LinkTypes::AllRequests)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut requests_created = 0;
+    let mut categories: BTreeMap<&'static str, usize> = BTreeMap::new();
+    let mut secs_to_fulfillment = Vec::new();
+    
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = latest_request_record(action_hash)? else {
+            continue;
+        };
+        let Some(request) = record
+            .entry()
+            .to_app_option::<Request>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        else {
+            continue;
+        };
+        
+        if in_window(request.created_at) {
+            requests_created += 1;
+            *categories.entry(category_name(&request.category)).or_default() += 1;
+        }
+        // Marking a request fulfilled is the only update a request gets
+        let fulfilled_at = record.action().timestamp();
+        if request.is_fulfilled && matches!(record.action(), Action::Update(_)) && in_window(fulfilled_at) {
+            secs_to_fulfillment.push((fulfilled_at.as_micros() - request.created_at.as_micros()) / 1_000_000);
+        }
+    }
+    
+    secs_to_fulfillment.sort();
+    let middle = secs_to_fulfillment.len() / 2;
+    let median_secs_to_fulfillment = match secs_to_fulfillment.len() {
+        0 => None,
+        len if len % 2 == 0 => Some((secs_to_fulfillment[middle - 1] + secs_to_fulfillment[middle]) / 2),
+        _ => Some(secs_to_fulfillment[middle]),
+    };
+    
+    let mut top_categories: Vec<CategoryCount> = categories
+        .into_iter()
+        .map(|(category, count)| CategoryCount {
+            category: category.to_string(),
+            count,
+        })
+        .collect();
+    // Stable, so equal counts stay in name order
+    top_categories.sort_by(|a, b| b.count.cmp(&a.count));
+    top_categories.truncate(TOP_CATEGORIES);
+    
+    Ok(AidStats {
+        window,
+        requests_created,
+        requests_fulfilled: secs_to_fulfillment.len(),
+        median_secs_to_fulfillment,
+        top_categories,
+    })
+}
+
+fn category_name(category: &RequestCategory) -> &'static str {
+    match category {
+        RequestCategory::Grocery => "Grocery",
+        RequestCategory::Moving => "Moving",
+        RequestCategory::Childcare => "Childcare",
+        RequestCategory::Transportation => "Transportation",
+        RequestCategory::PetCare => "PetCare",
+        RequestCategory::Repairs => "Repairs",
+        RequestCategory::Medical => "Medical",
+        RequestCategory::Technology => "Technology",
+        RequestCategory::Companionship => "Companionship",
+        RequestCategory::Other { .. } => "Other",
+    }
+}
+
+/// ───────────────────────────────────────────────────────────────────────────
+/// SIGNALS
+/// ───────────────────────────────────────────────────────────────────────────
+
+/// Let request authors reach commenters and offerers with remote signals
+#[hdk_extern]
+pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    let mut functions = BTreeSet::new();
+    functions.insert((zome_info()?.name, FunctionName::from("recv_remote_signal")));
+    create_cap_grant(CapGrantEntry {
+        tag: "remote_signals".to_string(),
+        access: CapAccess::Unrestricted,
+        functions: GrantedFunctions::Listed(functions),
+    })?;
+    Ok(InitCallbackResult::Pass)
+}
+
+/// Forward signals from other neighbors to the UI
+///
+/// Every helpinghands signal is about a request, and is only passed on if it
+/// came from that request's author.
+#[hdk_extern]
+pub fn recv_remote_signal(signal: ExternIO) -> ExternResult<()> {
+    let signal: Signal = signal.decode().map_err(|e| {
+        wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to decode helpinghands signal: {:?}",
+            e
+        )))
+    })?;
+    
+    let provenance = call_info()?.provenance;
+    let request_hash = match &signal {
+        Signal::RequestEscalated { request_hash, .. } => request_hash,
+        Signal::OfferAccepted { request_hash, .. } => request_hash,
+        Signal::OfferDeclined { request_hash } => request_hash,
+    };
+    let Some(request_output) = get_request(request_hash.clone())? else {
+        return Ok(());
+    };
+    if request_output.request.author != provenance {
+        return Ok(());
+    }
+    
+    emit_signal(SignalEnvelope::new(SIGNAL_ZOME, signal))?;
+    Ok(())
+}
+
 /// ───────────────────────────────────────────────────────────────────────────
 /// UTILITY FUNCTIONS
 /// ───────────────────────────────────────────────────────────────────────────
@@ -347,3 +1222,147 @@ fn get_comment_count(request_hash: ActionHash) -> ExternResult<usize> {
 pub fn get_my_agent_key(_: ()) -> ExternResult<AgentPubKey> {
     Ok(agent_info()?.agent_initial_pubkey)
 }
+
+/// Add or remove a help request in the search zome's keyword index
+fn call_search(fn_name: &str, target: ActionHash, title: &str, text: &str) -> ExternResult<()> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("search"),
+        FunctionName::from(fn_name),
+        None,
+        SearchIndexInput {
+            kind: SearchKind::Request,
+            target,
+            title: title.to_string(),
+            text: text.to_string(),
+        },
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to update search index: {:?}",
+            other
+        )))),
+    }
+}
+
+/// Add a located help request to the search zome's geohash index, if it has a geohash
+fn index_place(target: ActionHash, title: &str, place: &Option<Location>) -> ExternResult<()> {
+    let Some(geohash) = place.as_ref().and_then(|place| place.geohash.clone()) else {
+        return Ok(());
+    };
+
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("search"),
+        FunctionName::from("index_location"),
+        None,
+        LocationIndexInput {
+            kind: SearchKind::Request,
+            target,
+            title: title.to_string(),
+            geohash,
+        },
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to update location index: {:?}",
+            other
+        )))),
+    }
+}
+
+/// Mirror of the profile zome's GrantBadgeInput
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct GrantBadgeInput {
+    agent: AgentPubKey,
+    kind: BadgeKind,
+    evidence: Vec<ActionHash>,
+}
+
+/// The profile zome's BadgeKind variant this zome grants
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum BadgeKind {
+    Helper,
+}
+
+/// Offer the profile zome a helper's accepted offers as badge evidence;
+/// it grants the badge once there are enough and ignores the call otherwise
+fn grant_badge(agent: AgentPubKey, evidence: Vec<ActionHash>) -> ExternResult<()> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("profile"),
+        FunctionName::from("grant_badge"),
+        None,
+        GrantBadgeInput {
+            agent,
+            kind: BadgeKind::Helper,
+            evidence,
+        },
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to grant badge: {:?}",
+            other
+        )))),
+    }
+}
+
+/// ───────────────────────────────────────────────────────────────────────────
+/// DATA EXPORT
+/// ───────────────────────────────────────────────────────────────────────────
+
+/// The calling agent's requests and the comments/offers they've left
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct HelpingHandsExport {
+    pub requests: Vec<RequestOutput>,
+    pub comments: Vec<CommentOutput>,
+}
+
+/// Export the caller's Helping Hands footprint
+#[hdk_extern]
+pub fn export_my_data(_: ()) -> ExternResult<HelpingHandsExport> {
+    let mut comments = Vec::new();
+    for (action_hash, comment) in my_chain_entries::<Comment>(UnitEntryTypes::Comment)? {
+        comments.push(CommentOutput {
+            entry_hash: hash_entry(&comment)?,
+            comment,
+            action_hash,
+        });
+    }
+
+    Ok(HelpingHandsExport {
+        requests: get_my_requests(())?,
+        comments,
+    })
+}
+
+/// Every entry of one type on my own source chain, oldest first
+fn my_chain_entries<T>(entry_type: UnitEntryTypes) -> ExternResult<Vec<(ActionHash, T)>>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(entry_type.try_into()?)
+            .include_entries(true),
+    )?;
+
+    let mut entries = Vec::new();
+    for record in records {
+        if let Some(entry) = record
+            .entry()
+            .to_app_option::<T>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        {
+            entries.push((record.action_address().clone(), entry));
+        }
+    }
+
+    Ok(entries)
+}
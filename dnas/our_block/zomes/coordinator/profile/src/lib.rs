@@ -5,6 +5,7 @@
 
 use hdk::prelude::*;
 use profile_integrity::*;
+use std::collections::BTreeSet;
 
 /// Input for creating or updating a profile
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -13,15 +14,43 @@ pub struct CreateProfileInput {
     pub bio: Option<String>,
     pub avatar_url: Option<String>,
     pub location_metadata: Option<String>,
+    #[serde(default)]
+    pub pronouns: Option<String>,
+    #[serde(default)]
+    pub display_name_variants: Vec<DisplayNameVariant>,
 }
 
 /// Profile with additional metadata for the frontend
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct ProfileOutput {
     pub profile: Profile,
+    /// The nickname, or its variant in the viewer's script when listed with a locale
+    pub display_name: String,
     pub action_hash: ActionHash,
     pub entry_hash: EntryHash,
     pub agent: AgentPubKey,
+    /// Membership status resolved from the vouch zome
+    pub membership_status: MembershipStatus,
+    /// Whether the agent is a trusted anchor
+    pub is_anchor: bool,
+    /// The agent's current status, if one is set and hasn't expired
+    pub status: Option<ProfileStatus>,
+}
+
+/// Membership status as reported by the vouch zome
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub enum MembershipStatus {
+    Pending,
+    Verified,
+    Anchor,
+}
+
+/// Mirror of the vouch zome's `MembershipSummary`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct MembershipSummary {
+    agent: AgentPubKey,
+    status: MembershipStatus,
+    is_anchor: bool,
 }
 
 /// Anchor path for listing all profiles
@@ -49,6 +78,10 @@ pub fn create_profile(input: CreateProfileInput) -> ExternResult<ProfileOutput>
         bio: input.bio,
         avatar_url: input.avatar_url,
         location_metadata: input.location_metadata,
+        created_at: sys_time()?,
+        pronouns: input.pronouns,
+        display_name_variants: input.display_name_variants,
+        deactivated_at: None,
     };
 
     let action_hash = create_entry(EntryTypes::Profile(profile.clone()))?;
@@ -66,18 +99,46 @@ pub fn create_profile(input: CreateProfileInput) -> ExternResult<ProfileOutput>
     let anchor_hash = anchor_hash()?;
     create_link(anchor_hash, entry_hash.clone(), LinkTypes::AllProfiles, ())?;
 
-    Ok(ProfileOutput {
+    let mut output = ProfileOutput {
+        display_name: profile.nickname.clone(),
         profile,
         action_hash,
         entry_hash,
         agent,
-    })
+        membership_status: MembershipStatus::Pending,
+        is_anchor: false,
+        status: None,
+    };
+    attach_membership(std::slice::from_mut(&mut output))?;
+    attach_status(std::slice::from_mut(&mut output))?;
+    record_onboarding_step(OnboardingStep::ProfileCreated)?;
+
+    Ok(output)
 }
 
 /// Updates the calling agent's profile
-/// Uses get_agent_activity to ensure we're working with the latest source chain state
 #[hdk_extern]
 pub fn update_profile(input: CreateProfileInput) -> ExternResult<ProfileOutput> {
+    let (original_action_hash, previous_profile) = latest_own_profile()?;
+
+    // Create updated profile with new fields, keeping the original creation time
+    let profile = Profile {
+        nickname: input.nickname,
+        bio: input.bio,
+        avatar_url: input.avatar_url,
+        location_metadata: input.location_metadata,
+        created_at: previous_profile.created_at,
+        pronouns: input.pronouns,
+        display_name_variants: input.display_name_variants,
+        deactivated_at: previous_profile.deactivated_at,
+    };
+
+    commit_profile_update(original_action_hash, profile)
+}
+
+/// The calling agent's latest profile action and entry
+/// Uses get_agent_activity to ensure we're working with the latest source chain state
+fn latest_own_profile() -> ExternResult<(ActionHash, Profile)> {
     let agent = agent_info()?.agent_initial_pubkey;
 
     // Use get_agent_activity to find the latest profile action
@@ -88,31 +149,27 @@ pub fn update_profile(input: CreateProfileInput) -> ExternResult<ProfileOutput>
     )?;
 
     // Find the latest profile entry in the agent's chain
-    let mut latest_profile_action: Option<ActionHash> = None;
+    let mut latest_profile: Option<(ActionHash, Profile)> = None;
     
     for activity_item in activity.valid_activity.iter().rev() {
         if let Some(record) = get(activity_item.0.clone(), GetOptions::default())? {
-            if let Some(_profile) = record
+            if let Some(profile) = record
                 .entry()
                 .to_app_option::<Profile>()
                 .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
             {
-                latest_profile_action = Some(activity_item.0.clone());
+                latest_profile = Some((activity_item.0.clone(), profile));
                 break;
             }
         }
     }
 
-    let original_action_hash = latest_profile_action
-        .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("No profile exists to update.".to_string())))?;
+    latest_profile.ok_or_else(|| wasm_error!(WasmErrorInner::Guest("No profile exists to update.".to_string())))
+}
 
-    // Create updated profile with new fields
-    let profile = Profile {
-        nickname: input.nickname,
-        bio: input.bio,
-        avatar_url: input.avatar_url,
-        location_metadata: input.location_metadata,
-    };
+/// Write an updated profile, repoint the agent's profile link, and signal the change
+fn commit_profile_update(original_action_hash: ActionHash, profile: Profile) -> ExternResult<ProfileOutput> {
+    let agent = agent_info()?.agent_initial_pubkey;
 
     let action_hash = update_entry(original_action_hash.clone(), &profile)?;
     let entry_hash = hash_entry(&profile)?;
@@ -140,12 +197,20 @@ pub fn update_profile(input: CreateProfileInput) -> ExternResult<ProfileOutput>
         action_hash: action_hash.clone(),
     })?;
 
-    Ok(ProfileOutput {
+    let mut output = ProfileOutput {
+        display_name: profile.nickname.clone(),
         profile,
         action_hash,
         entry_hash,
         agent,
-    })
+        membership_status: MembershipStatus::Pending,
+        is_anchor: false,
+        status: None,
+    };
+    attach_membership(std::slice::from_mut(&mut output))?;
+    attach_status(std::slice::from_mut(&mut output))?;
+
+    Ok(output)
 }
 
 /// Gets the profile for the calling agent
@@ -187,17 +252,39 @@ fn get_profile_for_agent(agent: AgentPubKey) -> ExternResult<Option<ProfileOutpu
         .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
         .ok_or_else(|| wasm_error!(WasmErrorInner::Guest("Profile entry not found".to_string())))?;
 
-    Ok(Some(ProfileOutput {
+    let mut output = ProfileOutput {
+        display_name: profile.nickname.clone(),
         profile,
         action_hash: record.action_address().clone(),
         entry_hash,
         agent,
-    }))
+        membership_status: MembershipStatus::Pending,
+        is_anchor: false,
+        status: None,
+    };
+    attach_membership(std::slice::from_mut(&mut output))?;
+    attach_status(std::slice::from_mut(&mut output))?;
+
+    Ok(Some(output))
+}
+
+/// Options for `get_all_profiles`
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ProfileListOptions {
+    /// BCP 47 tag of the viewer, e.g. `sv-SE` or `ja`
+    #[serde(default)]
+    pub locale: Option<String>,
 }
 
 /// Gets all profiles in the neighborhood
+///
+/// With a locale, each profile's `display_name` uses its variant in a script
+/// that locale reads (falling back to the nickname) and the list is sorted by
+/// that name under the locale's alphabet. Without one, nicknames are sorted
+/// with diacritics folded.
 #[hdk_extern]
-pub fn get_all_profiles(_: ()) -> ExternResult<Vec<ProfileOutput>> {
+pub fn get_all_profiles(options: Option<ProfileListOptions>) -> ExternResult<Vec<ProfileOutput>> {
+    let locale = options.unwrap_or_default().locale;
     let anchor_hash = anchor_hash()?;
     let links = get_links(
         LinkQuery::try_new(anchor_hash, LinkTypes::AllProfiles)?,
@@ -217,21 +304,244 @@ pub fn get_all_profiles(_: ()) -> ExternResult<Vec<ProfileOutput>> {
                 .to_app_option::<Profile>()
                 .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
             {
+                // Neighbors who moved away drop out of the directory
+                if profile.deactivated_at.is_some() {
+                    continue;
+                }
                 // Get the agent from the record's author
                 let agent = record.action().author().clone();
                 profiles.push(ProfileOutput {
+                    display_name: profile.nickname.clone(),
                     profile,
                     action_hash: record.action_address().clone(),
                     entry_hash,
                     agent,
+                    membership_status: MembershipStatus::Pending,
+                    is_anchor: false,
+                    status: None,
                 });
             }
         }
     }
 
+    // Resolve membership for every profile with a single vouch zome call
+    attach_membership(&mut profiles)?;
+    attach_status(&mut profiles)?;
+    sort_for_locale(&mut profiles, locale.as_deref());
+
     Ok(profiles)
 }
 
+// ============================================================================
+// Locale-aware Ordering
+// ============================================================================
+
+/// Scripts a reader of the given language is expected to read, most preferred first
+fn preferred_scripts(language: &str) -> &'static [&'static str] {
+    match language {
+        "ru" | "uk" | "be" | "bg" | "sr" | "mk" | "kk" => &["Cyrl"],
+        "el" => &["Grek"],
+        "ar" | "fa" | "ur" => &["Arab"],
+        "he" | "yi" => &["Hebr"],
+        "hi" | "mr" | "ne" => &["Deva"],
+        "th" => &["Thai"],
+        "ko" => &["Kore", "Hang"],
+        "ja" => &["Jpan", "Hira", "Kana", "Hani"],
+        "zh" => &["Hans", "Hant", "Hani"],
+        _ => &["Latn"],
+    }
+}
+
+/// Letters a language sorts after `z`, in its alphabetical order
+fn letters_after_z(language: &str) -> &'static [char] {
+    match language {
+        "sv" | "fi" => &['å', 'ä', 'ö'],
+        "da" | "nb" | "nn" | "no" => &['æ', 'ø', 'å'],
+        _ => &[],
+    }
+}
+
+/// Fold common Latin diacritics onto their base letter
+fn fold_diacritic(c: char) -> char {
+    match c {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' | 'ā' => 'a',
+        'ç' | 'ć' | 'č' => 'c',
+        'è' | 'é' | 'ê' | 'ë' | 'ē' | 'ę' | 'ě' => 'e',
+        'ì' | 'í' | 'î' | 'ï' | 'ī' => 'i',
+        'ñ' | 'ń' | 'ň' => 'n',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' | 'ō' => 'o',
+        'ù' | 'ú' | 'û' | 'ü' | 'ū' | 'ů' => 'u',
+        'ý' | 'ÿ' => 'y',
+        'ś' | 'š' => 's',
+        'ź' | 'ż' | 'ž' => 'z',
+        'ł' => 'l',
+        'ř' => 'r',
+        other => other,
+    }
+}
+
+/// Sort key for a name under a language's alphabet
+///
+/// Each character maps to a primary weight; the lowercased name breaks ties
+/// so names differing only in accents still sort consistently.
+fn collation_key(name: &str, language: &str) -> (Vec<u32>, String) {
+    let tail = letters_after_z(language);
+    let lower = name.to_lowercase();
+    let weights = lower
+        .chars()
+        .map(|c| {
+            if let Some(position) = tail.iter().position(|letter| *letter == c) {
+                // Straight after 'z'
+                return 'z' as u32 * 4 + 1 + position as u32;
+            }
+            if language == "es" && c == 'ñ' {
+                return 'n' as u32 * 4 + 1;
+            }
+            match c {
+                'æ' => 'a' as u32 * 4 + 2,
+                'ß' => 's' as u32 * 4 + 2,
+                other => fold_diacritic(other) as u32 * 4,
+            }
+        })
+        .collect();
+    (weights, lower)
+}
+
+/// Pick each profile's display name for the locale and sort by it
+fn sort_for_locale(profiles: &mut [ProfileOutput], locale: Option<&str>) {
+    let language = locale
+        .and_then(|locale| locale.split(['-', '_']).next())
+        .map(str::to_ascii_lowercase)
+        .unwrap_or_default();
+
+    if locale.is_some() {
+        let scripts = preferred_scripts(&language);
+        for output in profiles.iter_mut() {
+            let variant = scripts.iter().find_map(|script| {
+                output
+                    .profile
+                    .display_name_variants
+                    .iter()
+                    .find(|variant| variant.script == *script)
+            });
+            if let Some(variant) = variant {
+                output.display_name = variant.name.clone();
+            }
+        }
+    }
+
+    profiles.sort_by_cached_key(|output| collation_key(&output.display_name, &language));
+}
+
+/// Fill in membership status for the given profiles via one batched
+/// cross-zome call to the vouch zome
+fn attach_membership(profiles: &mut [ProfileOutput]) -> ExternResult<()> {
+    if profiles.is_empty() {
+        return Ok(());
+    }
+
+    let agents: Vec<AgentPubKey> = profiles.iter().map(|p| p.agent.clone()).collect();
+
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("vouch"),
+        FunctionName::from("get_membership_summaries"),
+        None,
+        agents,
+    )?;
+
+    let summaries: Vec<MembershipSummary> = match response {
+        ZomeCallResponse::Ok(result) => result.decode().map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!("Failed to decode membership summaries: {:?}", e)))
+        })?,
+        other => {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Failed to fetch membership status: {:?}",
+                other
+            ))));
+        }
+    };
+
+    for profile in profiles.iter_mut() {
+        if let Some(summary) = summaries.iter().find(|s| s.agent == profile.agent) {
+            profile.membership_status = summary.status.clone();
+            profile.is_anchor = summary.is_anchor;
+        }
+    }
+
+    Ok(())
+}
+
+/// Fill in each profile's current status
+fn attach_status(profiles: &mut [ProfileOutput]) -> ExternResult<()> {
+    for profile in profiles.iter_mut() {
+        profile.status = current_status(&profile.agent)?;
+    }
+    Ok(())
+}
+
+/// A single revision of an agent's profile
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProfileRevision {
+    pub profile: Profile,
+    pub action_hash: ActionHash,
+    /// When this revision was committed
+    pub revised_at: Timestamp,
+}
+
+/// Gets every revision of an agent's profile, newest first
+///
+/// Walks the update chain backwards from the current profile to the
+/// original create, so the first element is the current profile and the
+/// rest are prior revisions.
+#[hdk_extern]
+pub fn get_profile_history(agent: AgentPubKey) -> ExternResult<Vec<ProfileRevision>> {
+    let links = get_links(
+        LinkQuery::try_new(agent, LinkTypes::AgentToProfile)?,
+        GetStrategy::Local,
+    )?;
+
+    let Some(link) = links.into_iter().next() else {
+        return Ok(Vec::new());
+    };
+
+    let entry_hash = EntryHash::try_from(link.target).map_err(|_| {
+        wasm_error!(WasmErrorInner::Guest("Invalid entry hash in link".to_string()))
+    })?;
+
+    let Some(latest) = get(entry_hash, GetOptions::default())? else {
+        return Ok(Vec::new());
+    };
+
+    let mut revisions = Vec::new();
+    let mut next = Some(latest.action_address().clone());
+
+    while let Some(action_hash) = next {
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            break;
+        };
+
+        next = match record.action() {
+            Action::Update(update) => Some(update.original_action_address.clone()),
+            _ => None,
+        };
+
+        if let Some(profile) = record
+            .entry()
+            .to_app_option::<Profile>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        {
+            revisions.push(ProfileRevision {
+                profile,
+                action_hash,
+                revised_at: record.action().timestamp(),
+            });
+        }
+    }
+
+    Ok(revisions)
+}
+
 /// Creates a deterministic anchor hash for all profiles
 fn anchor_hash() -> ExternResult<EntryHash> {
     // Use a simple path-based anchor
@@ -239,6 +549,373 @@ fn anchor_hash() -> ExternResult<EntryHash> {
     path.path_entry_hash()
 }
 
+// ============================================================================
+// Badges
+// ============================================================================
+
+/// Input for granting a badge; called by other zomes, not the UI
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GrantBadgeInput {
+    pub agent: AgentPubKey,
+    pub kind: BadgeKind,
+    /// Candidate records from the issuing zome; extras are ignored
+    pub evidence: Vec<ActionHash>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BadgeOutput {
+    pub badge: Badge,
+    pub action_hash: ActionHash,
+}
+
+/// Grant a badge once enough evidence exists
+///
+/// Returns the agent's existing badge of that kind if they have one, and
+/// None if there isn't enough evidence yet. Validation checks each evidence
+/// record against the badge kind, so callers must only pass records that
+/// show the agent earning it.
+#[hdk_extern]
+pub fn grant_badge(input: GrantBadgeInput) -> ExternResult<Option<BadgeOutput>> {
+    if let Some(existing) = get_agent_badges(input.agent.clone())?
+        .into_iter()
+        .find(|output| output.badge.kind == input.kind)
+    {
+        return Ok(Some(existing));
+    }
+
+    let mut evidence: Vec<ActionHash> = Vec::new();
+    for hash in input.evidence {
+        if !evidence.contains(&hash) {
+            evidence.push(hash);
+        }
+    }
+    let required = input.kind.evidence_required();
+    if evidence.len() < required {
+        return Ok(None);
+    }
+    evidence.truncate(required);
+
+    let badge = Badge {
+        agent: input.agent.clone(),
+        kind: input.kind,
+        evidence,
+        granted_at: sys_time()?,
+    };
+    let action_hash = create_entry(EntryTypes::Badge(badge.clone()))?;
+    create_link(input.agent, action_hash.clone(), LinkTypes::AgentToBadges, ())?;
+
+    Ok(Some(BadgeOutput { badge, action_hash }))
+}
+
+/// An agent's badges, one per kind, earliest first
+#[hdk_extern]
+pub fn get_agent_badges(agent: AgentPubKey) -> ExternResult<Vec<BadgeOutput>> {
+    let links = get_links(
+        LinkQuery::try_new(agent.clone(), LinkTypes::AgentToBadges)?,
+        GetStrategy::Local,
+    )?;
+
+    let mut badges: Vec<BadgeOutput> = Vec::new();
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let Some(badge) = record
+            .entry()
+            .to_app_option::<Badge>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        else {
+            continue;
+        };
+        if badge.agent == agent {
+            badges.push(BadgeOutput { badge, action_hash });
+        }
+    }
+
+    badges.sort_by(|a, b| a.badge.granted_at.cmp(&b.badge.granted_at));
+    let mut seen = Vec::new();
+    badges.retain(|output| {
+        let first = !seen.contains(&output.badge.kind);
+        seen.push(output.badge.kind);
+        first
+    });
+
+    Ok(badges)
+}
+
+// ============================================================================
+// Status
+// ============================================================================
+
+/// Input for setting the caller's status
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetStatusInput {
+    pub emoji: Option<String>,
+    pub text: String,
+    pub expires_at: Option<Timestamp>,
+}
+
+/// Set the caller's status, replacing any previous one
+#[hdk_extern]
+pub fn set_status(input: SetStatusInput) -> ExternResult<ProfileStatus> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+    if input.expires_at.is_some_and(|expires_at| expires_at <= now) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Status expiry must be in the future".to_string()
+        )));
+    }
+
+    let status = ProfileStatus {
+        emoji: input.emoji.filter(|emoji| !emoji.trim().is_empty()),
+        text: input.text.trim().to_string(),
+        expires_at: input.expires_at,
+        set_at: now,
+    };
+
+    clear_status(())?;
+    let action_hash = create_entry(EntryTypes::ProfileStatus(status.clone()))?;
+    create_link(agent.clone(), action_hash.clone(), LinkTypes::AgentToStatus, ())?;
+
+    emit_signal(SystemSignal::ProfileUpdated { agent, action_hash })?;
+
+    Ok(status)
+}
+
+/// Clear the caller's status
+#[hdk_extern]
+pub fn clear_status(_: ()) -> ExternResult<()> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    let links = get_links(
+        LinkQuery::try_new(agent, LinkTypes::AgentToStatus)?,
+        GetStrategy::Local,
+    )?;
+    for link in links {
+        delete_link(link.create_link_hash, GetOptions::default())?;
+    }
+    Ok(())
+}
+
+/// An agent's current status; None if unset or expired
+#[hdk_extern]
+pub fn get_agent_status(agent: AgentPubKey) -> ExternResult<Option<ProfileStatus>> {
+    current_status(&agent)
+}
+
+fn current_status(agent: &AgentPubKey) -> ExternResult<Option<ProfileStatus>> {
+    let links = get_links(
+        LinkQuery::try_new(agent.clone(), LinkTypes::AgentToStatus)?,
+        GetStrategy::Local,
+    )?;
+    let Some(link) = links.into_iter().max_by_key(|link| link.timestamp) else {
+        return Ok(None);
+    };
+    let Some(action_hash) = link.target.into_action_hash() else {
+        return Ok(None);
+    };
+    let Some(record) = get(action_hash, GetOptions::default())? else {
+        return Ok(None);
+    };
+    let status = record
+        .entry()
+        .to_app_option::<ProfileStatus>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+
+    let now = sys_time()?;
+    Ok(status.filter(|status| status.is_active(now)))
+}
+
+// ============================================================================
+// Onboarding Checklist
+// ============================================================================
+
+/// Where the calling agent is in onboarding
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OnboardingState {
+    /// In ONBOARDING_STEPS order
+    pub completed: Vec<OnboardingStep>,
+    /// The first step not yet done; None once onboarding is finished
+    pub next_step: Option<OnboardingStep>,
+}
+
+/// The parts of the vouch zome's `MembershipInfo` onboarding needs
+#[derive(Deserialize, Debug)]
+struct MembershipProgress {
+    status: MembershipStatus,
+    vouches_received: Vec<serde::de::IgnoredAny>,
+}
+
+/// The calling agent's onboarding checklist
+///
+/// Steps are recorded as other zomes see them happen. Vouches are made on
+/// the voucher's chain, so vouch progress is also checked here and recorded
+/// when found.
+#[hdk_extern]
+pub fn get_onboarding_state(_: ()) -> ExternResult<OnboardingState> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("vouch"),
+        FunctionName::from("get_my_membership_status"),
+        None,
+        (),
+    )?;
+    let membership: MembershipProgress = match response {
+        ZomeCallResponse::Ok(result) => result.decode().map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!("Failed to decode membership status: {:?}", e)))
+        })?,
+        other => {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Failed to fetch membership status: {:?}",
+                other
+            ))));
+        }
+    };
+
+    let mut reached = Vec::new();
+    if !membership.vouches_received.is_empty() {
+        reached.push(OnboardingStep::FirstVouchReceived);
+    }
+    if membership.status != MembershipStatus::Pending {
+        reached.push(OnboardingStep::Verified);
+    }
+    record_onboarding_steps(&reached)
+}
+
+/// Mark an onboarding step done for the calling agent
+///
+/// Called by other zomes when the agent does something that completes a
+/// step; steps already done are left alone.
+#[hdk_extern]
+pub fn record_onboarding_step(step: OnboardingStep) -> ExternResult<OnboardingState> {
+    record_onboarding_steps(&[step])
+}
+
+fn record_onboarding_steps(steps: &[OnboardingStep]) -> ExternResult<OnboardingState> {
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::ProfileOnboarding.try_into()?)
+            .include_entries(true),
+    )?;
+    let mut completed = match records.last() {
+        Some(record) => record
+            .entry()
+            .to_app_option::<ProfileOnboarding>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+            .map(|onboarding| onboarding.completed)
+            .unwrap_or_default(),
+        None => Vec::new(),
+    };
+
+    let new_steps: Vec<OnboardingStep> = steps.iter().copied().filter(|step| !completed.contains(step)).collect();
+    if !new_steps.is_empty() {
+        completed.extend(new_steps);
+        completed.sort_by_key(|step| ONBOARDING_STEPS.iter().position(|known| known == step));
+        create_entry(EntryTypes::ProfileOnboarding(ProfileOnboarding {
+            completed: completed.clone(),
+            updated_at: sys_time()?,
+        }))?;
+    }
+
+    Ok(OnboardingState {
+        next_step: ONBOARDING_STEPS.into_iter().find(|step| !completed.contains(step)),
+        completed,
+    })
+}
+
+// ============================================================================
+// Account Deactivation
+// ============================================================================
+
+/// Confirmation for `deactivate_my_account`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeactivateAccountInput {
+    /// Must match the current nickname, so a stray call can't tear an account down
+    pub confirm_nickname: String,
+}
+
+/// Everything `deactivate_my_account` wound down
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeactivationReport {
+    pub profile: ProfileOutput,
+    pub reservations_cancelled: Vec<ActionHash>,
+    pub rsvps_cancelled: Vec<ActionHash>,
+    pub items_marked_unavailable: Vec<ActionHash>,
+    pub borrow_requests_withdrawn: Vec<ActionHash>,
+}
+
+/// Mirror of the toolshed zome's `LendingWithdrawal`
+#[derive(Serialize, Deserialize, Debug)]
+struct LendingWithdrawal {
+    items_marked_unavailable: Vec<ActionHash>,
+    requests_withdrawn: Vec<ActionHash>,
+}
+
+/// Deactivate the calling agent's account, e.g. when moving away
+///
+/// Cancels future reservations and RSVPs, marks owned items Unavailable,
+/// withdraws open borrow requests, and leaves online presence, then marks the
+/// profile inactive. The profile is marked last so a failed step can simply
+/// be retried; each step skips what is already wound down.
+#[hdk_extern]
+pub fn deactivate_my_account(input: DeactivateAccountInput) -> ExternResult<DeactivationReport> {
+    let (original_action_hash, mut profile) = latest_own_profile()?;
+    if input.confirm_nickname.trim() != profile.nickname {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Type your nickname exactly to confirm deactivation".to_string()
+        )));
+    }
+
+    let reservations_cancelled: Vec<ActionHash> = call_zome("spaces", "cancel_my_future_reservations", ())?;
+    let rsvps_cancelled: Vec<ActionHash> = call_zome("events", "cancel_my_future_rsvps", ())?;
+    let lending: LendingWithdrawal = call_zome("toolshed", "withdraw_from_lending", ())?;
+    call_zome::<_, ()>("chat", "leave_presence", ())?;
+
+    let profile = match profile.deactivated_at {
+        Some(_) => get_profile_for_agent(agent_info()?.agent_initial_pubkey)?.ok_or_else(|| {
+            wasm_error!(WasmErrorInner::Guest("Profile not found".to_string()))
+        })?,
+        None => {
+            profile.deactivated_at = Some(sys_time()?);
+            commit_profile_update(original_action_hash, profile)?
+        }
+    };
+
+    Ok(DeactivationReport {
+        profile,
+        reservations_cancelled,
+        rsvps_cancelled,
+        items_marked_unavailable: lending.items_marked_unavailable,
+        borrow_requests_withdrawn: lending.requests_withdrawn,
+    })
+}
+
+fn call_zome<I, O>(zome: &str, fn_name: &str, payload: I) -> ExternResult<O>
+where
+    I: serde::Serialize + std::fmt::Debug,
+    O: serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from(zome),
+        FunctionName::from(fn_name),
+        None,
+        payload,
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(result) => result.decode().map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!("Failed to decode {} response: {:?}", zome, e)))
+        }),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to call {}.{}: {:?}",
+            zome, fn_name, other
+        )))),
+    }
+}
+
 // ============================================================================
 // System Signals
 // ============================================================================
@@ -917,6 +1594,71 @@ fn is_agent_revoked_coordinator(agent: &AgentPubKey) -> ExternResult<bool> {
 // Onboarding & Vouching Functions
 // ============================================================================
 
+/// Let a neighbor who just vouched for this agent start their profile
+#[hdk_extern]
+pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    let mut functions = BTreeSet::new();
+    functions.insert((zome_info()?.name, FunctionName::from("bootstrap_profile")));
+    create_cap_grant(CapGrantEntry {
+        tag: "onboarding".to_string(),
+        access: CapAccess::Unrestricted,
+        functions: GrantedFunctions::Listed(functions),
+    })?;
+    Ok(InitCallbackResult::Pass)
+}
+
+/// Input for `bootstrap_profile`, sent by the vouch zome's `onboard_neighbor`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct BootstrapProfileInput {
+    pub nickname: String,
+    /// The caller's vouch for this agent
+    pub vouch_hash: ActionHash,
+}
+
+/// The part of the vouch zome's Vouch entry needed to check a bootstrap call
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+struct VouchEntry {
+    vouchee: AgentPubKey,
+}
+
+/// Start a skeleton profile for this agent (called remotely by a voucher)
+///
+/// Lets a newly vouched-in neighbor show up by name straight away; they fill
+/// in the rest with `update_profile`. Only someone who has vouched for this
+/// agent may call it, and it returns None if a profile already exists.
+#[hdk_extern]
+pub fn bootstrap_profile(input: BootstrapProfileInput) -> ExternResult<Option<ProfileOutput>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let caller = call_info()?.provenance;
+
+    let Some(record) = get(input.vouch_hash, GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Vouch not found".to_string())));
+    };
+    let vouch = record
+        .entry()
+        .to_app_option::<VouchEntry>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Not a vouch".to_string())))?;
+    if record.action().author() != &caller || vouch.vouchee != me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only a neighbor who vouched for you can start your profile".to_string()
+        )));
+    }
+
+    if get_profile_for_agent(me)?.is_some() {
+        return Ok(None);
+    }
+    create_profile(CreateProfileInput {
+        nickname: input.nickname,
+        bio: None,
+        avatar_url: None,
+        location_metadata: None,
+        pronouns: None,
+        display_name_variants: Vec::new(),
+    })
+    .map(Some)
+}
+
 /// Get the membrane proof for the current agent
 /// Returns the voucher's AgentPubKey if the agent was vouched in
 #[hdk_extern]
@@ -954,3 +1696,31 @@ pub struct GetAgentActivityInput {
     pub agent: AgentPubKey,
 }
 
+// ============================================================================
+// DATA EXPORT
+// ============================================================================
+
+/// Everything the profile zome holds about the calling agent
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProfileExport {
+    pub agent: AgentPubKey,
+    pub profile: Option<ProfileOutput>,
+    pub profile_history: Vec<ProfileRevision>,
+    pub exported_at: Timestamp,
+}
+
+/// Export the caller's profile and its revision history
+///
+/// Each zome has its own `export_my_data`; together they make up the
+/// caller's full neighborhood footprint.
+#[hdk_extern]
+pub fn export_my_data(_: ()) -> ExternResult<ProfileExport> {
+    let agent = agent_info()?.agent_initial_pubkey;
+
+    Ok(ProfileExport {
+        profile: get_profile_for_agent(agent.clone())?,
+        profile_history: get_profile_history(agent.clone())?,
+        agent,
+        exported_at: sys_time()?,
+    })
+}
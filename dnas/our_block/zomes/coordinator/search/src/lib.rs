@@ -0,0 +1,338 @@
+//! Search Coordinator Zome
+//!
+//! A lightweight keyword index over posts, tool shed items, help requests,
+//! and events. The owning zomes call `index_content` when content is created;
+//! each keyword becomes a link from a `search.<keyword>` path to the record,
+//! with the kind and title in the link tag so results can be listed cheaply.
+//!
+//! Located content is indexed the same way under `geo.<prefix>` paths, one
+//! per geohash prefix, so `get_nearby` is a single link lookup at any precision.
+
+use hdk::prelude::*;
+use location::is_valid_geohash;
+use search_integrity::*;
+use std::collections::{HashMap, HashSet};
+
+/// Input for adding content to (or removing it from) the index
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IndexContentInput {
+    pub kind: ContentKind,
+    pub target: ActionHash,
+    pub title: String,
+    /// Body text; indexed together with the title
+    pub text: String,
+}
+
+/// Input for searching; `kinds` of None searches everything
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SearchInput {
+    pub query: String,
+    pub kinds: Option<Vec<ContentKind>>,
+    pub limit: Option<usize>,
+}
+
+/// One search result
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SearchHit {
+    pub kind: ContentKind,
+    pub target: ActionHash,
+    pub title: String,
+    pub author: AgentPubKey,
+    pub indexed_at: Timestamp,
+    /// How many query keywords matched
+    pub score: u32,
+}
+
+/// Input for adding located content to (or removing it from) the geohash index
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LocationIndexInput {
+    pub kind: ContentKind,
+    pub target: ActionHash,
+    pub title: String,
+    pub geohash: String,
+}
+
+/// Input for finding content near a place
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NearbyInput {
+    pub kind: ContentKind,
+    /// Shorter prefixes cover larger areas
+    pub geohash_prefix: String,
+}
+
+/// One piece of content located within the queried cell
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NearbyHit {
+    pub kind: ContentKind,
+    pub target: ActionHash,
+    pub title: String,
+    pub author: AgentPubKey,
+    pub indexed_at: Timestamp,
+}
+
+const SEARCH_PATH_PREFIX: &str = "search";
+const GEO_PATH_PREFIX: &str = "geo";
+/// Keywords indexed per entry; long posts only get their first words indexed
+const MAX_KEYWORDS_PER_ENTRY: usize = 40;
+const MAX_QUERY_KEYWORDS: usize = 8;
+const MIN_KEYWORD_LENGTH: usize = 3;
+const MAX_KEYWORD_LENGTH: usize = 32;
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+const MAX_SEARCH_LIMIT: usize = 200;
+
+const STOP_WORDS: &[&str] = &[
+    "and", "are", "but", "can", "for", "from", "had", "has", "have", "her", "his", "its", "not",
+    "our", "that", "the", "their", "them", "then", "there", "they", "this", "was", "were", "will",
+    "with", "you", "your",
+];
+
+// ============================================================================
+// INDEXING
+// ============================================================================
+
+/// Index content under each of its keywords; returns the keyword count
+///
+/// Called by the feed, toolshed, helpinghands, and events zomes after they
+/// create an entry. The links are authored by the caller, so only they can
+/// remove them again.
+#[hdk_extern]
+pub fn index_content(input: IndexContentInput) -> ExternResult<usize> {
+    let keywords = content_keywords(&input.title, &input.text);
+    let tag = search_tag(input.kind, &input.title)?;
+
+    for keyword in &keywords {
+        create_link(
+            keyword_path_hash(keyword)?,
+            input.target.clone(),
+            LinkTypes::KeywordToContent,
+            tag.clone(),
+        )?;
+    }
+
+    Ok(keywords.len())
+}
+
+/// Remove the caller's keyword links for content, e.g. before re-indexing an edit
+///
+/// The input must carry the title and text that were indexed, since those
+/// determine which keyword paths hold links.
+#[hdk_extern]
+pub fn unindex_content(input: IndexContentInput) -> ExternResult<usize> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let target = AnyLinkableHash::from(input.target);
+    let mut removed = 0;
+
+    for keyword in content_keywords(&input.title, &input.text) {
+        let links = get_links(
+            LinkQuery::try_new(keyword_path_hash(&keyword)?, LinkTypes::KeywordToContent)?,
+            GetStrategy::Local,
+        )?;
+        for link in links {
+            if link.target == target && link.author == me {
+                delete_link(link.create_link_hash, GetOptions::default())?;
+                removed += 1;
+            }
+        }
+    }
+
+    Ok(removed)
+}
+
+// ============================================================================
+// SEARCH
+// ============================================================================
+
+/// Search posts, items, requests, and events by keyword
+///
+/// Results matching more of the query's keywords rank first, then newer
+/// content. Matching is on whole words, case-insensitively.
+#[hdk_extern]
+pub fn search_all(input: SearchInput) -> ExternResult<Vec<SearchHit>> {
+    let kinds: Option<HashSet<ContentKind>> = input.kinds.map(|kinds| kinds.into_iter().collect());
+    let limit = input.limit.unwrap_or(DEFAULT_SEARCH_LIMIT).min(MAX_SEARCH_LIMIT);
+
+    let mut hits: HashMap<ActionHash, SearchHit> = HashMap::new();
+
+    for keyword in tokenize(&input.query).into_iter().take(MAX_QUERY_KEYWORDS) {
+        let links = get_links(
+            LinkQuery::try_new(keyword_path_hash(&keyword)?, LinkTypes::KeywordToContent)?,
+            GetStrategy::Local,
+        )?;
+
+        // The same content can be linked twice under one keyword; count it once
+        let mut seen: HashSet<ActionHash> = HashSet::new();
+
+        for link in links {
+            let Some(target) = link.target.into_action_hash() else {
+                continue;
+            };
+            if !seen.insert(target.clone()) {
+                continue;
+            }
+            let Some(tag) = decode_tag(link.tag) else {
+                continue;
+            };
+            if kinds.as_ref().is_some_and(|kinds| !kinds.contains(&tag.kind)) {
+                continue;
+            }
+
+            hits.entry(target.clone())
+                .and_modify(|hit| hit.score += 1)
+                .or_insert(SearchHit {
+                    kind: tag.kind,
+                    target,
+                    title: tag.title,
+                    author: link.author,
+                    indexed_at: link.timestamp,
+                    score: 1,
+                });
+        }
+    }
+
+    let mut hits: Vec<SearchHit> = hits.into_values().collect();
+    hits.sort_by(|a, b| b.score.cmp(&a.score).then(b.indexed_at.cmp(&a.indexed_at)));
+    hits.truncate(limit);
+
+    Ok(hits)
+}
+
+// ============================================================================
+// LOCATIONS
+// ============================================================================
+
+/// Index located content under every prefix of its geohash
+///
+/// Called by the helpinghands, toolshed, events, and spaces zomes when an
+/// entry is created with a geohash. Returns the number of links created.
+#[hdk_extern]
+pub fn index_location(input: LocationIndexInput) -> ExternResult<usize> {
+    let prefixes = geohash_prefixes(&input.geohash)?;
+    let tag = search_tag(input.kind, &input.title)?;
+
+    for prefix in &prefixes {
+        create_link(
+            geohash_path_hash(prefix)?,
+            input.target.clone(),
+            LinkTypes::GeohashToContent,
+            tag.clone(),
+        )?;
+    }
+
+    Ok(prefixes.len())
+}
+
+/// Remove the caller's geohash links for content, e.g. when it moves
+#[hdk_extern]
+pub fn unindex_location(input: LocationIndexInput) -> ExternResult<usize> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let target = AnyLinkableHash::from(input.target);
+    let mut removed = 0;
+
+    for prefix in geohash_prefixes(&input.geohash)? {
+        let links = get_links(
+            LinkQuery::try_new(geohash_path_hash(&prefix)?, LinkTypes::GeohashToContent)?,
+            GetStrategy::Local,
+        )?;
+        for link in links {
+            if link.target == target && link.author == me {
+                delete_link(link.create_link_hash, GetOptions::default())?;
+                removed += 1;
+            }
+        }
+    }
+
+    Ok(removed)
+}
+
+/// Content of one kind located within a geohash cell, newest first
+#[hdk_extern]
+pub fn get_nearby(input: NearbyInput) -> ExternResult<Vec<NearbyHit>> {
+    if !is_valid_geohash(&input.geohash_prefix) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Invalid geohash prefix".into())));
+    }
+
+    let links = get_links(
+        LinkQuery::try_new(geohash_path_hash(&input.geohash_prefix)?, LinkTypes::GeohashToContent)?,
+        GetStrategy::Local,
+    )?;
+
+    let mut seen: HashSet<ActionHash> = HashSet::new();
+    let mut hits = Vec::new();
+
+    for link in links {
+        let Some(target) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(tag) = decode_tag(link.tag) else {
+            continue;
+        };
+        if tag.kind != input.kind || !seen.insert(target.clone()) {
+            continue;
+        }
+        hits.push(NearbyHit {
+            kind: tag.kind,
+            target,
+            title: tag.title,
+            author: link.author,
+            indexed_at: link.timestamp,
+        });
+    }
+
+    hits.sort_by(|a, b| b.indexed_at.cmp(&a.indexed_at));
+    Ok(hits)
+}
+
+// ============================================================================
+// HELPER FUNCTIONS
+// ============================================================================
+
+/// Lowercased words of at least MIN_KEYWORD_LENGTH characters, minus stop
+/// words and duplicates, in order of first appearance
+fn tokenize(text: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+
+    text.split(|c: char| !c.is_alphanumeric())
+        .map(|word| word.to_lowercase())
+        .filter(|word| word.chars().count() >= MIN_KEYWORD_LENGTH)
+        .filter(|word| !STOP_WORDS.contains(&word.as_str()))
+        .map(|word| word.chars().take(MAX_KEYWORD_LENGTH).collect::<String>())
+        .filter(|word| seen.insert(word.clone()))
+        .collect()
+}
+
+fn content_keywords(title: &str, text: &str) -> Vec<String> {
+    let mut keywords = tokenize(&format!("{} {}", title, text));
+    keywords.truncate(MAX_KEYWORDS_PER_ENTRY);
+    keywords
+}
+
+fn search_tag(kind: ContentKind, title: &str) -> ExternResult<LinkTag> {
+    let tag = SearchTag {
+        kind,
+        title: title.chars().take(MAX_TAG_TITLE_LENGTH).collect(),
+    };
+    let bytes = SerializedBytes::try_from(tag).map_err(|e| wasm_error!(e))?;
+    Ok(LinkTag::new(bytes.bytes().clone()))
+}
+
+fn decode_tag(tag: LinkTag) -> Option<SearchTag> {
+    SearchTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.into_inner()))).ok()
+}
+
+/// "u4pr" -> ["u", "u4", "u4p", "u4pr"]
+fn geohash_prefixes(geohash: &str) -> ExternResult<Vec<String>> {
+    if !is_valid_geohash(geohash) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Invalid geohash".into())));
+    }
+    Ok((1..=geohash.len()).map(|len| geohash[..len].to_string()).collect())
+}
+
+fn geohash_path_hash(prefix: &str) -> ExternResult<EntryHash> {
+    let path = Path::from(format!("{}.{}", GEO_PATH_PREFIX, prefix));
+    path.path_entry_hash()
+}
+
+fn keyword_path_hash(keyword: &str) -> ExternResult<EntryHash> {
+    let path = Path::from(format!("{}.{}", SEARCH_PATH_PREFIX, keyword));
+    path.path_entry_hash()
+}
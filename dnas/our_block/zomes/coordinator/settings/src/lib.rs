@@ -0,0 +1,191 @@
+//! Neighborhood Settings Coordinator Zome
+//!
+//! Stores neighborhood-wide configuration (name, description, rules, and
+//! feature toggles). Only trusted anchors can change it, and every change is
+//! kept as a revision so the history can be audited.
+
+use hdk::prelude::*;
+use settings_integrity::*;
+
+/// The settings currently in effect
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NeighborhoodSettingsOutput {
+    pub name: String,
+    pub description: String,
+    pub rules: String,
+    pub features: FeatureToggles,
+    /// None while the neighborhood still runs on defaults
+    pub revision: Option<ActionHash>,
+    pub updated_by: Option<AgentPubKey>,
+    pub updated_at: Option<Timestamp>,
+}
+
+/// Input for changing settings; omitted fields keep their current value
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UpdateSettingsInput {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub rules: Option<String>,
+    pub features: Option<FeatureToggles>,
+    pub change_note: Option<String>,
+}
+
+/// One entry in the settings change history
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SettingsRevision {
+    pub settings: NeighborhoodSettings,
+    pub action_hash: ActionHash,
+    pub editor: AgentPubKey,
+}
+
+const SETTINGS_PATH: &str = "neighborhood_settings";
+
+// ============================================================================
+// SETTINGS
+// ============================================================================
+
+/// Get the neighborhood settings currently in effect
+///
+/// Before an anchor saves any settings, the name comes from the
+/// `neighborhood_name` DNA property and everything else is default.
+#[hdk_extern]
+pub fn get_neighborhood_settings(_: ()) -> ExternResult<NeighborhoodSettingsOutput> {
+    match get_settings_history(())?.into_iter().next() {
+        Some(revision) => Ok(NeighborhoodSettingsOutput {
+            name: revision.settings.name,
+            description: revision.settings.description,
+            rules: revision.settings.rules,
+            features: revision.settings.features,
+            revision: Some(revision.action_hash),
+            updated_by: Some(revision.editor),
+            updated_at: Some(revision.settings.created_at),
+        }),
+        None => Ok(NeighborhoodSettingsOutput {
+            name: default_neighborhood_name()?,
+            description: String::new(),
+            rules: String::new(),
+            features: FeatureToggles::default(),
+            revision: None,
+            updated_by: None,
+            updated_at: None,
+        }),
+    }
+}
+
+/// Change the neighborhood settings (trusted anchors only)
+#[hdk_extern]
+pub fn update_neighborhood_settings(input: UpdateSettingsInput) -> ExternResult<SettingsRevision> {
+    let editor = agent_info()?.agent_initial_pubkey;
+
+    let Some(editor_anchor) = get_my_anchor_record()? else {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only trusted anchors can change neighborhood settings".to_string()
+        )));
+    };
+
+    let current = get_neighborhood_settings(())?;
+
+    let settings = NeighborhoodSettings {
+        name: input.name.unwrap_or(current.name),
+        description: input.description.unwrap_or(current.description),
+        rules: input.rules.unwrap_or(current.rules),
+        features: input.features.unwrap_or(current.features),
+        previous: current.revision,
+        editor_anchor,
+        change_note: input.change_note,
+        created_at: sys_time()?,
+    };
+
+    let action_hash = create_entry(EntryTypes::NeighborhoodSettings(settings.clone()))?;
+    create_link(
+        settings_path_hash()?,
+        action_hash.clone(),
+        LinkTypes::SettingsRevisions,
+        (),
+    )?;
+
+    Ok(SettingsRevision {
+        settings,
+        action_hash,
+        editor,
+    })
+}
+
+/// Get every settings revision, newest first
+#[hdk_extern]
+pub fn get_settings_history(_: ()) -> ExternResult<Vec<SettingsRevision>> {
+    let links = get_links(
+        LinkQuery::try_new(settings_path_hash()?, LinkTypes::SettingsRevisions)?,
+        GetStrategy::Local,
+    )?;
+
+    let mut revisions = Vec::new();
+
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        if let Some(settings) = record
+            .entry()
+            .to_app_option::<NeighborhoodSettings>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        {
+            revisions.push(SettingsRevision {
+                settings,
+                action_hash,
+                editor: record.action().author().clone(),
+            });
+        }
+    }
+
+    // Concurrent edits by two anchors both stay in the history; the later one wins
+    revisions.sort_by(|a, b| b.settings.created_at.cmp(&a.settings.created_at));
+
+    Ok(revisions)
+}
+
+// ============================================================================
+// HELPER FUNCTIONS
+// ============================================================================
+
+/// Ask the vouch zome for the caller's TrustedAnchor record
+fn get_my_anchor_record() -> ExternResult<Option<ActionHash>> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("vouch"),
+        FunctionName::from("get_my_anchor_record"),
+        None,
+        (),
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(result) => result.decode().map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "Failed to decode anchor record: {:?}",
+                e
+            )))
+        }),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to check anchor status: {:?}",
+            other
+        )))),
+    }
+}
+
+fn default_neighborhood_name() -> ExternResult<String> {
+    let properties = dna_info()?.modifiers.properties;
+    Ok(properties
+        .get("neighborhood_name")
+        .and_then(|v| v.as_str())
+        .filter(|name| !name.is_empty())
+        .unwrap_or("My Neighborhood")
+        .to_string())
+}
+
+fn settings_path_hash() -> ExternResult<EntryHash> {
+    let path = Path::from(SETTINGS_PATH);
+    path.path_entry_hash()
+}
@@ -5,13 +5,25 @@
 
 use hdk::prelude::*;
 use spaces_integrity::*;
+use idempotency::{find_previous_create, idempotency_tag};
+use list_options::{get_list_links, ListOptions};
+use signal_envelope::SignalEnvelope;
+use std::collections::BTreeSet;
 
-/// Signal types for real-time updates
+/// Signal types for real-time updates, emitted inside a SignalEnvelope
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(tag = "type")]
+#[serde(tag = "event", content = "payload")]
 pub enum Signal {
     NewSpace { space_hash: ActionHash, space: Space },
     NewReservation { space_hash: ActionHash, reservation_hash: ActionHash },
+    ReservationApproved { space_hash: ActionHash, reservation_hash: ActionHash },
+}
+
+/// Zome name clients see in the signal envelope
+const SIGNAL_ZOME: &str = "spaces";
+
+fn emit_zome_signal(signal: Signal) -> ExternResult<()> {
+    emit_signal(SignalEnvelope::new(SIGNAL_ZOME, signal))
 }
 
 /// Input for creating a space
@@ -21,6 +33,16 @@ pub struct CreateSpaceInput {
     pub description: String,
     pub capacity: u32,
     pub available_hours: String,
+    #[serde(default)]
+    pub place: Option<Location>,
+    /// Allow overlapping reservations while their party sizes fit the capacity
+    #[serde(default)]
+    pub allow_shared_bookings: bool,
+    #[serde(default)]
+    pub booking_rules: BookingRules,
+    /// Client-chosen key; resubmitting with the same key returns the original
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 /// Output for space operations
@@ -29,6 +51,34 @@ pub struct SpaceOutput {
     pub space: Space,
     pub action_hash: ActionHash,
     pub entry_hash: EntryHash,
+    /// Toolshed items kept at the space
+    #[serde(default)]
+    pub equipment: Vec<SpaceEquipment>,
+}
+
+/// A toolshed item attached to a space
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpaceEquipment {
+    pub item_hash: ActionHash,
+    pub title: String,
+    /// Held for the space's reservations by `reserve_space_equipment`
+    pub auto_reserve: bool,
+}
+
+/// Input for attaching one of my toolshed items to a space I manage
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AttachEquipmentInput {
+    pub space_hash: ActionHash,
+    pub item_hash: ActionHash,
+    #[serde(default)]
+    pub auto_reserve: bool,
+}
+
+/// Input for detaching an item from a space
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DetachEquipmentInput {
+    pub space_hash: ActionHash,
+    pub item_hash: ActionHash,
 }
 
 /// Input for creating a reservation
@@ -38,6 +88,12 @@ pub struct CreateReservationInput {
     pub start_time: Timestamp,
     pub end_time: Timestamp,
     pub purpose: Option<String>,
+    /// Defaults to 1
+    #[serde(default)]
+    pub party_size: Option<u32>,
+    /// Client-chosen key; resubmitting with the same key returns the original
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 /// Output for reservation operations
@@ -46,19 +102,169 @@ pub struct ReservationOutput {
     pub reservation: Reservation,
     pub action_hash: ActionHash,
     pub entry_hash: EntryHash,
+    /// Places left in the space while this reservation runs; only filled in
+    /// when listing a space's reservations or creating one
+    pub remaining_capacity: Option<u32>,
+    /// Another reservation booked at the same moment won the slot; this one
+    /// doesn't hold and should be rebooked or cancelled
+    pub superseded: bool,
+    /// The space requires the manager's approval and hasn't got it yet; the
+    /// reservation doesn't hold its slot until then
+    pub awaiting_approval: bool,
+}
+
+impl ReservationOutput {
+    /// Whether the reservation counts as booked
+    fn holds(&self) -> bool {
+        !self.superseded && !self.awaiting_approval
+    }
+}
+
+/// The parts of the settings zome's NeighborhoodSettingsOutput this zome reads
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct NeighborhoodSettings {
+    features: NeighborhoodFeatures,
+}
+
+/// Mirror of the settings zome's FeatureToggles
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct NeighborhoodFeatures {
+    require_reservation_approval: bool,
+    require_space_approval: bool,
+}
+
+/// Mirror of the search zome's LocationIndexInput
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LocationIndexInput {
+    kind: SearchKind,
+    target: ActionHash,
+    title: String,
+    geohash: String,
+}
+
+/// The toolshed Item fields spaces needs
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct EquipmentItem {
+    title: String,
+    owner: AgentPubKey,
+}
+
+/// Mirror of the toolshed zome's ScheduleUnavailabilityInput
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ScheduleUnavailabilityInput {
+    item_hash: ActionHash,
+    starts_at: Timestamp,
+    ends_at: Timestamp,
+    reason: Option<String>,
+}
+
+/// The toolshed UnavailabilityWindowOutput fields spaces needs
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct UnavailabilityWindowOutput {
+    window: UnavailabilityWindow,
+    action_hash: ActionHash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct UnavailabilityWindow {
+    starts_at: Timestamp,
+    ends_at: Timestamp,
+}
+
+/// The search zome's ContentKind variant for spaces
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum SearchKind {
+    Space,
+}
+
+/// Input for setting a space's access instructions
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetAccessInstructionsInput {
+    pub space_hash: ActionHash,
+    pub instructions: String,
+}
+
+/// Access instructions revealed for one reservation
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AccessInstructionsOutput {
+    pub space_hash: ActionHash,
+    pub reservation_hash: ActionHash,
+    pub instructions: String,
+    /// The instructions stop being revealed after this
+    pub valid_until: Timestamp,
+}
+
+/// Input for setting a space's after-use checklist
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SetChecklistInput {
+    pub space_hash: ActionHash,
+    pub items: Vec<String>,
+}
+
+/// Input for reporting the state a space was left in
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SubmitConditionReportInput {
+    pub reservation_hash: ActionHash,
+    pub checklist: Vec<ChecklistResult>,
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub photos: Vec<EntryHash>,
+}
+
+/// Output for condition report operations
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConditionReportOutput {
+    pub report: ConditionReport,
+    pub action_hash: ActionHash,
+    pub has_problems: bool,
+}
+
+/// Input for a manager's report query
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpaceReportsInput {
+    pub space_hash: ActionHash,
+    /// Only reports with an undone item or notes
+    #[serde(default)]
+    pub problems_only: bool,
 }
 
 const ALL_SPACES_PATH: &str = "all_spaces";
+const PENDING_SPACES_PATH: &str = "pending_spaces";
+/// How long before a reservation starts its access instructions are revealed
+const ACCESS_LEAD_TIME_SECS: i64 = 30 * 60;
+
+/// Let reservers ask a space's manager for its access instructions
+#[hdk_extern]
+pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    let mut functions = BTreeSet::new();
+    functions.insert((zome_info()?.name, FunctionName::from("reveal_access_instructions")));
+    create_cap_grant(CapGrantEntry {
+        tag: "access_instructions".to_string(),
+        access: CapAccess::Unrestricted,
+        functions: GrantedFunctions::Listed(functions),
+    })?;
+    Ok(InitCallbackResult::Pass)
+}
 
 // ============================================================================
 // SPACE MANAGEMENT
 // ============================================================================
 
-/// Create a new shared space
+/// Create a new shared space; it is listed once an anchor approves it, or
+/// straight away if the neighborhood settings turn space approval off
 #[hdk_extern]
 pub fn create_space(input: CreateSpaceInput) -> ExternResult<SpaceOutput> {
     let manager = agent_info()?.agent_initial_pubkey;
     
+    if let Some(original) = find_previous_create(
+        input.idempotency_key.as_deref(),
+        manager.clone(),
+        LinkTypes::AgentToSpaces,
+    )? {
+        let space: Space = get_entry(original.clone(), "Original space")?;
+        return space_output(original, space);
+    }
+    
     let space = Space {
         name: input.name,
         description: input.description,
@@ -66,30 +272,36 @@ pub fn create_space(input: CreateSpaceInput) -> ExternResult<SpaceOutput> {
         available_hours: input.available_hours,
         manager: manager.clone(),
         created_at: sys_time()?,
+        place: input.place,
+        allow_shared_bookings: input.allow_shared_bookings,
+        booking_rules: input.booking_rules,
     };
     
+    require_verified()?;
+    ensure_membership_proof()?;
     let action_hash = create_entry(EntryTypes::Space(space.clone()))?;
     let entry_hash = hash_entry(&space)?;
     
-    // Link from manager to space
+    // Link from manager to space, tagged with the idempotency key
     create_link(
         manager,
         action_hash.clone(),
         LinkTypes::AgentToSpaces,
-        (),
+        idempotency_tag(input.idempotency_key.as_deref())?,
     )?;
     
-    // Link to global all_spaces anchor
-    let all_spaces_anchor = all_spaces_anchor_hash()?;
+    // Queue for anchor approval before it joins the all_spaces anchor
     create_link(
-        all_spaces_anchor,
+        pending_spaces_anchor_hash()?,
         action_hash.clone(),
-        LinkTypes::AllSpaces,
+        LinkTypes::PendingSpaces,
         (),
     )?;
     
+    index_place(action_hash.clone(), &space.name, &space.place)?;
+    
     // Emit signal for real-time updates
-    emit_signal(Signal::NewSpace {
+    emit_zome_signal(Signal::NewSpace {
         space_hash: action_hash.clone(),
         space: space.clone(),
     })?;
@@ -98,33 +310,100 @@ pub fn create_space(input: CreateSpaceInput) -> ExternResult<SpaceOutput> {
         space,
         action_hash,
         entry_hash,
+        equipment: Vec::new(),
     })
 }
 
-/// Get all shared spaces
+/// Approve a pending space so it shows up in `get_all_spaces` (anchors only)
 #[hdk_extern]
-pub fn get_all_spaces(_: ()) -> ExternResult<Vec<SpaceOutput>> {
-    let all_spaces_anchor = all_spaces_anchor_hash()?;
+pub fn approve_space(space_hash: ActionHash) -> ExternResult<ActionHash> {
+    let approver_anchor = require_anchor()?;
+    let _: Space = get_entry(space_hash.clone(), "Space")?;
+    
+    if approved_space_hashes()?.contains(&space_hash) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Space is already approved".to_string())));
+    }
+    
+    let approval = SpaceApproval {
+        space_hash: space_hash.clone(),
+        approver_anchor,
+        approved_at: sys_time()?,
+    };
+    let approval_hash = create_entry(EntryTypes::SpaceApproval(approval))?;
+    
+    // The listing link carries the approval so validators can check it
+    create_link(
+        all_spaces_anchor_hash()?,
+        space_hash,
+        LinkTypes::AllSpaces,
+        LinkTag::new(approval_hash.get_raw_39().to_vec()),
+    )?;
+    
+    Ok(approval_hash)
+}
+
+/// Spaces still waiting for approval, oldest first (anchors only)
+#[hdk_extern]
+pub fn get_pending_spaces(_: ()) -> ExternResult<Vec<SpaceOutput>> {
+    require_anchor()?;
+    let approved = approved_space_hashes()?;
     
     let links = get_links(
-        LinkQuery::try_new(all_spaces_anchor, LinkTypes::AllSpaces)?,
-        GetStrategy::Local,
+        LinkQuery::try_new(pending_spaces_anchor_hash()?, LinkTypes::PendingSpaces)?,
+        GetStrategy::Network,
     )?;
     
     let mut spaces = Vec::new();
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        if approved.contains(&action_hash) {
+            continue;
+        }
+        if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
+            if let Some(space) = record.entry().to_app_option::<Space>()
+                .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+            {
+                spaces.push(space_output(action_hash, space)?);
+            }
+        }
+    }
+    
+    spaces.sort_by_key(|output| output.space.created_at);
+    
+    Ok(spaces)
+}
+
+/// Get all approved shared spaces, and pending ones too when the
+/// neighborhood settings don't require approval
+#[hdk_extern]
+pub fn get_all_spaces(options: Option<ListOptions>) -> ExternResult<Vec<SpaceOutput>> {
+    let options = options.unwrap_or_default();
+    let all_spaces_anchor = all_spaces_anchor_hash()?;
+    
+    let mut links = get_list_links(all_spaces_anchor, LinkTypes::AllSpaces, options.prefer_network)?;
+    if !neighborhood_features()?.require_space_approval {
+        links.extend(get_list_links(
+            pending_spaces_anchor_hash()?,
+            LinkTypes::PendingSpaces,
+            options.prefer_network,
+        )?);
+    }
+    
+    let mut spaces = Vec::new();
+    let mut seen = BTreeSet::new();
     
     for link in links {
         if let Some(action_hash) = link.target.into_action_hash() {
+            if !seen.insert(action_hash.clone()) {
+                continue;
+            }
             if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
                 if let Some(space) = record.entry().to_app_option::<Space>()
                     .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
                 {
-                    let entry_hash = hash_entry(&space)?;
-                    spaces.push(SpaceOutput {
-                        space,
-                        action_hash,
-                        entry_hash,
-                    });
+                    spaces.push(space_output(action_hash, space)?);
                 }
             }
         }
@@ -136,6 +415,153 @@ pub fn get_all_spaces(_: ()) -> ExternResult<Vec<SpaceOutput>> {
     Ok(spaces)
 }
 
+// ============================================================================
+// SHARED EQUIPMENT
+// ============================================================================
+
+/// Attach one of my toolshed items to a space I manage, or change whether
+/// it is auto-reserved
+#[hdk_extern]
+pub fn attach_equipment(input: AttachEquipmentInput) -> ExternResult<SpaceEquipment> {
+    let manager = agent_info()?.agent_initial_pubkey;
+    let space: Space = get_entry(input.space_hash.clone(), "Space")?;
+    if space.manager != manager {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the space manager can attach equipment".into()
+        )));
+    }
+    
+    let item: EquipmentItem = get_entry(input.item_hash.clone(), "Item")?;
+    if item.owner != manager {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "You can only attach items you own".to_string()
+        )));
+    }
+    
+    // Re-attaching replaces the old link so the auto-reserve flag can change
+    for link in equipment_links(&input.space_hash)? {
+        if link.target.clone().into_action_hash().as_ref() == Some(&input.item_hash) {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
+    }
+    let tag = if input.auto_reserve { EQUIPMENT_AUTO_RESERVE_TAG.to_vec() } else { Vec::new() };
+    create_link(
+        input.space_hash,
+        input.item_hash.clone(),
+        LinkTypes::SpaceToEquipment,
+        LinkTag::new(tag),
+    )?;
+    
+    Ok(SpaceEquipment {
+        item_hash: input.item_hash,
+        title: item.title,
+        auto_reserve: input.auto_reserve,
+    })
+}
+
+/// Detach an item from a space I manage
+#[hdk_extern]
+pub fn detach_equipment(input: DetachEquipmentInput) -> ExternResult<()> {
+    let manager = agent_info()?.agent_initial_pubkey;
+    let space: Space = get_entry(input.space_hash.clone(), "Space")?;
+    if space.manager != manager {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the space manager can detach equipment".into()
+        )));
+    }
+    
+    for link in equipment_links(&input.space_hash)? {
+        if link.target.clone().into_action_hash().as_ref() == Some(&input.item_hash) {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
+    }
+    Ok(())
+}
+
+/// Items attached to a space; items that no longer exist are left out
+#[hdk_extern]
+pub fn get_space_equipment(space_hash: ActionHash) -> ExternResult<Vec<SpaceEquipment>> {
+    let mut equipment = Vec::new();
+    for link in equipment_links(&space_hash)? {
+        let Some(item_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(item_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        if let Some(item) = record.entry().to_app_option::<EquipmentItem>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        {
+            equipment.push(SpaceEquipment {
+                item_hash,
+                title: item.title,
+                auto_reserve: link.tag.0 == EQUIPMENT_AUTO_RESERVE_TAG,
+            });
+        }
+    }
+    equipment.sort_by(|a, b| a.title.cmp(&b.title));
+    Ok(equipment)
+}
+
+/// Hold a space's auto-reserve equipment for its upcoming reservations
+///
+/// Only an item's owner can block it off in the toolshed, so the manager
+/// runs this: each reservation that holds its slot and hasn't ended gets a
+/// matching unavailability window on every auto-reserve item, unless one
+/// is already there. Returns the windows created.
+#[hdk_extern]
+pub fn reserve_space_equipment(space_hash: ActionHash) -> ExternResult<Vec<ActionHash>> {
+    let manager = agent_info()?.agent_initial_pubkey;
+    let space: Space = get_entry(space_hash.clone(), "Space")?;
+    if space.manager != manager {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the space manager can reserve its equipment".into()
+        )));
+    }
+    
+    let auto_reserved: Vec<SpaceEquipment> = get_space_equipment(space_hash.clone())?
+        .into_iter()
+        .filter(|equipment| equipment.auto_reserve)
+        .collect();
+    if auto_reserved.is_empty() {
+        return Ok(Vec::new());
+    }
+    
+    let now = sys_time()?;
+    let (_, reservations) = settled_reservations(space_hash)?;
+    let upcoming: Vec<Reservation> = reservations
+        .into_iter()
+        .filter(|output| output.holds() && output.reservation.end_time > now)
+        .map(|output| output.reservation)
+        .collect();
+    
+    let mut created = Vec::new();
+    for equipment in auto_reserved {
+        let windows: Vec<UnavailabilityWindowOutput> =
+            call_zome("toolshed", "get_unavailability_windows", equipment.item_hash.clone())?;
+        for reservation in &upcoming {
+            let already_held = windows.iter().any(|output| {
+                output.window.starts_at == reservation.start_time && output.window.ends_at == reservation.end_time
+            });
+            if already_held {
+                continue;
+            }
+            let window: UnavailabilityWindowOutput = call_zome(
+                "toolshed",
+                "schedule_unavailability",
+                ScheduleUnavailabilityInput {
+                    item_hash: equipment.item_hash.clone(),
+                    starts_at: reservation.start_time,
+                    ends_at: reservation.end_time,
+                    reason: Some(format!("Reserved with {}", space.name)),
+                },
+            )?;
+            created.push(window.action_hash);
+        }
+    }
+    Ok(created)
+}
+
 // ============================================================================
 // RESERVATION MANAGEMENT
 // ============================================================================
@@ -145,17 +571,53 @@ pub fn get_all_spaces(_: ()) -> ExternResult<Vec<SpaceOutput>> {
 pub fn create_reservation(input: CreateReservationInput) -> ExternResult<ReservationOutput> {
     let reserver = agent_info()?.agent_initial_pubkey;
     
-    // Check for conflicts with existing reservations
+    // A resubmission would otherwise conflict with its own original
+    if let Some(original) = find_previous_create(
+        input.idempotency_key.as_deref(),
+        reserver.clone(),
+        LinkTypes::AgentToReservations,
+    )? {
+        let reservation: Reservation = get_entry(original.clone(), "Original reservation")?;
+        return Ok(ReservationOutput {
+            entry_hash: hash_entry(&reservation)?,
+            reservation,
+            action_hash: original,
+            remaining_capacity: None,
+            superseded: false,
+            awaiting_approval: false,
+        });
+    }
+    
+    let space: Space = get_entry(input.space_hash.clone(), "Space")?;
+    let party_size = input.party_size.unwrap_or(1);
+    if party_size == 0 || party_size > space.capacity {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Party size must be between 1 and {}",
+            space.capacity
+        ))));
+    }
+    
+    // Check for conflicts with existing reservations; those awaiting approval don't hold a slot yet
     let existing_reservations = get_space_reservations(input.space_hash.clone())?;
+    let (known_overlaps, overlapping): (Vec<ActionHash>, Vec<Reservation>) = existing_reservations
+        .into_iter()
+        .filter(|output| !output.awaiting_approval)
+        .filter(|output| overlaps(&output.reservation, input.start_time, input.end_time))
+        .map(|output| (output.action_hash, output.reservation))
+        .unzip();
     
-    for res_output in existing_reservations {
-        let res = res_output.reservation;
-        // Check if times overlap
-        if !(input.end_time <= res.start_time || input.start_time >= res.end_time) {
-            return Err(wasm_error!(WasmErrorInner::Guest(
-                "Time slot conflicts with existing reservation".into()
-            )));
-        }
+    if !space.allow_shared_bookings && !overlapping.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Time slot conflicts with existing reservation".into()
+        )));
+    }
+    let booked = booked_places(overlapping.iter());
+    if booked + party_size > space.capacity {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Only {} of {} places are free for that time slot",
+            space.capacity.saturating_sub(booked),
+            space.capacity
+        ))));
     }
     
     let reservation = Reservation {
@@ -165,8 +627,26 @@ pub fn create_reservation(input: CreateReservationInput) -> ExternResult<Reserva
         end_time: input.end_time,
         purpose: input.purpose,
         created_at: sys_time()?,
+        party_size,
+        known_overlaps,
     };
     
+    // Integrity enforces the same rules
+    space.booking_rules
+        .check(&reservation, reservation.created_at)
+        .map_err(|message| wasm_error!(WasmErrorInner::Guest(message)))?;
+    if let Some(max_per_week) = space.booking_rules.max_bookings_per_week {
+        if my_bookings_in_week(&input.space_hash, booking_week(input.start_time))? >= max_per_week {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Only {} reservations of this space are allowed per week",
+                max_per_week
+            ))));
+        }
+    }
+    
+    let awaiting_approval = approval_required(&space)?;
+    
+    ensure_membership_proof()?;
     let action_hash = create_entry(EntryTypes::Reservation(reservation.clone()))?;
     let entry_hash = hash_entry(&reservation)?;
     
@@ -178,16 +658,16 @@ pub fn create_reservation(input: CreateReservationInput) -> ExternResult<Reserva
         (),
     )?;
     
-    // Link from agent to reservation
+    // Link from agent to reservation, tagged with the idempotency key
     create_link(
         reserver,
         action_hash.clone(),
         LinkTypes::AgentToReservations,
-        (),
+        idempotency_tag(input.idempotency_key.as_deref())?,
     )?;
     
     // Emit signal for real-time updates
-    emit_signal(Signal::NewReservation {
+    emit_zome_signal(Signal::NewReservation {
         space_hash: input.space_hash,
         reservation_hash: action_hash.clone(),
     })?;
@@ -196,39 +676,45 @@ pub fn create_reservation(input: CreateReservationInput) -> ExternResult<Reserva
         reservation,
         action_hash,
         entry_hash,
+        remaining_capacity: Some(space.capacity - booked - party_size),
+        superseded: false,
+        awaiting_approval,
     })
 }
 
-/// Get all reservations for a space
+/// Get all reservations for a space, each with the places left while it runs
+///
+/// Reservations that lost a booking race to one made at the same moment
+/// are left out; their reservers see them as superseded. Reservations
+/// awaiting the manager's approval are listed but don't take up places.
 #[hdk_extern]
 pub fn get_space_reservations(space_hash: ActionHash) -> ExternResult<Vec<ReservationOutput>> {
-    let links = get_links(
-        LinkQuery::try_new(space_hash, LinkTypes::SpaceToReservations)?,
-        GetStrategy::Local,
-    )?;
+    let (space, settled) = settled_reservations(space_hash)?;
     
-    let mut reservations = Vec::new();
+    // Reservations that lost a booking race don't hold their slot
+    let mut reservations: Vec<ReservationOutput> = settled
+        .into_iter()
+        .filter(|output| !output.superseded)
+        .collect();
     
-    for link in links {
-        if let Some(action_hash) = link.target.into_action_hash() {
-            if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
-                if let Some(reservation) = record.entry().to_app_option::<Reservation>()
-                    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
-                {
-                    let entry_hash = hash_entry(&reservation)?;
-                    reservations.push(ReservationOutput {
-                        reservation,
-                        action_hash,
-                        entry_hash,
-                    });
-                }
-            }
-        }
+    let remaining: Vec<u32> = reservations
+        .iter()
+        .map(|output| {
+            let res = &output.reservation;
+            let booked = booked_places(
+                reservations
+                    .iter()
+                    .filter(|other| !other.awaiting_approval)
+                    .map(|other| &other.reservation)
+                    .filter(|other| overlaps(other, res.start_time, res.end_time)),
+            );
+            space.capacity.saturating_sub(booked)
+        })
+        .collect();
+    for (output, remaining) in reservations.iter_mut().zip(remaining) {
+        output.remaining_capacity = Some(remaining);
     }
     
-    // Sort by start_time
-    reservations.sort_by(|a, b| a.reservation.start_time.cmp(&b.reservation.start_time));
-    
     Ok(reservations)
 }
 
@@ -242,28 +728,152 @@ pub fn get_my_reservations(_: ()) -> ExternResult<Vec<ReservationOutput>> {
         GetStrategy::Local,
     )?;
     
+    let mine: BTreeSet<ActionHash> = links
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+        .collect();
+    let mut space_hashes = BTreeSet::new();
+    for action_hash in &mine {
+        if let Some((_, output)) = reservation_output(action_hash.clone())? {
+            space_hashes.insert(output.reservation.space_hash);
+        }
+    }
+    
+    // Settled per space, so reservations that lost a booking race are marked
     let mut reservations = Vec::new();
+    for space_hash in space_hashes {
+        let (_, settled) = settled_reservations(space_hash)?;
+        reservations.extend(settled.into_iter().filter(|output| mine.contains(&output.action_hash)));
+    }
+    
+    reservations.sort_by(|a, b| a.reservation.start_time.cmp(&b.reservation.start_time));
     
+    Ok(reservations)
+}
+
+/// A space's reservations, by start time, each marked superseded if it lost
+/// its slot under the tie-break rule in `holding_reservations`, or awaiting
+/// approval if the space requires it. Only approved reservations compete for
+/// slots.
+fn settled_reservations(space_hash: ActionHash) -> ExternResult<(Space, Vec<ReservationOutput>)> {
+    let space: Space = get_entry(space_hash.clone(), "Space")?;
+    let approval_required = approval_required(&space)?;
+    let links = get_links(
+        LinkQuery::try_new(space_hash, LinkTypes::SpaceToReservations)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut booked = Vec::new();
     for link in links {
         if let Some(action_hash) = link.target.into_action_hash() {
-            if let Some(record) = get(action_hash.clone(), GetOptions::default())? {
-                if let Some(reservation) = record.entry().to_app_option::<Reservation>()
-                    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
-                {
-                    let entry_hash = hash_entry(&reservation)?;
-                    reservations.push(ReservationOutput {
-                        reservation,
-                        action_hash,
-                        entry_hash,
-                    });
-                }
+            if let Some((booked_at, output)) = reservation_output(action_hash)? {
+                let awaiting_approval = approval_required && !is_approved(&space, &output.action_hash)?;
+                booked.push((booked_at, ReservationOutput { awaiting_approval, ..output }));
             }
         }
     }
+    let (waiting, booked): (Vec<_>, Vec<_>) = booked.into_iter().partition(|(_, output)| output.awaiting_approval);
     
+    let holds = holding_reservations(
+        &space,
+        &booked
+            .iter()
+            .map(|(booked_at, output)| (&output.action_hash, *booked_at, &output.reservation))
+            .collect::<Vec<_>>(),
+    );
+    let mut reservations: Vec<ReservationOutput> = booked
+        .into_iter()
+        .zip(holds)
+        .map(|((_, output), holds)| ReservationOutput {
+            superseded: !holds,
+            ..output
+        })
+        .chain(waiting.into_iter().map(|(_, output)| output))
+        .collect();
     reservations.sort_by(|a, b| a.reservation.start_time.cmp(&b.reservation.start_time));
     
-    Ok(reservations)
+    Ok((space, reservations))
+}
+
+/// A reservation with when it was booked, if it can be found
+fn reservation_output(action_hash: ActionHash) -> ExternResult<Option<(Timestamp, ReservationOutput)>> {
+    let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+        return Ok(None);
+    };
+    let Some(reservation) = record
+        .entry()
+        .to_app_option::<Reservation>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    else {
+        return Ok(None);
+    };
+    
+    Ok(Some((
+        record.action().timestamp(),
+        ReservationOutput {
+            entry_hash: hash_entry(&reservation)?,
+            reservation,
+            action_hash,
+            remaining_capacity: None,
+            superseded: false,
+            awaiting_approval: false,
+        },
+    )))
+}
+
+/// Approve a reservation of a space I manage so it can hold its slot
+///
+/// Only needed when the space or the neighborhood settings require approval.
+/// Fails if the reservation no longer fits alongside the ones that hold.
+#[hdk_extern]
+pub fn approve_reservation(reservation_hash: ActionHash) -> ExternResult<ActionHash> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let reservation: Reservation = get_entry(reservation_hash.clone(), "Reservation")?;
+    let (space, settled) = settled_reservations(reservation.space_hash.clone())?;
+    if space.manager != me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the space manager can approve reservations".into()
+        )));
+    }
+    if !settled
+        .iter()
+        .any(|output| output.action_hash == reservation_hash && output.awaiting_approval)
+    {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "This reservation is not awaiting approval".into()
+        )));
+    }
+    
+    let holding: Vec<&Reservation> = settled
+        .iter()
+        .filter(|output| output.holds())
+        .map(|output| &output.reservation)
+        .filter(|other| overlaps(other, reservation.start_time, reservation.end_time))
+        .collect();
+    let fits = if space.allow_shared_bookings {
+        booked_places(holding.into_iter()) + reservation.party_size <= space.capacity
+    } else {
+        holding.is_empty()
+    };
+    if !fits {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "The time slot has been taken since this reservation was made".into()
+        )));
+    }
+    
+    let approval_hash = create_link(
+        reservation_hash.clone(),
+        reservation.space_hash.clone(),
+        LinkTypes::ReservationToApprovals,
+        (),
+    )?;
+    
+    emit_zome_signal(Signal::ReservationApproved {
+        space_hash: reservation.space_hash,
+        reservation_hash,
+    })?;
+    
+    Ok(approval_hash)
 }
 
 /// Cancel a reservation
@@ -273,11 +883,592 @@ pub fn cancel_reservation(reservation_hash: ActionHash) -> ExternResult<()> {
     Ok(())
 }
 
+/// Cancel every reservation of mine that hasn't started yet
+///
+/// Part of account deactivation; returns the cancelled reservations.
+#[hdk_extern]
+pub fn cancel_my_future_reservations(_: ()) -> ExternResult<Vec<ActionHash>> {
+    let now = sys_time()?;
+    let mut cancelled = Vec::new();
+    for output in get_my_reservations(())? {
+        if output.reservation.start_time > now {
+            cancel_reservation(output.action_hash.clone())?;
+            cancelled.push(output.action_hash);
+        }
+    }
+    Ok(cancelled)
+}
+
+// ============================================================================
+// ACCESS INSTRUCTIONS
+// ============================================================================
+
+/// Set the door code or key handoff notes for a space I manage
+///
+/// The instructions are kept on my own chain and never published; reservers
+/// get them from `get_access_instructions` while I'm online.
+#[hdk_extern]
+pub fn set_access_instructions(input: SetAccessInstructionsInput) -> ExternResult<ActionHash> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let space: Space = get_entry(input.space_hash.clone(), "Space")?;
+    if space.manager != me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the space manager can set access instructions".into()
+        )));
+    }
+    
+    create_entry(EntryTypes::AccessInstruction(AccessInstruction {
+        space_hash: input.space_hash,
+        instructions: input.instructions.trim().to_string(),
+        updated_at: sys_time()?,
+    }))
+}
+
+/// The current access instructions for a space I manage
+#[hdk_extern]
+pub fn get_space_access_instructions(space_hash: ActionHash) -> ExternResult<Option<AccessInstruction>> {
+    latest_access_instruction(&space_hash)
+}
+
+/// Fetch the access instructions for one of my reservations from the space's manager
+///
+/// Only works from ACCESS_LEAD_TIME_SECS before the reservation starts until
+/// it ends, and only while the manager is online.
+#[hdk_extern]
+pub fn get_access_instructions(reservation_hash: ActionHash) -> ExternResult<AccessInstructionsOutput> {
+    let reservation: Reservation = get_entry(reservation_hash.clone(), "Reservation")?;
+    let space: Space = get_entry(reservation.space_hash.clone(), "Space")?;
+    
+    if space.manager == agent_info()?.agent_initial_pubkey {
+        return reveal_access_instructions(reservation_hash);
+    }
+    
+    match call_remote(
+        space.manager,
+        zome_info()?.name,
+        FunctionName::from("reveal_access_instructions"),
+        None,
+        reservation_hash,
+    )? {
+        ZomeCallResponse::Ok(result) => result.decode().map_err(|e| wasm_error!(e)),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Could not reach the space manager: {:?}",
+            other
+        )))),
+    }
+}
+
+/// Reveal a space's access instructions to the holder of a live reservation
+/// that holds its slot and, if required, has been approved (called remotely
+/// by the reserver)
+#[hdk_extern]
+pub fn reveal_access_instructions(reservation_hash: ActionHash) -> ExternResult<AccessInstructionsOutput> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let caller = call_info()?.provenance;
+    
+    let Some(details) = get_details(reservation_hash.clone(), GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Reservation not found".into())));
+    };
+    let Details::Record(details) = details else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Reservation not found".into())));
+    };
+    if !details.deletes.is_empty() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Reservation was cancelled".into())));
+    }
+    let reservation = details
+        .record
+        .entry()
+        .to_app_option::<Reservation>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Not a reservation".into())))?;
+    
+    if reservation.reserver != caller {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the reserver can see access instructions".into()
+        )));
+    }
+    let (space, settled) = settled_reservations(reservation.space_hash.clone())?;
+    if space.manager != me {
+        return Err(wasm_error!(WasmErrorInner::Guest("I don't manage this space".into())));
+    }
+    
+    // Only a reservation that holds its slot gets in
+    let Some(settled) = settled.into_iter().find(|output| output.action_hash == reservation_hash) else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Reservation not found".into())));
+    };
+    if settled.awaiting_approval {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "This reservation is still waiting for the manager's approval".into()
+        )));
+    }
+    if settled.superseded {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "This reservation lost its slot to one booked at the same moment".into()
+        )));
+    }
+    
+    let now = sys_time()?;
+    let opens_at = Timestamp::from_micros(
+        reservation.start_time.as_micros() - ACCESS_LEAD_TIME_SECS * 1_000_000,
+    );
+    if now < opens_at || now > reservation.end_time {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Access instructions are only available around the reservation time".into()
+        )));
+    }
+    
+    let Some(access) = latest_access_instruction(&reservation.space_hash)? else {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "This space has no access instructions".into()
+        )));
+    };
+    
+    Ok(AccessInstructionsOutput {
+        space_hash: reservation.space_hash,
+        reservation_hash,
+        instructions: access.instructions,
+        valid_until: reservation.end_time,
+    })
+}
+
+// ============================================================================
+// CHECKLISTS AND CONDITION REPORTS
+// ============================================================================
+
+/// Set the checklist reservers fill in after using a space I manage
+#[hdk_extern]
+pub fn set_space_checklist(input: SetChecklistInput) -> ExternResult<SpaceChecklist> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let space: Space = get_entry(input.space_hash.clone(), "Space")?;
+    if space.manager != me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the space manager can set its checklist".into()
+        )));
+    }
+    
+    let checklist = SpaceChecklist {
+        space_hash: input.space_hash.clone(),
+        items: input.items.iter().map(|item| item.trim().to_string()).collect(),
+        created_at: sys_time()?,
+    };
+    let action_hash = create_entry(EntryTypes::SpaceChecklist(checklist.clone()))?;
+    create_link(input.space_hash, action_hash, LinkTypes::SpaceToChecklists, ())?;
+    
+    Ok(checklist)
+}
+
+/// The current checklist for a space, if its manager has set one
+#[hdk_extern]
+pub fn get_space_checklist(space_hash: ActionHash) -> ExternResult<Option<SpaceChecklist>> {
+    let mut links = get_links(
+        LinkQuery::try_new(space_hash.clone(), LinkTypes::SpaceToChecklists)?,
+        GetStrategy::Local,
+    )?;
+    links.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(checklist) = find_entry::<SpaceChecklist>(action_hash)? else {
+            continue;
+        };
+        if checklist.space_hash == space_hash {
+            return Ok(Some(checklist));
+        }
+    }
+    
+    Ok(None)
+}
+
+/// Report the state I left a space in after one of my reservations
+#[hdk_extern]
+pub fn submit_condition_report(input: SubmitConditionReportInput) -> ExternResult<ConditionReportOutput> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let reservation: Reservation = get_entry(input.reservation_hash.clone(), "Reservation")?;
+    if reservation.reserver != me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the reserver can report on a reservation".into()
+        )));
+    }
+    let created_at = sys_time()?;
+    if created_at < reservation.start_time {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Condition reports can only be submitted once the reservation has started".into()
+        )));
+    }
+    let (_, settled) = settled_reservations(reservation.space_hash.clone())?;
+    if !settled
+        .iter()
+        .any(|output| output.action_hash == input.reservation_hash && output.holds())
+    {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only a reservation that holds its slot can be reported on".into()
+        )));
+    }
+    if get_reservation_condition_report(input.reservation_hash.clone())?.is_some() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "This reservation already has a condition report".into()
+        )));
+    }
+    
+    let report = ConditionReport {
+        reservation_hash: input.reservation_hash.clone(),
+        space_hash: reservation.space_hash.clone(),
+        reporter: me,
+        checklist: input.checklist,
+        notes: input.notes.filter(|notes| !notes.trim().is_empty()),
+        photos: input.photos,
+        created_at,
+    };
+    let action_hash = create_entry(EntryTypes::ConditionReport(report.clone()))?;
+    
+    create_link(
+        input.reservation_hash,
+        action_hash.clone(),
+        LinkTypes::ReservationToReports,
+        (),
+    )?;
+    create_link(
+        reservation.space_hash,
+        action_hash.clone(),
+        LinkTypes::SpaceToReports,
+        (),
+    )?;
+    
+    Ok(ConditionReportOutput {
+        has_problems: report.has_problems(),
+        report,
+        action_hash,
+    })
+}
+
+/// The condition report left for a reservation, if any
+#[hdk_extern]
+pub fn get_reservation_condition_report(reservation_hash: ActionHash) -> ExternResult<Option<ConditionReportOutput>> {
+    let links = get_links(
+        LinkQuery::try_new(reservation_hash.clone(), LinkTypes::ReservationToReports)?,
+        GetStrategy::Local,
+    )?;
+    
+    for link in links {
+        if let Some(output) = condition_report_output(link)? {
+            if output.report.reservation_hash == reservation_hash {
+                return Ok(Some(output));
+            }
+        }
+    }
+    
+    Ok(None)
+}
+
+/// Condition reports for a space I manage, newest first
+#[hdk_extern]
+pub fn get_space_condition_reports(input: SpaceReportsInput) -> ExternResult<Vec<ConditionReportOutput>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let space: Space = get_entry(input.space_hash.clone(), "Space")?;
+    if space.manager != me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the space manager can review condition reports".into()
+        )));
+    }
+    
+    let links = get_links(
+        LinkQuery::try_new(input.space_hash.clone(), LinkTypes::SpaceToReports)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut reports = Vec::new();
+    for link in links {
+        let Some(output) = condition_report_output(link)? else {
+            continue;
+        };
+        if output.report.space_hash != input.space_hash {
+            continue;
+        }
+        if input.problems_only && !output.has_problems {
+            continue;
+        }
+        reports.push(output);
+    }
+    
+    reports.sort_by(|a, b| b.report.created_at.cmp(&a.report.created_at));
+    Ok(reports)
+}
+
 // ============================================================================
 // HELPER FUNCTIONS
 // ============================================================================
 
+fn condition_report_output(link: Link) -> ExternResult<Option<ConditionReportOutput>> {
+    let Some(action_hash) = link.target.into_action_hash() else {
+        return Ok(None);
+    };
+    let Some(report) = find_entry::<ConditionReport>(action_hash.clone())? else {
+        return Ok(None);
+    };
+    Ok(Some(ConditionReportOutput {
+        has_problems: report.has_problems(),
+        report,
+        action_hash,
+    }))
+}
+
+fn latest_access_instruction(space_hash: &ActionHash) -> ExternResult<Option<AccessInstruction>> {
+    Ok(my_chain_entries::<AccessInstruction>(UnitEntryTypes::AccessInstruction)?
+        .into_iter()
+        .rev()
+        .map(|(_, access)| access)
+        .find(|access| access.space_hash == *space_hash))
+}
+
 fn all_spaces_anchor_hash() -> ExternResult<EntryHash> {
     let path = Path::from(ALL_SPACES_PATH);
     path.path_entry_hash()
 }
+
+fn space_output(action_hash: ActionHash, space: Space) -> ExternResult<SpaceOutput> {
+    Ok(SpaceOutput {
+        entry_hash: hash_entry(&space)?,
+        equipment: get_space_equipment(action_hash.clone())?,
+        space,
+        action_hash,
+    })
+}
+
+fn equipment_links(space_hash: &ActionHash) -> ExternResult<Vec<Link>> {
+    get_links(
+        LinkQuery::try_new(space_hash.clone(), LinkTypes::SpaceToEquipment)?,
+        GetStrategy::Local,
+    )
+}
+
+fn pending_spaces_anchor_hash() -> ExternResult<EntryHash> {
+    let path = Path::from(PENDING_SPACES_PATH);
+    path.path_entry_hash()
+}
+
+fn approved_space_hashes() -> ExternResult<BTreeSet<ActionHash>> {
+    let links = get_links(
+        LinkQuery::try_new(all_spaces_anchor_hash()?, LinkTypes::AllSpaces)?,
+        GetStrategy::Network,
+    )?;
+    Ok(links.into_iter().filter_map(|link| link.target.into_action_hash()).collect())
+}
+
+/// Whether reservations of the space wait for the manager's approval
+fn approval_required(space: &Space) -> ExternResult<bool> {
+    Ok(space.booking_rules.require_approval || neighborhood_features()?.require_reservation_approval)
+}
+
+/// Whether the space's manager has approved the reservation
+fn is_approved(space: &Space, reservation_hash: &ActionHash) -> ExternResult<bool> {
+    let links = get_links(
+        LinkQuery::try_new(reservation_hash.clone(), LinkTypes::ReservationToApprovals)?,
+        GetStrategy::Local,
+    )?;
+    Ok(links.iter().any(|link| link.author == space.manager))
+}
+
+fn neighborhood_features() -> ExternResult<NeighborhoodFeatures> {
+    Ok(call_zome::<_, NeighborhoodSettings>("settings", "get_neighborhood_settings", ())?.features)
+}
+
+/// Our uncancelled reservations of a space starting in the given week
+fn my_bookings_in_week(space_hash: &ActionHash, week: i64) -> ExternResult<u32> {
+    let cancelled: BTreeSet<ActionHash> = query(ChainQueryFilter::new().action_type(ActionType::Delete))?
+        .into_iter()
+        .filter_map(|record| match record.action() {
+            Action::Delete(delete) => Some(delete.deletes_address.clone()),
+            _ => None,
+        })
+        .collect();
+    
+    let count = my_chain_entries::<Reservation>(UnitEntryTypes::Reservation)?
+        .into_iter()
+        .filter(|(hash, reservation)| {
+            !cancelled.contains(hash)
+                && reservation.space_hash == *space_hash
+                && booking_week(reservation.start_time) == week
+        })
+        .count();
+    Ok(count as u32)
+}
+
+fn overlaps(reservation: &Reservation, start_time: Timestamp, end_time: Timestamp) -> bool {
+    start_time < reservation.end_time && end_time > reservation.start_time
+}
+
+/// Summed party sizes; treats every reservation given as running at once,
+/// which over-counts when they overlap the slot but not each other
+fn booked_places<'a>(reservations: impl Iterator<Item = &'a Reservation>) -> u32 {
+    reservations.map(|res| res.party_size).sum()
+}
+
+fn get_entry<T>(action_hash: ActionHash, what: &str) -> ExternResult<T>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let Some(record) = get(action_hash, GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("{} not found", what))));
+    };
+    record
+        .entry()
+        .to_app_option::<T>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!("{} is not a valid entry", what))))
+}
+
+/// Like `get_entry`, but `None` for a link target that hasn't resolved yet or
+/// isn't the expected entry, so one bad link can't fail a whole listing
+fn find_entry<T>(action_hash: ActionHash) -> ExternResult<Option<T>>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let Some(record) = get(action_hash, GetOptions::default())? else {
+        return Ok(None);
+    };
+    Ok(record.entry().to_app_option::<T>().ok().flatten())
+}
+
+/// Add a located space to the search zome's geohash index, if it has a geohash
+fn index_place(target: ActionHash, title: &str, place: &Option<Location>) -> ExternResult<()> {
+    let Some(geohash) = place.as_ref().and_then(|place| place.geohash.clone()) else {
+        return Ok(());
+    };
+    
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("search"),
+        FunctionName::from("index_location"),
+        None,
+        LocationIndexInput {
+            kind: SearchKind::Space,
+            target,
+            title: title.to_string(),
+            geohash,
+        },
+    )?;
+    
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to update location index: {:?}",
+            other
+        )))),
+    }
+}
+
+fn call_zome<I, O>(zome: &str, fn_name: &str, payload: I) -> ExternResult<O>
+where
+    I: serde::Serialize + std::fmt::Debug,
+    O: serde::de::DeserializeOwned + std::fmt::Debug,
+{
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from(zome),
+        FunctionName::from(fn_name),
+        None,
+        payload,
+    )?;
+    
+    match response {
+        ZomeCallResponse::Ok(result) => result.decode().map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "Failed to decode {} response: {:?}",
+                zome, e
+            )))
+        }),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to call {}.{}: {:?}",
+            zome, fn_name, other
+        )))),
+    }
+}
+
+/// Only members vouched into the neighborhood may create spaces
+fn require_verified() -> ExternResult<()> {
+    if !call_zome::<_, bool>("vouch", "am_i_verified", ())? {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only verified members can create spaces".to_string()
+        )));
+    }
+    Ok(())
+}
+
+/// Our TrustedAnchor record, or an error if we aren't an anchor
+fn require_anchor() -> ExternResult<ActionHash> {
+    call_zome::<_, Option<ActionHash>>("vouch", "get_my_anchor_record", ())?.ok_or(wasm_error!(WasmErrorInner::Guest(
+        "Only trusted anchors can approve spaces".to_string()
+    )))
+}
+
+/// Have the vouch zome put a MembershipProof on our chain if we don't have
+/// one yet; integrity rejects spaces and reservations from authors without one
+fn ensure_membership_proof() -> ExternResult<()> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("vouch"),
+        FunctionName::from("ensure_membership_proof"),
+        None,
+        (),
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to check membership proof: {:?}",
+            other
+        )))),
+    }
+}
+
+// ============================================================================
+// DATA EXPORT
+// ============================================================================
+
+/// Spaces the calling agent created and their reservations
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SpacesExport {
+    pub spaces: Vec<SpaceOutput>,
+    pub reservations: Vec<ReservationOutput>,
+}
+
+/// Export the caller's shared spaces footprint
+#[hdk_extern]
+pub fn export_my_data(_: ()) -> ExternResult<SpacesExport> {
+    let mut spaces = Vec::new();
+    for (action_hash, space) in my_chain_entries::<Space>(UnitEntryTypes::Space)? {
+        spaces.push(space_output(action_hash, space)?);
+    }
+
+    Ok(SpacesExport {
+        spaces,
+        reservations: get_my_reservations(())?,
+    })
+}
+
+/// Every entry of one type on my own source chain, oldest first
+fn my_chain_entries<T>(entry_type: UnitEntryTypes) -> ExternResult<Vec<(ActionHash, T)>>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(entry_type.try_into()?)
+            .include_entries(true),
+    )?;
+
+    let mut entries = Vec::new();
+    for record in records {
+        if let Some(entry) = record
+            .entry()
+            .to_app_option::<T>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        {
+            entries.push((record.action_address().clone(), entry));
+        }
+    }
+
+    Ok(entries)
+}
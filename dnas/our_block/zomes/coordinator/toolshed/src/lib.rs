@@ -9,7 +9,8 @@
 //! 2. Owner sees the request and calls `accept_borrow` to initiate countersigning
 //! 3. Both parties sign the Transaction entry
 //! 4. Upon successful countersign, item status is updated to Borrowed
-//! 5. When returned, `return_item` is called to complete the transaction
+//! 5. Both parties call `confirm_pickup` when the item changes hands
+//! 6. Both parties call `confirm_dropoff` when it comes back, completing the transaction
 //!
 //! ## Countersigning
 //!
@@ -17,8 +18,36 @@
 //! on the borrow terms before the transaction is committed.
 
 use hdk::prelude::*;
+use idempotency::{find_previous_create, idempotency_tag};
+use list_options::{get_list_links, ListOptions};
+use publish_status::{publish_status, PublishStatusOutput};
+use signal_envelope::SignalEnvelope;
+use std::collections::{BTreeSet, HashSet};
 use toolshed_integrity::*;
 
+/// Signal types for real-time updates, emitted inside a SignalEnvelope
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "event", content = "payload")]
+pub enum Signal {
+    /// Sent by a requester to the item owner when they ask to borrow it
+    BorrowRequested { request_hash: ActionHash, item_hash: ActionHash, title: String },
+    /// Sent by the item owner to the requester when they accept
+    BorrowAccepted { request_hash: ActionHash, transaction_hash: ActionHash, item_hash: ActionHash },
+    /// Sent by the item owner to the requester when they decline
+    BorrowDeclined { request_hash: ActionHash, item_hash: ActionHash, reason: Option<String> },
+    /// Sent by the item owner to a requester whose request expired unanswered
+    BorrowRequestLapsed { request_hash: ActionHash, item_hash: ActionHash },
+    /// Sent by a neighbor posting a wanted item to owners of similar items
+    WantedItemMatch { wanted_hash: ActionHash, item_hash: ActionHash, title: String },
+    /// Sent by an item owner to the neighbor whose wanted item they offered to fill
+    WantedFulfilled { wanted_hash: ActionHash, item_hash: ActionHash },
+    /// Sent by one party of a borrow to the other after confirming a handoff
+    HandoffConfirmed { transaction_hash: ActionHash, handoff: Handoff, status: TransactionStatus },
+}
+
+/// Zome name clients see in the signal envelope
+const SIGNAL_ZOME: &str = "toolshed";
+
 /// Input for creating an item
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CreateItemInput {
@@ -27,6 +56,17 @@ pub struct CreateItemInput {
     pub image_hash: Option<EntryHash>,
     pub consumables: Vec<Consumable>,
     pub notes: String,
+    /// Deposit, replacement value, and care instructions borrowers agree to
+    #[serde(default)]
+    pub lending_terms: Option<LendingTerms>,
+    #[serde(default)]
+    pub place: Option<Location>,
+    /// Brand, model, manual, and other catalog details
+    #[serde(default)]
+    pub catalog: Option<CatalogInfo>,
+    /// Client-chosen key; resubmitting with the same key returns the original
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 /// Output for item operations
@@ -43,6 +83,9 @@ pub struct RequestBorrowInput {
     pub item_hash: ActionHash,
     pub requested_due_date: Timestamp,
     pub message: Option<String>,
+    /// Defaults to DEFAULT_BORROW_REQUEST_TTL_SECS from now
+    #[serde(default)]
+    pub expires_at: Option<Timestamp>,
 }
 
 /// Output for borrow request
@@ -61,7 +104,117 @@ pub struct AcceptBorrowInput {
     pub notes: Option<String>,
 }
 
-/// Output for transaction operations
+/// Input for turning down a borrow request
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DeclineBorrowInput {
+    pub request_hash: ActionHash,
+    /// Passed on to the requester
+    pub reason: Option<String>,
+}
+
+/// Borrow requests removed by `cleanup_my_expired_requests`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ExpiredRequestsCleanup {
+    /// Lapsed requests for my items; their requesters were notified
+    pub incoming: Vec<ActionHash>,
+    /// My own lapsed requests for other neighbors' items
+    pub outgoing: Vec<ActionHash>,
+}
+
+/// What `withdraw_from_lending` changed
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LendingWithdrawal {
+    /// My items that were Available and are now Unavailable
+    pub items_marked_unavailable: Vec<ActionHash>,
+    /// My open requests for other neighbors' items
+    pub requests_withdrawn: Vec<ActionHash>,
+}
+
+/// Input for blocking off time on one of my items
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScheduleUnavailabilityInput {
+    pub item_hash: ActionHash,
+    pub starts_at: Timestamp,
+    pub ends_at: Timestamp,
+    pub reason: Option<String>,
+}
+
+/// Output for unavailability windows
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UnavailabilityWindowOutput {
+    pub window: UnavailabilityWindow,
+    pub action_hash: ActionHash,
+}
+
+/// Items whose status `refresh_my_item_availability` changed
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AvailabilityRefresh {
+    /// Items a window has started for
+    pub made_unavailable: Vec<ActionHash>,
+    /// Items whose windows have all ended
+    pub restored: Vec<ActionHash>,
+}
+
+/// Lending history for one item
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ItemStats {
+    pub item_hash: ActionHash,
+    pub times_borrowed: u32,
+    pub currently_borrowed: bool,
+    pub last_borrowed_at: Option<Timestamp>,
+    /// Over returned loans only
+    pub average_loan_days: Option<f64>,
+    /// Consecutive most recent returns made by the due date
+    pub on_time_streak: u32,
+}
+
+/// Lending and borrowing history for one agent
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AgentLendingStats {
+    pub agent: AgentPubKey,
+    pub items_listed: u32,
+    pub times_lent: u32,
+    pub times_borrowed: u32,
+    pub active_loans_lent: u32,
+    pub active_loans_borrowed: u32,
+    /// Average length of this agent's returned loans, as lender
+    pub average_loan_days: Option<f64>,
+    /// Share of this agent's returned borrows that came back by the due date
+    pub on_time_return_rate: Option<f64>,
+    /// Consecutive most recent borrows this agent returned on time
+    pub on_time_streak: u32,
+}
+
+/// Input for asking the neighborhood for an item
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PostWantedInput {
+    pub title: String,
+    pub description: String,
+}
+
+/// A wanted item and the items owners have offered for it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WantedItemOutput {
+    pub wanted: WantedItem,
+    pub action_hash: ActionHash,
+    pub offered_items: Vec<ActionHash>,
+}
+
+/// A newly posted wanted item and the existing items that look like a match
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PostWantedOutput {
+    pub wanted: WantedItemOutput,
+    pub matches: Vec<ItemOutput>,
+}
+
+/// Input for offering one of my items for a wanted item
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FulfillWantedInput {
+    pub wanted_hash: ActionHash,
+    pub item_hash: ActionHash,
+}
+
+/// Output for transaction operations; `transaction.terms` holds the agreed terms
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct TransactionOutput {
     pub transaction: Transaction,
@@ -70,8 +223,42 @@ pub struct TransactionOutput {
     pub status: TransactionStatus,
 }
 
+/// Mirror of the search zome's IndexContentInput
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct SearchIndexInput {
+    kind: SearchKind,
+    target: ActionHash,
+    title: String,
+    text: String,
+}
+
+/// Mirror of the search zome's LocationIndexInput
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct LocationIndexInput {
+    kind: SearchKind,
+    target: ActionHash,
+    title: String,
+    geohash: String,
+}
+
+/// Subset of file_storage's `FileMetadataOutput`, enough to tell a file exists
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct StoredFile {
+    metadata_hash: ActionHash,
+}
+
+/// The search zome's ContentKind variant for tool shed items
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum SearchKind {
+    Item,
+}
+
 /// Anchor paths
 const ALL_ITEMS_PATH: &str = "all_items";
+const ALL_WANTED_PATH: &str = "all_wanted_items";
+
+/// Title words shorter than this are ignored when matching wanted items
+const MIN_MATCH_WORD_LENGTH: usize = 3;
 
 // ============================================================================
 // ITEM MANAGEMENT
@@ -82,6 +269,19 @@ const ALL_ITEMS_PATH: &str = "all_items";
 pub fn create_item(input: CreateItemInput) -> ExternResult<ItemOutput> {
     let owner = agent_info()?.agent_initial_pubkey;
     
+    if let Some(original) = find_previous_create(
+        input.idempotency_key.as_deref(),
+        owner.clone(),
+        LinkTypes::AgentToItems,
+    )? {
+        return get_item(original)?
+            .ok_or(wasm_error!(WasmErrorInner::Guest("Original item not found".into())));
+    }
+    
+    if let Some(manual) = input.catalog.as_ref().and_then(|catalog| catalog.manual.as_ref()) {
+        require_manual(manual)?;
+    }
+    
     let item = Item {
         title: input.title,
         description: input.description,
@@ -91,17 +291,21 @@ pub fn create_item(input: CreateItemInput) -> ExternResult<ItemOutput> {
         owner: owner.clone(),
         status: ItemStatus::Available,
         created_at: sys_time()?,
+        lending_terms: input.lending_terms,
+        place: input.place,
+        catalog: input.catalog,
     };
     
+    ensure_membership_proof()?;
     let action_hash = create_entry(EntryTypes::Item(item.clone()))?;
     let entry_hash = hash_entry(&item)?;
     
-    // Link from owner to item
+    // Link from owner to item, tagged with the idempotency key
     create_link(
         owner,
         action_hash.clone(),
         LinkTypes::AgentToItems,
-        (),
+        idempotency_tag(input.idempotency_key.as_deref())?,
     )?;
     
     // Link to all items
@@ -113,6 +317,9 @@ pub fn create_item(input: CreateItemInput) -> ExternResult<ItemOutput> {
         (),
     )?;
     
+    call_search("index_content", action_hash.clone(), &item.title, &item.description)?;
+    index_place(action_hash.clone(), &item.title, &item.place)?;
+    
     Ok(ItemOutput {
         item,
         action_hash,
@@ -122,12 +329,10 @@ pub fn create_item(input: CreateItemInput) -> ExternResult<ItemOutput> {
 
 /// Get all items in the Tool Shed
 #[hdk_extern]
-pub fn get_all_items(_: ()) -> ExternResult<Vec<ItemOutput>> {
+pub fn get_all_items(options: Option<ListOptions>) -> ExternResult<Vec<ItemOutput>> {
+    let options = options.unwrap_or_default();
     let all_items_anchor = all_items_anchor_hash()?;
-    let links = get_links(
-        LinkQuery::try_new(all_items_anchor, LinkTypes::AllItems)?,
-        GetStrategy::Local,
-    )?;
+    let links = get_list_links(all_items_anchor, LinkTypes::AllItems, options.prefer_network)?;
     
     let mut items = Vec::new();
     
@@ -220,6 +425,12 @@ pub fn get_item(action_hash: ActionHash) -> ExternResult<Option<ItemOutput>> {
     }))
 }
 
+/// How far one of my item listings has spread, from its validation receipts
+#[hdk_extern]
+pub fn get_publish_status(action_hash: ActionHash) -> ExternResult<PublishStatusOutput> {
+    publish_status::<Item>(action_hash, "Item")
+}
+
 /// Input for updating item status
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct UpdateStatusInput {
@@ -295,12 +506,63 @@ pub fn update_item(input: UpdateItemInput) -> ExternResult<ItemOutput> {
         )));
     }
     
+    // Drop the old keywords so searches don't keep matching the previous text
+    call_search("unindex_content", input.action_hash.clone(), &item.title, &item.description)?;
+    
     item.title = input.title;
     item.description = input.description;
     
     let new_action_hash = update_entry(input.action_hash, &item)?;
     let entry_hash = hash_entry(&item)?;
     
+    call_search("index_content", new_action_hash.clone(), &item.title, &item.description)?;
+    
+    Ok(ItemOutput {
+        item,
+        action_hash: new_action_hash,
+        entry_hash,
+    })
+}
+
+/// Input for replacing an item's catalog details
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UpdateCatalogInput {
+    pub action_hash: ActionHash,
+    /// None clears the catalog details
+    pub catalog: Option<CatalogInfo>,
+}
+
+/// Replace an item's brand, model, manual, and other catalog details (owner only)
+#[hdk_extern]
+pub fn update_item_catalog(input: UpdateCatalogInput) -> ExternResult<ItemOutput> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    
+    let Some(record) = get(input.action_hash.clone(), GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Item not found".to_string())));
+    };
+    
+    let Some(mut item) = record
+        .entry()
+        .to_app_option::<Item>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Invalid item entry".to_string())));
+    };
+    
+    if item.owner != agent {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the owner can update catalog details".to_string()
+        )));
+    }
+    if let Some(manual) = input.catalog.as_ref().and_then(|catalog| catalog.manual.as_ref()) {
+        require_manual(manual)?;
+    }
+    
+    item.catalog = input.catalog;
+    
+    let new_action_hash = update_entry(input.action_hash, &item)?;
+    let entry_hash = hash_entry(&item)?;
+    
     Ok(ItemOutput {
         item,
         action_hash: new_action_hash,
@@ -308,6 +570,21 @@ pub fn update_item(input: UpdateItemInput) -> ExternResult<ItemOutput> {
     })
 }
 
+/// My items that don't have a manual attached yet, so I can fill them in
+#[hdk_extern]
+pub fn get_items_missing_manuals(_: ()) -> ExternResult<Vec<ItemOutput>> {
+    Ok(get_my_items(())?
+        .into_iter()
+        .filter(|item_output| {
+            item_output
+                .item
+                .catalog
+                .as_ref()
+                .map_or(true, |catalog| catalog.manual.is_none())
+        })
+        .collect())
+}
+
 // ============================================================================
 // BORROW REQUEST FLOW
 // ============================================================================
@@ -338,15 +615,24 @@ pub fn request_borrow(input: RequestBorrowInput) -> ExternResult<BorrowRequestOu
         )));
     }
     
+    let created_at = sys_time()?;
+    require_no_unavailability(&input.item_hash, created_at, input.requested_due_date)?;
+    let expires_at = input.expires_at.unwrap_or_else(|| {
+        Timestamp::from_micros(created_at.as_micros() + DEFAULT_BORROW_REQUEST_TTL_SECS * 1_000_000)
+    });
+    
     let request = BorrowRequest {
         item_hash: input.item_hash.clone(),
         requester: requester.clone(),
         owner: item_output.item.owner.clone(),
         requested_due_date: input.requested_due_date,
         message: input.message,
-        created_at: sys_time()?,
+        created_at,
+        expires_at: Some(expires_at),
+        terms: item_output.item.lending_terms.clone(),
     };
     
+    ensure_membership_proof()?;
     let action_hash = create_entry(EntryTypes::BorrowRequest(request.clone()))?;
     let entry_hash = hash_entry(&request)?;
     
@@ -366,6 +652,15 @@ pub fn request_borrow(input: RequestBorrowInput) -> ExternResult<BorrowRequestOu
         (),
     )?;
     
+    send_remote_signal(
+        Signal::BorrowRequested {
+            request_hash: action_hash.clone(),
+            item_hash: request.item_hash.clone(),
+            title: item_output.item.title,
+        },
+        vec![request.owner.clone()],
+    )?;
+    
     Ok(BorrowRequestOutput {
         request,
         action_hash,
@@ -373,9 +668,10 @@ pub fn request_borrow(input: RequestBorrowInput) -> ExternResult<BorrowRequestOu
     })
 }
 
-/// Get borrow requests for an item (owner use)
+/// Get open borrow requests for an item (owner use); expired ones are left out
 #[hdk_extern]
 pub fn get_borrow_requests_for_item(item_hash: ActionHash) -> ExternResult<Vec<BorrowRequestOutput>> {
+    let now = sys_time()?;
     let links = get_links(
         LinkQuery::try_new(item_hash, LinkTypes::ItemToBorrowRequests)?,
         GetStrategy::Local,
@@ -394,6 +690,9 @@ pub fn get_borrow_requests_for_item(item_hash: ActionHash) -> ExternResult<Vec<B
                 .to_app_option::<BorrowRequest>()
                 .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
             {
+                if request.is_expired(now) {
+                    continue;
+                }
                 let entry_hash = hash_entry(&request)?;
                 requests.push(BorrowRequestOutput {
                     request,
@@ -443,170 +742,797 @@ pub fn get_my_borrow_requests(_: ()) -> ExternResult<Vec<BorrowRequestOutput>> {
     Ok(requests)
 }
 
-// ============================================================================
-// COUNTERSIGNING BORROW FLOW
-// ============================================================================
-
-/// Accept a borrow request and create a transaction
-///
-/// This function is called by the item owner to accept a borrow request.
-/// In a full countersigning implementation, this would:
-/// 1. Create a preflight request
-/// 2. Both parties sign
-/// 3. Commit the countersigned entry
+/// Turn down a borrow request for one of my items
 ///
-/// For now, we implement a simplified version that creates the transaction
-/// as a regular entry. True countersigning requires the unstable features
-/// and a more complex session management flow.
+/// The request is taken off the item and the requester gets a
+/// `BorrowDeclined` signal with the reason, if one was given.
 #[hdk_extern]
-pub fn accept_borrow(input: AcceptBorrowInput) -> ExternResult<TransactionOutput> {
-    let lender = agent_info()?.agent_initial_pubkey;
+pub fn decline_borrow(input: DeclineBorrowInput) -> ExternResult<()> {
+    let owner = agent_info()?.agent_initial_pubkey;
     
-    // Get the borrow request
-    let Some(record) = get(input.request_hash.clone(), GetOptions::default())? else {
+    let Some(request) = get_borrow_request(input.request_hash.clone())? else {
         return Err(wasm_error!(WasmErrorInner::Guest(
             "Borrow request not found".to_string()
         )));
     };
-    
-    let Some(request) = record
-        .entry()
-        .to_app_option::<BorrowRequest>()
-        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
-    else {
-        return Err(wasm_error!(WasmErrorInner::Guest(
-            "Invalid borrow request".to_string()
-        )));
-    };
-    
-    // Verify the caller is the item owner
-    if request.owner != lender {
+    if request.owner != owner {
         return Err(wasm_error!(WasmErrorInner::Guest(
-            "Only the item owner can accept borrow requests".to_string()
+            "Only the item owner can decline borrow requests".to_string()
         )));
     }
+    if input.reason.as_ref().is_some_and(|reason| reason.len() > MAX_MESSAGE_LENGTH) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Reason cannot exceed {} chars",
+            MAX_MESSAGE_LENGTH
+        ))));
+    }
     
-    // Get the item to verify it's still available
-    let Some(item_output) = get_item(request.item_hash.clone())? else {
-        return Err(wasm_error!(WasmErrorInner::Guest("Item not found".to_string())));
-    };
-    
-    if item_output.item.status != ItemStatus::Available {
-        return Err(wasm_error!(WasmErrorInner::Guest(
-            "Item is no longer available".to_string()
-        )));
+    let links = get_links(
+        LinkQuery::try_new(request.item_hash.clone(), LinkTypes::ItemToBorrowRequests)?,
+        GetStrategy::Local,
+    )?;
+    for link in links {
+        if link.target.clone().into_action_hash() == Some(input.request_hash.clone()) {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
     }
     
+    send_remote_signal(
+        Signal::BorrowDeclined {
+            request_hash: input.request_hash,
+            item_hash: request.item_hash,
+            reason: input.reason,
+        },
+        vec![request.requester],
+    )?;
+    Ok(())
+}
+
+/// Remove lapsed borrow requests, both for my items and ones I made
+///
+/// Requesters whose requests for my items lapsed get a
+/// `BorrowRequestLapsed` signal so their UI can tell them.
+#[hdk_extern]
+pub fn cleanup_my_expired_requests(_: ()) -> ExternResult<ExpiredRequestsCleanup> {
+    let me = agent_info()?.agent_initial_pubkey;
     let now = sys_time()?;
-    
-    // Create the transaction
-    let transaction = Transaction {
-        item_hash: request.item_hash.clone(),
-        borrower: request.requester.clone(),
-        lender: lender.clone(),
-        due_date: input.due_date,
-        created_at: now,
-        notes: input.notes,
-    };
-    
-    let txn_action_hash = create_entry(EntryTypes::Transaction(transaction.clone()))?;
-    let txn_entry_hash = hash_entry(&transaction)?;
-    
-    // Create transaction record with Active status
-    let txn_record = TransactionRecord {
-        transaction_hash: txn_action_hash.clone(),
-        returned_at: None,
-        status: TransactionStatus::Active,
+    let mut cleanup = ExpiredRequestsCleanup {
+        incoming: Vec::new(),
+        outgoing: Vec::new(),
     };
     
-    create_entry(EntryTypes::TransactionRecord(txn_record))?;
+    for item_output in get_my_items(())? {
+        let links = get_links(
+            LinkQuery::try_new(item_output.action_hash.clone(), LinkTypes::ItemToBorrowRequests)?,
+            GetStrategy::Local,
+        )?;
+        for link in links {
+            let Some(request_hash) = link.target.into_action_hash() else {
+                continue;
+            };
+            let Some(request) = get_borrow_request(request_hash.clone())? else {
+                continue;
+            };
+            if !request.is_expired(now) {
+                continue;
+            }
+            
+            delete_link(link.create_link_hash, GetOptions::default())?;
+            send_remote_signal(
+                Signal::BorrowRequestLapsed {
+                    request_hash: request_hash.clone(),
+                    item_hash: item_output.action_hash.clone(),
+                },
+                vec![request.requester],
+            )?;
+            cleanup.incoming.push(request_hash);
+        }
+    }
     
-    // Link transaction to both agents
-    create_link(
-        lender.clone(),
-        txn_action_hash.clone(),
-        LinkTypes::AgentToTransactions,
-        (),
+    let links = get_links(
+        LinkQuery::try_new(me, LinkTypes::AgentToBorrowRequests)?,
+        GetStrategy::Local,
     )?;
+    for link in links {
+        let Some(request_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(request) = get_borrow_request(request_hash.clone())? else {
+            continue;
+        };
+        if request.is_expired(now) {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+            cleanup.outgoing.push(request_hash);
+        }
+    }
     
-    create_link(
-        request.requester.clone(),
-        txn_action_hash.clone(),
-        LinkTypes::AgentToTransactions,
-        (),
-    )?;
+    Ok(cleanup)
+}
+
+/// Take myself out of lending: list my available items as Unavailable and
+/// withdraw every borrow request I still have open
+///
+/// Part of account deactivation. Items currently out on loan keep their
+/// Borrowed status so the loan can still be returned.
+#[hdk_extern]
+pub fn withdraw_from_lending(_: ()) -> ExternResult<LendingWithdrawal> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let mut withdrawal = LendingWithdrawal {
+        items_marked_unavailable: Vec::new(),
+        requests_withdrawn: Vec::new(),
+    };
     
-    // Update item status to Borrowed
-    update_item_status(UpdateStatusInput {
-        action_hash: item_output.action_hash,
-        status: ItemStatus::Borrowed,
-    })?;
+    for item_output in get_my_items(())? {
+        if item_output.item.status == ItemStatus::Available {
+            update_item_status(UpdateStatusInput {
+                action_hash: item_output.action_hash.clone(),
+                status: ItemStatus::Unavailable,
+            })?;
+            withdrawal.items_marked_unavailable.push(item_output.action_hash);
+        }
+    }
     
-    // Delete the borrow request link (request is now fulfilled)
-    let request_links = get_links(
-        LinkQuery::try_new(request.item_hash.clone(), LinkTypes::ItemToBorrowRequests)?,
+    let links = get_links(
+        LinkQuery::try_new(me, LinkTypes::AgentToBorrowRequests)?,
         GetStrategy::Local,
     )?;
-    
-    for link in request_links {
-        if ActionHash::try_from(link.target.clone()).ok() == Some(input.request_hash.clone()) {
-            delete_link(link.create_link_hash, GetOptions::default())?;
+    for link in links {
+        let Some(request_hash) = link.target.clone().into_action_hash() else {
+            continue;
+        };
+        let Some(request) = get_borrow_request(request_hash.clone())? else {
+            continue;
+        };
+        
+        let item_links = get_links(
+            LinkQuery::try_new(request.item_hash, LinkTypes::ItemToBorrowRequests)?,
+            GetStrategy::Local,
+        )?;
+        for item_link in item_links {
+            if item_link.target.into_action_hash() == Some(request_hash.clone()) {
+                delete_link(item_link.create_link_hash, GetOptions::default())?;
+            }
         }
+        delete_link(link.create_link_hash, GetOptions::default())?;
+        withdrawal.requests_withdrawn.push(request_hash);
     }
     
-    Ok(TransactionOutput {
-        transaction,
-        action_hash: txn_action_hash,
-        entry_hash: txn_entry_hash,
-        status: TransactionStatus::Active,
-    })
+    Ok(withdrawal)
 }
 
-/// Mark an item as returned
+// ============================================================================
+// ITEM AVAILABILITY
+// ============================================================================
+
+/// Block off time for one of my items, e.g. while I'm on vacation
+///
+/// Borrow requests and acceptances that would overlap the window are refused.
+/// A window that has already started marks the item Unavailable right away.
 #[hdk_extern]
-pub fn return_item(transaction_hash: ActionHash) -> ExternResult<TransactionOutput> {
-    let agent = agent_info()?.agent_initial_pubkey;
+pub fn schedule_unavailability(input: ScheduleUnavailabilityInput) -> ExternResult<UnavailabilityWindowOutput> {
+    let owner = agent_info()?.agent_initial_pubkey;
     
-    // Get the transaction
-    let Some(record) = get(transaction_hash.clone(), GetOptions::default())? else {
+    let Some(item_output) = get_item(input.item_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Item not found".to_string())));
+    };
+    if item_output.item.owner != owner {
         return Err(wasm_error!(WasmErrorInner::Guest(
-            "Transaction not found".to_string()
+            "Only the owner can schedule unavailability".to_string()
         )));
-    };
+    }
     
-    let Some(transaction) = record
-        .entry()
-        .to_app_option::<Transaction>()
+    let now = sys_time()?;
+    let window = UnavailabilityWindow {
+        item_hash: input.item_hash.clone(),
+        starts_at: input.starts_at,
+        ends_at: input.ends_at,
+        reason: input.reason,
+        created_at: now,
+    };
+    
+    let action_hash = create_entry(EntryTypes::UnavailabilityWindow(window.clone()))?;
+    create_link(
+        input.item_hash,
+        action_hash.clone(),
+        LinkTypes::ItemToUnavailability,
+        (),
+    )?;
+    
+    if window.is_active(now) && item_output.item.status == ItemStatus::Available {
+        update_item_status(UpdateStatusInput {
+            action_hash: item_output.action_hash,
+            status: ItemStatus::Unavailable,
+        })?;
+    }
+    
+    Ok(UnavailabilityWindowOutput {
+        window,
+        action_hash,
+    })
+}
+
+/// Remove one of my unavailability windows, restoring the item if it was
+/// the only thing keeping it off the shelf
+#[hdk_extern]
+pub fn cancel_unavailability(window_hash: ActionHash) -> ExternResult<()> {
+    let owner = agent_info()?.agent_initial_pubkey;
+    
+    let Some(record) = get(window_hash.clone(), GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Unavailability window not found".to_string())));
+    };
+    if record.action().author() != &owner {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the owner can cancel unavailability".to_string()
+        )));
+    }
+    let Some(window) = record
+        .entry()
+        .to_app_option::<UnavailabilityWindow>()
         .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
     else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Invalid unavailability window".to_string())));
+    };
+    
+    for (link, output) in get_windows(&window.item_hash)? {
+        if output.action_hash == window_hash {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
+    }
+    delete_entry(window_hash)?;
+    
+    let now = sys_time()?;
+    if window.is_active(now) {
+        restore_if_available(&window.item_hash, now)?;
+    }
+    Ok(())
+}
+
+/// Windows for an item that haven't ended yet, soonest first
+#[hdk_extern]
+pub fn get_unavailability_windows(item_hash: ActionHash) -> ExternResult<Vec<UnavailabilityWindowOutput>> {
+    let now = sys_time()?;
+    let mut windows: Vec<UnavailabilityWindowOutput> = get_windows(&item_hash)?
+        .into_iter()
+        .map(|(_, output)| output)
+        .filter(|output| output.window.ends_at > now)
+        .collect();
+    windows.sort_by(|a, b| a.window.starts_at.cmp(&b.window.starts_at));
+    Ok(windows)
+}
+
+/// Bring my items' statuses in line with their windows
+///
+/// Items with a window in progress are marked Unavailable. Ended windows
+/// are cleared, and an item they kept Unavailable becomes Available again.
+/// Borrowed items are left alone.
+#[hdk_extern]
+pub fn refresh_my_item_availability(_: ()) -> ExternResult<AvailabilityRefresh> {
+    let now = sys_time()?;
+    let mut refresh = AvailabilityRefresh {
+        made_unavailable: Vec::new(),
+        restored: Vec::new(),
+    };
+    
+    for item_output in get_my_items(())? {
+        let windows = get_windows(&item_output.action_hash)?;
+        let mut ended = false;
+        for (link, output) in &windows {
+            if output.window.ends_at <= now {
+                delete_link(link.create_link_hash.clone(), GetOptions::default())?;
+                ended = true;
+            }
+        }
+        
+        let active = windows.iter().any(|(_, output)| output.window.is_active(now));
+        if active && item_output.item.status == ItemStatus::Available {
+            update_item_status(UpdateStatusInput {
+                action_hash: item_output.action_hash.clone(),
+                status: ItemStatus::Unavailable,
+            })?;
+            refresh.made_unavailable.push(item_output.action_hash);
+        } else if ended && restore_if_available(&item_output.action_hash, now)? {
+            refresh.restored.push(item_output.action_hash);
+        }
+    }
+    
+    Ok(refresh)
+}
+
+/// Every window still linked from an item, with its link
+fn get_windows(item_hash: &ActionHash) -> ExternResult<Vec<(Link, UnavailabilityWindowOutput)>> {
+    let links = get_links(
+        LinkQuery::try_new(item_hash.clone(), LinkTypes::ItemToUnavailability)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut windows = Vec::new();
+    for link in links {
+        let Some(action_hash) = link.target.clone().into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        if let Some(window) = record
+            .entry()
+            .to_app_option::<UnavailabilityWindow>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        {
+            windows.push((link, UnavailabilityWindowOutput { window, action_hash }));
+        }
+    }
+    
+    Ok(windows)
+}
+
+/// Refuse a borrow from `from` to `until` that runs into one of the owner's windows
+fn require_no_unavailability(item_hash: &ActionHash, from: Timestamp, until: Timestamp) -> ExternResult<()> {
+    let overlapping = get_windows(item_hash)?
+        .into_iter()
+        .map(|(_, output)| output.window)
+        .find(|window| window.overlaps(from, until));
+    
+    match overlapping {
+        Some(window) => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "The owner has marked this item unavailable from {} to {}",
+            window.starts_at, window.ends_at
+        )))),
+        None => Ok(()),
+    }
+}
+
+/// Put an Unavailable item back to Available unless a window is still in progress
+fn restore_if_available(item_hash: &ActionHash, now: Timestamp) -> ExternResult<bool> {
+    let Some(item_output) = get_item(item_hash.clone())? else {
+        return Ok(false);
+    };
+    if item_output.item.status != ItemStatus::Unavailable {
+        return Ok(false);
+    }
+    let still_active = get_windows(item_hash)?
+        .iter()
+        .any(|(_, output)| output.window.is_active(now));
+    if still_active {
+        return Ok(false);
+    }
+    
+    update_item_status(UpdateStatusInput {
+        action_hash: item_output.action_hash,
+        status: ItemStatus::Available,
+    })?;
+    Ok(true)
+}
+
+// ============================================================================
+// WANTED ITEMS
+// ============================================================================
+
+/// Ask the neighborhood for an item ("does anyone have a pressure washer?")
+///
+/// Existing items whose titles share a word with the wanted title are
+/// returned as matches, and their owners get a `WantedItemMatch` signal.
+#[hdk_extern]
+pub fn post_wanted_item(input: PostWantedInput) -> ExternResult<PostWantedOutput> {
+    let requester = agent_info()?.agent_initial_pubkey;
+    
+    let wanted = WantedItem {
+        title: input.title,
+        description: input.description,
+        requester: requester.clone(),
+        created_at: sys_time()?,
+    };
+    
+    ensure_membership_proof()?;
+    let action_hash = create_entry(EntryTypes::WantedItem(wanted.clone()))?;
+    create_link(
+        wanted_anchor_hash()?,
+        action_hash.clone(),
+        LinkTypes::AllWantedItems,
+        (),
+    )?;
+    create_link(
+        requester.clone(),
+        action_hash.clone(),
+        LinkTypes::AgentToWantedItems,
+        (),
+    )?;
+    
+    let wanted_words = title_words(&wanted.title);
+    let matches: Vec<ItemOutput> = get_all_items(None)?
+        .into_iter()
+        .filter(|item_output| item_output.item.owner != requester)
+        .filter(|item_output| !title_words(&item_output.item.title).is_disjoint(&wanted_words))
+        .collect();
+    
+    for item_output in &matches {
+        send_remote_signal(
+            Signal::WantedItemMatch {
+                wanted_hash: action_hash.clone(),
+                item_hash: item_output.action_hash.clone(),
+                title: wanted.title.clone(),
+            },
+            vec![item_output.item.owner.clone()],
+        )?;
+    }
+    
+    Ok(PostWantedOutput {
+        wanted: WantedItemOutput {
+            wanted,
+            action_hash,
+            offered_items: Vec::new(),
+        },
+        matches,
+    })
+}
+
+/// Get every wanted item, newest first
+#[hdk_extern]
+pub fn get_wanted_items(_: ()) -> ExternResult<Vec<WantedItemOutput>> {
+    let links = get_links(
+        LinkQuery::try_new(wanted_anchor_hash()?, LinkTypes::AllWantedItems)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut wanted_items = Vec::new();
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        if let Some(wanted_output) = get_wanted_item(action_hash)? {
+            wanted_items.push(wanted_output);
+        }
+    }
+    
+    wanted_items.sort_by(|a, b| b.wanted.created_at.cmp(&a.wanted.created_at));
+    Ok(wanted_items)
+}
+
+/// Offer one of my items for a wanted item
+///
+/// Links the two and lets the requester know, so they can send a borrow
+/// request for the item.
+#[hdk_extern]
+pub fn fulfill_wanted(input: FulfillWantedInput) -> ExternResult<WantedItemOutput> {
+    let owner = agent_info()?.agent_initial_pubkey;
+    
+    let Some(item_output) = get_item(input.item_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Item not found".to_string())));
+    };
+    if item_output.item.owner != owner {
         return Err(wasm_error!(WasmErrorInner::Guest(
-            "Invalid transaction".to_string()
+            "You can only offer your own items".to_string()
         )));
+    }
+    
+    let Some(mut wanted_output) = get_wanted_item(input.wanted_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Wanted item not found".to_string())));
     };
+    if wanted_output.offered_items.contains(&input.item_hash) {
+        return Ok(wanted_output);
+    }
+    
+    create_link(
+        input.wanted_hash.clone(),
+        input.item_hash.clone(),
+        LinkTypes::WantedToItems,
+        (),
+    )?;
+    
+    send_remote_signal(
+        Signal::WantedFulfilled {
+            wanted_hash: input.wanted_hash,
+            item_hash: input.item_hash.clone(),
+        },
+        vec![wanted_output.wanted.requester.clone()],
+    )?;
     
-    // Either borrower or lender can mark as returned
-    if agent != transaction.borrower && agent != transaction.lender {
+    wanted_output.offered_items.push(input.item_hash);
+    Ok(wanted_output)
+}
+
+fn get_wanted_item(action_hash: ActionHash) -> ExternResult<Option<WantedItemOutput>> {
+    let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+        return Ok(None);
+    };
+    let Some(wanted) = record
+        .entry()
+        .to_app_option::<WantedItem>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    else {
+        return Ok(None);
+    };
+    
+    let offered_items = get_links(
+        LinkQuery::try_new(action_hash.clone(), LinkTypes::WantedToItems)?,
+        GetStrategy::Local,
+    )?
+    .into_iter()
+    .filter_map(|link| link.target.into_action_hash())
+    .collect();
+    
+    Ok(Some(WantedItemOutput {
+        wanted,
+        action_hash,
+        offered_items,
+    }))
+}
+
+/// Lowercased title words used for matching wanted items against items
+fn title_words(title: &str) -> HashSet<String> {
+    title
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| word.chars().count() >= MIN_MATCH_WORD_LENGTH)
+        .map(|word| word.to_lowercase())
+        .collect()
+}
+
+// ============================================================================
+// COUNTERSIGNING BORROW FLOW
+// ============================================================================
+
+/// Accept a borrow request and create a transaction
+///
+/// This function is called by the item owner to accept a borrow request.
+/// In a full countersigning implementation, this would:
+/// 1. Create a preflight request
+/// 2. Both parties sign
+/// 3. Commit the countersigned entry
+///
+/// For now, we implement a simplified version that creates the transaction
+/// as a regular entry. True countersigning requires the unstable features
+/// and a more complex session management flow.
+#[hdk_extern]
+pub fn accept_borrow(input: AcceptBorrowInput) -> ExternResult<TransactionOutput> {
+    let lender = agent_info()?.agent_initial_pubkey;
+    
+    // Get the borrow request
+    let Some(record) = get(input.request_hash.clone(), GetOptions::default())? else {
         return Err(wasm_error!(WasmErrorInner::Guest(
-            "Only the borrower or lender can mark an item as returned".to_string()
+            "Borrow request not found".to_string()
+        )));
+    };
+    
+    let Some(request) = record
+        .entry()
+        .to_app_option::<BorrowRequest>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    else {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Invalid borrow request".to_string()
+        )));
+    };
+    
+    // Verify the caller is the item owner
+    if request.owner != lender {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the item owner can accept borrow requests".to_string()
         )));
     }
     
-    // Update item status back to Available
-    if let Some(item_output) = get_item(transaction.item_hash.clone())? {
-        update_item_status(UpdateStatusInput {
-            action_hash: item_output.action_hash,
-            status: ItemStatus::Available,
-        })?;
+    if request.is_expired(sys_time()?) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "This borrow request has expired".to_string()
+        )));
+    }
+    
+    // Get the item to verify it's still available
+    let Some(item_output) = get_item(request.item_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Item not found".to_string())));
+    };
+    
+    if item_output.item.status != ItemStatus::Available {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Item is no longer available".to_string()
+        )));
+    }
+    
+    // The borrower agreed to the terms in their request; if the listing has
+    // changed since, they need to see the new terms and ask again
+    if item_output.item.lending_terms != request.terms {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "The item's lending terms changed after this request was made".to_string()
+        )));
+    }
+    
+    let now = sys_time()?;
+    require_no_unavailability(&request.item_hash, now, input.due_date)?;
+    
+    // Create the transaction
+    let transaction = Transaction {
+        item_hash: request.item_hash.clone(),
+        borrower: request.requester.clone(),
+        lender: lender.clone(),
+        due_date: input.due_date,
+        created_at: now,
+        notes: input.notes,
+        terms: request.terms,
+        request_hash: Some(input.request_hash.clone()),
+    };
+    
+    let txn_action_hash = create_entry(EntryTypes::Transaction(transaction.clone()))?;
+    let txn_entry_hash = hash_entry(&transaction)?;
+    
+    // The borrow starts once both parties confirm the pickup
+    let txn_record = TransactionRecord {
+        transaction_hash: txn_action_hash.clone(),
+        returned_at: None,
+        status: TransactionStatus::AwaitingPickup,
+        handoff: None,
+        counterpart: None,
+    };
+    
+    let record_hash = create_entry(EntryTypes::TransactionRecord(txn_record))?;
+    create_link(
+        txn_action_hash.clone(),
+        record_hash,
+        LinkTypes::TransactionToRecords,
+        (),
+    )?;
+    
+    // Link transaction to both agents
+    create_link(
+        lender.clone(),
+        txn_action_hash.clone(),
+        LinkTypes::AgentToTransactions,
+        (),
+    )?;
+    
+    create_link(
+        request.requester.clone(),
+        txn_action_hash.clone(),
+        LinkTypes::AgentToTransactions,
+        (),
+    )?;
+    
+    // Update item status to Borrowed
+    update_item_status(UpdateStatusInput {
+        action_hash: item_output.action_hash,
+        status: ItemStatus::Borrowed,
+    })?;
+    
+    // Delete the borrow request link (request is now fulfilled)
+    let request_links = get_links(
+        LinkQuery::try_new(request.item_hash.clone(), LinkTypes::ItemToBorrowRequests)?,
+        GetStrategy::Local,
+    )?;
+    
+    for link in request_links {
+        if ActionHash::try_from(link.target.clone()).ok() == Some(input.request_hash.clone()) {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
+    }
+    
+    send_remote_signal(
+        Signal::BorrowAccepted {
+            request_hash: input.request_hash,
+            transaction_hash: txn_action_hash.clone(),
+            item_hash: request.item_hash,
+        },
+        vec![request.requester],
+    )?;
+    
+    Ok(TransactionOutput {
+        transaction,
+        action_hash: txn_action_hash,
+        entry_hash: txn_entry_hash,
+        status: TransactionStatus::AwaitingPickup,
+    })
+}
+
+/// Confirm the item changed hands at pickup
+///
+/// The transaction becomes Active once both the lender and the borrower have
+/// confirmed; until then it stays AwaitingPickup.
+#[hdk_extern]
+pub fn confirm_pickup(transaction_hash: ActionHash) -> ExternResult<TransactionOutput> {
+    confirm_handoff(transaction_hash, Handoff::Pickup)
+}
+
+/// Confirm the item was given back
+///
+/// The first confirmation leaves the transaction AwaitingDropoff and the
+/// second completes it as Returned, dated to the first. The lender's
+/// confirmation also makes the item available again.
+#[hdk_extern]
+pub fn confirm_dropoff(transaction_hash: ActionHash) -> ExternResult<TransactionOutput> {
+    confirm_handoff(transaction_hash, Handoff::Dropoff)
+}
+
+/// Mark an item as returned; the caller's half of the drop-off confirmation
+#[hdk_extern]
+pub fn return_item(transaction_hash: ActionHash) -> ExternResult<TransactionOutput> {
+    confirm_dropoff(transaction_hash)
+}
+
+fn confirm_handoff(transaction_hash: ActionHash, handoff: Handoff) -> ExternResult<TransactionOutput> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    
+    let Some(transaction) = get_transaction(transaction_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Transaction not found".to_string()
+        )));
+    };
+    
+    let other = if agent == transaction.lender {
+        transaction.borrower.clone()
+    } else if agent == transaction.borrower {
+        transaction.lender.clone()
+    } else {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the borrower or lender can confirm a handoff".to_string()
+        )));
+    };
+    
+    let records = get_status_records(transaction_hash.clone())?;
+    let current = current_status(&records);
+    let ready = match handoff {
+        Handoff::Pickup => current == TransactionStatus::AwaitingPickup,
+        Handoff::Dropoff => matches!(current, TransactionStatus::Active | TransactionStatus::AwaitingDropoff),
+    };
+    if !ready {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Cannot confirm {:?} while the transaction is {:?}",
+            handoff, current
+        ))));
     }
+    if handoff_confirmation(&records, handoff, &agent).is_some() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "You already confirmed this handoff".to_string()
+        )));
+    }
+    
+    // The other party's confirmation, if they got there first, completes the handoff
+    let counterpart = handoff_confirmation(&records, handoff, &other);
+    let status = match (handoff, counterpart.is_some()) {
+        (Handoff::Pickup, false) => TransactionStatus::AwaitingPickup,
+        (Handoff::Pickup, true) => TransactionStatus::Active,
+        (Handoff::Dropoff, false) => TransactionStatus::AwaitingDropoff,
+        (Handoff::Dropoff, true) => TransactionStatus::Returned,
+    };
+    let returned_at = counterpart
+        .filter(|_| status == TransactionStatus::Returned)
+        .map(|counterpart| counterpart.timestamp);
     
-    // Create updated transaction record
     let txn_record = TransactionRecord {
         transaction_hash: transaction_hash.clone(),
-        returned_at: Some(sys_time()?),
-        status: TransactionStatus::Returned,
+        returned_at,
+        status: status.clone(),
+        handoff: Some(handoff),
+        counterpart: counterpart.map(|counterpart| counterpart.action_hash.clone()),
     };
+    let record_hash = create_entry(EntryTypes::TransactionRecord(txn_record))?;
+    create_link(
+        transaction_hash.clone(),
+        record_hash.clone(),
+        LinkTypes::TransactionToRecords,
+        (),
+    )?;
     
-    create_entry(EntryTypes::TransactionRecord(txn_record))?;
+    // Only the owner can update the item, and their word is what puts it back on the shelf
+    if handoff == Handoff::Dropoff && agent == transaction.lender {
+        if let Some(item_output) = get_item(transaction.item_hash.clone())? {
+            update_item_status(UpdateStatusInput {
+                action_hash: item_output.action_hash,
+                status: ItemStatus::Available,
+            })?;
+        }
+    }
+    
+    // On-time returns count toward the borrower's badge
+    if returned_at.is_some_and(|returned_at| returned_at <= transaction.due_date) {
+        let mut evidence = vec![record_hash];
+        evidence.extend(on_time_returns(&transaction.borrower, &transaction_hash)?);
+        grant_badge(transaction.borrower.clone(), evidence)?;
+    }
+    
+    send_remote_signal(
+        Signal::HandoffConfirmed {
+            transaction_hash: transaction_hash.clone(),
+            handoff,
+            status: status.clone(),
+        },
+        vec![other],
+    )?;
     
     let entry_hash = hash_entry(&transaction)?;
     
@@ -614,10 +1540,69 @@ pub fn return_item(transaction_hash: ActionHash) -> ExternResult<TransactionOutp
         transaction,
         action_hash: transaction_hash,
         entry_hash,
-        status: TransactionStatus::Returned,
+        status,
     })
 }
 
+/// A transaction status record with who committed it and when
+struct StatusRecord {
+    action_hash: ActionHash,
+    author: AgentPubKey,
+    timestamp: Timestamp,
+    record: TransactionRecord,
+}
+
+fn get_status_records(transaction_hash: ActionHash) -> ExternResult<Vec<StatusRecord>> {
+    let links = get_links(
+        LinkQuery::try_new(transaction_hash, LinkTypes::TransactionToRecords)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut records = Vec::new();
+    for link in links {
+        let Some(record_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(record_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        if let Some(txn_record) = record
+            .entry()
+            .to_app_option::<TransactionRecord>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        {
+            records.push(StatusRecord {
+                action_hash: record_hash,
+                author: record.action().author().clone(),
+                timestamp: record.action().timestamp(),
+                record: txn_record,
+            });
+        }
+    }
+    
+    Ok(records)
+}
+
+/// The furthest status any record has reached
+fn current_status(records: &[StatusRecord]) -> TransactionStatus {
+    records
+        .iter()
+        .map(|status_record| status_record.record.status.clone())
+        .max_by_key(TransactionStatus::stage)
+        .unwrap_or(TransactionStatus::Pending)
+}
+
+/// An agent's acknowledgment of a handoff, if they've made one
+fn handoff_confirmation<'a>(
+    records: &'a [StatusRecord],
+    handoff: Handoff,
+    agent: &AgentPubKey,
+) -> Option<&'a StatusRecord> {
+    records
+        .iter()
+        .find(|status_record| status_record.author == *agent && status_record.record.handoff == Some(handoff))
+}
+
 /// Get my transactions (as borrower or lender)
 #[hdk_extern]
 pub fn get_my_transactions(_: ()) -> ExternResult<Vec<TransactionOutput>> {
@@ -642,12 +1627,12 @@ pub fn get_my_transactions(_: ()) -> ExternResult<Vec<TransactionOutput>> {
                 .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
             {
                 let entry_hash = hash_entry(&transaction)?;
-                // TODO: Get actual status from TransactionRecord
+                let status = current_status(&get_status_records(action_hash.clone())?);
                 transactions.push(TransactionOutput {
                     transaction,
                     action_hash,
                     entry_hash,
-                    status: TransactionStatus::Active,
+                    status,
                 });
             }
         }
@@ -656,11 +1641,505 @@ pub fn get_my_transactions(_: ()) -> ExternResult<Vec<TransactionOutput>> {
     Ok(transactions)
 }
 
+// ============================================================================
+// LENDING STATISTICS
+// ============================================================================
+
+/// A transaction together with when (if ever) the item came back
+struct Loan {
+    transaction: Transaction,
+    returned_at: Option<Timestamp>,
+}
+
+impl Loan {
+    fn days(&self) -> Option<f64> {
+        let returned_at = self.returned_at?;
+        let micros = returned_at.as_micros() - self.transaction.created_at.as_micros();
+        Some(micros as f64 / MICROS_PER_DAY)
+    }
+
+    fn on_time(&self) -> Option<bool> {
+        self.returned_at.map(|returned_at| returned_at <= self.transaction.due_date)
+    }
+}
+
+const MICROS_PER_DAY: f64 = 24.0 * 60.0 * 60.0 * 1_000_000.0;
+
+/// Get how often an item has been borrowed and how reliably it comes back
+///
+/// Loans are found through the owner's transactions, so this works for any
+/// item without a separate per-item index.
+#[hdk_extern]
+pub fn get_item_stats(item_hash: ActionHash) -> ExternResult<ItemStats> {
+    let Some(item_output) = get_item(item_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Item not found".to_string())));
+    };
+    
+    let loans: Vec<Loan> = get_loans_for(item_output.item.owner)?
+        .into_iter()
+        .filter(|loan| loan.transaction.item_hash == item_hash)
+        .collect();
+    
+    Ok(ItemStats {
+        item_hash,
+        times_borrowed: loans.len() as u32,
+        currently_borrowed: loans.iter().any(|loan| loan.returned_at.is_none()),
+        last_borrowed_at: loans.iter().map(|loan| loan.transaction.created_at).max(),
+        average_loan_days: average_days(&loans),
+        on_time_streak: on_time_streak(&loans),
+    })
+}
+
+/// Get an agent's lending and borrowing history
+#[hdk_extern]
+pub fn get_agent_lending_stats(agent: AgentPubKey) -> ExternResult<AgentLendingStats> {
+    let items_listed = get_items_for_owner(agent.clone())?.len() as u32;
+    
+    let (lent, borrowed): (Vec<Loan>, Vec<Loan>) = get_loans_for(agent.clone())?
+        .into_iter()
+        .partition(|loan| loan.transaction.lender == agent);
+    
+    let returned: Vec<bool> = borrowed.iter().filter_map(Loan::on_time).collect();
+    let on_time_return_rate = (!returned.is_empty())
+        .then(|| returned.iter().filter(|on_time| **on_time).count() as f64 / returned.len() as f64);
+    
+    Ok(AgentLendingStats {
+        items_listed,
+        times_lent: lent.len() as u32,
+        times_borrowed: borrowed.len() as u32,
+        active_loans_lent: lent.iter().filter(|loan| loan.returned_at.is_none()).count() as u32,
+        active_loans_borrowed: borrowed.iter().filter(|loan| loan.returned_at.is_none()).count() as u32,
+        average_loan_days: average_days(&lent),
+        on_time_return_rate,
+        on_time_streak: on_time_streak(&borrowed),
+        agent,
+    })
+}
+
+/// Every transaction an agent took part in, oldest first
+fn get_loans_for(agent: AgentPubKey) -> ExternResult<Vec<Loan>> {
+    let links = get_links(
+        LinkQuery::try_new(agent, LinkTypes::AgentToTransactions)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut loans = Vec::new();
+    for link in links {
+        let Some(transaction_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(transaction_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let Some(transaction) = record
+            .entry()
+            .to_app_option::<Transaction>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        else {
+            continue;
+        };
+        loans.push(Loan {
+            returned_at: get_returned_at(transaction_hash)?,
+            transaction,
+        });
+    }
+    
+    loans.sort_by(|a, b| a.transaction.created_at.cmp(&b.transaction.created_at));
+    Ok(loans)
+}
+
+/// One on-time Returned record per loan the agent borrowed, skipping `exclude`
+fn on_time_returns(borrower: &AgentPubKey, exclude: &ActionHash) -> ExternResult<Vec<ActionHash>> {
+    let links = get_links(
+        LinkQuery::try_new(borrower.clone(), LinkTypes::AgentToTransactions)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut returns = Vec::new();
+    for link in links {
+        let Some(transaction_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        if transaction_hash == *exclude {
+            continue;
+        }
+        let Some(record) = get(transaction_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let Some(transaction) = record
+            .entry()
+            .to_app_option::<Transaction>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        else {
+            continue;
+        };
+        if transaction.borrower != *borrower {
+            continue;
+        }
+        
+        let record_links = get_links(
+            LinkQuery::try_new(transaction_hash, LinkTypes::TransactionToRecords)?,
+            GetStrategy::Local,
+        )?;
+        for record_link in record_links {
+            let Some(record_hash) = record_link.target.into_action_hash() else {
+                continue;
+            };
+            let Some(record) = get(record_hash.clone(), GetOptions::default())? else {
+                continue;
+            };
+            let on_time = record
+                .entry()
+                .to_app_option::<TransactionRecord>()
+                .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+                .is_some_and(|txn_record| {
+                    txn_record.status == TransactionStatus::Returned
+                        && txn_record
+                            .returned_at
+                            .is_some_and(|returned_at| returned_at <= transaction.due_date)
+                });
+            if on_time {
+                returns.push(record_hash);
+                break;
+            }
+        }
+    }
+    
+    Ok(returns)
+}
+
+/// When a transaction's item was returned, from its status records
+fn get_returned_at(transaction_hash: ActionHash) -> ExternResult<Option<Timestamp>> {
+    let records = get_status_records(transaction_hash.clone())?;
+    if records.is_empty() {
+        return get_legacy_returned_at(transaction_hash);
+    }
+    // Only a confirmed return counts; one party's word leaves it AwaitingDropoff
+    Ok(records
+        .into_iter()
+        .filter(|status_record| status_record.record.status == TransactionStatus::Returned)
+        .filter_map(|status_record| status_record.record.returned_at)
+        .max())
+}
+
+/// When a transaction from before status records were linked was returned
+///
+/// Those records were only committed, never linked, so the one place to find
+/// them is the chain of whoever marked the return. Stats read by the other
+/// party still show such loans as open.
+fn get_legacy_returned_at(transaction_hash: ActionHash) -> ExternResult<Option<Timestamp>> {
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::TransactionRecord.try_into()?)
+            .include_entries(true),
+    )?;
+
+    let mut returned_at = None;
+    for record in records {
+        let Some(txn_record) = record
+            .entry()
+            .to_app_option::<TransactionRecord>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        else {
+            continue;
+        };
+        if txn_record.transaction_hash == transaction_hash && txn_record.status == TransactionStatus::Returned {
+            returned_at = returned_at.max(txn_record.returned_at);
+        }
+    }
+
+    Ok(returned_at)
+}
+
+fn average_days(loans: &[Loan]) -> Option<f64> {
+    let days: Vec<f64> = loans.iter().filter_map(Loan::days).collect();
+    (!days.is_empty()).then(|| days.iter().sum::<f64>() / days.len() as f64)
+}
+
+/// Count on-time returns back from the most recent returned loan
+fn on_time_streak(loans: &[Loan]) -> u32 {
+    loans
+        .iter()
+        .rev()
+        .filter_map(Loan::on_time)
+        .take_while(|on_time| *on_time)
+        .count() as u32
+}
+
+// ============================================================================
+// SIGNALS
+// ============================================================================
+
+/// Let item owners reach requesters with remote signals
+#[hdk_extern]
+pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    let mut functions = BTreeSet::new();
+    functions.insert((zome_info()?.name, FunctionName::from("recv_remote_signal")));
+    create_cap_grant(CapGrantEntry {
+        tag: "remote_signals".to_string(),
+        access: CapAccess::Unrestricted,
+        functions: GrantedFunctions::Listed(functions),
+    })?;
+    Ok(InitCallbackResult::Pass)
+}
+
+/// Forward signals from other neighbors to the UI
+///
+/// Each signal is only passed on if it came from the neighbor it claims to:
+/// the item owner for answers, lapse, and fulfillment notices, the requester
+/// for new requests and matches, and a party to the borrow for handoff confirmations.
+#[hdk_extern]
+pub fn recv_remote_signal(signal: ExternIO) -> ExternResult<()> {
+    let signal: Signal = signal.decode().map_err(|e| {
+        wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to decode toolshed signal: {:?}",
+            e
+        )))
+    })?;
+    
+    let provenance = call_info()?.provenance;
+    match &signal {
+        Signal::BorrowRequested { request_hash, .. } => {
+            let Some(request) = get_borrow_request(request_hash.clone())? else {
+                return Ok(());
+            };
+            if request.requester != provenance {
+                return Ok(());
+            }
+        }
+        Signal::BorrowAccepted { request_hash, .. }
+        | Signal::BorrowDeclined { request_hash, .. }
+        | Signal::BorrowRequestLapsed { request_hash, .. } => {
+            let Some(request) = get_borrow_request(request_hash.clone())? else {
+                return Ok(());
+            };
+            if request.owner != provenance {
+                return Ok(());
+            }
+        }
+        Signal::WantedItemMatch { wanted_hash, .. } => {
+            let Some(wanted_output) = get_wanted_item(wanted_hash.clone())? else {
+                return Ok(());
+            };
+            if wanted_output.wanted.requester != provenance {
+                return Ok(());
+            }
+        }
+        Signal::WantedFulfilled { item_hash, .. } => {
+            let Some(item_output) = get_item(item_hash.clone())? else {
+                return Ok(());
+            };
+            if item_output.item.owner != provenance {
+                return Ok(());
+            }
+        }
+        Signal::HandoffConfirmed { transaction_hash, .. } => {
+            let Some(transaction) = get_transaction(transaction_hash.clone())? else {
+                return Ok(());
+            };
+            if transaction.borrower != provenance && transaction.lender != provenance {
+                return Ok(());
+            }
+        }
+    }
+    
+    emit_signal(SignalEnvelope::new(SIGNAL_ZOME, signal))?;
+    Ok(())
+}
+
 // ============================================================================
 // HELPER FUNCTIONS
 // ============================================================================
 
+fn get_borrow_request(action_hash: ActionHash) -> ExternResult<Option<BorrowRequest>> {
+    let Some(record) = get(action_hash, GetOptions::default())? else {
+        return Ok(None);
+    };
+    record
+        .entry()
+        .to_app_option::<BorrowRequest>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))
+}
+
+fn get_transaction(action_hash: ActionHash) -> ExternResult<Option<Transaction>> {
+    let Some(record) = get(action_hash, GetOptions::default())? else {
+        return Ok(None);
+    };
+    record
+        .entry()
+        .to_app_option::<Transaction>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))
+}
+
 fn all_items_anchor_hash() -> ExternResult<EntryHash> {
     let path = Path::from(ALL_ITEMS_PATH);
     path.path_entry_hash()
 }
+
+fn wanted_anchor_hash() -> ExternResult<EntryHash> {
+    let path = Path::from(ALL_WANTED_PATH);
+    path.path_entry_hash()
+}
+
+/// Add or remove a tool shed item in the search zome's keyword index
+fn call_search(fn_name: &str, target: ActionHash, title: &str, text: &str) -> ExternResult<()> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("search"),
+        FunctionName::from(fn_name),
+        None,
+        SearchIndexInput {
+            kind: SearchKind::Item,
+            target,
+            title: title.to_string(),
+            text: text.to_string(),
+        },
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to update search index: {:?}",
+            other
+        )))),
+    }
+}
+
+/// Add a located item to the search zome's geohash index, if it has a geohash
+fn index_place(target: ActionHash, title: &str, place: &Option<Location>) -> ExternResult<()> {
+    let Some(geohash) = place.as_ref().and_then(|place| place.geohash.clone()) else {
+        return Ok(());
+    };
+
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("search"),
+        FunctionName::from("index_location"),
+        None,
+        LocationIndexInput {
+            kind: SearchKind::Item,
+            target,
+            title: title.to_string(),
+            geohash,
+        },
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to update location index: {:?}",
+            other
+        )))),
+    }
+}
+
+/// Mirror of the profile zome's GrantBadgeInput
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct GrantBadgeInput {
+    agent: AgentPubKey,
+    kind: BadgeKind,
+    evidence: Vec<ActionHash>,
+}
+
+/// The profile zome's BadgeKind variant this zome grants
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum BadgeKind {
+    ReliableBorrower,
+}
+
+/// Offer the profile zome a borrower's on-time return records as badge evidence;
+/// it grants the badge once there are enough and ignores the call otherwise
+fn grant_badge(agent: AgentPubKey, evidence: Vec<ActionHash>) -> ExternResult<()> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("profile"),
+        FunctionName::from("grant_badge"),
+        None,
+        GrantBadgeInput {
+            agent,
+            kind: BadgeKind::ReliableBorrower,
+            evidence,
+        },
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to grant badge: {:?}",
+            other
+        )))),
+    }
+}
+
+/// Refuse a manual reference that file_storage doesn't know about
+fn require_manual(manual: &ActionHash) -> ExternResult<()> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("file_storage"),
+        FunctionName::from("get_file_metadata"),
+        None,
+        manual.clone(),
+    )?;
+    
+    let found: Option<StoredFile> = match response {
+        ZomeCallResponse::Ok(result) => result.decode().map_err(|e| {
+            wasm_error!(WasmErrorInner::Guest(format!(
+                "Failed to decode file_storage response: {:?}",
+                e
+            )))
+        })?,
+        other => {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "file_storage call failed: {:?}",
+                other
+            ))))
+        }
+    };
+    
+    match found {
+        Some(_) => Ok(()),
+        None => Err(wasm_error!(WasmErrorInner::Guest("Manual file not found".to_string()))),
+    }
+}
+
+/// Have the vouch zome put a MembershipProof on our chain if we don't have
+/// one yet; integrity rejects items, borrow requests, and wanted items from authors without one
+fn ensure_membership_proof() -> ExternResult<()> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("vouch"),
+        FunctionName::from("ensure_membership_proof"),
+        None,
+        (),
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to check membership proof: {:?}",
+            other
+        )))),
+    }
+}
+
+// ============================================================================
+// DATA EXPORT
+// ============================================================================
+
+/// The calling agent's items, borrow requests, and transactions
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ToolshedExport {
+    pub items: Vec<ItemOutput>,
+    pub borrow_requests: Vec<BorrowRequestOutput>,
+    pub transactions: Vec<TransactionOutput>,
+}
+
+/// Export the caller's Tool Shed footprint
+#[hdk_extern]
+pub fn export_my_data(_: ()) -> ExternResult<ToolshedExport> {
+    Ok(ToolshedExport {
+        items: get_my_items(())?,
+        borrow_requests: get_my_borrow_requests(())?,
+        transactions: get_my_transactions(())?,
+    })
+}
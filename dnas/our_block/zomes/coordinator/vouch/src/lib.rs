@@ -5,18 +5,44 @@
 //! - Creating vouches (when scanning a neighbor's QR code)
 //! - Checking membership status
 //! - Managing trusted anchors
+//! - Pre-vouching for neighbors who haven't joined yet
 //! - Revoking vouches if needed
 
+use ed25519_dalek::{Signer, SigningKey};
 use hdk::prelude::*;
+use list_options::get_list_links;
+use publish_status::{publish_status, PublishStatusOutput};
+use signal_envelope::SignalEnvelope;
+use std::collections::{BTreeSet, HashMap, HashSet};
 use vouch_integrity::*;
 
+/// Signal types for real-time updates, emitted inside a SignalEnvelope
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "event", content = "payload")]
+pub enum Signal {
+    NewVouch { vouch_hash: ActionHash, vouchee: AgentPubKey },
+    AnchorNominated { nomination_hash: ActionHash, nominee: AgentPubKey },
+    NewAnchor { agent: AgentPubKey },
+    MembershipAttested { attestation_hash: ActionHash, agent: AgentPubKey },
+    /// Sent to members near the voucher asking them to vouch for a new neighbor too
+    VouchRequested { vouch_hash: ActionHash, vouchee: AgentPubKey, nickname: String },
+    GuestPassIssued { pass_hash: ActionHash, guest: AgentPubKey },
+}
+
+/// Zome name clients see in the signal envelope
+const SIGNAL_ZOME: &str = "vouch";
+
+fn emit_zome_signal(signal: Signal) -> ExternResult<()> {
+    emit_signal(SignalEnvelope::new(SIGNAL_ZOME, signal))
+}
+
 /// Input for creating a vouch
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct CreateVouchInput {
     /// The agent being vouched for
     pub vouchee: AgentPubKey,
     
-    /// Type of verification performed
+    /// Neighbor, or Anchor when a trusted anchor vouches in that role
     pub vouch_type: VouchType,
     
     /// Optional note about this vouch
@@ -38,7 +64,12 @@ pub struct VouchInfo {
     pub voucher: AgentPubKey,
     pub vouch: Vouch,
     pub action_hash: ActionHash,
+    /// An Anchor-type vouch from a current anchor; these count toward the
+    /// anchor threshold
     pub is_from_anchor: bool,
+    /// KeyRotations from the key that received this vouch to the agent,
+    /// oldest first; empty when the agent's current key received it
+    pub via_rotations: Vec<ActionHash>,
 }
 
 /// Complete membership information for an agent
@@ -51,9 +82,231 @@ pub struct MembershipInfo {
     pub is_anchor: bool,
 }
 
+/// Membership status without the full vouch lists
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MembershipSummary {
+    pub agent: AgentPubKey,
+    pub status: MembershipStatus,
+    pub is_anchor: bool,
+}
+
+/// Input for pre-vouching for a neighbor who hasn't joined yet
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CreatePreVouchInput {
+    pub vouch_type: VouchType,
+    pub note: Option<String>,
+    /// Seconds until the invite expires (default: 7 days)
+    pub validity_secs: Option<i64>,
+}
+
+/// A newly created pre-vouch; the secret is only returned here
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PreVouchOutput {
+    pub secret: String,
+    pub invite: InvitedNeighbor,
+    pub action_hash: ActionHash,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum PreVouchStatus {
+    Pending,
+    /// Redeemed, but the inviter hasn't created the vouch yet
+    Redeemed,
+    Converted,
+    Expired,
+}
+
+/// A pre-vouch as seen by the inviter
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PreVouchInfo {
+    pub invite: InvitedNeighbor,
+    pub action_hash: ActionHash,
+    pub status: PreVouchStatus,
+    pub redeemed_by: Option<AgentPubKey>,
+    pub vouch_hash: Option<ActionHash>,
+}
+
+/// Output after redeeming a pre-vouch invite
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RedeemInviteOutput {
+    pub inviter: AgentPubKey,
+    pub invite_hash: ActionHash,
+    pub redemption_hash: ActionHash,
+    /// None if the inviter was unreachable; the vouch is created the next
+    /// time they run `convert_redeemed_invites`
+    pub vouch: Option<VouchOutput>,
+}
+
+/// A change in an agent's membership, as reconstructed from the DHT
+#[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(tag = "type")]
+pub enum MembershipEventKind {
+    VouchReceived {
+        voucher: AgentPubKey,
+        action_hash: ActionHash,
+        /// An Anchor-type vouch from someone who is an anchor now
+        from_anchor: bool,
+    },
+    VouchRevoked {
+        voucher: AgentPubKey,
+        action_hash: ActionHash,
+    },
+    BecameAnchor,
+    /// Crossed the verification threshold
+    Verified,
+    /// Dropped back below the threshold after revocations
+    VerificationLost,
+    Attested {
+        anchor: AgentPubKey,
+        action_hash: ActionHash,
+        status: MembershipStatus,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MembershipEvent {
+    pub timestamp: Timestamp,
+    pub event: MembershipEventKind,
+}
+
+/// An agent's membership timeline, oldest event first
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MembershipHistory {
+    pub agent: AgentPubKey,
+    pub status: MembershipStatus,
+    /// Start of the current verified period, if the agent is verified
+    pub verified_since: Option<Timestamp>,
+    pub events: Vec<MembershipEvent>,
+}
+
+/// Input for onboarding a neighbor in one step
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OnboardNeighborInput {
+    /// Scanned from the neighbor's QR code (see `generate_vouch_request`)
+    pub request: VouchRequest,
+    /// Name to start the neighbor's profile with, if they have none yet
+    pub nickname: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OnboardNeighborOutput {
+    pub vouch: VouchOutput,
+    /// False if the neighbor already had a profile or couldn't be reached
+    pub profile_created: bool,
+    /// Members asked to add their own vouch
+    pub notified: Vec<AgentPubKey>,
+}
+
+/// Input for issuing a guest pass
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IssueGuestPassInput {
+    pub guest: AgentPubKey,
+    pub scopes: Vec<GuestScope>,
+    /// At most `MAX_GUEST_PASS_VALIDITY_SECS` from now
+    pub expires_at: Timestamp,
+    pub note: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GuestPassInfo {
+    pub pass: GuestPass,
+    pub action_hash: ActionHash,
+    pub issuer: AgentPubKey,
+    /// Deleted by the issuer before it expired
+    pub revoked: bool,
+    /// Not revoked and within its validity window right now
+    pub active: bool,
+}
+
+/// Input for the participation check other zomes make before acting for an agent
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AccessCheckInput {
+    pub agent: AgentPubKey,
+    /// What the agent wants to do; None for anything guests can never do,
+    /// such as posting
+    pub scope: Option<GuestScope>,
+    /// The other side of a direct message, for `GuestScope::ChatIssuer`
+    #[serde(default)]
+    pub counterparty: Option<AgentPubKey>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct NominationInfo {
+    pub nomination: AnchorNomination,
+    pub action_hash: ActionHash,
+    pub nominator: AgentPubKey,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AttestationInfo {
+    pub attestation: MembershipAttestation,
+    pub action_hash: ActionHash,
+    pub anchor: AgentPubKey,
+}
+
 /// Anchor path for listing all trusted anchors
 const ALL_ANCHORS_PATH: &str = "all_trusted_anchors";
 
+/// Anchor path prefix for looking up an invite by its secret hash
+const PRE_VOUCH_PATH_PREFIX: &str = "pre_vouch";
+
+const DEFAULT_INVITE_VALIDITY_SECS: i64 = 7 * 24 * 60 * 60;
+const DEFAULT_NOMINATION_VALIDITY_SECS: i64 = 7 * 24 * 60 * 60;
+const INVITE_SECRET_BYTES: u32 = 32;
+/// How long a vouch request QR can be scanned after it is shown (10 minutes)
+const VOUCH_REQUEST_VALIDITY_MICROS: i64 = 10 * 60 * 1_000_000;
+
+/// Let invitees ask their inviter to convert a redeemed pre-vouch, and
+/// members send each other vouch requests
+#[hdk_extern]
+pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    let mut functions = BTreeSet::new();
+    functions.insert((zome_info()?.name, FunctionName::from("complete_pre_vouch")));
+    create_cap_grant(CapGrantEntry {
+        tag: "pre_vouch".to_string(),
+        access: CapAccess::Unrestricted,
+        functions: GrantedFunctions::Listed(functions),
+    })?;
+    
+    let mut functions = BTreeSet::new();
+    functions.insert((zome_info()?.name, FunctionName::from("recv_remote_signal")));
+    create_cap_grant(CapGrantEntry {
+        tag: "remote_signals".to_string(),
+        access: CapAccess::Unrestricted,
+        functions: GrantedFunctions::Listed(functions),
+    })?;
+    Ok(InitCallbackResult::Pass)
+}
+
+/// Forward vouch requests from other members to the UI
+///
+/// Only passed on if the sender really authored the vouch it mentions.
+#[hdk_extern]
+pub fn recv_remote_signal(signal: ExternIO) -> ExternResult<()> {
+    let signal: Signal = signal.decode().map_err(|e| {
+        wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to decode vouch signal: {:?}",
+            e
+        )))
+    })?;
+    
+    let provenance = call_info()?.provenance;
+    let Signal::VouchRequested { vouch_hash, vouchee, .. } = &signal else {
+        return Ok(());
+    };
+    let Some(record) = get(vouch_hash.clone(), GetOptions::default())? else {
+        return Ok(());
+    };
+    let Ok(Some(vouch)) = record.entry().to_app_option::<Vouch>() else {
+        return Ok(());
+    };
+    if record.action().author() != &provenance || &vouch.vouchee != vouchee {
+        return Ok(());
+    }
+    
+    emit_zome_signal(signal)
+}
+
 // ============================================================================
 // VOUCH FUNCTIONS
 // ============================================================================
@@ -83,12 +336,21 @@ pub fn create_vouch(input: CreateVouchInput) -> ExternResult<VouchOutput> {
         }
     }
     
+    // Anchor vouches carry the voucher's anchor record for integrity to check
+    let anchor = match input.vouch_type {
+        VouchType::Neighbor => None,
+        VouchType::Anchor => Some(get_anchor_record_hash(&voucher)?.ok_or(wasm_error!(
+            WasmErrorInner::Guest("Only trusted anchors can give anchor vouches".to_string())
+        ))?),
+    };
+    
     // Create the vouch entry
     let vouch = Vouch {
         vouchee: input.vouchee.clone(),
         vouch_type: input.vouch_type,
         created_at: sys_time()?,
         note: input.note,
+        anchor,
     };
     
     let action_hash = create_entry(EntryTypes::Vouch(vouch.clone()))?;
@@ -112,6 +374,20 @@ pub fn create_vouch(input: CreateVouchInput) -> ExternResult<VouchOutput> {
         (),
     )?;
     
+    // This vouch may be the one that verifies them
+    if is_verified(input.vouchee.clone())? {
+        let evidence = get_vouches_for(input.vouchee.clone())?
+            .into_iter()
+            .map(|info| info.action_hash)
+            .collect();
+        grant_badge(input.vouchee.clone(), evidence)?;
+    }
+    
+    emit_zome_signal(Signal::NewVouch {
+        vouch_hash: action_hash.clone(),
+        vouchee: input.vouchee,
+    })?;
+    
     Ok(VouchOutput {
         vouch,
         action_hash,
@@ -123,14 +399,45 @@ pub fn create_vouch(input: CreateVouchInput) -> ExternResult<VouchOutput> {
 /// Get all vouches that an agent has received
 #[hdk_extern]
 pub fn get_vouches_for(agent: AgentPubKey) -> ExternResult<Vec<VouchInfo>> {
+    let anchor_keys = get_anchor_keys()?;
+    get_vouches_received(agent, &anchor_keys)
+}
+
+/// Get vouches received by an agent or any of their earlier keys,
+/// classifying vouchers against a pre-fetched anchor list so batch callers
+/// only load anchors once
+///
+/// Each voucher is listed once, preferring a vouch for the current key.
+/// Vouches between an agent's own keys don't count.
+fn get_vouches_received(agent: AgentPubKey, anchor_keys: &[AgentPubKey]) -> ExternResult<Vec<VouchInfo>> {
+    let predecessors = key_predecessors(&agent)?;
+    let own_keys: HashSet<AgentPubKey> = predecessors
+        .iter()
+        .map(|(key, _)| key.clone())
+        .chain([agent.clone()])
+        .collect();
+    
+    let mut vouches = get_vouches_received_by_key(agent, anchor_keys, &[])?;
+    for (key, rotations) in &predecessors {
+        vouches.extend(get_vouches_received_by_key(key.clone(), anchor_keys, rotations)?);
+    }
+    
+    let mut vouchers = HashSet::new();
+    vouches.retain(|info| !own_keys.contains(&info.voucher) && vouchers.insert(info.voucher.clone()));
+    Ok(vouches)
+}
+
+/// Vouches received by one key, reached from the agent through `rotations`
+fn get_vouches_received_by_key(
+    agent: AgentPubKey,
+    anchor_keys: &[AgentPubKey],
+    rotations: &[ActionHash],
+) -> ExternResult<Vec<VouchInfo>> {
     let links = get_links(
-        LinkQuery::try_new(agent.clone(), LinkTypes::AgentToVouchesReceived)?,
+        LinkQuery::try_new(agent, LinkTypes::AgentToVouchesReceived)?,
         GetStrategy::Local,
     )?;
     
-    let anchors = get_all_anchors(())?;
-    let anchor_keys: Vec<AgentPubKey> = anchors.iter().map(|a| a.agent.clone()).collect();
-    
     let mut vouches = Vec::new();
     
     for link in links {
@@ -145,13 +452,18 @@ pub fn get_vouches_for(agent: AgentPubKey) -> ExternResult<Vec<VouchInfo>> {
                 .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
             {
                 let voucher = record.action().author().clone();
-                let is_from_anchor = anchor_keys.contains(&voucher);
+                // Vouches a key gives after handing itself over don't count
+                if retired_since(&voucher)?.is_some_and(|since| since <= record.action().timestamp()) {
+                    continue;
+                }
+                let is_from_anchor = vouch.vouch_type == VouchType::Anchor && anchor_keys.contains(&voucher);
                 
                 vouches.push(VouchInfo {
                     voucher,
                     vouch,
                     action_hash: record.action_address().clone(),
                     is_from_anchor,
+                    via_rotations: rotations.to_vec(),
                 });
             }
         }
@@ -160,15 +472,23 @@ pub fn get_vouches_for(agent: AgentPubKey) -> ExternResult<Vec<VouchInfo>> {
     Ok(vouches)
 }
 
-/// Vouch for a neighbor with a physical handshake verification
+/// Vouch for a neighbor after meeting them in person
 /// 
-/// Simplified function that defaults to PhysicalHandshake vouch type.
-/// This is the primary function called when scanning a neighbor's QR code.
+/// Gives an Anchor vouch if the caller is a trusted anchor, otherwise a
+/// Neighbor vouch. This is the primary function called when scanning a
+/// neighbor's QR code.
 #[hdk_extern]
 pub fn vouch_for_neighbor(target_agent: AgentPubKey) -> ExternResult<VouchOutput> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let vouch_type = if get_anchor_record_hash(&me)?.is_some() {
+        VouchType::Anchor
+    } else {
+        VouchType::Neighbor
+    };
+    
     create_vouch(CreateVouchInput {
         vouchee: target_agent,
-        vouch_type: VouchType::Neighbor,
+        vouch_type,
         note: None,
     })
 }
@@ -186,8 +506,10 @@ pub fn get_my_vouches(_: ()) -> ExternResult<Vec<VouchInfo>> {
 /// 
 /// Returns true if the agent:
 /// - Is a trusted anchor, OR
-/// - Has at least 1 vouch from a trusted anchor, OR
-/// - Has at least 2 vouches from verified members
+/// - Has enough vouches from trusted anchors, OR
+/// - Has enough vouches from verified members (not merely pending ones)
+///
+/// The thresholds come from the DNA's trust policy (see `get_trust_policy`).
 #[hdk_extern]
 pub fn is_verified(agent: AgentPubKey) -> ExternResult<bool> {
     let info = get_membership_status(agent)?;
@@ -244,182 +566,1349 @@ pub fn get_my_given_vouches(_: ()) -> ExternResult<Vec<VouchOutput>> {
     get_vouches_given_by(agent)
 }
 
+/// How far one of my vouches has spread, from its validation receipts
+///
+/// A vouch only counts toward someone's membership once peers can see it, so
+/// the UI can show whether a fresh vouch has landed yet.
+#[hdk_extern]
+pub fn get_publish_status(vouch_hash: ActionHash) -> ExternResult<PublishStatusOutput> {
+    publish_status::<Vouch>(vouch_hash, "Vouch")
+}
+
 // ============================================================================
-// MEMBERSHIP STATUS FUNCTIONS
+// PRE-VOUCH INVITATIONS
 // ============================================================================
 
-/// Check if vouch threshold is met for verification
-fn vouch_threshold_met(_is_anchor: bool, vouches_from_anchors: usize, vouches_from_members: usize) -> bool {
-    // 1 vouch from anchor OR 2+ vouches from verified members
-    vouches_from_anchors >= ANCHOR_VOUCHES_REQUIRED || vouches_from_members >= VOUCHES_REQUIRED
-}
-
-/// Get the membership status for an agent
+/// Vouch for a neighbor who hasn't joined yet
+///
+/// Returns a one-time secret to hand to the neighbor (e.g. as a QR code).
+/// When they join and call `redeem_invite` with it, the pending vouch is
+/// converted into a real Vouch for their agent key.
 #[hdk_extern]
-pub fn get_membership_status(agent: AgentPubKey) -> ExternResult<MembershipInfo> {
-    let vouches_received = get_vouches_for(agent.clone())?;
-    let vouches_given = get_vouches_given_by(agent.clone())?;
-    
-    // Check if this agent is an anchor
-    let anchors = get_all_anchors(())?;
-    let is_anchor = anchors.iter().any(|a| a.agent == agent);
-    
-    // Count vouches by type
-    let vouches_from_anchors = vouches_received.iter().filter(|v| v.is_from_anchor).count();
-    let vouches_from_members = vouches_received.len(); // Total vouches (simplified)
-    
-    // Determine status (using only Pending, Verified, Anchor from integrity)
-    let status = if is_anchor {
-        MembershipStatus::Anchor
-    } else if vouch_threshold_met(is_anchor, vouches_from_anchors, vouches_from_members) {
-        MembershipStatus::Verified
-    } else {
-        MembershipStatus::Pending
+pub fn create_pre_vouch(input: CreatePreVouchInput) -> ExternResult<PreVouchOutput> {
+    let inviter = agent_info()?.agent_initial_pubkey;
+
+    // Checked again when the invite converts, but fail before handing out a secret
+    if input.vouch_type == VouchType::Anchor && get_anchor_record_hash(&inviter)?.is_none() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only trusted anchors can give anchor vouches".to_string()
+        )));
+    }
+
+    let secret = hex_encode(&random_bytes(INVITE_SECRET_BYTES)?);
+    let secret_hash = hash_sha256(secret.as_bytes().to_vec())?;
+
+    let created_at = sys_time()?;
+    let validity_secs = input.validity_secs.unwrap_or(DEFAULT_INVITE_VALIDITY_SECS);
+    let invite = InvitedNeighbor {
+        secret_hash: secret_hash.clone(),
+        vouch_type: input.vouch_type,
+        note: input.note,
+        created_at,
+        expires_at: Timestamp::from_micros(created_at.as_micros() + validity_secs * 1_000_000),
+        redeem_key: Some(invite_public_key(&invite_signing_key(&secret)?)?),
     };
-    
-    Ok(MembershipInfo {
-        agent,
-        status,
-        vouches_received,
-        vouches_given,
-        is_anchor,
-    })
-}
 
-/// Get the calling agent's membership status
-#[hdk_extern]
-pub fn get_my_membership_status(_: ()) -> ExternResult<MembershipInfo> {
-    let agent = agent_info()?.agent_initial_pubkey;
-    get_membership_status(agent)
-}
+    let action_hash = create_entry(EntryTypes::InvitedNeighbor(invite.clone()))?;
 
-/// Check if an agent can participate fully (post, etc.)
-#[hdk_extern]
-pub fn can_participate(agent: AgentPubKey) -> ExternResult<bool> {
-    let info = get_membership_status(agent)?;
-    Ok(matches!(
-        info.status,
-        MembershipStatus::Verified | MembershipStatus::Anchor
-    ))
-}
+    create_link(secret_path_hash(&secret_hash)?, action_hash.clone(), LinkTypes::SecretToInvite, ())?;
+    create_link(inviter, action_hash.clone(), LinkTypes::AgentToInvites, ())?;
 
-/// Check if the calling agent can participate
-#[hdk_extern]
-pub fn can_i_participate(_: ()) -> ExternResult<bool> {
-    let agent = agent_info()?.agent_initial_pubkey;
-    can_participate(agent)
+    Ok(PreVouchOutput {
+        secret,
+        invite,
+        action_hash,
+    })
 }
 
-// ============================================================================
-// TRUSTED ANCHOR FUNCTIONS
-// ============================================================================
-
-/// Initialize the first trusted anchor (founding member)
+/// Redeem a pre-vouch invite as the newly joined neighbor
 ///
-/// This should only be called once when bootstrapping the neighborhood.
-/// The first agent to call this becomes the founding anchor.
+/// Records a signature made with the key the secret seeds, so the secret
+/// itself is never published, then asks the inviter's node to create the
+/// vouch. Redeeming the same invite again is a no-op that retries the
+/// conversion.
 #[hdk_extern]
-pub fn initialize_as_anchor(_: ()) -> ExternResult<TrustedAnchor> {
-    let agent = agent_info()?.agent_initial_pubkey;
-    
-    // Check if any anchors exist
-    let existing_anchors = get_all_anchors(())?;
-    if !existing_anchors.is_empty() {
-        // If anchors exist, check if this is being called by an existing anchor
-        let is_existing_anchor = existing_anchors.iter().any(|a| a.agent == agent);
-        if !is_existing_anchor {
-            return Err(wasm_error!(WasmErrorInner::Guest(
-                "Anchors already exist. Only existing anchors can designate new anchors.".to_string()
-            )));
-        }
-        // Already an anchor
+pub fn redeem_invite(secret: String) -> ExternResult<RedeemInviteOutput> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let secret = secret.trim();
+    let secret_hash = hash_sha256(secret.as_bytes().to_vec())?;
+
+    let links = get_links(
+        LinkQuery::try_new(secret_path_hash(&secret_hash)?, LinkTypes::SecretToInvite)?,
+        GetStrategy::Local,
+    )?;
+    let Some(invite_hash) = links.into_iter().find_map(|link| link.target.into_action_hash()) else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Invite not found".to_string())));
+    };
+    let (inviter, invite) = get_invite(invite_hash.clone())?;
+
+    if inviter == me {
         return Err(wasm_error!(WasmErrorInner::Guest(
-            "You are already a trusted anchor.".to_string()
+            "Cannot redeem your own invite".to_string()
         )));
     }
-    
-    // Create the anchor entry
-    let anchor = TrustedAnchor {
-        agent: agent.clone(),
-        created_at: sys_time()?,
+
+    let redemptions = get_redemptions(invite_hash.clone())?;
+    let redemption_hash = match redemptions.first() {
+        Some((redeemer, hash)) if *redeemer == me => hash.clone(),
+        Some(_) => {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "This invite has already been redeemed".to_string()
+            )));
+        }
+        None => {
+            if sys_time()? > invite.expires_at {
+                return Err(wasm_error!(WasmErrorInner::Guest(
+                    "This invite has expired".to_string()
+                )));
+            }
+            let signature = invite_signing_key(secret)?.sign(&redemption_payload(&invite_hash, &me));
+            let redemption_hash = create_entry(EntryTypes::InviteRedemption(InviteRedemption {
+                invite: invite_hash.clone(),
+                signature: Signature(signature.to_bytes()),
+            }))?;
+            create_link(
+                invite_hash.clone(),
+                redemption_hash.clone(),
+                LinkTypes::InviteToRedemptions,
+                (),
+            )?;
+            redemption_hash
+        }
     };
-    
-    let action_hash = create_entry(EntryTypes::TrustedAnchor(anchor.clone()))?;
-    let entry_hash = hash_entry(&anchor)?;
-    
-    // Link to all anchors path
-    let anchor_path = anchor_path_hash()?;
-    create_link(anchor_path, entry_hash, LinkTypes::AllAnchors, ())?;
-    
-    Ok(anchor)
+
+    // The inviter may be offline; they'll convert it on their next sweep
+    let vouch = match call_remote(
+        inviter.clone(),
+        zome_info()?.name,
+        FunctionName::from("complete_pre_vouch"),
+        None,
+        redemption_hash.clone(),
+    )? {
+        ZomeCallResponse::Ok(result) => result.decode::<Option<VouchOutput>>().ok().flatten(),
+        _ => None,
+    };
+
+    Ok(RedeemInviteOutput {
+        inviter,
+        invite_hash,
+        redemption_hash,
+        vouch,
+    })
 }
 
-/// Designate a new trusted anchor (only existing anchors can do this)
+/// Convert a redeemed invite into a vouch (called remotely by the invitee)
 #[hdk_extern]
-pub fn designate_anchor(new_anchor_agent: AgentPubKey) -> ExternResult<TrustedAnchor> {
-    let designator = agent_info()?.agent_initial_pubkey;
-    
-    // Check if caller is an anchor
-    let existing_anchors = get_all_anchors(())?;
-    let is_anchor = existing_anchors.iter().any(|a| a.agent == designator);
-    
-    if !is_anchor {
-        return Err(wasm_error!(WasmErrorInner::Guest(
-            "Only trusted anchors can designate new anchors.".to_string()
-        )));
-    }
-    
-    // Check if target is already an anchor
-    if existing_anchors.iter().any(|a| a.agent == new_anchor_agent) {
+pub fn complete_pre_vouch(redemption_hash: ActionHash) -> ExternResult<Option<VouchOutput>> {
+    let me = agent_info()?.agent_initial_pubkey;
+
+    let Some(record) = get(redemption_hash, GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Redemption not found".to_string())));
+    };
+    let redemption = record
+        .entry()
+        .to_app_option::<InviteRedemption>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Not an invite redemption".to_string())))?;
+
+    let (inviter, invite) = get_invite(redemption.invite.clone())?;
+    if inviter != me {
         return Err(wasm_error!(WasmErrorInner::Guest(
-            "This agent is already a trusted anchor.".to_string()
+            "This invite was created by another agent".to_string()
         )));
     }
-    
-    // Create the anchor entry
-    let anchor = TrustedAnchor {
-        agent: new_anchor_agent,
-        created_at: sys_time()?,
-    };
-    
-    let action_hash = create_entry(EntryTypes::TrustedAnchor(anchor.clone()))?;
-    let entry_hash = hash_entry(&anchor)?;
-    
-    // Link to all anchors path
-    let anchor_path = anchor_path_hash()?;
-    create_link(anchor_path, entry_hash, LinkTypes::AllAnchors, ())?;
-    
-    Ok(anchor)
+
+    convert_invite(redemption.invite, invite)
 }
 
-/// Get all trusted anchors
+/// Create vouches for all of my invites that were redeemed while I was offline
 #[hdk_extern]
-pub fn get_all_anchors(_: ()) -> ExternResult<Vec<TrustedAnchor>> {
-    let anchor_path = anchor_path_hash()?;
-    let links = get_links(
-        LinkQuery::try_new(anchor_path, LinkTypes::AllAnchors)?,
-        GetStrategy::Local,
-    )?;
-    
-    let mut anchors = Vec::new();
-    
-    for link in links {
-        let entry_hash = EntryHash::try_from(link.target).map_err(|_| {
-            wasm_error!(WasmErrorInner::Guest("Invalid entry hash in link".to_string()))
-        })?;
-        
-        if let Some(record) = get(entry_hash, GetOptions::default())? {
-            if let Some(anchor) = record
-                .entry()
-                .to_app_option::<TrustedAnchor>()
-                .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
-            {
-                anchors.push(anchor);
+pub fn convert_redeemed_invites(_: ()) -> ExternResult<Vec<VouchOutput>> {
+    let mut converted = Vec::new();
+
+    for info in get_my_pre_vouches(())? {
+        if info.status == PreVouchStatus::Redeemed {
+            if let Some(vouch) = convert_invite(info.action_hash, info.invite)? {
+                converted.push(vouch);
             }
         }
     }
-    
-    Ok(anchors)
+
+    Ok(converted)
+}
+
+/// Get the pre-vouches I have created and whether they've been used
+#[hdk_extern]
+pub fn get_my_pre_vouches(_: ()) -> ExternResult<Vec<PreVouchInfo>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+
+    let links = get_links(
+        LinkQuery::try_new(me, LinkTypes::AgentToInvites)?,
+        GetStrategy::Local,
+    )?;
+
+    let mut invites = Vec::new();
+
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let (_, invite) = get_invite(action_hash.clone())?;
+
+        let vouch_hash = get_invite_vouch_hash(action_hash.clone())?;
+        let redeemed_by = get_redemptions(action_hash.clone())?
+            .into_iter()
+            .next()
+            .map(|(redeemer, _)| redeemer);
+
+        let status = match (&vouch_hash, &redeemed_by) {
+            (Some(_), _) => PreVouchStatus::Converted,
+            (None, Some(_)) => PreVouchStatus::Redeemed,
+            (None, None) if now > invite.expires_at => PreVouchStatus::Expired,
+            (None, None) => PreVouchStatus::Pending,
+        };
+
+        invites.push(PreVouchInfo {
+            invite,
+            action_hash,
+            status,
+            redeemed_by,
+            vouch_hash,
+        });
+    }
+
+    Ok(invites)
+}
+
+/// Vouch for whoever redeemed the invite first, unless already converted
+fn convert_invite(invite_hash: ActionHash, invite: InvitedNeighbor) -> ExternResult<Option<VouchOutput>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let given = get_vouches_given_by(me)?;
+
+    if let Some(vouch_hash) = get_invite_vouch_hash(invite_hash.clone())? {
+        return Ok(given.into_iter().find(|v| v.action_hash == vouch_hash));
+    }
+
+    let Some((invitee, redemption_hash)) = get_redemptions(invite_hash.clone())?.into_iter().next() else {
+        return Ok(None);
+    };
+
+    // A direct vouch may already exist if we met before they redeemed
+    let vouch = match given.into_iter().find(|v| v.vouch.vouchee == invitee) {
+        Some(existing) => existing,
+        None => create_vouch(CreateVouchInput {
+            vouchee: invitee,
+            vouch_type: invite.vouch_type,
+            note: invite.note,
+        })?,
+    };
+
+    // The tag names the redemption, so validation can check the vouch is for its author
+    create_link(
+        invite_hash,
+        vouch.action_hash.clone(),
+        LinkTypes::InviteToVouch,
+        LinkTag::new(redemption_hash.get_raw_39().to_vec()),
+    )?;
+
+    Ok(Some(vouch))
+}
+
+fn get_invite(invite_hash: ActionHash) -> ExternResult<(AgentPubKey, InvitedNeighbor)> {
+    let Some(record) = get(invite_hash, GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Invite not found".to_string())));
+    };
+    let invite = record
+        .entry()
+        .to_app_option::<InvitedNeighbor>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Not a pre-vouch invite".to_string())))?;
+    Ok((record.action().author().clone(), invite))
+}
+
+/// Redeemers of an invite with their redemption hashes, earliest redemption
+/// action first
+fn get_redemptions(invite_hash: ActionHash) -> ExternResult<Vec<(AgentPubKey, ActionHash)>> {
+    let links = get_links(
+        LinkQuery::try_new(invite_hash, LinkTypes::InviteToRedemptions)?,
+        GetStrategy::Local,
+    )?;
+
+    let mut redemptions = Vec::new();
+    for link in links {
+        let Some(redemption_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(redemption_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let action = record.action();
+        redemptions.push((action.timestamp(), action.author().clone(), redemption_hash));
+    }
+    redemptions.sort_by(|a, b| (a.0, &a.2).cmp(&(b.0, &b.2)));
+
+    Ok(redemptions
+        .into_iter()
+        .map(|(_, redeemer, redemption_hash)| (redeemer, redemption_hash))
+        .collect())
+}
+
+/// The ed25519 key an invite secret seeds (see `invite_key_seed`)
+fn invite_signing_key(secret: &str) -> ExternResult<SigningKey> {
+    let seed: [u8; 32] = invite_key_seed(secret)?
+        .try_into()
+        .map_err(|_| wasm_error!(WasmErrorInner::Guest("Invite key seed must be 32 bytes".to_string())))?;
+    Ok(SigningKey::from_bytes(&seed))
+}
+
+/// The invite key as an agent key, so validation can check signatures with it
+fn invite_public_key(key: &SigningKey) -> ExternResult<AgentPubKey> {
+    let core = key.verifying_key().to_bytes();
+    // Holochain's DHT location: a 16-byte BLAKE2b of the key, XOR-folded to 4 bytes
+    let mut location = [0u8; 4];
+    for (i, byte) in hash_blake2b(core.to_vec(), 16)?.iter().enumerate() {
+        location[i % 4] ^= byte;
+    }
+    Ok(AgentPubKey::from_raw_36([core.as_slice(), &location].concat()))
+}
+
+fn get_invite_vouch_hash(invite_hash: ActionHash) -> ExternResult<Option<ActionHash>> {
+    let links = get_links(
+        LinkQuery::try_new(invite_hash, LinkTypes::InviteToVouch)?,
+        GetStrategy::Local,
+    )?;
+    Ok(links.into_iter().find_map(|link| link.target.into_action_hash()))
+}
+
+// ============================================================================
+// ONBOARDING
+// ============================================================================
+
+/// Onboard a neighbor from their QR code in one step
+///
+/// Checks the signed vouch request, vouches for the neighbor (an Anchor vouch
+/// if the caller is an anchor), asks the neighbor's node to start a profile
+/// with `nickname`, and, if they aren't verified yet, asks the caller's
+/// vouching circle to add their vouches. That circle is the verified members
+/// the caller vouched for or was vouched by, as the people most likely to
+/// know the neighbor too.
+#[hdk_extern]
+pub fn onboard_neighbor(input: OnboardNeighborInput) -> ExternResult<OnboardNeighborOutput> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let request = input.request;
+    
+    let now = sys_time()?;
+    if now < request.timestamp || now.as_micros() - request.timestamp.as_micros() > VOUCH_REQUEST_VALIDITY_MICROS {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "This QR code has expired; ask your neighbor to show a new one".to_string()
+        )));
+    }
+    let payload = vouch_request_payload(&request.agent, request.timestamp);
+    if !verify_signature(request.agent.clone(), request.signature.clone(), payload)? {
+        return Err(wasm_error!(WasmErrorInner::Guest("Invalid vouch request QR code".to_string())));
+    }
+    
+    let vouch = vouch_for_neighbor(request.agent.clone())?;
+    let profile_created = bootstrap_neighbor_profile(&request.agent, &input.nickname, &vouch.action_hash);
+    
+    let mut notified = Vec::new();
+    if !is_verified(request.agent.clone())? {
+        let mut seen = HashSet::new();
+        let circle: Vec<AgentPubKey> = get_vouches_given_by(me.clone())?
+            .into_iter()
+            .map(|given| given.vouch.vouchee)
+            .chain(get_vouches_for(me.clone())?.into_iter().map(|received| received.voucher))
+            .filter(|agent| *agent != me && *agent != request.agent && seen.insert(agent.clone()))
+            .collect();
+        
+        let mut resolver = MembershipResolver::new()?;
+        for agent in circle {
+            if resolver.counts_as_voucher(&agent)? {
+                notified.push(agent);
+            }
+        }
+        
+        if !notified.is_empty() {
+            send_remote_signal(
+                Signal::VouchRequested {
+                    vouch_hash: vouch.action_hash.clone(),
+                    vouchee: request.agent,
+                    nickname: input.nickname,
+                },
+                notified.clone(),
+            )?;
+        }
+    }
+    
+    Ok(OnboardNeighborOutput {
+        vouch,
+        profile_created,
+        notified,
+    })
+}
+
+/// Mirror of the profile zome's BootstrapProfileInput
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct BootstrapProfileInput {
+    nickname: String,
+    vouch_hash: ActionHash,
+}
+
+/// Ask the neighbor's node to start their profile; false if it didn't
+///
+/// The neighbor is usually standing right there, but an unreachable node or
+/// an existing profile isn't a reason to fail the onboarding.
+fn bootstrap_neighbor_profile(neighbor: &AgentPubKey, nickname: &str, vouch_hash: &ActionHash) -> bool {
+    let response = call_remote(
+        neighbor.clone(),
+        ZomeName::from("profile"),
+        FunctionName::from("bootstrap_profile"),
+        None,
+        BootstrapProfileInput {
+            nickname: nickname.to_string(),
+            vouch_hash: vouch_hash.clone(),
+        },
+    );
+    
+    match response {
+        Ok(ZomeCallResponse::Ok(result)) => {
+            matches!(result.decode::<Option<serde::de::IgnoredAny>>(), Ok(Some(_)))
+        }
+        _ => false,
+    }
+}
+
+// ============================================================================
+// MEMBERSHIP STATUS FUNCTIONS
+// ============================================================================
+
+/// Get the neighborhood's trust policy
+///
+/// The vouch thresholds are set in the DNA properties at install time, so
+/// UIs should display these rather than assume the defaults.
+#[hdk_extern]
+pub fn get_trust_policy(_: ()) -> ExternResult<TrustPolicy> {
+    trust_policy()
+}
+
+/// Check if vouch threshold is met for verification
+fn vouch_threshold_met(policy: &TrustPolicy, vouches_from_anchors: usize, vouches_from_members: usize) -> bool {
+    vouches_from_anchors >= policy.anchor_vouches_required || vouches_from_members >= policy.vouches_required
+}
+
+/// Resolves membership status by checking that vouchers are themselves verified
+///
+/// A vouch only counts if its voucher is verified, so the check recurses
+/// through vouchers back toward the anchors, at most `max_vouch_depth` hops.
+/// An agent already on the current path counts as unverified, which stops
+/// pending accounts from verifying each other in a loop. Results are cached
+/// for the lifetime of the resolver, so batch callers should share one.
+struct MembershipResolver {
+    policy: TrustPolicy,
+    anchor_keys: Vec<AgentPubKey>,
+    /// Hops from an anchor, and the hop limit the result was computed under
+    depths: HashMap<AgentPubKey, (Option<usize>, usize)>,
+    visiting: HashSet<AgentPubKey>,
+    cycles_hit: usize,
+}
+
+impl MembershipResolver {
+    fn new() -> ExternResult<Self> {
+        Ok(Self {
+            policy: trust_policy()?,
+            anchor_keys: get_anchor_keys()?,
+            depths: HashMap::new(),
+            visiting: HashSet::new(),
+            cycles_hit: 0,
+        })
+    }
+
+    fn is_anchor(&self, agent: &AgentPubKey) -> bool {
+        self.anchor_keys.contains(agent)
+    }
+
+    /// Status of an agent whose received vouches are already loaded
+    ///
+    /// A key that has been handed over is pending; its successor holds the membership.
+    fn status(&mut self, agent: &AgentPubKey, vouches_received: &[VouchInfo]) -> ExternResult<MembershipStatus> {
+        if retired_since(agent)?.is_some() {
+            return Ok(MembershipStatus::Pending);
+        }
+        if self.is_anchor(agent) {
+            return Ok(MembershipStatus::Anchor);
+        }
+
+        self.visiting.insert(agent.clone());
+        let depth = self.depth_from(vouches_received, self.policy.max_vouch_depth);
+        self.visiting.remove(agent);
+
+        Ok(if depth?.is_some() {
+            MembershipStatus::Verified
+        } else {
+            MembershipStatus::Pending
+        })
+    }
+
+    /// Hops between an agent and an anchor, if no more than `limit`
+    fn depth(&mut self, agent: &AgentPubKey, limit: usize) -> ExternResult<Option<usize>> {
+        if self.is_anchor(agent) {
+            return Ok(Some(0));
+        }
+        if limit == 0 {
+            return Ok(None);
+        }
+
+        match self.depths.get(agent) {
+            Some((Some(depth), _)) => return Ok(Some(*depth).filter(|d| *d <= limit)),
+            Some((None, checked)) if *checked >= limit => return Ok(None),
+            _ => {}
+        }
+
+        if !self.visiting.insert(agent.clone()) {
+            self.cycles_hit += 1;
+            return Ok(None);
+        }
+
+        let cycles_before = self.cycles_hit;
+        let vouches = get_vouches_received(agent.clone(), &self.anchor_keys)?;
+        let depth = self.depth_from(&vouches, limit);
+        self.visiting.remove(agent);
+        let depth = depth?;
+
+        // A negative result reached through a cycle depends on the path taken
+        if depth.is_some() || self.cycles_hit == cycles_before {
+            self.depths.insert(agent.clone(), (depth, limit));
+        }
+
+        Ok(depth)
+    }
+
+    /// Depth implied by a set of received vouches, if no more than `limit`
+    fn depth_from(&mut self, vouches: &[VouchInfo], limit: usize) -> ExternResult<Option<usize>> {
+        let vouches_from_anchors = vouches.iter().filter(|v| v.is_from_anchor).count();
+        if vouch_threshold_met(&self.policy, vouches_from_anchors, 0) {
+            return Ok(Some(1));
+        }
+
+        let mut voucher_depths = Vec::new();
+        for vouch in vouches {
+            if let Some(depth) = self.depth(&vouch.voucher, limit - 1)? {
+                voucher_depths.push(depth);
+            }
+        }
+        voucher_depths.sort_unstable();
+
+        // Only as close to an anchor as the furthest voucher needed to meet the threshold
+        Ok(voucher_depths
+            .get(self.policy.vouches_required - 1)
+            .map(|depth| depth + 1)
+            .filter(|depth| *depth <= limit))
+    }
+
+    /// Whether a voucher's vouch counts toward someone else's threshold
+    fn counts_as_voucher(&mut self, voucher: &AgentPubKey) -> ExternResult<bool> {
+        let limit = self.policy.max_vouch_depth.saturating_sub(1);
+        Ok(self.depth(voucher, limit)?.is_some())
+    }
+}
+
+/// Get the membership status for an agent
+///
+/// Only vouches from verified members and anchors count toward the threshold.
+#[hdk_extern]
+pub fn get_membership_status(agent: AgentPubKey) -> ExternResult<MembershipInfo> {
+    let mut resolver = MembershipResolver::new()?;
+    let vouches_received = get_vouches_received(agent.clone(), &resolver.anchor_keys)?;
+    let vouches_given = get_vouches_given_by(agent.clone())?;
+    
+    // Check if this agent is an anchor
+    let is_anchor = resolver.is_anchor(&agent);
+    let status = resolver.status(&agent, &vouches_received)?;
+    
+    Ok(MembershipInfo {
+        agent,
+        status,
+        vouches_received,
+        vouches_given,
+        is_anchor,
+    })
+}
+
+/// Get the calling agent's membership status
+#[hdk_extern]
+pub fn get_my_membership_status(_: ()) -> ExternResult<MembershipInfo> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    get_membership_status(agent)
+}
+
+/// Get membership summaries for many agents at once
+///
+/// Used by other zomes (e.g. profile) to decorate lists without making
+/// one cross-zome call per agent. The anchor list is only fetched once,
+/// and voucher statuses are shared across the whole batch.
+#[hdk_extern]
+pub fn get_membership_summaries(agents: Vec<AgentPubKey>) -> ExternResult<Vec<MembershipSummary>> {
+    let mut resolver = MembershipResolver::new()?;
+    
+    let mut summaries = Vec::with_capacity(agents.len());
+    
+    for agent in agents {
+        let is_anchor = resolver.is_anchor(&agent);
+        let status = if is_anchor && retired_since(&agent)?.is_none() {
+            MembershipStatus::Anchor
+        } else {
+            let vouches_received = get_vouches_received(agent.clone(), &resolver.anchor_keys)?;
+            resolver.status(&agent, &vouches_received)?
+        };
+        
+        summaries.push(MembershipSummary {
+            agent,
+            status,
+            is_anchor,
+        });
+    }
+    
+    Ok(summaries)
+}
+
+/// Check if an agent can participate fully (post, etc.)
+#[hdk_extern]
+pub fn can_participate(agent: AgentPubKey) -> ExternResult<bool> {
+    let info = get_membership_status(agent)?;
+    Ok(matches!(
+        info.status,
+        MembershipStatus::Verified | MembershipStatus::Anchor
+    ))
+}
+
+/// Check if the calling agent can participate
+#[hdk_extern]
+pub fn can_i_participate(_: ()) -> ExternResult<bool> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    can_participate(agent)
+}
+
+// ============================================================================
+// GUEST PASSES
+// ============================================================================
+
+/// Give a contractor or visiting relative limited, temporary access
+///
+/// Only verified members can issue passes. The pass lapses on its own at
+/// `expires_at`; `revoke_guest_pass` ends it early.
+#[hdk_extern]
+pub fn issue_guest_pass(input: IssueGuestPassInput) -> ExternResult<GuestPassInfo> {
+    let issuer = agent_info()?.agent_initial_pubkey;
+    
+    if !is_verified(issuer.clone())? {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only verified members can issue guest passes.".to_string()
+        )));
+    }
+    if is_verified(input.guest.clone())? {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "This neighbor is already a verified member.".to_string()
+        )));
+    }
+    
+    let mut scopes = Vec::new();
+    for scope in input.scopes {
+        if !scopes.contains(&scope) {
+            scopes.push(scope);
+        }
+    }
+    
+    let pass = GuestPass {
+        guest: input.guest.clone(),
+        scopes,
+        issued_at: sys_time()?,
+        expires_at: input.expires_at,
+        note: input.note,
+    };
+    
+    let action_hash = create_entry(EntryTypes::GuestPass(pass.clone()))?;
+    create_link(input.guest.clone(), action_hash.clone(), LinkTypes::AgentToGuestPasses, ())?;
+    create_link(issuer.clone(), action_hash.clone(), LinkTypes::AgentToIssuedGuestPasses, ())?;
+    
+    emit_zome_signal(Signal::GuestPassIssued {
+        pass_hash: action_hash.clone(),
+        guest: input.guest,
+    })?;
+    
+    Ok(GuestPassInfo {
+        pass,
+        action_hash,
+        issuer,
+        revoked: false,
+        active: true,
+    })
+}
+
+/// End a guest pass before it expires
+#[hdk_extern]
+pub fn revoke_guest_pass(pass_hash: ActionHash) -> ExternResult<ActionHash> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let Some(info) = get_guest_pass(pass_hash.clone())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Guest pass not found".into())));
+    };
+    
+    if info.issuer != me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the member who issued a guest pass can revoke it.".to_string()
+        )));
+    }
+    
+    delete_entry(pass_hash)
+}
+
+/// Passes the calling agent has issued, newest first
+#[hdk_extern]
+pub fn get_my_issued_guest_passes(_: ()) -> ExternResult<Vec<GuestPassInfo>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    guest_passes_from(get_links(
+        LinkQuery::try_new(me, LinkTypes::AgentToIssuedGuestPasses)?,
+        GetStrategy::Local,
+    )?)
+}
+
+/// Every pass issued to an agent, including expired and revoked ones, newest first
+#[hdk_extern]
+pub fn get_guest_passes_for(agent: AgentPubKey) -> ExternResult<Vec<GuestPassInfo>> {
+    guest_passes_from(get_list_links(agent, LinkTypes::AgentToGuestPasses, false)?)
+}
+
+/// Check whether an agent may do something, for other zomes' participation checks
+///
+/// Only guests are restricted: agents who have never held a guest pass, and
+/// guests who have since become verified members, are always allowed. A
+/// guest needs an active pass granting `scope`, and for `ChatIssuer` the
+/// counterparty must be the member who issued that pass. Once every pass has
+/// expired or been revoked the guest can do nothing.
+#[hdk_extern]
+pub fn check_access(input: AccessCheckInput) -> ExternResult<bool> {
+    let passes = get_guest_passes_for(input.agent.clone())?;
+    if passes.is_empty() || can_participate(input.agent)? {
+        return Ok(true);
+    }
+    
+    let Some(scope) = input.scope else {
+        return Ok(false);
+    };
+    
+    Ok(passes.iter().filter(|info| info.active).any(|info| {
+        info.pass.scopes.contains(&scope)
+            && (scope != GuestScope::ChatIssuer || input.counterparty.as_ref() == Some(&info.issuer))
+    }))
+}
+
+fn get_guest_pass(action_hash: ActionHash) -> ExternResult<Option<GuestPassInfo>> {
+    let Some(Details::Record(details)) = get_details(action_hash.clone(), GetOptions::default())? else {
+        return Ok(None);
+    };
+    let Some(pass) = details.record.entry().to_app_option::<GuestPass>().ok().flatten() else {
+        return Ok(None);
+    };
+    
+    let now = sys_time()?;
+    let revoked = !details.deletes.is_empty();
+    Ok(Some(GuestPassInfo {
+        active: !revoked && pass.issued_at <= now && now < pass.expires_at,
+        issuer: details.record.action().author().clone(),
+        pass,
+        action_hash,
+        revoked,
+    }))
+}
+
+fn guest_passes_from(links: Vec<Link>) -> ExternResult<Vec<GuestPassInfo>> {
+    let mut passes = Vec::new();
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        if let Some(info) = get_guest_pass(action_hash)? {
+            passes.push(info);
+        }
+    }
+    passes.sort_by(|a, b| b.pass.issued_at.cmp(&a.pass.issued_at));
+    Ok(passes)
+}
+
+// ============================================================================
+// MEMBERSHIP PROOFS
+// ============================================================================
+
+/// Make sure the caller has a MembershipProof on their chain, committing one
+/// if they're verified and have none yet
+///
+/// Feed, toolshed, events, and spaces call this before publishing, because
+/// their integrity zomes reject writes from authors without a proof. Returns
+/// None if the caller isn't verified; the write is then left to fail
+/// validation unless the neighborhood doesn't require vouching.
+#[hdk_extern]
+pub fn ensure_membership_proof(_: ()) -> ExternResult<Option<ActionHash>> {
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::MembershipProof.try_into()?)
+            .include_entries(false),
+    )?;
+    if let Some(existing) = records.last() {
+        return Ok(Some(existing.action_address().clone()));
+    }
+    
+    let me = agent_info()?.agent_initial_pubkey;
+    let proof = match get_anchor_record_hash(&me)? {
+        Some(anchor) => MembershipProof {
+            anchor: Some(anchor),
+            vouches: Vec::new(),
+            depth: 0,
+        },
+        None => match build_membership_proof(&me)? {
+            Some(proof) => proof,
+            None => return Ok(None),
+        },
+    };
+    
+    let action_hash = create_entry(EntryTypes::MembershipProof(proof))?;
+    create_link(me, action_hash.clone(), LinkTypes::AgentToMembershipProofs, ())?;
+    record_onboarding_step()?;
+    Ok(Some(action_hash))
+}
+
+/// Pick vouches that meet the trust policy the way vouch_integrity checks it
+///
+/// Neighbor vouches only count if the voucher has a proof of their own, so
+/// this can come up short for an agent `get_membership_status` calls
+/// verified, until their vouchers have published proofs.
+fn build_membership_proof(agent: &AgentPubKey) -> ExternResult<Option<MembershipProof>> {
+    let policy = trust_policy()?;
+    let anchor_keys = get_anchor_keys()?;
+    let vouches = get_vouches_received(agent.clone(), &anchor_keys)?;
+    
+    let anchor_evidence: Vec<VouchEvidence> = vouches
+        .iter()
+        .filter(|info| info.vouch.vouch_type == VouchType::Anchor)
+        .map(|info| VouchEvidence {
+            vouch: info.action_hash.clone(),
+            voucher_proof: None,
+            rotations: info.via_rotations.clone(),
+        })
+        .take(policy.anchor_vouches_required)
+        .collect();
+    if anchor_evidence.len() == policy.anchor_vouches_required {
+        return Ok(Some(MembershipProof {
+            anchor: None,
+            vouches: anchor_evidence,
+            depth: 1,
+        }));
+    }
+    
+    let mut candidates = Vec::new();
+    for info in vouches {
+        if info.vouch.vouch_type == VouchType::Anchor {
+            candidates.push((
+                0,
+                VouchEvidence { vouch: info.action_hash, voucher_proof: None, rotations: info.via_rotations },
+            ));
+        } else if let Some((proof_hash, proof)) = membership_proof_of(info.voucher)? {
+            candidates.push((
+                proof.depth,
+                VouchEvidence {
+                    vouch: info.action_hash,
+                    voucher_proof: Some(proof_hash),
+                    rotations: info.via_rotations,
+                },
+            ));
+        }
+    }
+    candidates.sort_by_key(|(depth, _)| *depth);
+    candidates.truncate(policy.vouches_required);
+    
+    // Only as close to an anchor as the furthest voucher needed to meet the threshold
+    let Some((furthest, _)) = candidates.last() else {
+        return Ok(None);
+    };
+    let depth = furthest + 1;
+    if candidates.len() < policy.vouches_required || depth as usize > policy.max_vouch_depth {
+        return Ok(None);
+    }
+    
+    Ok(Some(MembershipProof {
+        anchor: None,
+        vouches: candidates.into_iter().map(|(_, evidence)| evidence).collect(),
+        depth,
+    }))
+}
+
+/// An agent's published proof closest to an anchor
+fn membership_proof_of(agent: AgentPubKey) -> ExternResult<Option<(ActionHash, MembershipProof)>> {
+    let mut best: Option<(ActionHash, MembershipProof)> = None;
+    for link in get_list_links(agent, LinkTypes::AgentToMembershipProofs, false)? {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        let Some(proof) = record.entry().to_app_option::<MembershipProof>().ok().flatten() else {
+            continue;
+        };
+        if best.as_ref().map_or(true, |(_, current)| proof.depth < current.depth) {
+            best = Some((action_hash, proof));
+        }
+    }
+    Ok(best)
+}
+
+// ============================================================================
+// MEMBERSHIP HISTORY
+// ============================================================================
+
+/// Reconstruct when an agent received and lost vouches, and when they
+/// crossed the verification threshold
+///
+/// Vouches are replayed in order against the current trust policy. An Anchor
+/// vouch counts toward the anchor threshold from the time its author's
+/// TrustedAnchor entry was created, and any other vouch counts only if its
+/// voucher is verified today.
+#[hdk_extern]
+pub fn get_membership_history(agent: AgentPubKey) -> ExternResult<MembershipHistory> {
+    let mut resolver = MembershipResolver::new()?;
+    let policy = resolver.policy.clone();
+    let anchors = get_all_anchors(())?;
+    let anchor_since = |key: &AgentPubKey| anchors.iter().find(|a| &a.agent == key).map(|a| a.created_at);
+
+    let mut events = get_vouch_events(agent.clone())?;
+
+    resolver.visiting.insert(agent.clone());
+    let mut counted = HashSet::new();
+    for event in &events {
+        if let MembershipEventKind::VouchReceived { voucher, .. } = &event.event {
+            if resolver.counts_as_voucher(voucher)? {
+                counted.insert(voucher.clone());
+            }
+        }
+    }
+
+    if let Some(since) = anchor_since(&agent) {
+        events.push(MembershipEvent {
+            timestamp: since,
+            event: MembershipEventKind::BecameAnchor,
+        });
+    }
+    events.sort_by_key(|e| e.timestamp);
+
+    // Replay, recording threshold crossings after the event that caused them
+    let mut timeline = Vec::with_capacity(events.len());
+    let mut active: Vec<(ActionHash, AgentPubKey, bool)> = Vec::new();
+    let mut verified_since: Option<Timestamp> = None;
+
+    for event in events {
+        let at = event.timestamp;
+        match &event.event {
+            MembershipEventKind::VouchReceived { voucher, action_hash, from_anchor } => {
+                active.push((action_hash.clone(), voucher.clone(), *from_anchor));
+            }
+            MembershipEventKind::VouchRevoked { action_hash, .. } => {
+                active.retain(|(hash, _, _)| hash != action_hash);
+            }
+            _ => {}
+        }
+        timeline.push(event);
+
+        let is_anchor = anchor_since(&agent).is_some_and(|since| since <= at);
+        let vouches_from_anchors = active
+            .iter()
+            .filter(|(_, voucher, from_anchor)| *from_anchor && anchor_since(voucher).is_some_and(|since| since <= at))
+            .count();
+        let vouches_from_members = active.iter().filter(|(_, voucher, _)| counted.contains(voucher)).count();
+        let verified = is_anchor || vouch_threshold_met(&policy, vouches_from_anchors, vouches_from_members);
+
+        match (verified, verified_since) {
+            (true, None) => {
+                verified_since = Some(at);
+                timeline.push(MembershipEvent {
+                    timestamp: at,
+                    event: MembershipEventKind::Verified,
+                });
+            }
+            (false, Some(_)) => {
+                verified_since = None;
+                timeline.push(MembershipEvent {
+                    timestamp: at,
+                    event: MembershipEventKind::VerificationLost,
+                });
+            }
+            _ => {}
+        }
+    }
+
+    for info in get_membership_attestations(agent.clone())? {
+        timeline.push(MembershipEvent {
+            timestamp: info.attestation.attested_at,
+            event: MembershipEventKind::Attested {
+                anchor: info.anchor,
+                action_hash: info.action_hash,
+                status: info.attestation.status,
+            },
+        });
+    }
+    // Stable sort keeps each crossing right after the event that caused it
+    timeline.sort_by_key(|e| e.timestamp);
+
+    let status = if anchor_since(&agent).is_some() {
+        MembershipStatus::Anchor
+    } else if verified_since.is_some() {
+        MembershipStatus::Verified
+    } else {
+        MembershipStatus::Pending
+    };
+
+    Ok(MembershipHistory {
+        agent,
+        status,
+        verified_since,
+        events: timeline,
+    })
+}
+
+/// Issue a signed attestation of an agent's current membership (anchors only)
+#[hdk_extern]
+pub fn attest_membership(agent: AgentPubKey) -> ExternResult<AttestationInfo> {
+    let me = agent_info()?.agent_initial_pubkey;
+
+    let Some(anchor) = get_anchor_record_hash(&me)? else {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only trusted anchors can attest membership".to_string()
+        )));
+    };
+
+    let history = get_membership_history(agent.clone())?;
+    let attested_at = sys_time()?;
+    let signature = sign(
+        me.clone(),
+        attestation_payload(&agent, &history.status, history.verified_since, attested_at),
+    )?;
+
+    let attestation = MembershipAttestation {
+        agent: agent.clone(),
+        status: history.status,
+        verified_since: history.verified_since,
+        anchor,
+        attested_at,
+        signature,
+    };
+
+    let action_hash = create_entry(EntryTypes::MembershipAttestation(attestation.clone()))?;
+    create_link(agent.clone(), action_hash.clone(), LinkTypes::AgentToAttestations, ())?;
+
+    emit_zome_signal(Signal::MembershipAttested {
+        attestation_hash: action_hash.clone(),
+        agent,
+    })?;
+
+    Ok(AttestationInfo {
+        attestation,
+        action_hash,
+        anchor: me,
+    })
+}
+
+/// Get all attestations issued about an agent
+#[hdk_extern]
+pub fn get_membership_attestations(agent: AgentPubKey) -> ExternResult<Vec<AttestationInfo>> {
+    let links = get_links(
+        LinkQuery::try_new(agent, LinkTypes::AgentToAttestations)?,
+        GetStrategy::Local,
+    )?;
+
+    let mut attestations = Vec::new();
+
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        if let Some(attestation) = record
+            .entry()
+            .to_app_option::<MembershipAttestation>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        {
+            attestations.push(AttestationInfo {
+                attestation,
+                action_hash,
+                anchor: record.action().author().clone(),
+            });
+        }
+    }
+
+    Ok(attestations)
+}
+
+/// Vouch creations and revocations for an agent, unsorted
+fn get_vouch_events(agent: AgentPubKey) -> ExternResult<Vec<MembershipEvent>> {
+    let anchor_keys = get_anchor_keys()?;
+    let links = get_links(
+        LinkQuery::try_new(agent, LinkTypes::AgentToVouchesReceived)?,
+        GetStrategy::Local,
+    )?;
+
+    let mut events = Vec::new();
+
+    for link in links {
+        let entry_hash = EntryHash::try_from(link.target).map_err(|_| {
+            wasm_error!(WasmErrorInner::Guest("Invalid entry hash in link".to_string()))
+        })?;
+
+        let Some(Details::Entry(details)) = get_details(entry_hash, GetOptions::default())? else {
+            continue;
+        };
+        let is_anchor_vouch =
+            Vouch::try_from(&details.entry).is_ok_and(|vouch| vouch.vouch_type == VouchType::Anchor);
+
+        for action in &details.actions {
+            let voucher = action.action().author().clone();
+            events.push(MembershipEvent {
+                timestamp: action.action().timestamp(),
+                event: MembershipEventKind::VouchReceived {
+                    from_anchor: is_anchor_vouch && anchor_keys.contains(&voucher),
+                    voucher,
+                    action_hash: action.as_hash().clone(),
+                },
+            });
+        }
+
+        for delete in &details.deletes {
+            if let Action::Delete(revocation) = delete.action() {
+                events.push(MembershipEvent {
+                    timestamp: revocation.timestamp,
+                    event: MembershipEventKind::VouchRevoked {
+                        voucher: revocation.author.clone(),
+                        action_hash: revocation.deletes_address.clone(),
+                    },
+                });
+            }
+        }
+    }
+
+    Ok(events)
+}
+
+// ============================================================================
+// TRUSTED ANCHOR FUNCTIONS
+// ============================================================================
+
+/// Initialize the first trusted anchor (founding ceremony)
+///
+/// This should only be called once when bootstrapping the neighborhood,
+/// by the agent named in the DNA's `founding_anchor` property.
+#[hdk_extern]
+pub fn initialize_as_anchor(_: ()) -> ExternResult<TrustedAnchor> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    
+    if founding_anchor()? != Some(agent.clone()) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the founding anchor named in the DNA can found this neighborhood.".to_string()
+        )));
+    }
+    
+    // Check if any anchors exist
+    let existing_anchors = get_all_anchors(())?;
+    if !existing_anchors.is_empty() {
+        // If anchors exist, check if this is being called by an existing anchor
+        let is_existing_anchor = existing_anchors.iter().any(|a| a.agent == agent);
+        if !is_existing_anchor {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Anchors already exist. Ask an existing anchor to nominate you.".to_string()
+            )));
+        }
+        // Already an anchor
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "You are already a trusted anchor.".to_string()
+        )));
+    }
+    
+    commit_anchor(agent, None)
+}
+
+/// Nominate another agent as a trusted anchor (only existing anchors can do this)
+///
+/// Anchors must commit their own TrustedAnchor entry, so this only creates a
+/// signed nomination. The nominee becomes an anchor once they call
+/// `accept_anchor_nomination`.
+#[hdk_extern]
+pub fn nominate_anchor(nominee: AgentPubKey) -> ExternResult<NominationInfo> {
+    let nominator = agent_info()?.agent_initial_pubkey;
+    
+    let Some(nominator_anchor) = get_anchor_record_hash(&nominator)? else {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only trusted anchors can nominate new anchors.".to_string()
+        )));
+    };
+    
+    // Check if target is already an anchor
+    if get_anchor_record_hash(&nominee)?.is_some() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "This agent is already a trusted anchor.".to_string()
+        )));
+    }
+    
+    let nominated_at = sys_time()?;
+    let expires_at = Timestamp::from_micros(
+        nominated_at.as_micros() + DEFAULT_NOMINATION_VALIDITY_SECS * 1_000_000,
+    );
+    let signature = sign(
+        nominator.clone(),
+        nomination_payload(&nominee, nominated_at, expires_at),
+    )?;
+    
+    let nomination = AnchorNomination {
+        nominee: nominee.clone(),
+        nominator_anchor,
+        nominated_at,
+        expires_at,
+        signature,
+    };
+    
+    let action_hash = create_entry(EntryTypes::AnchorNomination(nomination.clone()))?;
+    create_link(nominee.clone(), action_hash.clone(), LinkTypes::AgentToNominations, ())?;
+    
+    emit_zome_signal(Signal::AnchorNominated {
+        nomination_hash: action_hash.clone(),
+        nominee,
+    })?;
+    
+    Ok(NominationInfo {
+        nomination,
+        action_hash,
+        nominator,
+    })
+}
+
+/// Get the unexpired anchor nominations naming the calling agent
+#[hdk_extern]
+pub fn get_my_anchor_nominations(_: ()) -> ExternResult<Vec<NominationInfo>> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+    let links = get_links(
+        LinkQuery::try_new(agent, LinkTypes::AgentToNominations)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut nominations = Vec::new();
+    
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let info = get_nomination(action_hash)?;
+        if info.nomination.expires_at >= now {
+            nominations.push(info);
+        }
+    }
+    
+    Ok(nominations)
+}
+
+/// Accept a nomination by committing our own TrustedAnchor entry
+#[hdk_extern]
+pub fn accept_anchor_nomination(nomination_hash: ActionHash) -> ExternResult<TrustedAnchor> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    let info = get_nomination(nomination_hash.clone())?;
+    
+    if info.nomination.nominee != agent {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "This nomination is for another agent.".to_string()
+        )));
+    }
+    if sys_time()? > info.nomination.expires_at {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "This nomination has expired.".to_string()
+        )));
+    }
+    if get_anchor_record_hash(&agent)?.is_some() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "You are already a trusted anchor.".to_string()
+        )));
+    }
+    
+    commit_anchor(agent, Some(nomination_hash))
+}
+
+/// Create our TrustedAnchor entry and list it under the all-anchors path
+fn commit_anchor(agent: AgentPubKey, nomination: Option<ActionHash>) -> ExternResult<TrustedAnchor> {
+    let anchor = TrustedAnchor {
+        agent,
+        created_at: sys_time()?,
+        nomination,
+    };
+    
+    let action_hash = create_entry(EntryTypes::TrustedAnchor(anchor.clone()))?;
+    
+    // Link to all anchors path; validation checks the target is our own record
+    let anchor_path = anchor_path_hash()?;
+    create_link(anchor_path, action_hash, LinkTypes::AllAnchors, ())?;
+    
+    emit_zome_signal(Signal::NewAnchor {
+        agent: anchor.agent.clone(),
+    })?;
+    
+    Ok(anchor)
+}
+
+fn get_nomination(action_hash: ActionHash) -> ExternResult<NominationInfo> {
+    let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Nomination not found".to_string())));
+    };
+    let nomination = record
+        .entry()
+        .to_app_option::<AnchorNomination>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Not an anchor nomination".to_string())))?;
+    Ok(NominationInfo {
+        nomination,
+        action_hash,
+        nominator: record.action().author().clone(),
+    })
+}
+
+/// Get all trusted anchors
+#[hdk_extern]
+pub fn get_all_anchors(_: ()) -> ExternResult<Vec<TrustedAnchor>> {
+    let anchor_path = anchor_path_hash()?;
+    let links = get_links(
+        LinkQuery::try_new(anchor_path, LinkTypes::AllAnchors)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut anchors = Vec::new();
+    
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        
+        if let Some(record) = get(action_hash, GetOptions::default())? {
+            if let Ok(Some(anchor)) = record.entry().to_app_option::<TrustedAnchor>() {
+                anchors.push(anchor);
+            }
+        }
+    }
+    
+    Ok(anchors)
+}
+
+/// Get the calling agent's TrustedAnchor record, if they are an anchor
+///
+/// Other zomes reference this record so their integrity checks can confirm
+/// an action was taken by an anchor.
+#[hdk_extern]
+pub fn get_my_anchor_record(_: ()) -> ExternResult<Option<ActionHash>> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    get_anchor_record_hash(&agent)
 }
 
 /// Check if the calling agent is a trusted anchor
@@ -430,26 +1919,242 @@ pub fn am_i_anchor(_: ()) -> ExternResult<bool> {
     Ok(anchors.iter().any(|a| a.agent == agent))
 }
 
+// ============================================================================
+// KEY ROTATION
+// ============================================================================
+
+/// Input for approving a move from one agent key to another
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SignKeyRotationInput {
+    pub old_key: AgentPubKey,
+    pub new_key: AgentPubKey,
+}
+
+/// Approve a neighbor's move to a new agent key
+///
+/// Called by the old key when the old device is still around, or otherwise
+/// by a trusted anchor after confirming the handover in person. Either way
+/// the caller commits a KeyRetirement naming the new key, which they can do
+/// only once per old key. The returned rotation is handed to the new device,
+/// which commits it with `record_key_rotation`.
+#[hdk_extern]
+pub fn sign_key_rotation(input: SignKeyRotationInput) -> ExternResult<KeyRotation> {
+    let me = agent_info()?.agent_initial_pubkey;
+    if input.old_key == input.new_key {
+        return Err(wasm_error!(WasmErrorInner::Guest("A key cannot rotate to itself".to_string())));
+    }
+    if i_retired(&input.old_key)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "You have already handed this key over to a new key".to_string()
+        )));
+    }
+    
+    let rotated_at = sys_time()?;
+    let payload = key_rotation_payload(&input.old_key, &input.new_key, rotated_at);
+    let (attestation, anchor) = if me == input.old_key {
+        (RotationAttestation::OldKey(sign(me, payload)?), None)
+    } else if let Some(anchor) = get_anchor_record_hash(&me)? {
+        let attestation = RotationAttestation::Anchor {
+            anchor: anchor.clone(),
+            signature: sign(me, payload)?,
+        };
+        (attestation, Some(anchor))
+    } else {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the old key or a trusted anchor can approve a key rotation".to_string()
+        )));
+    };
+    
+    let retirement = KeyRetirement {
+        old_key: input.old_key.clone(),
+        successor: input.new_key.clone(),
+        retired_at: rotated_at,
+        anchor,
+    };
+    let retirement_hash = create_entry(EntryTypes::KeyRetirement(retirement))?;
+    create_link(input.old_key.clone(), retirement_hash.clone(), LinkTypes::AgentToKeyRetirements, ())?;
+    
+    Ok(KeyRotation {
+        old_key: input.old_key,
+        new_key: input.new_key,
+        rotated_at,
+        attestation,
+        retirement: Some(retirement_hash),
+    })
+}
+
+/// Whether the calling agent has already retired `old_key`
+fn i_retired(old_key: &AgentPubKey) -> ExternResult<bool> {
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::KeyRetirement.try_into()?)
+            .include_entries(true),
+    )?;
+    Ok(records.iter().any(|record| {
+        matches!(
+            record.entry().to_app_option::<KeyRetirement>(),
+            Ok(Some(retirement)) if &retirement.old_key == old_key
+        )
+    }))
+}
+
+/// When a key was first handed over to a successor, if it has been
+fn retired_since(key: &AgentPubKey) -> ExternResult<Option<Timestamp>> {
+    let links = get_links(
+        LinkQuery::try_new(key.clone(), LinkTypes::AgentToKeyRetirements)?,
+        GetStrategy::Local,
+    )?;
+    
+    let mut since: Option<Timestamp> = None;
+    for link in links {
+        let Some(retirement_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(retirement_hash, GetOptions::default())? else {
+            continue;
+        };
+        if !matches!(
+            record.entry().to_app_option::<KeyRetirement>(),
+            Ok(Some(retirement)) if &retirement.old_key == key
+        ) {
+            continue;
+        }
+        let timestamp = record.action().timestamp();
+        if since.map_or(true, |since| timestamp < since) {
+            since = Some(timestamp);
+        }
+    }
+    Ok(since)
+}
+
+/// Commit an approved key rotation from the new key
+///
+/// Vouches received by the old key count toward this key's membership from
+/// then on, and a membership proof is committed if they are enough.
+#[hdk_extern]
+pub fn record_key_rotation(rotation: KeyRotation) -> ExternResult<ActionHash> {
+    let me = agent_info()?.agent_initial_pubkey;
+    if rotation.new_key != me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Key rotations must be recorded by the new key".to_string()
+        )));
+    }
+    
+    let action_hash = create_entry(EntryTypes::KeyRotation(rotation))?;
+    create_link(me, action_hash.clone(), LinkTypes::AgentToKeyRotations, ())?;
+    ensure_membership_proof(())?;
+    
+    Ok(action_hash)
+}
+
+/// An agent's earlier keys, most recent first
+#[hdk_extern]
+pub fn get_key_predecessors(agent: AgentPubKey) -> ExternResult<Vec<AgentPubKey>> {
+    Ok(key_predecessors(&agent)?.into_iter().map(|(key, _)| key).collect())
+}
+
+/// Earlier keys of an agent, each with the KeyRotations leading from it to
+/// the agent, oldest first; at most `MAX_KEY_ROTATIONS` hops back
+fn key_predecessors(agent: &AgentPubKey) -> ExternResult<Vec<(AgentPubKey, Vec<ActionHash>)>> {
+    let mut predecessors = Vec::new();
+    let mut seen = HashSet::from([agent.clone()]);
+    let mut frontier = vec![(agent.clone(), Vec::new())];
+    
+    for _ in 0..MAX_KEY_ROTATIONS {
+        let mut next = Vec::new();
+        for (key, later_rotations) in frontier {
+            let links = get_links(
+                LinkQuery::try_new(key.clone(), LinkTypes::AgentToKeyRotations)?,
+                GetStrategy::Local,
+            )?;
+            for link in links {
+                let Some(rotation_hash) = link.target.into_action_hash() else {
+                    continue;
+                };
+                let Some(record) = get(rotation_hash.clone(), GetOptions::default())? else {
+                    continue;
+                };
+                let Some(rotation) = record.entry().to_app_option::<KeyRotation>().ok().flatten() else {
+                    continue;
+                };
+                if rotation.new_key != key || !seen.insert(rotation.old_key.clone()) {
+                    continue;
+                }
+                
+                let mut rotations = vec![rotation_hash];
+                rotations.extend(later_rotations.iter().cloned());
+                predecessors.push((rotation.old_key.clone(), rotations.clone()));
+                next.push((rotation.old_key, rotations));
+            }
+        }
+        frontier = next;
+    }
+    
+    Ok(predecessors)
+}
+
 // ============================================================================
 // HELPER FUNCTIONS
 // ============================================================================
 
+/// Get the public keys of all trusted anchors
+fn get_anchor_keys() -> ExternResult<Vec<AgentPubKey>> {
+    let anchors = get_all_anchors(())?;
+    Ok(anchors.into_iter().map(|a| a.agent).collect())
+}
+
+/// Get the action hash of an agent's TrustedAnchor record, if they are an anchor
+fn get_anchor_record_hash(agent: &AgentPubKey) -> ExternResult<Option<ActionHash>> {
+    let links = get_links(
+        LinkQuery::try_new(anchor_path_hash()?, LinkTypes::AllAnchors)?,
+        GetStrategy::Local,
+    )?;
+
+    for link in links {
+        let Some(action_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(action_hash, GetOptions::default())? else {
+            continue;
+        };
+        if let Ok(Some(anchor)) = record.entry().to_app_option::<TrustedAnchor>() {
+            if &anchor.agent == agent {
+                return Ok(Some(record.action_address().clone()));
+            }
+        }
+    }
+
+    Ok(None)
+}
+
 /// Get the path hash for the all-anchors anchor
 fn anchor_path_hash() -> ExternResult<EntryHash> {
     let path = Path::from(ALL_ANCHORS_PATH);
     path.path_entry_hash()
 }
 
+/// Get the path hash used to look up an invite by its secret hash
+fn secret_path_hash(secret_hash: &[u8]) -> ExternResult<EntryHash> {
+    let path = Path::from(format!("{}.{}", PRE_VOUCH_PATH_PREFIX, hex_encode(secret_hash)));
+    path.path_entry_hash()
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
 /// Generate QR code data for vouch scanning
 /// Returns a signed payload that another agent can use to vouch
 #[hdk_extern]
 pub fn generate_vouch_request(_: ()) -> ExternResult<VouchRequest> {
     let agent = agent_info()?.agent_initial_pubkey;
     let timestamp = sys_time()?;
+    let signature = sign(agent.clone(), vouch_request_payload(&agent, timestamp))?;
     
     Ok(VouchRequest {
         agent,
         timestamp,
+        signature,
     })
 }
 
@@ -458,4 +2163,107 @@ pub fn generate_vouch_request(_: ()) -> ExternResult<VouchRequest> {
 pub struct VouchRequest {
     pub agent: AgentPubKey,
     pub timestamp: Timestamp,
+    /// By `agent`, so a QR can't name someone else's key
+    pub signature: Signature,
+}
+
+/// Bytes an agent signs in their vouch request QR
+fn vouch_request_payload(agent: &AgentPubKey, timestamp: Timestamp) -> Vec<u8> {
+    let mut payload = b"OURBLOCK_VOUCH_REQUEST".to_vec();
+    payload.extend_from_slice(agent.get_raw_39());
+    payload.extend_from_slice(&timestamp.as_micros().to_le_bytes());
+    payload
+}
+
+/// Mirror of the profile zome's GrantBadgeInput
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct GrantBadgeInput {
+    agent: AgentPubKey,
+    kind: BadgeKind,
+    evidence: Vec<ActionHash>,
+}
+
+/// The profile zome's BadgeKind variant this zome grants
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum BadgeKind {
+    Verified,
+}
+
+/// Offer the profile zome the vouches an agent received as badge evidence;
+/// it grants the badge once there are enough and ignores the call otherwise
+fn grant_badge(agent: AgentPubKey, evidence: Vec<ActionHash>) -> ExternResult<()> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("profile"),
+        FunctionName::from("grant_badge"),
+        None,
+        GrantBadgeInput {
+            agent,
+            kind: BadgeKind::Verified,
+            evidence,
+        },
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to grant badge: {:?}",
+            other
+        )))),
+    }
+}
+
+/// The profile zome's OnboardingStep variant this zome completes
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum OnboardingStep {
+    Verified,
+}
+
+/// Tick off an onboarding step in the profile zome; it ignores steps already done
+fn record_onboarding_step() -> ExternResult<()> {
+    let response = call(
+        CallTargetCell::Local,
+        ZomeName::from("profile"),
+        FunctionName::from("record_onboarding_step"),
+        None,
+        OnboardingStep::Verified,
+    )?;
+
+    match response {
+        ZomeCallResponse::Ok(_) => Ok(()),
+        other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Failed to record onboarding step: {:?}",
+            other
+        )))),
+    }
+}
+
+// ============================================================================
+// DATA EXPORT
+// ============================================================================
+
+/// The calling agent's standing and every vouch they've given or received
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VouchExport {
+    pub membership: MembershipInfo,
+    pub received: Vec<VouchInfo>,
+    pub given: Vec<VouchOutput>,
+    pub pre_vouches: Vec<PreVouchInfo>,
+    pub attestations: Vec<AttestationInfo>,
+    pub anchor_nominations: Vec<NominationInfo>,
+}
+
+/// Export the caller's vouching footprint
+#[hdk_extern]
+pub fn export_my_data(_: ()) -> ExternResult<VouchExport> {
+    let agent = agent_info()?.agent_initial_pubkey;
+
+    Ok(VouchExport {
+        membership: get_my_membership_status(())?,
+        received: get_my_vouches(())?,
+        given: get_my_given_vouches(())?,
+        pre_vouches: get_my_pre_vouches(())?,
+        attestations: get_membership_attestations(agent)?,
+        anchor_nominations: get_my_anchor_nominations(())?,
+    })
 }
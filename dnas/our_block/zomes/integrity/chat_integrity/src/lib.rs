@@ -6,23 +6,161 @@ pub struct ChatMessage {
     pub content: String,
     pub timestamp: i64,
     pub message_id: String,
+    /// What the conversation is about, when it was started from a request,
+    /// item, or event
+    #[serde(default)]
+    pub context: Option<ChatContext>,
+    /// Who the message is for; a device drops messages addressed to anyone
+    /// but itself or the agent that registered it
+    #[serde(default)]
+    pub recipient: Option<AgentPubKey>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize)]
+/// The entry a conversation was started about
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type")]
+pub enum ChatSubject {
+    Request { request_hash: ActionHash },
+    Item { item_hash: ActionHash },
+    Event { event_hash: ActionHash },
+}
+
+impl ChatSubject {
+    pub fn target(&self) -> &ActionHash {
+        match self {
+            ChatSubject::Request { request_hash } => request_hash,
+            ChatSubject::Item { item_hash } => item_hash,
+            ChatSubject::Event { event_hash } => event_hash,
+        }
+    }
+}
+
+/// Reference carried with each message about a subject, so the thread keeps
+/// its paper trail attached to the thing being discussed
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChatContext {
+    pub subject: ChatSubject,
+    /// The subject's title when the conversation started
+    pub title: String,
+}
+
+/// Reference to a file shared in a DM; the bytes live in file_storage
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChatAttachment {
+    pub sender: AgentPubKey,
+    pub metadata_hash: ActionHash,
+    pub file_name: String,
+    pub file_type: String,
+    pub size: u32,
+    pub timestamp: i64,
+    pub message_id: String,
+}
+
+/// A recorded voice message; the audio lives in file_storage
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ChatVoiceMemo {
+    pub sender: AgentPubKey,
+    pub metadata_hash: ActionHash,
+    pub file_type: String,
+    pub size: u32,
+    pub duration_ms: u32,
+    pub timestamp: i64,
+    pub message_id: String,
+}
+
+/// The sender's profile status, carried with presence so peers can show it
+/// without a profile lookup
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PresenceStatus {
+    pub emoji: Option<String>,
+    pub text: String,
+    pub expires_at: Option<Timestamp>,
+}
+
+/// Sent peer-to-peer as-is, and wrapped in a SignalEnvelope when emitted to the UI
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(tag = "event", content = "payload")]
 pub enum ChatSignal {
     Message(ChatMessage),
-    Typing { sender: AgentPubKey },
+    /// Sender is typing; UIs should hide the indicator once `expires_at`
+    /// (ms since epoch) passes, even if no `TypingStopped` ever arrives
+    TypingStarted { sender: AgentPubKey, timestamp: i64, expires_at: i64 },
+    TypingStopped { sender: AgentPubKey, timestamp: i64 },
     Read { sender: AgentPubKey, message_id: String },
-    Online { agent: AgentPubKey },
+    Online {
+        agent: AgentPubKey,
+        #[serde(default)]
+        status: Option<PresenceStatus>,
+    },
     Offline { agent: AgentPubKey },
+    Reaction { message_id: String, emoji: String, sender: AgentPubKey },
+    Attachment(ChatAttachment),
+    VoiceMemo(ChatVoiceMemo),
+    /// The sender changed a message's text; `timestamp` is still the time it
+    /// was first sent so UIs keep it in place
+    Edited { sender: AgentPubKey, message_id: String, content: String, timestamp: i64, edited_at: i64 },
+    Deleted { sender: AgentPubKey, message_id: String },
+    /// Emitted locally when a sender goes over the receive rate limit and
+    /// their messages start being dropped; ignored if it arrives from a peer
+    Flooding { sender: AgentPubKey, window_ms: i64 },
 }
 
 pub const MAX_MESSAGE_LENGTH: usize = 5000;
+pub const MAX_CONTEXT_TITLE_LENGTH: usize = 200;
+pub const MAX_DEVICES: usize = 10;
+pub const MAX_DEVICE_LABEL_LENGTH: usize = 50;
+/// Length of the ed25519 signature at the start of a device link tag
+pub const DEVICE_SIGNATURE_BYTES: usize = 64;
+/// Max bytes for a reaction emoji (room for ZWJ sequences and skin tones)
+pub const MAX_REACTION_EMOJI_LENGTH: usize = 32;
+/// How long a typing indicator stays visible unless renewed
+pub const TYPING_INDICATOR_TTL_MS: i64 = 6_000;
+/// Minimum gap between TypingStarted signals while the user keeps typing
+pub const TYPING_DEBOUNCE_MS: i64 = 2_000;
+/// Messages, edits, and attachments one agent may send one recipient per window
+pub const MAX_MESSAGES_PER_WINDOW: usize = 20;
+pub const RATE_LIMIT_WINDOW_MS: i64 = 60_000;
+/// Receivers allow twice the sending limit, since delivery delays can bunch
+/// up messages that were sent within it
+pub const MAX_RECEIVED_PER_WINDOW: usize = MAX_MESSAGES_PER_WINDOW * 2;
+pub const MAX_ATTACHMENT_SIZE: usize = 10_000_000; // ~10MB
+pub const MIN_VOICE_MEMO_DURATION_MS: u32 = 500;
+pub const MAX_VOICE_MEMO_DURATION_MS: u32 = 5 * 60 * 1000;
+pub const MAX_VOICE_MEMO_SIZE: usize = 5_000_000; // ~5MB
+/// What browsers' MediaRecorder produces: Chrome webm, Firefox ogg, plus mp3
+pub const VOICE_MEMO_TYPES: &[&str] = &["audio/webm", "audio/ogg", "audio/mpeg"];
+pub const ALLOWED_ATTACHMENT_TYPES: &[&str] = &[
+    "image/jpeg",
+    "image/png",
+    "image/gif",
+    "image/webp",
+    "application/pdf",
+];
 
 #[hdk_link_types]
 pub enum LinkTypes {
     OnlineAgents,
+    /// From an agent to the keys of their other devices; the tag is the
+    /// device's signature over `device_registration_payload`, then a label
+    AgentToDevices,
+}
+
+/// What a device signs to agree to receive an owner's messages, so nobody can
+/// register someone else's key as their device
+pub fn device_registration_payload(owner: &AgentPubKey) -> Vec<u8> {
+    let mut payload = b"OURBLOCK_CHAT_DEVICE".to_vec();
+    payload.extend_from_slice(owner.get_raw_39());
+    payload
+}
+
+/// Split a device link tag into the device's signature and the label
+pub fn device_tag_parts(tag: &LinkTag) -> Option<(Signature, String)> {
+    if tag.0.len() < DEVICE_SIGNATURE_BYTES {
+        return None;
+    }
+    let (signature, label) = tag.0.split_at(DEVICE_SIGNATURE_BYTES);
+    let signature: [u8; DEVICE_SIGNATURE_BYTES] = signature.try_into().ok()?;
+    Some((Signature(signature), String::from_utf8(label.to_vec()).ok()?))
 }
 
 #[hdk_entry_helper]
@@ -32,14 +170,123 @@ pub struct ChatPresence {
     pub online: bool,
 }
 
+/// A DM kept on the sender's and recipient's own chains so the
+/// conversation can be exported later; private, so it never reaches the DHT.
+/// Edits and deletes append a new revision with the same `message_id`.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct StoredMessage {
+    /// The other side of the conversation
+    pub peer: AgentPubKey,
+    pub sender: AgentPubKey,
+    pub content: String,
+    pub timestamp: i64,
+    pub message_id: String,
+    /// Set on the revision written when the sender edits the message
+    #[serde(default)]
+    pub edited_at: Option<i64>,
+    /// Set on the revision written when the sender deletes the message,
+    /// which carries no content or context; earlier revisions stay on the
+    /// private chain, which can't be rewritten, but are never read again
+    #[serde(default)]
+    pub deleted: bool,
+    #[serde(default)]
+    pub context: Option<ChatContext>,
+}
+
+/// How far one side of a conversation has read, kept privately on our own
+/// chain so unread badges survive restarts. Written for our own reads and for
+/// the read receipts the peer sends us; the newest marker per reader wins.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct ReadMarker {
+    pub peer: AgentPubKey,
+    /// Us, or the peer when recording their read receipt
+    pub reader: AgentPubKey,
+    /// The last message read
+    pub message_id: String,
+    /// When that message was sent (ms), for ordering markers
+    pub message_timestamp: i64,
+}
+
+/// Written when we start dropping a sender's messages for going over the
+/// receive rate limit, so the UI is flagged once per window rather than per drop
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct FloodNotice {
+    pub sender: AgentPubKey,
+}
+
+/// Attachments and voice memos travel as signals and aren't kept as
+/// `StoredMessage`s, so one of these is written for each one sent or received
+/// to count it toward the chat rate limits
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct AttachmentLog {
+    /// The other side of the conversation
+    pub peer: AgentPubKey,
+    pub sender: AgentPubKey,
+}
+
 #[hdk_entry_types]
 #[unit_enum(UnitEntryTypes)]
 pub enum EntryTypes {
     #[entry_type(name = "chat_presence", visibility = "public")]
     ChatPresence(ChatPresence),
+    #[entry_type(name = "stored_message", visibility = "private")]
+    StoredMessage(StoredMessage),
+    #[entry_type(name = "read_marker", visibility = "private")]
+    ReadMarker(ReadMarker),
+    #[entry_type(name = "flood_notice", visibility = "private")]
+    FloodNotice(FloodNotice),
+    #[entry_type(name = "attachment_log", visibility = "private")]
+    AttachmentLog(AttachmentLog),
 }
 
 #[hdk_extern]
-pub fn validate(_op: Op) -> ExternResult<ValidateCallbackResult> {
-    Ok(ValidateCallbackResult::Valid)
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    match op.flattened::<EntryTypes, LinkTypes>()? {
+        FlatOp::RegisterCreateLink {
+            link_type: LinkTypes::AgentToDevices,
+            base_address,
+            target_address,
+            tag,
+            action,
+            ..
+        } => {
+            if base_address.into_agent_pub_key().as_ref() != Some(&action.author) {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Agents can only register their own devices".to_string()
+                ));
+            }
+            let Some(device) = target_address.into_agent_pub_key() else {
+                return Ok(ValidateCallbackResult::Invalid("Devices must be agent keys".to_string()));
+            };
+            let Some((signature, label)) = device_tag_parts(&tag) else {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Device links must carry the device's signature and a label".to_string()
+                ));
+            };
+            if label.len() > MAX_DEVICE_LABEL_LENGTH {
+                return Ok(ValidateCallbackResult::Invalid(format!(
+                    "Device label cannot exceed {} bytes", MAX_DEVICE_LABEL_LENGTH
+                )));
+            }
+            if !verify_signature(device, signature, device_registration_payload(&action.author))? {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "The device has not agreed to receive this agent's messages".to_string()
+                ));
+            }
+            Ok(ValidateCallbackResult::Valid)
+        }
+        FlatOp::RegisterDeleteLink { link_type: LinkTypes::AgentToDevices, original_action, action, .. } => {
+            if original_action.author != action.author {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Only the agent who registered a device can revoke it".to_string()
+                ));
+            }
+            Ok(ValidateCallbackResult::Valid)
+        }
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
 }
@@ -1,4 +1,6 @@
 use hdi::prelude::*;
+use membership_proof::require_membership_proof;
+pub use location::{validate_location, Location};
 
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq, Eq)]
@@ -11,18 +13,143 @@ pub struct Event {
     pub attendees: Vec<AgentPubKey>,
     pub max_attendees: Option<u32>,
     pub created_at: Timestamp,
+    /// Approximate position for nearby queries; `location` stays the display text
+    #[serde(default)]
+    pub place: Option<Location>,
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Lowercased free-form tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub guest_list_visibility: GuestListVisibility,
+    #[serde(default)]
+    pub duration_minutes: Option<u32>,
+    #[serde(default)]
+    pub visibility: EventVisibility,
+    /// Who may see and RSVP to an invite-only event, besides the host
+    #[serde(default)]
+    pub invitees: Vec<AgentPubKey>,
+}
+
+impl Event {
+    pub fn is_invited(&self, agent: &AgentPubKey) -> bool {
+        match self.visibility {
+            EventVisibility::Neighborhood => true,
+            EventVisibility::InviteOnly => self.host == *agent || self.invitees.contains(agent),
+        }
+    }
+}
+
+/// Who can see and RSVP to an event
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EventVisibility {
+    #[default]
+    Neighborhood,
+    InviteOnly,
+}
+
+/// Who the events zome shows an event's attendees to; max_attendees applies
+/// either way
+///
+/// This only filters what the zome's functions return. `attendees` is still
+/// part of the public `Event` entry, so anyone reading the entry straight from
+/// the DHT sees the whole guest list. It keeps the list out of the app, not
+/// out of reach.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GuestListVisibility {
+    #[default]
+    Public,
+    AttendeesOnly,
+    HostOnly,
+}
+
+/// Which discussion signals about an event an attendee wants to see
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum NotificationLevel {
+    #[default]
+    All,
+    MentionsOnly,
+    None,
+}
+
+/// An agent's notification level for one event; private, latest entry per event wins
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct EventNotificationPreference {
+    pub event_hash: ActionHash,
+    pub level: NotificationLevel,
 }
 
 pub const MAX_TITLE_LENGTH: usize = 100;
 pub const MAX_DESCRIPTION_LENGTH: usize = 2000;
 pub const MAX_LOCATION_LENGTH: usize = 200;
 pub const MAX_ATTENDEES: usize = 100;
+pub const MAX_INVITEES: usize = 100;
+pub const MAX_EVENT_MESSAGE_LENGTH: usize = 1000;
+/// One week
+pub const MAX_DURATION_MINUTES: u32 = 7 * 24 * 60;
+pub const MAX_CATEGORY_LENGTH: usize = 50;
+pub const MAX_TAGS: usize = 5;
+pub const MAX_TAG_LENGTH: usize = 30;
+/// Longest a host-generated check-in code may stay valid (12 hours)
+pub const MAX_CHECKIN_CODE_VALIDITY_MICROS: i64 = 12 * 60 * 60 * 1_000_000;
+
+/// Reusable defaults for a recurring event; only its creator can update it
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct EventTemplate {
+    pub title: String,
+    pub description: String,
+    pub location: String,
+    pub duration_minutes: Option<u32>,
+    pub max_attendees: Option<u32>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub creator: AgentPubKey,
+    pub created_at: Timestamp,
+}
+
+/// Record of an agent physically checking in to an event.
+///
+/// Carries the host's signature over the check-in code so any validator
+/// can confirm the attendee scanned a real, unexpired code from the host.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct Attendance {
+    pub event_hash: ActionHash,
+    pub attendee: AgentPubKey,
+    pub code_issued_at: Timestamp,
+    pub code_expires_at: Timestamp,
+    pub host_signature: Signature,
+    pub checked_in_at: Timestamp,
+}
+
+/// Bytes the host signs when generating a check-in code
+pub fn checkin_code_payload(
+    event_hash: &ActionHash,
+    issued_at: Timestamp,
+    expires_at: Timestamp,
+) -> Vec<u8> {
+    let mut payload = b"OURBLOCK_CHECKIN".to_vec();
+    payload.extend_from_slice(event_hash.get_raw_39());
+    payload.extend_from_slice(&issued_at.as_micros().to_le_bytes());
+    payload.extend_from_slice(&expires_at.as_micros().to_le_bytes());
+    payload
+}
 
 #[hdk_link_types]
 pub enum LinkTypes {
     AllEvents,
     AgentToEvents,
     AgentToAttendingEvents,
+    EventToAttendance,
+    AgentToAttendance,
+    /// Week bucket path (`events.week.<n>`) to events happening that week
+    WeekToEvents,
+    AgentToEventTemplates,
+    /// Invitee to the invite-only events they were invited to
+    AgentToInvitations,
 }
 
 #[hdk_entry_types]
@@ -30,6 +157,12 @@ pub enum LinkTypes {
 pub enum EntryTypes {
     #[entry_type(name = "event", visibility = "public")]
     Event(Event),
+    #[entry_type(name = "attendance", visibility = "public")]
+    Attendance(Attendance),
+    #[entry_type(name = "event_template", visibility = "public")]
+    EventTemplate(EventTemplate),
+    #[entry_type(name = "event_notification_preference", visibility = "private")]
+    EventNotificationPreference(EventNotificationPreference),
 }
 
 #[hdk_extern]
@@ -38,18 +171,42 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
         FlatOp::StoreEntry(store_entry) => match store_entry {
             OpEntry::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::Event(event) => validate_event(event, action.author.clone()),
+                EntryTypes::Attendance(attendance) => {
+                    validate_attendance(attendance, action.author.clone(), action.timestamp)
+                },
+                EntryTypes::EventTemplate(template) => validate_event_template(template, action.author.clone()),
+                EntryTypes::EventNotificationPreference(_) => Ok(ValidateCallbackResult::Valid),
             },
-            OpEntry::UpdateEntry { app_entry, action, .. } => match app_entry {
+            OpEntry::UpdateEntry { app_entry, action, original_action_hash, .. } => match app_entry {
                 EntryTypes::Event(event) => validate_event(event, action.author.clone()),
+                EntryTypes::Attendance(_) => Ok(ValidateCallbackResult::Invalid("Attendance cannot be updated".into())),
+                EntryTypes::EventTemplate(template) => {
+                    validate_update_event_template(template, action.author.clone(), original_action_hash)
+                },
+                EntryTypes::EventNotificationPreference(_) => Ok(ValidateCallbackResult::Invalid(
+                    "Notification preferences are replaced, not updated".into(),
+                )),
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
         FlatOp::StoreRecord(store_record) => match store_record {
             OpRecord::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::Event(event) => validate_event(event, action.author.clone()),
+                EntryTypes::Attendance(attendance) => {
+                    validate_attendance(attendance, action.author.clone(), action.timestamp)
+                },
+                EntryTypes::EventTemplate(template) => validate_event_template(template, action.author.clone()),
+                EntryTypes::EventNotificationPreference(_) => Ok(ValidateCallbackResult::Valid),
             },
-            OpRecord::UpdateEntry { app_entry, action, .. } => match app_entry {
+            OpRecord::UpdateEntry { app_entry, action, original_action_hash, .. } => match app_entry {
                 EntryTypes::Event(event) => validate_event(event, action.author.clone()),
+                EntryTypes::Attendance(_) => Ok(ValidateCallbackResult::Invalid("Attendance cannot be updated".into())),
+                EntryTypes::EventTemplate(template) => {
+                    validate_update_event_template(template, action.author.clone(), original_action_hash)
+                },
+                EntryTypes::EventNotificationPreference(_) => Ok(ValidateCallbackResult::Invalid(
+                    "Notification preferences are replaced, not updated".into(),
+                )),
             },
             OpRecord::DeleteEntry { original_action_hash, action, .. } => {
                 let original_record = must_get_valid_record(original_action_hash)?;
@@ -65,6 +222,18 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
+        FlatOp::RegisterCreateLink {
+            link_type: LinkTypes::AgentToInvitations,
+            base_address,
+            target_address,
+            action,
+            ..
+        } => validate_invitation_link(base_address, target_address, action.author),
+        // Only verified members may publish; checked against the author's own chain
+        FlatOp::RegisterAgentActivity(OpActivity::CreateEntry {
+            app_entry_type: Some(UnitEntryTypes::Event | UnitEntryTypes::Attendance | UnitEntryTypes::EventTemplate),
+            action,
+        }) => require_membership_proof(&action.author, &action.prev_action),
         _ => Ok(ValidateCallbackResult::Valid),
     }
 }
@@ -101,8 +270,180 @@ fn validate_event(event: Event, author: AgentPubKey) -> ExternResult<ValidateCal
             return Ok(ValidateCallbackResult::Invalid("Attendees exceed max_attendees limit".into()));
         }
     }
+    if let Some(ref category) = event.category {
+        if category.trim().is_empty() || category.len() > MAX_CATEGORY_LENGTH {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Category must be 1-{} characters", MAX_CATEGORY_LENGTH
+            )));
+        }
+    }
+    if event.tags.len() > MAX_TAGS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Cannot have more than {} tags", MAX_TAGS
+        )));
+    }
+    if event.tags.iter().any(|tag| tag.trim().is_empty() || tag.len() > MAX_TAG_LENGTH) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Tags must be 1-{} characters", MAX_TAG_LENGTH
+        )));
+    }
+    if event.duration_minutes.is_some_and(|minutes| minutes == 0 || minutes > MAX_DURATION_MINUTES) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Duration must be 1-{} minutes", MAX_DURATION_MINUTES
+        )));
+    }
     if event.host != author {
         return Ok(ValidateCallbackResult::Invalid("Event host must match action author".into()));
     }
+    match event.visibility {
+        EventVisibility::Neighborhood if !event.invitees.is_empty() => {
+            return Ok(ValidateCallbackResult::Invalid("Only invite-only events have invitees".into()));
+        }
+        EventVisibility::InviteOnly => {
+            if event.invitees.len() > MAX_INVITEES {
+                return Ok(ValidateCallbackResult::Invalid(format!(
+                    "Cannot invite more than {} neighbors", MAX_INVITEES
+                )));
+            }
+            if event.attendees.iter().any(|attendee| !event.is_invited(attendee)) {
+                return Ok(ValidateCallbackResult::Invalid("Only invitees can attend an invite-only event".into()));
+            }
+        }
+        _ => {}
+    }
+    if let Some(ref place) = event.place {
+        if let Err(message) = validate_location(place) {
+            return Ok(ValidateCallbackResult::Invalid(message));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Only the host invites, and only agents on the event's invite list
+fn validate_invitation_link(
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    author: AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let (Some(invitee), Some(event_hash)) = (base_address.into_agent_pub_key(), target_address.into_action_hash())
+    else {
+        return Ok(ValidateCallbackResult::Invalid("Invitations go from an agent to an event".into()));
+    };
+    let event_record = must_get_valid_record(event_hash)?;
+    let Ok(Some(event)) = event_record.entry().to_app_option::<Event>() else {
+        return Ok(ValidateCallbackResult::Invalid("Invitations must be to an event".into()));
+    };
+    if event.host != author {
+        return Ok(ValidateCallbackResult::Invalid("Only the host can invite neighbors".into()));
+    }
+    if event.visibility != EventVisibility::InviteOnly || !event.invitees.contains(&invitee) {
+        return Ok(ValidateCallbackResult::Invalid("Agent is not on the event's invite list".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_event_template(template: EventTemplate, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if template.creator != author {
+        return Ok(ValidateCallbackResult::Invalid("Template creator must match action author".into()));
+    }
+    if template.title.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Template title cannot be empty".into()));
+    }
+    if template.title.len() > MAX_TITLE_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Title cannot exceed {} characters", MAX_TITLE_LENGTH
+        )));
+    }
+    if template.description.len() > MAX_DESCRIPTION_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Description cannot exceed {} characters", MAX_DESCRIPTION_LENGTH
+        )));
+    }
+    if template.location.len() > MAX_LOCATION_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Location cannot exceed {} characters", MAX_LOCATION_LENGTH
+        )));
+    }
+    if template.duration_minutes.is_some_and(|minutes| minutes == 0 || minutes > MAX_DURATION_MINUTES) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Duration must be 1-{} minutes", MAX_DURATION_MINUTES
+        )));
+    }
+    if template.max_attendees == Some(0) {
+        return Ok(ValidateCallbackResult::Invalid("Max attendees must be greater than 0".into()));
+    }
+    if let Some(ref category) = template.category {
+        if category.trim().is_empty() || category.len() > MAX_CATEGORY_LENGTH {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Category must be 1-{} characters", MAX_CATEGORY_LENGTH
+            )));
+        }
+    }
+    if template.tags.len() > MAX_TAGS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Cannot have more than {} tags", MAX_TAGS
+        )));
+    }
+    if template.tags.iter().any(|tag| tag.trim().is_empty() || tag.len() > MAX_TAG_LENGTH) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Tags must be 1-{} characters", MAX_TAG_LENGTH
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Only a template's creator can edit it
+fn validate_update_event_template(
+    template: EventTemplate,
+    author: AgentPubKey,
+    original_action_hash: ActionHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let original_action = must_get_action(original_action_hash)?;
+    if original_action.action().author() != &author {
+        return Ok(ValidateCallbackResult::Invalid("Only the creator can update their template".into()));
+    }
+    validate_event_template(template, author)
+}
+
+fn validate_attendance(
+    attendance: Attendance,
+    author: AgentPubKey,
+    committed_at: Timestamp,
+) -> ExternResult<ValidateCallbackResult> {
+    if attendance.attendee != author {
+        return Ok(ValidateCallbackResult::Invalid("Attendee must match action author".into()));
+    }
+    if attendance.code_expires_at <= attendance.code_issued_at {
+        return Ok(ValidateCallbackResult::Invalid("Check-in code expiry must be after issue time".into()));
+    }
+    if attendance.code_expires_at.as_micros() - attendance.code_issued_at.as_micros()
+        > MAX_CHECKIN_CODE_VALIDITY_MICROS
+    {
+        return Ok(ValidateCallbackResult::Invalid("Check-in code validity window is too long".into()));
+    }
+    if committed_at < attendance.code_issued_at || committed_at > attendance.code_expires_at {
+        return Ok(ValidateCallbackResult::Invalid("Check-in code was not valid at check-in time".into()));
+    }
+
+    let event_record = must_get_valid_record(attendance.event_hash.clone())?;
+    let Some(event) = event_record
+        .entry()
+        .to_app_option::<Event>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    else {
+        return Ok(ValidateCallbackResult::Invalid("Attendance must reference an event".into()));
+    };
+    if attendance.attendee == event.host {
+        return Ok(ValidateCallbackResult::Invalid("Host cannot check in to their own event".into()));
+    }
+
+    let payload = checkin_code_payload(
+        &attendance.event_hash,
+        attendance.code_issued_at,
+        attendance.code_expires_at,
+    );
+    if !verify_signature(event.host, attendance.host_signature, payload)? {
+        return Ok(ValidateCallbackResult::Invalid("Check-in code was not signed by the event host".into()));
+    }
     Ok(ValidateCallbackResult::Valid)
 }
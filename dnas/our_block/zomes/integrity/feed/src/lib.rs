@@ -1,4 +1,6 @@
 use hdi::prelude::*;
+use membership_proof::require_membership_proof;
+pub use location::{validate_location, Location};
 
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq, Eq)]
@@ -7,12 +9,88 @@ pub struct Post {
     pub content: String,
     pub author: AgentPubKey,
     pub created_at: Timestamp,
+    /// Topic such as "lost-and-found"; None for general posts
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Preview cards for URLs shared in the post
+    #[serde(default)]
+    pub links: Vec<LinkPreview>,
+    /// Normalized hashtags, without the `#`; from the content or given explicitly
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Lowercase a tag and drop a leading `#`; None if it isn't a valid tag
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim().trim_start_matches('#').to_lowercase();
+    let valid = !tag.is_empty()
+        && tag.chars().count() <= MAX_TAG_LENGTH
+        && tag.chars().all(is_tag_char);
+    valid.then_some(tag)
+}
+
+/// `#tags` in a piece of text, normalized, in order of first appearance
+///
+/// A `#` only starts a tag at the beginning of a word, so URL fragments and
+/// "C#" are left alone.
+pub fn parse_hashtags(text: &str) -> Vec<String> {
+    let mut tags: Vec<String> = Vec::new();
+    let mut previous = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((start, c)) = chars.next() {
+        let at_word_start = previous.map_or(true, |p: char| !is_tag_char(p) && p != '#');
+        previous = Some(c);
+        if c != '#' || !at_word_start {
+            continue;
+        }
+        let mut end = start + 1;
+        while let Some(&(index, next)) = chars.peek() {
+            if !is_tag_char(next) {
+                break;
+            }
+            end = index + next.len_utf8();
+            previous = Some(next);
+            chars.next();
+        }
+        if let Some(tag) = normalize_tag(&text[start + 1..end]) {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+        }
+    }
+    tags
+}
+
+fn is_tag_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '-'
+}
+
+/// Metadata for a shared URL, fetched by the author's client
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkPreview {
+    pub url: String,
+    pub title: String,
+    pub description: Option<String>,
+    /// Preview image uploaded through file_storage
+    pub image_hash: Option<EntryHash>,
+}
+
+/// What kind of record a reaction is attached to
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ReactionTarget {
+    #[default]
+    Post,
+    Comment,
 }
 
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq, Eq)]
 pub struct Reaction {
-    pub post_hash: ActionHash,
+    /// The post or comment reacted to; older reactions stored it as `post_hash`
+    #[serde(alias = "post_hash")]
+    pub target: ActionHash,
+    #[serde(default)]
+    pub target_kind: ReactionTarget,
     pub author: AgentPubKey,
     pub reaction_type: String, // "like", "love", "celebrate", etc.
     pub created_at: Timestamp,
@@ -27,11 +105,173 @@ pub struct Comment {
     pub created_at: Timestamp,
 }
 
+/// What an agent doesn't want to see in their feed
+///
+/// Private to the agent's own chain; each change is a new entry and the
+/// latest one wins.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq, Default)]
+pub struct FeedPreferences {
+    pub muted_agents: Vec<AgentPubKey>,
+    pub muted_channels: Vec<String>,
+    /// Lowercase words or phrases; posts containing any of them are hidden
+    pub keyword_filters: Vec<String>,
+    /// Normalized hashtags whose posts the agent wants to see
+    #[serde(default)]
+    pub followed_tags: Vec<String>,
+}
+
+/// The newest post timestamp an agent has seen; private, latest entry wins
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct ReadMarker {
+    pub up_to: Timestamp,
+}
+
+/// How often an agent wants the "what's new" digest pushed to them
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(tag = "type")]
+pub enum DigestFrequency {
+    #[default]
+    Off,
+    Daily,
+    Weekly,
+}
+
+impl DigestFrequency {
+    /// Time between digests; None when they're off
+    pub fn interval_secs(&self) -> Option<i64> {
+        match self {
+            DigestFrequency::Off => None,
+            DigestFrequency::Daily => Some(24 * 60 * 60),
+            DigestFrequency::Weekly => Some(7 * 24 * 60 * 60),
+        }
+    }
+}
+
+/// How an agent wants their digest delivered; private, latest entry wins
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq, Default)]
+pub struct DigestPreferences {
+    pub frequency: DigestFrequency,
+}
+
+/// Whether the author lost something or found something
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum LostFoundKind {
+    Lost,
+    Found,
+}
+
+/// Where a lost-and-found post stands; only moves forward
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum LostFoundStatus {
+    Open,
+    /// A lost item has turned up
+    Found,
+    /// The item is back with its owner
+    Claimed,
+}
+
+impl LostFoundStatus {
+    pub fn can_become(&self, next: LostFoundStatus, kind: LostFoundKind) -> bool {
+        matches!(
+            (self, next, kind),
+            (LostFoundStatus::Open, LostFoundStatus::Found, LostFoundKind::Lost)
+                | (LostFoundStatus::Open, LostFoundStatus::Claimed, _)
+                | (LostFoundStatus::Found, LostFoundStatus::Claimed, _)
+        )
+    }
+}
+
+/// A lost or found item, tracked until it's back with its owner
+///
+/// Only `status` and `resolved_at` change after posting; each change is an
+/// update of the latest revision.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct LostFoundPost {
+    pub kind: LostFoundKind,
+    pub item_description: String,
+    /// Where it was lost, or where it was found
+    pub last_seen: Option<Location>,
+    pub last_seen_at: Option<Timestamp>,
+    /// Identifying marks, how to get in touch, ...
+    pub details: Option<String>,
+    pub author: AgentPubKey,
+    pub created_at: Timestamp,
+    pub status: LostFoundStatus,
+    /// When the status last moved past Open
+    pub resolved_at: Option<Timestamp>,
+}
+
+/// A neighbor asking the anchors to look at a post
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct Report {
+    pub post_hash: ActionHash,
+    pub reporter: AgentPubKey,
+    pub reason: String,
+    pub created_at: Timestamp,
+}
+
+/// What an anchor decided to do about a report
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum ResolutionAction {
+    /// Nothing wrong; the post stays
+    Dismiss,
+    /// The post is left out of everyone's feed
+    Hide,
+    /// The post stays, and its author is told why it was reported
+    WarnAuthor,
+}
+
+/// An anchor's decision on a report
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct ReportResolution {
+    pub report: ActionHash,
+    pub action: ResolutionAction,
+    /// Shown to the reporter, and to the author when warned
+    pub note: Option<String>,
+    /// The resolver's TrustedAnchor record in the vouch zome
+    pub resolver_anchor: ActionHash,
+    pub resolved_at: Timestamp,
+}
+
+/// Mirror of the vouch zome's TrustedAnchor, for checking resolvers
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct TrustedAnchor {
+    pub agent: AgentPubKey,
+    pub created_at: Timestamp,
+    #[serde(default)]
+    pub nomination: Option<ActionHash>,
+}
+
 pub const MIN_TITLE_LENGTH: usize = 5;
 pub const MAX_TITLE_LENGTH: usize = 100;
 pub const MAX_CONTENT_LENGTH: usize = 10000;
 pub const MAX_COMMENT_LENGTH: usize = 1000;
 pub const MAX_REACTION_TYPE_LENGTH: usize = 20;
+pub const MAX_CHANNEL_LENGTH: usize = 32;
+pub const MAX_LINK_PREVIEWS: usize = 5;
+pub const MAX_URL_LENGTH: usize = 2048;
+pub const MAX_PREVIEW_TITLE_LENGTH: usize = 200;
+pub const MAX_PREVIEW_DESCRIPTION_LENGTH: usize = 500;
+pub const MAX_MUTED_AGENTS: usize = 500;
+pub const MAX_MUTED_CHANNELS: usize = 50;
+pub const MAX_KEYWORD_FILTERS: usize = 50;
+pub const MAX_KEYWORD_FILTER_LENGTH: usize = 50;
+pub const MAX_TAGS: usize = 10;
+pub const MAX_TAG_LENGTH: usize = 32;
+pub const MAX_FOLLOWED_TAGS: usize = 50;
+pub const MAX_ITEM_DESCRIPTION_LENGTH: usize = 500;
+pub const MAX_LOST_FOUND_DETAILS_LENGTH: usize = 1000;
+pub const MAX_REPORT_REASON_LENGTH: usize = 500;
+pub const MAX_RESOLUTION_NOTE_LENGTH: usize = 500;
 
 #[hdk_link_types]
 pub enum LinkTypes {
@@ -40,6 +280,19 @@ pub enum LinkTypes {
     PostToReactions,
     PostToComments,
     AgentToReactions,
+    CommentToReactions,
+    /// Review queue anchor to every report; anchors read it
+    ReviewQueue,
+    ReportToResolutions,
+    /// Post to the Hide resolutions taken against it
+    PostToHides,
+    /// Hashtag anchor (`tags.<tag>`) to each original post carrying the tag
+    TagToPosts,
+    AllLostFound,
+    AgentToLostFound,
+    /// All-posts anchor to a post, tagged with its comment count (u32, little
+    /// endian) as the link's author last saw it; the highest count wins
+    CommentCounts,
 }
 
 #[hdk_entry_types]
@@ -51,6 +304,18 @@ pub enum EntryTypes {
     Reaction(Reaction),
     #[entry_type(name = "comment", visibility = "public")]
     Comment(Comment),
+    #[entry_type(name = "feed_preferences", visibility = "private")]
+    FeedPreferences(FeedPreferences),
+    #[entry_type(name = "read_marker", visibility = "private")]
+    ReadMarker(ReadMarker),
+    #[entry_type(name = "report", visibility = "public")]
+    Report(Report),
+    #[entry_type(name = "report_resolution", visibility = "public")]
+    ReportResolution(ReportResolution),
+    #[entry_type(name = "lost_found_post", visibility = "public")]
+    LostFoundPost(LostFoundPost),
+    #[entry_type(name = "digest_preferences", visibility = "private")]
+    DigestPreferences(DigestPreferences),
 }
 
 #[hdk_extern]
@@ -61,9 +326,19 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 EntryTypes::Post(post) => validate_post(post, action.author.clone()),
                 EntryTypes::Reaction(reaction) => validate_reaction(reaction, action.author.clone()),
                 EntryTypes::Comment(comment) => validate_comment(comment, action.author.clone()),
+                EntryTypes::FeedPreferences(preferences) => validate_feed_preferences(preferences),
+                EntryTypes::ReadMarker(marker) => validate_read_marker(marker, action.timestamp),
+                EntryTypes::Report(report) => validate_report(report, action.author.clone()),
+                EntryTypes::ReportResolution(resolution) => validate_resolution(resolution, action.author.clone()),
+                EntryTypes::LostFoundPost(post) => validate_lost_found(post, action.author.clone()),
+                // The frequency is the whole entry and every value is allowed
+                EntryTypes::DigestPreferences(_) => Ok(ValidateCallbackResult::Valid),
             },
-            OpEntry::UpdateEntry { app_entry, action, .. } => match app_entry {
-                EntryTypes::Post(post) => validate_post(post, action.author.clone()),
+            OpEntry::UpdateEntry { app_entry, action, original_action_hash, .. } => match app_entry {
+                EntryTypes::Post(post) => validate_update_post(post, action.author.clone(), original_action_hash),
+                EntryTypes::LostFoundPost(post) => {
+                    validate_update_lost_found(post, action.author.clone(), original_action_hash)
+                }
                 _ => Ok(ValidateCallbackResult::Invalid("Only posts can be updated".into())),
             },
             _ => Ok(ValidateCallbackResult::Valid),
@@ -73,9 +348,19 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 EntryTypes::Post(post) => validate_post(post, action.author.clone()),
                 EntryTypes::Reaction(reaction) => validate_reaction(reaction, action.author.clone()),
                 EntryTypes::Comment(comment) => validate_comment(comment, action.author.clone()),
+                EntryTypes::FeedPreferences(preferences) => validate_feed_preferences(preferences),
+                EntryTypes::ReadMarker(marker) => validate_read_marker(marker, action.timestamp),
+                EntryTypes::Report(report) => validate_report(report, action.author.clone()),
+                EntryTypes::ReportResolution(resolution) => validate_resolution(resolution, action.author.clone()),
+                EntryTypes::LostFoundPost(post) => validate_lost_found(post, action.author.clone()),
+                // The frequency is the whole entry and every value is allowed
+                EntryTypes::DigestPreferences(_) => Ok(ValidateCallbackResult::Valid),
             },
-            OpRecord::UpdateEntry { app_entry, action, .. } => match app_entry {
-                EntryTypes::Post(post) => validate_post(post, action.author.clone()),
+            OpRecord::UpdateEntry { app_entry, action, original_action_hash, .. } => match app_entry {
+                EntryTypes::Post(post) => validate_update_post(post, action.author.clone(), original_action_hash),
+                EntryTypes::LostFoundPost(post) => {
+                    validate_update_lost_found(post, action.author.clone(), original_action_hash)
+                }
                 _ => Ok(ValidateCallbackResult::Invalid("Only posts can be updated".into())),
             },
             OpRecord::DeleteEntry { original_action_hash, action, .. } => {
@@ -92,6 +377,39 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
+        FlatOp::RegisterCreateLink { link_type: LinkTypes::PostToHides, base_address, target_address, action, .. } => {
+            validate_hide_link(base_address, target_address, action.author)
+        }
+        FlatOp::RegisterCreateLink { link_type: LinkTypes::CommentCounts, tag, .. } => {
+            if tag.0.len() != 4 {
+                return Ok(ValidateCallbackResult::Invalid("Comment count tag must be a u32".into()));
+            }
+            Ok(ValidateCallbackResult::Valid)
+        }
+        FlatOp::RegisterDeleteLink { link_type: LinkTypes::CommentCounts, original_action, action, .. } => {
+            if original_action.author != action.author {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Only the agent who recorded a comment count can replace it".into(),
+                ));
+            }
+            Ok(ValidateCallbackResult::Valid)
+        }
+        // Reports and their outcomes are the moderation record; they stay
+        FlatOp::RegisterDeleteLink {
+            link_type: LinkTypes::ReviewQueue | LinkTypes::ReportToResolutions | LinkTypes::PostToHides,
+            ..
+        } => Ok(ValidateCallbackResult::Invalid("Moderation links cannot be deleted".into())),
+        // Only verified members may publish; checked against the author's own chain
+        FlatOp::RegisterAgentActivity(OpActivity::CreateEntry {
+            app_entry_type: Some(
+                UnitEntryTypes::Post
+                | UnitEntryTypes::Reaction
+                | UnitEntryTypes::Comment
+                | UnitEntryTypes::Report
+                | UnitEntryTypes::LostFoundPost,
+            ),
+            action,
+        }) => require_membership_proof(&action.author, &action.prev_action),
         _ => Ok(ValidateCallbackResult::Valid),
     }
 }
@@ -118,9 +436,77 @@ fn validate_post(post: Post, author: AgentPubKey) -> ExternResult<ValidateCallba
     if post.author != author {
         return Ok(ValidateCallbackResult::Invalid("Post author must match action author".into()));
     }
+    if let Some(ref channel) = post.channel {
+        if channel.trim().is_empty() || channel.len() > MAX_CHANNEL_LENGTH {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Channel must be 1-{} characters", MAX_CHANNEL_LENGTH
+            )));
+        }
+    }
+    if post.links.len() > MAX_LINK_PREVIEWS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Posts cannot have more than {} link previews", MAX_LINK_PREVIEWS
+        )));
+    }
+    for link in &post.links {
+        let result = validate_link_preview(link)?;
+        if result != ValidateCallbackResult::Valid {
+            return Ok(result);
+        }
+    }
+    if post.tags.len() > MAX_TAGS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Posts cannot have more than {} tags", MAX_TAGS
+        )));
+    }
+    for (index, tag) in post.tags.iter().enumerate() {
+        if normalize_tag(tag).as_ref() != Some(tag) {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Tags must be 1-{} lowercase letters, digits, '-' or '_'", MAX_TAG_LENGTH
+            )));
+        }
+        if post.tags[..index].contains(tag) {
+            return Ok(ValidateCallbackResult::Invalid("Tags cannot repeat".into()));
+        }
+    }
     Ok(ValidateCallbackResult::Valid)
 }
 
+fn validate_link_preview(link: &LinkPreview) -> ExternResult<ValidateCallbackResult> {
+    if !(link.url.starts_with("https://") || link.url.starts_with("http://")) {
+        return Ok(ValidateCallbackResult::Invalid("Link preview URL must be http or https".into()));
+    }
+    if link.url.len() > MAX_URL_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Link preview URL cannot exceed {} characters", MAX_URL_LENGTH
+        )));
+    }
+    if link.title.len() > MAX_PREVIEW_TITLE_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Link preview title cannot exceed {} characters", MAX_PREVIEW_TITLE_LENGTH
+        )));
+    }
+    if link.description.as_ref().is_some_and(|description| description.len() > MAX_PREVIEW_DESCRIPTION_LENGTH) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Link preview description cannot exceed {} characters", MAX_PREVIEW_DESCRIPTION_LENGTH
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Only a post's author can revise it
+fn validate_update_post(
+    post: Post,
+    author: AgentPubKey,
+    original_action_hash: ActionHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let original_action = must_get_action(original_action_hash)?;
+    if original_action.action().author() != &author {
+        return Ok(ValidateCallbackResult::Invalid("Only the author can update their post".into()));
+    }
+    validate_post(post, author)
+}
+
 fn validate_reaction(reaction: Reaction, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
     if reaction.author != author {
         return Ok(ValidateCallbackResult::Invalid("Reaction author must match action author".into()));
@@ -133,6 +519,20 @@ fn validate_reaction(reaction: Reaction, author: AgentPubKey) -> ExternResult<Va
             "Reaction type cannot exceed {} characters", MAX_REACTION_TYPE_LENGTH
         )));
     }
+    let target_record = must_get_valid_record(reaction.target)?;
+    let target_matches = match reaction.target_kind {
+        ReactionTarget::Post => matches!(target_record.entry().to_app_option::<Post>(), Ok(Some(_))),
+        ReactionTarget::Comment => matches!(target_record.entry().to_app_option::<Comment>(), Ok(Some(_))),
+    };
+    if !target_matches {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Reaction target must be a {}",
+            match reaction.target_kind {
+                ReactionTarget::Post => "post",
+                ReactionTarget::Comment => "comment",
+            }
+        )));
+    }
     Ok(ValidateCallbackResult::Valid)
 }
 
@@ -150,3 +550,182 @@ fn validate_comment(comment: Comment, author: AgentPubKey) -> ExternResult<Valid
     }
     Ok(ValidateCallbackResult::Valid)
 }
+
+fn validate_feed_preferences(preferences: FeedPreferences) -> ExternResult<ValidateCallbackResult> {
+    if preferences.muted_agents.len() > MAX_MUTED_AGENTS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Cannot mute more than {} neighbors", MAX_MUTED_AGENTS
+        )));
+    }
+    if preferences.muted_channels.len() > MAX_MUTED_CHANNELS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Cannot mute more than {} channels", MAX_MUTED_CHANNELS
+        )));
+    }
+    if preferences.muted_channels.iter().any(|channel| channel.len() > MAX_CHANNEL_LENGTH) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Channel cannot exceed {} characters", MAX_CHANNEL_LENGTH
+        )));
+    }
+    if preferences.keyword_filters.len() > MAX_KEYWORD_FILTERS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Cannot have more than {} keyword filters", MAX_KEYWORD_FILTERS
+        )));
+    }
+    if preferences.followed_tags.len() > MAX_FOLLOWED_TAGS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Cannot follow more than {} tags", MAX_FOLLOWED_TAGS
+        )));
+    }
+    if preferences.followed_tags.iter().any(|tag| normalize_tag(tag).as_ref() != Some(tag)) {
+        return Ok(ValidateCallbackResult::Invalid("Followed tags must be normalized".into()));
+    }
+    for keyword in &preferences.keyword_filters {
+        if keyword.trim().is_empty() {
+            return Ok(ValidateCallbackResult::Invalid("Keyword filters cannot be empty".into()));
+        }
+        if keyword.len() > MAX_KEYWORD_FILTER_LENGTH {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Keyword filter cannot exceed {} characters", MAX_KEYWORD_FILTER_LENGTH
+            )));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_read_marker(marker: ReadMarker, committed_at: Timestamp) -> ExternResult<ValidateCallbackResult> {
+    if marker.up_to > committed_at {
+        return Ok(ValidateCallbackResult::Invalid("Cannot mark posts from the future as read".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_lost_found(post: LostFoundPost, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if post.author != author {
+        return Ok(ValidateCallbackResult::Invalid("Post author must match action author".into()));
+    }
+    if post.status != LostFoundStatus::Open || post.resolved_at.is_some() {
+        return Ok(ValidateCallbackResult::Invalid("Lost and found posts start out open".into()));
+    }
+    validate_lost_found_fields(&post)
+}
+
+fn validate_lost_found_fields(post: &LostFoundPost) -> ExternResult<ValidateCallbackResult> {
+    if post.item_description.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Item description cannot be empty".into()));
+    }
+    if post.item_description.len() > MAX_ITEM_DESCRIPTION_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Item description cannot exceed {} characters", MAX_ITEM_DESCRIPTION_LENGTH
+        )));
+    }
+    if post.details.as_ref().is_some_and(|details| details.len() > MAX_LOST_FOUND_DETAILS_LENGTH) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Details cannot exceed {} characters", MAX_LOST_FOUND_DETAILS_LENGTH
+        )));
+    }
+    if let Some(ref place) = post.last_seen {
+        if let Err(message) = validate_location(place) {
+            return Ok(ValidateCallbackResult::Invalid(message));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Only the author can move a post's status, and only forward
+fn validate_update_lost_found(
+    post: LostFoundPost,
+    author: AgentPubKey,
+    original_action_hash: ActionHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let original_record = must_get_valid_record(original_action_hash)?;
+    let Ok(Some(original)) = original_record.entry().to_app_option::<LostFoundPost>() else {
+        return Ok(ValidateCallbackResult::Invalid("Original must be a lost and found post".into()));
+    };
+    if original_record.action().author() != &author {
+        return Ok(ValidateCallbackResult::Invalid("Only the author can update their post".into()));
+    }
+    let unchanged = LostFoundPost {
+        status: post.status,
+        resolved_at: post.resolved_at,
+        ..original.clone()
+    };
+    if post != unchanged {
+        return Ok(ValidateCallbackResult::Invalid("Only the status of a lost and found post can change".into()));
+    }
+    if !original.status.can_become(post.status, original.kind) || post.resolved_at.is_none() {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "A {:?} post cannot go from {:?} to {:?}", original.kind, original.status, post.status
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_report(report: Report, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if report.reporter != author {
+        return Ok(ValidateCallbackResult::Invalid("Reporter must match action author".into()));
+    }
+    if report.reason.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Report reason cannot be empty".into()));
+    }
+    if report.reason.len() > MAX_REPORT_REASON_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Report reason cannot exceed {} characters", MAX_REPORT_REASON_LENGTH
+        )));
+    }
+    let post_record = must_get_valid_record(report.post_hash)?;
+    if !matches!(post_record.entry().to_app_option::<Post>(), Ok(Some(_))) {
+        return Ok(ValidateCallbackResult::Invalid("Only posts can be reported".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_resolution(resolution: ReportResolution, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if resolution.note.as_ref().is_some_and(|note| note.len() > MAX_RESOLUTION_NOTE_LENGTH) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Resolution note cannot exceed {} characters", MAX_RESOLUTION_NOTE_LENGTH
+        )));
+    }
+
+    // Only anchors may resolve: the resolver must reference their own TrustedAnchor
+    let anchor_record = must_get_valid_record(resolution.resolver_anchor)?;
+    let Ok(Some(anchor)) = anchor_record.entry().to_app_option::<TrustedAnchor>() else {
+        return Ok(ValidateCallbackResult::Invalid("Resolver must reference a trusted anchor".into()));
+    };
+    if anchor.agent != author || anchor_record.action().author() != &author {
+        return Ok(ValidateCallbackResult::Invalid("Only trusted anchors can resolve reports".into()));
+    }
+
+    let report_record = must_get_valid_record(resolution.report)?;
+    if !matches!(report_record.entry().to_app_option::<Report>(), Ok(Some(_))) {
+        return Ok(ValidateCallbackResult::Invalid("Resolution must be for a report".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// A post is hidden by linking it to the Hide resolution of a report against it
+fn validate_hide_link(
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    author: AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let (Some(post_hash), Some(resolution_hash)) = (base_address.into_action_hash(), target_address.into_action_hash())
+    else {
+        return Ok(ValidateCallbackResult::Invalid("Hide link must go from a post to a resolution".into()));
+    };
+    let resolution_record = must_get_valid_record(resolution_hash)?;
+    let Ok(Some(resolution)) = resolution_record.entry().to_app_option::<ReportResolution>() else {
+        return Ok(ValidateCallbackResult::Invalid("Hide link must point to a resolution".into()));
+    };
+    if resolution_record.action().author() != &author || resolution.action != ResolutionAction::Hide {
+        return Ok(ValidateCallbackResult::Invalid("Only the anchor who chose to hide a post can hide it".into()));
+    }
+    let report_record = must_get_valid_record(resolution.report)?;
+    let Ok(Some(report)) = report_record.entry().to_app_option::<Report>() else {
+        return Ok(ValidateCallbackResult::Invalid("Resolution must be for a report".into()));
+    };
+    if report.post_hash != post_hash {
+        return Ok(ValidateCallbackResult::Invalid("Hide link must start at the reported post".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
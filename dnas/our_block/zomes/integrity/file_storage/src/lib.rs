@@ -11,6 +11,32 @@ pub struct FileMetadata {
     pub chunks: Vec<ActionHash>, // References to file chunks
     pub author: AgentPubKey,
     pub created_at: Timestamp,
+    /// Hex SHA-256 of each chunk, in chunk order, so a corrupted chunk is
+    /// caught on its own; empty for files stored before this was added
+    #[serde(default)]
+    pub chunk_hashes: Vec<String>,
+    /// Sensitive documents (budgets, incident reports) record a FileAccess
+    /// each time someone other than the author fetches them
+    #[serde(default)]
+    pub log_access: bool,
+    /// Short-lived files (flyers) disappear from listings after this and are
+    /// deleted by the author's next `cleanup_expired_files`. Only this field
+    /// may change in an update, so the author can renew or clear it.
+    #[serde(default)]
+    pub expires_at: Option<Timestamp>,
+    /// Deflate if any chunk is stored compressed. `hash` and `size` are of
+    /// the original content; `chunk_hashes` are of the bytes as stored.
+    #[serde(default)]
+    pub compression: Compression,
+}
+
+/// How a chunk's bytes are stored
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Compression {
+    #[default]
+    None,
+    /// Raw DEFLATE (RFC 1951); each chunk inflates on its own
+    Deflate,
 }
 
 /// A chunk of file data (max ~4MB per chunk for DHT efficiency)
@@ -21,18 +47,233 @@ pub struct FileChunk {
     pub chunk_index: u32,
     #[serde(with = "serde_bytes")]
     pub data: Vec<u8>,
+    /// Chunks are only compressed when that makes them smaller
+    #[serde(default)]
+    pub compression: Compression,
+}
+
+/// Uploader-private state for a multi-call upload
+///
+/// Chunks uploaded in the session reference the session's action hash as
+/// their `file_hash` until `finish_upload` assembles the FileMetadata.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct UploadSession {
+    pub name: String,
+    pub file_type: String,
+    pub size: u32,
+    pub sha256: String, // Expected hex SHA-256 of the whole file
+    pub chunk_size: u32,
+    pub total_chunks: u32,
+    pub started_at: Timestamp,
+    pub expires_at: Timestamp,
+    /// Carried over to the FileMetadata by `finish_upload`
+    #[serde(default)]
+    pub log_access: bool,
+    #[serde(default)]
+    pub file_expires_at: Option<Timestamp>,
+    /// Whether `upload_chunk` tries compressing each chunk
+    #[serde(default)]
+    pub compress: bool,
+}
+
+/// A named folder of shared files ("Meeting Minutes 2025"), optionally nested
+/// under another collection. Only managers may file things into it.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct FileCollection {
+    pub name: String,
+    pub description: Option<String>,
+    pub parent: Option<ActionHash>,
+    pub managers: Vec<AgentPubKey>,
+    pub created_at: Timestamp,
+}
+
+/// One fetch of an access-logged file, linked from the file so its author can
+/// see who has read it. Access records can't be deleted.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct FileAccess {
+    pub file: ActionHash,
+    pub accessor: AgentPubKey,
+    pub accessed_at: Timestamp,
 }
 
 pub const MAX_FILE_NAME_LENGTH: usize = 255;
 pub const MAX_FILE_TYPE_LENGTH: usize = 100;
 pub const MAX_CHUNK_SIZE: usize = 4_000_000; // ~4MB
 pub const MAX_FILE_CHUNKS: usize = 100; // Max ~400MB per file
+pub const DEFAULT_SESSION_CHUNK_SIZE: usize = 1_000_000; // ~1MB keeps each zome call small
+pub const DEFAULT_FILE_PAGE_SIZE: u32 = 20;
+pub const MAX_FILE_PAGE_SIZE: u32 = 100;
+pub const UPLOAD_SESSION_TTL_MICROS: i64 = 24 * 60 * 60 * 1_000_000; // 24 hours
+pub const STORAGE_STATS_TOP_UPLOADERS: usize = 10;
+/// Files smaller than this aren't worth compressing unless the uploader asks
+pub const COMPRESSION_MIN_SIZE: u32 = 16 * 1024; // 16KB
+/// Compressible formats; everything else (images, audio, PDFs, archives) is
+/// already compressed. `text/*` covers plain text and CSV.
+pub const COMPRESSIBLE_FILE_TYPES: &[&str] = &["text/*", "application/json", "application/xml", "image/svg+xml"];
+/// Authors are offered renewal this long before a file expires
+pub const FILE_EXPIRY_WARNING_MICROS: i64 = 3 * 24 * 60 * 60 * 1_000_000; // 3 days
+pub const MAX_COLLECTION_NAME_LENGTH: usize = 100;
+pub const MAX_COLLECTION_DESCRIPTION_LENGTH: usize = 500;
+pub const MAX_COLLECTION_MANAGERS: usize = 10;
+pub const MAX_COLLECTION_DEPTH: usize = 4; // Top-level collections are depth 1
+/// Used when the DNA properties don't set `max_file_size_bytes`
+pub const DEFAULT_MAX_FILE_SIZE: u32 = 50_000_000; // ~50MB
+/// Used when the DNA properties don't set `allowed_file_types`
+pub const DEFAULT_ALLOWED_FILE_TYPES: &[&str] = &[
+    "image/jpeg",
+    "image/png",
+    "image/gif",
+    "image/webp",
+    "application/pdf",
+    "text/plain",
+    "text/csv",
+    "audio/mpeg",
+    "audio/ogg",
+    "audio/webm",
+    "video/mp4",
+];
+
+/// Which files the neighborhood accepts, set per DNA at install time
+///
+/// Read from the `allowed_file_types` (a list of MIME types, where
+/// `image/*` allows a whole family) and `max_file_size_bytes` DNA properties.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UploadPolicy {
+    pub allowed_types: Vec<String>,
+    pub max_file_size: u32,
+}
+
+impl Default for UploadPolicy {
+    fn default() -> Self {
+        Self {
+            allowed_types: DEFAULT_ALLOWED_FILE_TYPES.iter().map(|t| t.to_string()).collect(),
+            max_file_size: DEFAULT_MAX_FILE_SIZE,
+        }
+    }
+}
+
+impl UploadPolicy {
+    pub fn allows_type(&self, file_type: &str) -> bool {
+        self.allowed_types.iter().any(|allowed| match allowed.strip_suffix("/*") {
+            Some(family) => file_type.split('/').next() == Some(family),
+            None => allowed == file_type,
+        })
+    }
+}
+
+/// Load the upload policy from DNA properties, falling back to the defaults
+pub fn upload_policy() -> ExternResult<UploadPolicy> {
+    let properties = dna_info()?.modifiers.properties;
+    let defaults = UploadPolicy::default();
+
+    let allowed_types = properties
+        .get("allowed_file_types")
+        .and_then(|v| v.as_array())
+        .map(|types| {
+            types
+                .iter()
+                .filter_map(|t| t.as_str())
+                .map(normalize_file_type)
+                .collect()
+        })
+        .unwrap_or(defaults.allowed_types);
+    let max_file_size = properties
+        .get("max_file_size_bytes")
+        .and_then(|v| v.as_u64())
+        .map(|v| v.min(u32::MAX as u64) as u32)
+        .unwrap_or(defaults.max_file_size);
+
+    Ok(UploadPolicy {
+        allowed_types,
+        max_file_size,
+    })
+}
+
+/// Lowercase a MIME type and drop parameters like `; charset=utf-8`
+pub fn normalize_file_type(file_type: &str) -> String {
+    file_type.split(';').next().unwrap_or("").trim().to_lowercase()
+}
+
+/// Identify a file from its leading bytes
+///
+/// Returns None for formats without a signature, such as plain text.
+pub fn sniff_file_type(bytes: &[u8]) -> Option<&'static str> {
+    let riff = |kind: &[u8]| bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == kind;
+    if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(&[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A]) {
+        Some("image/png")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if riff(b"WEBP") {
+        Some("image/webp")
+    } else if riff(b"WAVE") {
+        Some("audio/wav")
+    } else if bytes.starts_with(b"%PDF-") {
+        Some("application/pdf")
+    } else if bytes.starts_with(b"PK\x03\x04") {
+        Some("application/zip")
+    } else if bytes.starts_with(b"ID3") || bytes.starts_with(&[0xFF, 0xFB]) {
+        Some("audio/mpeg")
+    } else if bytes.starts_with(b"OggS") {
+        Some("audio/ogg")
+    } else if bytes.starts_with(&[0x1A, 0x45, 0xDF, 0xA3]) {
+        Some("video/webm")
+    } else if bytes.len() >= 8 && &bytes[4..8] == b"ftyp" {
+        Some("video/mp4")
+    } else {
+        None
+    }
+}
+
+/// Whether a file's leading bytes are consistent with its declared type
+///
+/// Formats with a signature must match it; zip-based office documents may
+/// declare their own type. Types without a signature are only accepted as
+/// text, and only if the bytes look like UTF-8.
+pub fn content_matches_type(file_type: &str, first_bytes: &[u8]) -> bool {
+    match sniff_file_type(first_bytes) {
+        Some("application/zip") => {
+            file_type == "application/zip" || file_type.starts_with("application/vnd.openxmlformats-officedocument.")
+        }
+        Some("audio/ogg") => file_type == "audio/ogg" || file_type == "video/ogg",
+        Some("video/webm") => file_type == "video/webm" || file_type == "audio/webm",
+        Some(sniffed) => sniffed == file_type,
+        None => {
+            let is_text = file_type.starts_with("text/") || file_type == "application/json";
+            // A chunk boundary may split the last character
+            let utf8 = match std::str::from_utf8(first_bytes) {
+                Ok(_) => true,
+                Err(e) => e.error_len().is_none(),
+            };
+            is_text && utf8 && !first_bytes.contains(&0)
+        }
+    }
+}
+
+/// A chunk's original content; compressed chunks are inflated, and never
+/// past MAX_CHUNK_SIZE
+pub fn chunk_content(chunk: &FileChunk) -> Result<Vec<u8>, String> {
+    match chunk.compression {
+        Compression::None => Ok(chunk.data.clone()),
+        Compression::Deflate => miniz_oxide::inflate::decompress_to_vec_with_limit(&chunk.data, MAX_CHUNK_SIZE)
+            .map_err(|e| format!("Chunk {} does not inflate: {:?}", chunk.chunk_index, e.status)),
+    }
+}
 
 #[hdk_link_types]
 pub enum LinkTypes {
     AllFiles,
     AgentToFiles,
     FileToChunks,
+    UploadSessionToChunks,
+    AllCollections,
+    CollectionToFiles,
+    CollectionToChildren,
+    FileToAccesses,
 }
 
 #[hdk_entry_types]
@@ -42,6 +283,12 @@ pub enum EntryTypes {
     FileMetadata(FileMetadata),
     #[entry_type(name = "file_chunk", visibility = "public")]
     FileChunk(FileChunk),
+    #[entry_type(name = "upload_session", visibility = "private")]
+    UploadSession(UploadSession),
+    #[entry_type(name = "file_collection", visibility = "public")]
+    FileCollection(FileCollection),
+    #[entry_type(name = "file_access", visibility = "public")]
+    FileAccess(FileAccess),
 }
 
 #[hdk_extern]
@@ -51,6 +298,15 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             OpEntry::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::FileMetadata(metadata) => validate_file_metadata(metadata, action.author.clone()),
                 EntryTypes::FileChunk(chunk) => validate_file_chunk(chunk),
+                EntryTypes::UploadSession(session) => validate_upload_session(session),
+                EntryTypes::FileCollection(collection) => validate_file_collection(collection, action.author.clone()),
+                EntryTypes::FileAccess(access) => validate_file_access(access, action.author.clone()),
+            },
+            OpEntry::UpdateEntry { app_entry: EntryTypes::FileCollection(_), .. } => Ok(
+                ValidateCallbackResult::Invalid("File collections cannot be updated".into()),
+            ),
+            OpEntry::UpdateEntry { app_entry: EntryTypes::FileMetadata(metadata), original_action_hash, action, .. } => {
+                validate_file_metadata_update(metadata, original_action_hash, action.author.clone())
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
@@ -58,6 +314,15 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             OpRecord::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::FileMetadata(metadata) => validate_file_metadata(metadata, action.author.clone()),
                 EntryTypes::FileChunk(chunk) => validate_file_chunk(chunk),
+                EntryTypes::UploadSession(session) => validate_upload_session(session),
+                EntryTypes::FileCollection(collection) => validate_file_collection(collection, action.author.clone()),
+                EntryTypes::FileAccess(access) => validate_file_access(access, action.author.clone()),
+            },
+            OpRecord::UpdateEntry { app_entry: EntryTypes::FileCollection(_), .. } => Ok(
+                ValidateCallbackResult::Invalid("File collections cannot be updated".into()),
+            ),
+            OpRecord::UpdateEntry { app_entry: EntryTypes::FileMetadata(metadata), original_action_hash, action, .. } => {
+                validate_file_metadata_update(metadata, original_action_hash, action.author.clone())
             },
             OpRecord::DeleteEntry { original_action_hash, action, .. } => {
                 let original_record = must_get_valid_record(original_action_hash)?;
@@ -69,10 +334,33 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 if action.author != original_action.author {
                     return Ok(ValidateCallbackResult::Invalid("Only the author can delete their file".into()));
                 }
+                if matches!(original_record.entry().to_app_option::<FileAccess>(), Ok(Some(_))) {
+                    return Ok(ValidateCallbackResult::Invalid("File access records cannot be deleted".into()));
+                }
                 Ok(ValidateCallbackResult::Valid)
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
+        FlatOp::RegisterCreateLink { link_type, base_address, target_address, action, .. } => match link_type {
+            LinkTypes::CollectionToFiles | LinkTypes::CollectionToChildren => {
+                let result = validate_collection_manager(base_address, &action.author)?;
+                if result != ValidateCallbackResult::Valid {
+                    return Ok(result);
+                }
+                validate_collection_link_target(link_type, target_address)
+            },
+            LinkTypes::FileToAccesses => validate_file_access_link(base_address, target_address, &action.author),
+            _ => Ok(ValidateCallbackResult::Valid),
+        },
+        FlatOp::RegisterDeleteLink { link_type, original_action, action, .. } => match link_type {
+            LinkTypes::CollectionToFiles | LinkTypes::CollectionToChildren => {
+                validate_collection_manager(original_action.base_address, &action.author)
+            },
+            LinkTypes::FileToAccesses => Ok(ValidateCallbackResult::Invalid(
+                "File access records cannot be unlinked".into(),
+            )),
+            _ => Ok(ValidateCallbackResult::Valid),
+        },
         _ => Ok(ValidateCallbackResult::Valid),
     }
 }
@@ -102,9 +390,80 @@ fn validate_file_metadata(metadata: FileMetadata, author: AgentPubKey) -> Extern
     if metadata.author != author {
         return Ok(ValidateCallbackResult::Invalid("File author must match action author".into()));
     }
+    if metadata.chunk_hashes.len() != metadata.chunks.len() {
+        return Ok(ValidateCallbackResult::Invalid("File needs one hash per chunk".into()));
+    }
+    if !metadata.chunk_hashes.iter().all(|hash| is_sha256_hex(hash)) {
+        return Ok(ValidateCallbackResult::Invalid("Chunk hashes must be 64 lowercase hex characters".into()));
+    }
+    if metadata.expires_at.is_some_and(|expires_at| expires_at <= metadata.created_at) {
+        return Ok(ValidateCallbackResult::Invalid("File expiry must be after its creation".into()));
+    }
+
+    let policy = upload_policy()?;
+    if let Some(invalid) = check_upload_policy(&policy, &metadata.file_type, metadata.size) {
+        return Ok(invalid);
+    }
+    let first_chunk: FileChunk = must_get_valid_record(metadata.chunks[0].clone())?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("First chunk is not a file chunk".into())))?;
+    if first_chunk.compression != Compression::None && metadata.compression == Compression::None {
+        return Ok(ValidateCallbackResult::Invalid("File with compressed chunks must declare its compression".into()));
+    }
+    let first_bytes = match chunk_content(&first_chunk) {
+        Ok(bytes) => bytes,
+        Err(e) => return Ok(ValidateCallbackResult::Invalid(e)),
+    };
+    if !content_matches_type(&metadata.file_type, &first_bytes) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "File content does not match its declared type {}", metadata.file_type
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Updates only renew or clear a file's expiry; everything else stays as uploaded
+fn validate_file_metadata_update(
+    metadata: FileMetadata,
+    original_action_hash: ActionHash,
+    author: AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let original: FileMetadata = must_get_valid_record(original_action_hash)?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Updated action is not a file".into())))?;
+    if original.author != author {
+        return Ok(ValidateCallbackResult::Invalid("Only the author can update their file".into()));
+    }
+    if (FileMetadata { expires_at: original.expires_at, ..metadata.clone() }) != original {
+        return Ok(ValidateCallbackResult::Invalid("Only a file's expiry can be changed".into()));
+    }
+    if metadata.expires_at.is_some_and(|expires_at| expires_at <= metadata.created_at) {
+        return Ok(ValidateCallbackResult::Invalid("File expiry must be after its creation".into()));
+    }
     Ok(ValidateCallbackResult::Valid)
 }
 
+fn check_upload_policy(policy: &UploadPolicy, file_type: &str, size: u32) -> Option<ValidateCallbackResult> {
+    if file_type != normalize_file_type(file_type) {
+        return Some(ValidateCallbackResult::Invalid("File type must be a lowercase MIME type without parameters".into()));
+    }
+    if !policy.allows_type(file_type) {
+        return Some(ValidateCallbackResult::Invalid(format!(
+            "Files of type {} are not allowed in this neighborhood", file_type
+        )));
+    }
+    if size > policy.max_file_size {
+        return Some(ValidateCallbackResult::Invalid(format!(
+            "File cannot exceed {} bytes", policy.max_file_size
+        )));
+    }
+    None
+}
+
 fn validate_file_chunk(chunk: FileChunk) -> ExternResult<ValidateCallbackResult> {
     if chunk.data.is_empty() {
         return Ok(ValidateCallbackResult::Invalid("Chunk data cannot be empty".into()));
@@ -114,5 +473,178 @@ fn validate_file_chunk(chunk: FileChunk) -> ExternResult<ValidateCallbackResult>
             "Chunk size cannot exceed {} bytes", MAX_CHUNK_SIZE
         )));
     }
+    // Compressed chunks must inflate within the chunk limit, so a small
+    // chunk can't expand into something huge on read
+    if let Err(e) = chunk_content(&chunk) {
+        return Ok(ValidateCallbackResult::Invalid(e));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_upload_session(session: UploadSession) -> ExternResult<ValidateCallbackResult> {
+    if session.name.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("File name cannot be empty".into()));
+    }
+    if session.name.len() > MAX_FILE_NAME_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "File name cannot exceed {} characters", MAX_FILE_NAME_LENGTH
+        )));
+    }
+    if session.file_type.len() > MAX_FILE_TYPE_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "File type cannot exceed {} characters", MAX_FILE_TYPE_LENGTH
+        )));
+    }
+    if !is_sha256_hex(&session.sha256) {
+        return Ok(ValidateCallbackResult::Invalid("SHA-256 must be 64 hex characters".into()));
+    }
+    if session.size == 0 {
+        return Ok(ValidateCallbackResult::Invalid("File cannot be empty".into()));
+    }
+    if let Some(invalid) = check_upload_policy(&upload_policy()?, &session.file_type, session.size) {
+        return Ok(invalid);
+    }
+    if session.chunk_size == 0 || session.chunk_size as usize > MAX_CHUNK_SIZE {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Chunk size must be between 1 and {} bytes", MAX_CHUNK_SIZE
+        )));
+    }
+    let expected_chunks = (session.size as u64).div_ceil(session.chunk_size as u64);
+    if session.total_chunks as u64 != expected_chunks {
+        return Ok(ValidateCallbackResult::Invalid("Chunk count does not match file size".into()));
+    }
+    if session.total_chunks as usize > MAX_FILE_CHUNKS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "File cannot exceed {} chunks", MAX_FILE_CHUNKS
+        )));
+    }
+    if session.expires_at <= session.started_at {
+        return Ok(ValidateCallbackResult::Invalid("Session expiry must be after start".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn is_sha256_hex(hash: &str) -> bool {
+    hash.len() == 64 && hash.chars().all(|c| matches!(c, '0'..='9' | 'a'..='f'))
+}
+
+fn validate_file_collection(collection: FileCollection, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if collection.name.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Collection name cannot be empty".into()));
+    }
+    if collection.name.len() > MAX_COLLECTION_NAME_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Collection name cannot exceed {} characters", MAX_COLLECTION_NAME_LENGTH
+        )));
+    }
+    if let Some(description) = &collection.description {
+        if description.len() > MAX_COLLECTION_DESCRIPTION_LENGTH {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Collection description cannot exceed {} characters", MAX_COLLECTION_DESCRIPTION_LENGTH
+            )));
+        }
+    }
+    if !collection.managers.contains(&author) {
+        return Ok(ValidateCallbackResult::Invalid("Collection creator must be a manager".into()));
+    }
+    if collection.managers.len() > MAX_COLLECTION_MANAGERS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Collection cannot have more than {} managers", MAX_COLLECTION_MANAGERS
+        )));
+    }
+
+    // Nesting: only managers of the parent may create sub-collections, up to the depth limit
+    if let Some(parent_hash) = collection.parent {
+        let parent = must_get_collection(parent_hash)?;
+        if !parent.managers.contains(&author) {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Only managers of the parent collection can nest collections in it".into(),
+            ));
+        }
+        let mut depth = 2;
+        let mut ancestor = parent.parent;
+        while let Some(ancestor_hash) = ancestor {
+            depth += 1;
+            if depth > MAX_COLLECTION_DEPTH {
+                break;
+            }
+            ancestor = must_get_collection(ancestor_hash)?.parent;
+        }
+        if depth > MAX_COLLECTION_DEPTH {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Collections cannot be nested more than {} levels deep", MAX_COLLECTION_DEPTH
+            )));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_collection_manager(base_address: AnyLinkableHash, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    let Some(collection_hash) = base_address.into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("Collection link base must be an action hash".into()));
+    };
+    let collection = must_get_collection(collection_hash)?;
+    if !collection.managers.contains(author) {
+        return Ok(ValidateCallbackResult::Invalid("Only collection managers can change its contents".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_collection_link_target(link_type: LinkTypes, target_address: AnyLinkableHash) -> ExternResult<ValidateCallbackResult> {
+    let Some(target_hash) = target_address.into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("Collection link target must be an action hash".into()));
+    };
+    let record = must_get_valid_record(target_hash)?;
+    // A deserialization failure just means the target is some other entry type
+    let valid = match link_type {
+        LinkTypes::CollectionToFiles => matches!(record.entry().to_app_option::<FileMetadata>(), Ok(Some(_))),
+        _ => matches!(record.entry().to_app_option::<FileCollection>(), Ok(Some(_))),
+    };
+    if !valid {
+        return Ok(ValidateCallbackResult::Invalid("Collection link target has the wrong entry type".into()));
+    }
     Ok(ValidateCallbackResult::Valid)
 }
+
+fn validate_file_access(access: FileAccess, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if access.accessor != author {
+        return Ok(ValidateCallbackResult::Invalid("File access must be recorded by the accessor".into()));
+    }
+    let file = must_get_valid_record(access.file)?
+        .entry()
+        .to_app_option::<FileMetadata>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Accessed action is not a file".into())))?;
+    if !file.log_access {
+        return Ok(ValidateCallbackResult::Invalid("File does not log access".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_file_access_link(
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(access_hash) = target_address.into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("File access link target must be an action hash".into()));
+    };
+    let Ok(Some(access)) = must_get_valid_record(access_hash)?.entry().to_app_option::<FileAccess>() else {
+        return Ok(ValidateCallbackResult::Invalid("File access link target must be a file access".into()));
+    };
+    if base_address.into_action_hash().as_ref() != Some(&access.file) {
+        return Ok(ValidateCallbackResult::Invalid("File access must be linked from the accessed file".into()));
+    }
+    if &access.accessor != author {
+        return Ok(ValidateCallbackResult::Invalid("Only the accessor can link a file access".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn must_get_collection(collection_hash: ActionHash) -> ExternResult<FileCollection> {
+    must_get_valid_record(collection_hash)?
+        .entry()
+        .to_app_option::<FileCollection>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Referenced action is not a file collection".into())))
+}
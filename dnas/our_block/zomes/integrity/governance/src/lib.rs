@@ -0,0 +1,302 @@
+use hdi::prelude::*;
+use membership_proof::require_membership_proof;
+
+/// A binding question put to the neighborhood
+///
+/// Only verified members may propose or vote, each member votes once, and
+/// the outcome only counts once two trusted anchors certify the tally in a
+/// PollResult.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct Poll {
+    pub title: String,
+    pub description: String,
+    pub options: Vec<String>,
+    pub created_at: Timestamp,
+    /// Votes are accepted until this time; results only after it
+    pub closes_at: Timestamp,
+}
+
+/// One member's vote; the voter is the author
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct Vote {
+    pub poll: ActionHash,
+    /// Index into the poll's options
+    pub option: u32,
+}
+
+/// An anchor's signature over a closing tally (see `result_payload`)
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AnchorCertification {
+    pub anchor: AgentPubKey,
+    /// The anchor's TrustedAnchor record in the vouch zome
+    pub anchor_record: ActionHash,
+    pub signature: Signature,
+}
+
+/// The certified outcome of a closed poll
+///
+/// Committed by one of the certifying anchors. Validation recounts `votes`,
+/// so the tallies can't disagree with the votes they claim to count.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct PollResult {
+    pub poll: ActionHash,
+    /// Votes per option, in the poll's option order
+    pub tallies: Vec<u32>,
+    /// Every vote counted
+    pub votes: Vec<ActionHash>,
+    pub certifications: Vec<AnchorCertification>,
+    pub certified_at: Timestamp,
+}
+
+/// Mirror of the vouch zome's TrustedAnchor, for checking certifiers
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct TrustedAnchor {
+    pub agent: AgentPubKey,
+    pub created_at: Timestamp,
+    #[serde(default)]
+    pub nomination: Option<ActionHash>,
+}
+
+/// Bytes each certifying anchor signs for a poll's closing tally
+pub fn result_payload(poll: &ActionHash, tallies: &[u32], votes: &[ActionHash]) -> Vec<u8> {
+    let mut payload = b"OURBLOCK_POLL_RESULT".to_vec();
+    payload.extend_from_slice(poll.get_raw_39());
+    for tally in tallies {
+        payload.extend_from_slice(&tally.to_le_bytes());
+    }
+    for vote in votes {
+        payload.extend_from_slice(vote.get_raw_39());
+    }
+    payload
+}
+
+pub const MIN_TITLE_LENGTH: usize = 3;
+pub const MAX_TITLE_LENGTH: usize = 200;
+pub const MAX_DESCRIPTION_LENGTH: usize = 5000;
+pub const MIN_POLL_OPTIONS: usize = 2;
+pub const MAX_POLL_OPTIONS: usize = 10;
+pub const MAX_OPTION_LENGTH: usize = 200;
+pub const MAX_POLL_DURATION_SECS: i64 = 60 * 24 * 60 * 60;
+/// Distinct anchors that must certify a result
+pub const REQUIRED_CERTIFICATIONS: usize = 2;
+
+#[hdk_link_types]
+pub enum LinkTypes {
+    /// All polls path to every poll
+    AllPolls,
+    PollToVotes,
+    PollToResults,
+    /// Decisions path to every certified result
+    AllDecisions,
+}
+
+#[hdk_entry_types]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+    #[entry_type(name = "poll", visibility = "public")]
+    Poll(Poll),
+    #[entry_type(name = "vote", visibility = "public")]
+    Vote(Vote),
+    #[entry_type(name = "poll_result", visibility = "public")]
+    PollResult(PollResult),
+}
+
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    match op.flattened::<EntryTypes, LinkTypes>()? {
+        FlatOp::StoreEntry(store_entry) => match store_entry {
+            OpEntry::CreateEntry { app_entry, action } => match app_entry {
+                EntryTypes::Poll(poll) => validate_poll(poll),
+                EntryTypes::Vote(vote) => validate_vote(vote, action.timestamp),
+                EntryTypes::PollResult(result) => validate_result(result, action.author.clone(), action.timestamp),
+            },
+            OpEntry::UpdateEntry { .. } => Ok(ValidateCallbackResult::Invalid(
+                "Polls, votes, and results cannot be changed".into(),
+            )),
+            _ => Ok(ValidateCallbackResult::Valid),
+        },
+        FlatOp::StoreRecord(store_record) => match store_record {
+            OpRecord::CreateEntry { app_entry, action } => match app_entry {
+                EntryTypes::Poll(poll) => validate_poll(poll),
+                EntryTypes::Vote(vote) => validate_vote(vote, action.timestamp),
+                EntryTypes::PollResult(result) => validate_result(result, action.author.clone(), action.timestamp),
+            },
+            OpRecord::UpdateEntry { .. } => Ok(ValidateCallbackResult::Invalid(
+                "Polls, votes, and results cannot be changed".into(),
+            )),
+            OpRecord::DeleteEntry { .. } => Ok(ValidateCallbackResult::Invalid(
+                "Polls, votes, and results cannot be deleted".into(),
+            )),
+            _ => Ok(ValidateCallbackResult::Valid),
+        },
+        // Only verified members may propose or vote; checked against the author's own chain
+        FlatOp::RegisterAgentActivity(OpActivity::CreateEntry {
+            app_entry_type: Some(UnitEntryTypes::Poll),
+            action,
+        }) => require_membership_proof(&action.author, &action.prev_action),
+        FlatOp::RegisterAgentActivity(OpActivity::CreateEntry {
+            app_entry_type: Some(UnitEntryTypes::Vote),
+            action,
+        }) => validate_vote_activity(action),
+        FlatOp::RegisterDeleteLink { .. } => Ok(ValidateCallbackResult::Invalid(
+            "Governance records cannot be unlinked".into(),
+        )),
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
+fn validate_poll(poll: Poll) -> ExternResult<ValidateCallbackResult> {
+    if poll.title.trim().len() < MIN_TITLE_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Title must be at least {} characters", MIN_TITLE_LENGTH
+        )));
+    }
+    if poll.title.len() > MAX_TITLE_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Title cannot exceed {} characters", MAX_TITLE_LENGTH
+        )));
+    }
+    if poll.description.len() > MAX_DESCRIPTION_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Description cannot exceed {} characters", MAX_DESCRIPTION_LENGTH
+        )));
+    }
+    if poll.options.len() < MIN_POLL_OPTIONS || poll.options.len() > MAX_POLL_OPTIONS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Polls need between {} and {} options", MIN_POLL_OPTIONS, MAX_POLL_OPTIONS
+        )));
+    }
+    for (i, option) in poll.options.iter().enumerate() {
+        if option.trim().is_empty() || option.len() > MAX_OPTION_LENGTH {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Options must be 1 to {} characters", MAX_OPTION_LENGTH
+            )));
+        }
+        if poll.options[..i].contains(option) {
+            return Ok(ValidateCallbackResult::Invalid("Options cannot repeat".into()));
+        }
+    }
+    let duration = poll.closes_at.as_micros() - poll.created_at.as_micros();
+    if duration <= 0 || duration > MAX_POLL_DURATION_SECS * 1_000_000 {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Polls must close within {} days", MAX_POLL_DURATION_SECS / (24 * 60 * 60)
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_vote(vote: Vote, timestamp: Timestamp) -> ExternResult<ValidateCallbackResult> {
+    let poll_record = must_get_valid_record(vote.poll)?;
+    let Ok(Some(poll)) = poll_record.entry().to_app_option::<Poll>() else {
+        return Ok(ValidateCallbackResult::Invalid("Votes must be for a poll".into()));
+    };
+    if vote.option as usize >= poll.options.len() {
+        return Ok(ValidateCallbackResult::Invalid("No such option".into()));
+    }
+    if timestamp >= poll.closes_at {
+        return Ok(ValidateCallbackResult::Invalid("This poll has closed".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Voters must be verified and may vote once per poll
+///
+/// Checked on the voter's chain, where every earlier vote is visible.
+fn validate_vote_activity(action: Create) -> ExternResult<ValidateCallbackResult> {
+    let membership = require_membership_proof(&action.author, &action.prev_action)?;
+    if !matches!(membership, ValidateCallbackResult::Valid) {
+        return Ok(membership);
+    }
+
+    let Some(vote) = vote_entry(&action.entry_hash)? else {
+        return Ok(ValidateCallbackResult::Invalid("Not a vote".into()));
+    };
+    let vote_type: EntryType = UnitEntryTypes::Vote.try_into()?;
+    let activity = must_get_agent_activity(action.author.clone(), ChainFilter::new(action.prev_action.clone()))?;
+    for item in activity {
+        let earlier = item.action.action();
+        if !matches!(earlier, Action::Create(_)) || earlier.entry_type() != Some(&vote_type) {
+            continue;
+        }
+        let Some(entry_hash) = earlier.entry_hash() else {
+            continue;
+        };
+        if vote_entry(entry_hash)?.is_some_and(|earlier_vote| earlier_vote.poll == vote.poll) {
+            return Ok(ValidateCallbackResult::Invalid("You have already voted in this poll".into()));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn vote_entry(entry_hash: &EntryHash) -> ExternResult<Option<Vote>> {
+    let entry = must_get_entry(entry_hash.clone())?;
+    Ok(Vote::try_from(entry.content).ok())
+}
+
+fn validate_result(
+    result: PollResult,
+    author: AgentPubKey,
+    timestamp: Timestamp,
+) -> ExternResult<ValidateCallbackResult> {
+    let poll_record = must_get_valid_record(result.poll.clone())?;
+    let Ok(Some(poll)) = poll_record.entry().to_app_option::<Poll>() else {
+        return Ok(ValidateCallbackResult::Invalid("Results must be for a poll".into()));
+    };
+    if timestamp < poll.closes_at {
+        return Ok(ValidateCallbackResult::Invalid("Results can only be certified after the poll closes".into()));
+    }
+
+    // Recount the votes
+    let mut tallies = vec![0u32; poll.options.len()];
+    let mut voters = Vec::new();
+    for vote_hash in &result.votes {
+        let vote_record = must_get_valid_record(vote_hash.clone())?;
+        let Ok(Some(vote)) = vote_record.entry().to_app_option::<Vote>() else {
+            return Ok(ValidateCallbackResult::Invalid("Counted votes must be votes".into()));
+        };
+        if vote.poll != result.poll {
+            return Ok(ValidateCallbackResult::Invalid("Counted votes must be for this poll".into()));
+        }
+        let voter = vote_record.action().author().clone();
+        if voters.contains(&voter) {
+            return Ok(ValidateCallbackResult::Invalid("Each member's vote can only count once".into()));
+        }
+        voters.push(voter);
+        tallies[vote.option as usize] += 1;
+    }
+    if tallies != result.tallies {
+        return Ok(ValidateCallbackResult::Invalid("Tallies do not match the counted votes".into()));
+    }
+
+    // Two distinct anchors must sign the tally, and one of them commits it
+    let mut certifiers = Vec::new();
+    let payload = result_payload(&result.poll, &result.tallies, &result.votes);
+    for certification in result.certifications {
+        if certifiers.contains(&certification.anchor) {
+            return Ok(ValidateCallbackResult::Invalid("Each anchor can only certify once".into()));
+        }
+        let anchor_record = must_get_valid_record(certification.anchor_record)?;
+        let Ok(Some(anchor)) = anchor_record.entry().to_app_option::<TrustedAnchor>() else {
+            return Ok(ValidateCallbackResult::Invalid("Certifiers must reference a trusted anchor".into()));
+        };
+        if anchor.agent != certification.anchor || anchor_record.action().author() != &certification.anchor {
+            return Ok(ValidateCallbackResult::Invalid("Only trusted anchors can certify results".into()));
+        }
+        if !verify_signature(certification.anchor.clone(), certification.signature, payload.clone())? {
+            return Ok(ValidateCallbackResult::Invalid("Certification signature does not match the anchor".into()));
+        }
+        certifiers.push(certification.anchor);
+    }
+    if certifiers.len() < REQUIRED_CERTIFICATIONS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Results must be certified by {} anchors", REQUIRED_CERTIFICATIONS
+        )));
+    }
+    if !certifiers.contains(&author) {
+        return Ok(ValidateCallbackResult::Invalid("Results must be committed by a certifying anchor".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
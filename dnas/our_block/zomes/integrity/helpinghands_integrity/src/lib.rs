@@ -1,4 +1,5 @@
 use hdi::prelude::*;
+pub use location::{validate_location, Location};
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type")]
@@ -23,6 +24,26 @@ pub enum Urgency {
     Emergency,
 }
 
+impl Urgency {
+    /// The next level up, if any; escalation moves one level at a time
+    pub fn next(&self) -> Option<Urgency> {
+        match self {
+            Urgency::Low => Some(Urgency::High),
+            Urgency::High => Some(Urgency::Emergency),
+            Urgency::Emergency => None,
+        }
+    }
+}
+
+/// Where a request stands; derived from the request and its acceptance
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum RequestStatus {
+    Open,
+    InProgress,
+    Fulfilled,
+}
+
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq, Eq)]
 pub struct Request {
@@ -33,6 +54,10 @@ pub struct Request {
     pub author: AgentPubKey,
     pub created_at: Timestamp,
     pub is_fulfilled: bool,
+    /// Where help is needed. Skipped when None so the placeholder Request
+    /// the all-requests anchor is hashed from keeps its hash.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub place: Option<Location>,
 }
 
 pub const MIN_TITLE_LENGTH: usize = 3;
@@ -51,11 +76,93 @@ pub struct Comment {
 
 pub const MAX_COMMENT_LENGTH: usize = 1000;
 
+/// A request's urgency being raised by its author
+///
+/// Escalations form a chain through `previous`, so each step can be checked
+/// against the level it raised from.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct Escalation {
+    pub request_hash: ActionHash,
+    /// The escalation this one follows; None for the first
+    pub previous: Option<ActionHash>,
+    pub from: Urgency,
+    pub to: Urgency,
+    pub note: Option<String>,
+    pub created_at: Timestamp,
+}
+
+pub const MAX_ESCALATION_NOTE_LENGTH: usize = 500;
+
+/// A request's author choosing one of the offers made on it
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct OfferAcceptance {
+    pub request_hash: ActionHash,
+    /// The comment the offer was made in
+    pub offer_hash: ActionHash,
+    pub helper: AgentPubKey,
+    /// When the helper and author agreed to meet
+    pub scheduled_for: Option<Timestamp>,
+    pub created_at: Timestamp,
+}
+
+/// A weekly slot when a helper is usually free
+///
+/// Times are minutes after midnight UTC; the UI converts from local time and
+/// splits slots that cross midnight.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct WeeklyWindow {
+    /// 0 is Monday
+    pub day: u8,
+    pub start_minute: u16,
+    /// Exclusive
+    pub end_minute: u16,
+}
+
+impl WeeklyWindow {
+    pub fn covers(&self, at: Timestamp) -> bool {
+        let micros = at.as_micros();
+        // 1970-01-01 was a Thursday
+        let day = (micros.div_euclid(DAY_MICROS) + 3).rem_euclid(7);
+        let minute = micros.rem_euclid(DAY_MICROS) / MINUTE_MICROS;
+        day == i64::from(self.day) && (i64::from(self.start_minute)..i64::from(self.end_minute)).contains(&minute)
+    }
+}
+
+/// When a neighbor is usually free to help; each change is a new entry and
+/// the latest one wins
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct HelperAvailability {
+    pub helper: AgentPubKey,
+    pub windows: Vec<WeeklyWindow>,
+    pub updated_at: Timestamp,
+}
+
+impl HelperAvailability {
+    pub fn covers(&self, at: Timestamp) -> bool {
+        self.windows.iter().any(|window| window.covers(at))
+    }
+}
+
+pub const MAX_WEEKLY_WINDOWS: usize = 28;
+pub const MINUTES_PER_DAY: u16 = 24 * 60;
+const MINUTE_MICROS: i64 = 60 * 1_000_000;
+const DAY_MICROS: i64 = MINUTES_PER_DAY as i64 * MINUTE_MICROS;
+
 #[hdk_link_types]
 pub enum LinkTypes {
     AllRequests,
     RequestToComments,
     AgentToRequests,
+    RequestToEscalations,
+    RequestToAcceptance,
+    HelperToAcceptances,
+    /// Helper to each revision of their availability
+    HelperToAvailability,
+    /// Directory anchor to every helper who has set their availability
+    AvailableHelpers,
 }
 
 #[hdk_entry_types]
@@ -65,6 +172,12 @@ pub enum EntryTypes {
     Request(Request),
     #[entry_type(name = "comment", visibility = "public")]
     Comment(Comment),
+    #[entry_type(name = "escalation", visibility = "public")]
+    Escalation(Escalation),
+    #[entry_type(name = "offer_acceptance", visibility = "public")]
+    OfferAcceptance(OfferAcceptance),
+    #[entry_type(name = "helper_availability", visibility = "public")]
+    HelperAvailability(HelperAvailability),
 }
 
 #[hdk_extern]
@@ -74,10 +187,20 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             OpEntry::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::Request(req) => validate_request(req, action.author.clone()),
                 EntryTypes::Comment(comment) => validate_comment(comment, action.author.clone()),
+                EntryTypes::Escalation(escalation) => validate_escalation(escalation, action.author.clone()),
+                EntryTypes::OfferAcceptance(acceptance) => validate_offer_acceptance(acceptance, action.author.clone()),
+                EntryTypes::HelperAvailability(availability) => {
+                    validate_helper_availability(availability, action.author.clone())
+                }
             },
             OpEntry::UpdateEntry { app_entry, action, .. } => match app_entry {
                 EntryTypes::Request(req) => validate_request(req, action.author.clone()),
                 EntryTypes::Comment(_) => Ok(ValidateCallbackResult::Invalid("Comments cannot be updated".into())),
+                EntryTypes::Escalation(_) => Ok(ValidateCallbackResult::Invalid("Escalations cannot be updated".into())),
+                EntryTypes::OfferAcceptance(_) => Ok(ValidateCallbackResult::Invalid("Accepted offers cannot be updated".into())),
+                EntryTypes::HelperAvailability(_) => {
+                    Ok(ValidateCallbackResult::Invalid("Availability is replaced, not updated".into()))
+                }
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
@@ -85,13 +208,35 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             OpRecord::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::Request(req) => validate_request(req, action.author.clone()),
                 EntryTypes::Comment(comment) => validate_comment(comment, action.author.clone()),
+                EntryTypes::Escalation(escalation) => validate_escalation(escalation, action.author.clone()),
+                EntryTypes::OfferAcceptance(acceptance) => validate_offer_acceptance(acceptance, action.author.clone()),
+                EntryTypes::HelperAvailability(availability) => {
+                    validate_helper_availability(availability, action.author.clone())
+                }
             },
             OpRecord::UpdateEntry { app_entry, action, .. } => match app_entry {
                 EntryTypes::Request(req) => validate_request(req, action.author.clone()),
                 EntryTypes::Comment(_) => Ok(ValidateCallbackResult::Invalid("Comments cannot be updated".into())),
+                EntryTypes::Escalation(_) => Ok(ValidateCallbackResult::Invalid("Escalations cannot be updated".into())),
+                EntryTypes::OfferAcceptance(_) => Ok(ValidateCallbackResult::Invalid("Accepted offers cannot be updated".into())),
+                EntryTypes::HelperAvailability(_) => {
+                    Ok(ValidateCallbackResult::Invalid("Availability is replaced, not updated".into()))
+                }
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
+        FlatOp::RegisterCreateLink { link_type: LinkTypes::HelperToAvailability, base_address, action, .. } => {
+            if base_address.into_agent_pub_key().as_ref() != Some(&action.author) {
+                return Ok(ValidateCallbackResult::Invalid("Helpers can only link their own availability".into()));
+            }
+            Ok(ValidateCallbackResult::Valid)
+        }
+        FlatOp::RegisterCreateLink { link_type: LinkTypes::AvailableHelpers, target_address, action, .. } => {
+            if target_address.into_agent_pub_key().as_ref() != Some(&action.author) {
+                return Ok(ValidateCallbackResult::Invalid("Helpers can only list themselves".into()));
+            }
+            Ok(ValidateCallbackResult::Valid)
+        }
         _ => Ok(ValidateCallbackResult::Valid),
     }
 }
@@ -112,6 +257,11 @@ fn validate_request(req: Request, author: AgentPubKey) -> ExternResult<ValidateC
     if req.author != author {
         return Ok(ValidateCallbackResult::Invalid("Request author must match action author".into()));
     }
+    if let Some(ref place) = req.place {
+        if let Err(message) = validate_location(place) {
+            return Ok(ValidateCallbackResult::Invalid(message));
+        }
+    }
     Ok(ValidateCallbackResult::Valid)
 }
 
@@ -127,3 +277,94 @@ fn validate_comment(comment: Comment, author: AgentPubKey) -> ExternResult<Valid
     }
     Ok(ValidateCallbackResult::Valid)
 }
+
+fn validate_escalation(escalation: Escalation, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    let request_record = must_get_valid_record(escalation.request_hash.clone())?;
+    let Ok(Some(request)) = request_record.entry().to_app_option::<Request>() else {
+        return Ok(ValidateCallbackResult::Invalid("Escalation must reference a request".into()));
+    };
+    if request.author != author {
+        return Ok(ValidateCallbackResult::Invalid("Only the request author can escalate it".into()));
+    }
+
+    let current = match escalation.previous {
+        None => request.urgency,
+        Some(previous_hash) => {
+            let previous_record = must_get_valid_record(previous_hash)?;
+            let Ok(Some(previous)) = previous_record.entry().to_app_option::<Escalation>() else {
+                return Ok(ValidateCallbackResult::Invalid("Previous escalation not found".into()));
+            };
+            if previous.request_hash != escalation.request_hash {
+                return Ok(ValidateCallbackResult::Invalid("Previous escalation is for another request".into()));
+            }
+            previous.to
+        }
+    };
+    if escalation.from != current {
+        return Ok(ValidateCallbackResult::Invalid("Escalation must start from the current urgency".into()));
+    }
+    if escalation.from.next() != Some(escalation.to) {
+        return Ok(ValidateCallbackResult::Invalid("Urgency can only be raised one level at a time".into()));
+    }
+    if let Some(ref note) = escalation.note {
+        if note.len() > MAX_ESCALATION_NOTE_LENGTH {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Escalation note cannot exceed {} chars", MAX_ESCALATION_NOTE_LENGTH
+            )));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_offer_acceptance(acceptance: OfferAcceptance, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    let request_record = must_get_valid_record(acceptance.request_hash.clone())?;
+    let Ok(Some(request)) = request_record.entry().to_app_option::<Request>() else {
+        return Ok(ValidateCallbackResult::Invalid("Accepted offer must reference a request".into()));
+    };
+    if request.author != author {
+        return Ok(ValidateCallbackResult::Invalid("Only the request author can accept an offer".into()));
+    }
+
+    let offer_record = must_get_valid_record(acceptance.offer_hash)?;
+    let Ok(Some(offer)) = offer_record.entry().to_app_option::<Comment>() else {
+        return Ok(ValidateCallbackResult::Invalid("Accepted offer must reference a comment".into()));
+    };
+    if !offer.is_offer {
+        return Ok(ValidateCallbackResult::Invalid("Only offers can be accepted".into()));
+    }
+    if offer.request_hash != acceptance.request_hash {
+        return Ok(ValidateCallbackResult::Invalid("Offer was made on another request".into()));
+    }
+    if offer.author != acceptance.helper {
+        return Ok(ValidateCallbackResult::Invalid("Helper must be the offer's author".into()));
+    }
+    if acceptance.helper == author {
+        return Ok(ValidateCallbackResult::Invalid("Cannot accept your own offer".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_helper_availability(
+    availability: HelperAvailability,
+    author: AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    if availability.helper != author {
+        return Ok(ValidateCallbackResult::Invalid("Helpers can only set their own availability".into()));
+    }
+    if availability.windows.len() > MAX_WEEKLY_WINDOWS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Cannot have more than {} availability windows", MAX_WEEKLY_WINDOWS
+        )));
+    }
+    for window in &availability.windows {
+        if window.day > 6 {
+            return Ok(ValidateCallbackResult::Invalid("Day must be 0 (Monday) to 6 (Sunday)".into()));
+        }
+        if window.start_minute >= window.end_minute || window.end_minute > MINUTES_PER_DAY {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Availability windows must start before they end, within one day".into(),
+            ));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -37,6 +37,24 @@ pub struct Profile {
     pub bio: Option<String>,
     pub avatar_url: Option<String>,
     pub location_metadata: Option<String>,
+    /// When the profile was first created; preserved across updates
+    pub created_at: Timestamp,
+    #[serde(default)]
+    pub pronouns: Option<String>,
+    /// The nickname written in other scripts, at most one per script
+    #[serde(default)]
+    pub display_name_variants: Vec<DisplayNameVariant>,
+    /// Set when the agent deactivates their account, e.g. on moving away
+    #[serde(default)]
+    pub deactivated_at: Option<Timestamp>,
+}
+
+/// A nickname as written in one script, e.g. `Jpan` for a Japanese spelling
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DisplayNameVariant {
+    /// ISO 15924 code: four ASCII letters, first uppercase (`Cyrl`, `Hani`)
+    pub script: String,
+    pub name: String,
 }
 
 /// Revocation record for blacklisting malicious agents
@@ -54,11 +72,157 @@ pub struct RevocationAnchor {
     pub revoker: AgentPubKey,
 }
 
+/// Achievements earned in other zomes
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BadgeKind {
+    /// Vouched for into the neighborhood
+    Verified,
+    /// Returned borrowed tools on time
+    ReliableBorrower,
+    /// Had offers of help accepted
+    Helper,
+    /// Hosted neighborhood events
+    EventHost,
+}
+
+impl BadgeKind {
+    /// Integrity zome whose records prove the badge was earned
+    pub fn issuing_zome(&self) -> &'static str {
+        match self {
+            BadgeKind::Verified => "vouch_integrity",
+            BadgeKind::ReliableBorrower => "toolshed_integrity",
+            BadgeKind::Helper => "helpinghands_integrity",
+            BadgeKind::EventHost => "events_integrity",
+        }
+    }
+
+    /// How many distinct evidence records the badge needs
+    pub fn evidence_required(&self) -> usize {
+        match self {
+            BadgeKind::Verified => 1,
+            BadgeKind::ReliableBorrower => 5,
+            BadgeKind::Helper => 5,
+            BadgeKind::EventHost => 3,
+        }
+    }
+}
+
+/// A badge granted to an agent by another zome's coordinator
+///
+/// Whoever commits it, it is only valid if every evidence record comes from
+/// the kind's issuing zome and shows `agent` earning it, so badges can't be
+/// granted from the UI or by another zome.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct Badge {
+    pub agent: AgentPubKey,
+    pub kind: BadgeKind,
+    pub evidence: Vec<ActionHash>,
+    pub granted_at: Timestamp,
+}
+
+/// A short, expiring note shown next to an agent's name
+///
+/// Kept separate from the profile so neighbors can post "on vacation until
+/// July 3" without touching their bio. The newest status linked from the
+/// agent is the current one.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct ProfileStatus {
+    pub emoji: Option<String>,
+    pub text: String,
+    /// When the status stops being shown; None keeps it until cleared
+    pub expires_at: Option<Timestamp>,
+    pub set_at: Timestamp,
+}
+
+impl ProfileStatus {
+    pub fn is_active(&self, now: Timestamp) -> bool {
+        self.expires_at.map_or(true, |expires_at| expires_at > now)
+    }
+}
+
+/// Milestones a new neighbor works through, in the order the UI suggests them
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OnboardingStep {
+    ProfileCreated,
+    FirstVouchReceived,
+    Verified,
+    JoinedFirstEvent,
+}
+
+pub const ONBOARDING_STEPS: [OnboardingStep; 4] = [
+    OnboardingStep::ProfileCreated,
+    OnboardingStep::FirstVouchReceived,
+    OnboardingStep::Verified,
+    OnboardingStep::JoinedFirstEvent,
+];
+
+/// The onboarding steps an agent has completed; private, latest entry wins
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct ProfileOnboarding {
+    pub completed: Vec<OnboardingStep>,
+    pub updated_at: Timestamp,
+}
+
+/// Mirror of the vouch zome's Vouch
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+struct VouchEvidence {
+    vouchee: AgentPubKey,
+}
+
+/// Mirror of the toolshed zome's TransactionRecord
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+struct ReturnEvidence {
+    transaction_hash: ActionHash,
+    returned_at: Option<Timestamp>,
+    status: TransactionStatusEvidence,
+}
+
+/// Mirror of the toolshed zome's TransactionStatus
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(tag = "type")]
+enum TransactionStatusEvidence {
+    Pending,
+    AwaitingPickup,
+    Active,
+    AwaitingDropoff,
+    Returned,
+    Cancelled,
+}
+
+/// Mirror of the toolshed zome's Transaction
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+struct LoanEvidence {
+    borrower: AgentPubKey,
+    due_date: Timestamp,
+}
+
+/// Mirror of the helpinghands zome's OfferAcceptance
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+struct AcceptanceEvidence {
+    helper: AgentPubKey,
+}
+
+/// Mirror of the events zome's Event
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+struct HostedEventEvidence {
+    host: AgentPubKey,
+}
+
 pub const MIN_NICKNAME_LENGTH: usize = 2;
 pub const MAX_NICKNAME_LENGTH: usize = 50;
 pub const MAX_BIO_LENGTH: usize = 500;
 pub const MAX_AVATAR_URL_LENGTH: usize = 500;
 pub const MAX_LOCATION_METADATA_LENGTH: usize = 200;
+pub const MAX_PRONOUNS_LENGTH: usize = 40;
+pub const MAX_DISPLAY_NAME_VARIANTS: usize = 4;
+/// Display name variants are measured in characters, not bytes, so names in
+/// multi-byte scripts get the same room as Latin ones
+pub const MAX_DISPLAY_NAME_CHARS: usize = 50;
+pub const MAX_STATUS_TEXT_LENGTH: usize = 100;
+pub const MAX_STATUS_EMOJI_LENGTH: usize = 16;
 
 #[hdk_link_types]
 pub enum LinkTypes {
@@ -66,6 +230,8 @@ pub enum LinkTypes {
     AllProfiles,
     AllInvitations,
     RevokedAgents,  // Links from RevocationAnchor to revoked agents
+    AgentToBadges,
+    AgentToStatus,
 }
 
 #[hdk_entry_types]
@@ -77,6 +243,12 @@ pub enum EntryTypes {
     Invitation(Invitation),
     #[entry_type(name = "revocation_anchor", visibility = "public")]
     RevocationAnchor(RevocationAnchor),
+    #[entry_type(name = "badge", visibility = "public")]
+    Badge(Badge),
+    #[entry_type(name = "profile_status", visibility = "public")]
+    ProfileStatus(ProfileStatus),
+    #[entry_type(name = "profile_onboarding", visibility = "private")]
+    ProfileOnboarding(ProfileOnboarding),
 }
 
 #[hdk_extern]
@@ -95,8 +267,11 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 },
                 EntryTypes::Invitation(invitation) => validate_invitation(invitation),
                 EntryTypes::RevocationAnchor(revocation) => validate_revocation(revocation),
+                EntryTypes::Badge(badge) => validate_badge(badge),
+                EntryTypes::ProfileStatus(status) => validate_status(status),
+                EntryTypes::ProfileOnboarding(_) => Ok(ValidateCallbackResult::Valid),
             },
-            OpEntry::UpdateEntry { app_entry, action, .. } => match app_entry {
+            OpEntry::UpdateEntry { app_entry, action, original_entry_hash, .. } => match app_entry {
                 EntryTypes::Profile(profile) => {
                     // Check if author is revoked
                     if is_agent_revoked(&action.author)? {
@@ -104,10 +279,17 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                             "Agent has been revoked from this neighborhood".to_string()
                         ));
                     }
-                    validate_profile(profile)
+                    validate_profile_update(profile, original_entry_hash)
                 },
                 EntryTypes::Invitation(invitation) => validate_invitation(invitation),
                 EntryTypes::RevocationAnchor(revocation) => validate_revocation(revocation),
+                EntryTypes::Badge(_) => Ok(ValidateCallbackResult::Invalid("Badges cannot be updated".to_string())),
+                EntryTypes::ProfileStatus(_) => Ok(ValidateCallbackResult::Invalid(
+                    "Statuses cannot be updated; set a new one instead".to_string()
+                )),
+                EntryTypes::ProfileOnboarding(_) => Ok(ValidateCallbackResult::Invalid(
+                    "Onboarding state is replaced, not updated".to_string()
+                )),
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
@@ -124,8 +306,11 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 },
                 EntryTypes::Invitation(invitation) => validate_invitation(invitation),
                 EntryTypes::RevocationAnchor(revocation) => validate_revocation(revocation),
+                EntryTypes::Badge(badge) => validate_badge(badge),
+                EntryTypes::ProfileStatus(status) => validate_status(status),
+                EntryTypes::ProfileOnboarding(_) => Ok(ValidateCallbackResult::Valid),
             },
-            OpRecord::UpdateEntry { app_entry, action, .. } => match app_entry {
+            OpRecord::UpdateEntry { app_entry, action, original_entry_hash, .. } => match app_entry {
                 EntryTypes::Profile(profile) => {
                     // Check if author is revoked
                     if is_agent_revoked(&action.author)? {
@@ -133,10 +318,17 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                             "Agent has been revoked from this neighborhood".to_string()
                         ));
                     }
-                    validate_profile(profile)
+                    validate_profile_update(profile, original_entry_hash)
                 },
                 EntryTypes::Invitation(invitation) => validate_invitation(invitation),
                 EntryTypes::RevocationAnchor(revocation) => validate_revocation(revocation),
+                EntryTypes::Badge(_) => Ok(ValidateCallbackResult::Invalid("Badges cannot be updated".to_string())),
+                EntryTypes::ProfileStatus(_) => Ok(ValidateCallbackResult::Invalid(
+                    "Statuses cannot be updated; set a new one instead".to_string()
+                )),
+                EntryTypes::ProfileOnboarding(_) => Ok(ValidateCallbackResult::Invalid(
+                    "Onboarding state is replaced, not updated".to_string()
+                )),
             },
             OpRecord::CreateLink { base_address, target_address, tag, link_type, action } => {
                 // Check if author is revoked
@@ -145,6 +337,13 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                         "Agent has been revoked from this neighborhood".to_string()
                     ));
                 }
+                if link_type == LinkTypes::AgentToStatus
+                    && base_address.into_agent_pub_key().as_ref() != Some(&action.author)
+                {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "Agents can only set their own status".to_string()
+                    ));
+                }
                 Ok(ValidateCallbackResult::Valid)
             },
             _ => Ok(ValidateCallbackResult::Valid),
@@ -185,9 +384,63 @@ fn validate_profile(profile: Profile) -> ExternResult<ValidateCallbackResult> {
             )));
         }
     }
+    if let Some(ref pronouns) = profile.pronouns {
+        if pronouns.trim().is_empty()
+            || pronouns.len() > MAX_PRONOUNS_LENGTH
+            || pronouns.chars().any(char::is_control)
+        {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Pronouns must be 1-{} printable characters", MAX_PRONOUNS_LENGTH
+            )));
+        }
+    }
+    if profile.display_name_variants.len() > MAX_DISPLAY_NAME_VARIANTS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Cannot have more than {} display name variants", MAX_DISPLAY_NAME_VARIANTS
+        )));
+    }
+    for (index, variant) in profile.display_name_variants.iter().enumerate() {
+        if !is_script_code(&variant.script) {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "'{}' is not an ISO 15924 script code", variant.script
+            )));
+        }
+        if profile.display_name_variants[..index].iter().any(|other| other.script == variant.script) {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Only one display name per script; {} is repeated", variant.script
+            )));
+        }
+        let chars = variant.name.chars().count();
+        if variant.name.trim().is_empty()
+            || chars > MAX_DISPLAY_NAME_CHARS
+            || variant.name.chars().any(char::is_control)
+        {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Display names must be 1-{} printable characters", MAX_DISPLAY_NAME_CHARS
+            )));
+        }
+    }
     Ok(ValidateCallbackResult::Valid)
 }
 
+/// Four ASCII letters, the first uppercase and the rest lowercase
+fn is_script_code(code: &str) -> bool {
+    let bytes = code.as_bytes();
+    bytes.len() == 4
+        && bytes[0].is_ascii_uppercase()
+        && bytes[1..].iter().all(|byte| byte.is_ascii_lowercase())
+}
+
+fn validate_profile_update(profile: Profile, original_entry_hash: EntryHash) -> ExternResult<ValidateCallbackResult> {
+    let original: Profile = must_get_entry(original_entry_hash)?.content.try_into()?;
+    if profile.created_at != original.created_at {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Profile creation time cannot be changed".to_string()
+        ));
+    }
+    validate_profile(profile)
+}
+
 fn validate_invitation(invitation: Invitation) -> ExternResult<ValidateCallbackResult> {
     if invitation.neighbor_name.is_empty() {
         return Ok(ValidateCallbackResult::Invalid(
@@ -233,6 +486,108 @@ fn validate_revocation(revocation: RevocationAnchor) -> ExternResult<ValidateCal
     Ok(ValidateCallbackResult::Valid)
 }
 
+fn validate_badge(badge: Badge) -> ExternResult<ValidateCallbackResult> {
+    let required = badge.kind.evidence_required();
+    if badge.evidence.len() != required {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "{:?} badge needs exactly {} evidence records", badge.kind, required
+        )));
+    }
+    let mut seen = Vec::new();
+    for hash in &badge.evidence {
+        if seen.contains(hash) {
+            return Ok(ValidateCallbackResult::Invalid("Badge evidence cannot repeat".to_string()));
+        }
+        seen.push(hash.clone());
+    }
+
+    let zome_names = dna_info()?.zome_names;
+    let issuing_zome = ZomeName::from(badge.kind.issuing_zome());
+    let mut transactions = Vec::new();
+
+    for hash in badge.evidence {
+        let record = must_get_valid_record(hash)?;
+        let from_zome = match record.action().entry_type() {
+            Some(EntryType::App(def)) => zome_names.get(def.zome_index.0 as usize),
+            _ => None,
+        };
+        if from_zome != Some(&issuing_zome) {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "{:?} badge evidence must come from {}", badge.kind, issuing_zome
+            )));
+        }
+
+        let earned = match badge.kind {
+            BadgeKind::Verified => {
+                evidence_entry::<VouchEvidence>(&record)?.is_some_and(|vouch| vouch.vouchee == badge.agent)
+            }
+            BadgeKind::ReliableBorrower => match evidence_entry::<ReturnEvidence>(&record)? {
+                Some(ret) if ret.status == TransactionStatusEvidence::Returned => {
+                    if transactions.contains(&ret.transaction_hash) {
+                        return Ok(ValidateCallbackResult::Invalid(
+                            "Each on-time return must be for a different loan".to_string()
+                        ));
+                    }
+                    transactions.push(ret.transaction_hash.clone());
+                    let loan_record = must_get_valid_record(ret.transaction_hash)?;
+                    evidence_entry::<LoanEvidence>(&loan_record)?.is_some_and(|loan| {
+                        loan.borrower == badge.agent
+                            && ret.returned_at.is_some_and(|returned_at| returned_at <= loan.due_date)
+                    })
+                }
+                _ => false,
+            },
+            BadgeKind::Helper => {
+                evidence_entry::<AcceptanceEvidence>(&record)?.is_some_and(|acceptance| acceptance.helper == badge.agent)
+            }
+            BadgeKind::EventHost => {
+                // RSVPs update the event too; only the original create counts
+                matches!(record.action(), Action::Create(_))
+                    && evidence_entry::<HostedEventEvidence>(&record)?.is_some_and(|event| event.host == badge.agent)
+            }
+        };
+        if !earned {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Evidence does not show the agent earning a {:?} badge", badge.kind
+            )));
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn evidence_entry<T>(record: &Record) -> ExternResult<Option<T>>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    Ok(record.entry().to_app_option::<T>().ok().flatten())
+}
+
+fn validate_status(status: ProfileStatus) -> ExternResult<ValidateCallbackResult> {
+    let emoji = status.emoji.as_deref().unwrap_or("");
+    if status.text.trim().is_empty() && emoji.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Status needs text or an emoji".to_string()
+        ));
+    }
+    if status.text.len() > MAX_STATUS_TEXT_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Status text cannot exceed {} characters", MAX_STATUS_TEXT_LENGTH
+        )));
+    }
+    if emoji.len() > MAX_STATUS_EMOJI_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Status emoji cannot exceed {} bytes", MAX_STATUS_EMOJI_LENGTH
+        )));
+    }
+    if status.expires_at.is_some_and(|expires_at| expires_at <= status.set_at) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Status must expire after it is set".to_string()
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
 /// Check if an agent has been revoked from the neighborhood
 /// Returns true if agent is found in the revocation list
 fn is_agent_revoked(agent: &AgentPubKey) -> ExternResult<bool> {
@@ -256,6 +611,24 @@ pub fn genesis_self_check(data: GenesisSelfCheckData) -> ExternResult<ValidateCa
     // Get DNA properties
     let properties = dna_info()?.modifiers.properties;
     
+    // Without a founder nobody can ever be vouched for, so a neighborhood that
+    // requires vouching would be read-only for everyone; refuse to install it
+    let require_vouching = properties
+        .get("require_vouching")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    let founding_anchor = properties
+        .get("founding_anchor")
+        .and_then(|v| v.as_str())
+        .unwrap_or("");
+    if require_vouching && founding_anchor.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "This neighborhood requires vouching but has no founding_anchor DNA property; \
+             install it with the founder's agent key set"
+                .to_string(),
+        ));
+    }
+    
     // Check if this is a private neighborhood
     let private_neighborhood = properties
         .get("private_neighborhood")
@@ -0,0 +1,63 @@
+use hdi::prelude::*;
+
+/// The kinds of content the search index covers
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ContentKind {
+    Post,
+    Item,
+    Request,
+    Event,
+    Space,
+}
+
+/// Stored in each keyword link's tag so hits can be listed without fetching
+/// every record
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct SearchTag {
+    pub kind: ContentKind,
+    pub title: String,
+}
+
+/// Titles are truncated to this many characters before going into a tag
+pub const MAX_TAG_TITLE_LENGTH: usize = 100;
+
+#[hdk_link_types]
+pub enum LinkTypes {
+    /// Keyword path (`search.<keyword>`) to indexed content
+    KeywordToContent,
+    /// Geohash prefix path (`geo.<prefix>`) to content located in that cell
+    GeohashToContent,
+}
+
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    match op.flattened::<(), LinkTypes>()? {
+        FlatOp::RegisterCreateLink { link_type, target_address, tag, .. } => match link_type {
+            LinkTypes::KeywordToContent | LinkTypes::GeohashToContent => validate_keyword_link(target_address, tag),
+        },
+        FlatOp::RegisterDeleteLink { original_action, action, .. } => {
+            if original_action.author != action.author {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Only the author can remove content from the search index".into(),
+                ));
+            }
+            Ok(ValidateCallbackResult::Valid)
+        }
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
+fn validate_keyword_link(target_address: AnyLinkableHash, tag: LinkTag) -> ExternResult<ValidateCallbackResult> {
+    if target_address.into_action_hash().is_none() {
+        return Ok(ValidateCallbackResult::Invalid("Search links must point to a record".into()));
+    }
+    let Ok(search_tag) = SearchTag::try_from(SerializedBytes::from(UnsafeBytes::from(tag.into_inner()))) else {
+        return Ok(ValidateCallbackResult::Invalid("Search link tag is malformed".into()));
+    };
+    if search_tag.title.chars().count() > MAX_TAG_TITLE_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Search titles cannot exceed {} characters", MAX_TAG_TITLE_LENGTH
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -0,0 +1,143 @@
+use hdi::prelude::*;
+
+/// Neighborhood-wide toggles for optional behavior, read by the spaces zome
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FeatureToggles {
+    /// Space managers must approve reservations before they hold
+    pub require_reservation_approval: bool,
+    /// Anchors must approve new spaces before they are listed; when off, new
+    /// spaces are listed as soon as they are created
+    pub require_space_approval: bool,
+}
+
+/// Spaces have always needed an anchor's approval, so that stays the default
+impl Default for FeatureToggles {
+    fn default() -> Self {
+        Self {
+            require_reservation_approval: false,
+            require_space_approval: true,
+        }
+    }
+}
+
+/// One revision of the neighborhood settings
+///
+/// Settings are never updated in place: each change is a new entry pointing
+/// at the revision it replaces, so the full change history stays readable.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct NeighborhoodSettings {
+    pub name: String,
+    pub description: String,
+    pub rules: String,
+    pub features: FeatureToggles,
+    /// The revision this one replaces; None for the first
+    pub previous: Option<ActionHash>,
+    /// The editor's TrustedAnchor record in the vouch zome
+    pub editor_anchor: ActionHash,
+    pub change_note: Option<String>,
+    pub created_at: Timestamp,
+}
+
+/// Mirror of the vouch zome's TrustedAnchor, for checking editors
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct TrustedAnchor {
+    pub agent: AgentPubKey,
+    pub created_at: Timestamp,
+    #[serde(default)]
+    pub nomination: Option<ActionHash>,
+}
+
+pub const MAX_NAME_LENGTH: usize = 100;
+pub const MAX_DESCRIPTION_LENGTH: usize = 2000;
+pub const MAX_RULES_LENGTH: usize = 10000;
+pub const MAX_CHANGE_NOTE_LENGTH: usize = 500;
+
+#[hdk_link_types]
+pub enum LinkTypes {
+    /// Settings path to every revision
+    SettingsRevisions,
+}
+
+#[hdk_entry_types]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+    #[entry_type(name = "neighborhood_settings", visibility = "public")]
+    NeighborhoodSettings(NeighborhoodSettings),
+}
+
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    match op.flattened::<EntryTypes, LinkTypes>()? {
+        FlatOp::StoreEntry(store_entry) => match store_entry {
+            OpEntry::CreateEntry { app_entry, action } => match app_entry {
+                EntryTypes::NeighborhoodSettings(settings) => validate_settings(settings, action.author.clone()),
+            },
+            OpEntry::UpdateEntry { .. } => Ok(ValidateCallbackResult::Invalid(
+                "Settings cannot be updated; create a new revision".into(),
+            )),
+            _ => Ok(ValidateCallbackResult::Valid),
+        },
+        FlatOp::StoreRecord(store_record) => match store_record {
+            OpRecord::CreateEntry { app_entry, action } => match app_entry {
+                EntryTypes::NeighborhoodSettings(settings) => validate_settings(settings, action.author.clone()),
+            },
+            OpRecord::UpdateEntry { .. } => Ok(ValidateCallbackResult::Invalid(
+                "Settings cannot be updated; create a new revision".into(),
+            )),
+            OpRecord::DeleteEntry { .. } => Ok(ValidateCallbackResult::Invalid(
+                "Settings revisions cannot be deleted".into(),
+            )),
+            _ => Ok(ValidateCallbackResult::Valid),
+        },
+        FlatOp::RegisterDeleteLink { .. } => Ok(ValidateCallbackResult::Invalid(
+            "Settings history cannot be removed".into(),
+        )),
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
+fn validate_settings(settings: NeighborhoodSettings, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if settings.name.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Neighborhood name cannot be empty".into()));
+    }
+    if settings.name.len() > MAX_NAME_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Name cannot exceed {} characters", MAX_NAME_LENGTH
+        )));
+    }
+    if settings.description.len() > MAX_DESCRIPTION_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Description cannot exceed {} characters", MAX_DESCRIPTION_LENGTH
+        )));
+    }
+    if settings.rules.len() > MAX_RULES_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Rules cannot exceed {} characters", MAX_RULES_LENGTH
+        )));
+    }
+    if let Some(ref note) = settings.change_note {
+        if note.len() > MAX_CHANGE_NOTE_LENGTH {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Change note cannot exceed {} characters", MAX_CHANGE_NOTE_LENGTH
+            )));
+        }
+    }
+
+    if let Some(previous) = settings.previous {
+        let previous_record = must_get_valid_record(previous)?;
+        if !matches!(previous_record.entry().to_app_option::<NeighborhoodSettings>(), Ok(Some(_))) {
+            return Ok(ValidateCallbackResult::Invalid("Previous revision must be neighborhood settings".into()));
+        }
+    }
+
+    // Only anchors may edit: the editor must reference their own TrustedAnchor
+    let anchor_record = must_get_valid_record(settings.editor_anchor)?;
+    let Ok(Some(anchor)) = anchor_record.entry().to_app_option::<TrustedAnchor>() else {
+        return Ok(ValidateCallbackResult::Invalid("Editor must reference a trusted anchor".into()));
+    };
+    if anchor.agent != author || anchor_record.action().author() != &author {
+        return Ok(ValidateCallbackResult::Invalid("Only trusted anchors can change settings".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -1,4 +1,7 @@
 use hdi::prelude::*;
+use std::collections::BTreeSet;
+use membership_proof::require_membership_proof;
+pub use location::{validate_location, Location};
 
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq, Eq)]
@@ -9,6 +12,61 @@ pub struct Space {
     pub available_hours: String, // e.g., "9:00-21:00"
     pub manager: AgentPubKey,
     pub created_at: Timestamp,
+    #[serde(default)]
+    pub place: Option<Location>,
+    /// Lets reservations overlap as long as their party sizes fit the capacity
+    #[serde(default)]
+    pub allow_shared_bookings: bool,
+    #[serde(default)]
+    pub booking_rules: BookingRules,
+}
+
+/// Fair-use limits on a space's reservations; None means no limit
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct BookingRules {
+    /// Longest single reservation
+    pub max_consecutive_hours: Option<u32>,
+    /// Reservations one agent may hold starting in any UTC week (Monday to Sunday)
+    pub max_bookings_per_week: Option<u32>,
+    /// How far ahead of its start a reservation may be made
+    pub advance_booking_days: Option<u32>,
+    /// The manager approves each reservation before it holds its slot, as if
+    /// the neighborhood's `require_reservation_approval` setting were on
+    #[serde(default)]
+    pub require_approval: bool,
+}
+
+impl BookingRules {
+    /// The limits that depend only on the reservation and when it was made
+    pub fn check(&self, reservation: &Reservation, booked_at: Timestamp) -> Result<(), String> {
+        let duration_secs = (reservation.end_time.as_micros() - reservation.start_time.as_micros()) / 1_000_000;
+        if let Some(hours) = self.max_consecutive_hours {
+            if duration_secs > i64::from(hours) * 3600 {
+                return Err(format!("Reservations of this space cannot exceed {} hours", hours));
+            }
+        }
+        if let Some(days) = self.advance_booking_days {
+            let lead_secs = (reservation.start_time.as_micros() - booked_at.as_micros()) / 1_000_000;
+            if lead_secs > i64::from(days) * SECS_PER_DAY {
+                return Err(format!("This space can only be booked {} days in advance", days));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        let limits = [self.max_consecutive_hours, self.max_bookings_per_week, self.advance_booking_days];
+        if limits.contains(&Some(0)) {
+            return Err("Booking limits must be greater than 0".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// The UTC week (Monday to Sunday) a moment falls in, counted from the epoch
+pub fn booking_week(time: Timestamp) -> i64 {
+    // The epoch was a Thursday
+    (time.as_micros() / 1_000_000 + 3 * SECS_PER_DAY).div_euclid(7 * SECS_PER_DAY)
 }
 
 #[hdk_entry_helper]
@@ -20,19 +78,161 @@ pub struct Reservation {
     pub end_time: Timestamp,
     pub purpose: Option<String>,
     pub created_at: Timestamp,
+    /// Number of people the reservation is for
+    #[serde(default = "default_party_size")]
+    pub party_size: u32,
+    /// Reservations of the same space overlapping this one that the reserver
+    /// saw when booking; validation checks the capacity against them. The
+    /// reserver picks this list, so it can't stop overbooking on its own; see
+    /// `holding_reservations`
+    #[serde(default)]
+    pub known_overlaps: Vec<ActionHash>,
+}
+
+fn default_party_size() -> u32 {
+    1
+}
+
+impl Reservation {
+    pub fn overlaps(&self, other: &Reservation) -> bool {
+        self.start_time < other.end_time && self.end_time > other.start_time
+    }
+}
+
+/// Which of a space's reservations hold the slots they booked
+///
+/// Validation only sees the overlaps a reserver lists in `known_overlaps`,
+/// and the reserver chooses that list, so an overbooking reservation can be
+/// valid, whether by leaving overlaps out or by booking at the same moment as
+/// someone else. The only guarantee is this deterministic tie-break, which
+/// every peer settles the same way: in booking order (action timestamp, then
+/// action hash), a reservation holds if it still fits alongside the earlier
+/// ones that hold. Anything that treats a reservation as booked (access
+/// instructions, condition reports, equipment, listings) must go by this.
+/// Returns one flag per reservation, in the order given.
+pub fn holding_reservations(space: &Space, reservations: &[(&ActionHash, Timestamp, &Reservation)]) -> Vec<bool> {
+    let mut order: Vec<usize> = (0..reservations.len()).collect();
+    order.sort_by(|&a, &b| {
+        let (hash_a, booked_a, _) = reservations[a];
+        let (hash_b, booked_b, _) = reservations[b];
+        booked_a.cmp(&booked_b).then_with(|| hash_a.cmp(hash_b))
+    });
+
+    let mut holds = vec![false; reservations.len()];
+    for index in order {
+        let reservation = reservations[index].2;
+        let booked: u32 = reservations
+            .iter()
+            .zip(&holds)
+            .filter(|((_, _, other), held)| **held && other.overlaps(reservation))
+            .map(|((_, _, other), _)| other.party_size)
+            .sum();
+        holds[index] = if space.allow_shared_bookings {
+            booked + reservation.party_size <= space.capacity
+        } else {
+            booked == 0
+        };
+    }
+    holds
+}
+
+/// Door code or key handoff notes for a space; private to the manager's
+/// chain, latest entry per space wins
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct AccessInstruction {
+    pub space_hash: ActionHash,
+    pub instructions: String,
+    pub updated_at: Timestamp,
+}
+
+/// What a space's manager wants checked after each use; latest link wins
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct SpaceChecklist {
+    pub space_hash: ActionHash,
+    pub items: Vec<String>,
+    pub created_at: Timestamp,
+}
+
+/// One checklist item as the reserver left it
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ChecklistResult {
+    pub item: String,
+    pub done: bool,
+}
+
+/// The state a reserver left a space in after their reservation
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct ConditionReport {
+    pub reservation_hash: ActionHash,
+    pub space_hash: ActionHash,
+    pub reporter: AgentPubKey,
+    pub checklist: Vec<ChecklistResult>,
+    pub notes: Option<String>,
+    /// File storage hashes
+    pub photos: Vec<EntryHash>,
+    pub created_at: Timestamp,
+}
+
+impl ConditionReport {
+    /// An item left undone, or notes describing something, need a look
+    pub fn has_problems(&self) -> bool {
+        self.checklist.iter().any(|result| !result.done)
+            || self.notes.as_ref().is_some_and(|notes| !notes.trim().is_empty())
+    }
+}
+
+/// An anchor letting a new space into the shared list
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct SpaceApproval {
+    pub space_hash: ActionHash,
+    /// The approver's TrustedAnchor record in the vouch zome
+    pub approver_anchor: ActionHash,
+    pub approved_at: Timestamp,
+}
+
+/// Mirror of the vouch zome's TrustedAnchor, for checking approvers
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+pub struct TrustedAnchor {
+    pub agent: AgentPubKey,
+    pub created_at: Timestamp,
+    #[serde(default)]
+    pub nomination: Option<ActionHash>,
 }
 
 pub const MAX_NAME_LENGTH: usize = 100;
 pub const MAX_DESCRIPTION_LENGTH: usize = 500;
 pub const MAX_PURPOSE_LENGTH: usize = 200;
 pub const MAX_AVAILABLE_HOURS_LENGTH: usize = 50;
+pub const MAX_ACCESS_INSTRUCTIONS_LENGTH: usize = 1000;
+pub const MAX_CHECKLIST_ITEMS: usize = 30;
+pub const MAX_CHECKLIST_ITEM_LENGTH: usize = 100;
+pub const MAX_REPORT_NOTES_LENGTH: usize = 1000;
+pub const MAX_REPORT_PHOTOS: usize = 5;
+const SECS_PER_DAY: i64 = 24 * 60 * 60;
+pub const EQUIPMENT_AUTO_RESERVE_TAG: &[u8] = b"auto_reserve";
 
 #[hdk_link_types]
 pub enum LinkTypes {
+    /// Approved spaces; the tag holds the raw SpaceApproval action hash
     AllSpaces,
+    /// Spaces waiting for an anchor's approval
+    PendingSpaces,
     AgentToSpaces,
     SpaceToReservations,
     AgentToReservations,
+    SpaceToChecklists,
+    ReservationToReports,
+    SpaceToReports,
+    /// Toolshed items kept at a space; tagged with EQUIPMENT_AUTO_RESERVE_TAG
+    /// when they should be held for the space's reservations
+    SpaceToEquipment,
+    /// The space manager's approval of a reservation, from the reservation to
+    /// its space; only consulted when approval is required
+    ReservationToApprovals,
 }
 
 #[hdk_entry_types]
@@ -42,6 +242,14 @@ pub enum EntryTypes {
     Space(Space),
     #[entry_type(name = "reservation", visibility = "public")]
     Reservation(Reservation),
+    #[entry_type(name = "access_instruction", visibility = "private")]
+    AccessInstruction(AccessInstruction),
+    #[entry_type(name = "space_checklist", visibility = "public")]
+    SpaceChecklist(SpaceChecklist),
+    #[entry_type(name = "condition_report", visibility = "public")]
+    ConditionReport(ConditionReport),
+    #[entry_type(name = "space_approval", visibility = "public")]
+    SpaceApproval(SpaceApproval),
 }
 
 #[hdk_extern]
@@ -50,7 +258,13 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
         FlatOp::StoreEntry(store_entry) => match store_entry {
             OpEntry::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::Space(space) => validate_space(space, action.author.clone()),
-                EntryTypes::Reservation(reservation) => validate_reservation(reservation, action.author.clone()),
+                EntryTypes::Reservation(reservation) => {
+                    validate_reservation(reservation, action.author.clone(), action.timestamp)
+                }
+                EntryTypes::AccessInstruction(access) => validate_access_instruction(access, action.author.clone()),
+                EntryTypes::SpaceChecklist(checklist) => validate_space_checklist(checklist, action.author.clone()),
+                EntryTypes::ConditionReport(report) => validate_condition_report(report, action.author.clone()),
+                EntryTypes::SpaceApproval(approval) => validate_space_approval(approval, action.author.clone()),
             },
             OpEntry::UpdateEntry { app_entry, action, .. } => match app_entry {
                 EntryTypes::Space(space) => validate_space(space, action.author.clone()),
@@ -61,7 +275,13 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
         FlatOp::StoreRecord(store_record) => match store_record {
             OpRecord::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::Space(space) => validate_space(space, action.author.clone()),
-                EntryTypes::Reservation(reservation) => validate_reservation(reservation, action.author.clone()),
+                EntryTypes::Reservation(reservation) => {
+                    validate_reservation(reservation, action.author.clone(), action.timestamp)
+                }
+                EntryTypes::AccessInstruction(access) => validate_access_instruction(access, action.author.clone()),
+                EntryTypes::SpaceChecklist(checklist) => validate_space_checklist(checklist, action.author.clone()),
+                EntryTypes::ConditionReport(report) => validate_condition_report(report, action.author.clone()),
+                EntryTypes::SpaceApproval(approval) => validate_space_approval(approval, action.author.clone()),
             },
             OpRecord::UpdateEntry { app_entry, action, .. } => match app_entry {
                 EntryTypes::Space(space) => validate_space(space, action.author.clone()),
@@ -81,6 +301,44 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
+        FlatOp::RegisterCreateLink { link_type: LinkTypes::AllSpaces, target_address, tag, action, .. } => {
+            validate_listing_link(target_address, tag, action.author)
+        }
+        FlatOp::RegisterCreateLink {
+            link_type: LinkTypes::SpaceToEquipment,
+            base_address,
+            target_address,
+            action,
+            ..
+        } => validate_equipment_link(base_address, target_address, action.author),
+        FlatOp::RegisterCreateLink {
+            link_type: LinkTypes::ReservationToApprovals,
+            base_address,
+            target_address,
+            action,
+            ..
+        } => validate_reservation_approval_link(base_address, target_address, action.author),
+        FlatOp::RegisterDeleteLink { link_type: LinkTypes::SpaceToEquipment, original_action, action, .. } => {
+            if original_action.author != action.author {
+                return Ok(ValidateCallbackResult::Invalid("Only the space manager can detach equipment".into()));
+            }
+            Ok(ValidateCallbackResult::Valid)
+        }
+        // Only verified members may publish; checked against the author's own chain
+        FlatOp::RegisterAgentActivity(OpActivity::CreateEntry {
+            app_entry_type: Some(UnitEntryTypes::Space),
+            action,
+        }) => require_membership_proof(&action.author, &action.prev_action),
+        FlatOp::RegisterAgentActivity(OpActivity::CreateEntry {
+            app_entry_type: Some(UnitEntryTypes::Reservation),
+            action,
+        }) => {
+            let proof = require_membership_proof(&action.author, &action.prev_action)?;
+            if proof != ValidateCallbackResult::Valid {
+                return Ok(proof);
+            }
+            validate_weekly_bookings(&action)
+        }
         _ => Ok(ValidateCallbackResult::Valid),
     }
 }
@@ -110,10 +368,111 @@ fn validate_space(space: Space, author: AgentPubKey) -> ExternResult<ValidateCal
     if space.manager != author {
         return Ok(ValidateCallbackResult::Invalid("Space manager must match action author".into()));
     }
+    if let Some(ref place) = space.place {
+        if let Err(message) = validate_location(place) {
+            return Ok(ValidateCallbackResult::Invalid(message));
+        }
+    }
+    if let Err(message) = space.booking_rules.validate() {
+        return Ok(ValidateCallbackResult::Invalid(message));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_space_approval(approval: SpaceApproval, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    // Only anchors may approve: the approver must reference their own TrustedAnchor
+    let anchor_record = must_get_valid_record(approval.approver_anchor)?;
+    let Ok(Some(anchor)) = anchor_record.entry().to_app_option::<TrustedAnchor>() else {
+        return Ok(ValidateCallbackResult::Invalid("Approver must reference a trusted anchor".into()));
+    };
+    if anchor.agent != author || anchor_record.action().author() != &author {
+        return Ok(ValidateCallbackResult::Invalid("Only trusted anchors can approve spaces".into()));
+    }
+
+    let space_record = must_get_valid_record(approval.space_hash)?;
+    if !matches!(space_record.entry().to_app_option::<Space>(), Ok(Some(_))) {
+        return Ok(ValidateCallbackResult::Invalid("Approval must be for a space".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// A space is listed by its approving anchor, with the approval in the tag
+fn validate_listing_link(
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+    author: AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let Ok(approval_hash) = ActionHash::try_from_raw_39(tag.0) else {
+        return Ok(ValidateCallbackResult::Invalid("Listing a space requires its approval".into()));
+    };
+    let approval_record = must_get_valid_record(approval_hash)?;
+    let Ok(Some(approval)) = approval_record.entry().to_app_option::<SpaceApproval>() else {
+        return Ok(ValidateCallbackResult::Invalid("Listing a space requires its approval".into()));
+    };
+    if approval_record.action().author() != &author {
+        return Ok(ValidateCallbackResult::Invalid("Only the approving anchor can list a space".into()));
+    }
+    if target_address.into_action_hash() != Some(approval.space_hash) {
+        return Ok(ValidateCallbackResult::Invalid("Approval is for another space".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Managers attach items they own, so they can hold them for reservations
+/// Only the manager of the reserved space can approve a reservation
+fn validate_reservation_approval_link(
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    author: AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let (Some(reservation_hash), Some(space_hash)) =
+        (base_address.into_action_hash(), target_address.into_action_hash())
+    else {
+        return Ok(ValidateCallbackResult::Invalid("Approvals must link a reservation to its space".into()));
+    };
+    let Ok(Some(reservation)) = must_get_valid_record(reservation_hash)?.entry().to_app_option::<Reservation>() else {
+        return Ok(ValidateCallbackResult::Invalid("Approvals must link a reservation to its space".into()));
+    };
+    if reservation.space_hash != space_hash {
+        return Ok(ValidateCallbackResult::Invalid("Reservation is for another space".into()));
+    }
+    let Ok(Some(space)) = must_get_valid_record(space_hash)?.entry().to_app_option::<Space>() else {
+        return Ok(ValidateCallbackResult::Invalid("Approvals must link a reservation to its space".into()));
+    };
+    if space.manager != author {
+        return Ok(ValidateCallbackResult::Invalid("Only the space manager can approve reservations".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_equipment_link(
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    author: AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let (Some(space_hash), Some(item_hash)) = (base_address.into_action_hash(), target_address.into_action_hash())
+    else {
+        return Ok(ValidateCallbackResult::Invalid("Equipment links go from a space to an item".into()));
+    };
+    let space_record = must_get_valid_record(space_hash)?;
+    let Ok(Some(space)) = space_record.entry().to_app_option::<Space>() else {
+        return Ok(ValidateCallbackResult::Invalid("Equipment must be attached to a space".into()));
+    };
+    if space.manager != author {
+        return Ok(ValidateCallbackResult::Invalid("Only the space manager can attach equipment".into()));
+    }
+    let item_record = must_get_valid_record(item_hash)?;
+    if item_record.action().author() != &author {
+        return Ok(ValidateCallbackResult::Invalid("Managers can only attach their own items".into()));
+    }
     Ok(ValidateCallbackResult::Valid)
 }
 
-fn validate_reservation(reservation: Reservation, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+fn validate_reservation(
+    reservation: Reservation,
+    author: AgentPubKey,
+    booked_at: Timestamp,
+) -> ExternResult<ValidateCallbackResult> {
     if reservation.reserver != author {
         return Ok(ValidateCallbackResult::Invalid("Reserver must match action author".into()));
     }
@@ -127,5 +486,209 @@ fn validate_reservation(reservation: Reservation, author: AgentPubKey) -> Extern
             )));
         }
     }
+    if reservation.party_size == 0 {
+        return Ok(ValidateCallbackResult::Invalid("Party size must be greater than 0".into()));
+    }
+    // Only the overlaps the reserver lists can be checked here; anything they
+    // leave out, or booked at the same moment, is settled by `holding_reservations`
+    let space_record = must_get_valid_record(reservation.space_hash.clone())?;
+    let Some(space) = space_record
+        .entry()
+        .to_app_option::<Space>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    else {
+        return Ok(ValidateCallbackResult::Invalid("Reservation must be for a space".into()));
+    };
+    if reservation.party_size > space.capacity {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Party size cannot exceed the space's capacity of {}", space.capacity
+        )));
+    }
+    if let Err(message) = space.booking_rules.check(&reservation, booked_at) {
+        return Ok(ValidateCallbackResult::Invalid(message));
+    }
+    if reservation.known_overlaps.len() >= space.capacity as usize {
+        return Ok(ValidateCallbackResult::Invalid("The space is fully booked for that time".into()));
+    }
+    let unique: BTreeSet<&ActionHash> = reservation.known_overlaps.iter().collect();
+    if unique.len() != reservation.known_overlaps.len() {
+        return Ok(ValidateCallbackResult::Invalid("Known overlaps cannot repeat".into()));
+    }
+
+    let mut booked = 0;
+    for overlap_hash in &reservation.known_overlaps {
+        let overlap_record = must_get_valid_record(overlap_hash.clone())?;
+        let Some(overlap) = overlap_record
+            .entry()
+            .to_app_option::<Reservation>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        else {
+            return Ok(ValidateCallbackResult::Invalid("Known overlaps must be reservations".into()));
+        };
+        if overlap.space_hash != reservation.space_hash || !overlap.overlaps(&reservation) {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Known overlaps must be reservations of the same space at the same time".into(),
+            ));
+        }
+        booked += overlap.party_size;
+    }
+    if !space.allow_shared_bookings && booked > 0 {
+        return Ok(ValidateCallbackResult::Invalid("Time slot conflicts with an existing reservation".into()));
+    }
+    if booked + reservation.party_size > space.capacity {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Only {} of {} places are free for that time slot",
+            space.capacity.saturating_sub(booked),
+            space.capacity
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Enforce `max_bookings_per_week` against the reserver's own chain, where
+/// all their reservations live; cancelled ones don't count
+fn validate_weekly_bookings(action: &Create) -> ExternResult<ValidateCallbackResult> {
+    let reservation = reservation_entry(&action.entry_hash)?;
+    let space_record = must_get_valid_record(reservation.space_hash.clone())?;
+    let Ok(Some(space)) = space_record.entry().to_app_option::<Space>() else {
+        return Ok(ValidateCallbackResult::Invalid("Reservation must be for a space".into()));
+    };
+    let Some(max_per_week) = space.booking_rules.max_bookings_per_week else {
+        return Ok(ValidateCallbackResult::Valid);
+    };
+
+    let activity = must_get_agent_activity(action.author.clone(), ChainFilter::new(action.prev_action.clone()))?;
+    let cancelled: BTreeSet<&ActionHash> = activity
+        .iter()
+        .filter_map(|item| match item.action.action() {
+            Action::Delete(delete) => Some(&delete.deletes_address),
+            _ => None,
+        })
+        .collect();
+
+    let reservation_type: EntryType = UnitEntryTypes::Reservation.try_into()?;
+    let week = booking_week(reservation.start_time);
+    let mut booked = 0;
+    for item in &activity {
+        let Action::Create(create) = item.action.action() else {
+            continue;
+        };
+        if create.entry_type != reservation_type || cancelled.contains(item.action.as_hash()) {
+            continue;
+        }
+        let earlier = reservation_entry(&create.entry_hash)?;
+        if earlier.space_hash == reservation.space_hash && booking_week(earlier.start_time) == week {
+            booked += 1;
+        }
+    }
+    if booked >= max_per_week {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Only {} reservations of this space are allowed per week", max_per_week
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn reservation_entry(entry_hash: &EntryHash) -> ExternResult<Reservation> {
+    let entry = must_get_entry(entry_hash.clone())?.content;
+    Reservation::try_from(entry)
+}
+
+fn validate_access_instruction(access: AccessInstruction, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if access.instructions.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Access instructions cannot be empty".into()));
+    }
+    if access.instructions.len() > MAX_ACCESS_INSTRUCTIONS_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Access instructions cannot exceed {} characters", MAX_ACCESS_INSTRUCTIONS_LENGTH
+        )));
+    }
+    let space_record = must_get_valid_record(access.space_hash)?;
+    let Some(space) = space_record
+        .entry()
+        .to_app_option::<Space>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    else {
+        return Ok(ValidateCallbackResult::Invalid("Access instructions must be for a space".into()));
+    };
+    if space.manager != author {
+        return Ok(ValidateCallbackResult::Invalid("Only the space manager can set access instructions".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_space_checklist(checklist: SpaceChecklist, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if checklist.items.len() > MAX_CHECKLIST_ITEMS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "A checklist cannot have more than {} items", MAX_CHECKLIST_ITEMS
+        )));
+    }
+    if let Some(message) = invalid_checklist_item(checklist.items.iter()) {
+        return Ok(ValidateCallbackResult::Invalid(message));
+    }
+    let space_record = must_get_valid_record(checklist.space_hash)?;
+    let Some(space) = space_record
+        .entry()
+        .to_app_option::<Space>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    else {
+        return Ok(ValidateCallbackResult::Invalid("Checklist must be for a space".into()));
+    };
+    if space.manager != author {
+        return Ok(ValidateCallbackResult::Invalid("Only the space manager can set its checklist".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_condition_report(report: ConditionReport, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if report.reporter != author {
+        return Ok(ValidateCallbackResult::Invalid("Reporter must match action author".into()));
+    }
+    if report.checklist.len() > MAX_CHECKLIST_ITEMS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "A checklist cannot have more than {} items", MAX_CHECKLIST_ITEMS
+        )));
+    }
+    if let Some(message) = invalid_checklist_item(report.checklist.iter().map(|result| &result.item)) {
+        return Ok(ValidateCallbackResult::Invalid(message));
+    }
+    if let Some(ref notes) = report.notes {
+        if notes.len() > MAX_REPORT_NOTES_LENGTH {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Notes cannot exceed {} characters", MAX_REPORT_NOTES_LENGTH
+            )));
+        }
+    }
+    if report.photos.len() > MAX_REPORT_PHOTOS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "A report cannot have more than {} photos", MAX_REPORT_PHOTOS
+        )));
+    }
+    let reservation_record = must_get_valid_record(report.reservation_hash)?;
+    let Some(reservation) = reservation_record
+        .entry()
+        .to_app_option::<Reservation>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    else {
+        return Ok(ValidateCallbackResult::Invalid("Condition report must be for a reservation".into()));
+    };
+    if reservation.reserver != author {
+        return Ok(ValidateCallbackResult::Invalid("Only the reserver can report on a reservation".into()));
+    }
+    if reservation.space_hash != report.space_hash {
+        return Ok(ValidateCallbackResult::Invalid("Report space must match the reservation's space".into()));
+    }
     Ok(ValidateCallbackResult::Valid)
 }
+
+fn invalid_checklist_item<'a>(mut items: impl Iterator<Item = &'a String>) -> Option<String> {
+    items.find_map(|item| {
+        if item.trim().is_empty() {
+            Some("Checklist items cannot be empty".to_string())
+        } else if item.len() > MAX_CHECKLIST_ITEM_LENGTH {
+            Some(format!("Checklist items cannot exceed {} characters", MAX_CHECKLIST_ITEM_LENGTH))
+        } else {
+            None
+        }
+    })
+}
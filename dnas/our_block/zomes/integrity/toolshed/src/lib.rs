@@ -1,4 +1,6 @@
 use hdi::prelude::*;
+use membership_proof::require_membership_proof;
+pub use location::{validate_location, Location};
 
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq, Eq)]
@@ -19,6 +21,41 @@ impl Default for ItemStatus {
     fn default() -> Self { ItemStatus::Available }
 }
 
+/// What a lender asks of a borrower; shown on the item, agreed to in the
+/// borrow request, and carried into the transaction
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LendingTerms {
+    /// e.g. "$20 cash, returned with the item"
+    pub deposit: Option<String>,
+    /// What the borrower owes if the item is lost or broken
+    pub replacement_value: Option<String>,
+    pub care_instructions: Option<String>,
+}
+
+/// What an item runs on
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum PowerSource {
+    Manual,
+    Battery,
+    Corded,
+    Gas,
+}
+
+/// Library-of-things catalog details; every field is optional
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CatalogInfo {
+    pub brand: Option<String>,
+    pub model: Option<String>,
+    /// The manual's FileMetadata action hash in the file_storage zome
+    pub manual: Option<ActionHash>,
+    /// e.g. "$150 new"
+    pub replacement_value: Option<String>,
+    pub power_source: Option<PowerSource>,
+    /// e.g. "20V battery, charger included"
+    pub power_details: Option<String>,
+}
+
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq, Eq)]
 pub struct Item {
@@ -30,6 +67,13 @@ pub struct Item {
     pub owner: AgentPubKey,
     pub status: ItemStatus,
     pub created_at: Timestamp,
+    #[serde(default)]
+    pub lending_terms: Option<LendingTerms>,
+    /// Where the item can be picked up
+    #[serde(default)]
+    pub place: Option<Location>,
+    #[serde(default)]
+    pub catalog: Option<CatalogInfo>,
 }
 
 #[hdk_entry_helper]
@@ -41,6 +85,25 @@ pub struct BorrowRequest {
     pub requested_due_date: Timestamp,
     pub message: Option<String>,
     pub created_at: Timestamp,
+    /// When the request lapses if the owner hasn't answered it. Requests
+    /// made before expiry existed have None and use the default lifetime.
+    #[serde(default)]
+    pub expires_at: Option<Timestamp>,
+    /// The item's lending terms as the requester saw them
+    #[serde(default)]
+    pub terms: Option<LendingTerms>,
+}
+
+impl BorrowRequest {
+    pub fn expiry(&self) -> Timestamp {
+        self.expires_at.unwrap_or_else(|| {
+            Timestamp::from_micros(self.created_at.as_micros() + DEFAULT_BORROW_REQUEST_TTL_SECS * 1_000_000)
+        })
+    }
+
+    pub fn is_expired(&self, now: Timestamp) -> bool {
+        now >= self.expiry()
+    }
 }
 
 #[hdk_entry_helper]
@@ -52,31 +115,117 @@ pub struct Transaction {
     pub due_date: Timestamp,
     pub created_at: Timestamp,
     pub notes: Option<String>,
+    /// Terms both parties agreed to; copied from the borrow request
+    #[serde(default)]
+    pub terms: Option<LendingTerms>,
+    /// The borrow request this transaction accepts
+    #[serde(default)]
+    pub request_hash: Option<ActionHash>,
+}
+
+/// A neighbor asking whether anyone has an item they could lend
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct WantedItem {
+    pub title: String,
+    pub description: String,
+    pub requester: AgentPubKey,
+    pub created_at: Timestamp,
+}
+
+/// A status change on a transaction. Handoff records are one party's
+/// acknowledgment of a pickup or drop-off; the second party's record cites
+/// the first as `counterpart`, so a finished handoff carries both signatures.
+/// A stretch of time an owner has blocked off for an item, e.g. while
+/// they're away; borrows that would overlap it are refused
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct UnavailabilityWindow {
+    pub item_hash: ActionHash,
+    pub starts_at: Timestamp,
+    pub ends_at: Timestamp,
+    pub reason: Option<String>,
+    pub created_at: Timestamp,
+}
+
+impl UnavailabilityWindow {
+    pub fn is_active(&self, now: Timestamp) -> bool {
+        self.starts_at <= now && now < self.ends_at
+    }
+
+    /// Whether a borrow running from `from` to `until` would fall in this window
+    pub fn overlaps(&self, from: Timestamp, until: Timestamp) -> bool {
+        self.starts_at < until && from < self.ends_at
+    }
 }
 
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq, Eq)]
 pub struct TransactionRecord {
     pub transaction_hash: ActionHash,
+    /// Set on Returned records: when the first party confirmed the drop-off
     pub returned_at: Option<Timestamp>,
     pub status: TransactionStatus,
+    /// Which handoff the author is acknowledging, if any
+    #[serde(default)]
+    pub handoff: Option<Handoff>,
+    /// The other party's acknowledgment of the same handoff
+    #[serde(default)]
+    pub counterpart: Option<ActionHash>,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum TransactionStatus {
     Pending,
+    /// Accepted; waiting for both parties to confirm the pickup
+    AwaitingPickup,
     Active,
+    /// One party has confirmed the drop-off; waiting for the other
+    AwaitingDropoff,
     Returned,
     Cancelled,
 }
 
+impl TransactionStatus {
+    /// How far along the borrow a status is; the furthest record wins
+    pub fn stage(&self) -> u8 {
+        match self {
+            TransactionStatus::Pending => 0,
+            TransactionStatus::AwaitingPickup => 1,
+            TransactionStatus::Active => 2,
+            TransactionStatus::AwaitingDropoff => 3,
+            TransactionStatus::Returned => 4,
+            TransactionStatus::Cancelled => 5,
+        }
+    }
+}
+
+/// The physical handoffs both parties acknowledge
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Handoff {
+    /// The borrower has the item
+    Pickup,
+    /// The lender has the item back
+    Dropoff,
+}
+
 pub const MAX_TITLE_LENGTH: usize = 100;
 pub const MAX_DESCRIPTION_LENGTH: usize = 1000;
 pub const MAX_MESSAGE_LENGTH: usize = 500;
 pub const MAX_NOTES_LENGTH: usize = 500;
 pub const MAX_CONSUMABLES: usize = 20;
 pub const MAX_CONSUMABLE_NAME_LENGTH: usize = 50;
+pub const MAX_TERMS_FIELD_LENGTH: usize = 200;
+pub const MAX_CARE_INSTRUCTIONS_LENGTH: usize = 1000;
+pub const MAX_CATALOG_FIELD_LENGTH: usize = 100;
+/// How long a borrow request stays open when the requester doesn't pick an expiry
+pub const DEFAULT_BORROW_REQUEST_TTL_SECS: i64 = 7 * 24 * 60 * 60;
+pub const MAX_BORROW_REQUEST_TTL_SECS: i64 = 30 * 24 * 60 * 60;
+/// How far a request's `created_at` may stray from its action's timestamp
+pub const MAX_CREATED_AT_SKEW_SECS: i64 = 5 * 60;
+pub const MAX_UNAVAILABILITY_WINDOW_SECS: i64 = 365 * 24 * 60 * 60;
 
 #[hdk_link_types]
 pub enum LinkTypes {
@@ -85,6 +234,14 @@ pub enum LinkTypes {
     ItemToBorrowRequests,
     AgentToTransactions,
     AgentToBorrowRequests,
+    /// Transaction to each of its status records (handoff confirmations, returned, ...)
+    TransactionToRecords,
+    AllWantedItems,
+    AgentToWantedItems,
+    /// Wanted item to an item its owner offered to fulfill it
+    WantedToItems,
+    /// Item to the windows its owner blocked off
+    ItemToUnavailability,
 }
 
 #[hdk_entry_types]
@@ -98,6 +255,10 @@ pub enum EntryTypes {
     Transaction(Transaction),
     #[entry_type(name = "transaction_record", visibility = "public")]
     TransactionRecord(TransactionRecord),
+    #[entry_type(name = "wanted_item", visibility = "public")]
+    WantedItem(WantedItem),
+    #[entry_type(name = "unavailability_window", visibility = "public")]
+    UnavailabilityWindow(UnavailabilityWindow),
 }
 
 #[hdk_extern]
@@ -106,9 +267,17 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
         FlatOp::StoreEntry(store_entry) => match store_entry {
             OpEntry::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::Item(item) => validate_item(item, action.author.clone()),
-                EntryTypes::BorrowRequest(req) => validate_borrow_request(req, action.author.clone()),
-                EntryTypes::Transaction(txn) => validate_transaction(txn),
-                EntryTypes::TransactionRecord(_) => Ok(ValidateCallbackResult::Valid),
+                EntryTypes::BorrowRequest(req) => {
+                    validate_borrow_request(req, action.author.clone(), action.timestamp)
+                }
+                EntryTypes::Transaction(txn) => validate_transaction(txn, action.author.clone()),
+                EntryTypes::TransactionRecord(txn_record) => {
+                    validate_transaction_record(txn_record, action.author.clone())
+                }
+                EntryTypes::WantedItem(wanted) => validate_wanted_item(wanted, action.author.clone()),
+                EntryTypes::UnavailabilityWindow(window) => {
+                    validate_unavailability_window(window, action.author.clone())
+                }
             },
             OpEntry::UpdateEntry { app_entry, action, .. } => match app_entry {
                 EntryTypes::Item(item) => validate_item(item, action.author.clone()),
@@ -119,9 +288,17 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
         FlatOp::StoreRecord(store_record) => match store_record {
             OpRecord::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::Item(item) => validate_item(item, action.author.clone()),
-                EntryTypes::BorrowRequest(req) => validate_borrow_request(req, action.author.clone()),
-                EntryTypes::Transaction(txn) => validate_transaction(txn),
-                EntryTypes::TransactionRecord(_) => Ok(ValidateCallbackResult::Valid),
+                EntryTypes::BorrowRequest(req) => {
+                    validate_borrow_request(req, action.author.clone(), action.timestamp)
+                }
+                EntryTypes::Transaction(txn) => validate_transaction(txn, action.author.clone()),
+                EntryTypes::TransactionRecord(txn_record) => {
+                    validate_transaction_record(txn_record, action.author.clone())
+                }
+                EntryTypes::WantedItem(wanted) => validate_wanted_item(wanted, action.author.clone()),
+                EntryTypes::UnavailabilityWindow(window) => {
+                    validate_unavailability_window(window, action.author.clone())
+                }
             },
             OpRecord::UpdateEntry { app_entry, action, .. } => match app_entry {
                 EntryTypes::Item(item) => validate_item(item, action.author.clone()),
@@ -141,6 +318,23 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
+        FlatOp::RegisterCreateLink { link_type, base_address, target_address, action, .. } => match link_type {
+            LinkTypes::WantedToItems => validate_wanted_to_item(base_address, target_address, action.author),
+            LinkTypes::ItemToUnavailability => validate_item_owner(base_address, action.author),
+            _ => Ok(ValidateCallbackResult::Valid),
+        },
+        FlatOp::RegisterDeleteLink { link_type, original_action, base_address, action, .. } => match link_type {
+            LinkTypes::ItemToBorrowRequests => {
+                validate_delete_borrow_request_link(original_action, base_address, action.author)
+            }
+            LinkTypes::ItemToUnavailability => validate_item_owner(base_address, action.author),
+            _ => Ok(ValidateCallbackResult::Valid),
+        },
+        // Only verified members may publish; checked against the author's own chain
+        FlatOp::RegisterAgentActivity(OpActivity::CreateEntry {
+            app_entry_type: Some(UnitEntryTypes::Item | UnitEntryTypes::BorrowRequest | UnitEntryTypes::WantedItem),
+            action,
+        }) => require_membership_proof(&action.author, &action.prev_action),
         _ => Ok(ValidateCallbackResult::Valid),
     }
 }
@@ -172,13 +366,40 @@ fn validate_item(item: Item, author: AgentPubKey) -> ExternResult<ValidateCallba
     if item.owner != author {
         return Ok(ValidateCallbackResult::Invalid("Item owner must match author".into()));
     }
+    if let Some(ref place) = item.place {
+        if let Err(message) = validate_location(place) {
+            return Ok(ValidateCallbackResult::Invalid(message));
+        }
+    }
+    if let Some(ref catalog) = item.catalog {
+        let result = validate_catalog(catalog)?;
+        if !matches!(result, ValidateCallbackResult::Valid) {
+            return Ok(result);
+        }
+    }
+    if let Some(ref terms) = item.lending_terms {
+        return validate_lending_terms(terms);
+    }
     Ok(ValidateCallbackResult::Valid)
 }
 
-fn validate_borrow_request(req: BorrowRequest, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+fn validate_borrow_request(
+    req: BorrowRequest,
+    author: AgentPubKey,
+    written_at: Timestamp,
+) -> ExternResult<ValidateCallbackResult> {
     if req.requester != author {
         return Ok(ValidateCallbackResult::Invalid("Requester must match author".into()));
     }
+    // `created_at` decides when a request without `expires_at` lapses, so it
+    // can't be backdated or postdated past ordinary clock drift
+    let skew_secs = (req.created_at.as_micros() - written_at.as_micros()).abs() / 1_000_000;
+    if skew_secs > MAX_CREATED_AT_SKEW_SECS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Request creation time must be within {} minutes of when it was written",
+            MAX_CREATED_AT_SKEW_SECS / 60
+        )));
+    }
     if req.requester == req.owner {
         return Ok(ValidateCallbackResult::Invalid("Cannot borrow your own item".into()));
     }
@@ -187,12 +408,284 @@ fn validate_borrow_request(req: BorrowRequest, author: AgentPubKey) -> ExternRes
             return Ok(ValidateCallbackResult::Invalid(format!("Message cannot exceed {} chars", MAX_MESSAGE_LENGTH)));
         }
     }
+    if let Some(expires_at) = req.expires_at {
+        if expires_at <= req.created_at {
+            return Ok(ValidateCallbackResult::Invalid("Request must expire after it is created".into()));
+        }
+        let lifetime_secs = (expires_at.as_micros() - req.created_at.as_micros()) / 1_000_000;
+        if lifetime_secs > MAX_BORROW_REQUEST_TTL_SECS {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Requests cannot stay open longer than {} days", MAX_BORROW_REQUEST_TTL_SECS / 86_400
+            )));
+        }
+    }
+    if let Some(ref terms) = req.terms {
+        return validate_lending_terms(terms);
+    }
     Ok(ValidateCallbackResult::Valid)
 }
 
-fn validate_transaction(txn: Transaction) -> ExternResult<ValidateCallbackResult> {
+/// The requester can withdraw their request; the item owner can clear it
+/// once it's answered or has lapsed
+fn validate_delete_borrow_request_link(
+    original_action: CreateLink,
+    base_address: AnyLinkableHash,
+    author: AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    if original_action.author == author {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    let Some(item_hash) = base_address.into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("Borrow request link base must be an item".into()));
+    };
+    let item_record = must_get_valid_record(item_hash)?;
+    let Ok(Some(item)) = item_record.entry().to_app_option::<Item>() else {
+        return Ok(ValidateCallbackResult::Invalid("Borrow request link base must be an item".into()));
+    };
+    if item.owner != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the requester or the item owner can remove a borrow request".into(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_unavailability_window(
+    window: UnavailabilityWindow,
+    author: AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    if window.ends_at <= window.starts_at {
+        return Ok(ValidateCallbackResult::Invalid("Unavailability must end after it starts".into()));
+    }
+    if window.ends_at.as_micros() - window.starts_at.as_micros() > MAX_UNAVAILABILITY_WINDOW_SECS * 1_000_000 {
+        return Ok(ValidateCallbackResult::Invalid("Unavailability cannot last more than a year".into()));
+    }
+    if window.reason.as_ref().is_some_and(|reason| reason.len() > MAX_MESSAGE_LENGTH) {
+        return Ok(ValidateCallbackResult::Invalid(format!("Reason cannot exceed {} chars", MAX_MESSAGE_LENGTH)));
+    }
+    validate_item_owner(window.item_hash.into(), author)
+}
+
+/// Only an item's owner can block off time for it
+fn validate_item_owner(item_hash: AnyLinkableHash, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    let Some(item_hash) = item_hash.into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("Unavailability must be for an item".into()));
+    };
+    let item_record = must_get_valid_record(item_hash)?;
+    let Ok(Some(item)) = item_record.entry().to_app_option::<Item>() else {
+        return Ok(ValidateCallbackResult::Invalid("Unavailability must be for an item".into()));
+    };
+    if item.owner != author {
+        return Ok(ValidateCallbackResult::Invalid("Only the item owner can schedule unavailability".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_wanted_item(wanted: WantedItem, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if wanted.title.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Wanted item title cannot be empty".into()));
+    }
+    if wanted.title.len() > MAX_TITLE_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!("Title cannot exceed {} chars", MAX_TITLE_LENGTH)));
+    }
+    if wanted.description.len() > MAX_DESCRIPTION_LENGTH {
+        return Ok(ValidateCallbackResult::Invalid(format!("Description cannot exceed {} chars", MAX_DESCRIPTION_LENGTH)));
+    }
+    if wanted.requester != author {
+        return Ok(ValidateCallbackResult::Invalid("Requester must match author".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Only an item's owner can offer it for a wanted item
+fn validate_wanted_to_item(
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    author: AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let (Some(wanted_hash), Some(item_hash)) = (base_address.into_action_hash(), target_address.into_action_hash()) else {
+        return Ok(ValidateCallbackResult::Invalid("Fulfillment links must join records".into()));
+    };
+    let wanted_record = must_get_valid_record(wanted_hash)?;
+    if !matches!(wanted_record.entry().to_app_option::<WantedItem>(), Ok(Some(_))) {
+        return Ok(ValidateCallbackResult::Invalid("Fulfillment link base must be a wanted item".into()));
+    }
+    let item_record = must_get_valid_record(item_hash)?;
+    let Ok(Some(item)) = item_record.entry().to_app_option::<Item>() else {
+        return Ok(ValidateCallbackResult::Invalid("Fulfillment link target must be an item".into()));
+    };
+    if item.owner != author {
+        return Ok(ValidateCallbackResult::Invalid("Only the item owner can offer it".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_transaction(txn: Transaction, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
     if txn.borrower == txn.lender {
         return Ok(ValidateCallbackResult::Invalid("Borrower and lender cannot be the same".into()));
     }
+    if txn.lender != author {
+        return Ok(ValidateCallbackResult::Invalid("Only the lender can create a transaction".into()));
+    }
+    if let Some(ref terms) = txn.terms {
+        if let ValidateCallbackResult::Invalid(reason) = validate_lending_terms(terms)? {
+            return Ok(ValidateCallbackResult::Invalid(reason));
+        }
+    }
+
+    // The parties, item and terms must be the ones the borrower asked for
+    let Some(request_hash) = txn.request_hash.clone() else {
+        return Ok(ValidateCallbackResult::Invalid("Transaction must cite the borrow request it accepts".into()));
+    };
+    let Some(request) = must_get_valid_record(request_hash)?
+        .entry()
+        .to_app_option::<BorrowRequest>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    else {
+        return Ok(ValidateCallbackResult::Invalid("Transaction must cite a borrow request".into()));
+    };
+    if request.item_hash != txn.item_hash || request.requester != txn.borrower || request.owner != txn.lender {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Transaction must match the item and parties of its borrow request".into(),
+        ));
+    }
+    if request.terms != txn.terms {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Transaction terms must match the borrow request's terms".into(),
+        ));
+    }
+    let Some(item) = must_get_valid_record(txn.item_hash.clone())?
+        .entry()
+        .to_app_option::<Item>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    else {
+        return Ok(ValidateCallbackResult::Invalid("Transaction must point to an item".into()));
+    };
+    if item.owner != txn.lender {
+        return Ok(ValidateCallbackResult::Invalid("Only the item's owner can lend it".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_transaction_record(
+    txn_record: TransactionRecord,
+    author: AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let record = must_get_valid_record(txn_record.transaction_hash.clone())?;
+    let Some(transaction) = record
+        .entry()
+        .to_app_option::<Transaction>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    else {
+        return Ok(ValidateCallbackResult::Invalid("Record must point to a transaction".into()));
+    };
+    let other = if author == transaction.lender {
+        transaction.borrower
+    } else if author == transaction.borrower {
+        transaction.lender
+    } else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the borrower or lender can record a transaction's status".into(),
+        ));
+    };
+
+    // Which statuses each kind of record may claim, and whether reaching it
+    // takes the other party's acknowledgment
+    let needs_counterpart = match (txn_record.handoff, &txn_record.status) {
+        (None, TransactionStatus::Pending | TransactionStatus::AwaitingPickup | TransactionStatus::Cancelled) => false,
+        (Some(Handoff::Pickup), TransactionStatus::AwaitingPickup) => false,
+        (Some(Handoff::Pickup), TransactionStatus::Active) => true,
+        (Some(Handoff::Dropoff), TransactionStatus::AwaitingDropoff) => false,
+        (Some(Handoff::Dropoff), TransactionStatus::Returned) => true,
+        (handoff, status) => {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "A {:?} record cannot set the status to {:?}", handoff, status
+            )))
+        }
+    };
+
+    let Some(counterpart_hash) = txn_record.counterpart else {
+        if needs_counterpart {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Completing a handoff needs the other party's confirmation".into(),
+            ));
+        }
+        if txn_record.returned_at.is_some() {
+            return Ok(ValidateCallbackResult::Invalid("Only a Returned record has a return time".into()));
+        }
+        return Ok(ValidateCallbackResult::Valid);
+    };
+    if !needs_counterpart {
+        return Ok(ValidateCallbackResult::Invalid("Only a completed handoff cites a counterpart".into()));
+    }
+
+    let counterpart_record = must_get_valid_record(counterpart_hash)?;
+    let confirms_same_handoff = counterpart_record.action().author() == &other
+        && counterpart_record
+            .entry()
+            .to_app_option::<TransactionRecord>()
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+            .is_some_and(|counterpart| {
+                counterpart.transaction_hash == txn_record.transaction_hash
+                    && counterpart.handoff == txn_record.handoff
+            });
+    if !confirms_same_handoff {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Counterpart must be the other party's confirmation of the same handoff".into(),
+        ));
+    }
+
+    // The item came back when the first party said so, not when the second caught up
+    let expected_returned_at = (txn_record.status == TransactionStatus::Returned)
+        .then(|| counterpart_record.action().timestamp());
+    if txn_record.returned_at != expected_returned_at {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Return time must be when the other party confirmed the drop-off".into(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_catalog(catalog: &CatalogInfo) -> ExternResult<ValidateCallbackResult> {
+    let fields = [
+        ("Brand", &catalog.brand, MAX_CATALOG_FIELD_LENGTH),
+        ("Model", &catalog.model, MAX_CATALOG_FIELD_LENGTH),
+        ("Replacement value", &catalog.replacement_value, MAX_TERMS_FIELD_LENGTH),
+        ("Power details", &catalog.power_details, MAX_CATALOG_FIELD_LENGTH),
+    ];
+    for (name, value, max) in fields {
+        let Some(value) = value else {
+            continue;
+        };
+        if value.trim().is_empty() {
+            return Ok(ValidateCallbackResult::Invalid(format!("{} cannot be blank", name)));
+        }
+        if value.len() > max {
+            return Ok(ValidateCallbackResult::Invalid(format!("{} cannot exceed {} chars", name, max)));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_lending_terms(terms: &LendingTerms) -> ExternResult<ValidateCallbackResult> {
+    let fields = [
+        ("Deposit", &terms.deposit, MAX_TERMS_FIELD_LENGTH),
+        ("Replacement value", &terms.replacement_value, MAX_TERMS_FIELD_LENGTH),
+        ("Care instructions", &terms.care_instructions, MAX_CARE_INSTRUCTIONS_LENGTH),
+    ];
+    if fields.iter().all(|(_, value, _)| value.is_none()) {
+        return Ok(ValidateCallbackResult::Invalid("Lending terms cannot be empty".into()));
+    }
+    for (name, value, max) in fields {
+        let Some(value) = value else {
+            continue;
+        };
+        if value.trim().is_empty() {
+            return Ok(ValidateCallbackResult::Invalid(format!("{} cannot be blank", name)));
+        }
+        if value.len() > max {
+            return Ok(ValidateCallbackResult::Invalid(format!("{} cannot exceed {} chars", name, max)));
+        }
+    }
     Ok(ValidateCallbackResult::Valid)
 }
@@ -3,7 +3,11 @@ use hdi::prelude::*;
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum VouchType {
+    /// A member vouching for a neighbor; counts toward `vouches_required`
+    /// once the voucher is verified
     Neighbor,
+    /// A trusted anchor vouching in that role; counts toward
+    /// `anchor_vouches_required`. Only anchors may author these.
     Anchor,
 }
 
@@ -14,13 +18,158 @@ pub struct Vouch {
     pub vouch_type: VouchType,
     pub created_at: Timestamp,
     pub note: Option<String>,
+    /// The author's TrustedAnchor record; required for Anchor vouches and
+    /// absent otherwise
+    #[serde(default)]
+    pub anchor: Option<ActionHash>,
 }
 
+/// Committed by the anchor themselves
+///
+/// Founding anchors have no nomination; every later anchor must reference an
+/// AnchorNomination naming them.
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq, Eq)]
 pub struct TrustedAnchor {
     pub agent: AgentPubKey,
     pub created_at: Timestamp,
+    #[serde(default)]
+    pub nomination: Option<ActionHash>,
+}
+
+/// An existing anchor's signed offer to make another agent an anchor
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct AnchorNomination {
+    pub nominee: AgentPubKey,
+    /// The nominator's own TrustedAnchor record
+    pub nominator_anchor: ActionHash,
+    pub nominated_at: Timestamp,
+    pub expires_at: Timestamp,
+    pub signature: Signature,
+}
+
+/// Bytes an anchor signs when nominating another agent
+pub fn nomination_payload(nominee: &AgentPubKey, nominated_at: Timestamp, expires_at: Timestamp) -> Vec<u8> {
+    let mut payload = b"OURBLOCK_ANCHOR_NOMINATION".to_vec();
+    payload.extend_from_slice(nominee.get_raw_39());
+    payload.extend_from_slice(&nominated_at.as_micros().to_le_bytes());
+    payload.extend_from_slice(&expires_at.as_micros().to_le_bytes());
+    payload
+}
+
+/// The agent allowed to found the neighborhood without a nomination
+///
+/// Read from the `founding_anchor` DNA property (an `uhCAk...` agent key).
+/// If unset, nobody can become an anchor without a nomination, so the
+/// neighborhood can't be founded until the property is set at install.
+pub fn founding_anchor() -> ExternResult<Option<AgentPubKey>> {
+    let properties = dna_info()?.modifiers.properties;
+    match properties.get("founding_anchor").and_then(|v| v.as_str()) {
+        Some(key) if !key.is_empty() => AgentPubKeyB64::from_b64_str(key)
+            .map(|key| Some(key.into()))
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Invalid founding_anchor property: {:?}", e)))),
+        _ => Ok(None),
+    }
+}
+
+/// A vouch waiting for a neighbor who hasn't joined yet
+///
+/// Only the SHA-256 hash of the one-time invite secret is published; the
+/// inviter hands the secret to the invitee out of band.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct InvitedNeighbor {
+    pub secret_hash: Vec<u8>,
+    pub vouch_type: VouchType,
+    pub note: Option<String>,
+    pub created_at: Timestamp,
+    pub expires_at: Timestamp,
+    /// Public half of the ed25519 key seeded by `invite_key_seed`; redemptions
+    /// are signed with it. Invites without one can't be redeemed.
+    #[serde(default)]
+    pub redeem_key: Option<AgentPubKey>,
+}
+
+/// Proof that the author holds the secret of an InvitedNeighbor, without
+/// publishing it: a signature over `redemption_payload` by the invite's key
+///
+/// Ordered by action timestamp; only the redemption the inviter converts
+/// into a vouch counts, and each invite converts once.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct InviteRedemption {
+    pub invite: ActionHash,
+    pub signature: Signature,
+}
+
+/// Seed of the key an invite secret signs redemptions with. Domain-separated,
+/// since the plain SHA-256 of the secret is published as `secret_hash`.
+pub fn invite_key_seed(secret: &str) -> ExternResult<Vec<u8>> {
+    let mut input = b"OURBLOCK_INVITE_KEY".to_vec();
+    input.extend_from_slice(secret.as_bytes());
+    hash_sha256(input)
+}
+
+pub fn redemption_payload(invite: &ActionHash, redeemer: &AgentPubKey) -> Vec<u8> {
+    let mut payload = b"OURBLOCK_INVITE_REDEMPTION".to_vec();
+    payload.extend_from_slice(invite.get_raw_39());
+    payload.extend_from_slice(redeemer.get_raw_39());
+    payload
+}
+
+/// What a guest pass lets its holder do
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum GuestScope {
+    /// Read the Common Ground feed
+    ViewFeed,
+    /// Direct message the member who issued the pass, and nobody else
+    ChatIssuer,
+}
+
+/// Temporary, limited access for someone who isn't a member, e.g. a
+/// contractor or visiting family, issued by a verified member
+///
+/// The issuer is the author. Holders are limited to `scopes` until
+/// `expires_at`, then to nothing, unless they become members.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct GuestPass {
+    pub guest: AgentPubKey,
+    pub scopes: Vec<GuestScope>,
+    pub issued_at: Timestamp,
+    pub expires_at: Timestamp,
+    pub note: Option<String>,
+}
+
+/// One received vouch offered as evidence in a MembershipProof
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VouchEvidence {
+    pub vouch: ActionHash,
+    /// The voucher's own MembershipProof; required for Neighbor vouches,
+    /// since those only count once the voucher is verified
+    pub voucher_proof: Option<ActionHash>,
+    /// KeyRotations leading from the vouchee to the proof's author, oldest
+    /// first, when the vouch was received by one of the author's earlier keys
+    #[serde(default)]
+    pub rotations: Vec<ActionHash>,
+}
+
+/// Evidence, kept on a member's own chain, that they met the trust policy
+///
+/// Other integrity zomes can't call this zome, so they accept writes only
+/// from authors with one of these earlier on their chain (see the shared
+/// `membership_proof` crate). Validation re-checks the evidence, so a proof
+/// can't claim more than its vouches show. Vouches revoked after the proof
+/// was committed still count; integrity can only see what exists.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct MembershipProof {
+    /// The author's TrustedAnchor record, for anchors; `vouches` is then empty
+    pub anchor: Option<ActionHash>,
+    pub vouches: Vec<VouchEvidence>,
+    /// Vouch hops from an anchor; 0 for anchors themselves
+    pub depth: u32,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -30,16 +179,186 @@ pub enum MembershipStatus {
     Anchor,
 }
 
+impl MembershipStatus {
+    fn code(&self) -> u8 {
+        match self {
+            MembershipStatus::Pending => 0,
+            MembershipStatus::Verified => 1,
+            MembershipStatus::Anchor => 2,
+        }
+    }
+}
+
+/// An anchor's signed statement of an agent's membership status
+///
+/// The signature covers `attestation_payload`, so the attestation can be
+/// checked on its own, e.g. when shown outside the app to settle a dispute.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct MembershipAttestation {
+    pub agent: AgentPubKey,
+    pub status: MembershipStatus,
+    /// When the agent first crossed the verification threshold, if they have
+    pub verified_since: Option<Timestamp>,
+    /// The attesting anchor's TrustedAnchor record
+    pub anchor: ActionHash,
+    pub attested_at: Timestamp,
+    pub signature: Signature,
+}
+
+/// Bytes an anchor signs when attesting membership
+pub fn attestation_payload(
+    agent: &AgentPubKey,
+    status: &MembershipStatus,
+    verified_since: Option<Timestamp>,
+    attested_at: Timestamp,
+) -> Vec<u8> {
+    let mut payload = b"OURBLOCK_MEMBERSHIP".to_vec();
+    payload.extend_from_slice(agent.get_raw_39());
+    payload.push(status.code());
+    payload.extend_from_slice(&verified_since.map(|t| t.as_micros()).unwrap_or(-1).to_le_bytes());
+    payload.extend_from_slice(&attested_at.as_micros().to_le_bytes());
+    payload
+}
+
+/// A neighbor moving to a new agent key, e.g. after losing their device
+///
+/// Committed by the new key, so vouches the old key received keep counting.
+/// The handover is signed over `key_rotation_payload` either by the old key,
+/// when it is still at hand, or by a trusted anchor who has confirmed it in person.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyRotation {
+    pub old_key: AgentPubKey,
+    pub new_key: AgentPubKey,
+    pub rotated_at: Timestamp,
+    pub attestation: RotationAttestation,
+    /// The attester's KeyRetirement naming `new_key`; required
+    #[serde(default)]
+    pub retirement: Option<ActionHash>,
+}
+
+/// An attester handing an agent key over to its one successor
+///
+/// Committed on the attester's own chain: the old key itself, or the anchor
+/// approving the rotation of a lost key. A chain can retire a given key only
+/// once, so an approval can't be reused to mint more successors. A key that
+/// retired itself no longer counts as a member or as a voucher.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq, Eq)]
+pub struct KeyRetirement {
+    pub old_key: AgentPubKey,
+    pub successor: AgentPubKey,
+    pub retired_at: Timestamp,
+    /// The author's TrustedAnchor record, when an anchor retires a lost key
+    pub anchor: Option<ActionHash>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RotationAttestation {
+    /// Signed by the old key
+    OldKey(Signature),
+    /// Countersigned by the anchor whose TrustedAnchor record this is
+    Anchor { anchor: ActionHash, signature: Signature },
+}
+
+/// Bytes signed to approve a key rotation
+pub fn key_rotation_payload(old_key: &AgentPubKey, new_key: &AgentPubKey, rotated_at: Timestamp) -> Vec<u8> {
+    let mut payload = b"OURBLOCK_KEY_ROTATION".to_vec();
+    payload.extend_from_slice(old_key.get_raw_39());
+    payload.extend_from_slice(new_key.get_raw_39());
+    payload.extend_from_slice(&rotated_at.as_micros().to_le_bytes());
+    payload
+}
+
 #[hdk_link_types]
 pub enum LinkTypes {
     AgentToVouchesGiven,
     AgentToVouchesReceived,
     AllAnchors,
+    /// Anchor path derived from the secret hash to the InvitedNeighbor
+    SecretToInvite,
+    AgentToInvites,
+    InviteToRedemptions,
+    /// Marks an invite as converted into a real Vouch
+    InviteToVouch,
+    AgentToAttestations,
+    /// Nominee to the nominations naming them
+    AgentToNominations,
+    /// Guest to their guest passes; permanent, so a guest can't shed their
+    /// restrictions by removing it
+    AgentToGuestPasses,
+    AgentToIssuedGuestPasses,
+    AgentToMembershipProofs,
+    /// New key to the KeyRotations naming its predecessors
+    AgentToKeyRotations,
+    /// Old key to the KeyRetirements handing it over; permanent
+    AgentToKeyRetirements,
 }
 
-pub const VOUCHES_REQUIRED: usize = 2;
-pub const ANCHOR_VOUCHES_REQUIRED: usize = 1;
+/// Defaults used when the DNA properties don't set a trust policy
+pub const DEFAULT_VOUCHES_REQUIRED: usize = 2;
+pub const DEFAULT_ANCHOR_VOUCHES_REQUIRED: usize = 1;
+pub const DEFAULT_MAX_VOUCH_DEPTH: usize = 3;
 pub const MAX_NOTE_LENGTH: usize = 500;
+pub const MAX_INVITE_VALIDITY_SECS: i64 = 30 * 24 * 60 * 60;
+pub const MAX_NOMINATION_VALIDITY_SECS: i64 = 30 * 24 * 60 * 60;
+pub const MAX_GUEST_PASS_VALIDITY_SECS: i64 = 30 * 24 * 60 * 60;
+/// Longest chain of earlier keys whose vouches still count
+pub const MAX_KEY_ROTATIONS: usize = 5;
+
+/// Neighborhood trust rules, set per DNA at install time
+///
+/// Read from the `vouches_required`, `anchor_vouches_required`,
+/// `max_vouch_depth`, and `require_vouching` DNA properties.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrustPolicy {
+    /// Vouches from verified members needed to become verified
+    pub vouches_required: usize,
+    /// Vouches from trusted anchors needed to become verified
+    pub anchor_vouches_required: usize,
+    /// How many vouch hops verification may be delegated away from an anchor
+    pub max_vouch_depth: usize,
+    pub require_vouching: bool,
+}
+
+impl Default for TrustPolicy {
+    fn default() -> Self {
+        Self {
+            vouches_required: DEFAULT_VOUCHES_REQUIRED,
+            anchor_vouches_required: DEFAULT_ANCHOR_VOUCHES_REQUIRED,
+            max_vouch_depth: DEFAULT_MAX_VOUCH_DEPTH,
+            require_vouching: true,
+        }
+    }
+}
+
+/// Load the trust policy from DNA properties, falling back to the defaults
+///
+/// Thresholds of zero would let anyone verify themselves, so they are
+/// clamped to at least one.
+pub fn trust_policy() -> ExternResult<TrustPolicy> {
+    let properties = dna_info()?.modifiers.properties;
+    let defaults = TrustPolicy::default();
+
+    let count = |key: &str, default: usize| {
+        properties
+            .get(key)
+            .and_then(|v| v.as_u64())
+            .map(|v| (v as usize).max(1))
+            .unwrap_or(default)
+    };
+
+    Ok(TrustPolicy {
+        vouches_required: count("vouches_required", defaults.vouches_required),
+        anchor_vouches_required: count("anchor_vouches_required", defaults.anchor_vouches_required),
+        max_vouch_depth: count("max_vouch_depth", defaults.max_vouch_depth),
+        require_vouching: properties
+            .get("require_vouching")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(defaults.require_vouching),
+    })
+}
 
 #[hdk_entry_types]
 #[unit_enum(UnitEntryTypes)]
@@ -48,6 +367,24 @@ pub enum EntryTypes {
     Vouch(Vouch),
     #[entry_type(name = "trusted_anchor", visibility = "public")]
     TrustedAnchor(TrustedAnchor),
+    #[entry_type(name = "invited_neighbor", visibility = "public")]
+    InvitedNeighbor(InvitedNeighbor),
+    #[entry_type(name = "invite_redemption", visibility = "public")]
+    InviteRedemption(InviteRedemption),
+    #[entry_type(name = "membership_attestation", visibility = "public")]
+    MembershipAttestation(MembershipAttestation),
+    #[entry_type(name = "anchor_nomination", visibility = "public")]
+    AnchorNomination(AnchorNomination),
+    #[entry_type(name = "guest_pass", visibility = "public")]
+    GuestPass(GuestPass),
+    /// Must stay at index 7; the shared `membership_proof` crate finds proofs by it
+    #[entry_type(name = "membership_proof", visibility = "public")]
+    MembershipProof(MembershipProof),
+    #[entry_type(name = "key_rotation", visibility = "public")]
+    KeyRotation(KeyRotation),
+    /// Must stay at index 9; the shared `membership_proof` crate finds retirements by it
+    #[entry_type(name = "key_retirement", visibility = "public")]
+    KeyRetirement(KeyRetirement),
 }
 
 #[hdk_extern]
@@ -56,29 +393,450 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
         FlatOp::StoreEntry(store_entry) => match store_entry {
             OpEntry::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::Vouch(vouch) => validate_vouch(vouch, action.author.clone()),
-                EntryTypes::TrustedAnchor(anchor) => validate_anchor(anchor, action.author.clone()),
+                EntryTypes::TrustedAnchor(anchor) => validate_anchor(anchor, action.author.clone(), action.timestamp),
+                EntryTypes::InvitedNeighbor(invite) => validate_invited_neighbor(invite),
+                EntryTypes::InviteRedemption(redemption) => {
+                    validate_invite_redemption(redemption, action.author.clone(), action.timestamp)
+                }
+                EntryTypes::MembershipAttestation(attestation) => {
+                    validate_attestation(attestation, action.author.clone(), action.timestamp)
+                }
+                EntryTypes::AnchorNomination(nomination) => {
+                    validate_nomination(nomination, action.author.clone(), action.timestamp)
+                }
+                EntryTypes::GuestPass(pass) => validate_guest_pass(pass, action.author.clone(), action.timestamp),
+                EntryTypes::MembershipProof(proof) => validate_membership_proof(proof, action.author.clone()),
+                EntryTypes::KeyRotation(rotation) => {
+                    validate_key_rotation(rotation, action.author.clone(), action.timestamp)
+                }
+                EntryTypes::KeyRetirement(retirement) => {
+                    validate_key_retirement(retirement, action.author.clone(), action.timestamp)
+                }
             },
             OpEntry::UpdateEntry { app_entry, .. } => match app_entry {
                 EntryTypes::Vouch(_) => Ok(ValidateCallbackResult::Invalid("Vouches cannot be updated".into())),
                 EntryTypes::TrustedAnchor(_) => Ok(ValidateCallbackResult::Invalid("Anchors cannot be updated".into())),
+                EntryTypes::InvitedNeighbor(_) => Ok(ValidateCallbackResult::Invalid("Invites cannot be updated".into())),
+                EntryTypes::InviteRedemption(_) => {
+                    Ok(ValidateCallbackResult::Invalid("Invite redemptions cannot be updated".into()))
+                }
+                EntryTypes::MembershipAttestation(_) => {
+                    Ok(ValidateCallbackResult::Invalid("Attestations cannot be updated".into()))
+                }
+                EntryTypes::AnchorNomination(_) => {
+                    Ok(ValidateCallbackResult::Invalid("Nominations cannot be updated".into()))
+                }
+                EntryTypes::GuestPass(_) => Ok(ValidateCallbackResult::Invalid("Guest passes cannot be updated".into())),
+                EntryTypes::MembershipProof(_) => {
+                    Ok(ValidateCallbackResult::Invalid("Membership proofs cannot be updated".into()))
+                }
+                EntryTypes::KeyRotation(_) => {
+                    Ok(ValidateCallbackResult::Invalid("Key rotations cannot be updated".into()))
+                }
+                EntryTypes::KeyRetirement(_) => {
+                    Ok(ValidateCallbackResult::Invalid("Key retirements cannot be updated".into()))
+                }
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
         FlatOp::StoreRecord(store_record) => match store_record {
             OpRecord::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::Vouch(vouch) => validate_vouch(vouch, action.author.clone()),
-                EntryTypes::TrustedAnchor(anchor) => validate_anchor(anchor, action.author.clone()),
+                EntryTypes::TrustedAnchor(anchor) => validate_anchor(anchor, action.author.clone(), action.timestamp),
+                EntryTypes::InvitedNeighbor(invite) => validate_invited_neighbor(invite),
+                EntryTypes::InviteRedemption(redemption) => {
+                    validate_invite_redemption(redemption, action.author.clone(), action.timestamp)
+                }
+                EntryTypes::MembershipAttestation(attestation) => {
+                    validate_attestation(attestation, action.author.clone(), action.timestamp)
+                }
+                EntryTypes::AnchorNomination(nomination) => {
+                    validate_nomination(nomination, action.author.clone(), action.timestamp)
+                }
+                EntryTypes::GuestPass(pass) => validate_guest_pass(pass, action.author.clone(), action.timestamp),
+                EntryTypes::MembershipProof(proof) => validate_membership_proof(proof, action.author.clone()),
+                EntryTypes::KeyRotation(rotation) => {
+                    validate_key_rotation(rotation, action.author.clone(), action.timestamp)
+                }
+                EntryTypes::KeyRetirement(retirement) => {
+                    validate_key_retirement(retirement, action.author.clone(), action.timestamp)
+                }
             },
             OpRecord::UpdateEntry { app_entry, .. } => match app_entry {
                 EntryTypes::Vouch(_) => Ok(ValidateCallbackResult::Invalid("Vouches cannot be updated".into())),
                 EntryTypes::TrustedAnchor(_) => Ok(ValidateCallbackResult::Invalid("Anchors cannot be updated".into())),
+                EntryTypes::InvitedNeighbor(_) => Ok(ValidateCallbackResult::Invalid("Invites cannot be updated".into())),
+                EntryTypes::InviteRedemption(_) => {
+                    Ok(ValidateCallbackResult::Invalid("Invite redemptions cannot be updated".into()))
+                }
+                EntryTypes::MembershipAttestation(_) => {
+                    Ok(ValidateCallbackResult::Invalid("Attestations cannot be updated".into()))
+                }
+                EntryTypes::AnchorNomination(_) => {
+                    Ok(ValidateCallbackResult::Invalid("Nominations cannot be updated".into()))
+                }
+                EntryTypes::GuestPass(_) => Ok(ValidateCallbackResult::Invalid("Guest passes cannot be updated".into())),
+                EntryTypes::MembershipProof(_) => {
+                    Ok(ValidateCallbackResult::Invalid("Membership proofs cannot be updated".into()))
+                }
+                EntryTypes::KeyRotation(_) => {
+                    Ok(ValidateCallbackResult::Invalid("Key rotations cannot be updated".into()))
+                }
+                EntryTypes::KeyRetirement(_) => {
+                    Ok(ValidateCallbackResult::Invalid("Key retirements cannot be updated".into()))
+                }
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
+        FlatOp::RegisterCreateLink { link_type, base_address, target_address, tag, action, .. } => match link_type {
+            LinkTypes::InviteToVouch => validate_invite_to_vouch(base_address, target_address, tag, action.author),
+            LinkTypes::InviteToRedemptions => {
+                validate_invite_to_redemption(base_address, target_address, action.author)
+            }
+            LinkTypes::AgentToKeyRotations => validate_key_rotation_link(base_address, target_address, action.author),
+            LinkTypes::AllAnchors => validate_all_anchors_link(target_address, action.author),
+            LinkTypes::AgentToKeyRetirements => validate_key_retirement_link(base_address, target_address),
+            _ => Ok(ValidateCallbackResult::Valid),
+        },
+        FlatOp::RegisterDeleteLink { link_type: LinkTypes::AgentToGuestPasses, .. } => Ok(
+            ValidateCallbackResult::Invalid("Guest pass links cannot be removed; delete the pass instead".into()),
+        ),
+        FlatOp::RegisterDeleteLink { link_type: LinkTypes::AgentToKeyRetirements, .. } => {
+            Ok(ValidateCallbackResult::Invalid("Key retirement links cannot be removed".into()))
+        }
+        FlatOp::RegisterAgentActivity(OpActivity::CreateEntry {
+            app_entry_type: Some(UnitEntryTypes::InviteRedemption),
+            action,
+        }) => validate_invite_redemption_activity(action),
+        FlatOp::RegisterAgentActivity(OpActivity::CreateLink {
+            link_type: Some(LinkTypes::InviteToVouch),
+            action,
+            ..
+        }) => validate_invite_conversion_activity(action),
+        FlatOp::RegisterAgentActivity(OpActivity::CreateEntry {
+            app_entry_type: Some(UnitEntryTypes::KeyRetirement),
+            action,
+        }) => validate_key_retirement_activity(action),
+        FlatOp::RegisterAgentActivity(OpActivity::CreateEntry {
+            app_entry_type: Some(UnitEntryTypes::MembershipProof),
+            action,
+        }) => {
+            if has_retired(&action.author, &action.author, action.prev_action)? {
+                return Ok(ValidateCallbackResult::Invalid("This key has been handed over to a new key".into()));
+            }
+            Ok(ValidateCallbackResult::Valid)
+        }
+        FlatOp::RegisterDelete(OpDelete { action }) => validate_delete(action),
         _ => Ok(ValidateCallbackResult::Valid),
     }
 }
 
+/// Only the author of a record may delete it, so a guest can't delete the
+/// pass that limits them. Key retirements are permanent.
+fn validate_delete(action: Delete) -> ExternResult<ValidateCallbackResult> {
+    let original = must_get_valid_record(action.deletes_address)?;
+    if original.action().author() != &action.author {
+        return Ok(ValidateCallbackResult::Invalid("Only the author can delete this".into()));
+    }
+    if matches!(original.entry().to_app_option::<KeyRetirement>(), Ok(Some(_))) {
+        return Ok(ValidateCallbackResult::Invalid("Key retirements cannot be deleted".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_guest_pass(
+    pass: GuestPass,
+    author: AgentPubKey,
+    timestamp: Timestamp,
+) -> ExternResult<ValidateCallbackResult> {
+    if pass.guest == author {
+        return Ok(ValidateCallbackResult::Invalid("Cannot issue a guest pass to yourself".into()));
+    }
+    if pass.scopes.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("A guest pass must grant at least one scope".into()));
+    }
+    if pass.issued_at > timestamp {
+        return Ok(ValidateCallbackResult::Invalid("Guest pass cannot be dated in the future".into()));
+    }
+    let validity = pass.expires_at.as_micros() - pass.issued_at.as_micros();
+    if validity <= 0 || validity > MAX_GUEST_PASS_VALIDITY_SECS * 1_000_000 {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Guest passes must expire within {} days",
+            MAX_GUEST_PASS_VALIDITY_SECS / (24 * 60 * 60)
+        )));
+    }
+    if let Some(ref note) = pass.note {
+        if note.len() > MAX_NOTE_LENGTH {
+            return Ok(ValidateCallbackResult::Invalid(format!("Note cannot exceed {} chars", MAX_NOTE_LENGTH)));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_membership_proof(proof: MembershipProof, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if let Some(anchor_hash) = proof.anchor {
+        if !proof.vouches.is_empty() || proof.depth != 0 {
+            return Ok(ValidateCallbackResult::Invalid("Anchor proofs carry no vouches and have depth 0".into()));
+        }
+        let anchor_record = must_get_valid_record(anchor_hash)?;
+        return Ok(match anchor_record.entry().to_app_option::<TrustedAnchor>() {
+            Ok(Some(anchor)) if anchor.agent == author => ValidateCallbackResult::Valid,
+            _ => ValidateCallbackResult::Invalid("Anchor proofs must reference the author's trusted anchor".into()),
+        });
+    }
+
+    let policy = trust_policy()?;
+    let mut vouchers = Vec::new();
+    let mut anchor_vouches = 0;
+    let mut voucher_depths = Vec::new();
+
+    for evidence in proof.vouches {
+        let vouch_record = must_get_valid_record(evidence.vouch)?;
+        let Ok(Some(vouch)) = vouch_record.entry().to_app_option::<Vouch>() else {
+            return Ok(ValidateCallbackResult::Invalid("Membership evidence must be vouches".into()));
+        };
+        let voucher = vouch_record.action().author().clone();
+        if let Some(before_vouch) = vouch_record.action().prev_action() {
+            if has_retired(&voucher, &voucher, before_vouch.clone())? {
+                return Ok(ValidateCallbackResult::Invalid("Vouches from a retired key don't count".into()));
+            }
+        }
+        let lineage = match rotation_lineage(&vouch.vouchee, &evidence.rotations)? {
+            Ok(lineage) => lineage,
+            Err(reason) => return Ok(ValidateCallbackResult::Invalid(reason)),
+        };
+        if lineage.last() != Some(&author) {
+            return Ok(ValidateCallbackResult::Invalid("Membership evidence must be vouches for the author".into()));
+        }
+        if lineage.contains(&voucher) {
+            return Ok(ValidateCallbackResult::Invalid("An earlier key cannot vouch for its successor".into()));
+        }
+        if vouchers.contains(&voucher) {
+            return Ok(ValidateCallbackResult::Invalid("Each vouch must come from a different voucher".into()));
+        }
+        vouchers.push(voucher.clone());
+
+        // Anchor vouches were checked against the voucher's TrustedAnchor when committed
+        if vouch.vouch_type == VouchType::Anchor {
+            anchor_vouches += 1;
+            voucher_depths.push(0);
+            continue;
+        }
+
+        let Some(voucher_proof) = evidence.voucher_proof else {
+            return Ok(ValidateCallbackResult::Invalid("Neighbor vouches need the voucher's membership proof".into()));
+        };
+        let proof_record = must_get_valid_record(voucher_proof)?;
+        match proof_record.entry().to_app_option::<MembershipProof>() {
+            Ok(Some(voucher_proof)) if proof_record.action().author() == &voucher => {
+                voucher_depths.push(voucher_proof.depth)
+            }
+            _ => {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Voucher proofs must be membership proofs by the voucher".into(),
+                ))
+            }
+        }
+    }
+
+    let depth = if anchor_vouches >= policy.anchor_vouches_required {
+        Some(1)
+    } else {
+        // Only as close to an anchor as the furthest voucher needed to meet the threshold
+        voucher_depths.sort_unstable();
+        voucher_depths.get(policy.vouches_required - 1).map(|depth| depth + 1)
+    };
+    match depth {
+        None => Ok(ValidateCallbackResult::Invalid("Not enough vouches to meet the trust policy".into())),
+        Some(depth) if depth as usize > policy.max_vouch_depth => Ok(ValidateCallbackResult::Invalid(format!(
+            "Vouches must lead back to an anchor within {} hops",
+            policy.max_vouch_depth
+        ))),
+        Some(depth) if depth != proof.depth => {
+            Ok(ValidateCallbackResult::Invalid(format!("Evidence shows depth {}, not {}", depth, proof.depth)))
+        }
+        Some(_) => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
+/// The keys a chain of KeyRotations leads through, starting at `from`
+fn rotation_lineage(from: &AgentPubKey, rotations: &[ActionHash]) -> ExternResult<Result<Vec<AgentPubKey>, String>> {
+    if rotations.len() > MAX_KEY_ROTATIONS {
+        return Ok(Err(format!("Vouches can follow at most {} key rotations", MAX_KEY_ROTATIONS)));
+    }
+    let mut lineage = vec![from.clone()];
+    for rotation_hash in rotations {
+        let record = must_get_valid_record(rotation_hash.clone())?;
+        let Ok(Some(rotation)) = record.entry().to_app_option::<KeyRotation>() else {
+            return Ok(Err("Rotation evidence must be key rotations".into()));
+        };
+        if lineage.last() != Some(&rotation.old_key) {
+            return Ok(Err("Key rotations must follow on from each other".into()));
+        }
+        lineage.push(rotation.new_key);
+    }
+    Ok(Ok(lineage))
+}
+
+fn validate_key_rotation(
+    rotation: KeyRotation,
+    author: AgentPubKey,
+    timestamp: Timestamp,
+) -> ExternResult<ValidateCallbackResult> {
+    if rotation.new_key != author {
+        return Ok(ValidateCallbackResult::Invalid("Key rotations must be committed by the new key".into()));
+    }
+    if rotation.old_key == rotation.new_key {
+        return Ok(ValidateCallbackResult::Invalid("A key cannot rotate to itself".into()));
+    }
+    if rotation.rotated_at > timestamp {
+        return Ok(ValidateCallbackResult::Invalid("Key rotation cannot be dated in the future".into()));
+    }
+
+    let payload = key_rotation_payload(&rotation.old_key, &rotation.new_key, rotation.rotated_at);
+    let attester = match rotation.attestation {
+        RotationAttestation::OldKey(signature) => {
+            if !verify_signature(rotation.old_key.clone(), signature, payload)? {
+                return Ok(ValidateCallbackResult::Invalid("Key rotation signature does not match the old key".into()));
+            }
+            rotation.old_key.clone()
+        }
+        RotationAttestation::Anchor { anchor, signature } => {
+            let anchor_record = must_get_valid_record(anchor)?;
+            let Ok(Some(anchor)) = anchor_record.entry().to_app_option::<TrustedAnchor>() else {
+                return Ok(ValidateCallbackResult::Invalid("Key rotation must reference a trusted anchor".into()));
+            };
+            if anchor_record.action().author() != &anchor.agent {
+                return Ok(ValidateCallbackResult::Invalid("Key rotation must reference a trusted anchor".into()));
+            }
+            if !verify_signature(anchor.agent.clone(), signature, payload)? {
+                return Ok(ValidateCallbackResult::Invalid("Key rotation signature does not match the anchor".into()));
+            }
+            anchor.agent
+        }
+    };
+
+    // The attester must have retired the old key in favour of this key, and only once
+    let Some(retirement_hash) = rotation.retirement else {
+        return Ok(ValidateCallbackResult::Invalid("Key rotation must reference the old key's retirement".into()));
+    };
+    let retirement_record = must_get_valid_record(retirement_hash)?;
+    let Ok(Some(retirement)) = retirement_record.entry().to_app_option::<KeyRetirement>() else {
+        return Ok(ValidateCallbackResult::Invalid("Key rotation must reference the old key's retirement".into()));
+    };
+    if retirement_record.action().author() != &attester {
+        return Ok(ValidateCallbackResult::Invalid("The rotation's approver must retire the old key".into()));
+    }
+    if retirement.old_key != rotation.old_key || retirement.successor != rotation.new_key {
+        return Ok(ValidateCallbackResult::Invalid("The retirement hands the old key to a different key".into()));
+    }
+    if let Some(before) = retirement_record.action().prev_action() {
+        if has_retired(&attester, &rotation.old_key, before.clone())? {
+            return Ok(ValidateCallbackResult::Invalid("The old key was already handed over to another key".into()));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_key_retirement(
+    retirement: KeyRetirement,
+    author: AgentPubKey,
+    timestamp: Timestamp,
+) -> ExternResult<ValidateCallbackResult> {
+    if retirement.old_key == retirement.successor {
+        return Ok(ValidateCallbackResult::Invalid("A key cannot be handed over to itself".into()));
+    }
+    if retirement.retired_at > timestamp {
+        return Ok(ValidateCallbackResult::Invalid("Key retirement cannot be dated in the future".into()));
+    }
+    match retirement.anchor {
+        None if retirement.old_key == author => Ok(ValidateCallbackResult::Valid),
+        None => Ok(ValidateCallbackResult::Invalid("Only the key itself or an anchor can retire a key".into())),
+        Some(_) if retirement.old_key == author => Ok(ValidateCallbackResult::Invalid(
+            "A key retiring itself doesn't reference an anchor".into(),
+        )),
+        Some(anchor_hash) => {
+            let anchor_record = must_get_valid_record(anchor_hash)?;
+            match anchor_record.entry().to_app_option::<TrustedAnchor>() {
+                Ok(Some(anchor)) if anchor.agent == author && anchor_record.action().author() == &author => {
+                    Ok(ValidateCallbackResult::Valid)
+                }
+                _ => Ok(ValidateCallbackResult::Invalid("Only trusted anchors can retire someone else's key".into())),
+            }
+        }
+    }
+}
+
+/// Each chain may retire a given key once
+///
+/// Checked on the author's chain, where every earlier retirement is visible.
+fn validate_key_retirement_activity(action: Create) -> ExternResult<ValidateCallbackResult> {
+    let Ok(retirement) = KeyRetirement::try_from(must_get_entry(action.entry_hash.clone())?.content) else {
+        return Ok(ValidateCallbackResult::Invalid("Not a key retirement".into()));
+    };
+    if has_retired(&action.author, &retirement.old_key, action.prev_action)? {
+        return Ok(ValidateCallbackResult::Invalid("This key has already been handed over to a new key".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Whether `author` retired `old_key` at or before `up_to` on their chain
+fn has_retired(author: &AgentPubKey, old_key: &AgentPubKey, up_to: ActionHash) -> ExternResult<bool> {
+    let retirement_type: EntryType = UnitEntryTypes::KeyRetirement.try_into()?;
+    let activity = must_get_agent_activity(author.clone(), ChainFilter::new(up_to))?;
+    for item in activity {
+        let earlier = item.action.action();
+        if !matches!(earlier, Action::Create(_)) || earlier.entry_type() != Some(&retirement_type) {
+            continue;
+        }
+        let Some(entry_hash) = earlier.entry_hash() else {
+            continue;
+        };
+        let retired = KeyRetirement::try_from(must_get_entry(entry_hash.clone())?.content)
+            .is_ok_and(|retirement| &retirement.old_key == old_key);
+        if retired {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+/// Retirement links hang off the key being retired
+fn validate_key_retirement_link(
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(retirement_hash) = target_address.into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("Key retirement links must point to a key retirement".into()));
+    };
+    let record = must_get_valid_record(retirement_hash)?;
+    let Ok(Some(retirement)) = record.entry().to_app_option::<KeyRetirement>() else {
+        return Ok(ValidateCallbackResult::Invalid("Key retirement links must point to a key retirement".into()));
+    };
+    if base_address.into_agent_pub_key() != Some(retirement.old_key) {
+        return Ok(ValidateCallbackResult::Invalid("Key retirement links must start at the retired key".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_key_rotation_link(
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    author: AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(rotation_hash) = target_address.into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("Key rotation links must point to a key rotation".into()));
+    };
+    let record = must_get_valid_record(rotation_hash)?;
+    let Ok(Some(rotation)) = record.entry().to_app_option::<KeyRotation>() else {
+        return Ok(ValidateCallbackResult::Invalid("Key rotation links must point to a key rotation".into()));
+    };
+    if base_address.into_agent_pub_key() != Some(rotation.new_key.clone()) || rotation.new_key != author {
+        return Ok(ValidateCallbackResult::Invalid("Only the new key can link its key rotation".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
 fn validate_vouch(vouch: Vouch, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
     if vouch.vouchee == author {
         return Ok(ValidateCallbackResult::Invalid("Cannot vouch for yourself".into()));
@@ -88,12 +846,286 @@ fn validate_vouch(vouch: Vouch, author: AgentPubKey) -> ExternResult<ValidateCal
             return Ok(ValidateCallbackResult::Invalid(format!("Note cannot exceed {} chars", MAX_NOTE_LENGTH)));
         }
     }
-    Ok(ValidateCallbackResult::Valid)
+
+    match (&vouch.vouch_type, vouch.anchor) {
+        (VouchType::Neighbor, None) => Ok(ValidateCallbackResult::Valid),
+        (VouchType::Neighbor, Some(_)) => Ok(ValidateCallbackResult::Invalid(
+            "Only anchor vouches reference an anchor record".into(),
+        )),
+        (VouchType::Anchor, None) => Ok(ValidateCallbackResult::Invalid(
+            "Anchor vouches must reference the author's trusted anchor".into(),
+        )),
+        (VouchType::Anchor, Some(anchor_hash)) => {
+            let anchor_record = must_get_valid_record(anchor_hash)?;
+            let Ok(Some(anchor)) = anchor_record.entry().to_app_option::<TrustedAnchor>() else {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Anchor vouches must reference a trusted anchor".into(),
+                ));
+            };
+            if anchor.agent != author {
+                return Ok(ValidateCallbackResult::Invalid("Only trusted anchors can give anchor vouches".into()));
+            }
+            Ok(ValidateCallbackResult::Valid)
+        }
+    }
 }
 
-fn validate_anchor(anchor: TrustedAnchor, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+fn validate_anchor(
+    anchor: TrustedAnchor,
+    author: AgentPubKey,
+    timestamp: Timestamp,
+) -> ExternResult<ValidateCallbackResult> {
     if anchor.agent != author {
         return Ok(ValidateCallbackResult::Invalid("Anchor agent must match author".into()));
     }
+
+    let Some(nomination_hash) = anchor.nomination else {
+        // Founding ceremony: only the configured founder may self-designate
+        return match founding_anchor()? {
+            Some(founder) if founder == author => Ok(ValidateCallbackResult::Valid),
+            _ => Ok(ValidateCallbackResult::Invalid(
+                "Only the founding anchor can become an anchor without a nomination".into(),
+            )),
+        };
+    };
+
+    let nomination_record = must_get_valid_record(nomination_hash)?;
+    let Ok(Some(nomination)) = nomination_record.entry().to_app_option::<AnchorNomination>() else {
+        return Ok(ValidateCallbackResult::Invalid("Anchor must reference a nomination".into()));
+    };
+    if nomination.nominee != author {
+        return Ok(ValidateCallbackResult::Invalid("Nomination is for a different agent".into()));
+    }
+    if timestamp > nomination.expires_at {
+        return Ok(ValidateCallbackResult::Invalid("Nomination has expired".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Anchor listings must point at the link author's own valid TrustedAnchor
+fn validate_all_anchors_link(target_address: AnyLinkableHash, author: AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    let Some(anchor_hash) = target_address.into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("Anchor links must point to a trusted anchor record".into()));
+    };
+    let record = must_get_valid_record(anchor_hash)?;
+    match record.entry().to_app_option::<TrustedAnchor>() {
+        Ok(Some(anchor)) if record.action().author() == &author && anchor.agent == author => {
+            Ok(ValidateCallbackResult::Valid)
+        }
+        _ => Ok(ValidateCallbackResult::Invalid("Only an anchor can list their own trusted anchor record".into())),
+    }
+}
+
+fn validate_nomination(
+    nomination: AnchorNomination,
+    author: AgentPubKey,
+    timestamp: Timestamp,
+) -> ExternResult<ValidateCallbackResult> {
+    if nomination.nominee == author {
+        return Ok(ValidateCallbackResult::Invalid("Cannot nominate yourself".into()));
+    }
+    if nomination.nominated_at > timestamp {
+        return Ok(ValidateCallbackResult::Invalid("Nomination cannot be dated in the future".into()));
+    }
+    let validity = nomination.expires_at.as_micros() - nomination.nominated_at.as_micros();
+    if validity <= 0 || validity > MAX_NOMINATION_VALIDITY_SECS * 1_000_000 {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Nominations must expire within {} days",
+            MAX_NOMINATION_VALIDITY_SECS / (24 * 60 * 60)
+        )));
+    }
+
+    let anchor_record = must_get_valid_record(nomination.nominator_anchor.clone())?;
+    let Ok(Some(anchor)) = anchor_record.entry().to_app_option::<TrustedAnchor>() else {
+        return Ok(ValidateCallbackResult::Invalid("Nominator must reference their trusted anchor".into()));
+    };
+    if anchor.agent != author {
+        return Ok(ValidateCallbackResult::Invalid("Only the referenced anchor can nominate".into()));
+    }
+
+    let payload = nomination_payload(&nomination.nominee, nomination.nominated_at, nomination.expires_at);
+    if !verify_signature(author, nomination.signature, payload)? {
+        return Ok(ValidateCallbackResult::Invalid("Nomination signature does not match the anchor".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_invited_neighbor(invite: InvitedNeighbor) -> ExternResult<ValidateCallbackResult> {
+    if invite.secret_hash.len() != 32 {
+        return Ok(ValidateCallbackResult::Invalid("Invite secret hash must be a SHA-256 digest".into()));
+    }
+    if let Some(ref note) = invite.note {
+        if note.len() > MAX_NOTE_LENGTH {
+            return Ok(ValidateCallbackResult::Invalid(format!("Note cannot exceed {} chars", MAX_NOTE_LENGTH)));
+        }
+    }
+    let validity = invite.expires_at.as_micros() - invite.created_at.as_micros();
+    if validity <= 0 || validity > MAX_INVITE_VALIDITY_SECS * 1_000_000 {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invites must expire within {} days",
+            MAX_INVITE_VALIDITY_SECS / (24 * 60 * 60)
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_invite_redemption(
+    redemption: InviteRedemption,
+    author: AgentPubKey,
+    timestamp: Timestamp,
+) -> ExternResult<ValidateCallbackResult> {
+    let invite_record = must_get_valid_record(redemption.invite.clone())?;
+    let Ok(Some(invite)) = invite_record.entry().to_app_option::<InvitedNeighbor>() else {
+        return Ok(ValidateCallbackResult::Invalid("Redemption must reference an invite".into()));
+    };
+    if invite_record.action().author() == &author {
+        return Ok(ValidateCallbackResult::Invalid("Cannot redeem your own invite".into()));
+    }
+    let Some(redeem_key) = invite.redeem_key else {
+        return Ok(ValidateCallbackResult::Invalid("This invite predates signed redemptions".into()));
+    };
+    let payload = redemption_payload(&redemption.invite, &author);
+    if !verify_signature_raw(redeem_key, redemption.signature, payload)? {
+        return Ok(ValidateCallbackResult::Invalid("Redemption is not signed with the invite's key".into()));
+    }
+    if timestamp > invite.expires_at {
+        return Ok(ValidateCallbackResult::Invalid("Invite has expired".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Redemption links must point at the link author's own redemption of the base invite
+fn validate_invite_to_redemption(
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    author: AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let (Some(invite_hash), Some(redemption_hash)) =
+        (base_address.into_action_hash(), target_address.into_action_hash())
+    else {
+        return Ok(ValidateCallbackResult::Invalid("Redemption links must join action hashes".into()));
+    };
+    let record = must_get_valid_record(redemption_hash)?;
+    let Ok(Some(redemption)) = record.entry().to_app_option::<InviteRedemption>() else {
+        return Ok(ValidateCallbackResult::Invalid("Redemption link target must be a redemption".into()));
+    };
+    if record.action().author() != &author || redemption.invite != invite_hash {
+        return Ok(ValidateCallbackResult::Invalid("Redemption link must match its redemption".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Only the inviter may mark their invite as converted, only to a vouch they
+/// authored, and only for the author of the redemption named in the tag
+fn validate_invite_to_vouch(
+    base_address: AnyLinkableHash,
+    target_address: AnyLinkableHash,
+    tag: LinkTag,
+    author: AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(invite_hash) = base_address.into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("Invite link base must be an action hash".into()));
+    };
+    if must_get_valid_record(invite_hash.clone())?.action().author() != &author {
+        return Ok(ValidateCallbackResult::Invalid("Only the inviter can convert an invite".into()));
+    }
+    let Some(vouch_hash) = target_address.into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid("Invite link target must be a vouch action hash".into()));
+    };
+    let vouch_record = must_get_valid_record(vouch_hash)?;
+    let Ok(Some(vouch)) = vouch_record.entry().to_app_option::<Vouch>() else {
+        return Ok(ValidateCallbackResult::Invalid("Invite must convert to the inviter's own vouch".into()));
+    };
+    if vouch_record.action().author() != &author {
+        return Ok(ValidateCallbackResult::Invalid("Invite must convert to the inviter's own vouch".into()));
+    }
+    let Ok(redemption_hash) = ActionHash::try_from_raw_39(tag.0) else {
+        return Ok(ValidateCallbackResult::Invalid("Invite link tag must be the redemption's action hash".into()));
+    };
+    let redemption_record = must_get_valid_record(redemption_hash)?;
+    let Ok(Some(redemption)) = redemption_record.entry().to_app_option::<InviteRedemption>() else {
+        return Ok(ValidateCallbackResult::Invalid("Invite link tag must name a redemption".into()));
+    };
+    if redemption.invite != invite_hash || redemption_record.action().author() != &vouch.vouchee {
+        return Ok(ValidateCallbackResult::Invalid("Invite must convert to a vouch for its redeemer".into()));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// An agent redeems each invite at most once
+fn validate_invite_redemption_activity(action: Create) -> ExternResult<ValidateCallbackResult> {
+    let Ok(redemption) = InviteRedemption::try_from(must_get_entry(action.entry_hash.clone())?.content) else {
+        return Ok(ValidateCallbackResult::Invalid("Not an invite redemption".into()));
+    };
+    let redemption_type: EntryType = UnitEntryTypes::InviteRedemption.try_into()?;
+    let activity = must_get_agent_activity(action.author, ChainFilter::new(action.prev_action))?;
+    for item in activity {
+        let earlier = item.action.action();
+        if !matches!(earlier, Action::Create(_)) || earlier.entry_type() != Some(&redemption_type) {
+            continue;
+        }
+        let Some(entry_hash) = earlier.entry_hash() else {
+            continue;
+        };
+        let repeated = InviteRedemption::try_from(must_get_entry(entry_hash.clone())?.content)
+            .is_ok_and(|earlier| earlier.invite == redemption.invite);
+        if repeated {
+            return Ok(ValidateCallbackResult::Invalid("You have already redeemed this invite".into()));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// An invite converts into a single vouch, so it is redeemed once no matter
+/// how many agents learn its secret
+fn validate_invite_conversion_activity(action: CreateLink) -> ExternResult<ValidateCallbackResult> {
+    let conversion: ScopedLinkType = LinkTypes::InviteToVouch.try_into()?;
+    let activity = must_get_agent_activity(action.author, ChainFilter::new(action.prev_action))?;
+    for item in activity {
+        let Action::CreateLink(earlier) = item.action.action() else {
+            continue;
+        };
+        if earlier.zome_index == conversion.zome_index
+            && earlier.link_type == conversion.zome_type
+            && earlier.base_address == action.base_address
+        {
+            return Ok(ValidateCallbackResult::Invalid("This invite has already been converted".into()));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_attestation(
+    attestation: MembershipAttestation,
+    author: AgentPubKey,
+    timestamp: Timestamp,
+) -> ExternResult<ValidateCallbackResult> {
+    if attestation.agent == author {
+        return Ok(ValidateCallbackResult::Invalid("Anchors cannot attest their own membership".into()));
+    }
+    if attestation.attested_at > timestamp {
+        return Ok(ValidateCallbackResult::Invalid("Attestation cannot be dated in the future".into()));
+    }
+    if attestation.verified_since.is_some_and(|since| since > attestation.attested_at) {
+        return Ok(ValidateCallbackResult::Invalid("Verification cannot start after the attestation".into()));
+    }
+
+    let anchor_record = must_get_valid_record(attestation.anchor.clone())?;
+    let Ok(Some(anchor)) = anchor_record.entry().to_app_option::<TrustedAnchor>() else {
+        return Ok(ValidateCallbackResult::Invalid("Attestation must reference a trusted anchor".into()));
+    };
+    if anchor.agent != author {
+        return Ok(ValidateCallbackResult::Invalid("Only the referenced anchor can attest".into()));
+    }
+
+    let payload = attestation_payload(
+        &attestation.agent,
+        &attestation.status,
+        attestation.verified_since,
+        attestation.attested_at,
+    );
+    if !verify_signature(author, attestation.signature, payload)? {
+        return Ok(ValidateCallbackResult::Invalid("Attestation signature does not match the anchor".into()));
+    }
     Ok(ValidateCallbackResult::Valid)
 }
@@ -0,0 +1,75 @@
+//! Duplicate-submission protection for create externs
+//!
+//! Clients may send an `idempotency_key` with a create call (one per form
+//! submission, e.g. a UUID). The coordinator puts the key in the tag of the
+//! link it already creates from the author to the new record, and before
+//! creating anything it scans its own recent source chain for a link with the
+//! same tag. A double-tap or a retry after a timeout then gets the original
+//! record back instead of creating a duplicate.
+
+use hdk::prelude::*;
+
+pub const MAX_IDEMPOTENCY_KEY_LENGTH: usize = 128;
+
+/// Keys are only remembered this long; after that the same key creates anew
+pub const IDEMPOTENCY_WINDOW_MICROS: i64 = 24 * 60 * 60 * 1_000_000;
+
+const TAG_PREFIX: &[u8] = b"idempotency:";
+
+/// The tag for the author link: the key if one was given, else empty
+pub fn idempotency_tag(key: Option<&str>) -> ExternResult<LinkTag> {
+    let Some(key) = key else {
+        return Ok(LinkTag::new(Vec::new()));
+    };
+    if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LENGTH {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Idempotency key must be 1-{} bytes",
+            MAX_IDEMPOTENCY_KEY_LENGTH
+        ))));
+    }
+    Ok(LinkTag::new([TAG_PREFIX, key.as_bytes()].concat()))
+}
+
+/// The record created earlier with this key from `base`, if any
+///
+/// Only looks at the caller's own chain, so it needs no network and can't be
+/// fooled by links someone else wrote. The link type (and so the zome) must
+/// match too, since one agent key is the base of every zome's author links.
+pub fn find_previous_create<T, E>(
+    key: Option<&str>,
+    base: impl Into<AnyLinkableHash>,
+    link_type: T,
+) -> ExternResult<Option<ActionHash>>
+where
+    ScopedLinkType: TryFrom<T, Error = E>,
+    WasmError: From<E>,
+{
+    if key.is_none() {
+        return Ok(None);
+    }
+    let tag = idempotency_tag(key)?;
+    let base = base.into();
+    let ScopedLinkType { zome_index, zome_type } = ScopedLinkType::try_from(link_type)?;
+    let cutoff = sys_time()?.as_micros() - IDEMPOTENCY_WINDOW_MICROS;
+
+    let records = query(ChainQueryFilter::new().action_type(ActionType::CreateLink))?;
+
+    // Newest first, stopping once we're past the window
+    for record in records.iter().rev() {
+        let Action::CreateLink(create_link) = record.action() else {
+            continue;
+        };
+        if create_link.timestamp.as_micros() < cutoff {
+            break;
+        }
+        if create_link.base_address == base
+            && create_link.zome_index == zome_index
+            && create_link.link_type == zome_type
+            && create_link.tag == tag
+        {
+            return Ok(create_link.target_address.clone().into_action_hash());
+        }
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,36 @@
+//! Where list externs read their links from
+//!
+//! Listing links with `GetStrategy::Local` is fast, but an agent that just
+//! joined holds almost nothing locally and would see empty lists until gossip
+//! catches up. List externs read through `get_list_links` instead: it reads
+//! locally and, if that finds nothing, asks the network. Callers that know
+//! their local view is stale (e.g. a pull-to-refresh) can set
+//! `prefer_network` to skip the local read entirely.
+
+use hdk::prelude::*;
+
+/// Optional argument for list externs; clients may send `null` for the defaults
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct ListOptions {
+    /// Read links from the network even when some are held locally
+    #[serde(default)]
+    pub prefer_network: bool,
+}
+
+/// Links from `base`, local first with a network fallback when there are none
+pub fn get_list_links(
+    base: impl Into<AnyLinkableHash>,
+    link_type: impl LinkTypeFilterExt + Clone,
+    prefer_network: bool,
+) -> ExternResult<Vec<Link>> {
+    let base = base.into();
+    if prefer_network {
+        return get_links(LinkQuery::try_new(base, link_type)?, GetStrategy::Network);
+    }
+
+    let links = get_links(LinkQuery::try_new(base.clone(), link_type.clone())?, GetStrategy::Local)?;
+    if !links.is_empty() {
+        return Ok(links);
+    }
+    get_links(LinkQuery::try_new(base, link_type)?, GetStrategy::Network)
+}
@@ -0,0 +1,49 @@
+//! Shared location type for requests, items, events, and spaces
+//!
+//! A location is a free-text label plus an optional geohash. Geohashes are
+//! capped at MAX_GEOHASH_PRECISION characters so nobody publishes a
+//! doorstep-accurate position; clients truncate before saving.
+//!
+//! This crate only depends on serde so integrity crates can use it too.
+
+use serde::{Deserialize, Serialize};
+
+pub const MAX_LABEL_LENGTH: usize = 100;
+/// Six characters is a cell of roughly 1.2km by 0.6km
+pub const MAX_GEOHASH_PRECISION: usize = 6;
+
+const GEOHASH_ALPHABET: &str = "0123456789bcdefghjkmnpqrstuvwxyz";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Location {
+    /// e.g. "Corner of Elm & 5th" or "Community garden"
+    pub label: String,
+    /// Approximate position, used for nearby queries
+    pub geohash: Option<String>,
+}
+
+/// Check a location's label and geohash; the error is the validation message
+pub fn validate_location(location: &Location) -> Result<(), String> {
+    if location.label.trim().is_empty() {
+        return Err("Location label cannot be empty".to_string());
+    }
+    if location.label.len() > MAX_LABEL_LENGTH {
+        return Err(format!("Location label cannot exceed {} chars", MAX_LABEL_LENGTH));
+    }
+    if let Some(ref geohash) = location.geohash {
+        if !is_valid_geohash(geohash) {
+            return Err(format!(
+                "Geohash must be 1-{} lowercase geohash characters",
+                MAX_GEOHASH_PRECISION
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Lowercase base32 geohash of at most MAX_GEOHASH_PRECISION characters
+pub fn is_valid_geohash(geohash: &str) -> bool {
+    !geohash.is_empty()
+        && geohash.len() <= MAX_GEOHASH_PRECISION
+        && geohash.chars().all(|c| GEOHASH_ALPHABET.contains(c))
+}
@@ -0,0 +1,93 @@
+//! Integrity-side check that an author is a verified member
+//!
+//! Coordinator zomes ask the vouch zome before writing, but a patched
+//! coordinator or a direct zome call skips that. Integrity zomes can't call
+//! the vouch zome, so members keep a `MembershipProof` (validated by
+//! vouch_integrity against its vouch evidence) on their own chain, and
+//! `require_membership_proof` looks for one in the author's agent activity
+//! before the action being validated. Call it from `RegisterAgentActivity`,
+//! whose validators hold the author's chain. A key that has since handed
+//! itself over to a new key (a `KeyRetirement` it committed) no longer counts.
+
+use hdi::prelude::*;
+
+const VOUCH_INTEGRITY_ZOME: &str = "vouch_integrity";
+
+/// Position of `MembershipProof` in vouch_integrity's `EntryTypes`
+const MEMBERSHIP_PROOF_ENTRY_INDEX: u8 = 7;
+
+/// Position of `KeyRetirement` in vouch_integrity's `EntryTypes`
+const KEY_RETIREMENT_ENTRY_INDEX: u8 = 9;
+
+/// The part of vouch_integrity's `KeyRetirement` this check reads
+#[hdk_entry_helper]
+struct RetiredKey {
+    old_key: AgentPubKey,
+}
+
+/// Valid if the author committed a valid MembershipProof before `prev_action`
+/// and hasn't retired their key since, or if the neighborhood doesn't require
+/// vouching
+///
+/// A neighborhood installed without a `founding_anchor` predates the trust
+/// graph: nobody there can ever be vouched in, so the check is skipped rather
+/// than making it read-only. New installs can't end up like this, since
+/// genesis refuses a vouching neighborhood without a founder.
+pub fn require_membership_proof(
+    author: &AgentPubKey,
+    prev_action: &ActionHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let dna = dna_info()?;
+    let properties = &dna.modifiers.properties;
+    let require_vouching = properties
+        .get("require_vouching")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(true);
+    let has_founder = properties
+        .get("founding_anchor")
+        .and_then(|v| v.as_str())
+        .is_some_and(|key| !key.is_empty());
+    if !require_vouching || !has_founder {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+
+    let vouch_zome = ZomeName::from(VOUCH_INTEGRITY_ZOME);
+    let Some(zome_index) = dna.zome_names.iter().position(|name| *name == vouch_zome) else {
+        return Ok(ValidateCallbackResult::Invalid("This DNA has no vouch zome to verify members".into()));
+    };
+
+    let activity = must_get_agent_activity(author.clone(), ChainFilter::new(prev_action.clone()))?;
+    let mut proof = None;
+    for item in activity {
+        let entry_index = match (item.action.action(), item.action.action().entry_type()) {
+            (Action::Create(_), Some(EntryType::App(def))) if def.zome_index.0 as usize == zome_index => {
+                def.entry_index.0
+            }
+            _ => continue,
+        };
+        if entry_index == MEMBERSHIP_PROOF_ENTRY_INDEX && proof.is_none() {
+            proof = Some(item.action.as_hash().clone());
+        }
+        if entry_index == KEY_RETIREMENT_ENTRY_INDEX {
+            let Some(entry_hash) = item.action.action().entry_hash() else {
+                continue;
+            };
+            if RetiredKey::try_from(must_get_entry(entry_hash.clone())?.content)
+                .is_ok_and(|retired| &retired.old_key == author)
+            {
+                return Ok(ValidateCallbackResult::Invalid("This key has been handed over to a new key".into()));
+            }
+        }
+    }
+
+    match proof {
+        Some(proof) => {
+            // Fails validation if the proof itself was invalid
+            must_get_valid_record(proof)?;
+            Ok(ValidateCallbackResult::Valid)
+        }
+        None => Ok(ValidateCallbackResult::Invalid(
+            "Only verified members can do this; get vouched by your neighbors first".into(),
+        )),
+    }
+}
@@ -0,0 +1,78 @@
+//! Delivery confidence for the caller's own records
+//!
+//! Publishing an action sends each DHT op it produces to the peers
+//! responsible for validating it, and they answer with signed validation
+//! receipts. The author's conductor counts these per op and marks an op
+//! complete once enough have arrived. Receipts only exist on the author's
+//! node, so this reports on the caller's own actions; zomes expose it as a
+//! `get_publish_status` extern for their entry types.
+
+use hdk::prelude::*;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum PublishStatus {
+    /// No peer has acknowledged any op yet; the record may only exist locally
+    Pending,
+    /// Some ops have receipts, but not all have enough
+    PartiallyPublished,
+    /// Every op has all the receipts it needs
+    Published,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PublishStatusOutput {
+    pub action_hash: ActionHash,
+    pub status: PublishStatus,
+    /// DHT ops the action produced
+    pub ops: u32,
+    /// Ops with all the receipts they need
+    pub ops_complete: u32,
+    /// Receipts received across every op
+    pub receipts: u32,
+}
+
+/// Summarize the validation receipts for one of the caller's actions
+///
+/// The action must create or update an entry of type `T`; `kind` names it in errors.
+pub fn publish_status<T>(action_hash: ActionHash, kind: &str) -> ExternResult<PublishStatusOutput>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let me = agent_info()?.agent_initial_pubkey;
+    let Some(record) = get(action_hash.clone(), GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("{} not found", kind))));
+    };
+    let entry = record
+        .entry()
+        .to_app_option::<T>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?;
+    if entry.is_none() {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!("Action is not a {}", kind.to_lowercase()))));
+    }
+    if record.action().author() != &me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Publish status is only known for your own records".into()
+        )));
+    }
+
+    let sets = get_validation_receipts(GetValidationReceiptsInput::new(action_hash.clone()))?;
+    let ops = sets.len() as u32;
+    let ops_complete = sets.iter().filter(|set| set.receipts_complete).count() as u32;
+    let receipts = sets.iter().map(|set| set.receipts.len() as u32).sum();
+
+    let status = if ops > 0 && ops_complete == ops {
+        PublishStatus::Published
+    } else if receipts > 0 {
+        PublishStatus::PartiallyPublished
+    } else {
+        PublishStatus::Pending
+    };
+
+    Ok(PublishStatusOutput {
+        action_hash,
+        status,
+        ops,
+        ops_complete,
+        receipts,
+    })
+}
@@ -0,0 +1,43 @@
+//! Shared envelope for zome signals
+//!
+//! Every signal a zome emits to its UI goes out as
+//! `{ zome, version, event, payload }`, so clients can route on `zome` and
+//! `event` without knowing each zome's enum layout. Zome signal enums must be
+//! adjacently tagged to fill in `event` and `payload`:
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize, Debug, Clone)]
+//! #[serde(tag = "event", content = "payload")]
+//! pub enum Signal {
+//!     NewPost { post_hash: ActionHash, post: Post },
+//! }
+//!
+//! emit_signal(SignalEnvelope::new("feed", Signal::NewPost { .. }))?;
+//! ```
+//!
+//! This crate only depends on serde so integrity crates can use it too.
+
+use serde::{Deserialize, Serialize};
+
+/// Bumped whenever an existing event's payload changes shape
+pub const SIGNAL_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SignalEnvelope<T> {
+    /// Name of the zome that emitted the signal
+    pub zome: String,
+    pub version: u32,
+    /// The zome's signal, serialized as its `event` and `payload` fields
+    #[serde(flatten)]
+    pub signal: T,
+}
+
+impl<T> SignalEnvelope<T> {
+    pub fn new(zome: &str, signal: T) -> Self {
+        Self {
+            zome: zome.to_string(),
+            version: SIGNAL_VERSION,
+            signal,
+        }
+    }
+}
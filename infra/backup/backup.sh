@@ -7,7 +7,7 @@
 # - Lair keystore (encrypted credentials)
 # - Configuration files
 #
-# Encryption: AES-256-CBC using ADMIN_API_KEY as passphrase
+# Encryption: AES-256-CBC using the setup wizard's backup passphrase (or ADMIN_API_KEY)
 # Schedule: Daily at 3 AM via cron
 # Retention: 30 days of backups
 
@@ -54,9 +54,17 @@ warn() {
 
 log "Starting OurBlock backup process..."
 
-# Check if ADMIN_API_KEY is set (required for encryption)
-if [ -z "$ADMIN_API_KEY" ]; then
-    error "ADMIN_API_KEY environment variable not set. Cannot encrypt backup."
+# Use the passphrase chosen in the sidecar setup wizard if there is one,
+# otherwise fall back to ADMIN_API_KEY
+BACKUP_PASSPHRASE_FILE="${BACKUP_PASSPHRASE_FILE:-/sidecar/backup-passphrase}"
+if [ -s "$BACKUP_PASSPHRASE_FILE" ]; then
+    BACKUP_PASSPHRASE=$(cat "$BACKUP_PASSPHRASE_FILE")
+else
+    BACKUP_PASSPHRASE="$ADMIN_API_KEY"
+fi
+
+if [ -z "$BACKUP_PASSPHRASE" ]; then
+    error "No backup passphrase set and ADMIN_API_KEY not set. Cannot encrypt backup."
     exit 1
 fi
 
@@ -158,7 +166,7 @@ log "Tarball created: ${TARBALL_SIZE}"
 log "Encrypting backup with AES-256..."
 
 # Use openssl to encrypt with AES-256-CBC
-# Password is derived from ADMIN_API_KEY using PBKDF2
+# Password is derived from the backup passphrase using PBKDF2
 ENCRYPTED_FILE="${TARBALL}.enc"
 
 openssl enc -aes-256-cbc \
@@ -167,7 +175,7 @@ openssl enc -aes-256-cbc \
     -iter 100000 \
     -in "$TARBALL" \
     -out "$ENCRYPTED_FILE" \
-    -pass "pass:${ADMIN_API_KEY}"
+    -pass "pass:${BACKUP_PASSPHRASE}"
 
 if [ ! -f "$ENCRYPTED_FILE" ]; then
     error "Failed to encrypt backup"
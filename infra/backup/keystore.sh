@@ -0,0 +1,252 @@
+#!/bin/bash
+#
+# OurBlock Keystore Export and Restore
+#
+# Moves the conductor's Lair keystore (the agent keys that make up the hub's
+# neighborhood identity) between devices. Run by the sidecar through
+# `docker exec -i`.
+#
+#   keystore.sh export    stdin: passphrase line     stdout: encrypted archive
+#   keystore.sh restore   stdin: passphrase line, then the encrypted archive
+#
+# Archives use the same layout and encryption as backup.sh: a tarball with one
+# top-level directory holding `lair/` and `backup_metadata.json`, encrypted
+# with AES-256-CBC and PBKDF2 (100k iterations). Restore therefore also accepts
+# a full nightly backup and takes only its `lair/` directory.
+#
+# Lair encrypts the keystore with the passphrase in `$LAIR_DIR/.passphrase`,
+# so that file is part of every archive; one without it can't be unlocked on
+# another device and is refused.
+#
+# A restore copies the new keystore into a staging directory next to the live
+# one while Lair is still running, and only then stops the containers and swaps
+# the two. Whatever happens after the stop, the EXIT trap puts the previous
+# keystore back if the swap didn't finish and starts both containers again.
+#
+# Everything except the archive itself is logged to stderr.
+
+set -e  # Exit on error
+set -o pipefail
+
+# ============================================================================
+# Configuration
+# ============================================================================
+
+BACKUP_DIR="/backups"
+LAIR_DIR="/lair"
+LAIR_CONTAINER="${LAIR_CONTAINER:-ourblock-lair}"
+CONDUCTOR_CONTAINER="${CONDUCTOR_CONTAINER:-ourblock-conductor}"
+LAIR_PASSPHRASE_NAME="${LAIR_PASSPHRASE_NAME:-.passphrase}"
+TIMESTAMP=$(date +"%Y%m%d_%H%M%S")
+WORK_DIR=$(mktemp -d)
+
+# Same filesystem as the keystore, so the swap is a handful of renames
+STAGING_DIR="$LAIR_DIR/.restore-staging"
+REPLACED_DIR="$LAIR_DIR/.restore-replaced"
+RESTORING=false
+CONTAINERS_STOPPED=false
+SWAP_STARTED=false
+SWAP_DONE=false
+
+# ============================================================================
+# Logging
+# ============================================================================
+
+log() {
+    echo "[$(date +'%Y-%m-%d %H:%M:%S')] $1" >&2
+}
+
+error() {
+    echo "[$(date +'%Y-%m-%d %H:%M:%S')] ERROR: $1" >&2
+}
+
+# Move every entry of a directory (dotfiles included) into another, leaving
+# the staging and replaced directories where they are
+move_entries() {
+    find "$1" -mindepth 1 -maxdepth 1 \
+        ! -path "$STAGING_DIR" ! -path "$REPLACED_DIR" \
+        -exec mv {} "$2"/ \;
+}
+
+cleanup() {
+    # Keep going past failures so the containers are always started again
+    set +e
+    if [ "$RESTORING" = true ]; then
+        if [ "$SWAP_STARTED" = true ] && [ "$SWAP_DONE" != true ]; then
+            error "Keystore swap did not finish; putting the previous keystore back"
+            find "$LAIR_DIR" -mindepth 1 -maxdepth 1 \
+                ! -path "$STAGING_DIR" ! -path "$REPLACED_DIR" \
+                -exec rm -rf {} +
+            move_entries "$REPLACED_DIR" "$LAIR_DIR"
+        fi
+        rm -rf "$STAGING_DIR"
+        if [ "$SWAP_DONE" = true ]; then
+            rm -rf "$REPLACED_DIR"
+        else
+            # Only gone once empty; otherwise kept for recovery by hand
+            rmdir "$REPLACED_DIR" 2>/dev/null || true
+        fi
+    fi
+
+    if [ "$CONTAINERS_STOPPED" = true ]; then
+        log "Starting Lair keystore and conductor..."
+        docker start "$LAIR_CONTAINER" >&2 || error "Failed to start $LAIR_CONTAINER"
+        docker start "$CONDUCTOR_CONTAINER" >&2 || error "Failed to start $CONDUCTOR_CONTAINER"
+    fi
+    rm -rf "$WORK_DIR"
+}
+
+trap cleanup EXIT
+
+# Passphrase is passed to openssl through the environment, not argv, so it
+# doesn't show up in the process list
+read_passphrase() {
+    IFS= read -r KEYSTORE_PASSPHRASE || true
+    if [ -z "$KEYSTORE_PASSPHRASE" ]; then
+        error "No passphrase given on stdin"
+        exit 1
+    fi
+    export KEYSTORE_PASSPHRASE
+}
+
+# Package a keystore directory the way backup.sh packages a full backup
+pack_keystore() {
+    local source="$1"
+    local name="$2"
+    local pass_env="$3"
+
+    mkdir -p "$WORK_DIR/$name/lair"
+    cp -a "$source"/. "$WORK_DIR/$name/lair/"
+
+    cat > "$WORK_DIR/$name/backup_metadata.json" <<EOF
+{
+  "timestamp": "$TIMESTAMP",
+  "version": "v0.5.0",
+  "backup_name": "$name",
+  "type": "keystore",
+  "created_at": "$(date -u +%Y-%m-%dT%H:%M:%SZ)",
+  "hostname": "$(hostname)"
+}
+EOF
+
+    tar -czf - -C "$WORK_DIR" "$name" | openssl enc -aes-256-cbc \
+        -salt \
+        -pbkdf2 \
+        -iter 100000 \
+        -pass "env:${pass_env}"
+
+    rm -rf "$WORK_DIR/$name"
+}
+
+# ============================================================================
+# Export
+# ============================================================================
+
+export_keystore() {
+    read_passphrase
+
+    if [ -z "$(ls -A "$LAIR_DIR" 2>/dev/null)" ]; then
+        error "Lair keystore is empty: $LAIR_DIR"
+        exit 1
+    fi
+    if [ ! -s "$LAIR_DIR/$LAIR_PASSPHRASE_NAME" ]; then
+        error "Lair passphrase file is missing: $LAIR_DIR/$LAIR_PASSPHRASE_NAME"
+        exit 1
+    fi
+
+    log "Exporting Lair keystore..."
+    pack_keystore "$LAIR_DIR" "ourblock_keystore_${TIMESTAMP}" KEYSTORE_PASSPHRASE
+    log "Keystore exported"
+}
+
+# ============================================================================
+# Restore
+# ============================================================================
+
+restore_keystore() {
+    RESTORING=true
+    read_passphrase
+
+    # The rest of stdin is the archive
+    cat > "$WORK_DIR/archive.tar.gz.enc"
+
+    log "Decrypting archive..."
+    if ! openssl enc -d -aes-256-cbc \
+        -pbkdf2 \
+        -iter 100000 \
+        -in "$WORK_DIR/archive.tar.gz.enc" \
+        -out "$WORK_DIR/archive.tar.gz" \
+        -pass env:KEYSTORE_PASSPHRASE 2>/dev/null; then
+        error "Could not decrypt archive; wrong passphrase or not an OurBlock archive"
+        exit 2
+    fi
+
+    mkdir -p "$WORK_DIR/extracted"
+    if ! tar -xzf "$WORK_DIR/archive.tar.gz" -C "$WORK_DIR/extracted"; then
+        error "Archive is not a valid tarball"
+        exit 2
+    fi
+    rm -f "$WORK_DIR/archive.tar.gz.enc" "$WORK_DIR/archive.tar.gz"
+
+    # Keystore exports and full backups both keep it at <name>/lair
+    RESTORED_LAIR=$(find "$WORK_DIR/extracted" -mindepth 2 -maxdepth 2 -type d -name lair | head -n 1)
+    if [ -z "$RESTORED_LAIR" ] || [ -z "$(ls -A "$RESTORED_LAIR")" ]; then
+        error "Archive does not contain a Lair keystore"
+        exit 2
+    fi
+
+    if [ ! -s "$RESTORED_LAIR/$LAIR_PASSPHRASE_NAME" ]; then
+        error "Archive has no Lair passphrase file ($LAIR_PASSPHRASE_NAME), so its keystore can't be unlocked"
+        exit 2
+    fi
+
+    log "Staging restored keystore..."
+    rm -rf "$STAGING_DIR"
+    mkdir -p "$STAGING_DIR"
+    cp -a "$RESTORED_LAIR"/. "$STAGING_DIR/"
+    rm -rf "$WORK_DIR/extracted"
+
+    log "Stopping conductor and Lair keystore..."
+    CONTAINERS_STOPPED=true
+    docker stop "$CONDUCTOR_CONTAINER" "$LAIR_CONTAINER" >&2
+
+    # Keep the keystore being replaced, encrypted like the nightly backups,
+    # in case the wrong archive was restored
+    BACKUP_PASSPHRASE_FILE="${BACKUP_PASSPHRASE_FILE:-/sidecar/backup-passphrase}"
+    if [ -s "$BACKUP_PASSPHRASE_FILE" ]; then
+        BACKUP_PASSPHRASE=$(cat "$BACKUP_PASSPHRASE_FILE")
+    else
+        BACKUP_PASSPHRASE="$ADMIN_API_KEY"
+    fi
+    export BACKUP_PASSPHRASE
+
+    rm -rf "$REPLACED_DIR"
+    mkdir -p "$REPLACED_DIR"
+    SWAP_STARTED=true
+    move_entries "$LAIR_DIR" "$REPLACED_DIR"
+
+    if [ -n "$(ls -A "$REPLACED_DIR")" ] && [ -n "$BACKUP_PASSPHRASE" ]; then
+        PREVIOUS="${BACKUP_DIR}/ourblock_keystore_prerestore_${TIMESTAMP}.tar.gz.enc"
+        pack_keystore "$REPLACED_DIR" "ourblock_keystore_prerestore_${TIMESTAMP}" BACKUP_PASSPHRASE > "$PREVIOUS"
+        log "Previous keystore saved to $PREVIOUS"
+    fi
+
+    log "Swapping in restored keystore..."
+    move_entries "$STAGING_DIR" "$LAIR_DIR"
+    SWAP_DONE=true
+
+    log "Keystore restored"
+}
+
+case "$1" in
+    export)
+        export_keystore
+        ;;
+    restore)
+        restore_keystore
+        ;;
+    *)
+        error "Usage: $0 export|restore"
+        exit 1
+        ;;
+esac
@@ -68,13 +68,24 @@ echo "  ✓ Configuration ready"
 # Install hApp if not already installed
 # ─────────────────────────────────────────────────────────────────────────────────
 
-if [ -f "${HAPP_FILE}" ]; then
+if [ "${DEFER_APP_INSTALL:-false}" = "true" ]; then
+    # The sidecar installs it with the neighborhood's DNA properties
+    echo "  ✓ hApp installation deferred to the sidecar"
+elif [ -f "${HAPP_FILE}" ]; then
     echo "→ Checking hApp installation..."
     
     # Check if app is already installed (by looking for installed_apps marker)
     INSTALLED_MARKER="${DATA_DIR}/.ourblock_installed"
     
-    if [ ! -f "${INSTALLED_MARKER}" ]; then
+    if [ ! -f "${INSTALLED_MARKER}" ] && [ "${BUNDLE_HAS_FOUNDING_ANCHOR:-false}" != "true" ]; then
+        # hc app install takes the bundle's DNA properties as they are, and the
+        # stock bundle has no founding anchor, which the DNA refuses to install
+        echo "  ✗ Can't install the hApp here: its DNA properties have no founding_anchor"
+        echo "    Set DEFER_APP_INSTALL=true and FOUNDING_ANCHOR=<founder's uhCAk... key> so the"
+        echo "    sidecar installs it, or set BUNDLE_HAS_FOUNDING_ANCHOR=true if ${HAPP_FILE}"
+        echo "    was built with founding_anchor set in dna.yaml"
+        exit 1
+    elif [ ! -f "${INSTALLED_MARKER}" ]; then
         echo "  Installing OurBlock hApp..."
         
         # Start conductor temporarily to install the app
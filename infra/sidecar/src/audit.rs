@@ -0,0 +1,150 @@
+// ============================================================================
+// Admin Audit Log
+// ============================================================================
+//
+// Every authenticated call that changes something (plus backup downloads) is
+// appended to a JSON-lines file with the key that made it, the client IP, and
+// the HTTP outcome. The file is only ever appended to; the API reads it
+// newest first with a `before` cursor on the entry sequence number.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use tokio::{io::AsyncWriteExt, sync::Mutex};
+
+pub const DEFAULT_PAGE_SIZE: usize = 50;
+pub const MAX_PAGE_SIZE: usize = 500;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct AuditEntry {
+    /// Increases by one per entry; used as the pagination cursor
+    pub seq: u64,
+    pub at: String,
+    /// None for calls made before any key exists (the setup wizard)
+    pub key_id: Option<String>,
+    pub key_name: Option<String>,
+    pub ip: String,
+    pub method: String,
+    /// Path under `/api`
+    pub path: String,
+    pub status: u16,
+    /// `success`, `denied` (401/403), or `failure`
+    pub outcome: String,
+}
+
+#[derive(Serialize)]
+pub struct AuditPage {
+    pub entries: Vec<AuditEntry>,
+    /// Pass as `before` to fetch the next (older) page; None on the last page
+    pub next_before: Option<u64>,
+}
+
+/// What the middleware knows about a call once it has completed
+pub struct AuditRecord<'a> {
+    pub key_id: Option<&'a str>,
+    pub key_name: Option<&'a str>,
+    pub ip: &'a str,
+    pub method: &'a str,
+    pub path: &'a str,
+    pub status: u16,
+}
+
+pub struct AuditLog {
+    path: PathBuf,
+    /// Sequence number of the next entry; also serializes appends
+    next_seq: Mutex<u64>,
+}
+
+impl AuditLog {
+    pub async fn from_env() -> Result<Self, String> {
+        let path = PathBuf::from(
+            std::env::var("AUDIT_LOG_FILE").unwrap_or_else(|_| "/app/data/audit.log".to_string()),
+        );
+
+        let next_seq = match tokio::fs::read_to_string(&path).await {
+            Ok(contents) => contents
+                .lines()
+                .rev()
+                .find_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+                .map_or(0, |entry| entry.seq + 1),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(format!("Failed to read audit log {}: {}", path.display(), e)),
+        };
+
+        Ok(Self {
+            path,
+            next_seq: Mutex::new(next_seq),
+        })
+    }
+
+    /// Calls worth recording: anything that isn't a plain read, plus backup downloads
+    pub fn is_audited(method: &axum::http::Method, path: &str) -> bool {
+        method != axum::http::Method::GET || path == "/system/backup/download"
+    }
+
+    pub async fn record(&self, record: AuditRecord<'_>) -> Result<(), String> {
+        let mut next_seq = self.next_seq.lock().await;
+
+        let outcome = match record.status {
+            401 | 403 => "denied",
+            status if status < 400 => "success",
+            _ => "failure",
+        };
+        let entry = AuditEntry {
+            seq: *next_seq,
+            at: Utc::now().to_rfc3339(),
+            key_id: record.key_id.map(str::to_string),
+            key_name: record.key_name.map(str::to_string),
+            ip: record.ip.to_string(),
+            method: record.method.to_string(),
+            path: record.path.to_string(),
+            status: record.status,
+            outcome: outcome.to_string(),
+        };
+        let mut line = serde_json::to_vec(&entry).map_err(|e| e.to_string())?;
+        line.push(b'\n');
+
+        if let Some(parent) = self.path.parent() {
+            tokio::fs::create_dir_all(parent)
+                .await
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+        let mut options = tokio::fs::OpenOptions::new();
+        options.create(true).append(true);
+        #[cfg(unix)]
+        options.mode(0o600);
+        let mut file = options
+            .open(&self.path)
+            .await
+            .map_err(|e| format!("Failed to open audit log {}: {}", self.path.display(), e))?;
+        file.write_all(&line)
+            .await
+            .map_err(|e| format!("Failed to append to audit log: {}", e))?;
+
+        *next_seq += 1;
+        Ok(())
+    }
+
+    /// Newest entries first, optionally only those older than `before`
+    pub async fn page(&self, before: Option<u64>, limit: usize) -> Result<AuditPage, String> {
+        // Hold the append lock so a half-written line is never read
+        let _next_seq = self.next_seq.lock().await;
+
+        let contents = match tokio::fs::read_to_string(&self.path).await {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(format!("Failed to read audit log: {}", e)),
+        };
+
+        let limit = limit.clamp(1, MAX_PAGE_SIZE);
+        let mut older = contents
+            .lines()
+            .rev()
+            .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+            .filter(|entry| before.map_or(true, |before| entry.seq < before));
+        let entries: Vec<AuditEntry> = older.by_ref().take(limit).collect();
+        let next_before = older.next().and(entries.last().map(|entry| entry.seq));
+
+        Ok(AuditPage { entries, next_before })
+    }
+}
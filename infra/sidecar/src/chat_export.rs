@@ -0,0 +1,103 @@
+// ============================================================================
+// Chat Transcript Export
+// ============================================================================
+//
+// `GET /chat/export` downloads one conversation as a file. The transcript is
+// read from the requesting agent's own cell with the chat zome's
+// `export_conversation`, so it only ever holds what that agent's chain stored,
+// and a client can't dress up a file of its own making as a hub export. The
+// hub keeps no copy.
+
+use chrono::DateTime;
+use serde::Deserialize;
+use serde_bytes::ByteBuf;
+
+use crate::conductor::encode_hash;
+use crate::zome_calls::ZomeCaller;
+
+#[derive(Debug)]
+pub enum ExportError {
+    /// This hub doesn't host the agent's cell
+    NotHosted,
+    Failed(String),
+}
+
+/// A conversation ready to be written out
+pub struct Transcript {
+    pub json: serde_json::Value,
+    /// One line per message
+    pub text: String,
+}
+
+/// Export the conversation of `agent` (`uhCAk...`) with `peer` (a raw agent key)
+pub async fn export(zome_calls: &ZomeCaller, agent: &str, peer: Vec<u8>) -> Result<Transcript, ExportError> {
+    let cell = zome_calls
+        .find_cell(agent)
+        .await
+        .map_err(ExportError::Failed)?
+        .ok_or(ExportError::NotHosted)?;
+    let export: ConversationExport = zome_calls
+        .call(&cell, "chat", "export_conversation", &ByteBuf::from(peer))
+        .await
+        .map_err(ExportError::Failed)?;
+
+    let messages: Vec<serde_json::Value> = export
+        .messages
+        .iter()
+        .map(|message| {
+            serde_json::json!({
+                "message_id": message.message_id,
+                "sender": encode_hash(&message.sender),
+                "recipient": message.recipient.as_ref().map(|key| encode_hash(key)),
+                "content": message.content,
+                "sent_at": DateTime::from_timestamp_millis(message.timestamp).map(|t| t.to_rfc3339()),
+                "about": message.context.as_ref().map(|context| &context.title),
+            })
+        })
+        .collect();
+
+    Ok(Transcript {
+        json: serde_json::json!({
+            "me": encode_hash(&export.me),
+            "peer": encode_hash(&export.peer),
+            "exported_at": DateTime::from_timestamp_micros(export.exported_at).map(|t| t.to_rfc3339()),
+            "messages": messages,
+        }),
+        text: export.transcript,
+    })
+}
+
+// ============================================================================
+// Chat Zome Shapes
+// ============================================================================
+
+/// Mirror of the chat zome's `ConversationExport`
+#[derive(Deserialize)]
+struct ConversationExport {
+    me: ByteBuf,
+    peer: ByteBuf,
+    /// Microseconds since the epoch
+    exported_at: i64,
+    messages: Vec<ExportedMessage>,
+    transcript: String,
+}
+
+/// Mirror of the chat zome's `ChatMessage`
+#[derive(Deserialize)]
+struct ExportedMessage {
+    sender: ByteBuf,
+    content: String,
+    /// Milliseconds since the epoch
+    timestamp: i64,
+    message_id: String,
+    #[serde(default)]
+    context: Option<ExportedContext>,
+    #[serde(default)]
+    recipient: Option<ByteBuf>,
+}
+
+/// The chat zome's `ChatContext`, without the subject's hash
+#[derive(Deserialize)]
+struct ExportedContext {
+    title: String,
+}
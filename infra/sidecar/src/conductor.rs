@@ -0,0 +1,171 @@
+// ============================================================================
+// Conductor API Client
+// ============================================================================
+//
+// Minimal client for the Holochain admin and app WebSockets. Each call opens a
+// connection, sends one request, and waits for the matching response. Frames
+// are MessagePack: a wire envelope whose `data` holds the MessagePack-encoded
+// request or response, both tagged as `{"type": ..., "value": ...}`. App
+// connections must authenticate with a single-use token before their request.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use futures_util::{SinkExt, StreamExt};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use std::time::Duration;
+use tokio::time::timeout;
+use tokio_tungstenite::tungstenite::{client::IntoClientRequest, Message};
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+/// Origin the conductor's admin and app interfaces must allow (matches the
+/// health probe)
+const SIDECAR_ORIGIN: &str = "ourblock-sidecar";
+
+#[derive(Serialize)]
+struct WireRequest {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    id: u64,
+    #[serde(with = "serde_bytes")]
+    data: Vec<u8>,
+}
+
+#[derive(Serialize)]
+struct WireAuthenticate {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    #[serde(with = "serde_bytes")]
+    data: Vec<u8>,
+}
+
+/// The token is sent as a plain byte array, the way the conductor issues it
+#[derive(Serialize)]
+struct AppAuthentication {
+    token: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct WireResponse {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    id: Option<u64>,
+    #[serde(default)]
+    data: Option<serde_bytes::ByteBuf>,
+}
+
+#[derive(Deserialize)]
+struct ResponseTag {
+    #[serde(rename = "type")]
+    kind: String,
+}
+
+#[derive(Deserialize)]
+struct ResponseBody<T> {
+    value: T,
+}
+
+/// Send one admin request and decode the response's `value` as `T`.
+/// `request` must serialize to the tagged `{"type", "value"}` form and
+/// `expected` is the response type, e.g. `storage_info`.
+pub async fn admin_call<R: Serialize, T: DeserializeOwned>(
+    addr: &str,
+    request: &R,
+    expected: &str,
+) -> Result<T, String> {
+    let data = rmp_serde::to_vec_named(request).map_err(|e| format!("Failed to encode admin request: {}", e))?;
+    let body = exchange(addr, "admin", None, data).await?;
+    decode_response(&body, expected)
+}
+
+/// Send one request to an app interface, authenticating the connection with
+/// a token from `issue_app_authentication_token` first. Same request and
+/// response shapes as `admin_call`, e.g. `call_zome` answered by `zome_called`.
+pub async fn app_call<R: Serialize, T: DeserializeOwned>(
+    addr: &str,
+    token: Vec<u8>,
+    request: &R,
+    expected: &str,
+) -> Result<T, String> {
+    let authenticate = WireAuthenticate {
+        kind: "authenticate",
+        data: rmp_serde::to_vec_named(&AppAuthentication { token })
+            .map_err(|e| format!("Failed to encode app authentication: {}", e))?,
+    };
+    let authenticate =
+        rmp_serde::to_vec_named(&authenticate).map_err(|e| format!("Failed to encode app authentication: {}", e))?;
+    let data = rmp_serde::to_vec_named(request).map_err(|e| format!("Failed to encode app request: {}", e))?;
+    let body = exchange(addr, "app", Some(authenticate), data).await?;
+    decode_response(&body, expected)
+}
+
+/// Open a connection, optionally send an authentication frame, then send one
+/// request and return the raw body of its response
+async fn exchange(addr: &str, api: &str, authenticate: Option<Vec<u8>>, data: Vec<u8>) -> Result<Vec<u8>, String> {
+    let wire = WireRequest {
+        kind: "request",
+        id: 0,
+        data,
+    };
+    let frame = rmp_serde::to_vec_named(&wire).map_err(|e| format!("Failed to encode {} request: {}", api, e))?;
+
+    let mut ws_request = format!("ws://{}", addr)
+        .into_client_request()
+        .map_err(|e| format!("Invalid conductor address: {}", e))?;
+    ws_request
+        .headers_mut()
+        .insert("Origin", SIDECAR_ORIGIN.parse().expect("static origin is a valid header"));
+
+    let exchange = async {
+        let (mut socket, _) = tokio_tungstenite::connect_async(ws_request)
+            .await
+            .map_err(|e| format!("Conductor {} API unreachable: {}", api, e))?;
+        if let Some(authenticate) = authenticate {
+            socket
+                .send(Message::Binary(authenticate))
+                .await
+                .map_err(|e| format!("Failed to authenticate with the {} API: {}", api, e))?;
+        }
+        socket
+            .send(Message::Binary(frame))
+            .await
+            .map_err(|e| format!("Failed to send {} request: {}", api, e))?;
+
+        while let Some(message) = socket.next().await {
+            let bytes = match message.map_err(|e| format!("Conductor {} connection failed: {}", api, e))? {
+                Message::Binary(bytes) => bytes,
+                Message::Close(_) => break,
+                _ => continue,
+            };
+            let wire: WireResponse =
+                rmp_serde::from_slice(&bytes).map_err(|e| format!("Malformed {} response: {}", api, e))?;
+            if wire.kind == "response" && wire.id == Some(0) {
+                let _ = socket.close(None).await;
+                return wire
+                    .data
+                    .map(|data| data.into_vec())
+                    .ok_or_else(|| "Conductor sent an empty response".to_string());
+            }
+        }
+        Err(format!("Conductor closed the {} connection", api))
+    };
+
+    timeout(REQUEST_TIMEOUT, exchange)
+        .await
+        .map_err(|_| format!("Conductor {} API timed out", api))?
+}
+
+/// Check the response's type tag and decode its `value` as `T`
+fn decode_response<T: DeserializeOwned>(body: &[u8], expected: &str) -> Result<T, String> {
+    let tag: ResponseTag = rmp_serde::from_slice(body).map_err(|e| format!("Malformed response: {}", e))?;
+    if tag.kind != expected {
+        return Err(format!("Conductor answered '{}' instead of '{}'", tag.kind, expected));
+    }
+    rmp_serde::from_slice::<ResponseBody<T>>(body)
+        .map(|response| response.value)
+        .map_err(|e| format!("Malformed '{}' response: {}", expected, e))
+}
+
+/// Holochain's base64 form of a raw 39-byte hash (`uhC0k...` for DNAs)
+pub fn encode_hash(raw: &[u8]) -> String {
+    format!("u{}", URL_SAFE_NO_PAD.encode(raw))
+}
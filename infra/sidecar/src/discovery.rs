@@ -0,0 +1,125 @@
+// ============================================================================
+// Hub Discovery for Mobile Onboarding
+// ============================================================================
+//
+// The peer browser keeps a passive registry of *other* hubs for federation.
+// Onboarding needs something different: a fresh list of every hub answering
+// on the LAN right now, this one included, so a phone can offer a picker. Each
+// scan runs on its own short-lived mDNS daemon, because a second browse for
+// the same service type on the main daemon would replace the peer browser.
+// Results are cached briefly so repeated polling doesn't flood the LAN.
+
+use chrono::Utc;
+use mdns_sd::{ServiceDaemon, ServiceEvent};
+use serde::Serialize;
+use std::{collections::BTreeMap, time::Duration};
+use tokio::{sync::Mutex, time::Instant};
+use tracing::warn;
+
+use crate::peers::SERVICE_TYPE;
+
+/// How long a scan listens for answers
+const BROWSE_DURATION: Duration = Duration::from_secs(3);
+/// Scans within this window reuse the previous result
+const CACHE_TTL: Duration = Duration::from_secs(15);
+
+#[derive(Serialize, Clone, Debug)]
+pub struct DiscoveredHub {
+    /// mDNS instance name, e.g. `OurBlock Hub - Maple Street`
+    pub name: String,
+    pub hostname: String,
+    pub neighborhood_name: Option<String>,
+    pub version: Option<String>,
+    pub addresses: Vec<String>,
+    pub port: u16,
+    /// The hub answering this request
+    pub this_hub: bool,
+}
+
+#[derive(Serialize, Clone)]
+pub struct DiscoveryResult {
+    pub hubs: Vec<DiscoveredHub>,
+    pub scanned_at: String,
+}
+
+pub struct HubBrowser {
+    own_hostname: String,
+    /// Held for the whole scan so concurrent requests share one
+    cache: Mutex<Option<(Instant, DiscoveryResult)>>,
+}
+
+impl HubBrowser {
+    pub fn new(own_hostname: &str) -> Self {
+        Self {
+            own_hostname: own_hostname.trim_end_matches('.').to_string(),
+            cache: Mutex::new(None),
+        }
+    }
+
+    pub async fn hubs(&self) -> Result<DiscoveryResult, String> {
+        let mut cache = self.cache.lock().await;
+        if let Some((scanned, result)) = cache.as_ref() {
+            if scanned.elapsed() < CACHE_TTL {
+                return Ok(result.clone());
+            }
+        }
+
+        let result = DiscoveryResult {
+            hubs: self.scan().await?,
+            scanned_at: Utc::now().to_rfc3339(),
+        };
+        *cache = Some((Instant::now(), result.clone()));
+        Ok(result)
+    }
+
+    async fn scan(&self) -> Result<Vec<DiscoveredHub>, String> {
+        let daemon = ServiceDaemon::new().map_err(|e| format!("Failed to create mDNS daemon: {}", e))?;
+        let receiver = daemon
+            .browse(SERVICE_TYPE)
+            .map_err(|e| format!("Failed to start mDNS browse: {}", e))?;
+
+        // Keyed by fullname so repeated answers from one hub collapse
+        let mut hubs = BTreeMap::new();
+        let deadline = Instant::now() + BROWSE_DURATION;
+        while let Ok(Ok(event)) = tokio::time::timeout_at(deadline, receiver.recv_async()).await {
+            if let ServiceEvent::ServiceResolved(info) = event {
+                let hostname = info.get_hostname().trim_end_matches('.').to_string();
+                let mut addresses: Vec<String> = info.get_addresses().iter().map(|addr| addr.to_string()).collect();
+                addresses.sort();
+
+                let fullname = info.get_fullname().to_string();
+                let name = fullname
+                    .strip_suffix(SERVICE_TYPE)
+                    .map(|name| name.trim_end_matches('.'))
+                    .unwrap_or(&fullname)
+                    .to_string();
+
+                hubs.insert(
+                    fullname.clone(),
+                    DiscoveredHub {
+                        name,
+                        this_hub: hostname == self.own_hostname,
+                        hostname,
+                        neighborhood_name: info.get_property_val_str("neighborhood").map(str::to_string),
+                        version: info.get_property_val_str("version").map(str::to_string),
+                        addresses,
+                        port: info.get_port(),
+                    },
+                );
+            }
+        }
+
+        if let Err(e) = daemon.shutdown() {
+            warn!(error = %e, "Failed to shut down discovery mDNS daemon");
+        }
+
+        let mut hubs: Vec<DiscoveredHub> = hubs.into_values().collect();
+        hubs.sort_by(|a, b| {
+            b.this_hub
+                .cmp(&a.this_hub)
+                .then_with(|| a.neighborhood_name.cmp(&b.neighborhood_name))
+                .then_with(|| a.hostname.cmp(&b.hostname))
+        });
+        Ok(hubs)
+    }
+}
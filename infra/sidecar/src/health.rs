@@ -0,0 +1,272 @@
+// ============================================================================
+// Component Health Probes
+// ============================================================================
+//
+// Each probe returns a ComponentHealth; the overall hub status is the worst
+// status of any component. Probes use raw TCP so the sidecar doesn't need an
+// HTTP client dependency just for health checks.
+
+use chrono::Utc;
+use serde::Serialize;
+use std::{collections::BTreeMap, path::Path, process::Command, time::Duration};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    time::{timeout, Instant},
+};
+
+use crate::AppState;
+
+const PROBE_TIMEOUT: Duration = Duration::from_secs(3);
+
+/// Backups run daily; allow a couple of hours of slack before warning
+const BACKUP_DEGRADED_AFTER_HOURS: i64 = 26;
+const BACKUP_UNHEALTHY_AFTER_HOURS: i64 = 72;
+
+const DISK_DEGRADED_PERCENT: f64 = 80.0;
+const DISK_UNHEALTHY_PERCENT: f64 = 95.0;
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum HealthStatus {
+    Healthy,
+    Degraded,
+    Unhealthy,
+}
+
+#[derive(Serialize, Debug)]
+pub struct ComponentHealth {
+    pub status: HealthStatus,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u64>,
+}
+
+impl ComponentHealth {
+    fn new(status: HealthStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+            latency_ms: None,
+        }
+    }
+
+    fn with_latency(mut self, started: Instant) -> Self {
+        self.latency_ms = Some(started.elapsed().as_millis() as u64);
+        self
+    }
+}
+
+#[derive(Serialize)]
+pub struct HealthReport {
+    pub status: HealthStatus,
+    pub timestamp: String,
+    pub components: BTreeMap<String, ComponentHealth>,
+}
+
+/// Run every probe concurrently and aggregate the results
+pub async fn check_all(state: &AppState) -> HealthReport {
+    let (conductor, nginx, backup, disk) = tokio::join!(
+        probe_conductor(&state.conductor_addr),
+        probe_nginx(&state.nginx_addr),
+        probe_backup(crate::BACKUP_PATH),
+        probe_disk(&state.data_path),
+    );
+
+    let mut components = BTreeMap::new();
+    components.insert(
+        "sidecar".to_string(),
+        ComponentHealth::new(HealthStatus::Healthy, "Sidecar is running"),
+    );
+    components.insert("conductor".to_string(), conductor);
+    components.insert("nginx".to_string(), nginx);
+    components.insert("backup".to_string(), backup);
+    components.insert("disk".to_string(), disk);
+
+    let status = components
+        .values()
+        .map(|component| component.status)
+        .max()
+        .unwrap_or(HealthStatus::Healthy);
+
+    HealthReport {
+        status,
+        timestamp: Utc::now().to_rfc3339(),
+        components,
+    }
+}
+
+/// The conductor is healthy only if it completes a WebSocket upgrade
+pub async fn probe_conductor(addr: &str) -> ComponentHealth {
+    let started = Instant::now();
+    let request = format!(
+        "GET / HTTP/1.1\r\n\
+         Host: {addr}\r\n\
+         Origin: ourblock-sidecar\r\n\
+         Upgrade: websocket\r\n\
+         Connection: Upgrade\r\n\
+         Sec-WebSocket-Key: dGhlIHNhbXBsZSBub25jZQ==\r\n\
+         Sec-WebSocket-Version: 13\r\n\r\n"
+    );
+
+    match http_status(addr, &request).await {
+        Ok(101) => ComponentHealth::new(HealthStatus::Healthy, "Conductor WebSocket accepted upgrade")
+            .with_latency(started),
+        Ok(code) => ComponentHealth::new(
+            HealthStatus::Degraded,
+            format!("Conductor reachable but refused WebSocket upgrade (HTTP {code})"),
+        )
+        .with_latency(started),
+        Err(e) => ComponentHealth::new(HealthStatus::Unhealthy, format!("Conductor unreachable: {e}")),
+    }
+}
+
+/// Any HTTP response below 500 means nginx is up and serving
+pub async fn probe_nginx(addr: &str) -> ComponentHealth {
+    let started = Instant::now();
+    let request = format!("GET /health HTTP/1.0\r\nHost: {addr}\r\n\r\n");
+
+    match http_status(addr, &request).await {
+        Ok(code) if code < 500 => {
+            ComponentHealth::new(HealthStatus::Healthy, "Reverse proxy is serving").with_latency(started)
+        }
+        Ok(code) => ComponentHealth::new(HealthStatus::Degraded, format!("Reverse proxy returned HTTP {code}"))
+            .with_latency(started),
+        Err(e) => ComponentHealth::new(HealthStatus::Unhealthy, format!("Reverse proxy unreachable: {e}")),
+    }
+}
+
+async fn probe_backup(path: &str) -> ComponentHealth {
+    let modified = match tokio::fs::metadata(path).await.and_then(|metadata| metadata.modified()) {
+        Ok(modified) => chrono::DateTime::<Utc>::from(modified),
+        // A fresh hub has no backup until the first nightly run
+        Err(_) => return ComponentHealth::new(HealthStatus::Degraded, "No backup found"),
+    };
+
+    let age_hours = (Utc::now() - modified).num_hours();
+    let status = if age_hours >= BACKUP_UNHEALTHY_AFTER_HOURS {
+        HealthStatus::Unhealthy
+    } else if age_hours >= BACKUP_DEGRADED_AFTER_HOURS {
+        HealthStatus::Degraded
+    } else {
+        HealthStatus::Healthy
+    };
+
+    ComponentHealth::new(
+        status,
+        format!("Latest backup is {age_hours}h old ({})", modified.to_rfc3339()),
+    )
+}
+
+async fn probe_disk(path: &str) -> ComponentHealth {
+    let path = path.to_string();
+    let usage = tokio::task::spawn_blocking(move || disk_usage(Path::new(&path)))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|usage| usage);
+
+    match usage {
+        Ok(usage) => {
+            let percent = usage.used_percent();
+            let status = if percent >= DISK_UNHEALTHY_PERCENT {
+                HealthStatus::Unhealthy
+            } else if percent >= DISK_DEGRADED_PERCENT {
+                HealthStatus::Degraded
+            } else {
+                HealthStatus::Healthy
+            };
+            ComponentHealth::new(
+                status,
+                format!(
+                    "{:.1}% used, {:.2} GB free",
+                    percent,
+                    usage.available_bytes as f64 / (1024.0 * 1024.0 * 1024.0)
+                ),
+            )
+        }
+        Err(e) => ComponentHealth::new(HealthStatus::Degraded, format!("Could not read disk usage: {e}")),
+    }
+}
+
+// ============================================================================
+// Probe Helpers
+// ============================================================================
+
+#[derive(Serialize, Clone, Copy, Debug)]
+pub struct DiskUsage {
+    pub total_bytes: u64,
+    pub used_bytes: u64,
+    pub available_bytes: u64,
+}
+
+impl DiskUsage {
+    pub fn used_percent(&self) -> f64 {
+        if self.total_bytes == 0 {
+            return 0.0;
+        }
+        self.used_bytes as f64 / self.total_bytes as f64 * 100.0
+    }
+}
+
+/// Usage of the filesystem containing `path`, via POSIX `df`
+pub fn disk_usage(path: &Path) -> Result<DiskUsage, String> {
+    let output = Command::new("df")
+        .args(["-P", "-k"])
+        .arg(path)
+        .output()
+        .map_err(|e| format!("Failed to execute df: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("df failed: {}", stderr));
+    }
+
+    // Filesystem 1024-blocks Used Available Capacity Mounted-on
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let fields: Vec<&str> = stdout
+        .lines()
+        .nth(1)
+        .ok_or("Unexpected df output")?
+        .split_whitespace()
+        .collect();
+
+    let kib = |index: usize| -> Result<u64, String> {
+        fields
+            .get(index)
+            .and_then(|value| value.parse::<u64>().ok())
+            .map(|value| value * 1024)
+            .ok_or_else(|| "Unexpected df output".to_string())
+    };
+
+    Ok(DiskUsage {
+        total_bytes: kib(1)?,
+        used_bytes: kib(2)?,
+        available_bytes: kib(3)?,
+    })
+}
+
+/// Send a raw HTTP request and return the status code of the response line
+async fn http_status(addr: &str, request: &str) -> Result<u16, String> {
+    let exchange = async {
+        let mut stream = TcpStream::connect(addr).await.map_err(|e| e.to_string())?;
+        stream
+            .write_all(request.as_bytes())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut buf = [0u8; 256];
+        let read = stream.read(&mut buf).await.map_err(|e| e.to_string())?;
+        let response = String::from_utf8_lossy(&buf[..read]);
+
+        response
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|code| code.parse::<u16>().ok())
+            .ok_or_else(|| "Malformed HTTP response".to_string())
+    };
+
+    timeout(PROBE_TIMEOUT, exchange)
+        .await
+        .map_err(|_| "Timed out".to_string())?
+}
@@ -0,0 +1,241 @@
+// ============================================================================
+// Admin API Keys with Scopes
+// ============================================================================
+//
+// Keys are stored on disk as SHA-256 hashes only; the secret is returned once,
+// when a key is created or rotated. The legacy ADMIN_API_KEY still works as a
+// built-in key with every scope so existing deployments keep running, but it
+// is never written to disk and can't be revoked through the API.
+
+use chrono::Utc;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{path::PathBuf, sync::Arc};
+use tokio::sync::RwLock;
+
+const KEY_PREFIX: &str = "obk_";
+const MAX_KEY_NAME_LENGTH: usize = 64;
+const BUILTIN_KEY_ID: &str = "builtin";
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// Health, version, status, and listing endpoints
+    Read,
+    /// Download or trigger backups
+    Backup,
+    /// Update, roll back, or restart containers
+    Update,
+    /// Everything, including key management
+    Admin,
+}
+
+impl Scope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Scope::Read => "read",
+            Scope::Backup => "backup",
+            Scope::Update => "update",
+            Scope::Admin => "admin",
+        }
+    }
+
+    /// Scope required to call an `/api` route; unknown routes need Admin
+    pub fn required_for(method: &axum::http::Method, path: &str) -> Scope {
+        use axum::http::Method;
+
+        match (method, path) {
+            (_, p) if p.starts_with("/system/keys") || p.starts_with("/system/ws-invites") => Scope::Admin,
+            (_, "/system/audit") => Scope::Admin,
+            (_, "/update" | "/restart") => Scope::Update,
+            (&Method::POST, p) if p.starts_with("/update/") => Scope::Update,
+            (_, "/system/backup/download" | "/system/backup/trigger") => Scope::Backup,
+            (&Method::GET, _) => Scope::Read,
+            _ => Scope::Admin,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ApiKey {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<Scope>,
+    #[serde(skip_serializing_if = "String::is_empty", default)]
+    hash: String,
+    pub created_at: String,
+    pub rotated_at: Option<String>,
+    pub revoked_at: Option<String>,
+}
+
+impl ApiKey {
+    pub fn allows(&self, scope: Scope) -> bool {
+        self.scopes.contains(&Scope::Admin) || self.scopes.contains(&scope)
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.revoked_at.is_none()
+    }
+
+    /// Copy without the hash, for API responses
+    pub fn public(&self) -> ApiKey {
+        ApiKey {
+            hash: String::new(),
+            ..self.clone()
+        }
+    }
+}
+
+/// A newly issued key; `secret` is never retrievable again
+#[derive(Serialize)]
+pub struct IssuedKey {
+    pub key: ApiKey,
+    pub secret: String,
+}
+
+pub struct KeyStore {
+    path: PathBuf,
+    builtin_hash: Option<String>,
+    keys: Vec<ApiKey>,
+}
+
+pub type SharedKeyStore = Arc<RwLock<KeyStore>>;
+
+impl KeyStore {
+    pub async fn load(path: PathBuf, builtin_secret: Option<&str>) -> Result<Self, String> {
+        let keys = match tokio::fs::read(&path).await {
+            Ok(contents) => serde_json::from_slice(&contents)
+                .map_err(|e| format!("Failed to parse key store {}: {}", path.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(format!("Failed to read key store {}: {}", path.display(), e)),
+        };
+
+        Ok(Self {
+            path,
+            builtin_hash: builtin_secret.map(hash_secret),
+            keys,
+        })
+    }
+
+    /// Find the active key matching a presented secret
+    pub fn authenticate(&self, secret: &str) -> Option<ApiKey> {
+        let presented = hash_secret(secret);
+
+        if let Some(builtin_hash) = &self.builtin_hash {
+            if constant_time_eq(builtin_hash, &presented) {
+                return Some(ApiKey {
+                    id: BUILTIN_KEY_ID.to_string(),
+                    name: "ADMIN_API_KEY".to_string(),
+                    scopes: vec![Scope::Admin],
+                    hash: String::new(),
+                    created_at: String::new(),
+                    rotated_at: None,
+                    revoked_at: None,
+                });
+            }
+        }
+
+        self.keys
+            .iter()
+            .filter(|key| key.is_active())
+            .find(|key| constant_time_eq(&key.hash, &presented))
+            .map(ApiKey::public)
+    }
+
+    pub fn list(&self) -> Vec<ApiKey> {
+        self.keys.iter().map(ApiKey::public).collect()
+    }
+
+    pub async fn create(&mut self, name: String, scopes: Vec<Scope>) -> Result<IssuedKey, String> {
+        let name = name.trim().to_string();
+        if name.is_empty() || name.len() > MAX_KEY_NAME_LENGTH {
+            return Err(format!("Key name must be 1-{} characters", MAX_KEY_NAME_LENGTH));
+        }
+        if scopes.is_empty() {
+            return Err("At least one scope is required".to_string());
+        }
+        if self.keys.iter().any(|key| key.is_active() && key.name == name) {
+            return Err(format!("An active key named '{}' already exists", name));
+        }
+
+        let secret = generate_secret();
+        let key = ApiKey {
+            id: random_hex(8),
+            name,
+            scopes,
+            hash: hash_secret(&secret),
+            created_at: Utc::now().to_rfc3339(),
+            rotated_at: None,
+            revoked_at: None,
+        };
+        self.keys.push(key.clone());
+        self.save().await?;
+
+        Ok(IssuedKey {
+            key: key.public(),
+            secret,
+        })
+    }
+
+    /// Replace a key's secret; the old secret stops working immediately
+    pub async fn rotate(&mut self, id: &str) -> Result<IssuedKey, String> {
+        let secret = generate_secret();
+        let key = self.active_key_mut(id)?;
+        key.hash = hash_secret(&secret);
+        key.rotated_at = Some(Utc::now().to_rfc3339());
+        let key = key.public();
+        self.save().await?;
+
+        Ok(IssuedKey { key, secret })
+    }
+
+    pub async fn revoke(&mut self, id: &str) -> Result<ApiKey, String> {
+        let key = self.active_key_mut(id)?;
+        key.revoked_at = Some(Utc::now().to_rfc3339());
+        let key = key.public();
+        self.save().await?;
+
+        Ok(key)
+    }
+
+    fn active_key_mut(&mut self, id: &str) -> Result<&mut ApiKey, String> {
+        if id == BUILTIN_KEY_ID {
+            return Err("The built-in ADMIN_API_KEY is managed through the environment".to_string());
+        }
+        self.keys
+            .iter_mut()
+            .find(|key| key.id == id && key.is_active())
+            .ok_or_else(|| "Key not found or already revoked".to_string())
+    }
+
+    async fn save(&self) -> Result<(), String> {
+        crate::persist::write_json_atomic(&self.path, &self.keys).await
+    }
+}
+
+fn generate_secret() -> String {
+    format!("{}{}", KEY_PREFIX, random_hex(32))
+}
+
+pub fn random_hex(bytes: usize) -> String {
+    let mut buf = vec![0u8; bytes];
+    rand::thread_rng().fill_bytes(&mut buf);
+    buf.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+pub fn hash_secret(secret: &str) -> String {
+    Sha256::digest(secret.as_bytes())
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+pub fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len()
+        && a
+            .bytes()
+            .zip(b.bytes())
+            .fold(0u8, |diff, (x, y)| diff | (x ^ y))
+            == 0
+}
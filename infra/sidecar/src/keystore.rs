@@ -0,0 +1,165 @@
+// ============================================================================
+// Conductor Keystore Export and Restore
+// ============================================================================
+//
+// The Lair keystore holds the hub's agent keys; losing it means losing the
+// hub's identity in the neighborhood. The sidecar doesn't mount the keystore
+// itself, so both directions run `/scripts/keystore.sh` in the backup
+// container and stream the passphrase and archive over stdin/stdout. Archives
+// use the nightly backup format, so a full backup can be restored here too.
+//
+// Lair encrypts the keystore with the passphrase in its `.passphrase` file,
+// which lives in the keystore directory and so travels with every archive;
+// the script refuses archives without it, since they could never be unlocked.
+//
+// An uploaded archive may be a full backup of up to a gigabyte, so it is
+// streamed to a temp file and from there into the script, never held in memory.
+
+use axum::body::Bytes;
+use futures_util::{Stream, StreamExt};
+use std::{
+    path::Path,
+    process::Stdio,
+};
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+const BACKUP_CONTAINER: &str = "ourblock-backup";
+const KEYSTORE_SCRIPT: &str = "/scripts/keystore.sh";
+const MIN_PASSPHRASE_LENGTH: usize = 12;
+/// Keystore exports are a few KB; full backups are accepted up to this size
+pub const MAX_RESTORE_ARCHIVE_BYTES: usize = 1024 * 1024 * 1024;
+
+/// `keystore.sh` exits with this when the archive itself is the problem
+const EXIT_BAD_ARCHIVE: i32 = 2;
+
+#[derive(Debug)]
+pub enum KeystoreError {
+    Invalid(String),
+    /// Archive over `MAX_RESTORE_ARCHIVE_BYTES`
+    TooLarge,
+    /// Wrong passphrase, or not an archive with a keystore in it
+    BadArchive(String),
+    Failed(String),
+}
+
+pub fn validate_passphrase(passphrase: &str) -> Result<(), KeystoreError> {
+    if passphrase.chars().count() < MIN_PASSPHRASE_LENGTH {
+        return Err(KeystoreError::Invalid(format!(
+            "Passphrase must be at least {} characters",
+            MIN_PASSPHRASE_LENGTH
+        )));
+    }
+    if passphrase.contains('\n') {
+        return Err(KeystoreError::Invalid("Passphrase must be a single line".to_string()));
+    }
+    Ok(())
+}
+
+/// Encrypted keystore archive, protected by `passphrase`
+pub async fn export(passphrase: &str) -> Result<Vec<u8>, KeystoreError> {
+    validate_passphrase(passphrase)?;
+    run_script("export", passphrase, None).await
+}
+
+/// Replace the conductor's keystore with the one in the uploaded archive; the
+/// script stops the conductor and Lair while swapping and starts them again
+/// after, whether or not the swap succeeds
+pub async fn restore<S, E>(passphrase: &str, upload: S) -> Result<(), KeystoreError>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+    E: std::fmt::Display,
+{
+    validate_passphrase(passphrase)?;
+
+    let path = std::env::temp_dir().join(format!(".keystore-upload-{}", crate::keys::random_hex(8)));
+    let result = async {
+        if receive(&path, upload).await? == 0 {
+            return Err(KeystoreError::BadArchive("Archive is empty".to_string()));
+        }
+        run_script("restore", passphrase, Some(&path)).await.map(|_| ())
+    }
+    .await;
+    let _ = tokio::fs::remove_file(&path).await;
+    result
+}
+
+/// Write the upload to an owner-only file at `path`, returning its size
+async fn receive<S, E>(path: &Path, mut upload: S) -> Result<usize, KeystoreError>
+where
+    S: Stream<Item = Result<Bytes, E>> + Unpin,
+    E: std::fmt::Display,
+{
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options
+        .open(path)
+        .await
+        .map_err(|e| KeystoreError::Failed(format!("Failed to create {}: {}", path.display(), e)))?;
+
+    let mut size = 0;
+    while let Some(chunk) = upload.next().await {
+        let chunk = chunk.map_err(|e| KeystoreError::Invalid(format!("Failed to receive archive: {}", e)))?;
+        size += chunk.len();
+        if size > MAX_RESTORE_ARCHIVE_BYTES {
+            return Err(KeystoreError::TooLarge);
+        }
+        file.write_all(&chunk)
+            .await
+            .map_err(|e| KeystoreError::Failed(format!("Failed to write {}: {}", path.display(), e)))?;
+    }
+    file.flush()
+        .await
+        .map_err(|e| KeystoreError::Failed(format!("Failed to write {}: {}", path.display(), e)))?;
+    Ok(size)
+}
+
+async fn run_script(action: &str, passphrase: &str, archive: Option<&Path>) -> Result<Vec<u8>, KeystoreError> {
+    let mut child = Command::new("docker")
+        .args(["exec", "-i", BACKUP_CONTAINER, KEYSTORE_SCRIPT, action])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| KeystoreError::Failed(format!("Failed to run keystore script: {}", e)))?;
+
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| KeystoreError::Failed("Keystore script has no stdin".to_string()))?;
+    let passphrase_line = format!("{}\n", passphrase);
+    let archive = archive.map(Path::to_path_buf);
+
+    // Feed stdin while reading output so a large archive can't deadlock the pipes
+    let writer = async move {
+        stdin.write_all(passphrase_line.as_bytes()).await?;
+        if let Some(archive) = archive {
+            let mut file = tokio::fs::File::open(&archive).await?;
+            tokio::io::copy(&mut file, &mut stdin).await?;
+        }
+        drop(stdin);
+        Ok::<_, std::io::Error>(())
+    };
+    let (written, output) = tokio::join!(writer, child.wait_with_output());
+    let output = output.map_err(|e| KeystoreError::Failed(format!("Keystore script failed: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        let message = stderr
+            .lines()
+            .rev()
+            .find(|line| line.contains("ERROR:"))
+            .and_then(|line| line.split("ERROR:").nth(1))
+            .map(|line| line.trim().to_string())
+            .unwrap_or_else(|| format!("Keystore {} failed", action));
+        return Err(match output.status.code() {
+            Some(EXIT_BAD_ARCHIVE) => KeystoreError::BadArchive(message),
+            _ => KeystoreError::Failed(message),
+        });
+    }
+    written.map_err(|e| KeystoreError::Failed(format!("Failed to send data to keystore script: {}", e)))?;
+
+    Ok(output.stdout)
+}
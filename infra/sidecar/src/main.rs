@@ -1,28 +1,55 @@
 use axum::{
     extract::{
         ws::{Message, WebSocket, WebSocketUpgrade},
-        ConnectInfo, State,
+        ConnectInfo, DefaultBodyLimit, Path, Query, State,
     },
     http::{HeaderMap, StatusCode},
     middleware::{self, Next},
     response::{IntoResponse, Response},
-    routing::{get, post},
+    routing::{delete, get, post},
     Json, Router,
 };
 use chrono::Utc;
 use mdns_sd::{ServiceDaemon, ServiceInfo};
 use serde::{Deserialize, Serialize};
-use std::{net::SocketAddr, process::Command, sync::Arc, time::Duration};
+use std::{net::SocketAddr, process::Command, sync::Arc};
+use axum_server::tls_rustls::RustlsConfig;
 use tower::ServiceBuilder;
-use tower_governor::{
-    governor::GovernorConfigBuilder, key_extractor::SmartIpKeyExtractor, GovernorLayer,
-};
 use tower_http::{
     cors::{AllowOrigin, CorsLayer},
     services::ServeDir,
     trace::TraceLayer,
 };
-use tracing::{error, info, warn};
+use tracing::{error, info, warn, Instrument};
+
+mod audit;
+mod chat_export;
+mod conductor;
+mod discovery;
+mod health;
+mod keys;
+mod keystore;
+mod network;
+mod peers;
+mod persist;
+mod presence;
+mod qr;
+mod ratelimit;
+mod sessions;
+mod setup;
+mod storage;
+mod supervisor;
+mod tls;
+mod tunnel;
+mod ui_assets;
+mod updates;
+mod zome_calls;
+
+/// Latest encrypted backup written by the backup container
+const BACKUP_PATH: &str = "/backups/latest-backup.tar.gz.enc";
+
+/// Placeholder `ADMIN_API_KEY` from `.env.example`; treated as unset
+const DEFAULT_ADMIN_API_KEY: &str = "change-me-in-production";
 
 // ============================================================================
 // Configuration
@@ -30,22 +57,39 @@ use tracing::{error, info, warn};
 
 #[derive(Clone)]
 struct AppState {
-    admin_api_key: String,
+    keys: keys::SharedKeyStore,
     docker_compose_file: String,
     neighborhood_name: String,
     mdns_hostname: String,
+    conductor_addr: String,
+    nginx_addr: String,
+    data_path: String,
+    port: u16,
+    peers: peers::PeerRegistry,
+    tls: tls::TlsSettings,
+    update_state_path: std::path::PathBuf,
+    update_state: Arc<tokio::sync::Mutex<updates::UpdateState>>,
+    /// Held for the whole duration of an update or rollback
+    update_lock: Arc<tokio::sync::Mutex<()>>,
+    storage: storage::StorageConfig,
+    /// Hub-wide events fanned out to every connected WebSocket client
+    events: tokio::sync::broadcast::Sender<String>,
+    sessions: Arc<sessions::SessionManager>,
+    setup: Arc<setup::SetupManager>,
+    /// Transport totals from the last `/system/network` report
+    network_sample: Arc<tokio::sync::Mutex<Option<network::TrafficSample>>>,
+    tunnel: Arc<tunnel::TunnelManager>,
+    discovery: Arc<discovery::HubBrowser>,
+    audit: Arc<audit::AuditLog>,
+    presence: Arc<presence::PresenceBridge>,
+    ui: Arc<ui_assets::UiAssets>,
+    zome_calls: Arc<zome_calls::ZomeCaller>,
 }
 
 // ============================================================================
 // API Models
 // ============================================================================
 
-#[derive(Serialize, Deserialize)]
-struct HealthResponse {
-    status: String,
-    timestamp: String,
-}
-
 #[derive(Serialize, Deserialize)]
 struct VersionInfo {
     version: String,
@@ -69,6 +113,10 @@ struct ErrorResponse {
     error: String,
 }
 
+fn app_version() -> String {
+    std::env::var("APP_VERSION").unwrap_or_else(|_| "0.1.0".to_string())
+}
+
 // ============================================================================
 // Authentication Middleware
 // ============================================================================
@@ -85,23 +133,52 @@ async fn auth_middleware(
         return Ok(next.run(request).await);
     }
 
+    // Setup runs before any admin key exists; its handlers refuse once initialized
+    if request.uri().path() == "/setup" {
+        let method = request.method().clone();
+        let response = next.run(request).await;
+        if audit::AuditLog::is_audited(&method, "/setup") {
+            let ip = client_ip(&state, &headers, &addr).await;
+            audit_call(&state, None, &ip, &method, "/setup", response.status()).await;
+        }
+        return Ok(response);
+    }
+
+    // Phones browse for hubs before they have any credentials; mDNS announces the same data
+    if request.uri().path() == "/discovery/hubs" {
+        return Ok(next.run(request).await);
+    }
+
+    // The UI polls this to notice a new bundle; the hash is in every UI response anyway
+    if request.uri().path() == "/ui/version" {
+        return Ok(next.run(request).await);
+    }
+
     // Extract real IP from X-Real-IP header (set by nginx)
     let real_ip = headers
         .get("X-Real-IP")
         .and_then(|v| v.to_str().ok())
         .unwrap_or("unknown");
 
-    let api_key = headers
+    let presented = headers
         .get("X-OurBlock-Admin-Key")
         .and_then(|v| v.to_str().ok());
 
-    if api_key != Some(&state.admin_api_key) {
+    let key = match presented {
+        Some(secret) => state.keys.read().await.authenticate(secret),
+        None => None,
+    };
+
+    let Some(key) = key else {
         warn!(
             real_ip = real_ip,
             proxy_ip = %addr.ip(),
             path = %request.uri().path(),
             "Unauthorized access attempt"
         );
+        let ip = client_ip(&state, &headers, &addr).await;
+        let path = request.uri().path();
+        audit_call(&state, None, &ip, request.method(), path, StatusCode::UNAUTHORIZED).await;
         
         return Err((
             StatusCode::UNAUTHORIZED,
@@ -109,31 +186,102 @@ async fn auth_middleware(
                 error: "Invalid or missing API key".to_string(),
             }),
         ));
+    };
+
+    let method = request.method().clone();
+    let path = request.uri().path().to_string();
+    let audited = audit::AuditLog::is_audited(&method, &path);
+
+    let required = keys::Scope::required_for(&method, &path);
+    if !key.allows(required) {
+        warn!(
+            real_ip = real_ip,
+            key_id = %key.id,
+            key_name = %key.name,
+            path = %path,
+            required_scope = required.as_str(),
+            "Key lacks required scope"
+        );
+        if audited {
+            let ip = client_ip(&state, &headers, &addr).await;
+            audit_call(&state, Some(&key), &ip, &method, &path, StatusCode::FORBIDDEN).await;
+        }
+
+        return Err((
+            StatusCode::FORBIDDEN,
+            Json(ErrorResponse {
+                error: format!("API key lacks the '{}' scope", required.as_str()),
+            }),
+        ));
     }
 
     info!(
         real_ip = real_ip,
         proxy_ip = %addr.ip(),
-        path = %request.uri().path(),
+        key_id = %key.id,
+        key_name = %key.name,
+        path = %path,
         "Authenticated request"
     );
 
-    Ok(next.run(request).await)
+    let response = next.run(request).await;
+    if audited {
+        let ip = client_ip(&state, &headers, &addr).await;
+        audit_call(&state, Some(&key), &ip, &method, &path, response.status()).await;
+    }
+
+    Ok(response)
+}
+
+/// Client address as seen by nginx, falling back to the connecting address.
+/// X-Real-IP is only believed from nginx itself; anything else reaching the
+/// sidecar directly could put whatever it likes there.
+async fn client_ip(state: &AppState, headers: &HeaderMap, addr: &SocketAddr) -> String {
+    let from_proxy = tokio::net::lookup_host(state.nginx_addr.as_str())
+        .await
+        .is_ok_and(|mut proxies| proxies.any(|proxy| proxy.ip() == addr.ip()));
+    headers
+        .get("X-Real-IP")
+        .filter(|_| from_proxy)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+        .unwrap_or_else(|| addr.ip().to_string())
+}
+
+/// Append to the audit log; a failed write is logged but never fails the request
+async fn audit_call(
+    state: &AppState,
+    key: Option<&keys::ApiKey>,
+    ip: &str,
+    method: &axum::http::Method,
+    path: &str,
+    status: StatusCode,
+) {
+    let record = audit::AuditRecord {
+        key_id: key.map(|key| key.id.as_str()),
+        key_name: key.map(|key| key.name.as_str()),
+        ip,
+        method: method.as_str(),
+        path,
+        status: status.as_u16(),
+    };
+    if let Err(e) = state.audit.record(record).await {
+        error!(error = %e, path = %path, "Failed to write audit log entry");
+    }
 }
 
 // ============================================================================
 // Handlers
 // ============================================================================
 
-async fn health_handler() -> Json<HealthResponse> {
-    Json(HealthResponse {
-        status: "ok".to_string(),
-        timestamp: Utc::now().to_rfc3339(),
-    })
+/// GET /api/health
+/// Probes the conductor, reverse proxy, backups, and disk, returning a status per component
+async fn health_handler(State(state): State<Arc<AppState>>) -> Json<health::HealthReport> {
+    Json(health::check_all(&state).await)
 }
 
 async fn version_handler() -> Result<Json<VersionInfo>, (StatusCode, Json<ErrorResponse>)> {
-    let current_version = std::env::var("APP_VERSION").unwrap_or_else(|_| "0.1.0".to_string());
+    let current_version = app_version();
     
     // TODO: Query Docker Hub or GitHub for latest version
     let latest_version = current_version.clone();
@@ -164,16 +312,22 @@ async fn update_handler(
         "Update request received"
     );
 
+    let requested = payload
+        .version
+        .map(|version| version.trim().to_string())
+        .filter(|version| !version.is_empty());
+    if let Some(version) = &requested {
+        updates::validate_version(version).map_err(|e| {
+            (StatusCode::BAD_REQUEST, Json(ErrorResponse { error: e }))
+        })?;
+    }
+
+    let guard = state.update_lock.clone().try_lock_owned().map_err(|_| update_in_progress())?;
+
     // Execute docker compose pull and up in background
     tokio::spawn(async move {
-        match execute_docker_update(&state.docker_compose_file).await {
-            Ok(_) => {
-                info!("Update completed successfully");
-            }
-            Err(e) => {
-                error!(error = %e, "Update failed");
-            }
-        }
+        let _guard = guard;
+        run_update(&state, requested).await;
     });
 
     Ok(Json(UpdateResponse {
@@ -182,6 +336,171 @@ async fn update_handler(
     }))
 }
 
+/// POST /api/update/rollback
+/// Restores the images that were running before the most recent update
+async fn rollback_handler(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+) -> Result<Json<UpdateResponse>, (StatusCode, Json<ErrorResponse>)> {
+    info!(proxy_ip = %addr.ip(), "Rollback request received");
+
+    let guard = state.update_lock.clone().try_lock_owned().map_err(|_| update_in_progress())?;
+
+    if state.update_state.lock().await.previous.is_none() {
+        return Err((
+            StatusCode::CONFLICT,
+            Json(ErrorResponse {
+                error: "No previous version recorded to roll back to".to_string(),
+            }),
+        ));
+    }
+
+    tokio::spawn(async move {
+        let _guard = guard;
+        run_rollback(&state).await;
+    });
+
+    Ok(Json(UpdateResponse {
+        status: "rolling_back".to_string(),
+        message: "Rollback process started".to_string(),
+    }))
+}
+
+#[derive(Serialize)]
+struct UpdateStatusResponse {
+    in_progress: bool,
+    #[serde(flatten)]
+    state: updates::UpdateState,
+}
+
+/// GET /api/update/status
+/// Pinned version, previous image snapshot, and the outcome of the last update or rollback
+async fn update_status_handler(State(state): State<Arc<AppState>>) -> Json<UpdateStatusResponse> {
+    Json(UpdateStatusResponse {
+        in_progress: state.update_lock.try_lock().is_err(),
+        state: state.update_state.lock().await.clone(),
+    })
+}
+
+fn update_in_progress() -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::CONFLICT,
+        Json(ErrorResponse {
+            error: "An update or rollback is already in progress".to_string(),
+        }),
+    )
+}
+
+/// Snapshot the running images, update, and roll back automatically if the hub doesn't come back healthy
+async fn run_update(state: &AppState, requested: Option<String>) {
+    let (target, running_version) = {
+        let update_state = state.update_state.lock().await;
+        (
+            update_state.target_version(requested.as_deref()),
+            update_state
+                .current_version
+                .clone()
+                .unwrap_or_else(|| update_state.target_version(None)),
+        )
+    };
+
+    let snapshot = match updates::snapshot_images(&state.docker_compose_file, &running_version) {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            error!(error = %e, "Could not record running images; refusing to update without a rollback point");
+            record_update_outcome(state, "update", "failed", format!("Could not record running images: {}", e)).await;
+            return;
+        }
+    };
+
+    {
+        let mut update_state = state.update_state.lock().await;
+        update_state.previous = Some(snapshot.clone());
+        if requested.is_some() {
+            update_state.pinned_version = (target != updates::DEFAULT_VERSION).then(|| target.clone());
+        }
+    }
+
+    info!(version = %target, "Updating hub");
+    let result = match execute_docker_update(&state.docker_compose_file, &target).await {
+        Ok(_) => updates::wait_until_healthy(state).await,
+        Err(e) => Err(e),
+    };
+
+    match result {
+        Ok(()) => {
+            info!(version = %target, "Update completed successfully");
+            state.update_state.lock().await.current_version = Some(target.clone());
+            record_update_outcome(state, "update", "success", format!("Updated to {}", target)).await;
+        }
+        Err(e) => {
+            error!(error = %e, "Update failed, rolling back");
+            match updates::restore_snapshot(&state.docker_compose_file, &snapshot) {
+                Ok(()) => {
+                    record_update_outcome(
+                        state,
+                        "update",
+                        "rolled_back",
+                        format!("Update to {} failed and was rolled back: {}", target, e),
+                    )
+                    .await
+                }
+                Err(rollback_error) => {
+                    error!(error = %rollback_error, "Automatic rollback failed");
+                    record_update_outcome(
+                        state,
+                        "update",
+                        "failed",
+                        format!("Update to {} failed ({}); rollback also failed: {}", target, e, rollback_error),
+                    )
+                    .await
+                }
+            }
+        }
+    }
+}
+
+/// Swap back to the previous snapshot, keeping the current images as the new rollback point
+async fn run_rollback(state: &AppState) {
+    let (previous, running_version) = {
+        let update_state = state.update_state.lock().await;
+        let Some(previous) = update_state.previous.clone() else {
+            return;
+        };
+        let running_version = update_state
+            .current_version
+            .clone()
+            .unwrap_or_else(|| update_state.target_version(None));
+        (previous, running_version)
+    };
+
+    let current = updates::snapshot_images(&state.docker_compose_file, &running_version).ok();
+
+    match updates::restore_snapshot(&state.docker_compose_file, &previous) {
+        Ok(()) => {
+            info!(version = %previous.version, "Rollback completed successfully");
+            {
+                let mut update_state = state.update_state.lock().await;
+                update_state.current_version = Some(previous.version.clone());
+                update_state.previous = current;
+            }
+            record_update_outcome(state, "rollback", "success", format!("Rolled back to {}", previous.version)).await;
+        }
+        Err(e) => {
+            error!(error = %e, "Rollback failed");
+            record_update_outcome(state, "rollback", "failed", e).await;
+        }
+    }
+}
+
+async fn record_update_outcome(state: &AppState, action: &str, status: &str, message: String) {
+    let mut update_state = state.update_state.lock().await;
+    update_state.record(action, status, message);
+    if let Err(e) = update_state.save(&state.update_state_path).await {
+        error!(error = %e, "Failed to persist update state");
+    }
+}
+
 async fn restart_handler(
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     headers: HeaderMap,
@@ -214,26 +533,172 @@ async fn restart_handler(
     }))
 }
 
+// ============================================================================
+// UI Bundle Handlers
+// ============================================================================
+
+#[derive(Serialize)]
+struct UiUpdateResponse {
+    status: String,
+    message: String,
+    bundle: ui_assets::ActiveBundle,
+}
+
+fn ui_update_error(e: ui_assets::UiUpdateError) -> (StatusCode, Json<ErrorResponse>) {
+    let (status, error) = match e {
+        ui_assets::UiUpdateError::Invalid(e) => (StatusCode::UNPROCESSABLE_ENTITY, e),
+        ui_assets::UiUpdateError::Failed(e) => {
+            error!(error = %e, "UI update failed");
+            (StatusCode::INTERNAL_SERVER_ERROR, e)
+        }
+    };
+    (status, Json(ErrorResponse { error }))
+}
+
+/// GET /api/ui/version
+/// Hash of the UI bundle being served, and whether it came from the image or an upload
+async fn ui_version_handler(State(state): State<Arc<AppState>>) -> Json<ui_assets::ActiveBundle> {
+    Json(state.ui.active().await)
+}
+
+/// POST /api/update/ui
+/// Swaps in a new UI bundle (gzipped tar of the built `dist/`) without touching the containers
+async fn update_ui_handler(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    archive: axum::body::Bytes,
+) -> Result<Json<UiUpdateResponse>, (StatusCode, Json<ErrorResponse>)> {
+    info!(proxy_ip = %addr.ip(), size = archive.len(), "UI update requested");
+
+    // A full update replaces the image bundle; don't race it
+    let _guard = state.update_lock.clone().try_lock_owned().map_err(|_| update_in_progress())?;
+
+    let (bundle, changed) = match state.ui.install(&archive).await {
+        Ok(installed) => installed,
+        Err(e) => {
+            let message = match &e {
+                ui_assets::UiUpdateError::Invalid(e) | ui_assets::UiUpdateError::Failed(e) => e.clone(),
+            };
+            record_update_outcome(&state, "ui_update", "failed", message).await;
+            return Err(ui_update_error(e));
+        }
+    };
+
+    if !changed {
+        return Ok(Json(UiUpdateResponse {
+            status: "unchanged".to_string(),
+            message: "This UI bundle is already being served".to_string(),
+            bundle,
+        }));
+    }
+
+    let message = format!("UI bundle {}", bundle.hash);
+    record_update_outcome(&state, "ui_update", "success", message.clone()).await;
+
+    let event = serde_json::json!({
+        "type": "ui_updated",
+        "hash": bundle.hash,
+    });
+    // No receivers just means no clients are connected
+    let _ = state.events.send(event.to_string());
+
+    Ok(Json(UiUpdateResponse {
+        status: "success".to_string(),
+        message,
+        bundle,
+    }))
+}
+
+/// Static UI files from the active bundle. Hashed `/assets/` files are cached
+/// for good; everything else is revalidated so a new bundle is picked up.
+async fn serve_ui(State(state): State<Arc<AppState>>, request: axum::extract::Request) -> Response {
+    use tower::Service;
+
+    let bundle = state.ui.active().await;
+    let path = request.uri().path().to_string();
+    let response = ServeDir::new(&bundle.dir)
+        .call(request)
+        .await
+        .unwrap_or_else(|infallible| match infallible {});
+    let mut response = response.into_response();
+
+    let cache_control = if response.status().is_success() || response.status() == StatusCode::NOT_MODIFIED {
+        ui_assets::cache_control(&path)
+    } else {
+        ui_assets::REVALIDATE_CACHE_CONTROL
+    };
+    let headers = response.headers_mut();
+    headers.insert(
+        axum::http::header::CACHE_CONTROL,
+        axum::http::HeaderValue::from_static(cache_control),
+    );
+    if let Ok(version) = axum::http::HeaderValue::from_str(&bundle.hash) {
+        headers.insert("X-UI-Version", version);
+    }
+    response
+}
+
 // ============================================================================
 // WebSocket Handler for Mobile Clients
 // ============================================================================
 
+#[derive(Deserialize)]
+struct WsParams {
+    token: Option<String>,
+}
+
 async fn ws_handler(
     ws: WebSocketUpgrade,
+    State(state): State<Arc<AppState>>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
-) -> impl IntoResponse {
-    info!(client_ip = %addr.ip(), "New WebSocket connection");
-    ws.on_upgrade(move |socket| handle_socket(socket, addr))
+    Query(params): Query<WsParams>,
+) -> Response {
+    let session = match params.token.as_deref().map(|token| state.sessions.verify(token)) {
+        Some(Ok(session)) => Some(session),
+        Some(Err(e)) => {
+            warn!(client_ip = %addr.ip(), error = %e, "WebSocket connection refused");
+            return (StatusCode::UNAUTHORIZED, Json(ErrorResponse { error: e })).into_response();
+        }
+        None if state.sessions.require_auth() => {
+            warn!(client_ip = %addr.ip(), "WebSocket connection without session token refused");
+            return (
+                StatusCode::UNAUTHORIZED,
+                Json(ErrorResponse {
+                    error: "Session token required (POST /session, then connect to /ws?token=...)".to_string(),
+                }),
+            )
+                .into_response();
+        }
+        None => None,
+    };
+
+    // Every log line from this connection carries its session for correlation
+    let span = match &session {
+        Some(session) => tracing::info_span!("ws_session", session_id = %session.id, identity = %session.identity),
+        None => tracing::info_span!("ws_session", session_id = "anonymous"),
+    };
+
+    info!(parent: &span, client_ip = %addr.ip(), "New WebSocket connection");
+    let events = state.events.subscribe();
+    ws.on_upgrade(move |socket| handle_socket(socket, addr, events, state, session).instrument(span))
 }
 
-async fn handle_socket(mut socket: WebSocket, addr: SocketAddr) {
+async fn handle_socket(
+    mut socket: WebSocket,
+    addr: SocketAddr,
+    mut events: tokio::sync::broadcast::Receiver<String>,
+    state: Arc<AppState>,
+    session: Option<sessions::Session>,
+) {
     info!(client_ip = %addr.ip(), "WebSocket connection established");
     
     // Send welcome message
     let welcome = serde_json::json!({
         "type": "welcome",
         "message": "Connected to OurBlock Hub",
-        "version": std::env::var("APP_VERSION").unwrap_or_else(|_| "0.1.0".to_string()),
+        "version": app_version(),
+        "session_id": session.as_ref().map(|session| &session.id),
+        "expires_at": session.as_ref().map(|session| session.expires_at),
     });
     
     if socket
@@ -245,39 +710,82 @@ async fn handle_socket(mut socket: WebSocket, addr: SocketAddr) {
         return;
     }
 
-    // Handle incoming messages
-    while let Some(msg) = socket.recv().await {
-        match msg {
-            Ok(Message::Text(text)) => {
-                info!(client_ip = %addr.ip(), message = %text, "Received message");
-                
-                // Echo back for now (will be replaced with Holochain conductor proxy)
-                let response = serde_json::json!({
-                    "type": "echo",
-                    "data": text,
-                });
-                
-                if socket
-                    .send(Message::Text(response.to_string()))
-                    .await
-                    .is_err()
-                {
-                    error!(client_ip = %addr.ip(), "Failed to send response");
+    // Agents are online in chat for as long as they hold a connection
+    let agent = session
+        .as_ref()
+        .and_then(|session| session.identity.strip_prefix("agent:"))
+        .map(str::to_string);
+    if let Some(agent) = &agent {
+        state.presence.connected(agent);
+    }
+
+    loop {
+        tokio::select! {
+            // Forward hub events (e.g. storage warnings) to this client
+            event = events.recv() => match event {
+                Ok(event) => {
+                    if socket.send(Message::Text(event)).await.is_err() {
+                        error!(client_ip = %addr.ip(), "Failed to forward hub event");
+                        break;
+                    }
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                    warn!(client_ip = %addr.ip(), skipped = skipped, "WebSocket client fell behind on hub events");
+                }
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            },
+            // Handle incoming messages
+            msg = socket.recv() => match msg {
+                Some(Ok(Message::Text(text))) => {
+                    if let Some(session) = &session {
+                        if let Err(wait) = state.sessions.check_message(&session.id) {
+                            warn!(client_ip = %addr.ip(), "WebSocket session rate limit exceeded");
+                            let error = serde_json::json!({
+                                "type": "error",
+                                "error": "rate_limited",
+                                "retry_after_ms": wait.as_millis() as u64,
+                            });
+                            if socket.send(Message::Text(error.to_string())).await.is_err() {
+                                break;
+                            }
+                            continue;
+                        }
+                    }
+
+                    info!(client_ip = %addr.ip(), message = %text, "Received message");
+                    
+                    // Echo back for now (will be replaced with Holochain conductor proxy)
+                    let response = serde_json::json!({
+                        "type": "echo",
+                        "data": text,
+                    });
+                    
+                    if socket
+                        .send(Message::Text(response.to_string()))
+                        .await
+                        .is_err()
+                    {
+                        error!(client_ip = %addr.ip(), "Failed to send response");
+                        break;
+                    }
+                }
+                Some(Ok(Message::Close(_))) | None => {
+                    info!(client_ip = %addr.ip(), "Client closed connection");
                     break;
                 }
-            }
-            Ok(Message::Close(_)) => {
-                info!(client_ip = %addr.ip(), "Client closed connection");
-                break;
-            }
-            Err(e) => {
-                error!(client_ip = %addr.ip(), error = %e, "WebSocket error");
-                break;
-            }
-            _ => {}
+                Some(Err(e)) => {
+                    error!(client_ip = %addr.ip(), error = %e, "WebSocket error");
+                    break;
+                }
+                _ => {}
+            },
         }
     }
 
+    if let Some(agent) = &agent {
+        state.presence.disconnected(agent);
+    }
+
     info!(client_ip = %addr.ip(), "WebSocket connection closed");
 }
 
@@ -285,28 +793,29 @@ async fn handle_socket(mut socket: WebSocket, addr: SocketAddr) {
 // mDNS Service Discovery
 // ============================================================================
 
-async fn start_mdns_service(hostname: &str, neighborhood_name: &str, port: u16) {
+/// Announce this hub and return the daemon so it can also browse for peers
+async fn start_mdns_service(hostname: &str, neighborhood_name: &str, port: u16) -> Option<(ServiceDaemon, String)> {
     info!("Starting mDNS service discovery...");
     
     let mdns = match ServiceDaemon::new() {
         Ok(daemon) => daemon,
         Err(e) => {
             error!(error = %e, "Failed to create mDNS daemon");
-            return;
+            return None;
         }
     };
 
-    let service_type = "_ourblock._tcp.local.";
     let instance_name = format!("OurBlock Hub - {}", neighborhood_name);
+    let version = app_version();
     
     let properties = [
-        ("version", "0.1.0"),
+        ("version", version.as_str()),
         ("neighborhood", neighborhood_name),
         ("type", "hub"),
     ];
 
     let service_info = ServiceInfo::new(
-        service_type,
+        peers::SERVICE_TYPE,
         &instance_name,
         hostname,
         (),
@@ -314,6 +823,7 @@ async fn start_mdns_service(hostname: &str, neighborhood_name: &str, port: u16)
         &properties[..],
     )
     .expect("Failed to create service info");
+    let fullname = service_info.get_fullname().to_string();
 
     match mdns.register(service_info) {
         Ok(_) => {
@@ -329,12 +839,140 @@ async fn start_mdns_service(hostname: &str, neighborhood_name: &str, port: u16)
         }
     }
 
-    // Keep the daemon running
-    tokio::spawn(async move {
-        loop {
-            tokio::time::sleep(Duration::from_secs(60)).await;
+    Some((mdns, fullname))
+}
+
+// ============================================================================
+// Peer Hub Handlers
+// ============================================================================
+
+#[derive(Deserialize)]
+struct PeerHandshakeRequest {
+    hostname: String,
+}
+
+fn hub_metadata(state: &AppState) -> peers::HubMetadata {
+    peers::HubMetadata {
+        neighborhood_name: state.neighborhood_name.clone(),
+        hostname: state.mdns_hostname.clone(),
+        version: app_version(),
+        port: state.port,
+    }
+}
+
+/// GET /api/peers
+/// Lists OurBlock hubs discovered on the LAN or met via handshake
+async fn list_peers_handler(State(state): State<Arc<AppState>>) -> Json<Vec<peers::PeerHub>> {
+    let mut hubs: Vec<peers::PeerHub> = state.peers.read().await.values().cloned().collect();
+    hubs.sort_by(|a, b| a.hostname.cmp(&b.hostname));
+    Json(hubs)
+}
+
+/// GET /api/discovery/hubs
+/// Scans the LAN for every OurBlock hub, this one included, for the onboarding picker
+async fn discover_hubs_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<discovery::DiscoveryResult>, (StatusCode, Json<ErrorResponse>)> {
+    let result = state.discovery.hubs().await.map_err(|e| {
+        error!(error = %e, "Hub discovery failed");
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse {
+                error: "mDNS discovery is unavailable".to_string(),
+            }),
+        )
+    })?;
+
+    Ok(Json(result))
+}
+
+/// POST /api/peers/handshake
+/// Initiates a handshake with a discovered peer, exchanging neighborhood metadata
+async fn initiate_handshake_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<PeerHandshakeRequest>,
+) -> Result<Json<peers::HubMetadata>, (StatusCode, Json<ErrorResponse>)> {
+    let peer = state
+        .peers
+        .read()
+        .await
+        .get(&payload.hostname)
+        .cloned()
+        .ok_or((
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "Unknown peer hub".to_string(),
+            }),
+        ))?;
+
+    let ours = hub_metadata(&state);
+    let mut last_error = "Peer has no known addresses".to_string();
+    for address in &peer.addresses {
+        match peers::send_handshake(address, peer.port, &ours).await {
+            Ok(theirs) => {
+                let origin = peers::HandshakeOrigin::Outbound(address.clone());
+                if let Err(e) = peers::record_handshake(&state.peers, &theirs, origin).await {
+                    warn!(hostname = %theirs.hostname, error = ?e, "Could not record peer hub");
+                }
+                info!(hostname = %theirs.hostname, neighborhood = %theirs.neighborhood_name, "Handshake completed");
+                return Ok(Json(theirs));
+            }
+            Err(e) => {
+                warn!(address = %address, error = %e, "Handshake attempt failed");
+                last_error = e;
+            }
         }
-    });
+    }
+
+    Err((
+        StatusCode::BAD_GATEWAY,
+        Json(ErrorResponse {
+            error: format!("Handshake failed: {}", last_error),
+        }),
+    ))
+}
+
+/// POST /federation/handshake
+/// Called by other hubs (no admin key); records the caller and replies with our metadata
+async fn federation_handshake_handler(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Json(remote): Json<peers::HubMetadata>,
+) -> Result<Json<peers::HubMetadata>, (StatusCode, Json<ErrorResponse>)> {
+    if remote.hostname.is_empty() || remote.hostname.len() > 255 || remote.neighborhood_name.len() > 255 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            Json(ErrorResponse {
+                error: "Invalid hub metadata".to_string(),
+            }),
+        ));
+    }
+
+    let origin = peers::HandshakeOrigin::Inbound(addr.ip().to_string());
+    if let Err(e) = peers::record_handshake(&state.peers, &remote, origin).await {
+        let (status, error) = match e {
+            peers::HandshakeError::RegistryFull => (StatusCode::SERVICE_UNAVAILABLE, "Peer registry is full"),
+            peers::HandshakeError::UnknownAddress => {
+                warn!(peer_ip = %addr.ip(), hostname = %remote.hostname, "Handshake claimed a known hub's hostname");
+                (StatusCode::CONFLICT, "A hub with this hostname is already known at another address")
+            }
+        };
+        return Err((
+            status,
+            Json(ErrorResponse {
+                error: error.to_string(),
+            }),
+        ));
+    }
+
+    info!(
+        peer_ip = %addr.ip(),
+        hostname = %remote.hostname,
+        neighborhood = %remote.neighborhood_name,
+        "Handshake received from peer hub"
+    );
+
+    Ok(Json(hub_metadata(&state)))
 }
 
 // ============================================================================
@@ -354,9 +992,7 @@ async fn backup_status_handler() -> Result<Json<BackupStatusResponse>, (StatusCo
     info!("Backup status requested");
     
     // Check if latest backup symlink exists
-    let backup_path = "/backups/latest-backup.tar.gz.enc";
-    
-    match tokio::fs::metadata(backup_path).await {
+    match tokio::fs::metadata(BACKUP_PATH).await {
         Ok(metadata) => {
             let size_bytes = metadata.len();
             let size_mb = size_bytes as f64 / (1024.0 * 1024.0);
@@ -399,10 +1035,8 @@ async fn backup_status_handler() -> Result<Json<BackupStatusResponse>, (StatusCo
 async fn download_backup_handler() -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
     info!("Backup download requested");
     
-    let backup_path = "/backups/latest-backup.tar.gz.enc";
-    
     // Read backup file
-    let file_contents = tokio::fs::read(backup_path).await.map_err(|e| {
+    let file_contents = tokio::fs::read(BACKUP_PATH).await.map_err(|e| {
         error!(error = %e, "Failed to read backup file");
         (
             StatusCode::NOT_FOUND,
@@ -477,26 +1111,676 @@ async fn trigger_backup_handler() -> Result<Json<UpdateResponse>, (StatusCode, J
 }
 
 // ============================================================================
-// Docker Operations
+// Keystore Handlers
 // ============================================================================
 
-async fn execute_docker_update(compose_file: &str) -> Result<(), String> {
-    info!("Pulling latest Docker images...");
-    
-    let pull_output = Command::new("docker")
-        .args(["compose", "-f", compose_file, "pull"])
-        .output()
-        .map_err(|e| format!("Failed to execute docker compose pull: {}", e))?;
+#[derive(Deserialize)]
+struct KeystoreExportRequest {
+    passphrase: String,
+}
 
-    if !pull_output.status.success() {
-        let stderr = String::from_utf8_lossy(&pull_output.stderr);
-        return Err(format!("Docker compose pull failed: {}", stderr));
-    }
+/// Header carrying the passphrase for a restore, whose body is the raw archive
+const KEYSTORE_PASSPHRASE_HEADER: &str = "X-OurBlock-Keystore-Passphrase";
+
+fn keystore_error(e: keystore::KeystoreError) -> (StatusCode, Json<ErrorResponse>) {
+    let (status, error) = match e {
+        keystore::KeystoreError::Invalid(e) => (StatusCode::BAD_REQUEST, e),
+        keystore::KeystoreError::TooLarge => (
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("Archive is larger than {} bytes", keystore::MAX_RESTORE_ARCHIVE_BYTES),
+        ),
+        keystore::KeystoreError::BadArchive(e) => (StatusCode::UNPROCESSABLE_ENTITY, e),
+        keystore::KeystoreError::Failed(e) => {
+            error!(error = %e, "Keystore operation failed");
+            (StatusCode::INTERNAL_SERVER_ERROR, e)
+        }
+    };
+    (status, Json(ErrorResponse { error }))
+}
+
+/// POST /api/system/keystore/export
+/// Downloads the conductor's keystore, encrypted with the given passphrase
+async fn keystore_export_handler(
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Json(payload): Json<KeystoreExportRequest>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    info!(proxy_ip = %addr.ip(), "Keystore export requested");
+
+    let archive = keystore::export(&payload.passphrase).await.map_err(keystore_error)?;
+
+    let timestamp = Utc::now().format("%Y%m%d_%H%M%S");
+    let filename = format!("ourblock_keystore_{}.tar.gz.enc", timestamp);
+
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        "application/octet-stream".parse().unwrap(),
+    );
+    headers.insert(
+        axum::http::header::CONTENT_DISPOSITION,
+        format!("attachment; filename=\"{}\"", filename).parse().unwrap(),
+    );
+
+    info!(filename = %filename, size = archive.len(), "Sending keystore export");
+
+    Ok((headers, archive))
+}
+
+/// POST /api/system/keystore/restore
+/// Replaces the conductor's keystore with one from a keystore export or full backup
+async fn keystore_restore_handler(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    archive: axum::body::Body,
+) -> Result<Json<UpdateResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let passphrase = headers
+        .get(KEYSTORE_PASSPHRASE_HEADER)
+        .and_then(|v| v.to_str().ok())
+        .ok_or_else(|| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: format!("Missing {} header", KEYSTORE_PASSPHRASE_HEADER),
+                }),
+            )
+        })?;
+
+    // The conductor is stopped during the swap; don't race an update doing the same
+    let _guard = state.update_lock.clone().try_lock_owned().map_err(|_| update_in_progress())?;
+
+    let size = headers
+        .get(axum::http::header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .unwrap_or("unknown");
+    warn!(proxy_ip = %addr.ip(), size = %size, "Keystore restore requested");
+
+    keystore::restore(passphrase, archive.into_data_stream())
+        .await
+        .map_err(keystore_error)?;
+
+    info!("Keystore restored; conductor restarted with the restored identity");
+
+    Ok(Json(UpdateResponse {
+        status: "success".to_string(),
+        message: "Keystore restored and conductor restarted".to_string(),
+    }))
+}
+
+// ============================================================================
+// Storage Handlers
+// ============================================================================
+
+/// GET /api/system/storage
+/// Per-volume usage (conductor data, backups, UI assets) against the warning thresholds
+async fn storage_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<storage::StorageReport>, (StatusCode, Json<ErrorResponse>)> {
+    let config = state.storage.clone();
+    let report = tokio::task::spawn_blocking(move || storage::report(&config))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Storage report failed");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse {
+                    error: "Failed to measure storage".to_string(),
+                }),
+            )
+        })?;
+
+    Ok(Json(report))
+}
+
+// ============================================================================
+// Network Handlers
+// ============================================================================
+
+/// GET /api/system/network
+/// Gossip throughput, connected peers, and per-DNA storage from the conductor admin API
+async fn network_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<network::NetworkReport>, (StatusCode, Json<ErrorResponse>)> {
+    let mut previous = state.network_sample.lock().await;
+    let report = network::report(&state.conductor_addr, &mut previous).await.map_err(|e| {
+        error!(error = %e, "Network report failed");
+        (StatusCode::BAD_GATEWAY, Json(ErrorResponse { error: e }))
+    })?;
+
+    Ok(Json(report))
+}
+
+// ============================================================================
+// Audit Log Handlers
+// ============================================================================
+
+#[derive(Deserialize)]
+struct AuditQuery {
+    /// Only entries older than this sequence number
+    before: Option<u64>,
+    limit: Option<usize>,
+}
+
+/// GET /api/system/audit?limit=50&before=<seq>
+/// Admin actions, newest first
+async fn audit_log_handler(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<AuditQuery>,
+) -> Result<Json<audit::AuditPage>, (StatusCode, Json<ErrorResponse>)> {
+    let page = state
+        .audit
+        .page(query.before, query.limit.unwrap_or(audit::DEFAULT_PAGE_SIZE))
+        .await
+        .map_err(|e| {
+            error!(error = %e, "Failed to read audit log");
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(ErrorResponse { error: e }))
+        })?;
+
+    Ok(Json(page))
+}
+
+// ============================================================================
+// API Key Management Handlers
+// ============================================================================
+
+#[derive(Deserialize)]
+struct CreateKeyRequest {
+    name: String,
+    scopes: Vec<keys::Scope>,
+}
+
+fn key_error(e: String) -> (StatusCode, Json<ErrorResponse>) {
+    let status = if e.starts_with("Failed") {
+        StatusCode::INTERNAL_SERVER_ERROR
+    } else if e.contains("not found") {
+        StatusCode::NOT_FOUND
+    } else {
+        StatusCode::BAD_REQUEST
+    };
+    (status, Json(ErrorResponse { error: e }))
+}
+
+/// GET /api/system/keys
+/// Lists API keys (never their secrets or hashes)
+async fn list_keys_handler(State(state): State<Arc<AppState>>) -> Json<Vec<keys::ApiKey>> {
+    Json(state.keys.read().await.list())
+}
+
+/// POST /api/system/keys
+/// Creates a named key with scopes; the secret is only returned here
+async fn create_key_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<CreateKeyRequest>,
+) -> Result<Json<keys::IssuedKey>, (StatusCode, Json<ErrorResponse>)> {
+    let issued = state
+        .keys
+        .write()
+        .await
+        .create(payload.name, payload.scopes)
+        .await
+        .map_err(key_error)?;
+
+    info!(key_id = %issued.key.id, key_name = %issued.key.name, scopes = ?issued.key.scopes, "API key created");
+    Ok(Json(issued))
+}
+
+/// POST /api/system/keys/:id/rotate
+/// Issues a new secret for a key; the old secret stops working immediately
+async fn rotate_key_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<keys::IssuedKey>, (StatusCode, Json<ErrorResponse>)> {
+    let issued = state.keys.write().await.rotate(&id).await.map_err(key_error)?;
+
+    info!(key_id = %issued.key.id, key_name = %issued.key.name, "API key rotated");
+    Ok(Json(issued))
+}
+
+/// DELETE /api/system/keys/:id
+/// Revokes a key
+async fn revoke_key_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<keys::ApiKey>, (StatusCode, Json<ErrorResponse>)> {
+    let key = state.keys.write().await.revoke(&id).await.map_err(key_error)?;
+
+    info!(key_id = %key.id, key_name = %key.name, "API key revoked");
+    Ok(Json(key))
+}
+
+// ============================================================================
+// Remote Access Tunnel Handlers
+// ============================================================================
+
+fn tunnel_error(e: tunnel::TunnelError) -> (StatusCode, Json<ErrorResponse>) {
+    let (status, error) = match e {
+        tunnel::TunnelError::NotConfigured => (StatusCode::CONFLICT, "Tunnel is not configured".to_string()),
+        tunnel::TunnelError::ClientNotFound => (StatusCode::NOT_FOUND, "Tunnel device not found".to_string()),
+        tunnel::TunnelError::Invalid(e) => (StatusCode::BAD_REQUEST, e),
+        tunnel::TunnelError::Failed(e) => {
+            error!(error = %e, "Tunnel operation failed");
+            (StatusCode::INTERNAL_SERVER_ERROR, e)
+        }
+    };
+    (status, Json(ErrorResponse { error }))
+}
+
+/// GET /api/system/tunnel
+/// Tunnel settings, container state, and member devices
+async fn tunnel_status_handler(State(state): State<Arc<AppState>>) -> Json<tunnel::TunnelStatus> {
+    Json(state.tunnel.status().await)
+}
+
+/// POST /api/system/tunnel
+/// Configures WireGuard or relay mode; a running tunnel is restarted
+async fn configure_tunnel_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<tunnel::ConfigureRequest>,
+) -> Result<Json<tunnel::TunnelStatus>, (StatusCode, Json<ErrorResponse>)> {
+    state.tunnel.configure(payload).await.map_err(tunnel_error)?;
+
+    info!("Tunnel configured");
+    Ok(Json(state.tunnel.status().await))
+}
+
+/// POST /api/system/tunnel/enable
+async fn enable_tunnel_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<tunnel::TunnelStatus>, (StatusCode, Json<ErrorResponse>)> {
+    state.tunnel.enable().await.map_err(tunnel_error)?;
+
+    info!("Tunnel enabled");
+    Ok(Json(state.tunnel.status().await))
+}
+
+/// POST /api/system/tunnel/disable
+async fn disable_tunnel_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<tunnel::TunnelStatus>, (StatusCode, Json<ErrorResponse>)> {
+    state.tunnel.disable().await.map_err(tunnel_error)?;
+
+    info!("Tunnel disabled");
+    Ok(Json(state.tunnel.status().await))
+}
+
+/// POST /api/system/tunnel/clients
+/// Generates a WireGuard config for a member device; its private key is shown only once
+async fn add_tunnel_client_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<tunnel::AddClientRequest>,
+) -> Result<Json<tunnel::IssuedClient>, (StatusCode, Json<ErrorResponse>)> {
+    let issued = state.tunnel.add_client(payload.name).await.map_err(tunnel_error)?;
+
+    info!(
+        client_id = %issued.client.id,
+        client_name = %issued.client.name,
+        address = %issued.client.address,
+        "Tunnel device added"
+    );
+    Ok(Json(issued))
+}
+
+/// DELETE /api/system/tunnel/clients/:id
+async fn remove_tunnel_client_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<tunnel::TunnelClient>, (StatusCode, Json<ErrorResponse>)> {
+    let client = state.tunnel.remove_client(&id).await.map_err(tunnel_error)?;
+
+    info!(client_id = %client.id, client_name = %client.name, "Tunnel device removed");
+    Ok(Json(client))
+}
+
+// ============================================================================
+// WebSocket Sessions
+// ============================================================================
+
+/// POST /session
+/// Exchanges an invite code or agent signature for a short-lived WebSocket token
+async fn create_session_handler(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Json(payload): Json<sessions::SessionRequest>,
+) -> Result<Json<sessions::IssuedSession>, (StatusCode, Json<ErrorResponse>)> {
+    let issued = state.sessions.issue(payload).await.map_err(|e| {
+        warn!(client_ip = %addr.ip(), error = %e, "WebSocket session refused");
+        let status = if e.starts_with("Failed") {
+            StatusCode::INTERNAL_SERVER_ERROR
+        } else {
+            StatusCode::UNAUTHORIZED
+        };
+        (status, Json(ErrorResponse { error: e }))
+    })?;
+
+    info!(
+        client_ip = %addr.ip(),
+        session_id = %issued.session_id,
+        identity = %issued.identity,
+        "WebSocket session issued"
+    );
+    Ok(Json(issued))
+}
+
+/// GET /api/system/ws-invites
+/// Lists WebSocket invite codes (never the codes themselves)
+async fn list_invites_handler(State(state): State<Arc<AppState>>) -> Json<Vec<sessions::Invite>> {
+    Json(state.sessions.list_invites().await)
+}
+
+/// POST /api/system/ws-invites
+/// Creates an invite code for pairing a mobile client; the code is only returned here
+async fn create_invite_handler(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<sessions::CreateInviteRequest>,
+) -> Result<Json<sessions::IssuedInvite>, (StatusCode, Json<ErrorResponse>)> {
+    let issued = state.sessions.create_invite(payload).await.map_err(key_error)?;
+
+    info!(invite_id = %issued.invite.id, label = %issued.invite.label, max_uses = issued.invite.max_uses, "WebSocket invite created");
+    Ok(Json(issued))
+}
+
+/// DELETE /api/system/ws-invites/:id
+/// Revokes an invite; sessions already issued from it run until they expire
+async fn revoke_invite_handler(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<String>,
+) -> Result<Json<sessions::Invite>, (StatusCode, Json<ErrorResponse>)> {
+    let invite = state.sessions.revoke_invite(&id).await.map_err(key_error)?;
+
+    info!(invite_id = %invite.id, label = %invite.label, "WebSocket invite revoked");
+    Ok(Json(invite))
+}
+
+// ============================================================================
+// First-Boot Setup
+// ============================================================================
+
+/// GET /api/setup
+/// Whether the hub has been initialized, and with what settings
+async fn setup_status_handler(State(state): State<Arc<AppState>>) -> Json<setup::SetupStatus> {
+    Json(state.setup.status().await)
+}
+
+/// POST /api/setup
+/// Initializes a fresh hub; returns the admin key once and then locks itself
+async fn setup_handler(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    Json(payload): Json<setup::SetupRequest>,
+) -> Result<Json<setup::SetupResult>, (StatusCode, Json<ErrorResponse>)> {
+    let result = state.setup.complete(payload, &state.keys).await.map_err(|e| {
+        let (status, error) = match e {
+            setup::SetupError::AlreadyInitialized => {
+                warn!(client_ip = %addr.ip(), "Setup attempted on an initialized hub");
+                (StatusCode::CONFLICT, "Hub is already set up".to_string())
+            }
+            setup::SetupError::Invalid(e) => (StatusCode::BAD_REQUEST, e),
+            setup::SetupError::Storage(e) => {
+                error!(error = %e, "Failed to save setup");
+                (StatusCode::INTERNAL_SERVER_ERROR, e)
+            }
+            setup::SetupError::Conductor(e) => {
+                error!(error = %e, "Failed to install the hApp during setup");
+                (StatusCode::BAD_GATEWAY, e)
+            }
+        };
+        (status, Json(ErrorResponse { error }))
+    })?;
+
+    info!(
+        client_ip = %addr.ip(),
+        neighborhood = %result.config.neighborhood_name,
+        key_id = %result.admin_key.key.id,
+        "Hub setup completed"
+    );
+    Ok(Json(result))
+}
+
+// ============================================================================
+// Chat Export
+// ============================================================================
+
+#[derive(Deserialize)]
+struct ChatExportParams {
+    /// Agent key (`uhCAk...`) of the other side of the conversation
+    peer: String,
+    /// "json" (default) or "text"
+    format: Option<String>,
+}
+
+/// GET /chat/export?peer=uhCAk...&format=json|text
+/// Downloads the session agent's conversation with `peer`, read from their
+/// own cell through the chat zome's `export_conversation`. Transcripts are
+/// private, so an agent session token is always required, and nothing is kept
+/// on the hub.
+async fn chat_export_handler(
+    State(state): State<Arc<AppState>>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Query(params): Query<ChatExportParams>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let reject = |status: StatusCode, error: String| (status, Json(ErrorResponse { error }));
+
+    let token = headers
+        .get(axum::http::header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    let session = match token.map(|token| state.sessions.verify(token)) {
+        Some(Ok(session)) => session,
+        Some(Err(e)) => {
+            warn!(client_ip = %addr.ip(), error = %e, "Chat export refused");
+            return Err(reject(StatusCode::UNAUTHORIZED, e));
+        }
+        None => {
+            return Err(reject(
+                StatusCode::UNAUTHORIZED,
+                "Session token required (Authorization: Bearer <token>)".to_string(),
+            ));
+        }
+    };
+    let Some(agent) = session.identity.strip_prefix("agent:") else {
+        return Err(reject(
+            StatusCode::FORBIDDEN,
+            "Chat exports need an agent session; invite sessions have no conversations".to_string(),
+        ));
+    };
+    let Some(peer) = sessions::decode_agent_key(&params.peer) else {
+        return Err(reject(
+            StatusCode::BAD_REQUEST,
+            "peer must be an agent key (uhCAk...)".to_string(),
+        ));
+    };
+    let format = params.format.as_deref().unwrap_or("json");
+    if format != "json" && format != "text" {
+        return Err(reject(
+            StatusCode::BAD_REQUEST,
+            format!("Unknown export format '{}' (expected json or text)", format),
+        ));
+    }
+
+    let transcript = chat_export::export(&state.zome_calls, agent, peer)
+        .await
+        .map_err(|e| match e {
+            chat_export::ExportError::NotHosted => reject(
+                StatusCode::NOT_FOUND,
+                "This hub doesn't host your cell; export from the device that does".to_string(),
+            ),
+            chat_export::ExportError::Failed(e) => {
+                error!(session_id = %session.id, error = %e, "Chat export failed");
+                reject(StatusCode::BAD_GATEWAY, e)
+            }
+        })?;
+
+    let (body, content_type, extension) = match format {
+        "text" => (transcript.text.into_bytes(), "text/plain; charset=utf-8", "txt"),
+        _ => (
+            serde_json::to_vec_pretty(&transcript.json).unwrap_or_default(),
+            "application/json",
+            "json",
+        ),
+    };
+
+    let filename = format!("ourblock_chat_{}.{}", Utc::now().format("%Y%m%d_%H%M%S"), extension);
+
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert(axum::http::header::CONTENT_TYPE, content_type.parse().unwrap());
+    headers.insert(
+        axum::http::header::CONTENT_DISPOSITION,
+        format!("attachment; filename=\"{}\"", filename)
+            .parse()
+            .unwrap(),
+    );
+
+    info!(session_id = %session.id, filename = %filename, size = body.len(), "Sending chat export");
+    Ok((headers, body))
+}
+
+// ============================================================================
+// TLS Handlers
+// ============================================================================
+
+#[derive(Serialize)]
+struct TlsStatusResponse {
+    mode: String,
+    https_url: Option<String>,
+    certificate_url: Option<String>,
+    fingerprint_sha256: Option<String>,
+    trust_qr_svg: Option<String>,
+}
+
+/// GET /api/system/tls
+/// Reports the TLS mode and, for self-signed certificates, a trust-bootstrap QR code
+async fn tls_status_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<Json<TlsStatusResponse>, (StatusCode, Json<ErrorResponse>)> {
+    let settings = &state.tls;
+    if !settings.enabled() {
+        return Ok(Json(TlsStatusResponse {
+            mode: settings.mode_name().to_string(),
+            https_url: None,
+            certificate_url: None,
+            fingerprint_sha256: None,
+            trust_qr_svg: None,
+        }));
+    }
+
+    let fingerprint = tls::fingerprint(&settings.cert_path()).map_err(|e| {
+        warn!(error = %e, "TLS certificate not available");
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse {
+                error: "TLS certificate has not been provisioned yet".to_string(),
+            }),
+        )
+    })?;
+
+    let hostname = settings.public_hostname();
+    let certificate_url = format!("http://{}:{}/tls/certificate.pem", hostname, state.port);
+
+    // Publicly trusted ACME certificates need no bootstrap; self-signed ones do
+    let trust_qr_svg = match settings.mode {
+        tls::TlsMode::SelfSigned => {
+            let payload = format!("{}#sha256={}", certificate_url, fingerprint.replace(':', ""));
+            Some(qr::svg(&payload).map_err(|e| {
+                error!(error = %e, "Failed to render trust QR code");
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(ErrorResponse {
+                        error: "Failed to render trust QR code".to_string(),
+                    }),
+                )
+            })?)
+        }
+        _ => None,
+    };
+
+    Ok(Json(TlsStatusResponse {
+        mode: settings.mode_name().to_string(),
+        https_url: Some(format!("https://{}:{}", hostname, settings.port)),
+        certificate_url: Some(certificate_url),
+        fingerprint_sha256: Some(fingerprint),
+        trust_qr_svg,
+    }))
+}
+
+/// GET /tls/certificate.pem
+/// Public certificate download for installing a self-signed certificate on a device
+async fn tls_certificate_handler(
+    State(state): State<Arc<AppState>>,
+) -> Result<impl IntoResponse, (StatusCode, Json<ErrorResponse>)> {
+    let pem = tokio::fs::read(state.tls.cert_path()).await.map_err(|_| {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ErrorResponse {
+                error: "No TLS certificate available".to_string(),
+            }),
+        )
+    })?;
+
+    let mut headers = axum::http::HeaderMap::new();
+    headers.insert(
+        axum::http::header::CONTENT_TYPE,
+        "application/x-pem-file".parse().unwrap(),
+    );
+    headers.insert(
+        axum::http::header::CONTENT_DISPOSITION,
+        "attachment; filename=\"ourblock-hub.pem\"".parse().unwrap(),
+    );
+
+    Ok((headers, pem))
+}
+
+/// Provision the certificate, then serve the same app over HTTPS and keep it renewed
+async fn serve_https(app: Router, settings: tls::TlsSettings) {
+    if let Err(e) = tls::provision(&settings).await {
+        error!(error = %e, "TLS provisioning failed; HTTPS disabled");
+        return;
+    }
+
+    let config = match RustlsConfig::from_pem_file(settings.cert_path(), settings.key_path()).await {
+        Ok(config) => config,
+        Err(e) => {
+            error!(error = %e, "Failed to load TLS certificate; HTTPS disabled");
+            return;
+        }
+    };
+
+    tokio::spawn(tls::renewal_loop(settings.clone(), config.clone()));
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], settings.port));
+    info!("🔒 HTTPS ({}) listening on https://{}", settings.mode_name(), addr);
+
+    if let Err(e) = axum_server::bind_rustls(addr, config)
+        .serve(app.into_make_service_with_connect_info::<SocketAddr>())
+        .await
+    {
+        error!(error = %e, "HTTPS server failed");
+    }
+}
+
+// ============================================================================
+// Docker Operations
+// ============================================================================
+
+async fn execute_docker_update(compose_file: &str, version: &str) -> Result<(), String> {
+    info!(version = version, "Pulling Docker images...");
+    
+    let pull_output = Command::new("docker")
+        .args(["compose", "-f", compose_file, "pull"])
+        .env(updates::VERSION_ENV, version)
+        .output()
+        .map_err(|e| format!("Failed to execute docker compose pull: {}", e))?;
+
+    if !pull_output.status.success() {
+        let stderr = String::from_utf8_lossy(&pull_output.stderr);
+        return Err(format!("Docker compose pull failed: {}", stderr));
+    }
 
     info!("Restarting containers with new images...");
     
     let up_output = Command::new("docker")
         .args(["compose", "-f", compose_file, "up", "-d"])
+        .env(updates::VERSION_ENV, version)
         .output()
         .map_err(|e| format!("Failed to execute docker compose up: {}", e))?;
 
@@ -530,6 +1814,9 @@ async fn execute_docker_restart() -> Result<(), String> {
 
 #[tokio::main]
 async fn main() {
+    // rustls is built without a default crypto provider; ring is the only one compiled in
+    let _ = rustls::crypto::ring::default_provider().install_default();
+
     // Initialize tracing
     tracing_subscriber::fmt()
         .with_env_filter(
@@ -542,18 +1829,43 @@ async fn main() {
     // Load configuration from environment
     dotenvy::dotenv().ok();
     
+    // The default is public, so it's never accepted as a key
     let admin_api_key = std::env::var("ADMIN_API_KEY")
-        .unwrap_or_else(|_| "change-me-in-production".to_string());
+        .ok()
+        .filter(|key| !key.is_empty() && key != DEFAULT_ADMIN_API_KEY);
     
-    if admin_api_key == "change-me-in-production" {
-        warn!("⚠️  Using default API key - CHANGE THIS IN PRODUCTION!");
+    if admin_api_key.is_none() {
+        warn!("⚠️  ADMIN_API_KEY is unset or the default; only keys from the setup wizard or /system/keys work");
     }
 
+    let keys_file = std::env::var("API_KEYS_FILE")
+        .unwrap_or_else(|_| "/app/data/api-keys.json".to_string());
+
+    let key_store = keys::KeyStore::load(keys_file.into(), admin_api_key.as_deref())
+        .await
+        .expect("Failed to load API key store");
+
+    let conductor_addr = std::env::var("CONDUCTOR_ADMIN_ADDR")
+        .unwrap_or_else(|_| "ourblock:8001".to_string());
+
+    // Hubs configured through the environment skip the setup wizard
+    let preconfigured = admin_api_key.is_some() || !key_store.list().is_empty();
+    let setup_manager = setup::SetupManager::from_env(preconfigured, &conductor_addr)
+        .await
+        .expect("Failed to load setup config");
+
     let docker_compose_file = std::env::var("DOCKER_COMPOSE_FILE")
         .unwrap_or_else(|_| "/app/docker-compose.yaml".to_string());
 
-    let neighborhood_name = std::env::var("NEIGHBORHOOD_NAME")
-        .unwrap_or_else(|_| "My Neighborhood".to_string());
+    let tunnel_manager = tunnel::TunnelManager::from_env(docker_compose_file.clone())
+        .await
+        .expect("Failed to load tunnel state");
+
+    let neighborhood_name = match setup_manager.neighborhood_name().await {
+        Some(name) => name,
+        None => std::env::var("NEIGHBORHOOD_NAME")
+            .unwrap_or_else(|_| "My Neighborhood".to_string()),
+    };
 
     let mdns_hostname = std::env::var("MDNS_HOSTNAME")
         .unwrap_or_else(|_| "ourblock.local".to_string());
@@ -561,41 +1873,140 @@ async fn main() {
     let ui_path = std::env::var("UI_PATH")
         .unwrap_or_else(|_| "/app/ui/dist".to_string());
 
+    let nginx_addr = std::env::var("NGINX_ADDR")
+        .unwrap_or_else(|_| "nginx:80".to_string());
+
+    let data_path = std::env::var("DATA_PATH")
+        .unwrap_or_else(|_| "/".to_string());
+
     let port: u16 = std::env::var("PORT")
         .unwrap_or_else(|_| "3001".to_string())
         .parse()
         .expect("PORT must be a valid number");
 
+    let tls_settings = tls::TlsSettings::from_env(&mdns_hostname)
+        .expect("Invalid TLS configuration");
+
+    let update_state_path = std::path::PathBuf::from(
+        std::env::var("UPDATE_STATE_FILE").unwrap_or_else(|_| "/app/data/update-state.json".to_string()),
+    );
+    let update_state = updates::UpdateState::load(&update_state_path).await;
+
+    let storage_config = storage::StorageConfig::from_env()
+        .expect("Invalid storage configuration");
+
+    let supervisor_config = supervisor::SupervisorConfig::from_env()
+        .expect("Invalid supervisor configuration");
+
+    let (events, _) = tokio::sync::broadcast::channel(64);
+
+    let peer_registry = peers::PeerRegistry::default();
+
+    let zome_calls = Arc::new(
+        zome_calls::ZomeCaller::from_env(&conductor_addr)
+            .await
+            .expect("Invalid zome call configuration"),
+    );
+
+    let session_manager = sessions::SessionManager::from_env(zome_calls.clone())
+        .await
+        .expect("Invalid WebSocket session configuration");
+
+    let audit_log = audit::AuditLog::from_env()
+        .await
+        .expect("Failed to open audit log");
+
+    let presence_bridge = presence::PresenceBridge::from_env(zome_calls.clone())
+        .expect("Invalid presence bridge configuration");
+
+    let ui_assets = ui_assets::UiAssets::from_env(&ui_path)
+        .await
+        .expect("Failed to load UI bundle");
+
     let state = Arc::new(AppState {
-        admin_api_key,
+        keys: Arc::new(tokio::sync::RwLock::new(key_store)),
         docker_compose_file,
         neighborhood_name: neighborhood_name.clone(),
         mdns_hostname: mdns_hostname.clone(),
+        conductor_addr,
+        nginx_addr,
+        data_path,
+        port,
+        peers: peer_registry.clone(),
+        tls: tls_settings.clone(),
+        update_state_path,
+        update_state: Arc::new(tokio::sync::Mutex::new(update_state)),
+        update_lock: Arc::new(tokio::sync::Mutex::new(())),
+        storage: storage_config.clone(),
+        events: events.clone(),
+        sessions: Arc::new(session_manager),
+        setup: Arc::new(setup_manager),
+        network_sample: Arc::new(tokio::sync::Mutex::new(None)),
+        tunnel: Arc::new(tunnel_manager),
+        discovery: Arc::new(discovery::HubBrowser::new(&mdns_hostname)),
+        audit: Arc::new(audit_log),
+        presence: Arc::new(presence_bridge),
+        ui: Arc::new(ui_assets),
+        zome_calls,
     });
 
-    // Start mDNS service discovery
-    start_mdns_service(&mdns_hostname, &neighborhood_name, port).await;
-
-    // Configure rate limiting: 1 request per 5 minutes per IP
-    let governor_conf = Box::new(
-        GovernorConfigBuilder::default()
-            .per_second(0) // Disable per-second limiting
-            .burst_size(1) // Allow 1 request
-            .period(Duration::from_secs(300)) // 5 minutes = 300 seconds
-            .key_extractor(SmartIpKeyExtractor)
-            .finish()
-            .unwrap(),
+    // Warn connected clients when a data volume fills up
+    tokio::spawn(storage::monitor(storage_config, events));
+
+    // Restart the conductor or nginx when they stay unhealthy
+    tokio::spawn(supervisor::run(supervisor_config, state.clone()));
+
+    // Hubs configured through the environment skip the wizard that installs the hApp
+    tokio::spawn(state.setup.clone().install_preconfigured(neighborhood_name.clone()));
+
+    // Start mDNS service discovery and browse for other hubs
+    let mdns = start_mdns_service(&mdns_hostname, &neighborhood_name, port).await;
+    if let Some((daemon, fullname)) = &mdns {
+        peers::start_browser(daemon, fullname.clone(), peer_registry);
+    }
+
+    // Configure rate limiting per route group (RATE_LIMIT_<GROUP>=<burst>/<period_secs>)
+    let rate_limiter = Arc::new(
+        ratelimit::RateLimiter::from_env().expect("Invalid rate limit configuration"),
     );
 
     // API routes (authenticated)
     let api_routes = Router::new()
         .route("/health", get(health_handler))
+        .route("/setup", get(setup_status_handler).post(setup_handler))
         .route("/version", get(version_handler))
         .route("/update", post(update_handler))
+        .route("/update/rollback", post(rollback_handler))
+        .route("/update/status", get(update_status_handler))
+        .route(
+            "/update/ui",
+            post(update_ui_handler).layer(DefaultBodyLimit::max(ui_assets::MAX_BUNDLE_BYTES)),
+        )
+        .route("/ui/version", get(ui_version_handler))
         .route("/restart", post(restart_handler))
         .route("/system/backup/status", get(backup_status_handler))
         .route("/system/backup/download", get(download_backup_handler))
         .route("/system/backup/trigger", post(trigger_backup_handler))
+        .route("/system/keystore/export", post(keystore_export_handler))
+        // The archive is streamed to disk; `keystore::restore` enforces its size limit
+        .route("/system/keystore/restore", post(keystore_restore_handler))
+        .route("/peers", get(list_peers_handler))
+        .route("/peers/handshake", post(initiate_handshake_handler))
+        .route("/discovery/hubs", get(discover_hubs_handler))
+        .route("/system/tls", get(tls_status_handler))
+        .route("/system/storage", get(storage_handler))
+        .route("/system/network", get(network_handler))
+        .route("/system/tunnel", get(tunnel_status_handler).post(configure_tunnel_handler))
+        .route("/system/tunnel/enable", post(enable_tunnel_handler))
+        .route("/system/tunnel/disable", post(disable_tunnel_handler))
+        .route("/system/tunnel/clients", post(add_tunnel_client_handler))
+        .route("/system/tunnel/clients/:id", delete(remove_tunnel_client_handler))
+        .route("/system/audit", get(audit_log_handler))
+        .route("/system/keys", get(list_keys_handler).post(create_key_handler))
+        .route("/system/keys/:id", delete(revoke_key_handler))
+        .route("/system/keys/:id/rotate", post(rotate_key_handler))
+        .route("/system/ws-invites", get(list_invites_handler).post(create_invite_handler))
+        .route("/system/ws-invites/:id", delete(revoke_invite_handler))
         .layer(
             ServiceBuilder::new()
                 .layer(TraceLayer::new_for_http())
@@ -612,6 +2023,7 @@ async fn main() {
                         .allow_methods(vec![
                             axum::http::Method::GET,
                             axum::http::Method::POST,
+                            axum::http::Method::DELETE,
                             axum::http::Method::OPTIONS,
                         ])
                         .allow_headers(vec![
@@ -620,30 +2032,79 @@ async fn main() {
                         ])
                         .allow_credentials(true),
                 )
+                .layer(middleware::from_fn_with_state(
+                    rate_limiter.clone(),
+                    ratelimit::rate_limit_middleware,
+                ))
                 .layer(middleware::from_fn_with_state(
                     state.clone(),
                     auth_middleware,
-                ))
-                .layer(GovernorLayer {
-                    config: Box::leak(governor_conf),
-                }),
+                )),
         )
         .with_state(state.clone());
 
-    // WebSocket route for mobile clients (no auth - handled by Holochain)
+    // WebSocket route for mobile clients (session token, not an API key)
     let ws_route = Router::new()
         .route("/ws", get(ws_handler))
-        .layer(TraceLayer::new_for_http());
+        .layer(TraceLayer::new_for_http())
+        .with_state(state.clone());
+
+    // Session token exchange for mobile clients (rate limited like admin routes)
+    let session_route = Router::new()
+        .route("/session", post(create_session_handler))
+        .layer(
+            ServiceBuilder::new()
+                .layer(TraceLayer::new_for_http())
+                .layer(middleware::from_fn_with_state(
+                    rate_limiter.clone(),
+                    ratelimit::rate_limit_middleware,
+                )),
+        )
+        .with_state(state.clone());
+
+    // Chat transcript download (session token checked in the handler)
+    let chat_export_route = Router::new()
+        .route("/chat/export", get(chat_export_handler))
+        .layer(
+            ServiceBuilder::new()
+                .layer(TraceLayer::new_for_http())
+                .layer(middleware::from_fn_with_state(
+                    rate_limiter.clone(),
+                    ratelimit::rate_limit_middleware,
+                )),
+        )
+        .with_state(state.clone());
+
+    // Federation handshake for other hubs (no admin key - peers don't share it)
+    let federation_route = Router::new()
+        .route("/federation/handshake", post(federation_handshake_handler))
+        .layer(TraceLayer::new_for_http())
+        .with_state(state.clone());
+
+    // Certificate download and ACME HTTP-01 challenges (no auth - must be reachable before trust exists)
+    let tls_routes = Router::new()
+        .route("/tls/certificate.pem", get(tls_certificate_handler))
+        .nest_service(
+            "/.well-known/acme-challenge",
+            ServeDir::new(tls_settings.acme_webroot().join(".well-known/acme-challenge")),
+        )
+        .layer(TraceLayer::new_for_http())
+        .with_state(state.clone());
 
     // Static file serving for React UI (no auth - public web access)
     let static_files = Router::new()
-        .nest_service("/", ServeDir::new(&ui_path))
-        .layer(TraceLayer::new_for_http());
+        .fallback(serve_ui)
+        .layer(TraceLayer::new_for_http())
+        .with_state(state.clone());
 
     // Combine all routes
     let app = Router::new()
         .nest("/api", api_routes)
         .merge(ws_route)
+        .merge(session_route)
+        .merge(chat_export_route)
+        .merge(federation_route)
+        .merge(tls_routes)
         .fallback_service(static_files);
 
     let addr = SocketAddr::from(([0, 0, 0, 0], port));
@@ -652,27 +2113,57 @@ async fn main() {
         "🚀 OurBlock Hub Sidecar starting on http://{}",
         addr
     );
-    info!("   Version: {}", std::env::var("APP_VERSION").unwrap_or_else(|_| "0.1.0".to_string()));
+    info!("   Version: {}", app_version());
     info!("   Neighborhood: {}", neighborhood_name);
     info!("   mDNS: {}", mdns_hostname);
     info!("   Docker Compose: {}", std::env::var("DOCKER_COMPOSE_FILE").unwrap_or_else(|_| "/app/docker-compose.yaml".to_string()));
     info!("");
     info!("Available endpoints:");
     info!("   GET  /                     - React UI (static files)");
-    info!("   GET  /ws                   - WebSocket for mobile clients");
-    info!("   GET  /api/health           - Health check");
+    info!("   GET  /ws?token=...         - WebSocket for mobile clients");
+    info!("   POST /session              - Exchange invite code or agent signature for a WebSocket token");
+    info!("   GET  /chat/export          - Download a chat transcript as JSON or text (agent session token)");
+    info!("   GET  /api/health           - Component health (conductor, nginx, backup, disk)");
+    info!("   *    /api/setup            - First-boot setup (no key needed until initialized)");
     info!("   GET  /api/version          - Version information");
-    info!("   POST /api/update           - Trigger Docker update (auth required)");
+    info!("   POST /api/update           - Trigger Docker update, optionally pinned (auth required)");
+    info!("   POST /api/update/rollback  - Restore images from before the last update (auth required)");
+    info!("   GET  /api/update/status    - Pinned version and last update outcome (auth required)");
+    info!("   POST /api/update/ui        - Install a UI bundle without a Docker update (auth required)");
+    info!("   GET  /api/ui/version       - Hash of the UI bundle being served (no key needed)");
     info!("   POST /api/restart          - Restart containers (auth required)");
+    info!("   GET  /api/peers            - Discovered peer hubs (auth required)");
+    info!("   POST /api/peers/handshake  - Handshake with a peer hub (auth required)");
+    info!("   GET  /api/discovery/hubs   - Scan the LAN for hubs (no key needed)");
+    info!("   POST /federation/handshake - Peer hub handshake");
+    info!("   GET  /api/system/tls       - TLS status and trust QR (auth required)");
+    info!("   GET  /api/system/storage   - Data volume usage (auth required)");
+    info!("   GET  /api/system/network   - Peers, gossip throughput, and per-DNA storage (auth required)");
+    info!("   *    /api/system/tunnel    - Remote access tunnel and member device configs (admin scope for changes)");
+    info!("   POST /api/system/keystore/export  - Download the encrypted conductor keystore (admin scope)");
+    info!("   POST /api/system/keystore/restore - Restore the conductor keystore from an export or backup (admin scope)");
+    info!("   GET  /tls/certificate.pem  - Hub certificate download");
+    info!("   GET  /api/system/audit     - Admin action audit log (admin scope)");
+    info!("   *    /api/system/keys      - Manage scoped API keys (admin scope)");
+    info!("   *    /api/system/ws-invites - Manage WebSocket invite codes (admin scope)");
     info!("");
-    info!("⚡ Rate limit: 1 update per 5 minutes per IP");
-    info!("🔐 Admin API: X-OurBlock-Admin-Key header required");
+    let mut limits: Vec<_> = rate_limiter.limits().iter().collect();
+    limits.sort_by_key(|(group, _)| *group);
+    for (group, limit) in limits {
+        info!("⚡ Rate limit ({}): {} per {}s per IP", group, limit.burst, limit.period.as_secs());
+    }
+    info!("🔐 Admin API: X-OurBlock-Admin-Key header required (scoped keys supported)");
     info!("🌐 Discovery: Announcing as {}", mdns_hostname);
 
     let listener = tokio::net::TcpListener::bind(addr)
         .await
         .expect("Failed to bind to address");
 
+    // HTTPS starts after the plain listener is bound so HTTP-01 challenges can be answered
+    if tls_settings.enabled() {
+        tokio::spawn(serve_https(app.clone(), tls_settings));
+    }
+
     axum::serve(
         listener,
         app.into_make_service_with_connect_info::<SocketAddr>(),
@@ -0,0 +1,243 @@
+// ============================================================================
+// Network and Sync Statistics
+// ============================================================================
+//
+// Combines three conductor admin calls: transport stats (per-connection byte
+// and message counters), kitsune2 network metrics (known peers and gossip
+// rounds per DNA), and storage info (database sizes per DNA). The transport
+// counters are cumulative, so throughput is the difference from the previous
+// report; the first report after a restart has none.
+
+use chrono::Utc;
+use serde::{de::IgnoredAny, Deserialize, Serialize};
+use std::collections::HashMap;
+use tokio::time::Instant;
+
+use crate::conductor::{admin_call, encode_hash};
+
+#[derive(Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+enum AdminRequest {
+    DumpNetworkStats,
+    DumpNetworkMetrics {
+        dna_hash: Option<serde_bytes::ByteBuf>,
+        include_dht_summary: bool,
+    },
+    StorageInfo,
+}
+
+#[derive(Deserialize)]
+struct TransportStats {
+    #[serde(default)]
+    backend: String,
+    #[serde(default)]
+    connections: Vec<TransportConnection>,
+}
+
+#[derive(Deserialize)]
+struct TransportConnection {
+    #[serde(default)]
+    send_message_count: u64,
+    #[serde(default)]
+    send_bytes: u64,
+    #[serde(default)]
+    recv_message_count: u64,
+    #[serde(default)]
+    recv_bytes: u64,
+}
+
+#[derive(Deserialize)]
+struct NetworkMetrics {
+    #[serde(default)]
+    gossip_state_summary: GossipSummary,
+    #[serde(default)]
+    local_agents: Vec<IgnoredAny>,
+}
+
+#[derive(Deserialize, Default)]
+struct GossipSummary {
+    #[serde(default)]
+    initiated_round: Option<IgnoredAny>,
+    #[serde(default)]
+    accepted_rounds: Vec<IgnoredAny>,
+    #[serde(default)]
+    peer_meta: HashMap<String, IgnoredAny>,
+}
+
+#[derive(Deserialize)]
+struct StorageInfo {
+    #[serde(default)]
+    blobs: Vec<StorageBlob>,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+enum StorageBlob {
+    Dna(DnaStorageInfo),
+}
+
+#[derive(Deserialize)]
+struct DnaStorageInfo {
+    dna_hash: serde_bytes::ByteBuf,
+    #[serde(default)]
+    used_by: Vec<String>,
+    #[serde(default)]
+    authored_data_size: u64,
+    #[serde(default)]
+    dht_data_size: u64,
+    #[serde(default)]
+    cache_data_size: u64,
+    #[serde(default)]
+    authored_data_size_on_disk: u64,
+    #[serde(default)]
+    dht_data_size_on_disk: u64,
+    #[serde(default)]
+    cache_data_size_on_disk: u64,
+}
+
+/// Transport counters summed over every open connection
+#[derive(Serialize, Clone, Copy, Default, Debug)]
+pub struct TrafficTotals {
+    pub sent_bytes: u64,
+    pub received_bytes: u64,
+    pub sent_messages: u64,
+    pub received_messages: u64,
+}
+
+/// Totals from the previous report, for computing throughput
+#[derive(Clone, Copy, Debug)]
+pub struct TrafficSample {
+    at: Instant,
+    totals: TrafficTotals,
+}
+
+#[derive(Serialize)]
+pub struct Throughput {
+    pub sent_bytes_per_sec: f64,
+    pub received_bytes_per_sec: f64,
+    pub interval_secs: f64,
+}
+
+#[derive(Serialize)]
+pub struct DnaStorage {
+    pub authored_bytes: u64,
+    pub dht_bytes: u64,
+    pub cache_bytes: u64,
+    pub on_disk_bytes: u64,
+}
+
+#[derive(Serialize)]
+pub struct DnaNetwork {
+    pub dna_hash: String,
+    pub apps: Vec<String>,
+    /// Peers gossip has met for this DNA
+    pub known_peers: usize,
+    pub local_agents: usize,
+    pub active_gossip_rounds: usize,
+    pub storage: Option<DnaStorage>,
+}
+
+#[derive(Serialize)]
+pub struct NetworkReport {
+    pub transport: String,
+    pub connected_peers: usize,
+    /// Cumulative over the currently open connections
+    pub totals: TrafficTotals,
+    /// Since the previous report; None on the first one
+    pub throughput: Option<Throughput>,
+    pub dnas: Vec<DnaNetwork>,
+    pub timestamp: String,
+}
+
+/// Query the conductor and build a report, updating `previous` with this sample
+pub async fn report(conductor_addr: &str, previous: &mut Option<TrafficSample>) -> Result<NetworkReport, String> {
+    let (stats, metrics, storage) = tokio::join!(
+        admin_call::<_, TransportStats>(conductor_addr, &AdminRequest::DumpNetworkStats, "network_stats_dumped"),
+        admin_call::<_, HashMap<serde_bytes::ByteBuf, NetworkMetrics>>(
+            conductor_addr,
+            &AdminRequest::DumpNetworkMetrics {
+                dna_hash: None,
+                include_dht_summary: false,
+            },
+            "network_metrics_dumped",
+        ),
+        admin_call::<_, StorageInfo>(conductor_addr, &AdminRequest::StorageInfo, "storage_info"),
+    );
+    let (stats, metrics, storage) = (stats?, metrics?, storage?);
+
+    let totals = stats.connections.iter().fold(TrafficTotals::default(), |mut totals, connection| {
+        totals.sent_bytes += connection.send_bytes;
+        totals.received_bytes += connection.recv_bytes;
+        totals.sent_messages += connection.send_message_count;
+        totals.received_messages += connection.recv_message_count;
+        totals
+    });
+
+    let now = Instant::now();
+    let throughput = previous.and_then(|sample| {
+        let interval_secs = now.duration_since(sample.at).as_secs_f64();
+        (interval_secs > 0.0).then(|| Throughput {
+            // Closed connections drop out of the totals, so never go negative
+            sent_bytes_per_sec: totals.sent_bytes.saturating_sub(sample.totals.sent_bytes) as f64 / interval_secs,
+            received_bytes_per_sec: totals.received_bytes.saturating_sub(sample.totals.received_bytes) as f64
+                / interval_secs,
+            interval_secs,
+        })
+    });
+    *previous = Some(TrafficSample { at: now, totals });
+
+    let mut storage_by_dna: HashMap<Vec<u8>, DnaStorageInfo> = storage
+        .blobs
+        .into_iter()
+        .map(|StorageBlob::Dna(info)| (info.dna_hash.to_vec(), info))
+        .collect();
+
+    let mut dnas: Vec<DnaNetwork> = metrics
+        .into_iter()
+        .map(|(dna_hash, metrics)| {
+            let storage = storage_by_dna.remove(dna_hash.as_slice());
+            dna_network(&dna_hash, Some(metrics), storage)
+        })
+        .collect();
+    // DNAs with data on disk but no running network, e.g. disabled apps
+    dnas.extend(
+        storage_by_dna
+            .into_iter()
+            .map(|(dna_hash, storage)| dna_network(&dna_hash, None, Some(storage))),
+    );
+    dnas.sort_by(|a, b| a.dna_hash.cmp(&b.dna_hash));
+
+    Ok(NetworkReport {
+        transport: stats.backend,
+        connected_peers: stats.connections.len(),
+        totals,
+        throughput,
+        dnas,
+        timestamp: Utc::now().to_rfc3339(),
+    })
+}
+
+fn dna_network(dna_hash: &[u8], metrics: Option<NetworkMetrics>, storage: Option<DnaStorageInfo>) -> DnaNetwork {
+    let (known_peers, local_agents, active_gossip_rounds) = metrics.map_or((0, 0, 0), |metrics| {
+        let gossip = metrics.gossip_state_summary;
+        (
+            gossip.peer_meta.len(),
+            metrics.local_agents.len(),
+            gossip.accepted_rounds.len() + usize::from(gossip.initiated_round.is_some()),
+        )
+    });
+
+    DnaNetwork {
+        dna_hash: encode_hash(dna_hash),
+        apps: storage.as_ref().map(|info| info.used_by.clone()).unwrap_or_default(),
+        known_peers,
+        local_agents,
+        active_gossip_rounds,
+        storage: storage.map(|info| DnaStorage {
+            authored_bytes: info.authored_data_size,
+            dht_bytes: info.dht_data_size,
+            cache_bytes: info.cache_data_size,
+            on_disk_bytes: info.authored_data_size_on_disk + info.dht_data_size_on_disk + info.cache_data_size_on_disk,
+        }),
+    }
+}
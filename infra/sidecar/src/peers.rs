@@ -0,0 +1,217 @@
+// ============================================================================
+// Peer Hub Discovery and Federation Handshake
+// ============================================================================
+//
+// Other OurBlock hubs on the LAN announce `_ourblock._tcp.local.` just like we
+// do. The browser keeps a registry of them; the handshake exchanges neighborhood
+// metadata so hubs know who they're talking to before any bridging happens.
+//
+// Hubs have no keys to sign handshakes with, so a handshake a hub receives can
+// only add a new peer or refresh one from an address it's already known at.
+// Anyone else could otherwise rename a known hub just by claiming its hostname.
+
+use chrono::Utc;
+use mdns_sd::{ServiceDaemon, ServiceEvent};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpStream,
+    sync::RwLock,
+    time::timeout,
+};
+use tracing::{error, info, warn};
+
+pub const SERVICE_TYPE: &str = "_ourblock._tcp.local.";
+
+/// Cap on tracked peers so a noisy LAN (or handshake spam) can't grow memory unbounded
+const MAX_PEERS: usize = 64;
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_HANDSHAKE_RESPONSE_BYTES: usize = 16 * 1024;
+
+/// What a hub tells other hubs about itself
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct HubMetadata {
+    pub neighborhood_name: String,
+    pub hostname: String,
+    pub version: String,
+    pub port: u16,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct PeerHub {
+    pub hostname: String,
+    /// mDNS instance name, if the hub was discovered rather than only handshaken
+    pub service_name: Option<String>,
+    pub neighborhood_name: Option<String>,
+    pub addresses: Vec<String>,
+    pub port: u16,
+    pub version: Option<String>,
+    pub last_seen: String,
+    /// Set once a handshake has completed in either direction
+    pub handshake_at: Option<String>,
+}
+
+pub type PeerRegistry = Arc<RwLock<HashMap<String, PeerHub>>>;
+
+/// Where a handshake's metadata came from
+pub enum HandshakeOrigin {
+    /// We reached the peer at this address, one it announced
+    Outbound(String),
+    /// The peer reached us from this address
+    Inbound(String),
+}
+
+#[derive(Debug)]
+pub enum HandshakeError {
+    RegistryFull,
+    /// A known hostname, claimed from an address that hub isn't known at
+    UnknownAddress,
+}
+
+/// Browse for other hubs and keep the registry in sync with mDNS announcements
+pub fn start_browser(mdns: &ServiceDaemon, own_fullname: String, peers: PeerRegistry) {
+    let receiver = match mdns.browse(SERVICE_TYPE) {
+        Ok(receiver) => receiver,
+        Err(e) => {
+            error!(error = %e, "Failed to start mDNS browser");
+            return;
+        }
+    };
+
+    tokio::spawn(async move {
+        while let Ok(event) = receiver.recv_async().await {
+            match event {
+                ServiceEvent::ServiceResolved(info) => {
+                    if info.get_fullname() == own_fullname {
+                        continue;
+                    }
+
+                    let hostname = info.get_hostname().trim_end_matches('.').to_string();
+                    let mut peers = peers.write().await;
+                    if !peers.contains_key(&hostname) && peers.len() >= MAX_PEERS {
+                        warn!(hostname = %hostname, "Peer registry full, ignoring hub");
+                        continue;
+                    }
+
+                    let peer = peers.entry(hostname.clone()).or_insert_with(|| PeerHub {
+                        hostname: hostname.clone(),
+                        service_name: None,
+                        neighborhood_name: None,
+                        addresses: Vec::new(),
+                        port: 0,
+                        version: None,
+                        last_seen: String::new(),
+                        handshake_at: None,
+                    });
+                    peer.addresses = info.get_addresses().iter().map(|addr| addr.to_string()).collect();
+                    peer.addresses.sort();
+                    peer.service_name = Some(info.get_fullname().to_string());
+                    peer.port = info.get_port();
+                    peer.neighborhood_name = info.get_property_val_str("neighborhood").map(str::to_string);
+                    peer.version = info.get_property_val_str("version").map(str::to_string);
+                    peer.last_seen = Utc::now().to_rfc3339();
+
+                    info!(hostname = %hostname, neighborhood = ?peer.neighborhood_name, "Discovered peer hub");
+                }
+                ServiceEvent::ServiceRemoved(_, fullname) => {
+                    peers
+                        .write()
+                        .await
+                        .retain(|_, peer| peer.service_name.as_deref() != Some(fullname.as_str()));
+                    info!(service = %fullname, "Peer hub went away");
+                }
+                _ => {}
+            }
+        }
+    });
+}
+
+/// Record (or refresh) a peer that completed a handshake
+pub async fn record_handshake(
+    peers: &PeerRegistry,
+    remote: &HubMetadata,
+    origin: HandshakeOrigin,
+) -> Result<(), HandshakeError> {
+    let mut peers = peers.write().await;
+    match (peers.get(&remote.hostname), &origin) {
+        (None, _) if peers.len() >= MAX_PEERS => return Err(HandshakeError::RegistryFull),
+        (Some(known), HandshakeOrigin::Inbound(address)) if !known.addresses.contains(address) => {
+            return Err(HandshakeError::UnknownAddress);
+        }
+        _ => {}
+    }
+
+    let now = Utc::now().to_rfc3339();
+    let peer = peers.entry(remote.hostname.clone()).or_insert_with(|| PeerHub {
+        hostname: remote.hostname.clone(),
+        service_name: None,
+        neighborhood_name: None,
+        addresses: Vec::new(),
+        port: remote.port,
+        version: None,
+        last_seen: now.clone(),
+        handshake_at: None,
+    });
+    peer.neighborhood_name = Some(remote.neighborhood_name.clone());
+    peer.version = Some(remote.version.clone());
+    peer.port = remote.port;
+    let (HandshakeOrigin::Outbound(address) | HandshakeOrigin::Inbound(address)) = origin;
+    if !peer.addresses.contains(&address) {
+        peer.addresses.push(address);
+    }
+    peer.last_seen = now.clone();
+    peer.handshake_at = Some(now);
+    Ok(())
+}
+
+/// POST our metadata to a peer's handshake endpoint and return theirs
+pub async fn send_handshake(address: &str, port: u16, ours: &HubMetadata) -> Result<HubMetadata, String> {
+    let body = serde_json::to_string(ours).map_err(|e| e.to_string())?;
+    let request = format!(
+        "POST /federation/handshake HTTP/1.0\r\n\
+         Host: {address}\r\n\
+         Content-Type: application/json\r\n\
+         Content-Length: {}\r\n\r\n{body}",
+        body.len()
+    );
+    // IPv6 literals need brackets in a socket address
+    let target = if address.contains(':') {
+        format!("[{address}]:{port}")
+    } else {
+        format!("{address}:{port}")
+    };
+
+    let exchange = async {
+        let mut stream = TcpStream::connect(&target).await.map_err(|e| e.to_string())?;
+        stream
+            .write_all(request.as_bytes())
+            .await
+            .map_err(|e| e.to_string())?;
+
+        let mut response = Vec::new();
+        stream
+            .take(MAX_HANDSHAKE_RESPONSE_BYTES as u64)
+            .read_to_end(&mut response)
+            .await
+            .map_err(|e| e.to_string())?;
+        Ok::<_, String>(response)
+    };
+
+    let response = timeout(HANDSHAKE_TIMEOUT, exchange)
+        .await
+        .map_err(|_| "Handshake timed out".to_string())??;
+    let response = String::from_utf8_lossy(&response);
+
+    let (head, body) = response
+        .split_once("\r\n\r\n")
+        .ok_or("Malformed handshake response")?;
+    if !head.lines().next().is_some_and(|status| status.contains(" 200 ")) {
+        return Err(format!(
+            "Peer rejected handshake: {}",
+            head.lines().next().unwrap_or_default()
+        ));
+    }
+
+    serde_json::from_str(body).map_err(|e| format!("Invalid handshake response: {}", e))
+}
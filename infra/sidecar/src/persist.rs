@@ -0,0 +1,51 @@
+// ============================================================================
+// Sidecar State Files
+// ============================================================================
+
+use serde::Serialize;
+use std::path::{Path, PathBuf};
+use tokio::io::AsyncWriteExt;
+
+/// Write JSON to a temp file readable by the sidecar user only, and rename it
+/// into place, so a crash never leaves a truncated or world-readable file
+pub async fn write_json_atomic<T: Serialize>(path: &Path, value: &T) -> Result<(), String> {
+    let contents = serde_json::to_vec_pretty(value).map_err(|e| e.to_string())?;
+    write_atomic(path, &contents).await
+}
+
+/// Same guarantees as `write_json_atomic`, for raw contents such as secrets
+/// read by other containers
+pub async fn write_atomic(path: &Path, contents: &[u8]) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        tokio::fs::create_dir_all(parent)
+            .await
+            .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+    }
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let tmp = PathBuf::from(tmp);
+    let _ = tokio::fs::remove_file(&tmp).await;
+
+    let mut options = tokio::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    // Created owner-only, so the contents are never readable by anyone else,
+    // not even between the write and a later chmod
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options
+        .open(&tmp)
+        .await
+        .map_err(|e| format!("Failed to create {}: {}", tmp.display(), e))?;
+    file.write_all(contents)
+        .await
+        .map_err(|e| format!("Failed to write {}: {}", tmp.display(), e))?;
+    file.sync_all()
+        .await
+        .map_err(|e| format!("Failed to flush {}: {}", tmp.display(), e))?;
+    drop(file);
+
+    tokio::fs::rename(&tmp, path)
+        .await
+        .map_err(|e| format!("Failed to replace {}: {}", path.display(), e))
+}
@@ -0,0 +1,157 @@
+// ============================================================================
+// Chat Presence Bridge
+// ============================================================================
+//
+// Chat presence is self-reported: an agent is listed by `get_online_agents`
+// only while its own cell has announced itself, and a client that vanishes
+// never takes itself off the list. The sidecar sees every `/ws` connection, so
+// it announces for agents whose cell this hub hosts. The first connection of an
+// agent calls `announce_online` (telling everyone already listed) and the last
+// disconnect calls `leave_presence`, which removes the agent from the list and
+// then calls `announce_offline`.
+//
+// Calls for one agent are serialized, and each run announces whatever the
+// current connection count calls for, so a reconnect racing a disconnect
+// settles on the right state.
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_bytes::ByteBuf;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tracing::{debug, info, warn};
+
+use crate::zome_calls::{AgentCell, ZomeCaller};
+
+const CHAT_ZOME: &str = "chat";
+
+struct AgentPresence {
+    connections: usize,
+    /// Whether the agent is currently announced online; locked for the whole
+    /// announcement so calls for one agent never overlap
+    announced: Arc<tokio::sync::Mutex<bool>>,
+}
+
+pub struct PresenceBridge {
+    enabled: bool,
+    zome_calls: Arc<ZomeCaller>,
+    agents: Mutex<HashMap<String, AgentPresence>>,
+}
+
+impl PresenceBridge {
+    pub fn from_env(zome_calls: Arc<ZomeCaller>) -> Result<Self, String> {
+        let enabled = match std::env::var("PRESENCE_BRIDGE_ENABLED").as_deref() {
+            Ok("false") => false,
+            Ok("true") | Err(_) => true,
+            Ok(other) => return Err(format!("PRESENCE_BRIDGE_ENABLED must be true or false, got '{}'", other)),
+        };
+
+        Ok(Self {
+            enabled,
+            zome_calls,
+            agents: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// A `/ws` connection for `agent` (`uhCAk...`) opened
+    pub fn connected(self: &Arc<Self>, agent: &str) {
+        self.track(agent, |connections| connections + 1);
+    }
+
+    /// A `/ws` connection for `agent` closed
+    pub fn disconnected(self: &Arc<Self>, agent: &str) {
+        self.track(agent, |connections| connections.saturating_sub(1));
+    }
+
+    fn track(self: &Arc<Self>, agent: &str, update: impl FnOnce(usize) -> usize) {
+        if !self.enabled {
+            return;
+        }
+        {
+            let mut agents = self.agents.lock().unwrap();
+            let presence = agents.entry(agent.to_string()).or_insert_with(|| AgentPresence {
+                connections: 0,
+                announced: Arc::new(tokio::sync::Mutex::new(false)),
+            });
+            presence.connections = update(presence.connections);
+        }
+        tokio::spawn(self.clone().reconcile(agent.to_string()));
+    }
+
+    fn connections(&self, agent: &str) -> usize {
+        self.agents
+            .lock()
+            .unwrap()
+            .get(agent)
+            .map_or(0, |presence| presence.connections)
+    }
+
+    /// Announce the agent online or offline if that differs from what was last
+    /// announced, then forget agents that are offline with no connections
+    async fn reconcile(self: Arc<Self>, agent: String) {
+        let Some(announced) = self.agents.lock().unwrap().get(&agent).map(|presence| presence.announced.clone())
+        else {
+            return;
+        };
+        let mut announced = announced.lock().await;
+
+        let online = self.connections(&agent) > 0;
+        if online != *announced {
+            let result = if online {
+                self.announce_online(&agent).await
+            } else {
+                self.announce_offline(&agent).await
+            };
+            match result {
+                Ok(true) => {
+                    info!(agent = %agent, online, "Updated chat presence");
+                    *announced = online;
+                }
+                Ok(false) => debug!(agent = %agent, "Agent's cell is not hosted here; presence left to the client"),
+                Err(e) => warn!(agent = %agent, online, error = %e, "Failed to update chat presence"),
+            }
+        }
+
+        if !*announced {
+            let mut agents = self.agents.lock().unwrap();
+            if agents.get(&agent).is_some_and(|presence| presence.connections == 0) {
+                agents.remove(&agent);
+            }
+        }
+    }
+
+    /// Ok(false) when this hub doesn't host the agent's cell
+    async fn announce_online(&self, agent: &str) -> Result<bool, String> {
+        let Some(cell) = self.zome_calls.find_cell(agent).await? else {
+            return Ok(false);
+        };
+        let listed: Vec<ByteBuf> = self.call_chat(&cell, "get_online_agents", &()).await?;
+        let mut others: Vec<ByteBuf> = Vec::new();
+        for other in listed {
+            if other != cell.agent && !others.contains(&other) {
+                others.push(other);
+            }
+        }
+        self.call_chat::<_, ()>(&cell, "announce_online", &others).await?;
+        Ok(true)
+    }
+
+    /// Ok(false) when this hub doesn't host the agent's cell
+    async fn announce_offline(&self, agent: &str) -> Result<bool, String> {
+        let Some(cell) = self.zome_calls.find_cell(agent).await? else {
+            return Ok(false);
+        };
+        self.call_chat::<_, ()>(&cell, "leave_presence", &()).await?;
+        Ok(true)
+    }
+
+    async fn call_chat<I: Serialize, O: DeserializeOwned>(
+        &self,
+        cell: &AgentCell,
+        fn_name: &str,
+        input: &I,
+    ) -> Result<O, String> {
+        self.zome_calls.call(cell, CHAT_ZOME, fn_name, input).await
+    }
+}
@@ -0,0 +1,15 @@
+// ============================================================================
+// QR Codes
+// ============================================================================
+//
+// Rendered as SVG so the UI can show them inline. Used for the certificate
+// trust link and for WireGuard configs imported by the mobile apps.
+
+/// Render `payload` as an SVG QR code
+pub fn svg(payload: &str) -> Result<String, String> {
+    let code = qrcode::QrCode::new(payload.as_bytes()).map_err(|e| e.to_string())?;
+    Ok(code
+        .render::<qrcode::render::svg::Color>()
+        .min_dimensions(256, 256)
+        .build())
+}
@@ -0,0 +1,227 @@
+// ============================================================================
+// Per-Route-Group Rate Limiting
+// ============================================================================
+//
+// Routes are grouped by the scope they require (see keys::Scope), and each
+// group gets its own token bucket per client IP. Read-only routes are exempt
+// unless configured otherwise. Limits come from RATE_LIMIT_<GROUP> env vars
+// in the form `<burst>/<period_secs>` or `off`.
+
+use axum::{
+    extract::{ConnectInfo, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::Serialize;
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+use crate::keys::Scope;
+
+/// Drop idle buckets once the table gets this large
+const MAX_TRACKED_CLIENTS: usize = 10_000;
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Limit {
+    pub burst: u32,
+    #[serde(rename = "period_secs", serialize_with = "serialize_secs")]
+    pub period: Duration,
+}
+
+fn serialize_secs<S: serde::Serializer>(period: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(period.as_secs())
+}
+
+impl Limit {
+    pub fn parse(value: &str) -> Result<Option<Limit>, String> {
+        if value.eq_ignore_ascii_case("off") {
+            return Ok(None);
+        }
+        let (burst, period) = value
+            .split_once('/')
+            .ok_or_else(|| format!("Invalid rate limit '{}' (expected <burst>/<period_secs> or off)", value))?;
+        let burst: u32 = burst.trim().parse().map_err(|_| format!("Invalid burst in '{}'", value))?;
+        let period: u64 = period.trim().parse().map_err(|_| format!("Invalid period in '{}'", value))?;
+        if burst == 0 || period == 0 {
+            return Err(format!("Rate limit '{}' must have a positive burst and period", value));
+        }
+        Ok(Some(Limit {
+            burst,
+            period: Duration::from_secs(period),
+        }))
+    }
+
+    /// Seconds for one token to refill
+    fn refill_interval(&self) -> f64 {
+        self.period.as_secs_f64() / self.burst as f64
+    }
+}
+
+/// A token bucket for one client; starts full
+pub struct TokenBucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    pub fn new(limit: &Limit) -> Self {
+        Self {
+            tokens: limit.burst as f64,
+            updated: Instant::now(),
+        }
+    }
+
+    /// Take a token, or return how long until one is available
+    pub fn take(&mut self, limit: &Limit) -> Result<(), Duration> {
+        let now = Instant::now();
+        let refilled = now.duration_since(self.updated).as_secs_f64() / limit.refill_interval();
+        self.tokens = (self.tokens + refilled).min(limit.burst as f64);
+        self.updated = now;
+
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            Ok(())
+        } else {
+            Err(Duration::from_secs_f64((1.0 - self.tokens) * limit.refill_interval()))
+        }
+    }
+
+    pub fn idle_for(&self) -> Duration {
+        self.updated.elapsed()
+    }
+}
+
+pub struct RateLimiter {
+    limits: HashMap<&'static str, Limit>,
+    buckets: Mutex<HashMap<(&'static str, String), TokenBucket>>,
+}
+
+pub type SharedRateLimiter = Arc<RateLimiter>;
+
+impl RateLimiter {
+    /// Defaults: updates 1 per 5 minutes, backups 3 per hour, admin 30 per minute, reads unlimited
+    pub fn from_env() -> Result<Self, String> {
+        let groups = [
+            (Scope::Read, "off"),
+            (Scope::Backup, "3/3600"),
+            (Scope::Update, "1/300"),
+            (Scope::Admin, "30/60"),
+        ];
+
+        let mut limits = HashMap::new();
+        for (scope, default) in groups {
+            let var = format!("RATE_LIMIT_{}", scope.as_str().to_uppercase());
+            let value = std::env::var(&var).unwrap_or_else(|_| default.to_string());
+            if let Some(limit) = Limit::parse(&value).map_err(|e| format!("{}: {}", var, e))? {
+                limits.insert(scope.as_str(), limit);
+            }
+        }
+
+        Ok(Self {
+            limits,
+            buckets: Mutex::new(HashMap::new()),
+        })
+    }
+
+    pub fn limits(&self) -> &HashMap<&'static str, Limit> {
+        &self.limits
+    }
+
+    /// Take a token for `client` in `group`, or return how long until one is available
+    fn check(&self, group: &'static str, client: &str) -> Result<(), Duration> {
+        let Some(limit) = self.limits.get(group) else {
+            return Ok(());
+        };
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if buckets.len() >= MAX_TRACKED_CLIENTS {
+            // Buckets idle for a full period have refilled and carry no state worth keeping
+            buckets.retain(|(group, _), bucket| {
+                self.limits
+                    .get(group)
+                    .is_some_and(|limit| bucket.idle_for() < limit.period)
+            });
+        }
+
+        buckets
+            .entry((group, client.to_string()))
+            .or_insert_with(|| TokenBucket::new(limit))
+            .take(limit)
+    }
+}
+
+#[derive(Serialize)]
+struct RateLimitedResponse {
+    error: String,
+    group: &'static str,
+    retry_after_secs: u64,
+    limit: Limit,
+}
+
+/// Client identity behind nginx: X-Real-IP, then the first X-Forwarded-For hop, then the socket
+pub fn client_ip(headers: &HeaderMap, addr: &SocketAddr) -> String {
+    headers
+        .get("X-Real-IP")
+        .and_then(|v| v.to_str().ok())
+        .or_else(|| {
+            headers
+                .get("X-Forwarded-For")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.split(',').next())
+        })
+        .map(|ip| ip.trim().to_string())
+        .unwrap_or_else(|| addr.ip().to_string())
+}
+
+pub async fn rate_limit_middleware(
+    State(limiter): State<SharedRateLimiter>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    request: axum::http::Request<axum::body::Body>,
+    next: Next,
+) -> Response {
+    let group = Scope::required_for(request.method(), request.uri().path()).as_str();
+    let client = client_ip(request.headers(), &addr);
+
+    match limiter.check(group, &client) {
+        Ok(()) => next.run(request).await,
+        Err(wait) => {
+            let retry_after_secs = wait.as_secs() + u64::from(wait.subsec_nanos() > 0);
+            warn!(
+                client_ip = %client,
+                group = group,
+                path = %request.uri().path(),
+                retry_after_secs = retry_after_secs,
+                "Rate limit exceeded"
+            );
+
+            let limit = limiter.limits[group];
+            let mut response = (
+                StatusCode::TOO_MANY_REQUESTS,
+                Json(RateLimitedResponse {
+                    error: format!(
+                        "Rate limit exceeded for {} requests: {} per {}s",
+                        group,
+                        limit.burst,
+                        limit.period.as_secs()
+                    ),
+                    group,
+                    retry_after_secs,
+                    limit,
+                }),
+            )
+                .into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after_secs));
+            response
+        }
+    }
+}
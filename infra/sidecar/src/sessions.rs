@@ -0,0 +1,470 @@
+// ============================================================================
+// WebSocket Session Tokens
+// ============================================================================
+//
+// Mobile clients exchange either an admin-issued invite code or a signature
+// from their Holochain agent key for a short-lived session token, then present
+// it as `/ws?token=...`. Agent keys must belong to a verified member whose cell
+// this hub hosts, checked with the vouch zome before the token is issued.
+// Tokens are `base64url(payload).base64url(hmac)` signed with
+// SESSION_SIGNING_KEY (random per boot if unset, which invalidates tokens on
+// restart). An agent signature is accepted once; a repeat inside the skew
+// window is refused so a captured request can't mint more tokens. Each
+// session gets its own message rate limit, and its ID and
+// identity tag every log line from the connection.
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use serde_bytes::ByteBuf;
+use std::{
+    collections::{HashMap, HashSet},
+    path::PathBuf,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::RwLock;
+
+use crate::keys::{constant_time_eq, hash_secret, random_hex};
+use crate::ratelimit::{Limit, TokenBucket};
+use crate::zome_calls::ZomeCaller;
+
+/// Clients sign `ourblock-ws-session:<agent_pub_key>:<timestamp_ms>`
+const AGENT_SIGNATURE_CONTEXT: &str = "ourblock-ws-session";
+/// How far an agent signature's timestamp may drift from the hub clock
+const MAX_SIGNATURE_SKEW_MS: i64 = 5 * 60 * 1000;
+/// Holochain's 3-byte type prefix for agent public keys (`uhCAk`)
+const AGENT_KEY_PREFIX: [u8; 3] = [0x84, 0x20, 0x24];
+const MAX_INVITE_LABEL_LENGTH: usize = 64;
+const MAX_INVITE_TTL_HOURS: i64 = 24 * 30;
+const INVITE_CODE_ALPHABET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
+/// Drop idle message buckets once the table gets this large
+const MAX_TRACKED_SESSIONS: usize = 10_000;
+/// Refuse agent signatures while this many are still inside the skew window
+const MAX_TRACKED_SIGNATURES: usize = 10_000;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Claims carried in a session token
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Session {
+    #[serde(rename = "sid")]
+    pub id: String,
+    /// `agent:<pubkey>` or `invite:<invite id>`
+    #[serde(rename = "sub")]
+    pub identity: String,
+    #[serde(rename = "iat")]
+    pub issued_at: i64,
+    #[serde(rename = "exp")]
+    pub expires_at: i64,
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "method", rename_all = "snake_case")]
+pub enum SessionRequest {
+    Invite {
+        code: String,
+    },
+    Agent {
+        agent_pub_key: String,
+        timestamp_ms: i64,
+        /// Base64 Ed25519 signature over the context string
+        signature: String,
+    },
+}
+
+#[derive(Serialize)]
+pub struct IssuedSession {
+    pub token: String,
+    pub session_id: String,
+    pub identity: String,
+    pub expires_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Invite {
+    pub id: String,
+    pub label: String,
+    #[serde(skip_serializing_if = "String::is_empty", default)]
+    hash: String,
+    pub max_uses: u32,
+    pub uses: u32,
+    pub created_at: String,
+    pub expires_at: String,
+    pub revoked_at: Option<String>,
+}
+
+impl Invite {
+    fn is_usable(&self) -> bool {
+        self.revoked_at.is_none()
+            && self.uses < self.max_uses
+            && chrono::DateTime::parse_from_rfc3339(&self.expires_at)
+                .is_ok_and(|expires| expires > Utc::now())
+    }
+
+    /// Copy without the hash, for API responses
+    pub fn public(&self) -> Invite {
+        Invite {
+            hash: String::new(),
+            ..self.clone()
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct CreateInviteRequest {
+    pub label: String,
+    #[serde(default = "default_max_uses")]
+    pub max_uses: u32,
+    #[serde(default = "default_ttl_hours")]
+    pub ttl_hours: i64,
+}
+
+fn default_max_uses() -> u32 {
+    1
+}
+
+fn default_ttl_hours() -> i64 {
+    72
+}
+
+/// A newly created invite; `code` is never retrievable again
+#[derive(Serialize)]
+pub struct IssuedInvite {
+    pub invite: Invite,
+    pub code: String,
+}
+
+pub struct SessionManager {
+    signing_key: Vec<u8>,
+    ttl: Duration,
+    require_auth: bool,
+    message_limit: Option<Limit>,
+    invites_path: PathBuf,
+    invites: RwLock<Vec<Invite>>,
+    buckets: Mutex<HashMap<String, TokenBucket>>,
+    /// Accepted `(agent_pub_key, timestamp_ms)` pairs, kept for the skew window
+    used_signatures: Mutex<HashSet<(String, i64)>>,
+    zome_calls: Arc<ZomeCaller>,
+}
+
+/// vouch's `MembershipStatus`
+#[derive(Deserialize, PartialEq)]
+enum MembershipStatus {
+    Pending,
+    Verified,
+    Anchor,
+}
+
+/// The part of vouch's `MembershipInfo` read here
+#[derive(Deserialize)]
+struct MembershipInfo {
+    status: MembershipStatus,
+}
+
+impl SessionManager {
+    pub async fn from_env(zome_calls: Arc<ZomeCaller>) -> Result<Self, String> {
+        let env = |key: &str| std::env::var(key).ok().filter(|value| !value.is_empty());
+
+        let signing_key = match env("SESSION_SIGNING_KEY") {
+            Some(key) if key.len() < 32 => {
+                return Err("SESSION_SIGNING_KEY must be at least 32 characters".to_string())
+            }
+            Some(key) => key.into_bytes(),
+            None => {
+                tracing::warn!("SESSION_SIGNING_KEY not set; WebSocket sessions won't survive a restart");
+                let mut key = vec![0u8; 32];
+                rand::thread_rng().fill_bytes(&mut key);
+                key
+            }
+        };
+
+        let ttl_secs: u64 = env("WS_SESSION_TTL_SECS")
+            .unwrap_or_else(|| "900".to_string())
+            .parse()
+            .map_err(|_| "WS_SESSION_TTL_SECS must be a number of seconds")?;
+
+        let require_auth = match env("WS_REQUIRE_AUTH").as_deref().unwrap_or("true") {
+            "true" => true,
+            "false" => false,
+            other => return Err(format!("WS_REQUIRE_AUTH must be true or false, got '{}'", other)),
+        };
+
+        let message_limit = Limit::parse(&env("WS_MESSAGE_LIMIT").unwrap_or_else(|| "120/60".to_string()))
+            .map_err(|e| format!("WS_MESSAGE_LIMIT: {}", e))?;
+
+        let invites_path = PathBuf::from(env("WS_INVITES_FILE").unwrap_or_else(|| "/app/data/ws-invites.json".to_string()));
+        let invites = match tokio::fs::read(&invites_path).await {
+            Ok(contents) => serde_json::from_slice(&contents)
+                .map_err(|e| format!("Failed to parse invite store {}: {}", invites_path.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => return Err(format!("Failed to read invite store {}: {}", invites_path.display(), e)),
+        };
+
+        Ok(Self {
+            signing_key,
+            ttl: Duration::from_secs(ttl_secs),
+            require_auth,
+            message_limit,
+            invites_path,
+            invites: RwLock::new(invites),
+            buckets: Mutex::new(HashMap::new()),
+            used_signatures: Mutex::new(HashSet::new()),
+            zome_calls,
+        })
+    }
+
+    pub fn require_auth(&self) -> bool {
+        self.require_auth
+    }
+
+    /// Exchange an invite code or agent signature for a session token
+    pub async fn issue(&self, request: SessionRequest) -> Result<IssuedSession, String> {
+        let identity = match request {
+            SessionRequest::Invite { code } => format!("invite:{}", self.redeem_invite(&code).await?),
+            SessionRequest::Agent {
+                agent_pub_key,
+                timestamp_ms,
+                signature,
+            } => {
+                verify_agent_signature(&agent_pub_key, timestamp_ms, &signature)?;
+                self.claim_signature(&agent_pub_key, timestamp_ms)?;
+                self.require_hosted_member(&agent_pub_key).await?;
+                format!("agent:{}", agent_pub_key)
+            }
+        };
+
+        let now = Utc::now().timestamp();
+        let session = Session {
+            id: random_hex(12),
+            identity,
+            issued_at: now,
+            expires_at: now + self.ttl.as_secs() as i64,
+        };
+
+        let payload = URL_SAFE_NO_PAD.encode(serde_json::to_vec(&session).map_err(|e| e.to_string())?);
+        let signature = URL_SAFE_NO_PAD.encode(self.sign(payload.as_bytes()));
+
+        Ok(IssuedSession {
+            token: format!("{}.{}", payload, signature),
+            expires_at: chrono::DateTime::from_timestamp(session.expires_at, 0)
+                .map(|at| at.to_rfc3339())
+                .unwrap_or_default(),
+            session_id: session.id,
+            identity: session.identity,
+        })
+    }
+
+    /// Check a presented token's signature and expiry
+    pub fn verify(&self, token: &str) -> Result<Session, String> {
+        let (payload, signature) = token.split_once('.').ok_or("Malformed session token")?;
+        let signature = URL_SAFE_NO_PAD
+            .decode(signature)
+            .map_err(|_| "Malformed session token signature")?;
+
+        let mut mac = HmacSha256::new_from_slice(&self.signing_key).map_err(|e| e.to_string())?;
+        mac.update(payload.as_bytes());
+        mac.verify_slice(&signature).map_err(|_| "Invalid session token signature")?;
+
+        let payload = URL_SAFE_NO_PAD.decode(payload).map_err(|_| "Malformed session token payload")?;
+        let session: Session = serde_json::from_slice(&payload).map_err(|_| "Malformed session token payload")?;
+
+        if session.expires_at <= Utc::now().timestamp() {
+            return Err("Session token expired".to_string());
+        }
+        Ok(session)
+    }
+
+    /// Take a message token for a session, or return how long until one is available
+    pub fn check_message(&self, session_id: &str) -> Result<(), Duration> {
+        let Some(limit) = &self.message_limit else {
+            return Ok(());
+        };
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if buckets.len() >= MAX_TRACKED_SESSIONS {
+            buckets.retain(|_, bucket| bucket.idle_for() < limit.period);
+        }
+
+        buckets
+            .entry(session_id.to_string())
+            .or_insert_with(|| TokenBucket::new(limit))
+            .take(limit)
+    }
+
+    pub async fn list_invites(&self) -> Vec<Invite> {
+        self.invites.read().await.iter().map(Invite::public).collect()
+    }
+
+    pub async fn create_invite(&self, request: CreateInviteRequest) -> Result<IssuedInvite, String> {
+        let label = request.label.trim().to_string();
+        if label.is_empty() || label.len() > MAX_INVITE_LABEL_LENGTH {
+            return Err(format!("Invite label must be 1-{} characters", MAX_INVITE_LABEL_LENGTH));
+        }
+        if request.max_uses == 0 {
+            return Err("max_uses must be at least 1".to_string());
+        }
+        if !(1..=MAX_INVITE_TTL_HOURS).contains(&request.ttl_hours) {
+            return Err(format!("ttl_hours must be between 1 and {}", MAX_INVITE_TTL_HOURS));
+        }
+
+        let code = generate_invite_code();
+        let now = Utc::now();
+        let invite = Invite {
+            id: random_hex(8),
+            label,
+            hash: hash_secret(&code),
+            max_uses: request.max_uses,
+            uses: 0,
+            created_at: now.to_rfc3339(),
+            expires_at: (now + chrono::Duration::hours(request.ttl_hours)).to_rfc3339(),
+            revoked_at: None,
+        };
+
+        let mut invites = self.invites.write().await;
+        invites.push(invite.clone());
+        self.save(&invites).await?;
+
+        Ok(IssuedInvite {
+            invite: invite.public(),
+            code,
+        })
+    }
+
+    pub async fn revoke_invite(&self, id: &str) -> Result<Invite, String> {
+        let mut invites = self.invites.write().await;
+        let invite = invites
+            .iter_mut()
+            .find(|invite| invite.id == id && invite.revoked_at.is_none())
+            .ok_or("Invite not found or already revoked")?;
+        invite.revoked_at = Some(Utc::now().to_rfc3339());
+        let invite = invite.public();
+        self.save(&invites).await?;
+
+        Ok(invite)
+    }
+
+    /// Count a use against a valid invite and return its ID
+    async fn redeem_invite(&self, code: &str) -> Result<String, String> {
+        let presented = hash_secret(&normalize_invite_code(code));
+        let mut invites = self.invites.write().await;
+        let invite = invites
+            .iter_mut()
+            .filter(|invite| invite.is_usable())
+            .find(|invite| constant_time_eq(&invite.hash, &presented))
+            .ok_or("Invalid or expired invite code")?;
+        invite.uses += 1;
+        let id = invite.id.clone();
+        self.save(&invites).await?;
+
+        Ok(id)
+    }
+
+    /// Anyone can make an ed25519 key; only verified members hosted here get sessions
+    async fn require_hosted_member(&self, agent_pub_key: &str) -> Result<(), String> {
+        let cell = self
+            .zome_calls
+            .find_cell(agent_pub_key)
+            .await
+            .map_err(|e| format!("Failed to look up agent: {}", e))?
+            .ok_or("This agent is not hosted on this hub")?;
+        let membership: MembershipInfo = self
+            .zome_calls
+            .call(&cell, "vouch", "get_membership_status", &ByteBuf::from(cell.agent.to_vec()))
+            .await
+            .map_err(|e| format!("Failed to check membership: {}", e))?;
+        if membership.status == MembershipStatus::Pending {
+            return Err("Only verified members can open a session with their agent key".to_string());
+        }
+        Ok(())
+    }
+
+    async fn save(&self, invites: &[Invite]) -> Result<(), String> {
+        crate::persist::write_json_atomic(&self.invites_path, &invites).await
+    }
+
+    /// Record a verified agent signature, refusing one that was already used
+    fn claim_signature(&self, agent_pub_key: &str, timestamp_ms: i64) -> Result<(), String> {
+        let now = Utc::now().timestamp_millis();
+        let mut used = self.used_signatures.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        // Older timestamps already fail the skew check
+        used.retain(|(_, used_at)| now - used_at <= MAX_SIGNATURE_SKEW_MS);
+
+        if used.contains(&(agent_pub_key.to_string(), timestamp_ms)) {
+            return Err("Signature already used; sign a fresh timestamp".to_string());
+        }
+        if used.len() >= MAX_TRACKED_SIGNATURES {
+            return Err("Too many agent session requests; try again shortly".to_string());
+        }
+
+        used.insert((agent_pub_key.to_string(), timestamp_ms));
+        Ok(())
+    }
+
+    fn sign(&self, payload: &[u8]) -> Vec<u8> {
+        let mut mac = HmacSha256::new_from_slice(&self.signing_key).expect("HMAC accepts any key length");
+        mac.update(payload);
+        mac.finalize().into_bytes().to_vec()
+    }
+}
+
+/// Verify an Ed25519 signature from a Holochain agent key (`uhCAk...`)
+fn verify_agent_signature(agent_pub_key: &str, timestamp_ms: i64, signature: &str) -> Result<(), String> {
+    let skew = (Utc::now().timestamp_millis() - timestamp_ms).abs();
+    if skew > MAX_SIGNATURE_SKEW_MS {
+        return Err("Signature timestamp is too far from the hub clock".to_string());
+    }
+
+    let raw = decode_agent_key(agent_pub_key).ok_or("Invalid agent public key")?;
+    let key_bytes: [u8; 32] = raw[3..35].try_into().map_err(|_| "Invalid agent public key")?;
+    let key = VerifyingKey::from_bytes(&key_bytes).map_err(|_| "Invalid agent public key")?;
+
+    let signature = base64::engine::general_purpose::STANDARD
+        .decode(signature)
+        .ok()
+        .and_then(|bytes| Signature::from_slice(&bytes).ok())
+        .ok_or("Malformed signature")?;
+
+    let message = format!("{}:{}:{}", AGENT_SIGNATURE_CONTEXT, agent_pub_key, timestamp_ms);
+    key.verify(message.as_bytes(), &signature)
+        .map_err(|_| "Signature does not match agent key".to_string())
+}
+
+/// Raw 39-byte form of a Holochain agent key (`uhCAk...`), if well formed
+pub fn decode_agent_key(agent_pub_key: &str) -> Option<Vec<u8>> {
+    agent_pub_key
+        .strip_prefix('u')
+        .and_then(|encoded| URL_SAFE_NO_PAD.decode(encoded).ok())
+        .filter(|raw| raw.len() == 39 && raw[..3] == AGENT_KEY_PREFIX)
+}
+
+/// Eight characters from an unambiguous alphabet, shown as `XXXX-XXXX`
+fn generate_invite_code() -> String {
+    let mut bytes = [0u8; 8];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    let code: String = bytes
+        .iter()
+        .map(|byte| INVITE_CODE_ALPHABET[*byte as usize % INVITE_CODE_ALPHABET.len()] as char)
+        .collect();
+    format!("{}-{}", &code[..4], &code[4..])
+}
+
+/// Accept codes typed in lowercase or without the dash
+fn normalize_invite_code(code: &str) -> String {
+    let code: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect();
+    if code.len() == 8 {
+        format!("{}-{}", &code[..4], &code[4..])
+    } else {
+        code
+    }
+}
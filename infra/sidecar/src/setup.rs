@@ -0,0 +1,352 @@
+// ============================================================================
+// First-Boot Setup
+// ============================================================================
+//
+// A fresh hub has no admin key, so the setup endpoints are the only `/api`
+// routes that work without one. Completing setup creates an admin key, stores
+// the backup passphrase where the backup container reads it, and writes the
+// neighborhood config. The config file is written last and marks the hub as
+// initialized; from then on the endpoints refuse to run again.
+//
+// The neighborhood's identity lives in DNA properties, which are fixed once
+// the app is installed, so setup also installs the hApp: the conductor leaves
+// it uninstalled (`DEFER_APP_INSTALL`) and the sidecar installs it through the
+// admin API with the default properties from `DNA_PROPERTIES_FILE`, plus the
+// chosen `founding_anchor` and `neighborhood_name`.
+//
+// Hubs already configured through the environment (a non-default
+// ADMIN_API_KEY or existing API keys) count as initialized. The wizard never
+// runs for them, so the sidecar installs the hApp itself once the conductor is
+// up, with `NEIGHBORHOOD_NAME` and `FOUNDING_ANCHOR` from the environment.
+
+use chrono::Utc;
+use serde::{de::IgnoredAny, Deserialize, Serialize};
+use std::{collections::HashMap, path::PathBuf, sync::Arc, time::Duration};
+use tokio::sync::Mutex;
+use tracing::{error, info, warn};
+
+use crate::conductor::admin_call;
+use crate::keys::{IssuedKey, Scope, SharedKeyStore};
+
+const MAX_NEIGHBORHOOD_NAME_LENGTH: usize = 64;
+const MIN_BACKUP_PASSPHRASE_LENGTH: usize = 12;
+const ROLE_NAME: &str = "our_block";
+/// How often to retry installing for an env-configured hub while the conductor starts
+const PRECONFIGURED_INSTALL_RETRY: Duration = Duration::from_secs(10);
+
+/// Neighborhood settings chosen in the setup wizard
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct SetupConfig {
+    pub neighborhood_name: String,
+    /// Agent key (`uhCAk...`) that founds the neighborhood as its first
+    /// trusted anchor; installed as the DNA's `founding_anchor` property
+    pub founding_anchor: Option<String>,
+    pub completed_at: String,
+}
+
+#[derive(Deserialize)]
+pub struct SetupRequest {
+    pub neighborhood_name: String,
+    /// Required; optional only so a missing one gets a clear error
+    pub founding_anchor: Option<String>,
+    pub backup_passphrase: String,
+}
+
+#[derive(Serialize)]
+pub struct SetupStatus {
+    pub initialized: bool,
+    pub config: Option<SetupConfig>,
+}
+
+#[derive(Serialize)]
+pub struct SetupResult {
+    pub config: SetupConfig,
+    /// Admin key for every later `/api` call; shown only this once
+    pub admin_key: IssuedKey,
+    /// The new neighborhood name is announced over mDNS after a restart
+    pub restart_required: bool,
+}
+
+pub struct SetupManager {
+    config_path: PathBuf,
+    passphrase_path: PathBuf,
+    conductor_addr: String,
+    /// The hApp bundle, as a path on the conductor
+    happ_path: String,
+    app_id: String,
+    /// JSON object of the DNA's default properties, mirroring `dna.yaml`
+    properties_path: PathBuf,
+    /// `FOUNDING_ANCHOR`, for hubs configured through the environment
+    env_founding_anchor: Option<String>,
+    preconfigured: bool,
+    /// Held for the whole of `complete` so two wizards can't both finish
+    config: Mutex<Option<SetupConfig>>,
+}
+
+impl SetupManager {
+    pub async fn from_env(preconfigured: bool, conductor_addr: &str) -> Result<Self, String> {
+        let env = |key: &str, default: &str| std::env::var(key).unwrap_or_else(|_| default.to_string());
+
+        let config_path = PathBuf::from(env("SETUP_FILE", "/app/data/setup.json"));
+        let passphrase_path = PathBuf::from(env("BACKUP_PASSPHRASE_FILE", "/app/data/backup-passphrase"));
+        let properties_path = PathBuf::from(env("DNA_PROPERTIES_FILE", "/app/dna-properties.json"));
+
+        let config = match tokio::fs::read(&config_path).await {
+            Ok(contents) => Some(
+                serde_json::from_slice(&contents)
+                    .map_err(|e| format!("Failed to parse setup config {}: {}", config_path.display(), e))?,
+            ),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(format!("Failed to read setup config {}: {}", config_path.display(), e)),
+        };
+
+        Ok(Self {
+            config_path,
+            passphrase_path,
+            conductor_addr: conductor_addr.to_string(),
+            happ_path: env("HAPP_PATH", "/happs/our_block.happ"),
+            app_id: env("INSTALLED_APP_ID", "ourblock"),
+            properties_path,
+            env_founding_anchor: std::env::var("FOUNDING_ANCHOR")
+                .ok()
+                .map(|key| key.trim().to_string())
+                .filter(|key| !key.is_empty()),
+            preconfigured,
+            config: Mutex::new(config),
+        })
+    }
+
+    pub async fn status(&self) -> SetupStatus {
+        let config = self.config.lock().await.clone();
+        SetupStatus {
+            initialized: self.preconfigured || config.is_some(),
+            config,
+        }
+    }
+
+    /// Neighborhood name chosen during setup, if setup has run
+    pub async fn neighborhood_name(&self) -> Option<String> {
+        self.config.lock().await.as_ref().map(|config| config.neighborhood_name.clone())
+    }
+
+    pub async fn complete(&self, request: SetupRequest, keys: &SharedKeyStore) -> Result<SetupResult, SetupError> {
+        let mut config = self.config.lock().await;
+        if self.preconfigured || config.is_some() {
+            return Err(SetupError::AlreadyInitialized);
+        }
+
+        let neighborhood_name = request.neighborhood_name.trim().to_string();
+        if neighborhood_name.is_empty() || neighborhood_name.len() > MAX_NEIGHBORHOOD_NAME_LENGTH {
+            return Err(SetupError::Invalid(format!(
+                "Neighborhood name must be 1-{} characters",
+                MAX_NEIGHBORHOOD_NAME_LENGTH
+            )));
+        }
+        let founding_anchor = request.founding_anchor.unwrap_or_default().trim().to_string();
+        validate_founding_anchor(&founding_anchor)?;
+        if request.backup_passphrase.chars().count() < MIN_BACKUP_PASSPHRASE_LENGTH {
+            return Err(SetupError::Invalid(format!(
+                "Backup passphrase must be at least {} characters",
+                MIN_BACKUP_PASSPHRASE_LENGTH
+            )));
+        }
+
+        self.install_app(&neighborhood_name, &founding_anchor).await?;
+
+        crate::persist::write_atomic(&self.passphrase_path, request.backup_passphrase.as_bytes())
+            .await
+            .map_err(SetupError::Storage)?;
+
+        let admin_key = keys
+            .write()
+            .await
+            .create("setup-admin".to_string(), vec![Scope::Admin])
+            .await
+            .map_err(SetupError::Storage)?;
+
+        let new_config = SetupConfig {
+            neighborhood_name,
+            founding_anchor: Some(founding_anchor),
+            completed_at: Utc::now().to_rfc3339(),
+        };
+        crate::persist::write_json_atomic(&self.config_path, &new_config)
+            .await
+            .map_err(SetupError::Storage)?;
+        *config = Some(new_config.clone());
+
+        Ok(SetupResult {
+            config: new_config,
+            admin_key,
+            restart_required: true,
+        })
+    }
+
+    /// For a hub configured through the environment, install the hApp once
+    /// the conductor answers. Does nothing if it's already installed.
+    pub async fn install_preconfigured(self: Arc<Self>, neighborhood_name: String) {
+        if !self.preconfigured {
+            return;
+        }
+        let founding_anchor = self.env_founding_anchor.clone().unwrap_or_default();
+        loop {
+            match self.app_installed().await {
+                Ok(true) => return,
+                Ok(false) => {
+                    if let Err(e) = validate_founding_anchor(&founding_anchor) {
+                        error!(
+                            error = ?e,
+                            "Not installing the hApp: set FOUNDING_ANCHOR to the founder's agent key and restart"
+                        );
+                        return;
+                    }
+                    match self.install_app(&neighborhood_name, &founding_anchor).await {
+                        Ok(()) => {
+                            info!(app_id = %self.app_id, "Installed the hApp for the env-configured hub");
+                            return;
+                        }
+                        Err(e) => warn!(error = ?e, "Failed to install the hApp; retrying"),
+                    }
+                }
+                Err(e) => warn!(error = ?e, "Conductor not reachable to install the hApp; retrying"),
+            }
+            tokio::time::sleep(PRECONFIGURED_INSTALL_RETRY).await;
+        }
+    }
+
+    async fn app_installed(&self) -> Result<bool, SetupError> {
+        let apps: Vec<InstalledApp> = admin_call(
+            &self.conductor_addr,
+            &AdminRequest::ListApps { status_filter: None },
+            "apps_listed",
+        )
+        .await
+        .map_err(SetupError::Conductor)?;
+        Ok(apps.iter().any(|app| app.installed_app_id == self.app_id))
+    }
+
+    /// Install and enable the hApp with the neighborhood's DNA properties.
+    /// An app installed before setup can't take them any more, so that fails
+    /// rather than leaving a neighborhood nobody can found.
+    async fn install_app(&self, neighborhood_name: &str, founding_anchor: &str) -> Result<(), SetupError> {
+        let contents = tokio::fs::read(&self.properties_path)
+            .await
+            .map_err(|e| SetupError::Storage(format!("Failed to read {}: {}", self.properties_path.display(), e)))?;
+        let mut properties: serde_json::Map<String, serde_json::Value> = serde_json::from_slice(&contents)
+            .map_err(|e| SetupError::Storage(format!("Invalid {}: {}", self.properties_path.display(), e)))?;
+        properties.insert("neighborhood_name".to_string(), neighborhood_name.into());
+        properties.insert("founding_anchor".to_string(), founding_anchor.into());
+
+        if self.app_installed().await? {
+            return Err(SetupError::Conductor(format!(
+                "App '{}' was installed before setup, so its DNA properties can't be set; \
+                 start the conductor with DEFER_APP_INSTALL=true",
+                self.app_id
+            )));
+        }
+
+        let request = AdminRequest::InstallApp(Box::new(InstallAppPayload {
+            source: AppBundleSource::Path(self.happ_path.clone()),
+            agent_key: None,
+            installed_app_id: Some(self.app_id.clone()),
+            network_seed: None,
+            roles_settings: Some(HashMap::from([(
+                ROLE_NAME.to_string(),
+                RoleSettings::Provisioned {
+                    membrane_proof: None,
+                    modifiers: Some(DnaModifiers {
+                        network_seed: None,
+                        properties: Some(serde_json::Value::Object(properties)),
+                    }),
+                },
+            )])),
+            ignore_genesis_failure: false,
+        }));
+        admin_call::<_, IgnoredAny>(&self.conductor_addr, &request, "app_installed")
+            .await
+            .map_err(SetupError::Conductor)?;
+
+        admin_call::<_, IgnoredAny>(
+            &self.conductor_addr,
+            &AdminRequest::EnableApp {
+                installed_app_id: self.app_id.clone(),
+            },
+            "app_enabled",
+        )
+        .await
+        .map_err(SetupError::Conductor)?;
+        Ok(())
+    }
+}
+
+/// Without a founder nobody can ever be vouched for, and the DNA refuses to
+/// install a neighborhood that requires vouching without one
+fn validate_founding_anchor(key: &str) -> Result<(), SetupError> {
+    if key.is_empty() {
+        return Err(SetupError::Invalid(
+            "A founding anchor is required; without one nobody can become a member".to_string(),
+        ));
+    }
+    if crate::sessions::decode_agent_key(key).is_none() {
+        return Err(SetupError::Invalid("Founding anchor must be an agent key (uhCAk...)".to_string()));
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+pub enum SetupError {
+    AlreadyInitialized,
+    Invalid(String),
+    Storage(String),
+    /// The conductor couldn't install the hApp
+    Conductor(String),
+}
+
+// ============================================================================
+// Conductor API Shapes
+// ============================================================================
+
+#[derive(Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+enum AdminRequest {
+    ListApps { status_filter: Option<()> },
+    InstallApp(Box<InstallAppPayload>),
+    EnableApp { installed_app_id: String },
+}
+
+#[derive(Serialize)]
+struct InstallAppPayload {
+    source: AppBundleSource,
+    /// Left to the conductor, which generates one
+    agent_key: Option<()>,
+    installed_app_id: Option<String>,
+    network_seed: Option<String>,
+    roles_settings: Option<HashMap<String, RoleSettings>>,
+    ignore_genesis_failure: bool,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+enum AppBundleSource {
+    Path(String),
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+enum RoleSettings {
+    Provisioned {
+        membrane_proof: Option<()>,
+        modifiers: Option<DnaModifiers>,
+    },
+}
+
+/// Properties replace the bundle's wholesale, hence the full default set
+#[derive(Serialize)]
+struct DnaModifiers {
+    network_seed: Option<String>,
+    properties: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct InstalledApp {
+    installed_app_id: String,
+}
@@ -0,0 +1,202 @@
+// ============================================================================
+// Data Volume Monitoring
+// ============================================================================
+//
+// Reports how much each data volume holds and how full its filesystem is.
+// A background monitor broadcasts a warning to connected WebSocket clients
+// when a filesystem crosses the critical threshold, once per crossing.
+
+use serde::Serialize;
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+use tokio::sync::broadcast;
+use tracing::{error, info, warn};
+
+use crate::health::{disk_usage, DiskUsage};
+
+const MONITOR_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+#[derive(Clone, Debug)]
+pub struct StorageConfig {
+    pub volumes: Vec<(String, PathBuf)>,
+    pub warning_percent: f64,
+    pub critical_percent: f64,
+}
+
+impl StorageConfig {
+    /// STORAGE_VOLUMES is a comma-separated list of `name=path`
+    pub fn from_env() -> Result<Self, String> {
+        let volumes = std::env::var("STORAGE_VOLUMES")
+            .unwrap_or_else(|_| "conductor=/data/conductor,backups=/backups,ui=/app/ui/dist".to_string())
+            .split(',')
+            .filter(|entry| !entry.trim().is_empty())
+            .map(|entry| {
+                entry
+                    .split_once('=')
+                    .map(|(name, path)| (name.trim().to_string(), PathBuf::from(path.trim())))
+                    .ok_or_else(|| format!("Invalid STORAGE_VOLUMES entry '{}' (expected name=path)", entry))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let percent = |var: &str, default: f64| -> Result<f64, String> {
+            match std::env::var(var) {
+                Ok(value) => value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|percent| (0.0..=100.0).contains(percent))
+                    .ok_or_else(|| format!("{} must be a percentage between 0 and 100", var)),
+                Err(_) => Ok(default),
+            }
+        };
+
+        let warning_percent = percent("STORAGE_WARNING_PERCENT", 80.0)?;
+        let critical_percent = percent("STORAGE_CRITICAL_PERCENT", 90.0)?;
+        if warning_percent > critical_percent {
+            return Err("STORAGE_WARNING_PERCENT cannot exceed STORAGE_CRITICAL_PERCENT".to_string());
+        }
+
+        Ok(Self {
+            volumes,
+            warning_percent,
+            critical_percent,
+        })
+    }
+
+    fn level(&self, used_percent: f64) -> StorageLevel {
+        if used_percent >= self.critical_percent {
+            StorageLevel::Critical
+        } else if used_percent >= self.warning_percent {
+            StorageLevel::Warning
+        } else {
+            StorageLevel::Ok
+        }
+    }
+}
+
+#[derive(Serialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum StorageLevel {
+    Ok,
+    Warning,
+    Critical,
+}
+
+#[derive(Serialize, Debug)]
+pub struct VolumeUsage {
+    pub name: String,
+    pub path: String,
+    /// Bytes stored under `path`; None if the path doesn't exist
+    pub size_bytes: Option<u64>,
+    /// The filesystem holding `path`
+    pub filesystem: Option<DiskUsage>,
+    pub used_percent: Option<f64>,
+    pub level: StorageLevel,
+}
+
+#[derive(Serialize)]
+pub struct StorageReport {
+    pub volumes: Vec<VolumeUsage>,
+    pub warning_percent: f64,
+    pub critical_percent: f64,
+}
+
+/// Measure every configured volume (walks directories, so run off the async runtime)
+pub fn report(config: &StorageConfig) -> StorageReport {
+    let volumes = config
+        .volumes
+        .iter()
+        .map(|(name, path)| {
+            let exists = path.exists();
+            let filesystem = exists.then(|| disk_usage(path).ok()).flatten();
+            let used_percent = filesystem.map(|usage| usage.used_percent());
+            VolumeUsage {
+                name: name.clone(),
+                path: path.display().to_string(),
+                size_bytes: exists.then(|| dir_size(path)),
+                filesystem,
+                used_percent,
+                level: used_percent.map(|percent| config.level(percent)).unwrap_or(StorageLevel::Ok),
+            }
+        })
+        .collect();
+
+    StorageReport {
+        volumes,
+        warning_percent: config.warning_percent,
+        critical_percent: config.critical_percent,
+    }
+}
+
+/// Periodically check filesystem usage and broadcast a warning when a volume turns critical
+pub async fn monitor(config: StorageConfig, events: broadcast::Sender<String>) {
+    let mut critical: HashSet<String> = HashSet::new();
+
+    loop {
+        for (name, path) in &config.volumes {
+            let path_for_check = path.clone();
+            let usage = match tokio::task::spawn_blocking(move || disk_usage(&path_for_check)).await {
+                Ok(Ok(usage)) => usage,
+                Ok(Err(e)) => {
+                    warn!(volume = %name, error = %e, "Could not read volume usage");
+                    continue;
+                }
+                Err(e) => {
+                    error!(volume = %name, error = %e, "Volume usage check panicked");
+                    continue;
+                }
+            };
+
+            let used_percent = usage.used_percent();
+            if config.level(used_percent) != StorageLevel::Critical {
+                if critical.remove(name) {
+                    info!(volume = %name, used_percent = used_percent, "Volume usage back below critical");
+                }
+                continue;
+            }
+            if !critical.insert(name.clone()) {
+                continue; // Already warned about this crossing
+            }
+
+            warn!(volume = %name, used_percent = used_percent, "Volume usage critical");
+            let warning = serde_json::json!({
+                "type": "storage_warning",
+                "volume": name,
+                "path": path.display().to_string(),
+                "used_percent": used_percent,
+                "available_bytes": usage.available_bytes,
+                "threshold_percent": config.critical_percent,
+            });
+            // No receivers just means no clients are connected
+            let _ = events.send(warning.to_string());
+        }
+
+        tokio::time::sleep(MONITOR_INTERVAL).await;
+    }
+}
+
+/// Total size of regular files under `path`, not following symlinks
+fn dir_size(path: &Path) -> u64 {
+    let mut total = 0;
+    let mut pending = vec![path.to_path_buf()];
+
+    while let Some(dir) = pending.pop() {
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let Ok(file_type) = entry.file_type() else {
+                continue;
+            };
+            if file_type.is_dir() {
+                pending.push(entry.path());
+            } else if file_type.is_file() {
+                total += entry.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+            }
+        }
+    }
+
+    total
+}
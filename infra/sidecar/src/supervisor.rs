@@ -0,0 +1,234 @@
+// ============================================================================
+// Container Supervisor
+// ============================================================================
+//
+// Probes the conductor and nginx on an interval and restarts a container once
+// it has been unhealthy for several checks in a row, so a wedged conductor
+// doesn't wait for someone to call `/api/restart`. Restarts of the same
+// container back off exponentially. Incidents are broadcast to connected
+// WebSocket clients. Checks pause while an update or keystore restore holds
+// the update lock, since those stop containers on purpose.
+
+use serde_json::json;
+use std::{sync::Arc, time::Duration};
+use tokio::{process::Command, time::Instant};
+use tracing::{error, info, warn};
+
+use crate::health::{self, ComponentHealth, HealthStatus};
+use crate::AppState;
+
+#[derive(Clone, Debug)]
+pub struct SupervisorConfig {
+    pub enabled: bool,
+    pub interval: Duration,
+    /// Consecutive unhealthy checks before a restart
+    pub failure_threshold: u32,
+    /// Wait after a restart before the same container may be restarted again;
+    /// doubles after each restart that doesn't bring it back
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+}
+
+impl SupervisorConfig {
+    pub fn from_env() -> Result<Self, String> {
+        let number = |var: &str, default: u64| -> Result<u64, String> {
+            match std::env::var(var) {
+                Ok(value) => value
+                    .parse::<u64>()
+                    .ok()
+                    .filter(|number| *number > 0)
+                    .ok_or_else(|| format!("{} must be a positive number", var)),
+                Err(_) => Ok(default),
+            }
+        };
+
+        let enabled = match std::env::var("SUPERVISOR_ENABLED").as_deref() {
+            Ok("false") => false,
+            Ok("true") | Err(_) => true,
+            Ok(other) => return Err(format!("SUPERVISOR_ENABLED must be true or false, got '{}'", other)),
+        };
+        let initial_backoff = Duration::from_secs(number("SUPERVISOR_BACKOFF_SECS", 120)?);
+        let max_backoff = Duration::from_secs(number("SUPERVISOR_MAX_BACKOFF_SECS", 1800)?);
+        if initial_backoff > max_backoff {
+            return Err("SUPERVISOR_BACKOFF_SECS cannot exceed SUPERVISOR_MAX_BACKOFF_SECS".to_string());
+        }
+
+        Ok(Self {
+            enabled,
+            interval: Duration::from_secs(number("SUPERVISOR_INTERVAL_SECS", 30)?),
+            failure_threshold: u32::try_from(number("SUPERVISOR_FAILURE_THRESHOLD", 3)?)
+                .map_err(|_| "SUPERVISOR_FAILURE_THRESHOLD is too large".to_string())?,
+            initial_backoff,
+            max_backoff,
+        })
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Component {
+    Conductor,
+    Nginx,
+}
+
+impl Component {
+    fn name(&self) -> &'static str {
+        match self {
+            Component::Conductor => "conductor",
+            Component::Nginx => "nginx",
+        }
+    }
+
+    fn container(&self) -> &'static str {
+        match self {
+            Component::Conductor => "ourblock-conductor",
+            Component::Nginx => "ourblock-nginx-proxy",
+        }
+    }
+
+    async fn probe(&self, state: &AppState) -> ComponentHealth {
+        match self {
+            Component::Conductor => health::probe_conductor(&state.conductor_addr).await,
+            Component::Nginx => health::probe_nginx(&state.nginx_addr).await,
+        }
+    }
+}
+
+struct Tracker {
+    component: Component,
+    consecutive_failures: u32,
+    backoff: Duration,
+    /// No restart before this, while a previous restart gets its chance
+    restart_after: Option<Instant>,
+    /// Set from the first restart until the component is healthy again
+    incident_open: bool,
+}
+
+pub async fn run(config: SupervisorConfig, state: Arc<AppState>) {
+    if !config.enabled {
+        info!("Container supervisor disabled");
+        return;
+    }
+
+    let mut trackers: Vec<Tracker> = [Component::Conductor, Component::Nginx]
+        .into_iter()
+        .map(|component| Tracker {
+            component,
+            consecutive_failures: 0,
+            backoff: config.initial_backoff,
+            restart_after: None,
+            incident_open: false,
+        })
+        .collect();
+
+    loop {
+        tokio::time::sleep(config.interval).await;
+
+        // Held through the probes and any restart, so an update can't start
+        // while the supervisor is restarting a container
+        let Ok(_guard) = state.update_lock.clone().try_lock_owned() else {
+            continue;
+        };
+
+        for tracker in &mut trackers {
+            let health = tracker.component.probe(&state).await;
+            check(&config, &state, tracker, health).await;
+        }
+    }
+}
+
+async fn check(config: &SupervisorConfig, state: &AppState, tracker: &mut Tracker, health: ComponentHealth) {
+    let component = tracker.component;
+
+    if health.status != HealthStatus::Unhealthy {
+        if tracker.incident_open {
+            info!(component = component.name(), "Component recovered");
+            broadcast(
+                state,
+                component,
+                "recovered",
+                tracker.consecutive_failures,
+                &health.message,
+                None,
+            );
+        }
+        tracker.consecutive_failures = 0;
+        tracker.backoff = config.initial_backoff;
+        tracker.restart_after = None;
+        tracker.incident_open = false;
+        return;
+    }
+
+    tracker.consecutive_failures += 1;
+    warn!(
+        component = component.name(),
+        consecutive_failures = tracker.consecutive_failures,
+        message = %health.message,
+        "Component unhealthy"
+    );
+
+    if tracker.consecutive_failures < config.failure_threshold {
+        return;
+    }
+    if tracker.restart_after.is_some_and(|after| Instant::now() < after) {
+        return;
+    }
+
+    let (status, message) = match restart(component.container()).await {
+        Ok(()) => {
+            warn!(component = component.name(), container = component.container(), "Restarted unhealthy container");
+            ("restarted", health.message)
+        }
+        Err(e) => {
+            error!(component = component.name(), error = %e, "Failed to restart unhealthy container");
+            ("restart_failed", e)
+        }
+    };
+
+    let backoff = tracker.backoff;
+    tracker.restart_after = Some(Instant::now() + backoff);
+    tracker.backoff = (backoff * 2).min(config.max_backoff);
+    tracker.incident_open = true;
+    broadcast(
+        state,
+        component,
+        status,
+        tracker.consecutive_failures,
+        &message,
+        Some(backoff),
+    );
+}
+
+async fn restart(container: &str) -> Result<(), String> {
+    let output = Command::new("docker")
+        .args(["restart", container])
+        .output()
+        .await
+        .map_err(|e| format!("Failed to execute docker restart: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Docker restart failed: {}", stderr.trim()));
+    }
+    Ok(())
+}
+
+fn broadcast(
+    state: &AppState,
+    component: Component,
+    status: &str,
+    consecutive_failures: u32,
+    message: &str,
+    next_restart_after: Option<Duration>,
+) {
+    let incident = json!({
+        "type": "container_incident",
+        "component": component.name(),
+        "container": component.container(),
+        "status": status,
+        "consecutive_failures": consecutive_failures,
+        "message": message,
+        "next_restart_after_secs": next_restart_after.map(|after| after.as_secs()),
+    });
+    // No receivers just means no clients are connected
+    let _ = state.events.send(incident.to_string());
+}
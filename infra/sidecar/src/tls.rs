@@ -0,0 +1,302 @@
+// ============================================================================
+// Native TLS: self-signed or ACME-provisioned certificates
+// ============================================================================
+//
+// With TLS_MODE=self-signed the sidecar generates its own certificate and
+// exposes a trust-bootstrap QR (URL + SHA-256 fingerprint) so phones on the LAN
+// can install and verify it. With TLS_MODE=acme it drives the `lego` ACME client
+// (HTTP-01 via our own webroot, or DNS-01 via any lego DNS provider) and hot
+// reloads the certificate after renewals. The plain HTTP listener keeps running
+// for health checks, ACME challenges, and certificate download.
+
+use axum_server::tls_rustls::RustlsConfig;
+use chrono::{Datelike, Duration as ChronoDuration, Utc};
+use rustls::pki_types::{pem::PemObject, CertificateDer};
+use sha2::{Digest, Sha256};
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    time::{Duration, SystemTime},
+};
+use tracing::{error, info};
+
+/// iOS refuses user-trusted server certificates valid for longer than 825 days
+const SELF_SIGNED_VALIDITY_DAYS: i64 = 825;
+/// Regenerate a self-signed certificate this long before it expires
+const SELF_SIGNED_RENEW_AFTER_DAYS: u64 = 800;
+const ACME_RENEW_DAYS_BEFORE_EXPIRY: &str = "30";
+const ACME_RENEW_CHECK_INTERVAL: Duration = Duration::from_secs(12 * 60 * 60);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum AcmeChallenge {
+    /// HTTP-01, answered from `acme_webroot()` on the plain HTTP listener
+    Http,
+    /// DNS-01 through the named lego DNS provider (credentials via its env vars)
+    Dns(String),
+}
+
+#[derive(Clone, Debug)]
+pub struct AcmeConfig {
+    pub domain: String,
+    pub email: String,
+    pub challenge: AcmeChallenge,
+    pub directory_url: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+pub enum TlsMode {
+    Off,
+    SelfSigned,
+    Acme(AcmeConfig),
+}
+
+#[derive(Clone, Debug)]
+pub struct TlsSettings {
+    pub mode: TlsMode,
+    pub port: u16,
+    pub cert_dir: PathBuf,
+    /// Hostnames the certificate must cover (the mDNS name plus TLS_EXTRA_SANS)
+    pub hostnames: Vec<String>,
+}
+
+impl TlsSettings {
+    pub fn from_env(mdns_hostname: &str) -> Result<Self, String> {
+        let env = |key: &str| std::env::var(key).ok().filter(|value| !value.is_empty());
+
+        let mode = match env("TLS_MODE").as_deref().unwrap_or("off") {
+            "off" => TlsMode::Off,
+            "self-signed" => TlsMode::SelfSigned,
+            "acme" => {
+                let challenge = match env("ACME_CHALLENGE").as_deref().unwrap_or("http") {
+                    "http" => AcmeChallenge::Http,
+                    "dns" => AcmeChallenge::Dns(
+                        env("ACME_DNS_PROVIDER").ok_or("ACME_DNS_PROVIDER is required for DNS challenges")?,
+                    ),
+                    other => return Err(format!("Unknown ACME_CHALLENGE '{}' (expected http or dns)", other)),
+                };
+                TlsMode::Acme(AcmeConfig {
+                    domain: env("ACME_DOMAIN").ok_or("ACME_DOMAIN is required when TLS_MODE=acme")?,
+                    email: env("ACME_EMAIL").ok_or("ACME_EMAIL is required when TLS_MODE=acme")?,
+                    challenge,
+                    directory_url: env("ACME_DIRECTORY_URL"),
+                })
+            }
+            other => return Err(format!("Unknown TLS_MODE '{}' (expected off, self-signed, or acme)", other)),
+        };
+
+        let port = env("TLS_PORT")
+            .unwrap_or_else(|| "3443".to_string())
+            .parse()
+            .map_err(|_| "TLS_PORT must be a valid number")?;
+
+        let mut hostnames = vec![mdns_hostname.to_string(), "localhost".to_string()];
+        if let Some(extra) = env("TLS_EXTRA_SANS") {
+            hostnames.extend(extra.split(',').map(|name| name.trim().to_string()).filter(|name| !name.is_empty()));
+        }
+
+        Ok(Self {
+            mode,
+            port,
+            cert_dir: PathBuf::from(env("TLS_CERT_DIR").unwrap_or_else(|| "/app/certs".to_string())),
+            hostnames,
+        })
+    }
+
+    pub fn enabled(&self) -> bool {
+        !matches!(self.mode, TlsMode::Off)
+    }
+
+    pub fn mode_name(&self) -> &'static str {
+        match self.mode {
+            TlsMode::Off => "off",
+            TlsMode::SelfSigned => "self-signed",
+            TlsMode::Acme(_) => "acme",
+        }
+    }
+
+    pub fn cert_path(&self) -> PathBuf {
+        self.cert_dir.join("cert.pem")
+    }
+
+    pub fn key_path(&self) -> PathBuf {
+        self.cert_dir.join("key.pem")
+    }
+
+    /// Served at `/.well-known/acme-challenge` for HTTP-01 challenges
+    pub fn acme_webroot(&self) -> PathBuf {
+        self.cert_dir.join("acme-webroot")
+    }
+
+    /// The name clients should use for HTTPS
+    pub fn public_hostname(&self) -> &str {
+        match &self.mode {
+            TlsMode::Acme(acme) => &acme.domain,
+            _ => &self.hostnames[0],
+        }
+    }
+}
+
+/// Make sure a usable certificate is on disk, creating or renewing it as needed
+pub async fn provision(settings: &TlsSettings) -> Result<(), String> {
+    tokio::fs::create_dir_all(&settings.cert_dir)
+        .await
+        .map_err(|e| format!("Failed to create certificate directory: {}", e))?;
+
+    match &settings.mode {
+        TlsMode::Off => Ok(()),
+        TlsMode::SelfSigned => {
+            if self_signed_needs_renewal(&settings.cert_path()) {
+                generate_self_signed(settings).await?;
+            }
+            Ok(())
+        }
+        TlsMode::Acme(acme) => {
+            // cert.pem may be a self-signed certificate from before switching
+            // to ACME; only one lego issued itself can be renewed
+            let (issued_cert, _) = lego_certificate(settings, acme);
+            let command = if issued_cert.exists() { "renew" } else { "run" };
+            let (lego_settings, lego_acme) = (settings.clone(), acme.clone());
+            tokio::task::spawn_blocking(move || run_lego(&lego_settings, &lego_acme, command))
+                .await
+                .map_err(|e| e.to_string())??;
+            install_lego_certificate(settings, acme).await
+        }
+    }
+}
+
+/// Periodically renew the certificate and hot-reload the HTTPS listener
+pub async fn renewal_loop(settings: TlsSettings, config: RustlsConfig) {
+    loop {
+        tokio::time::sleep(ACME_RENEW_CHECK_INTERVAL).await;
+
+        if let Err(e) = provision(&settings).await {
+            error!(error = %e, "Certificate renewal failed");
+            continue;
+        }
+        match config
+            .reload_from_pem_file(settings.cert_path(), settings.key_path())
+            .await
+        {
+            Ok(_) => info!("TLS certificate reloaded"),
+            Err(e) => error!(error = %e, "Failed to reload TLS certificate"),
+        }
+    }
+}
+
+/// SHA-256 fingerprint of the leaf certificate, as colon-separated hex
+pub fn fingerprint(cert_path: &Path) -> Result<String, String> {
+    let cert = CertificateDer::from_pem_file(cert_path).map_err(|e| format!("Failed to read certificate: {}", e))?;
+    let digest = Sha256::digest(cert.as_ref());
+    Ok(digest
+        .iter()
+        .map(|byte| format!("{:02X}", byte))
+        .collect::<Vec<_>>()
+        .join(":"))
+}
+
+fn self_signed_needs_renewal(cert_path: &Path) -> bool {
+    let age = std::fs::metadata(cert_path)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+
+    match age {
+        Some(age) => age > Duration::from_secs(SELF_SIGNED_RENEW_AFTER_DAYS * 24 * 60 * 60),
+        None => true,
+    }
+}
+
+async fn generate_self_signed(settings: &TlsSettings) -> Result<(), String> {
+    info!(hostnames = ?settings.hostnames, "Generating self-signed TLS certificate");
+
+    let mut params =
+        rcgen::CertificateParams::new(settings.hostnames.clone()).map_err(|e| e.to_string())?;
+    let now = Utc::now();
+    let expires = now + ChronoDuration::days(SELF_SIGNED_VALIDITY_DAYS);
+    params.not_before = rcgen::date_time_ymd(now.year(), now.month() as u8, now.day() as u8);
+    params.not_after = rcgen::date_time_ymd(expires.year(), expires.month() as u8, expires.day() as u8);
+    params
+        .distinguished_name
+        .push(rcgen::DnType::CommonName, settings.hostnames[0].clone());
+
+    let key_pair = rcgen::KeyPair::generate().map_err(|e| e.to_string())?;
+    let cert = params.self_signed(&key_pair).map_err(|e| e.to_string())?;
+
+    write_private(&settings.key_path(), key_pair.serialize_pem().as_bytes()).await?;
+    tokio::fs::write(settings.cert_path(), cert.pem())
+        .await
+        .map_err(|e| format!("Failed to write certificate: {}", e))?;
+
+    Ok(())
+}
+
+/// Where lego keeps the certificate and key it issued for the domain
+fn lego_certificate(settings: &TlsSettings, acme: &AcmeConfig) -> (PathBuf, PathBuf) {
+    let issued = settings.cert_dir.join("lego").join("certificates");
+    let domain_file = acme.domain.replace('*', "_");
+    (
+        issued.join(format!("{}.crt", domain_file)),
+        issued.join(format!("{}.key", domain_file)),
+    )
+}
+
+/// Obtain or renew a certificate with lego
+fn run_lego(settings: &TlsSettings, acme: &AcmeConfig, command: &str) -> Result<(), String> {
+    info!(domain = %acme.domain, command = command, "Running ACME client");
+
+    let lego_dir = settings.cert_dir.join("lego");
+    let mut lego = Command::new("lego");
+    lego.arg("--accept-tos")
+        .args(["--email", &acme.email])
+        .args(["--domains", &acme.domain])
+        .arg("--path")
+        .arg(&lego_dir);
+    if let Some(directory_url) = &acme.directory_url {
+        lego.args(["--server", directory_url]);
+    }
+    match &acme.challenge {
+        AcmeChallenge::Http => {
+            std::fs::create_dir_all(settings.acme_webroot())
+                .map_err(|e| format!("Failed to create ACME webroot: {}", e))?;
+            lego.arg("--http").arg("--http.webroot").arg(settings.acme_webroot());
+        }
+        AcmeChallenge::Dns(provider) => {
+            lego.args(["--dns", provider]);
+        }
+    }
+    lego.arg(command);
+    if command == "renew" {
+        lego.args(["--days", ACME_RENEW_DAYS_BEFORE_EXPIRY]);
+    }
+
+    let output = lego
+        .output()
+        .map_err(|e| format!("Failed to execute lego: {}", e))?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("ACME {} failed: {}", command, stderr));
+    }
+
+    Ok(())
+}
+
+/// Copy lego's certificate and key to cert.pem / key.pem
+async fn install_lego_certificate(settings: &TlsSettings, acme: &AcmeConfig) -> Result<(), String> {
+    let (issued_cert, issued_key) = lego_certificate(settings, acme);
+    let key = tokio::fs::read(&issued_key)
+        .await
+        .map_err(|e| format!("Failed to read issued private key: {}", e))?;
+    write_private(&settings.key_path(), &key).await?;
+    tokio::fs::copy(&issued_cert, settings.cert_path())
+        .await
+        .map_err(|e| format!("Failed to install certificate: {}", e))?;
+
+    Ok(())
+}
+
+/// Written owner-only from the start, never readable between write and chmod
+async fn write_private(path: &Path, contents: &[u8]) -> Result<(), String> {
+    crate::persist::write_atomic(path, contents)
+        .await
+        .map_err(|e| format!("Failed to write private key: {}", e))
+}
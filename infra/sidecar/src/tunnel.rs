@@ -0,0 +1,562 @@
+// ============================================================================
+// Remote Access Tunnel
+// ============================================================================
+//
+// Hubs behind CGNAT can't accept inbound connections, so remote access goes
+// through an outbound tunnel run by an optional compose service:
+//
+// - WireGuard: the hub joins a WireGuard network whose server has a public
+//   address (a small VPS). The sidecar generates the hub's keys and config,
+//   plus configs for member devices; the operator adds the printed `[Peer]`
+//   blocks to the server. Members then reach the hub at its tunnel address.
+// - Relay: a Cloudflare Tunnel token; the provider forwards a public hostname
+//   to nginx.
+//
+// Private keys for member devices are returned once and never stored. The
+// hub's own key only lives in the owner-only wg0.conf the tunnel service reads;
+// it's read back from there rather than kept in the state file.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use chrono::Utc;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::{
+    net::Ipv4Addr,
+    path::{Path, PathBuf},
+};
+use tokio::{process::Command, sync::Mutex};
+use x25519_dalek::{PublicKey, StaticSecret};
+
+const COMPOSE_PROFILE: &str = "tunnel";
+const WIREGUARD_SERVICE: &str = "tunnel-wireguard";
+const WIREGUARD_CONTAINER: &str = "ourblock-tunnel-wireguard";
+const RELAY_SERVICE: &str = "tunnel-relay";
+const RELAY_CONTAINER: &str = "ourblock-tunnel-relay";
+const WIREGUARD_CONFIG_FILE: &str = "wg0.conf";
+const RELAY_TOKEN_FILE: &str = "relay-token";
+
+const MAX_CLIENT_NAME_LENGTH: usize = 64;
+const PERSISTENT_KEEPALIVE_SECS: u16 = 25;
+/// Smallest subnet that leaves room for the server, the hub, and a member
+const MAX_PREFIX_LENGTH: u8 = 29;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct WireguardConfig {
+    /// Public `host:port` of the WireGuard server
+    pub endpoint: String,
+    pub server_public_key: String,
+    /// Hub address in CIDR form, e.g. `10.8.0.2/24`
+    pub address: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct RelayConfig {
+    /// Hostname the provider forwards to the hub
+    pub public_url: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "mode", rename_all = "lowercase")]
+pub enum TunnelConfig {
+    Wireguard(WireguardConfig),
+    Relay(RelayConfig),
+}
+
+impl TunnelConfig {
+    fn service(&self) -> (&'static str, &'static str) {
+        match self {
+            TunnelConfig::Wireguard(_) => (WIREGUARD_SERVICE, WIREGUARD_CONTAINER),
+            TunnelConfig::Relay(_) => (RELAY_SERVICE, RELAY_CONTAINER),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "mode", rename_all = "lowercase")]
+pub enum ConfigureRequest {
+    Wireguard {
+        endpoint: String,
+        server_public_key: String,
+        address: String,
+    },
+    Relay {
+        token: String,
+        public_url: String,
+    },
+}
+
+#[derive(Deserialize)]
+pub struct AddClientRequest {
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct TunnelClient {
+    pub id: String,
+    pub name: String,
+    /// Tunnel address of the member device
+    pub address: Ipv4Addr,
+    pub public_key: String,
+    pub created_at: String,
+}
+
+/// A newly added member device; `config` holds its private key and is never
+/// retrievable again
+#[derive(Serialize)]
+pub struct IssuedClient {
+    pub client: TunnelClient,
+    /// wg-quick config to import on the device
+    pub config: String,
+    /// The same config as a QR code for the WireGuard mobile apps
+    pub qr_svg: String,
+    /// Block to add to the WireGuard server
+    pub server_peer: String,
+}
+
+#[derive(Serialize, Deserialize, Default)]
+struct TunnelState {
+    config: Option<TunnelConfig>,
+    enabled: bool,
+    /// Base64 WireGuard private key of the hub; never returned by the API.
+    /// Loaded from wg0.conf, and only read from state files that predate that.
+    #[serde(default, skip_serializing)]
+    hub_private_key: Option<String>,
+    #[serde(default)]
+    clients: Vec<TunnelClient>,
+}
+
+#[derive(Serialize)]
+pub struct TunnelStatus {
+    pub config: Option<TunnelConfig>,
+    pub enabled: bool,
+    /// Docker state of the tunnel container (`running`, `exited`, ...)
+    pub service_state: Option<String>,
+    pub hub_public_key: Option<String>,
+    /// Block to add to the WireGuard server for the hub itself
+    pub server_peer: Option<String>,
+    /// Last WireGuard handshake with the server
+    pub last_handshake: Option<String>,
+    pub clients: Vec<TunnelClient>,
+}
+
+pub struct TunnelManager {
+    state_path: PathBuf,
+    config_dir: PathBuf,
+    compose_file: String,
+    state: Mutex<TunnelState>,
+}
+
+impl TunnelManager {
+    pub async fn from_env(compose_file: String) -> Result<Self, String> {
+        let env = |key: &str, default: &str| std::env::var(key).unwrap_or_else(|_| default.to_string());
+
+        let state_path = PathBuf::from(env("TUNNEL_STATE_FILE", "/app/data/tunnel.json"));
+        let config_dir = PathBuf::from(env("TUNNEL_CONFIG_DIR", "/app/tunnel"));
+
+        let mut state: TunnelState = match tokio::fs::read(&state_path).await {
+            Ok(contents) => serde_json::from_slice(&contents)
+                .map_err(|e| format!("Failed to parse tunnel state {}: {}", state_path.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => TunnelState::default(),
+            Err(e) => return Err(format!("Failed to read tunnel state {}: {}", state_path.display(), e)),
+        };
+        // Older state files carry the hub key; rewriting them drops it
+        if state.hub_private_key.is_some() {
+            crate::persist::write_json_atomic(&state_path, &state).await?;
+        } else {
+            state.hub_private_key = read_hub_private_key(&config_dir.join(WIREGUARD_CONFIG_FILE)).await?;
+        }
+        // Files written by older versions were world-readable
+        for file in [WIREGUARD_CONFIG_FILE, RELAY_TOKEN_FILE] {
+            restrict_permissions(&config_dir.join(file)).await?;
+        }
+
+        Ok(Self {
+            state_path,
+            config_dir,
+            compose_file,
+            state: Mutex::new(state),
+        })
+    }
+
+    pub async fn status(&self) -> TunnelStatus {
+        let state = self.state.lock().await;
+
+        let service_state = match &state.config {
+            Some(config) => container_state(config.service().1).await,
+            None => None,
+        };
+        let hub_public_key = state.hub_private_key.as_deref().and_then(public_key_for);
+        let (server_peer, last_handshake) = match (&state.config, &hub_public_key) {
+            (Some(TunnelConfig::Wireguard(wireguard)), Some(public_key)) => {
+                let hub_address = parse_cidr(&wireguard.address).map(|(address, _)| address);
+                let handshake = match service_state.as_deref() {
+                    Some("running") => last_handshake(&wireguard.server_public_key).await,
+                    _ => None,
+                };
+                (
+                    hub_address.map(|address| server_peer_block("OurBlock hub", public_key, address)),
+                    handshake,
+                )
+            }
+            _ => (None, None),
+        };
+
+        TunnelStatus {
+            config: state.config.clone(),
+            enabled: state.enabled,
+            service_state,
+            hub_public_key,
+            server_peer,
+            last_handshake,
+            clients: state.clients.clone(),
+        }
+    }
+
+    /// Store the tunnel settings and write the config the tunnel service reads.
+    /// A running tunnel is restarted with the new settings.
+    pub async fn configure(&self, request: ConfigureRequest) -> Result<(), TunnelError> {
+        let mut state = self.state.lock().await;
+        let previous = state.config.clone();
+
+        let config = match request {
+            ConfigureRequest::Wireguard {
+                endpoint,
+                server_public_key,
+                address,
+            } => {
+                let endpoint = endpoint.trim().to_string();
+                validate_endpoint(&endpoint)?;
+                let server_public_key = server_public_key.trim().to_string();
+                if decode_key(&server_public_key).is_none() {
+                    return Err(TunnelError::Invalid(
+                        "Server public key must be a base64 WireGuard key".to_string(),
+                    ));
+                }
+                let address = address.trim().to_string();
+                let (hub_address, prefix) = parse_cidr(&address).ok_or_else(|| {
+                    TunnelError::Invalid(format!(
+                        "Address must be an IPv4 CIDR with a prefix of at most /{}, e.g. 10.8.0.2/24",
+                        MAX_PREFIX_LENGTH
+                    ))
+                })?;
+
+                // The hub keeps its key across reconfigurations so the server's peer entry stays valid
+                let hub_private_key = match &state.hub_private_key {
+                    Some(key) => key.clone(),
+                    None => generate_private_key(),
+                };
+
+                let config = WireguardConfig {
+                    endpoint,
+                    server_public_key,
+                    address,
+                };
+                let contents = hub_config(&config, &hub_private_key, hub_address, prefix);
+                crate::persist::write_atomic(&self.config_dir.join(WIREGUARD_CONFIG_FILE), contents.as_bytes())
+                    .await
+                    .map_err(TunnelError::Failed)?;
+
+                // Members outside the new subnet can't reach the hub any more
+                state
+                    .clients
+                    .retain(|client| in_subnet(client.address, hub_address, prefix) && client.address != hub_address);
+                state.hub_private_key = Some(hub_private_key);
+                TunnelConfig::Wireguard(config)
+            }
+            ConfigureRequest::Relay { token, public_url } => {
+                let token = token.trim();
+                if token.is_empty() || token.contains(char::is_whitespace) {
+                    return Err(TunnelError::Invalid("Relay token is required".to_string()));
+                }
+                let public_url = public_url.trim().trim_end_matches('/').to_string();
+                if !public_url.starts_with("https://") {
+                    return Err(TunnelError::Invalid("Public URL must start with https://".to_string()));
+                }
+                crate::persist::write_atomic(&self.config_dir.join(RELAY_TOKEN_FILE), token.as_bytes())
+                    .await
+                    .map_err(TunnelError::Failed)?;
+
+                state.clients.clear();
+                TunnelConfig::Relay(RelayConfig { public_url })
+            }
+        };
+
+        if state.enabled {
+            if let Some(previous) = &previous {
+                if previous.service() != config.service() {
+                    self.compose(&["stop", previous.service().0]).await?;
+                }
+            }
+            self.compose(&["up", "-d", "--force-recreate", config.service().0]).await?;
+        }
+
+        state.config = Some(config);
+        self.save(&state).await
+    }
+
+    pub async fn enable(&self) -> Result<(), TunnelError> {
+        let mut state = self.state.lock().await;
+        let Some(config) = &state.config else {
+            return Err(TunnelError::NotConfigured);
+        };
+
+        self.compose(&["up", "-d", config.service().0]).await?;
+        state.enabled = true;
+        self.save(&state).await
+    }
+
+    pub async fn disable(&self) -> Result<(), TunnelError> {
+        let mut state = self.state.lock().await;
+        self.compose(&["stop", WIREGUARD_SERVICE, RELAY_SERVICE]).await?;
+        state.enabled = false;
+        self.save(&state).await
+    }
+
+    /// Issue WireGuard credentials for a member device
+    pub async fn add_client(&self, name: String) -> Result<IssuedClient, TunnelError> {
+        let mut state = self.state.lock().await;
+        let Some(TunnelConfig::Wireguard(config)) = &state.config else {
+            return Err(TunnelError::Invalid(
+                "Member devices can only be added in WireGuard mode".to_string(),
+            ));
+        };
+
+        let name = name.trim().to_string();
+        if name.is_empty() || name.len() > MAX_CLIENT_NAME_LENGTH {
+            return Err(TunnelError::Invalid(format!(
+                "Device name must be 1-{} characters",
+                MAX_CLIENT_NAME_LENGTH
+            )));
+        }
+
+        let (hub_address, prefix) = parse_cidr(&config.address)
+            .ok_or_else(|| TunnelError::Failed("Stored tunnel address is invalid".to_string()))?;
+        let taken: Vec<Ipv4Addr> = state.clients.iter().map(|client| client.address).collect();
+        let address = allocate_address(hub_address, prefix, &taken)
+            .ok_or_else(|| TunnelError::Invalid("No free addresses left in the tunnel subnet".to_string()))?;
+
+        let private_key = generate_private_key();
+        let public_key = public_key_for(&private_key)
+            .ok_or_else(|| TunnelError::Failed("Failed to derive device public key".to_string()))?;
+
+        let client_config = client_config(config, &private_key, address, hub_address);
+        let qr_svg = crate::qr::svg(&client_config).map_err(TunnelError::Failed)?;
+
+        let client = TunnelClient {
+            id: crate::keys::random_hex(8),
+            name,
+            address,
+            public_key,
+            created_at: Utc::now().to_rfc3339(),
+        };
+        let server_peer = server_peer_block(&client.name, &client.public_key, client.address);
+
+        state.clients.push(client.clone());
+        self.save(&state).await?;
+
+        Ok(IssuedClient {
+            client,
+            config: client_config,
+            qr_svg,
+            server_peer,
+        })
+    }
+
+    /// Forget a member device; its `[Peer]` block must also be removed from the server
+    pub async fn remove_client(&self, id: &str) -> Result<TunnelClient, TunnelError> {
+        let mut state = self.state.lock().await;
+        let index = state
+            .clients
+            .iter()
+            .position(|client| client.id == id)
+            .ok_or(TunnelError::ClientNotFound)?;
+        let client = state.clients.remove(index);
+        self.save(&state).await?;
+
+        Ok(client)
+    }
+
+    async fn compose(&self, args: &[&str]) -> Result<(), TunnelError> {
+        let output = Command::new("docker")
+            .args(["compose", "-f", &self.compose_file, "--profile", COMPOSE_PROFILE])
+            .args(args)
+            .output()
+            .await
+            .map_err(|e| TunnelError::Failed(format!("Failed to execute docker compose: {}", e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(TunnelError::Failed(format!("Docker compose {} failed: {}", args[0], stderr)));
+        }
+        Ok(())
+    }
+
+    async fn save(&self, state: &TunnelState) -> Result<(), TunnelError> {
+        crate::persist::write_json_atomic(&self.state_path, state)
+            .await
+            .map_err(TunnelError::Failed)
+    }
+}
+
+#[derive(Debug)]
+pub enum TunnelError {
+    NotConfigured,
+    ClientNotFound,
+    Invalid(String),
+    Failed(String),
+}
+
+async fn container_state(container: &str) -> Option<String> {
+    let output = Command::new("docker")
+        .args(["inspect", "--format", "{{.State.Status}}", container])
+        .output()
+        .await
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `wg show` prints `<public key>\t<unix seconds>` per peer; 0 means never
+async fn last_handshake(server_public_key: &str) -> Option<String> {
+    let output = Command::new("docker")
+        .args(["exec", WIREGUARD_CONTAINER, "wg", "show", "wg0", "latest-handshakes"])
+        .output()
+        .await
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let seconds: i64 = stdout
+        .lines()
+        .filter_map(|line| line.split_once('\t'))
+        .find(|(key, _)| *key == server_public_key)
+        .and_then(|(_, seconds)| seconds.trim().parse().ok())
+        .filter(|seconds| *seconds > 0)?;
+    chrono::DateTime::from_timestamp(seconds, 0).map(|at| at.to_rfc3339())
+}
+
+/// The hub's private key from the `[Interface]` of its wg0.conf, if written
+async fn read_hub_private_key(path: &Path) -> Result<Option<String>, String> {
+    let contents = match tokio::fs::read_to_string(path).await {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("Failed to read {}: {}", path.display(), e)),
+    };
+    Ok(contents
+        .lines()
+        .take_while(|line| line.trim() != "[Peer]")
+        .filter_map(|line| line.split_once('='))
+        .find(|(key, _)| key.trim() == "PrivateKey")
+        .map(|(_, value)| value.trim().to_string())
+        .filter(|key| decode_key(key).is_some()))
+}
+
+/// Make an existing secret file owner-only
+async fn restrict_permissions(path: &Path) -> Result<(), String> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        match tokio::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600)).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(format!("Failed to restrict {}: {}", path.display(), e)),
+        }
+    }
+    Ok(())
+}
+
+fn hub_config(config: &WireguardConfig, private_key: &str, address: Ipv4Addr, prefix: u8) -> String {
+    let subnet = Ipv4Addr::from(u32::from(address) & prefix_mask(prefix));
+    // Forward web traffic arriving over the tunnel to nginx
+    format!(
+        "[Interface]\n\
+         PrivateKey = {private_key}\n\
+         Address = {address}/{prefix}\n\
+         PostUp = iptables -t nat -A PREROUTING -i %i -p tcp -m multiport --dports 80,443 -j DNAT --to-destination $(getent hosts nginx | cut -d' ' -f1); iptables -t nat -A POSTROUTING -o eth+ -j MASQUERADE\n\
+         PostDown = iptables -t nat -F\n\
+         \n\
+         [Peer]\n\
+         PublicKey = {server_public_key}\n\
+         Endpoint = {endpoint}\n\
+         AllowedIPs = {subnet}/{prefix}\n\
+         PersistentKeepalive = {keepalive}\n",
+        server_public_key = config.server_public_key,
+        endpoint = config.endpoint,
+        keepalive = PERSISTENT_KEEPALIVE_SECS,
+    )
+}
+
+fn client_config(config: &WireguardConfig, private_key: &str, address: Ipv4Addr, hub_address: Ipv4Addr) -> String {
+    format!(
+        "[Interface]\n\
+         PrivateKey = {private_key}\n\
+         Address = {address}/32\n\
+         \n\
+         [Peer]\n\
+         PublicKey = {server_public_key}\n\
+         Endpoint = {endpoint}\n\
+         AllowedIPs = {hub_address}/32\n\
+         PersistentKeepalive = {keepalive}\n",
+        server_public_key = config.server_public_key,
+        endpoint = config.endpoint,
+        keepalive = PERSISTENT_KEEPALIVE_SECS,
+    )
+}
+
+fn server_peer_block(name: &str, public_key: &str, address: Ipv4Addr) -> String {
+    format!("[Peer]\n# {name}\nPublicKey = {public_key}\nAllowedIPs = {address}/32\n")
+}
+
+fn generate_private_key() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    STANDARD.encode(StaticSecret::from(bytes).to_bytes())
+}
+
+fn public_key_for(private_key: &str) -> Option<String> {
+    let secret = StaticSecret::from(decode_key(private_key)?);
+    Some(STANDARD.encode(PublicKey::from(&secret).as_bytes()))
+}
+
+fn decode_key(key: &str) -> Option<[u8; 32]> {
+    STANDARD.decode(key).ok()?.try_into().ok()
+}
+
+fn validate_endpoint(endpoint: &str) -> Result<(), TunnelError> {
+    let valid = endpoint
+        .rsplit_once(':')
+        .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok_and(|port| port > 0))
+        && !endpoint.contains(char::is_whitespace);
+    if valid {
+        Ok(())
+    } else {
+        Err(TunnelError::Invalid("Endpoint must be host:port".to_string()))
+    }
+}
+
+fn parse_cidr(cidr: &str) -> Option<(Ipv4Addr, u8)> {
+    let (address, prefix) = cidr.split_once('/')?;
+    let prefix: u8 = prefix.parse().ok()?;
+    if !(1..=MAX_PREFIX_LENGTH).contains(&prefix) {
+        return None;
+    }
+    Some((address.parse().ok()?, prefix))
+}
+
+fn prefix_mask(prefix: u8) -> u32 {
+    u32::MAX << (32 - prefix)
+}
+
+fn in_subnet(address: Ipv4Addr, network: Ipv4Addr, prefix: u8) -> bool {
+    u32::from(address) & prefix_mask(prefix) == u32::from(network) & prefix_mask(prefix)
+}
+
+/// Lowest free host address in the hub's subnet. The first host address is
+/// left for the WireGuard server.
+fn allocate_address(hub_address: Ipv4Addr, prefix: u8, taken: &[Ipv4Addr]) -> Option<Ipv4Addr> {
+    let network = u32::from(hub_address) & prefix_mask(prefix);
+    let broadcast = network | !prefix_mask(prefix);
+    (network + 2..broadcast)
+        .map(Ipv4Addr::from)
+        .find(|candidate| *candidate != hub_address && !taken.contains(candidate))
+}
@@ -0,0 +1,374 @@
+// ============================================================================
+// UI Asset Versioning
+// ============================================================================
+//
+// The UI is served from the bundle baked into the image (`UI_PATH`) until a
+// UI-only update installs another one. Uploaded bundles are unpacked into
+// their own directory under `UI_RELEASES_DIR`, named by a hash of their
+// contents, and only become active once fully unpacked and checked, so a
+// client never sees half of one bundle and half of another. A full image
+// update ships a new baked bundle, which then takes over again.
+//
+// Vite puts content-hashed files under `/assets/`; those are cached for a year
+// as immutable. Everything else, `index.html` in particular, must be
+// revalidated so clients pick up a new bundle on their next load.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::{
+    path::{Component, Path, PathBuf},
+    process::Stdio,
+};
+use tokio::{io::AsyncWriteExt, process::Command, sync::RwLock};
+use tracing::{info, warn};
+
+/// Uploaded bundles (a gzipped tar of the built `dist/`) are limited to this
+pub const MAX_BUNDLE_BYTES: usize = 100 * 1024 * 1024;
+/// Unpacked size limit, checked against the archive's listing before anything
+/// is extracted, so a small upload can't expand into a full disk
+const MAX_UNPACKED_BYTES: u64 = 500 * 1024 * 1024;
+const MAX_BUNDLE_ENTRIES: usize = 20_000;
+pub const IMMUTABLE_PREFIX: &str = "/assets/";
+pub const IMMUTABLE_CACHE_CONTROL: &str = "public, max-age=31536000, immutable";
+pub const REVALIDATE_CACHE_CONTROL: &str = "no-cache";
+const STATE_FILE: &str = "current.json";
+
+#[derive(Debug)]
+pub enum UiUpdateError {
+    /// Not a usable bundle
+    Invalid(String),
+    Failed(String),
+}
+
+#[derive(Serialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum BundleSource {
+    /// Baked into the running image
+    Image,
+    /// Installed with `POST /api/update/ui`
+    Upload,
+}
+
+#[derive(Serialize, Clone, Debug)]
+pub struct ActiveBundle {
+    /// Hex SHA-256 over every file's path and contents
+    pub hash: String,
+    pub source: BundleSource,
+    pub installed_at: Option<String>,
+    #[serde(skip)]
+    pub dir: PathBuf,
+}
+
+/// Which uploaded bundle is active, persisted across restarts
+#[derive(Serialize, Deserialize)]
+struct InstalledBundle {
+    hash: String,
+    installed_at: String,
+    /// Image bundle at install time; a different one means the image was
+    /// updated since, and its bundle wins
+    image_hash: String,
+}
+
+pub struct UiAssets {
+    releases_dir: PathBuf,
+    image_hash: String,
+    image_dir: PathBuf,
+    active: RwLock<ActiveBundle>,
+}
+
+impl UiAssets {
+    pub async fn from_env(image_dir: &str) -> Result<Self, String> {
+        let releases_dir = PathBuf::from(
+            std::env::var("UI_RELEASES_DIR").unwrap_or_else(|_| "/app/data/ui".to_string()),
+        );
+        let image_dir = PathBuf::from(image_dir);
+        let image_hash = hash_bundle(image_dir.clone()).await?;
+
+        let image_bundle = ActiveBundle {
+            hash: image_hash.clone(),
+            source: BundleSource::Image,
+            installed_at: None,
+            dir: image_dir.clone(),
+        };
+        let active = match tokio::fs::read(releases_dir.join(STATE_FILE)).await {
+            Ok(contents) => match serde_json::from_slice::<InstalledBundle>(&contents) {
+                Ok(installed) if installed.image_hash != image_hash => {
+                    info!(uploaded = %installed.hash, "Image ships a newer UI bundle; uploaded bundle retired");
+                    image_bundle
+                }
+                Ok(installed) if releases_dir.join(&installed.hash).is_dir() => ActiveBundle {
+                    dir: releases_dir.join(&installed.hash),
+                    hash: installed.hash,
+                    source: BundleSource::Upload,
+                    installed_at: Some(installed.installed_at),
+                },
+                Ok(installed) => {
+                    warn!(uploaded = %installed.hash, "Uploaded UI bundle is missing; serving the image bundle");
+                    image_bundle
+                }
+                Err(e) => {
+                    warn!(error = %e, "UI bundle state unreadable; serving the image bundle");
+                    image_bundle
+                }
+            },
+            Err(_) => image_bundle,
+        };
+
+        Ok(Self {
+            releases_dir,
+            image_hash,
+            image_dir,
+            active: RwLock::new(active),
+        })
+    }
+
+    pub async fn active(&self) -> ActiveBundle {
+        self.active.read().await.clone()
+    }
+
+    /// Unpack a gzipped tar of a built UI and switch to it. Returns the active
+    /// bundle and whether it changed; uploading the running bundle is a no-op.
+    pub async fn install(&self, archive: &[u8]) -> Result<(ActiveBundle, bool), UiUpdateError> {
+        if archive.is_empty() {
+            return Err(UiUpdateError::Invalid("Bundle is empty".to_string()));
+        }
+        tokio::fs::create_dir_all(&self.releases_dir)
+            .await
+            .map_err(|e| UiUpdateError::Failed(format!("Failed to create {}: {}", self.releases_dir.display(), e)))?;
+
+        let staging = self
+            .releases_dir
+            .join(format!(".staging-{}", crate::keys::random_hex(8)));
+        let result = self.unpack_and_activate(archive, &staging).await;
+        if staging.exists() {
+            let _ = tokio::fs::remove_dir_all(&staging).await;
+        }
+        result
+    }
+
+    async fn unpack_and_activate(&self, archive: &[u8], staging: &Path) -> Result<(ActiveBundle, bool), UiUpdateError> {
+        let listing = run_tar(&["-tzf", "-"], archive).await?;
+        if let Some(entry) = listing.lines().find(|entry| !is_safe_entry(entry)) {
+            return Err(UiUpdateError::Invalid(format!("Bundle entry '{}' escapes the bundle", entry)));
+        }
+        check_listing(&run_tar(&["-tvzf", "-"], archive).await?)?;
+
+        tokio::fs::create_dir_all(staging)
+            .await
+            .map_err(|e| UiUpdateError::Failed(format!("Failed to create {}: {}", staging.display(), e)))?;
+        let staging_arg = staging.to_string_lossy().to_string();
+        run_tar(&["-xzf", "-", "-C", &staging_arg], archive).await?;
+
+        check_tree(staging.to_path_buf()).await?;
+        if !staging.join("index.html").is_file() {
+            return Err(UiUpdateError::Invalid(
+                "Bundle has no index.html at its root; archive the contents of dist/, not the folder".to_string(),
+            ));
+        }
+        let hash = hash_bundle(staging.to_path_buf()).await.map_err(UiUpdateError::Failed)?;
+
+        let mut active = self.active.write().await;
+        if active.hash == hash {
+            return Ok((active.clone(), false));
+        }
+
+        let release = self.releases_dir.join(&hash);
+        if release.exists() {
+            tokio::fs::remove_dir_all(&release)
+                .await
+                .map_err(|e| UiUpdateError::Failed(format!("Failed to replace {}: {}", release.display(), e)))?;
+        }
+        tokio::fs::rename(staging, &release)
+            .await
+            .map_err(|e| UiUpdateError::Failed(format!("Failed to move bundle into place: {}", e)))?;
+
+        let installed = InstalledBundle {
+            hash: hash.clone(),
+            installed_at: Utc::now().to_rfc3339(),
+            image_hash: self.image_hash.clone(),
+        };
+        crate::persist::write_json_atomic(&self.releases_dir.join(STATE_FILE), &installed)
+            .await
+            .map_err(UiUpdateError::Failed)?;
+
+        let previous = std::mem::replace(
+            &mut *active,
+            ActiveBundle {
+                hash,
+                source: BundleSource::Upload,
+                installed_at: Some(installed.installed_at),
+                dir: release,
+            },
+        );
+        self.prune(&active, &previous).await;
+
+        info!(hash = %active.hash, previous = %previous.hash, "UI bundle updated");
+        Ok((active.clone(), true))
+    }
+
+    /// Keep the active and previous uploaded bundles, so clients still loading
+    /// the previous one can finish, and remove the rest
+    async fn prune(&self, active: &ActiveBundle, previous: &ActiveBundle) {
+        let Ok(mut entries) = tokio::fs::read_dir(&self.releases_dir).await else {
+            return;
+        };
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let path = entry.path();
+            let keep = path == active.dir
+                || path == previous.dir
+                || path == self.image_dir
+                || entry.file_name().to_string_lossy().starts_with('.')
+                || !path.is_dir();
+            if !keep {
+                if let Err(e) = tokio::fs::remove_dir_all(&path).await {
+                    warn!(path = %path.display(), error = %e, "Failed to remove old UI bundle");
+                }
+            }
+        }
+    }
+}
+
+/// Cache policy for a UI path
+pub fn cache_control(path: &str) -> &'static str {
+    if path.starts_with(IMMUTABLE_PREFIX) {
+        IMMUTABLE_CACHE_CONTROL
+    } else {
+        REVALIDATE_CACHE_CONTROL
+    }
+}
+
+/// Relative paths only, with no `..` components
+fn is_safe_entry(entry: &str) -> bool {
+    let path = Path::new(entry);
+    path.components()
+        .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+}
+
+/// Reject links, devices, and anything else that isn't a file or folder, and
+/// bundles that would unpack too large, from a `tar -tv` listing, whose lines
+/// start with the entry's mode (its type first) and have the size third
+fn check_listing(verbose_listing: &str) -> Result<(), UiUpdateError> {
+    let mut entries = 0;
+    let mut unpacked: u64 = 0;
+    for line in verbose_listing.lines().filter(|line| !line.trim().is_empty()) {
+        entries += 1;
+        if entries > MAX_BUNDLE_ENTRIES {
+            return Err(UiUpdateError::Invalid(format!(
+                "Bundle has more than {} entries",
+                MAX_BUNDLE_ENTRIES
+            )));
+        }
+
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        let kind = fields.first().and_then(|mode| mode.chars().next());
+        if !matches!(kind, Some('-') | Some('d')) || line.contains(" -> ") || line.contains(" link to ") {
+            return Err(UiUpdateError::Invalid(format!(
+                "Bundle may only contain files and folders; '{}' is neither",
+                line
+            )));
+        }
+        let size: u64 = fields
+            .get(2)
+            .and_then(|size| size.parse().ok())
+            .ok_or_else(|| UiUpdateError::Invalid(format!("Unreadable bundle entry '{}'", line)))?;
+        unpacked = unpacked.saturating_add(size);
+        if unpacked > MAX_UNPACKED_BYTES {
+            return Err(UiUpdateError::Invalid(format!(
+                "Bundle unpacks to more than {} bytes",
+                MAX_UNPACKED_BYTES
+            )));
+        }
+    }
+    Ok(())
+}
+
+async fn run_tar(args: &[&str], archive: &[u8]) -> Result<String, UiUpdateError> {
+    let mut child = Command::new("tar")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| UiUpdateError::Failed(format!("Failed to run tar: {}", e)))?;
+
+    let mut stdin = child
+        .stdin
+        .take()
+        .ok_or_else(|| UiUpdateError::Failed("tar has no stdin".to_string()))?;
+    let input = archive.to_vec();
+    // Feed stdin while reading output so a large bundle can't deadlock the pipes
+    let writer = async move {
+        let result = stdin.write_all(&input).await;
+        drop(stdin);
+        result
+    };
+    let (_, output) = tokio::join!(writer, child.wait_with_output());
+    let output = output.map_err(|e| UiUpdateError::Failed(format!("tar failed: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(UiUpdateError::Invalid(format!("Not a gzipped tar archive: {}", stderr.trim())));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Only regular files and directories; a symlink could point anywhere. The
+/// listing was checked before extracting, so this only backs it up.
+async fn check_tree(dir: PathBuf) -> Result<(), UiUpdateError> {
+    tokio::task::spawn_blocking(move || {
+        let mut pending = vec![dir];
+        while let Some(dir) = pending.pop() {
+            let entries = std::fs::read_dir(&dir).map_err(|e| UiUpdateError::Failed(e.to_string()))?;
+            for entry in entries {
+                let entry = entry.map_err(|e| UiUpdateError::Failed(e.to_string()))?;
+                let file_type = entry.file_type().map_err(|e| UiUpdateError::Failed(e.to_string()))?;
+                if file_type.is_dir() {
+                    pending.push(entry.path());
+                } else if !file_type.is_file() {
+                    return Err(UiUpdateError::Invalid(format!(
+                        "Bundle may only contain files and folders; '{}' is neither",
+                        entry.file_name().to_string_lossy()
+                    )));
+                }
+            }
+        }
+        Ok(())
+    })
+    .await
+    .map_err(|e| UiUpdateError::Failed(e.to_string()))?
+}
+
+/// Hex SHA-256 over each file's relative path, length, and contents, in path order
+async fn hash_bundle(dir: PathBuf) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || {
+        let mut files = Vec::new();
+        let mut pending = vec![dir.clone()];
+        while let Some(current) = pending.pop() {
+            let entries =
+                std::fs::read_dir(&current).map_err(|e| format!("Failed to read {}: {}", current.display(), e))?;
+            for entry in entries {
+                let path = entry.map_err(|e| e.to_string())?.path();
+                if path.is_dir() {
+                    pending.push(path);
+                } else {
+                    files.push(path);
+                }
+            }
+        }
+        files.sort();
+
+        let mut hasher = Sha256::new();
+        for path in files {
+            let relative = path.strip_prefix(&dir).unwrap_or(&path).to_string_lossy().replace('\\', "/");
+            let contents = std::fs::read(&path).map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            hasher.update(relative.as_bytes());
+            hasher.update([0]);
+            hasher.update((contents.len() as u64).to_le_bytes());
+            hasher.update(&contents);
+        }
+        Ok(format!("{:x}", hasher.finalize()))
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
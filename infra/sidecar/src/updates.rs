@@ -0,0 +1,202 @@
+// ============================================================================
+// Update State, Version Pinning, and Rollback
+// ============================================================================
+//
+// Before every update the image IDs of the running containers are recorded.
+// Rolling back re-tags those images to the names compose expects and runs
+// `docker compose up -d` without pulling, so it works offline and doesn't
+// depend on the registry still having the old tag.
+
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::{path::Path, process::Command, time::Duration};
+use tracing::{info, warn};
+
+use crate::health::{self, HealthStatus};
+use crate::AppState;
+
+/// Compose variable selecting the edge-node image tag
+pub const VERSION_ENV: &str = "OURBLOCK_VERSION";
+pub const DEFAULT_VERSION: &str = "latest";
+
+const MAX_VERSION_LENGTH: usize = 128;
+const POST_UPDATE_HEALTH_TIMEOUT: Duration = Duration::from_secs(180);
+const POST_UPDATE_HEALTH_INTERVAL: Duration = Duration::from_secs(10);
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ImageRecord {
+    pub container: String,
+    pub repository: String,
+    pub tag: String,
+    pub image_id: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct ImageSnapshot {
+    pub version: String,
+    pub images: Vec<ImageRecord>,
+    pub recorded_at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct UpdateOutcome {
+    pub action: String,
+    pub status: String,
+    pub message: String,
+    pub at: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct UpdateState {
+    /// Tag the edge node is pinned to; None follows `latest`
+    pub pinned_version: Option<String>,
+    pub current_version: Option<String>,
+    /// Images running before the most recent update
+    pub previous: Option<ImageSnapshot>,
+    pub last_outcome: Option<UpdateOutcome>,
+}
+
+impl UpdateState {
+    pub async fn load(path: &Path) -> Self {
+        match tokio::fs::read(path).await {
+            Ok(contents) => serde_json::from_slice(&contents).unwrap_or_else(|e| {
+                warn!(error = %e, "Update state unreadable, starting fresh");
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub async fn save(&self, path: &Path) -> Result<(), String> {
+        crate::persist::write_json_atomic(path, self).await
+    }
+
+    /// The version to deploy: explicit request, else the pin, else latest
+    pub fn target_version(&self, requested: Option<&str>) -> String {
+        requested
+            .map(str::to_string)
+            .or_else(|| self.pinned_version.clone())
+            .unwrap_or_else(|| DEFAULT_VERSION.to_string())
+    }
+
+    pub fn record(&mut self, action: &str, status: &str, message: impl Into<String>) {
+        self.last_outcome = Some(UpdateOutcome {
+            action: action.to_string(),
+            status: status.to_string(),
+            message: message.into(),
+            at: Utc::now().to_rfc3339(),
+        });
+    }
+}
+
+/// Image tags end up in a compose variable, so keep them to tag-safe characters
+pub fn validate_version(version: &str) -> Result<(), String> {
+    let valid = !version.is_empty()
+        && version.len() <= MAX_VERSION_LENGTH
+        && version
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_'));
+    if valid {
+        Ok(())
+    } else {
+        Err("Version must be 1-128 characters of letters, digits, '.', '-', or '_'".to_string())
+    }
+}
+
+/// Record the image each compose container is currently running
+pub fn snapshot_images(compose_file: &str, version: &str) -> Result<ImageSnapshot, String> {
+    let output = Command::new("docker")
+        .args(["compose", "-f", compose_file, "images", "--format", "json"])
+        .output()
+        .map_err(|e| format!("Failed to execute docker compose images: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Docker compose images failed: {}", stderr));
+    }
+
+    let entries: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout)
+        .map_err(|e| format!("Unexpected docker compose images output: {}", e))?;
+
+    let field = |entry: &serde_json::Value, key: &str| {
+        entry
+            .get(key)
+            .and_then(|value| value.as_str())
+            .unwrap_or_default()
+            .to_string()
+    };
+
+    let images = entries
+        .iter()
+        .map(|entry| ImageRecord {
+            container: field(entry, "ContainerName"),
+            repository: field(entry, "Repository"),
+            tag: field(entry, "Tag"),
+            image_id: field(entry, "ID"),
+        })
+        .filter(|record| !record.repository.is_empty() && !record.image_id.is_empty())
+        .collect();
+
+    Ok(ImageSnapshot {
+        version: version.to_string(),
+        images,
+        recorded_at: Utc::now().to_rfc3339(),
+    })
+}
+
+/// Point each recorded tag back at its old image, then recreate containers without pulling
+pub fn restore_snapshot(compose_file: &str, snapshot: &ImageSnapshot) -> Result<(), String> {
+    info!(version = %snapshot.version, images = snapshot.images.len(), "Rolling back to recorded images");
+
+    for record in &snapshot.images {
+        let reference = format!("{}:{}", record.repository, record.tag);
+        let output = Command::new("docker")
+            .args(["tag", &record.image_id, &reference])
+            .output()
+            .map_err(|e| format!("Failed to execute docker tag: {}", e))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(format!("Failed to restore {}: {}", reference, stderr));
+        }
+    }
+
+    let output = Command::new("docker")
+        .args(["compose", "-f", compose_file, "up", "-d"])
+        .env(VERSION_ENV, &snapshot.version)
+        .output()
+        .map_err(|e| format!("Failed to execute docker compose up: {}", e))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(format!("Docker compose up failed: {}", stderr));
+    }
+
+    Ok(())
+}
+
+/// Wait for the conductor to come back healthy after containers were recreated
+pub async fn wait_until_healthy(state: &AppState) -> Result<(), String> {
+    let deadline = tokio::time::Instant::now() + POST_UPDATE_HEALTH_TIMEOUT;
+
+    loop {
+        let report = health::check_all(state).await;
+        let conductor = report.components.get("conductor");
+        if conductor.is_some_and(|component| component.status == HealthStatus::Healthy) {
+            return Ok(());
+        }
+
+        if tokio::time::Instant::now() >= deadline {
+            let message = conductor
+                .map(|component| component.message.clone())
+                .unwrap_or_else(|| "no conductor status".to_string());
+            return Err(format!(
+                "Conductor not healthy {}s after update: {}",
+                POST_UPDATE_HEALTH_TIMEOUT.as_secs(),
+                message
+            ));
+        }
+
+        tokio::time::sleep(POST_UPDATE_HEALTH_INTERVAL).await;
+    }
+}
@@ -0,0 +1,333 @@
+// ============================================================================
+// Sidecar Zome Calls
+// ============================================================================
+//
+// The sidecar calls a few zome functions on the cells this hub hosts: chat
+// presence for connected agents, membership checks before issuing agent
+// WebSocket sessions, and an agent's own conversation for `/chat/export`.
+// Calls go through the conductor's app interface, signed with a sidecar key
+// that each cell grants access to `GRANTED_FUNCTIONS` only.
+// The key, the capability secret, and the cells already granted persist across
+// restarts so every cell is granted once per version of the function list.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use blake2::{digest::consts::U16, Blake2b, Digest};
+use chrono::Utc;
+use ed25519_dalek::{Signer, SigningKey};
+use rand::RngCore;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_bytes::ByteBuf;
+use sha2::Sha512;
+use std::{collections::HashMap, path::PathBuf};
+
+use crate::conductor::{admin_call, app_call, encode_hash};
+use crate::sessions::decode_agent_key;
+
+const ROLE_NAME: &str = "our_block";
+const GRANT_TAG: &str = "ourblock-sidecar";
+/// Every zome function the sidecar calls, as `(zome, function)`
+const GRANTED_FUNCTIONS: [(&str, &str); 5] = [
+    ("chat", "announce_online"),
+    ("chat", "leave_presence"),
+    ("chat", "get_online_agents"),
+    ("chat", "export_conversation"),
+    ("vouch", "get_membership_status"),
+];
+/// Bump when `GRANTED_FUNCTIONS` changes so every cell is granted again
+const GRANT_VERSION: u32 = 2;
+/// Holochain's 3-byte type prefix for agent public keys (`uhCAk`)
+const AGENT_KEY_PREFIX: [u8; 3] = [0x84, 0x20, 0x24];
+/// App interface tokens only need to outlive one connection
+const TOKEN_EXPIRY_SECS: u64 = 30;
+/// How long a signed zome call stays valid
+const CALL_EXPIRY_SECS: i64 = 60;
+
+#[derive(Serialize, Deserialize)]
+struct CallerState {
+    /// Base64 ed25519 seed the sidecar signs zome calls with
+    signing_key: String,
+    /// Base64 capability secret shared by every grant
+    cap_secret: String,
+    /// `<dna hash>:<agent key>:<grant version>` of cells that hold the grant
+    #[serde(default)]
+    granted_cells: Vec<String>,
+}
+
+/// Where an agent's cell lives on this hub
+pub struct AgentCell {
+    app_id: String,
+    dna_hash: ByteBuf,
+    pub agent: ByteBuf,
+}
+
+impl AgentCell {
+    fn grant_key(&self) -> String {
+        format!("{}:{}:{}", encode_hash(&self.dna_hash), encode_hash(&self.agent), GRANT_VERSION)
+    }
+}
+
+pub struct ZomeCaller {
+    conductor_addr: String,
+    /// Conductor app interface the zome calls are made on
+    app_addr: String,
+    state_path: PathBuf,
+    signing_key: SigningKey,
+    /// The signing key in Holochain's 39-byte agent key form
+    provenance: ByteBuf,
+    cap_secret: ByteBuf,
+    /// Also serializes grants and their persistence
+    state: tokio::sync::Mutex<CallerState>,
+}
+
+impl ZomeCaller {
+    pub async fn from_env(conductor_addr: &str) -> Result<Self, String> {
+        let app_addr = std::env::var("CONDUCTOR_APP_ADDR").unwrap_or_else(|_| "ourblock:8888".to_string());
+        let state_path = PathBuf::from(
+            std::env::var("ZOME_CALL_STATE_FILE").unwrap_or_else(|_| "/app/data/zome-calls.json".to_string()),
+        );
+
+        let state = match tokio::fs::read(&state_path).await {
+            Ok(contents) => serde_json::from_slice::<CallerState>(&contents)
+                .map_err(|e| format!("Invalid zome call state {}: {}", state_path.display(), e))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                let mut seed = [0u8; 32];
+                let mut cap_secret = [0u8; 64];
+                rand::thread_rng().fill_bytes(&mut seed);
+                rand::thread_rng().fill_bytes(&mut cap_secret);
+                let state = CallerState {
+                    signing_key: STANDARD.encode(seed),
+                    cap_secret: STANDARD.encode(cap_secret),
+                    granted_cells: Vec::new(),
+                };
+                crate::persist::write_json_atomic(&state_path, &state).await?;
+                state
+            }
+            Err(e) => return Err(format!("Failed to read {}: {}", state_path.display(), e)),
+        };
+
+        let seed: [u8; 32] = STANDARD
+            .decode(&state.signing_key)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or("Zome call signing key must be 32 bytes of base64")?;
+        let cap_secret = STANDARD
+            .decode(&state.cap_secret)
+            .ok()
+            .filter(|bytes| bytes.len() == 64)
+            .ok_or("Zome call capability secret must be 64 bytes of base64")?;
+        let signing_key = SigningKey::from_bytes(&seed);
+        let provenance = agent_key_bytes(signing_key.verifying_key().as_bytes());
+
+        Ok(Self {
+            conductor_addr: conductor_addr.to_string(),
+            app_addr,
+            state_path,
+            signing_key,
+            provenance: ByteBuf::from(provenance),
+            cap_secret: ByteBuf::from(cap_secret),
+            state: tokio::sync::Mutex::new(state),
+        })
+    }
+
+    /// The agent's cell, if this hub hosts it
+    pub async fn find_cell(&self, agent: &str) -> Result<Option<AgentCell>, String> {
+        let raw = decode_agent_key(agent).ok_or("Invalid agent public key")?;
+        let apps: Vec<AppInfo> = admin_call(
+            &self.conductor_addr,
+            &AdminRequest::ListApps { status_filter: None },
+            "apps_listed",
+        )
+        .await?;
+
+        Ok(apps
+            .into_iter()
+            .filter(|app| app.agent_pub_key.as_slice() == raw.as_slice())
+            .find_map(|app| {
+                let (dna_hash, agent) = app
+                    .cell_info
+                    .get(ROLE_NAME)?
+                    .iter()
+                    .filter(|cell| cell.kind == "provisioned")
+                    .find_map(|cell| cell.value.as_ref()?.cell_id.clone())?;
+                Some(AgentCell {
+                    app_id: app.installed_app_id,
+                    dna_hash,
+                    agent,
+                })
+            }))
+    }
+
+    /// Grant the sidecar key access to `GRANTED_FUNCTIONS` on this cell, once
+    /// per cell and grant version
+    async fn ensure_grant(&self, cell: &AgentCell) -> Result<(), String> {
+        let mut state = self.state.lock().await;
+        let key = cell.grant_key();
+        if state.granted_cells.contains(&key) {
+            return Ok(());
+        }
+
+        let request = AdminRequest::GrantZomeCallCapability {
+            cell_id: (cell.dna_hash.clone(), cell.agent.clone()),
+            cap_grant: ZomeCallCapGrant {
+                tag: GRANT_TAG,
+                access: CapAccess::Assigned {
+                    secret: self.cap_secret.clone(),
+                    assignees: vec![self.provenance.clone()],
+                },
+                functions: GrantedFunctions::Listed(GRANTED_FUNCTIONS.to_vec()),
+            },
+        };
+        admin_call::<_, ()>(&self.conductor_addr, &request, "zome_call_capability_granted").await?;
+
+        state.granted_cells.push(key);
+        crate::persist::write_json_atomic(&self.state_path, &*state).await
+    }
+
+    /// Call one of `GRANTED_FUNCTIONS` on the cell
+    pub async fn call<I: Serialize, O: DeserializeOwned>(
+        &self,
+        cell: &AgentCell,
+        zome_name: &str,
+        fn_name: &str,
+        input: &I,
+    ) -> Result<O, String> {
+        self.ensure_grant(cell).await?;
+
+        let token: AppAuthenticationToken = admin_call(
+            &self.conductor_addr,
+            &AdminRequest::IssueAppAuthenticationToken {
+                installed_app_id: cell.app_id.clone(),
+                expiry_seconds: TOKEN_EXPIRY_SECS,
+                single_use: true,
+            },
+            "app_authentication_token_issued",
+        )
+        .await?;
+
+        let mut nonce = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        let params = ZomeCallParams {
+            provenance: self.provenance.clone(),
+            cell_id: (cell.dna_hash.clone(), cell.agent.clone()),
+            zome_name,
+            fn_name,
+            cap_secret: Some(self.cap_secret.clone()),
+            payload: ByteBuf::from(
+                rmp_serde::to_vec_named(input).map_err(|e| format!("Failed to encode {} input: {}", fn_name, e))?,
+            ),
+            nonce: ByteBuf::from(nonce.to_vec()),
+            expires_at: (Utc::now() + chrono::Duration::seconds(CALL_EXPIRY_SECS)).timestamp_micros(),
+        };
+        let bytes = rmp_serde::to_vec_named(&params).map_err(|e| format!("Failed to encode zome call: {}", e))?;
+        let signature = self.signing_key.sign(&Sha512::digest(&bytes));
+
+        let request = AppRequest::CallZome {
+            bytes: ByteBuf::from(bytes),
+            signature: ByteBuf::from(signature.to_bytes().to_vec()),
+        };
+        let output: ByteBuf = app_call(&self.app_addr, token.token, &request, "zome_called").await?;
+        rmp_serde::from_slice(&output).map_err(|e| format!("Malformed {} output: {}", fn_name, e))
+    }
+}
+
+/// Holochain's 39-byte agent key: type prefix, ed25519 key, and the 4-byte
+/// DHT location (a 16-byte BLAKE2b of the key, XOR-folded)
+fn agent_key_bytes(key: &[u8; 32]) -> Vec<u8> {
+    let hash = Blake2b::<U16>::digest(key);
+    let mut location = [0u8; 4];
+    for (i, byte) in hash.iter().enumerate() {
+        location[i % 4] ^= byte;
+    }
+
+    let mut raw = AGENT_KEY_PREFIX.to_vec();
+    raw.extend_from_slice(key);
+    raw.extend_from_slice(&location);
+    raw
+}
+
+// ============================================================================
+// Conductor API Shapes
+// ============================================================================
+
+#[derive(Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+enum AdminRequest {
+    ListApps {
+        status_filter: Option<()>,
+    },
+    GrantZomeCallCapability {
+        cell_id: (ByteBuf, ByteBuf),
+        cap_grant: ZomeCallCapGrant,
+    },
+    IssueAppAuthenticationToken {
+        installed_app_id: String,
+        expiry_seconds: u64,
+        single_use: bool,
+    },
+}
+
+#[derive(Serialize)]
+struct ZomeCallCapGrant {
+    tag: &'static str,
+    access: CapAccess,
+    functions: GrantedFunctions,
+}
+
+#[derive(Serialize)]
+enum CapAccess {
+    Assigned { secret: ByteBuf, assignees: Vec<ByteBuf> },
+}
+
+#[derive(Serialize)]
+enum GrantedFunctions {
+    Listed(Vec<(&'static str, &'static str)>),
+}
+
+#[derive(Deserialize)]
+struct AppAuthenticationToken {
+    token: Vec<u8>,
+}
+
+#[derive(Deserialize)]
+struct AppInfo {
+    installed_app_id: String,
+    agent_pub_key: ByteBuf,
+    #[serde(default)]
+    cell_info: HashMap<String, Vec<CellInfo>>,
+}
+
+/// `provisioned`, `cloned`, or `stem`; only provisioned cells are used
+#[derive(Deserialize)]
+struct CellInfo {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    value: Option<CellSummary>,
+}
+
+#[derive(Deserialize)]
+struct CellSummary {
+    #[serde(default)]
+    cell_id: Option<(ByteBuf, ByteBuf)>,
+}
+
+#[derive(Serialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+enum AppRequest {
+    CallZome { bytes: ByteBuf, signature: ByteBuf },
+}
+
+/// Serialized, hashed with SHA-512, and signed; the conductor checks the
+/// signature against `provenance` before running the call
+#[derive(Serialize)]
+struct ZomeCallParams<'a> {
+    provenance: ByteBuf,
+    cell_id: (ByteBuf, ByteBuf),
+    zome_name: &'a str,
+    fn_name: &'a str,
+    cap_secret: Option<ByteBuf>,
+    payload: ByteBuf,
+    nonce: ByteBuf,
+    /// Microseconds since the epoch
+    expires_at: i64,
+}
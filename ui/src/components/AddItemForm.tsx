@@ -15,6 +15,8 @@ export function AddItemForm({ onItemAdded, onCancel }: Props) {
   const [title, setTitle] = useState('');
   const [description, setDescription] = useState('');
   const [itemImage, setItemImage] = useState<string>('');
+  // One key per form, so a double-tap or retry can't create the item twice
+  const [idempotencyKey] = useState(() => crypto.randomUUID());
   const [notes, setNotes] = useState('');
   const [consumables, setConsumables] = useState<Array<{ name: string; included: boolean }>>([]);
   const [newConsumable, setNewConsumable] = useState('');
@@ -77,6 +79,7 @@ export function AddItemForm({ onItemAdded, onCancel }: Props) {
           image_hash: imageHash,
           consumables: consumables,
           notes: notes,
+          idempotency_key: idempotencyKey,
         },
       });
 
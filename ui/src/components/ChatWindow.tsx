@@ -3,6 +3,7 @@ import { SignalType } from '@holochain/client';
 import { useHolochain } from '../contexts/HolochainContext';
 import type { ChatSignal, StoredMessage, ChatConversation, SendMessageInput } from '../types';
 import { agentKeyToHex, shortenAgentKey, MAX_MESSAGE_LENGTH } from '../types';
+import { isSignalEnvelope } from '../types/holochain';
 import './ChatWindow.css';
 
 const STORAGE_KEY = 'ourblock_chat_history';
@@ -74,19 +75,19 @@ export function ChatWindow({ recipientKey, onSelectConversation }: Props) {
       const appSignal = 'value' in signal && signal.type === SignalType.App ? signal.value : signal as unknown as any;
       
       // Check if this is a chat signal
-      if (appSignal.zome_name !== 'chat') return;
+      if (appSignal.zome_name !== 'chat' || !isSignalEnvelope(appSignal.payload, 'chat')) return;
       
-      const chatSignal = appSignal.payload as ChatSignal;
+      const chatSignal = appSignal.payload as unknown as ChatSignal;
 
-      if (chatSignal.type === 'Message') {
-        const senderHex = agentKeyToHex(chatSignal.sender);
+      if (chatSignal.event === 'Message') {
+        const senderHex = agentKeyToHex(chatSignal.payload.sender);
         
         const newMessage: StoredMessage = {
-          id: chatSignal.message_id,
+          id: chatSignal.payload.message_id,
           sender: senderHex,
           recipient: myAgentKey || '',
-          content: chatSignal.content,
-          timestamp: chatSignal.timestamp,
+          content: chatSignal.payload.content,
+          timestamp: chatSignal.payload.timestamp,
           isOutgoing: false,
           read: false,
         };
@@ -117,12 +118,17 @@ export function ChatWindow({ recipientKey, onSelectConversation }: Props) {
             },
           };
         });
-      } else if (chatSignal.type === 'Typing') {
-        const senderHex = agentKeyToHex(chatSignal.sender);
+      } else if (chatSignal.event === 'TypingStarted') {
+        const senderHex = agentKeyToHex(chatSignal.payload.sender);
         setTypingIndicator(senderHex);
-        setTimeout(() => setTypingIndicator(null), 3000);
-      } else if (chatSignal.type === 'Read') {
-        const senderHex = agentKeyToHex(chatSignal.sender);
+        // expires_at is re-stamped against the local clock by the zome
+        const ttl = Math.max(chatSignal.payload.expires_at - Date.now(), 0);
+        setTimeout(() => setTypingIndicator(current => (current === senderHex ? null : current)), ttl);
+      } else if (chatSignal.event === 'TypingStopped') {
+        const senderHex = agentKeyToHex(chatSignal.payload.sender);
+        setTypingIndicator(current => (current === senderHex ? null : current));
+      } else if (chatSignal.event === 'Read') {
+        const senderHex = agentKeyToHex(chatSignal.payload.sender);
         setConversations(prev => {
           const existing = prev[senderHex];
           if (!existing) return prev;
@@ -132,11 +138,43 @@ export function ChatWindow({ recipientKey, onSelectConversation }: Props) {
             [senderHex]: {
               ...existing,
               messages: existing.messages.map((m: StoredMessage) =>
-                m.id === chatSignal.message_id ? { ...m, read: true } : m
+                m.id === chatSignal.payload.message_id ? { ...m, read: true } : m
               ),
             },
           };
         });
+      } else if (chatSignal.event === 'Edited') {
+        const senderHex = agentKeyToHex(chatSignal.payload.sender);
+        const { message_id, content, edited_at } = chatSignal.payload;
+        setConversations(prev => {
+          const existing = prev[senderHex];
+          if (!existing) return prev;
+
+          return {
+            ...prev,
+            [senderHex]: {
+              ...existing,
+              messages: existing.messages.map((m: StoredMessage) =>
+                m.id === message_id ? { ...m, content, editedAt: edited_at } : m
+              ),
+            },
+          };
+        });
+      } else if (chatSignal.event === 'Deleted') {
+        const senderHex = agentKeyToHex(chatSignal.payload.sender);
+        const { message_id } = chatSignal.payload;
+        setConversations(prev => {
+          const existing = prev[senderHex];
+          if (!existing) return prev;
+
+          return {
+            ...prev,
+            [senderHex]: {
+              ...existing,
+              messages: existing.messages.filter((m: StoredMessage) => m.id !== message_id),
+            },
+          };
+        });
       }
     });
 
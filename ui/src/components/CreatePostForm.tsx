@@ -18,6 +18,8 @@ export function CreatePostForm({ onPostCreated }: Props) {
   const [isSubmitting, setIsSubmitting] = useState(false);
   const [error, setError] = useState<string | null>(null);
   const [isExpanded, setIsExpanded] = useState(false);
+  // One key per draft, so a double-tap or retry can't create the post twice
+  const [idempotencyKey, setIdempotencyKey] = useState(() => crypto.randomUUID());
 
   // Rate limit: 10 posts per minute
   const rateLimit = useRateLimit(10, 60000);
@@ -66,12 +68,14 @@ export function CreatePostForm({ onPostCreated }: Props) {
         payload: {
           title: titleValidation.data,
           content: contentValidation.data,
+          idempotency_key: idempotencyKey,
         },
       });
 
       // Clear form and collapse
       setTitle('');
       setContent('');
+      setIdempotencyKey(crypto.randomUUID());
       setIsExpanded(false);
 
       // Notify parent
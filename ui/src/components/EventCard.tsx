@@ -25,6 +25,7 @@ export function EventCard({ event, onRSVP, isPast = false, style }: EventCardPro
     month: 'short', 
     day: 'numeric' 
   });
+  const attendeeCount = event.attendeeCount ?? event.attendees.length;
 
   return (
     <article className={`event-card ${isPast ? 'past' : ''}`} style={style}>
@@ -62,7 +63,7 @@ export function EventCard({ event, onRSVP, isPast = false, style }: EventCardPro
           <div className="event-attendees">
             <span className="attendees-icon">👥</span>
             <span className="attendees-count">
-              {event.attendees.length} {event.attendees.length === 1 ? 'person' : 'people'} attending
+              {attendeeCount} {attendeeCount === 1 ? 'person' : 'people'} attending
             </span>
           </div>
           
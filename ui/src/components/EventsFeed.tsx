@@ -6,6 +6,7 @@ import { EventOutput, CreateEventInput } from '../types/events';
 import { useProfiles } from '../hooks/useProfile';
 import { AppSignal } from '@holochain/client';
 import { logger } from '../utils/logger';
+import { isSignalEnvelope } from '../types/holochain';
 import './EventsFeed.css';
 
 // Helper to convert Uint8Array to hex string (browser-compatible)
@@ -34,6 +35,7 @@ export interface Event {
   createdAt: number;
   hostKey?: Uint8Array; // For profile lookup
   attendeeKeys?: Uint8Array[]; // For profile lookup
+  attendeeCount?: number; // Includes attendees hidden by the host's guest list setting
 }
 
 export function EventsFeed() {
@@ -57,17 +59,12 @@ export function EventsFeed() {
     if (!isConnected) return;
 
     const unsubscribe = onSignal((signal: AppSignal) => {
-      if (signal.zome_name === 'events') {
-        const payload = signal.payload;
-        
-        if (payload && typeof payload === 'object' && 'type' in payload) {
-          const signalType = (payload as { type: string }).type;
-          
-          if (signalType === 'NewEvent' || signalType === 'EventRSVP') {
-            logger.debug('Event update signal received, refreshing');
-            loadEvents();
-          }
-        }
+      const envelope = signal.payload;
+      if (!isSignalEnvelope(envelope, 'events')) return;
+
+      if (envelope.event === 'NewEvent' || envelope.event === 'EventRSVP') {
+        logger.debug('Event update signal received, refreshing');
+        loadEvents();
       }
     });
 
@@ -111,6 +108,7 @@ export function EventsFeed() {
           createdAt: eventOutput.event.created_at * 1000,
           hostKey: eventOutput.event.host,
           attendeeKeys: eventOutput.event.attendees,
+          attendeeCount: eventOutput.attendee_count,
         };
       });
 
@@ -166,6 +164,7 @@ export function EventsFeed() {
         createdAt: result.event.created_at * 1000,
         hostKey: result.event.host,
         attendeeKeys: result.event.attendees,
+        attendeeCount: result.attendee_count,
       };
 
       setEvents([newEvent, ...events]);
@@ -209,6 +208,7 @@ export function EventsFeed() {
         createdAt: result.event.created_at * 1000,
         hostKey: result.event.host,
         attendeeKeys: result.event.attendees,
+        attendeeCount: result.attendee_count,
       };
 
       setEvents(events.map(e => e.id === eventId ? updatedEvent : e));
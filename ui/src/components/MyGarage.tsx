@@ -131,7 +131,9 @@ export function MyGarage() {
     );
   }
 
-  const activeBorrows = myTransactions.filter(t => t.status === 'Active');
+  const activeBorrows = myTransactions.filter(
+    t => t.status === 'Active' || t.status === 'AwaitingDropoff'
+  );
 
   return (
     <div className="my-garage">
@@ -122,7 +122,8 @@ export function PostCard({ post }: Props) {
           zome_name: 'feed',
           fn_name: 'add_reaction',
           payload: {
-            post_hash: post.action_hash,
+            target: post.action_hash,
+            target_kind: 'Post',
             reaction_type: 'like',
           },
         });
@@ -7,6 +7,7 @@ import { queryKeys } from '../utils/queryClient';
 import type { PostOutput } from '../types';
 import { AppSignal } from '@holochain/client';
 import { logger } from '../utils/logger';
+import { isSignalEnvelope } from '../types/holochain';
 import './PostFeed.css';
 
 export function PostFeed() {
@@ -44,18 +45,13 @@ export function PostFeed() {
 
     const unsubscribe = onSignal((signal: AppSignal) => {
       // Check if this is a feed signal
-      if (signal.zome_name === 'feed') {
-        const payload = signal.payload;
-        
-        if (payload && typeof payload === 'object' && 'type' in payload) {
-          const signalType = (payload as { type: string }).type;
-          
-          if (signalType === 'NewPost' || signalType === 'NewReaction' || signalType === 'NewComment') {
-            logger.debug('Feed signal received, invalidating cache', { signalType });
-            // Invalidate the query cache to trigger a background refetch
-            queryClient.invalidateQueries({ queryKey: queryKeys.posts.all });
-          }
-        }
+      const envelope = signal.payload;
+      if (!isSignalEnvelope(envelope, 'feed')) return;
+
+      if (envelope.event === 'NewPost' || envelope.event === 'NewReaction' || envelope.event === 'NewComment') {
+        logger.debug('Feed signal received, invalidating cache', { signalType: envelope.event });
+        // Invalidate the query cache to trigger a background refetch
+        queryClient.invalidateQueries({ queryKey: queryKeys.posts.all });
       }
     });
 
@@ -7,6 +7,8 @@ export interface Profile {
   bio: string | null;
   avatar_url: string | null;
   location_metadata: string | null;
+  pronouns?: string | null;
+  display_name_variants?: { script: string; name: string }[];
 }
 
 interface Props {
@@ -54,6 +56,9 @@ export function ProfileEditor({ profile, onClose, onSave }: Props) {
           bio: bio.trim() || null,
           avatar_url: avatarUrl.trim() || null,
           location_metadata: locationMetadata.trim() || null,
+          // Not edited here yet; sent back so saving doesn't clear them
+          pronouns: profile.pronouns ?? null,
+          display_name_variants: profile.display_name_variants ?? [],
         },
       });
 
@@ -62,6 +67,8 @@ export function ProfileEditor({ profile, onClose, onSave }: Props) {
         bio: bio.trim() || null,
         avatar_url: avatarUrl.trim() || null,
         location_metadata: locationMetadata.trim() || null,
+        pronouns: profile.pronouns ?? null,
+        display_name_variants: profile.display_name_variants ?? [],
       };
 
       onSave(updatedProfile);
@@ -7,6 +7,7 @@ import { SpaceOutput, ReservationOutput, CreateSpaceInput, CreateReservationInpu
 import { useProfiles } from '../hooks/useProfile';
 import { AppSignal } from '@holochain/client';
 import { logger } from '../utils/logger';
+import { isSignalEnvelope } from '../types/holochain';
 import './SharedSpaces.css';
 
 // Helper to convert Uint8Array to hex string (browser-compatible)
@@ -68,17 +69,12 @@ export function SharedSpaces() {
     if (!isConnected) return;
 
     const unsubscribe = onSignal((signal: AppSignal) => {
-      if (signal.zome_name === 'spaces') {
-        const payload = signal.payload;
-        
-        if (payload && typeof payload === 'object' && 'type' in payload) {
-          const signalType = (payload as { type: string }).type;
-          
-          if (signalType === 'NewSpace' || signalType === 'NewReservation') {
-            logger.debug('Spaces update signal received, refreshing');
-            loadSpacesAndReservations();
-          }
-        }
+      const envelope = signal.payload;
+      if (!isSignalEnvelope(envelope, 'spaces')) return;
+
+      if (envelope.event === 'NewSpace' || envelope.event === 'NewReservation') {
+        logger.debug('Spaces update signal received, refreshing');
+        loadSpacesAndReservations();
       }
     });
 
@@ -124,19 +120,22 @@ export function SharedSpaces() {
           payload: spaceOutput.action_hash,
         });
 
-        const convertedReservations = reservationsResult.map(resOutput => ({
-          id: uint8ArrayToHex(resOutput.action_hash),
-          spaceId: uint8ArrayToHex(resOutput.reservation.space_hash),
-          userName: (() => {
-            const reserverHex = uint8ArrayToHex(resOutput.reservation.reserver);
-            const reserverProfile = profiles.get(reserverHex);
-            return reserverProfile?.nickname || `Neighbor #${reserverHex.slice(0, 8).toUpperCase()}`;
-          })(),
-          startTime: new Date(resOutput.reservation.start_time * 1000).toISOString(),
-          endTime: new Date(resOutput.reservation.end_time * 1000).toISOString(),
-          notes: resOutput.reservation.purpose || undefined,
-          reserverKey: resOutput.reservation.reserver,
-        }));
+        // Reservations awaiting approval don't hold their slot yet
+        const convertedReservations = reservationsResult
+          .filter(resOutput => !resOutput.superseded && !resOutput.awaiting_approval)
+          .map(resOutput => ({
+            id: uint8ArrayToHex(resOutput.action_hash),
+            spaceId: uint8ArrayToHex(resOutput.reservation.space_hash),
+            userName: (() => {
+              const reserverHex = uint8ArrayToHex(resOutput.reservation.reserver);
+              const reserverProfile = profiles.get(reserverHex);
+              return reserverProfile?.nickname || `Neighbor #${reserverHex.slice(0, 8).toUpperCase()}`;
+            })(),
+            startTime: new Date(resOutput.reservation.start_time * 1000).toISOString(),
+            endTime: new Date(resOutput.reservation.end_time * 1000).toISOString(),
+            notes: resOutput.reservation.purpose || undefined,
+            reserverKey: resOutput.reservation.reserver,
+          }));
 
         allReservations.push(...convertedReservations);
       }
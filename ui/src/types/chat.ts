@@ -8,20 +8,70 @@ export interface ChatMessage {
   content: string;
   timestamp: number; // milliseconds since epoch
   message_id: string;
+  context: ChatContext | null; // Set when the conversation is about a request, item, or event
+  recipient: Uint8Array | null; // AgentPubKey the message is for
 }
 
-// Signal types
+// The entry a conversation was started about
+export type ChatSubject =
+  | { type: 'Request'; request_hash: Uint8Array }
+  | { type: 'Item'; item_hash: Uint8Array }
+  | { type: 'Event'; event_hash: Uint8Array };
+
+export interface ChatContext {
+  subject: ChatSubject;
+  title: string; // The subject's title when the conversation started
+}
+
+// Sender's profile status, included with Online presence
+export interface PresenceStatus {
+  emoji: string | null;
+  text: string;
+  expires_at: number | null; // microseconds since epoch
+}
+
+// Signal types (the `event` and `payload` of a chat SignalEnvelope)
 export type ChatSignal =
-  | { type: 'Message'; sender: Uint8Array; content: string; timestamp: number; message_id: string }
-  | { type: 'Typing'; sender: Uint8Array }
-  | { type: 'Read'; sender: Uint8Array; message_id: string }
-  | { type: 'Online'; agent: Uint8Array }
-  | { type: 'Offline'; agent: Uint8Array };
+  | { event: 'Message'; payload: ChatMessage }
+  | { event: 'TypingStarted'; payload: { sender: Uint8Array; timestamp: number; expires_at: number } }
+  | { event: 'TypingStopped'; payload: { sender: Uint8Array; timestamp: number } }
+  | { event: 'Read'; payload: { sender: Uint8Array; message_id: string } }
+  | { event: 'Online'; payload: { agent: Uint8Array; status: PresenceStatus | null } }
+  | { event: 'Offline'; payload: { agent: Uint8Array } }
+  | { event: 'Edited'; payload: { sender: Uint8Array; message_id: string; content: string; timestamp: number; edited_at: number } }
+  | { event: 'Deleted'; payload: { sender: Uint8Array; message_id: string } }
+  | { event: 'VoiceMemo'; payload: ChatVoiceMemo }
+  // Local only: this sender went over the rate limit and their messages are being dropped
+  | { event: 'Flooding'; payload: { sender: Uint8Array; window_ms: number } };
+
+// A recorded voice message; fetch the audio from file_storage by metadata_hash
+export interface ChatVoiceMemo {
+  sender: Uint8Array; // AgentPubKey
+  metadata_hash: Uint8Array; // ActionHash
+  file_type: string; // audio/webm, audio/ogg, or audio/mpeg
+  size: number;
+  duration_ms: number;
+  timestamp: number;
+  message_id: string;
+}
 
 // Input/Output types
+export interface SendVoiceMemoInput {
+  recipient: Uint8Array;
+  audio_bytes: Uint8Array; // As recorded by MediaRecorder; the format is detected from the bytes
+  duration_ms: number; // 0.5s to 5 minutes
+}
+
 export interface SendMessageInput {
   recipient: Uint8Array;
   message: string;
+  context?: ChatContext | null; // Pass the thread's context to keep replies attached
+}
+
+export interface StartChatAboutInput {
+  subject: ChatSubject;
+  recipient?: Uint8Array | null; // Defaults to the subject's author, owner, or host
+  message: string;
 }
 
 export interface SendMessageOutput {
@@ -30,8 +80,21 @@ export interface SendMessageOutput {
   success: boolean;
 }
 
+export interface StartChatAboutOutput {
+  peer: Uint8Array; // AgentPubKey
+  context: ChatContext;
+  message: SendMessageOutput;
+}
+
 export interface SendTypingInput {
   recipient: Uint8Array;
+  last_sent_at: number | null;
+}
+
+export interface SendTypingOutput {
+  sent: boolean;
+  last_sent_at: number;
+  expires_at: number;
 }
 
 export interface SendReadReceiptInput {
@@ -48,6 +111,7 @@ export interface StoredMessage {
   timestamp: number;
   isOutgoing: boolean;
   read: boolean;
+  editedAt?: number; // set when the sender edits the message
 }
 
 export interface ChatConversation {
@@ -57,8 +121,57 @@ export interface ChatConversation {
   unreadCount: number;
 }
 
+// From get_read_state; persisted on this device so unread badges survive restarts
+export interface ReadState {
+  peer: Uint8Array; // AgentPubKey
+  my_last_read: string | null; // message_id
+  peer_last_read: string | null; // message_id from the peer's last read receipt
+  unread_count: number;
+}
+
+// One row of get_conversations, most recently active first
+export interface ConversationSummary {
+  peer: Uint8Array; // AgentPubKey
+  last_message: ChatMessage;
+  read_state: ReadState;
+}
+
+export interface EditMessageInput {
+  message_id: string;
+  new_content: string;
+}
+
+export interface DeleteMessageInput {
+  message_id: string;
+}
+
+// Another device (e.g. the hub kiosk) that receives our messages too
+export interface RegisterDeviceInput {
+  device: Uint8Array; // AgentPubKey of the other device
+  label: string;
+  signature: Uint8Array; // From sign_device_registration, called on the device
+}
+
+export interface DeviceInfo {
+  device: Uint8Array;
+  label: string;
+  registered_at: number; // microseconds since epoch
+}
+
+// Output of export_conversation; the sidecar's GET /chat/export?peer= downloads it as a file
+export interface ConversationExport {
+  me: Uint8Array;
+  peer: Uint8Array;
+  exported_at: number; // microseconds since epoch
+  messages: ChatMessage[]; // oldest first
+  transcript: string; // plain text, one message per line
+}
+
 // Validation constants
 export const MAX_MESSAGE_LENGTH = 5000;
+export const MAX_CONTEXT_TITLE_LENGTH = 200;
+export const MAX_MESSAGES_PER_WINDOW = 20; // per recipient
+export const RATE_LIMIT_WINDOW_MS = 60_000;
 
 // Helper to convert Uint8Array to hex string
 export function agentKeyToHex(key: Uint8Array): string {
@@ -1,4 +1,15 @@
 import { ActionHash, AgentPubKey, EntryHash } from "@holochain/client";
+import type { Location } from "./location";
+
+/**
+ * Who the app shows an event's attendees to; max_attendees applies either way.
+ * Only filters what the zome returns: the guest list is still in the public
+ * Event entry, so it isn't private from anyone reading the DHT directly.
+ */
+export type GuestListVisibility = "Public" | "AttendeesOnly" | "HostOnly";
+
+/** Who can see and RSVP to an event */
+export type EventVisibility = "Neighborhood" | "InviteOnly";
 
 export interface Event {
   title: string;
@@ -9,6 +20,13 @@ export interface Event {
   attendees: AgentPubKey[];
   max_attendees: number | null;
   created_at: number;
+  place: Location | null; // Approximate position for nearby queries
+  category: string | null;
+  tags: string[]; // lowercased
+  guest_list_visibility: GuestListVisibility;
+  duration_minutes: number | null;
+  visibility: EventVisibility;
+  invitees: AgentPubKey[]; // Only for invite-only events
 }
 
 export interface CreateEventInput {
@@ -17,11 +35,131 @@ export interface CreateEventInput {
   location: string;
   event_date: number;
   max_attendees: number | null;
+  place?: Location | null;
+  category?: string | null;
+  tags?: string[];
+  guest_list_visibility?: GuestListVisibility;
+  duration_minutes?: number | null;
+  visibility?: EventVisibility;
+  invitees?: AgentPubKey[];
+  /** Reusing a key returns the original record instead of creating a duplicate */
+  idempotency_key?: string;
 }
 
 // Matches backend EventOutput
 export interface EventOutput {
+  /** `attendees` only lists the caller and host when the guest list is hidden */
   event: Event;
   action_hash: ActionHash;
   entry_hash: EntryHash;
+  attendee_count: number;
+}
+
+// Matches backend EventAttendees
+export interface EventAttendees {
+  event_hash: ActionHash;
+  host: AgentPubKey;
+  /** False when the guest list is hidden from the caller */
+  visible: boolean;
+  attendees: AgentPubKey[];
+  attendee_count: number;
+  max_attendees: number | null;
+}
+
+export interface UpcomingEventsInput {
+  /** Defaults to now */
+  from?: number | null;
+  /** Defaults to 13 weeks after `from`; at most 53 weeks */
+  to?: number | null;
+  /** Zero-based */
+  page?: number | null;
+  page_size?: number | null;
+  category?: string | null;
+  tag?: string | null;
+  /** Read from the network even when events are held locally */
+  prefer_network?: boolean;
+}
+
+export interface UpcomingEventsPage {
+  events: EventOutput[];
+  page: number;
+  has_more: boolean;
+}
+
+/** Where a page of past events stopped; the hash orders events on the same date */
+export interface PastEventsCursor {
+  event_date: number;
+  action_hash?: Uint8Array | null;
+}
+
+export interface PastEventsInput {
+  /** Cursor from the previous page; defaults to now */
+  before?: PastEventsCursor | null;
+  limit?: number | null;
+  category?: string | null;
+  tag?: string | null;
+  /** Read from the network even when events are held locally */
+  prefer_network?: boolean;
+}
+
+export interface PastEventsPage {
+  events: EventOutput[];
+  next_before: PastEventsCursor | null;
+}
+
+export interface EventTemplate {
+  title: string;
+  description: string;
+  location: string;
+  duration_minutes: number | null;
+  max_attendees: number | null;
+  category: string | null;
+  tags: string[];
+  creator: AgentPubKey;
+  created_at: number;
+}
+
+export interface EventTemplateInput {
+  title: string;
+  description: string;
+  location: string;
+  duration_minutes: number | null;
+  max_attendees: number | null;
+  category?: string | null;
+  tags?: string[];
+}
+
+export interface UpdateEventTemplateInput {
+  template_hash: ActionHash;
+  template: EventTemplateInput;
+}
+
+export interface CreateEventFromTemplateInput {
+  template_hash: ActionHash;
+  event_date: number;
+  idempotency_key?: string;
+}
+
+// Matches backend EventTemplateOutput
+export interface EventTemplateOutput {
+  /** Latest revision */
+  template: EventTemplate;
+  /** Stays the same across edits */
+  template_hash: ActionHash;
+  revision_hash: ActionHash;
+}
+
+/** Which messages about an event reach me */
+export type NotificationLevel = "All" | "MentionsOnly" | "None";
+
+export interface EventMessageInput {
+  event_hash: ActionHash;
+  message: string;
+  /** Attendees who should hear about it even with mentions-only notifications */
+  mentions?: AgentPubKey[];
+}
+
+export interface SetEventNotificationsInput {
+  event_hash: ActionHash;
+  level: NotificationLevel;
 }
@@ -2,34 +2,116 @@
  * TypeScript types matching the Feed zome types
  */
 
+import type { Urgency } from './helpinghands';
+import type { Location } from './location';
+
 export interface Post {
   title: string;
   content: string;
   author: Uint8Array; // AgentPubKey
   created_at: number; // Timestamp
+  channel: string | null;
+  links: LinkPreview[];
+  tags: string[]; // Normalized hashtags, without the '#'
+}
+
+// Preview card for a shared URL; fetched by the author's client
+export interface LinkPreview {
+  url: string;
+  title: string;
+  description: string | null;
+  image_hash: Uint8Array | null; // EntryHash in file_storage
+}
+
+export interface AttachLinkPreviewInput {
+  original_post_hash: Uint8Array;
+  preview: LinkPreview;
 }
 
 export interface CreatePostInput {
   title: string;
   content: string;
+  channel?: string | null;
+  links?: LinkPreview[];
+  /** Tags to add besides the #tags written in the content */
+  tags?: string[];
+  /** Reusing a key returns the original record instead of creating a duplicate */
+  idempotency_key?: string;
+}
+
+export interface UpdatePostInput {
+  original_post_hash: Uint8Array;
+  title: string;
+  content: string;
+  tags?: string[] | null; // Replaces tags given outside the content; omit to keep them
 }
 
 export interface PostOutput {
   post: Post;
-  action_hash: Uint8Array;
+  action_hash: Uint8Array; // The original post; reactions and comments attach here
   entry_hash: Uint8Array;
+  revision_hash: Uint8Array; // The revision `post` was read from
+  edited_at: number | null; // Timestamp
+  comment_count: number | null; // Cached count; set on feed pages only
+}
+
+// Where a page stopped; the hash orders links made in the same microsecond
+export interface PageCursor {
+  timestamp: number;
+  action_hash: Uint8Array;
+}
+
+export interface PostsPageInput {
+  before: PageCursor | null; // Cursor from the previous page's next_before
+  limit: number | null;
+  prefer_network?: boolean; // Skip the local read, e.g. on pull-to-refresh
+}
+
+// Optional argument to get_all_posts, get_all_items, get_all_events, and
+// get_all_spaces. Lists read locally and fall back to the network when empty.
+export interface ListOptions {
+  prefer_network?: boolean;
+}
+
+export interface PostsByTagInput {
+  tag: string; // With or without the leading '#'
+  page: PostsPageInput;
+}
+
+export interface PostsPage {
+  posts: PostOutput[];
+  next_before: PageCursor | null;
+}
+
+// Feed preferences (private to the caller)
+export interface FeedPreferences {
+  muted_agents: Uint8Array[]; // AgentPubKey
+  muted_channels: string[];
+  keyword_filters: string[];
+  followed_tags: string[]; // Changed through follow_tag / unfollow_tag
+}
+
+export type SetFeedPreferencesInput = Omit<FeedPreferences, 'followed_tags'>;
+
+// Read marker: the newest post timestamp the agent has seen
+export interface ReadMarker {
+  up_to: number; // Timestamp
 }
 
 // Reaction
+export type ReactionTarget = 'Post' | 'Comment';
+
 export interface Reaction {
-  post_hash: Uint8Array; // ActionHash
+  target: Uint8Array; // ActionHash of the post or comment
+  target_kind: ReactionTarget;
   author: Uint8Array; // AgentPubKey
   reaction_type: string;
   created_at: number; // Timestamp
 }
 
 export interface CreateReactionInput {
-  post_hash: Uint8Array;
+  target: Uint8Array;
+  target_kind?: ReactionTarget; // Defaults to 'Post'
   reaction_type: string;
 }
 
@@ -58,8 +140,149 @@ export interface CommentOutput {
   entry_hash: Uint8Array;
 }
 
+// Batched post details from get_posts_with_details
+export interface ReactionSummary {
+  reaction_type: string;
+  count: number;
+  my_reaction: Uint8Array | null; // The caller's reaction of this type
+}
+
+export interface CommentWithReactions {
+  comment: CommentOutput;
+  reactions: ReactionSummary[];
+}
+
+export interface PostWithDetails {
+  post: PostOutput;
+  comments: CommentWithReactions[]; // Oldest first
+  reactions: ReactionSummary[];
+}
+
+// Lost & found: items tracked until they're back with their owner
+export type LostFoundKind = { type: 'Lost' } | { type: 'Found' };
+
+// Open -> Found (lost items only) -> Claimed; never moves back
+export type LostFoundStatus = { type: 'Open' } | { type: 'Found' } | { type: 'Claimed' };
+
+export interface LostFoundPost {
+  kind: LostFoundKind;
+  item_description: string;
+  last_seen: Location | null; // Where it was lost, or where it was found
+  last_seen_at: number | null; // Timestamp
+  details: string | null;
+  author: Uint8Array; // AgentPubKey
+  created_at: number; // Timestamp
+  status: LostFoundStatus;
+  resolved_at: number | null; // Timestamp
+}
+
+export interface CreateLostFoundInput {
+  kind: LostFoundKind;
+  item_description: string;
+  last_seen: Location | null;
+  last_seen_at: number | null;
+  details: string | null;
+}
+
+export interface LostFoundOutput {
+  post: LostFoundPost;
+  action_hash: Uint8Array; // Original post; pass to mark_found / mark_claimed
+  revision_hash: Uint8Array;
+}
+
+// Reports: neighbors flag posts, anchors review them
+export interface Report {
+  post_hash: Uint8Array; // ActionHash
+  reporter: Uint8Array; // AgentPubKey
+  reason: string;
+  created_at: number; // Timestamp
+}
+
+export interface ReportPostInput {
+  post_hash: Uint8Array;
+  reason: string;
+}
+
+export interface ReportOutput {
+  report: Report;
+  action_hash: Uint8Array;
+}
+
+export type ResolutionAction =
+  | { type: 'Dismiss' }
+  | { type: 'Hide' } // Left out of everyone's feed
+  | { type: 'WarnAuthor' }; // Author gets a ModerationWarning signal
+
+export interface ReportResolution {
+  report: Uint8Array; // ActionHash
+  action: ResolutionAction;
+  note: string | null;
+  resolver_anchor: Uint8Array; // TrustedAnchor ActionHash
+  resolved_at: number; // Timestamp
+}
+
+export interface ResolveReportInput {
+  report_hash: Uint8Array;
+  action: ResolutionAction;
+  note: string | null;
+}
+
+export interface ResolutionOutput {
+  resolution: ReportResolution;
+  action_hash: Uint8Array;
+}
+
 // Validation constants matching the integrity zome
 export const MIN_TITLE_LENGTH = 5;
 export const MAX_TITLE_LENGTH = 100;
 export const MAX_CONTENT_LENGTH = 5000;
 export const MAX_COMMENT_LENGTH = 1000;
+export const MAX_TAGS = 10;
+export const MAX_TAG_LENGTH = 32;
+export const MAX_FOLLOWED_TAGS = 50;
+export const MAX_ITEM_DESCRIPTION_LENGTH = 500;
+export const MAX_LOST_FOUND_DETAILS_LENGTH = 1000;
+export const MAX_REPORT_REASON_LENGTH = 500;
+export const MAX_RESOLUTION_NOTE_LENGTH = 500;
+
+// Activity digest: what's new across the block since a timestamp
+export interface DigestEntry {
+  action_hash: Uint8Array;
+  title: string;
+  author: Uint8Array; // AgentPubKey
+  at: number; // Timestamp posted, or last escalated for requests
+  starts_at: number | null; // Event start time
+  urgency: Urgency | null; // Requests only
+}
+
+export interface ActivityDigest {
+  since: number;
+  generated_at: number;
+  posts: DigestEntry[];
+  items: DigestEntry[];
+  events: DigestEntry[];
+  open_requests: DigestEntry[];
+}
+
+// Push digest: off until the agent picks a frequency
+export type DigestFrequency = { type: 'Off' } | { type: 'Daily' } | { type: 'Weekly' };
+
+export interface DigestPreferences {
+  frequency: DigestFrequency;
+}
+
+export interface DigestPost {
+  entry: DigestEntry;
+  reaction_count: number;
+  comment_count: number;
+}
+
+export interface Digest {
+  since: number; // Timestamp of the last delivery
+  generated_at: number;
+  frequency: DigestFrequency;
+  due: boolean; // Deliver only when set
+  top_posts: DigestPost[];
+  events: DigestEntry[]; // Soonest first
+  urgent_requests: DigestEntry[]; // High and Emergency, most urgent first
+}
@@ -8,8 +8,18 @@ export interface FileMetadata {
   chunks: ActionHash[];
   author: AgentPubKey;
   created_at: number;
+  /** Hex SHA-256 per chunk; empty for older files */
+  chunk_hashes: string[];
+  /** Fetches by anyone but the author are recorded as FileAccess entries */
+  log_access: boolean;
+  /** Hidden from listings after this and deleted by the author's next cleanup */
+  expires_at: number | null;
+  /** "Deflate" if any chunk is stored compressed; reads return the original bytes */
+  compression: Compression;
 }
 
+export type Compression = "None" | "Deflate";
+
 export interface FileMetadataOutput {
   metadata_hash: ActionHash;
   metadata: FileMetadata;
@@ -19,9 +29,90 @@ export interface UploadFileInput {
   name: string;
   file_type: string;
   data: Uint8Array;
+  log_access?: boolean;
+  expires_at?: number | null;
+  /** Omit to compress large text-like files only */
+  compress?: boolean | null;
+}
+
+export interface GetFileChunkInput {
+  metadata_hash: ActionHash;
+  index: number;
+}
+
+export interface FileChunkOutput {
+  index: number;
+  total_chunks: number;
+  data: Uint8Array;
+}
+
+export interface FileVerification {
+  metadata_hash: ActionHash;
+  total_chunks: number;
+  corrupted_chunks: number[];
+  missing_chunks: number[];
+  /** null when chunks are missing */
+  file_hash_matches: boolean | null;
+  ok: boolean;
 }
 
 export interface FileOutput {
   metadata: FileMetadata;
   data: Uint8Array;
 }
+
+/** Accepted MIME types ("image/*" allows a family) and the size limit in bytes */
+export interface UploadPolicy {
+  allowed_types: string[];
+  max_file_size: number;
+}
+
+/** One fetch of an access-logged file, from `get_access_log` (author only) */
+export interface FileAccess {
+  file: ActionHash;
+  accessor: AgentPubKey;
+  accessed_at: number;
+}
+
+export interface RenewFileInput {
+  metadata_hash: ActionHash;
+  /** null keeps the file until it is deleted */
+  expires_at: number | null;
+}
+
+/** Result of `cleanup_expired_files` */
+export interface ExpiryCleanup {
+  deleted: number;
+  expiring_soon: FileMetadataOutput[];
+}
+
+export type FileStorageSignal = {
+  event: "FileExpiringSoon";
+  payload: { metadata_hash: ActionHash; name: string; expires_at: number };
+};
+
+/** Files and bytes under one top-level MIME type, e.g. "image" */
+export interface CategoryUsage {
+  category: string;
+  files: number;
+  bytes: number;
+}
+
+export interface UploaderUsage {
+  agent: AgentPubKey;
+  files: number;
+  bytes: number;
+}
+
+/** Result of `get_storage_stats` */
+export interface StorageStats {
+  /** Includes expired files awaiting cleanup */
+  total_files: number;
+  total_bytes: number;
+  by_category: CategoryUsage[];
+  top_uploaders: UploaderUsage[];
+  expired_files: number;
+  /** Lower bound: only chunks of deleted files are found */
+  orphaned_chunks: number;
+  my_abandoned_upload_chunks: number;
+}
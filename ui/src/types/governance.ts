@@ -0,0 +1,90 @@
+import { ActionHash, AgentPubKey, Signature } from "@holochain/client";
+
+// Binding neighborhood polls: verified members vote once each, and two
+// trusted anchors certify the closing tally
+
+export interface Poll {
+  title: string;
+  description: string;
+  options: string[];
+  created_at: number;
+  /** Votes are accepted until this time; results only after it */
+  closes_at: number;
+}
+
+export interface CreatePollInput {
+  title: string;
+  description: string;
+  options: string[];
+  closes_at: number;
+}
+
+export interface PollOutput {
+  poll: Poll;
+  action_hash: ActionHash;
+  proposer: AgentPubKey;
+}
+
+export interface PollSummary {
+  poll: PollOutput;
+  /** Votes per option seen so far; unofficial until certified */
+  tallies: number[];
+  my_vote: number | null;
+  closed: boolean;
+  result: ActionHash | null;
+}
+
+export interface Vote {
+  poll: ActionHash;
+  option: number;
+}
+
+export interface CastVoteInput {
+  poll_hash: ActionHash;
+  option: number;
+}
+
+export interface VoteOutput {
+  vote: Vote;
+  action_hash: ActionHash;
+  voter: AgentPubKey;
+}
+
+export interface AnchorCertification {
+  anchor: AgentPubKey;
+  anchor_record: ActionHash;
+  signature: Signature;
+}
+
+export interface PollResult {
+  poll: ActionHash;
+  tallies: number[];
+  votes: ActionHash[];
+  certifications: AnchorCertification[];
+  certified_at: number;
+}
+
+export interface CertifyPollInput {
+  poll_hash: ActionHash;
+  /** The second anchor asked to count and sign */
+  cosigner: AgentPubKey;
+}
+
+// Matches backend Decision
+export interface Decision {
+  poll: PollOutput;
+  result: PollResult;
+  result_hash: ActionHash;
+  /** More than one on a tie; empty if nobody voted */
+  winning_options: string[];
+}
+
+export type GovernanceSignal =
+  | { event: "NewPoll"; payload: { poll_hash: ActionHash; poll: Poll } }
+  | { event: "DecisionCertified"; payload: { poll_hash: ActionHash; result_hash: ActionHash } };
+
+// Constants matching the integrity zome
+export const MIN_POLL_OPTIONS = 2;
+export const MAX_POLL_OPTIONS = 10;
+export const MAX_POLL_DURATION_SECS = 60 * 24 * 60 * 60;
+export const REQUIRED_CERTIFICATIONS = 2;
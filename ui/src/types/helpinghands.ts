@@ -2,6 +2,8 @@
  * TypeScript types matching the Helping Hands zome types
  */
 
+import type { Location } from './location';
+
 // Request Category
 export type RequestCategory =
   | { type: 'Grocery' }
@@ -30,6 +32,7 @@ export interface Request {
   author: Uint8Array; // AgentPubKey
   created_at: number; // Timestamp
   is_fulfilled: boolean;
+  place?: Location; // Omitted when not set
 }
 
 export interface CreateRequestInput {
@@ -37,6 +40,40 @@ export interface CreateRequestInput {
   category: RequestCategory;
   urgency: Urgency;
   description: string;
+  place?: Location | null;
+  /** Reusing a key returns the original record instead of creating a duplicate */
+  idempotency_key?: string;
+}
+
+// Request templates for common needs
+export interface TemplatePrompt {
+  key: string;
+  label: string;
+}
+
+export interface RequestTemplate {
+  id: string; // e.g. 'airport_ride'
+  name: string;
+  title: string;
+  category: RequestCategory;
+  urgency: Urgency;
+  description: string; // Opening line; answers are added below it
+  prompts: TemplatePrompt[];
+}
+
+// Anything left out keeps the template's value
+export interface TemplateOverrides {
+  title?: string | null;
+  urgency?: Urgency | null;
+  answers?: Record<string, string>; // By prompt key
+  notes?: string | null;
+  place?: Location | null;
+  idempotency_key?: string;
+}
+
+export interface CreateFromTemplateInput {
+  template_id: string;
+  overrides?: TemplateOverrides;
 }
 
 export interface RequestOutput {
@@ -44,6 +81,49 @@ export interface RequestOutput {
   action_hash: Uint8Array;
   entry_hash: Uint8Array;
   comment_count: number;
+  bumped_at: number | null; // Timestamp of the latest escalation
+  status: RequestStatus;
+  acceptance: OfferAcceptance | null;
+}
+
+export type RequestStatus =
+  | { type: 'Open' }
+  | { type: 'InProgress' }
+  | { type: 'Fulfilled' };
+
+// Offer acceptance: the request author choosing a helper
+export interface OfferAcceptance {
+  request_hash: Uint8Array; // ActionHash
+  offer_hash: Uint8Array; // ActionHash of the offer comment
+  helper: Uint8Array; // AgentPubKey
+  scheduled_for: number | null; // Timestamp
+  created_at: number; // Timestamp
+}
+
+export interface AcceptOfferInput {
+  offer_hash: Uint8Array;
+  scheduled_for: number | null;
+}
+
+// Escalation: the request author raising urgency one level
+export interface Escalation {
+  request_hash: Uint8Array; // ActionHash
+  previous: Uint8Array | null; // ActionHash of the prior escalation
+  from: Urgency;
+  to: Urgency;
+  note: string | null;
+  created_at: number; // Timestamp
+}
+
+export interface EscalateRequestInput {
+  action_hash: Uint8Array;
+  new_urgency: Urgency;
+  note: string | null;
+}
+
+export interface EscalationOutput {
+  escalation: Escalation;
+  action_hash: Uint8Array;
 }
 
 // Comment
@@ -68,10 +148,55 @@ export interface CommentOutput {
 }
 
 // Validation constants
+// Helper availability: weekly slots in UTC, converted from local time by the UI
+export interface WeeklyWindow {
+  day: number; // 0 is Monday
+  start_minute: number; // Minutes after midnight UTC
+  end_minute: number; // Exclusive; slots never cross midnight
+}
+
+export interface HelperAvailability {
+  helper: Uint8Array; // AgentPubKey
+  windows: WeeklyWindow[];
+  updated_at: number; // Timestamp
+}
+
+export interface SuggestedHelpersInput {
+  request_hash: Uint8Array;
+  at?: number | null; // Defaults to the accepted offer's time, then now
+}
+
+export interface SuggestedHelper {
+  helper: Uint8Array; // AgentPubKey
+  available: boolean;
+  offered: boolean;
+}
+
+// Mutual aid statistics for organizers
+export interface AidStatsWindow {
+  from: number; // Timestamp
+  to: number; // Timestamp, exclusive
+}
+
+export interface CategoryCount {
+  category: string; // RequestCategory type, e.g. 'PetCare'; see CATEGORY_LABELS
+  count: number;
+}
+
+export interface AidStats {
+  window: AidStatsWindow;
+  requests_created: number;
+  requests_fulfilled: number; // Fulfilled during the window, whenever posted
+  median_secs_to_fulfillment: number | null;
+  top_categories: CategoryCount[];
+}
+
 export const MIN_TITLE_LENGTH = 3;
 export const MAX_TITLE_LENGTH = 100;
 export const MAX_DESCRIPTION_LENGTH = 2000;
 export const MAX_COMMENT_LENGTH = 1000;
+export const MAX_ESCALATION_NOTE_LENGTH = 500;
+export const MAX_WEEKLY_WINDOWS = 28;
 
 // Helper functions for categories
 export const CATEGORY_LABELS: Record<string, string> = {
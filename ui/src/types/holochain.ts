@@ -60,43 +60,44 @@ export interface AttachAppInterfaceRequest {
 }
 
 /**
- * Signal payload types for type-safe signal handling
+ * Envelope every zome signal is wrapped in (see the signal_envelope crate).
+ * Route on `zome` and `event`; `payload` is the event's data.
  */
-export interface PostSignalPayload {
-  type: 'NewPost';
-  post_hash: Uint8Array;
-  author: Uint8Array;
+export interface SignalEnvelope<E extends string = string, P = unknown> {
+  zome: string;
+  version: number;
+  event: E;
+  payload: P;
 }
 
-export interface EventSignalPayload {
-  type: 'NewEvent';
-  event_hash: Uint8Array;
-  author: Uint8Array;
-}
-
-export interface MessageSignalPayload {
-  type: 'NewMessage';
-  message_hash: Uint8Array;
-  channel_hash: Uint8Array;
-  author: Uint8Array;
-}
-
-export type OurBlockSignalPayload = 
-  | PostSignalPayload 
-  | EventSignalPayload 
-  | MessageSignalPayload;
-
 /**
- * Type guard for signal payloads
+ * Type guard for enveloped signals, optionally from a specific zome
  */
-export function isPostSignal(payload: any): payload is PostSignalPayload {
-  return payload && payload.type === 'NewPost';
+export function isSignalEnvelope(payload: unknown, zome?: string): payload is SignalEnvelope {
+  if (!payload || typeof payload !== 'object') return false;
+  const envelope = payload as Partial<SignalEnvelope>;
+  return (
+    typeof envelope.zome === 'string' &&
+    typeof envelope.event === 'string' &&
+    typeof envelope.version === 'number' &&
+    (zome === undefined || envelope.zome === zome)
+  );
 }
 
-export function isEventSignal(payload: any): payload is EventSignalPayload {
-  return payload && payload.type === 'NewEvent';
-}
+/**
+ * Delivery confidence for one of the caller's own records, from
+ * `get_publish_status` in the feed, toolshed, and vouch zomes
+ * (see the publish_status crate).
+ */
+export type PublishStatus = 'Pending' | 'PartiallyPublished' | 'Published';
 
-export function isMessageSignal(payload: any): payload is MessageSignalPayload {
-  return payload && payload.type === 'NewMessage';
+export interface PublishStatusOutput {
+  action_hash: Uint8Array;
+  status: PublishStatus;
+  /** DHT ops the action produced */
+  ops: number;
+  /** Ops with all the validation receipts they need */
+  ops_complete: number;
+  /** Receipts received across every op */
+  receipts: number;
 }
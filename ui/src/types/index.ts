@@ -3,12 +3,22 @@ export type {
   Profile,
   ProfileInput,
   ProfileOutput,
+  DisplayNameVariant,
+  ProfileListOptions,
+  DeactivateAccountInput,
+  DeactivationReport,
 } from './profile';
 
 export type {
   Vouch,
   VouchInput,
   VouchOutput,
+  GuestScope,
+  GuestPass,
+  GuestPassInfo,
+  KeyRotation,
+  KeyRetirement,
+  RotationAttestation,
 } from './vouch';
 
 export type {
@@ -21,6 +31,17 @@ export type {
   Comment,
   CommentInput,
   CommentOutput,
+  ReactionSummary,
+  CommentWithReactions,
+  PostWithDetails,
+  Report,
+  ReportOutput,
+  ResolutionAction,
+  ReportResolution,
+  LostFoundPost,
+  LostFoundOutput,
+  Digest,
+  DigestFrequency,
 } from './feed';
 
 export type {
@@ -34,6 +55,11 @@ export type {
   BorrowRequestOutput,
   TransactionOutput,
   TransactionStatus,
+  Handoff,
+  UnavailabilityWindow,
+  UnavailabilityWindowOutput,
+  CatalogInfo,
+  PowerSource,
 } from './toolshed';
 
 export type {
@@ -54,10 +80,16 @@ export type {
   ChannelOutput,
   ChatMessage,
   ChatSignal,
+  ChatContext,
+  ChatSubject,
   SendMessageInput,
   SendMessageOutput,
   StoredMessage,
   ChatConversation,
+  ReadState,
+  ConversationSummary,
+  ChatVoiceMemo,
+  SendVoiceMemoInput,
 } from './chat';
 
 export type {
@@ -70,6 +102,10 @@ export type {
   Space,
   CreateSpaceInput,
   SpaceOutput,
+  SpaceApproval,
+  BookingRules,
+  SpaceEquipment,
+  AttachEquipmentInput,
   Reservation,
   CreateReservationInput,
   ReservationOutput,
@@ -80,8 +116,27 @@ export type {
   FileMetadataOutput,
   UploadFileInput,
   FileOutput,
+  FileAccess,
+  RenewFileInput,
+  ExpiryCleanup,
+  FileStorageSignal,
+  CategoryUsage,
+  UploaderUsage,
+  StorageStats,
+  Compression,
 } from './file-storage';
 
+export type {
+  Poll,
+  CreatePollInput,
+  PollOutput,
+  PollSummary,
+  Vote,
+  CastVoteInput,
+  PollResult,
+  Decision,
+} from './governance';
+
 // Export chat helper functions and constants
 export {
   MAX_MESSAGE_LENGTH,
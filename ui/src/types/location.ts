@@ -0,0 +1,27 @@
+/**
+ * TypeScript types for locations and the search zome's nearby queries
+ */
+
+// Free-text label plus an approximate geohash (at most 6 characters)
+export interface Location {
+  label: string;
+  geohash: string | null;
+}
+
+export type ContentKind = 'Post' | 'Item' | 'Request' | 'Event' | 'Space';
+
+export interface NearbyInput {
+  kind: ContentKind;
+  geohash_prefix: string; // Shorter prefixes cover larger areas
+}
+
+export interface NearbyHit {
+  kind: ContentKind;
+  target: Uint8Array; // ActionHash
+  title: string;
+  author: Uint8Array; // AgentPubKey
+  indexed_at: number; // Timestamp
+}
+
+export const MAX_LOCATION_LABEL_LENGTH = 100;
+export const MAX_GEOHASH_PRECISION = 6;
@@ -7,6 +7,10 @@ export interface Profile {
   bio: string | null;
   avatar_url: string | null;
   location_metadata: string | null;
+  pronouns: string | null;
+  display_name_variants: DisplayNameVariant[];
+  /** Microseconds; set once the neighbor has deactivated their account */
+  deactivated_at: number | null;
 }
 
 export interface CreateProfileInput {
@@ -14,13 +18,84 @@ export interface CreateProfileInput {
   bio: string | null;
   avatar_url: string | null;
   location_metadata: string | null;
+  pronouns: string | null;
+  display_name_variants: DisplayNameVariant[];
+}
+
+export interface DeactivateAccountInput {
+  /** Must match the current nickname */
+  confirm_nickname: string;
+}
+
+export interface DeactivationReport {
+  profile: ProfileOutput;
+  reservations_cancelled: Uint8Array[];
+  rsvps_cancelled: Uint8Array[];
+  items_marked_unavailable: Uint8Array[];
+  borrow_requests_withdrawn: Uint8Array[];
+}
+
+/** The nickname in another script; `script` is an ISO 15924 code like "Cyrl" */
+export interface DisplayNameVariant {
+  script: string;
+  name: string;
+}
+
+export interface ProfileListOptions {
+  /** BCP 47 tag, e.g. "sv-SE"; picks display names and sort order */
+  locale: string | null;
 }
 
 export interface ProfileOutput {
   profile: Profile;
+  /** Nickname, or its variant in the requested locale's script */
+  display_name: string;
   action_hash: Uint8Array;
   entry_hash: Uint8Array;
   agent: Uint8Array;
+  /** Current status, omitted once it expires */
+  status: ProfileStatus | null;
+}
+
+/** A short note like "on vacation until July 3", separate from the bio */
+export interface ProfileStatus {
+  emoji: string | null;
+  text: string;
+  /** Microseconds since epoch; null means until cleared */
+  expires_at: number | null;
+  set_at: number;
+}
+
+export interface SetStatusInput {
+  emoji: string | null;
+  text: string;
+  expires_at: number | null;
+}
+
+/** Achievements granted by other zomes; each kind is held at most once */
+export type BadgeKind = "Verified" | "ReliableBorrower" | "Helper" | "EventHost";
+
+export interface Badge {
+  agent: Uint8Array;
+  kind: BadgeKind;
+  /** Records from the issuing zome proving the badge was earned */
+  evidence: Uint8Array[];
+  granted_at: number;
+}
+
+export interface BadgeOutput {
+  badge: Badge;
+  action_hash: Uint8Array;
+}
+
+/** Onboarding milestones, in the order the UI suggests them */
+export type OnboardingStep = "ProfileCreated" | "FirstVouchReceived" | "Verified" | "JoinedFirstEvent";
+
+// Matches backend OnboardingState
+export interface OnboardingState {
+  completed: OnboardingStep[];
+  /** null once onboarding is finished */
+  next_step: OnboardingStep | null;
 }
 
 // System signal types for profile events
@@ -1,4 +1,5 @@
 import { ActionHash, AgentPubKey, EntryHash } from "@holochain/client";
+import type { Location } from "./location";
 
 export interface Space {
   name: string;
@@ -7,6 +8,20 @@ export interface Space {
   available_hours: string;
   manager: AgentPubKey;
   created_at: number;
+  place: Location | null;
+  /** Reservations may overlap while their party sizes fit the capacity */
+  allow_shared_bookings: boolean;
+  booking_rules: BookingRules;
+}
+
+/** Fair-use limits on a space's reservations; null means no limit */
+export interface BookingRules {
+  max_consecutive_hours: number | null;
+  /** Per agent, counting reservations starting in the same UTC week (Monday to Sunday) */
+  max_bookings_per_week: number | null;
+  advance_booking_days: number | null;
+  /** The manager approves each reservation before it holds its slot */
+  require_approval?: boolean;
 }
 
 export interface CreateSpaceInput {
@@ -14,6 +29,11 @@ export interface CreateSpaceInput {
   description: string;
   capacity: number;
   available_hours: string;
+  place?: Location | null;
+  allow_shared_bookings?: boolean;
+  booking_rules?: BookingRules;
+  /** Reusing a key returns the original record instead of creating a duplicate */
+  idempotency_key?: string;
 }
 
 // Matches backend SpaceOutput
@@ -21,6 +41,28 @@ export interface SpaceOutput {
   space: Space;
   action_hash: ActionHash;
   entry_hash: EntryHash;
+  /** Toolshed items kept at the space */
+  equipment: SpaceEquipment[];
+}
+
+export interface SpaceEquipment {
+  item_hash: ActionHash;
+  title: string;
+  /** Held for the space's reservations by reserve_space_equipment */
+  auto_reserve: boolean;
+}
+
+export interface AttachEquipmentInput {
+  space_hash: ActionHash;
+  item_hash: ActionHash;
+  auto_reserve?: boolean;
+}
+
+/** An anchor's sign-off that lists a new space in get_all_spaces */
+export interface SpaceApproval {
+  space_hash: ActionHash;
+  approver_anchor: ActionHash;
+  approved_at: number;
 }
 
 export interface Reservation {
@@ -30,6 +72,8 @@ export interface Reservation {
   end_time: number;
   purpose: string | null;
   created_at: number;
+  party_size: number;
+  known_overlaps: ActionHash[]; // Overlapping reservations seen when booking
 }
 
 export interface CreateReservationInput {
@@ -37,6 +81,10 @@ export interface CreateReservationInput {
   start_time: number;
   end_time: number;
   purpose: string | null;
+  /** Defaults to 1 */
+  party_size?: number;
+  /** Reusing a key returns the original record instead of creating a duplicate */
+  idempotency_key?: string;
 }
 
 // Matches backend ReservationOutput
@@ -44,4 +92,77 @@ export interface ReservationOutput {
   reservation: Reservation;
   action_hash: ActionHash;
   entry_hash: EntryHash;
+  /** Places left while this reservation runs; null from get_my_reservations */
+  remaining_capacity: number | null;
+  /** Lost its slot to a reservation booked at the same moment; rebook or cancel */
+  superseded: boolean;
+  /** Waiting for the space manager's approval; doesn't hold its slot yet */
+  awaiting_approval: boolean;
+}
+
+export interface SetAccessInstructionsInput {
+  space_hash: ActionHash;
+  instructions: string;
+}
+
+export interface AccessInstruction {
+  space_hash: ActionHash;
+  instructions: string;
+  updated_at: number;
+}
+
+// Matches backend AccessInstructionsOutput
+export interface AccessInstructionsOutput {
+  space_hash: ActionHash;
+  reservation_hash: ActionHash;
+  instructions: string;
+  /** Instructions stop being revealed after this */
+  valid_until: number;
+}
+
+export interface SpaceChecklist {
+  space_hash: ActionHash;
+  items: string[];
+  created_at: number;
+}
+
+export interface SetChecklistInput {
+  space_hash: ActionHash;
+  items: string[];
+}
+
+export interface ChecklistResult {
+  item: string;
+  done: boolean;
+}
+
+export interface ConditionReport {
+  reservation_hash: ActionHash;
+  space_hash: ActionHash;
+  reporter: AgentPubKey;
+  checklist: ChecklistResult[];
+  notes: string | null;
+  /** File storage hashes */
+  photos: EntryHash[];
+  created_at: number;
+}
+
+export interface SubmitConditionReportInput {
+  reservation_hash: ActionHash;
+  checklist: ChecklistResult[];
+  notes: string | null;
+  photos?: EntryHash[];
+}
+
+// Matches backend ConditionReportOutput
+export interface ConditionReportOutput {
+  report: ConditionReport;
+  action_hash: ActionHash;
+  /** An item was left undone or the reserver left notes */
+  has_problems: boolean;
+}
+
+export interface SpaceReportsInput {
+  space_hash: ActionHash;
+  problems_only?: boolean;
 }
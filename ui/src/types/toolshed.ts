@@ -2,6 +2,8 @@
  * TypeScript types matching the Tool Shed zome types
  */
 
+import type { Location } from './location';
+
 // Item Status
 export type ItemStatus = 'Available' | 'Borrowed' | 'Unavailable';
 
@@ -11,6 +13,26 @@ export interface Consumable {
   included: boolean;
 }
 
+// Lending terms: what a lender asks of a borrower
+export interface LendingTerms {
+  deposit: string | null;
+  replacement_value: string | null;
+  care_instructions: string | null;
+}
+
+// What an item runs on
+export type PowerSource = 'Manual' | 'Battery' | 'Corded' | 'Gas';
+
+// Library-of-things catalog details; every field is optional
+export interface CatalogInfo {
+  brand: string | null;
+  model: string | null;
+  manual: Uint8Array | null; // file_storage FileMetadata ActionHash
+  replacement_value: string | null; // e.g. "$150 new"
+  power_source: PowerSource | null;
+  power_details: string | null; // e.g. "20V battery, charger included"
+}
+
 // Item
 export interface Item {
   title: string;
@@ -21,6 +43,9 @@ export interface Item {
   owner: Uint8Array; // AgentPubKey
   status: ItemStatus;
   created_at: number; // Timestamp
+  lending_terms: LendingTerms | null;
+  place: Location | null; // Where the item can be picked up
+  catalog: CatalogInfo | null;
 }
 
 export interface CreateItemInput {
@@ -29,6 +54,11 @@ export interface CreateItemInput {
   image_hash: Uint8Array | null;
   consumables: Consumable[];
   notes: string;
+  lending_terms?: LendingTerms | null;
+  place?: Location | null;
+  catalog?: CatalogInfo | null;
+  /** Reusing a key returns the original record instead of creating a duplicate */
+  idempotency_key?: string;
 }
 
 export interface ItemOutput {
@@ -37,6 +67,11 @@ export interface ItemOutput {
   entry_hash: Uint8Array;
 }
 
+export interface UpdateCatalogInput {
+  action_hash: Uint8Array;
+  catalog: CatalogInfo | null; // null clears the catalog details
+}
+
 // Borrow Request
 export interface BorrowRequest {
   item_hash: Uint8Array; // ActionHash
@@ -45,12 +80,16 @@ export interface BorrowRequest {
   requested_due_date: number; // Timestamp
   message: string | null;
   created_at: number; // Timestamp
+  expires_at: number | null; // Timestamp; null on requests made before expiry existed
+  terms: LendingTerms | null; // The item's terms when the request was made
 }
 
 export interface RequestBorrowInput {
   item_hash: Uint8Array;
   requested_due_date: number;
   message: string | null;
+  /** Defaults to 7 days from now; at most 30 days */
+  expires_at?: number;
 }
 
 export interface BorrowRequestOutput {
@@ -67,9 +106,20 @@ export interface Transaction {
   due_date: number; // Timestamp
   created_at: number; // Timestamp
   notes: string | null;
+  terms: LendingTerms | null; // Agreed by both parties
+  request_hash: Uint8Array | null; // ActionHash of the accepted BorrowRequest
 }
 
-export type TransactionStatus = 'Pending' | 'Active' | 'Returned' | 'Cancelled';
+export type TransactionStatus =
+  | 'Pending'
+  | 'AwaitingPickup' // Accepted; both parties still need to confirm the pickup
+  | 'Active'
+  | 'AwaitingDropoff' // One party has confirmed the drop-off
+  | 'Returned'
+  | 'Cancelled';
+
+// A physical handoff both parties acknowledge with confirm_pickup / confirm_dropoff
+export type Handoff = 'Pickup' | 'Dropoff';
 
 export interface TransactionOutput {
   transaction: Transaction;
@@ -84,6 +134,70 @@ export interface AcceptBorrowInput {
   notes: string | null;
 }
 
+export interface DeclineBorrowInput {
+  request_hash: Uint8Array;
+  reason: string | null; // Passed on to the requester
+}
+
+// Wanted items
+export interface WantedItem {
+  title: string;
+  description: string;
+  requester: Uint8Array; // AgentPubKey
+  created_at: number; // Timestamp
+}
+
+export interface PostWantedInput {
+  title: string;
+  description: string;
+}
+
+export interface WantedItemOutput {
+  wanted: WantedItem;
+  action_hash: Uint8Array;
+  offered_items: Uint8Array[]; // ActionHash of items owners have offered
+}
+
+export interface PostWantedOutput {
+  wanted: WantedItemOutput;
+  matches: ItemOutput[];
+}
+
+export interface FulfillWantedInput {
+  wanted_hash: Uint8Array;
+  item_hash: Uint8Array;
+}
+
+// Unavailability windows: time an owner has blocked off for an item
+export interface UnavailabilityWindow {
+  item_hash: Uint8Array; // ActionHash
+  starts_at: number; // Timestamp
+  ends_at: number; // Timestamp
+  reason: string | null;
+  created_at: number; // Timestamp
+}
+
+export interface ScheduleUnavailabilityInput {
+  item_hash: Uint8Array;
+  starts_at: number;
+  ends_at: number;
+  reason: string | null;
+}
+
+export interface UnavailabilityWindowOutput {
+  window: UnavailabilityWindow;
+  action_hash: Uint8Array;
+}
+
+export interface AvailabilityRefresh {
+  made_unavailable: Uint8Array[]; // Items a window has started for
+  restored: Uint8Array[]; // Items whose windows have all ended
+}
+
 // Validation constants
 export const MAX_TITLE_LENGTH = 100;
 export const MAX_DESCRIPTION_LENGTH = 1000;
+export const MAX_TERMS_FIELD_LENGTH = 200;
+export const MAX_CARE_INSTRUCTIONS_LENGTH = 1000;
+export const MAX_CATALOG_FIELD_LENGTH = 100;
+export const MAX_UNAVAILABILITY_WINDOW_SECS = 365 * 24 * 60 * 60;
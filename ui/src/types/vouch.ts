@@ -2,16 +2,17 @@
  * TypeScript types matching the Vouch zome types
  */
 
-export type VouchType = 
-  | 'PhysicalHandshake'
-  | 'ExistingRelationship'
-  | 'TrustedIntroduction';
+// Anchor vouches count toward the anchor threshold; only trusted anchors may give them
+export type VouchType =
+  | { type: 'Neighbor' }
+  | { type: 'Anchor' };
 
 export interface Vouch {
   vouchee: Uint8Array; // AgentPubKey
   vouch_type: VouchType;
-  timestamp: number;
+  created_at: number; // Timestamp
   note: string | null;
+  anchor: Uint8Array | null; // The voucher's TrustedAnchor record, for Anchor vouches
 }
 
 export interface CreateVouchInput {
@@ -31,7 +32,8 @@ export interface VouchInfo {
   voucher: Uint8Array; // AgentPubKey
   vouch: Vouch;
   action_hash: Uint8Array;
-  is_from_anchor: boolean;
+  is_from_anchor: boolean; // An Anchor vouch from a current anchor
+  via_rotations: Uint8Array[]; // KeyRotations from the key that received it, oldest first
 }
 
 export type MembershipStatus =
@@ -57,8 +59,90 @@ export interface TrustedAnchor {
 export interface VouchRequest {
   agent: Uint8Array; // AgentPubKey
   timestamp: number;
+  signature: Uint8Array; // By `agent`; QR codes are valid for 10 minutes
+}
+
+// One-step onboarding from a scanned VouchRequest
+export interface OnboardNeighborInput {
+  request: VouchRequest;
+  nickname: string; // Starts the neighbor's profile if they have none
+}
+
+export interface OnboardNeighborOutput {
+  vouch: VouchOutput;
+  profile_created: boolean;
+  notified: Uint8Array[]; // Members asked to add their vouch
+}
+
+// Temporary, limited access for non-members such as contractors or visiting family
+export type GuestScope = 'ViewFeed' | 'ChatIssuer'; // ChatIssuer: message the issuer only
+
+export interface GuestPass {
+  guest: Uint8Array; // AgentPubKey
+  scopes: GuestScope[];
+  issued_at: number; // Timestamp
+  expires_at: number; // Timestamp; at most 30 days after issued_at
+  note: string | null;
+}
+
+export interface IssueGuestPassInput {
+  guest: Uint8Array; // AgentPubKey
+  scopes: GuestScope[];
+  expires_at: number; // Timestamp
+  note: string | null;
+}
+
+export interface GuestPassInfo {
+  pass: GuestPass;
+  action_hash: Uint8Array;
+  issuer: Uint8Array; // AgentPubKey
+  revoked: boolean;
+  active: boolean; // Not revoked and not expired
+}
+
+export interface AccessCheckInput {
+  agent: Uint8Array; // AgentPubKey
+  scope: GuestScope | null; // null for writes, which guests can never do
+  counterparty?: Uint8Array | null; // Chat recipient, for ChatIssuer
+}
+
+export interface VouchEvidence {
+  vouch: Uint8Array; // ActionHash
+  voucher_proof: Uint8Array | null; // The voucher's MembershipProof, for Neighbor vouches
+  rotations: Uint8Array[]; // KeyRotations from the vouchee to the author, oldest first
+}
+
+// Approved by the old key, or by an anchor when the old device is lost
+export type RotationAttestation =
+  | { OldKey: Uint8Array }
+  | { Anchor: { anchor: Uint8Array; signature: Uint8Array } };
+
+// Committed by the new key so the old key's vouches keep counting
+export interface KeyRotation {
+  old_key: Uint8Array; // AgentPubKey
+  new_key: Uint8Array; // AgentPubKey
+  rotated_at: number;
+  attestation: RotationAttestation;
+  retirement: Uint8Array | null; // The approver's KeyRetirement naming new_key
+}
+
+// The approver's one-time handover of a key; a retired key no longer counts as a member
+export interface KeyRetirement {
+  old_key: Uint8Array; // AgentPubKey
+  successor: Uint8Array; // AgentPubKey
+  retired_at: number;
+  anchor: Uint8Array | null; // The approving anchor's TrustedAnchor record, for lost keys
+}
+
+// Kept on a member's own chain; feed, toolshed, events, and spaces reject
+// writes from authors without one. Created by ensure_membership_proof.
+export interface MembershipProof {
+  anchor: Uint8Array | null; // The author's TrustedAnchor record, for anchors
+  vouches: VouchEvidence[];
+  depth: number; // Vouch hops from an anchor
 }
 
 // Constants matching the integrity zome
 export const REQUIRED_VOUCHES_FROM_MEMBERS = 2;
 export const REQUIRED_VOUCHES_FROM_ANCHOR = 1;
+export const MAX_GUEST_PASS_VALIDITY_SECS = 30 * 24 * 60 * 60;